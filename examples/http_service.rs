@@ -0,0 +1,119 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![warn(clippy::all)]
+
+//! A web service transforming the Cypher query posted to `/sql` to SQL,
+//! answering with the JSON of a [`ServiceResponse`] and its status code.
+//! Run with `cargo run --features json_example --example http_service`,
+//! then:
+//!
+//! ```text
+//! $ curl -i --data 'MATCH (n:Person) RETURN n.name' localhost:8080/sql
+//! ```
+//!
+//! The service is only routing around [`handle_request`], here over plain
+//! `std::net` to need no web framework. In an Axum service, the handler is:
+//!
+//! ```text
+//! async fn sql(State(config): State<Arc<TransformConfig>>, query: String) -> impl IntoResponse {
+//!     let response = handle_request(&query, &config);
+//!     (StatusCode::from_u16(response.status_code()).unwrap(), Json(response))
+//! }
+//! ```
+//!
+//! and in an actix-web service:
+//!
+//! ```text
+//! #[post("/sql")]
+//! async fn sql(config: web::Data<TransformConfig>, query: String) -> HttpResponse {
+//!     let response = handle_request(&query, &config);
+//!     HttpResponse::build(StatusCode::from_u16(response.status_code()).unwrap()).json(response)
+//! }
+//! ```
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use sqlparser::transformer::service::{handle_request, ServiceResponse};
+use sqlparser::transformer::TransformConfig;
+
+fn main() -> io::Result<()> {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let config = TransformConfig::default();
+    let listener = TcpListener::bind(&address)?;
+    println!("Transforming Cypher posted to http://{address}/sql");
+    for stream in listener.incoming() {
+        if let Err(e) = serve(stream?, &config) {
+            eprintln!("{e}");
+        }
+    }
+    Ok(())
+}
+
+/// Answer the request read from `stream`
+fn serve(mut stream: TcpStream, config: &TransformConfig) -> io::Result<()> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let (status, body) = match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+        ["POST", "/sql"] => respond(handle_request(&String::from_utf8_lossy(&body), config)),
+        [_, "/sql"] => (405, String::new()),
+        _ => (404, String::new()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason(status),
+        body.len()
+    )
+}
+
+/// The status code and JSON body of `response`
+fn respond(response: ServiceResponse) -> (u16, String) {
+    let json = serde_json::to_string(&response).expect("a response serializes to JSON");
+    (response.status_code(), json)
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        _ => "",
+    }
+}
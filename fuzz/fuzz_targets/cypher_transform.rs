@@ -0,0 +1,50 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use honggfuzz::fuzz;
+use sqlparser::dialect::CypherDialect;
+use sqlparser::parser::Parser;
+use sqlparser::transformer::{
+    CypherToSqlTransformer, ParamStyle, SqlFormat, SqlTarget, TransformConfig,
+};
+
+fn main() {
+    let transformers = [
+        CypherToSqlTransformer::new(),
+        CypherToSqlTransformer::with_config(TransformConfig {
+            use_label_tables: false,
+            target: SqlTarget::Postgres,
+            format: SqlFormat::Pretty { indent: 2 },
+            param_style: ParamStyle::Numbered,
+            ..TransformConfig::default()
+        }),
+    ];
+
+    loop {
+        fuzz!(|data: String| {
+            let Ok(statement) = Parser::new(&CypherDialect)
+                .try_with_sql(&data)
+                .and_then(|mut parser| parser.parse_cypher_statement())
+            else {
+                return;
+            };
+            for transformer in &transformers {
+                let _ = transformer.to_sql(&statement);
+            }
+        });
+    }
+}
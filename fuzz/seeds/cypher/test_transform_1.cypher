@@ -0,0 +1 @@
+MATCH (n:Person) RETURN n.name
\ No newline at end of file
@@ -0,0 +1 @@
+MATCH (n:User) RETURN n.email
\ No newline at end of file
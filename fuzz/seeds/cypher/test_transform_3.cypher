@@ -0,0 +1 @@
+MATCH (n) RETURN n.id
\ No newline at end of file
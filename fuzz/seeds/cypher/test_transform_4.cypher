@@ -0,0 +1 @@
+MATCH (n:Person)-[r:KNOWS]->(m:Person) RETURN n.name, m.name
\ No newline at end of file
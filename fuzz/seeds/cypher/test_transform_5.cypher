@@ -0,0 +1 @@
+CREATE (n:Person)
\ No newline at end of file
@@ -18,8 +18,16 @@
 //! Cypher language AST nodes for graph query support
 
 #[cfg(not(feature = "std"))]
-use alloc::{vec, vec::Vec};
+use alloc::{
+    boxed::Box,
+    collections::BTreeSet,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use core::fmt;
+#[cfg(feature = "std")]
+use std::collections::BTreeSet;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -27,108 +35,547 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "visitor")]
 use sqlparser_derive::{Visit, VisitMut};
 
-use crate::ast::{display_comma_separated, Expr, Ident, OrderByExpr, SelectItem};
+use crate::dialect::CypherDialect;
+use crate::tokenizer::{Location, Token, Tokenizer};
+
+use crate::ast::{
+    display_comma_separated, display_separated, AccessExpr, DictionaryField, Expr, FunctionArg,
+    FunctionArgExpr, FunctionArguments, Ident, OrderByExpr, SelectItem, Value, ValueWithSpan,
+};
 
 /// A Cypher statement
+///
+/// Serialized, the fields a statement can do without, such as the
+/// `optional` flag of a `MATCH`, default when missing, so that statements
+/// serialized before such a field was added still deserialize.
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
 pub enum CypherStatement {
-    /// MATCH pattern [WHERE condition] [RETURN items]
+    /// [MATCH pattern [WHERE condition]] [WITH items] [next] [RETURN items]
+    ///
+    /// A query without patterns has no MATCH, e.g. `RETURN 1 + 1 AS two`,
+    /// and works on a single row without columns.
     Match {
+        #[cfg_attr(feature = "serde", serde(default))]
         optional: bool,
         patterns: Vec<Pattern>,
         r#where: Option<Expr>,
+        with: Option<WithClause>,
         r#return: Option<ReturnClause>,
+        /// The part of the query after the WITH, a MATCH or OPTIONAL MATCH
+        /// over the rows the WITH passes on, or without a WITH the next
+        /// MATCH or OPTIONAL MATCH clause, which has the RETURN in place of
+        /// this one
+        ///
+        /// Each clause keeps its own WHERE, so a condition after an OPTIONAL
+        /// MATCH only decides what that clause matches.
+        next: Option<Box<CypherStatement>>,
     },
-    /// CREATE pattern
+    /// [MATCH pattern [WHERE condition]] CREATE pattern
     Create {
+        /// The patterns of a MATCH binding existing nodes for the created
+        /// patterns to connect, or none
+        #[cfg_attr(feature = "serde", serde(default))]
+        r#match: Vec<Pattern>,
+        r#where: Option<Expr>,
         patterns: Vec<Pattern>,
+        /// The RETURN of the created elements, if any
+        #[cfg_attr(feature = "serde", serde(default))]
+        r#return: Option<ReturnClause>,
     },
-    /// MERGE pattern [ON CREATE SET] [ON MATCH SET]
+    /// MERGE pattern [ON CREATE SET | ON MATCH SET]...
     Merge {
         patterns: Vec<Pattern>,
-        on_create: Option<Vec<SetClause>>,
-        on_match: Option<Vec<SetClause>>,
+        /// The `ON CREATE` and `ON MATCH` actions in the order they were
+        /// written, at most one of each
+        #[cfg_attr(feature = "serde", serde(default))]
+        actions: Vec<MergeSetAction>,
     },
-    /// DELETE nodes/relationships [WHERE condition]
+    /// MATCH pattern [WHERE condition] [DETACH] DELETE variables
     Delete {
+        patterns: Vec<Pattern>,
+        #[cfg_attr(feature = "serde", serde(default))]
         detach: bool,
         what: Vec<Expr>,
         r#where: Option<Expr>,
     },
+    /// MATCH pattern [WHERE condition] SET items
+    Set {
+        patterns: Vec<Pattern>,
+        r#where: Option<Expr>,
+        items: Vec<SetClause>,
+    },
+    /// query UNION [ALL] query ...
+    ///
+    /// Cypher does not allow mixing `UNION` and `UNION ALL`, so a single
+    /// flag applies to every arm. `ORDER BY`, `SKIP` and `LIMIT` of the last
+    /// arm apply to the combined result.
+    Union {
+        #[cfg_attr(feature = "serde", serde(default))]
+        all: bool,
+        queries: Vec<CypherStatement>,
+    },
+    /// UNWIND list AS variable query
+    ///
+    /// The query runs once for each element of the list, which it refers
+    /// to by the variable, e.g. `UNWIND range(1, 3) AS i RETURN i * 2`.
+    Unwind {
+        list: Expr,
+        variable: Ident,
+        query: Box<CypherStatement>,
+    },
+    /// SHOW DATABASES [YIELD columns] [WHERE condition], and the other
+    /// administrative listings of [`ShowTarget`]
+    Show {
+        what: ShowTarget,
+        /// The columns of the `YIELD` clause, with their aliases, or `*`;
+        /// empty without one
+        #[cfg_attr(feature = "serde", serde(default))]
+        yield_items: Vec<SelectItem>,
+        r#where: Option<Expr>,
+    },
+    /// A statement recovered from errors parsing it by
+    /// [`Parser::parse_cypher_statement_recovering`], for editors to complete
+    /// and check, which the transformer rejects
+    ///
+    /// [`Parser::parse_cypher_statement_recovering`]: crate::parser::Parser::parse_cypher_statement_recovering
+    Recovered { statement: Box<CypherStatement> },
+}
+
+impl CypherStatement {
+    /// A `MATCH` of `patterns`, without a condition or `RETURN`
+    pub fn match_(patterns: impl IntoIterator<Item = Pattern>) -> Self {
+        CypherStatement::Match {
+            optional: false,
+            patterns: patterns.into_iter().collect(),
+            r#where: None,
+            with: None,
+            r#return: None,
+            next: None,
+        }
+    }
+
+    /// Make a `MATCH` an `OPTIONAL MATCH`
+    ///
+    /// Has no effect on other statements.
+    pub fn optional(mut self) -> Self {
+        if let CypherStatement::Match { optional, .. } = &mut self {
+            *optional = true;
+        }
+        self
+    }
+
+    /// Set the `WHERE` condition of a `MATCH`, `DELETE` or `SET`
+    ///
+    /// Has no effect on other statements.
+    pub fn where_(mut self, condition: Expr) -> Self {
+        if let CypherStatement::Match { r#where, .. }
+        | CypherStatement::Delete { r#where, .. }
+        | CypherStatement::Set { r#where, .. } = &mut self
+        {
+            *r#where = Some(condition);
+        }
+        self
+    }
+
+    /// Set the `WITH` clause of a `MATCH`
+    ///
+    /// Has no effect on other statements.
+    pub fn with(mut self, clause: WithClause) -> Self {
+        if let CypherStatement::Match { with, .. } = &mut self {
+            *with = Some(clause);
+        }
+        self
+    }
+
+    /// Set the `RETURN` clause of a `MATCH` or `CREATE`
+    ///
+    /// Has no effect on other statements.
+    pub fn return_(mut self, clause: ReturnClause) -> Self {
+        if let CypherStatement::Match { r#return, .. } | CypherStatement::Create { r#return, .. } =
+            &mut self
+        {
+            *r#return = Some(clause);
+        }
+        self
+    }
+
+    /// Continue a `MATCH` with the `MATCH` after its `WITH` clause, or
+    /// without one with the next `MATCH` or `OPTIONAL MATCH` clause
+    ///
+    /// Has no effect on other statements.
+    pub fn then(mut self, statement: CypherStatement) -> Self {
+        if let CypherStatement::Match { next, .. } = &mut self {
+            *next = Some(Box::new(statement));
+        }
+        self
+    }
 }
 
 impl fmt::Display for CypherStatement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Values display strings with SQL quoting, so the strings that need
+        // Cypher's backslash escapes are written as they are
+        let mut exprs = self.exprs();
+        while let Some(expr) = exprs.pop() {
+            if cypher_string_literal(expr).is_some() {
+                let mut escaped = self.clone();
+                let mut exprs = escaped.exprs_mut();
+                while let Some(expr) = exprs.pop() {
+                    if let Some(literal) = cypher_string_literal(expr) {
+                        *expr = Expr::value(Value::Placeholder(literal));
+                    }
+                    exprs.extend(subexpressions_mut(expr));
+                }
+                return escaped.fmt(f);
+            }
+            exprs.extend(subexpressions(expr));
+        }
+
         match self {
             CypherStatement::Match {
                 optional,
                 patterns,
                 r#where,
+                with,
                 r#return,
+                next,
             } => {
-                if *optional {
-                    write!(f, "OPTIONAL ")?;
+                let mut delim = "";
+                if !patterns.is_empty() {
+                    if *optional {
+                        write!(f, "OPTIONAL ")?;
+                    }
+                    write!(f, "MATCH {}", display_comma_separated(patterns))?;
+                    if let Some(condition) = r#where {
+                        write!(f, " WHERE {condition}")?;
+                    }
+                    delim = " ";
                 }
-                write!(f, "MATCH {}", display_comma_separated(patterns))?;
-                if let Some(condition) = r#where {
-                    write!(f, " WHERE {condition}")?;
+                if let Some(with) = with {
+                    write!(f, "{delim}{with}")?;
+                    delim = " ";
+                }
+                if let Some(next) = next {
+                    write!(f, "{delim}{next}")?;
                 }
                 if let Some(ret) = r#return {
-                    write!(f, " {ret}")?;
+                    write!(f, "{delim}{ret}")?;
                 }
                 Ok(())
             }
-            CypherStatement::Create { patterns } => {
-                write!(f, "CREATE {}", display_comma_separated(patterns))
-            }
-            CypherStatement::Merge {
+            CypherStatement::Create {
+                r#match,
+                r#where,
                 patterns,
-                on_create,
-                on_match,
+                r#return,
             } => {
-                write!(f, "MERGE {}", display_comma_separated(patterns))?;
-                if let Some(create_clauses) = on_create {
-                    write!(f, " ON CREATE SET {}", display_comma_separated(create_clauses))?;
+                if !r#match.is_empty() {
+                    write!(f, "MATCH {} ", display_comma_separated(r#match))?;
                 }
-                if let Some(match_clauses) = on_match {
-                    write!(f, " ON MATCH SET {}", display_comma_separated(match_clauses))?;
+                if let Some(condition) = r#where {
+                    write!(f, "WHERE {condition} ")?;
+                }
+                write!(f, "CREATE {}", display_comma_separated(patterns))?;
+                if let Some(ret) = r#return {
+                    write!(f, " {ret}")?;
+                }
+                Ok(())
+            }
+            CypherStatement::Merge { patterns, actions } => {
+                write!(f, "MERGE {}", display_comma_separated(patterns))?;
+                for action in actions {
+                    write!(f, " {action}")?;
                 }
                 Ok(())
             }
             CypherStatement::Delete {
+                patterns,
                 detach,
                 what,
                 r#where,
             } => {
+                write!(f, "MATCH {} ", display_comma_separated(patterns))?;
+                if let Some(condition) = r#where {
+                    write!(f, "WHERE {condition} ")?;
+                }
                 if *detach {
                     write!(f, "DETACH ")?;
                 }
-                write!(f, "DELETE {}", display_comma_separated(what))?;
+                write!(f, "DELETE {}", display_comma_separated(what))
+            }
+            CypherStatement::Set {
+                patterns,
+                r#where,
+                items,
+            } => {
+                write!(f, "MATCH {} ", display_comma_separated(patterns))?;
+                if let Some(condition) = r#where {
+                    write!(f, "WHERE {condition} ")?;
+                }
+                write!(f, "SET {}", display_comma_separated(items))
+            }
+            CypherStatement::Union { all, queries } => {
+                let separator = if *all { " UNION ALL " } else { " UNION " };
+                write!(f, "{}", display_separated(queries, separator))
+            }
+            CypherStatement::Unwind {
+                list,
+                variable,
+                query,
+            } => write!(f, "UNWIND {list} AS {variable} {query}"),
+            CypherStatement::Show {
+                what,
+                yield_items,
+                r#where,
+            } => {
+                write!(f, "SHOW {what}")?;
+                if !yield_items.is_empty() {
+                    write!(f, " YIELD {}", display_comma_separated(yield_items))?;
+                }
                 if let Some(condition) = r#where {
                     write!(f, " WHERE {condition}")?;
                 }
                 Ok(())
             }
+            CypherStatement::Recovered { statement } => write!(f, "{statement}"),
+        }
+    }
+}
+
+/// The keywords [`CypherFormatter`] changes the case of
+const CYPHER_KEYWORDS: &[&str] = &[
+    "ALL",
+    "AND",
+    "AS",
+    "ASC",
+    "BY",
+    "CASE",
+    "CONSTRAINTS",
+    "CONTAINS",
+    "CREATE",
+    "DATABASES",
+    "DELETE",
+    "DESC",
+    "DETACH",
+    "DISTINCT",
+    "ELSE",
+    "END",
+    "ENDS",
+    "FALSE",
+    "FIRST",
+    "FUNCTIONS",
+    "IN",
+    "INDEXES",
+    "IS",
+    "LAST",
+    "LIMIT",
+    "MATCH",
+    "MERGE",
+    "NOT",
+    "NULL",
+    "NULLS",
+    "ON",
+    "OPTIONAL",
+    "OR",
+    "ORDER",
+    "PROCEDURES",
+    "RETURN",
+    "SET",
+    "SHOW",
+    "SKIP",
+    "STARTS",
+    "THEN",
+    "TRANSACTIONS",
+    "TRUE",
+    "UNION",
+    "UNWIND",
+    "WHEN",
+    "WHERE",
+    "WITH",
+    "XOR",
+    "YIELD",
+];
+
+/// Options for writing a [`CypherStatement`] as canonical text, for
+/// comparing queries by their text
+///
+/// Statements that are equal are written identically, and the text parses
+/// back to an equal statement, with its property maps sorted when
+/// `sort_property_keys` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CypherFormatter {
+    /// Write keywords in upper case rather than lower case
+    pub uppercase_keywords: bool,
+    /// Separate tokens by at most a single space
+    pub normalize_whitespace: bool,
+    /// Order the keys of map literals alphabetically
+    pub sort_property_keys: bool,
+}
+
+impl Default for CypherFormatter {
+    fn default() -> Self {
+        CypherFormatter {
+            uppercase_keywords: true,
+            normalize_whitespace: true,
+            sort_property_keys: false,
+        }
+    }
+}
+
+impl CypherFormatter {
+    /// Write `statement` with these options
+    pub fn format(&self, statement: &CypherStatement) -> String {
+        let text = if self.sort_property_keys {
+            let mut sorted = statement.clone();
+            let mut exprs = sorted.exprs_mut();
+            while let Some(expr) = exprs.pop() {
+                if let Expr::Dictionary(fields) = expr {
+                    fields.sort_by(|a, b| a.key.value.cmp(&b.key.value));
+                }
+                exprs.extend(subexpressions_mut(expr));
+            }
+            sorted.to_string()
+        } else {
+            statement.to_string()
+        };
+
+        // Rewrite the tokens of the text, leaving literals and identifiers
+        // as they are
+        let Ok(tokens) = Tokenizer::new(&CypherDialect, &text).tokenize_with_location() else {
+            return text;
+        };
+        let mut offsets = token_offsets(&text, tokens.iter().map(|token| token.span.start));
+        offsets.push(text.len());
+        let significant: Vec<&Token> = tokens
+            .iter()
+            .map(|token| &token.token)
+            .filter(|token| !matches!(token, Token::Whitespace(_)))
+            .collect();
+
+        let mut canonical = String::with_capacity(text.len());
+        let mut position = 0;
+        for (index, token) in tokens.iter().enumerate() {
+            let source = &text[offsets[index]..offsets[index + 1]];
+            match &token.token {
+                Token::Whitespace(_) if self.normalize_whitespace => {
+                    if !canonical.is_empty() && !canonical.ends_with(' ') {
+                        canonical.push(' ');
+                    }
+                    continue;
+                }
+                Token::Word(word)
+                    if word.quote_style.is_none()
+                        && CYPHER_KEYWORDS.contains(&word.value.to_uppercase().as_str())
+                        && is_keyword_position(&significant, position) =>
+                {
+                    if self.uppercase_keywords {
+                        canonical.push_str(&source.to_uppercase());
+                    } else {
+                        canonical.push_str(&source.to_lowercase());
+                    }
+                }
+                _ => canonical.push_str(source),
+            }
+            if !matches!(token.token, Token::Whitespace(_)) {
+                position += 1;
+            }
+        }
+        if self.normalize_whitespace {
+            canonical.truncate(canonical.trim_end().len());
         }
+        canonical
     }
 }
 
+/// Whether the word at `position` of the non-whitespace `tokens` is used as
+/// a keyword rather than as a property, label, map key or alias
+fn is_keyword_position(tokens: &[&Token], position: usize) -> bool {
+    let previous = position.checked_sub(1).map(|previous| tokens[previous]);
+    let next = tokens.get(position + 1);
+    let follows_name_prefix = matches!(
+        previous,
+        Some(Token::Period | Token::Colon | Token::Pipe | Token::DoubleColon)
+    );
+    let is_alias =
+        matches!(previous, Some(Token::Word(word)) if word.value.eq_ignore_ascii_case("AS"));
+    !follows_name_prefix && !is_alias && !matches!(next, Some(Token::Colon))
+}
+
+/// The byte offsets in `text` of the token `starts`, which are in order
+fn token_offsets(text: &str, starts: impl Iterator<Item = Location>) -> Vec<usize> {
+    let mut offsets = vec![];
+    let mut chars = text.char_indices().peekable();
+    let (mut line, mut column) = (1, 1);
+    for start in starts {
+        while (line, column) < (start.line, start.column) {
+            match chars.next() {
+                Some((_, '\n')) => {
+                    line += 1;
+                    column = 1;
+                }
+                Some(_) => column += 1,
+                None => break,
+            }
+        }
+        offsets.push(chars.peek().map_or(text.len(), |(offset, _)| *offset));
+    }
+    offsets
+}
+
 /// A pattern describes nodes and relationships in a graph
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
 pub struct Pattern {
+    /// The variable of a named path, e.g. `p` in `p = (a)-[:KNOWS]->(b)`
+    pub variable: Option<Ident>,
     pub elements: Vec<PatternElement>,
 }
 
+impl Pattern {
+    /// A pattern of `elements`, alternating nodes and relationships
+    pub fn path(elements: impl IntoIterator<Item = PatternElement>) -> Self {
+        Pattern {
+            variable: None,
+            elements: elements.into_iter().collect(),
+        }
+    }
+
+    /// A path of `elements` bound to `variable`, such as
+    /// `p = (a)-[:KNOWS]->(b)`
+    pub fn named_path(
+        variable: impl Into<String>,
+        elements: impl IntoIterator<Item = PatternElement>,
+    ) -> Self {
+        Pattern {
+            variable: Some(Ident::new(variable)),
+            ..Pattern::path(elements)
+        }
+    }
+
+    /// The property maps of the elements of the pattern
+    pub(crate) fn properties(&self) -> impl Iterator<Item = &Expr> {
+        self.elements.iter().filter_map(PatternElement::properties)
+    }
+
+    pub(crate) fn properties_mut(&mut self) -> impl Iterator<Item = &mut Expr> {
+        self.elements
+            .iter_mut()
+            .filter_map(|element| match element {
+                PatternElement::Node { properties, .. }
+                | PatternElement::Relationship { properties, .. } => properties.as_mut(),
+            })
+    }
+}
+
 impl fmt::Display for Pattern {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for (i, element) in self.elements.iter().enumerate() {
-            if i > 0 {
-                write!(f, "-")?;
-            }
+        if let Some(variable) = &self.variable {
+            write!(f, "{variable} = ")?;
+        }
+        for element in &self.elements {
             write!(f, "{element}")?;
         }
         Ok(())
@@ -142,11 +589,13 @@ impl fmt::Display for Pattern {
 pub enum PatternElement {
     Node {
         variable: Option<Ident>,
+        #[cfg_attr(feature = "serde", serde(default))]
         labels: Vec<Ident>,
         properties: Option<Expr>,
     },
     Relationship {
         variable: Option<Ident>,
+        #[cfg_attr(feature = "serde", serde(default))]
         types: Vec<Ident>,
         properties: Option<Expr>,
         direction: RelationshipDirection,
@@ -154,6 +603,161 @@ pub enum PatternElement {
     },
 }
 
+impl PatternElement {
+    /// A node bound to `variable`, such as `(n)`
+    pub fn node(variable: impl Into<String>) -> Self {
+        PatternElement::Node {
+            variable: Some(Ident::new(variable)),
+            labels: vec![],
+            properties: None,
+        }
+    }
+
+    /// A node without a variable, such as `(:Person)`
+    pub fn anonymous_node() -> Self {
+        PatternElement::Node {
+            variable: None,
+            labels: vec![],
+            properties: None,
+        }
+    }
+
+    /// An undirected relationship bound to `variable`, such as `-[r]-`
+    pub fn rel(variable: impl Into<String>) -> Self {
+        PatternElement::Relationship {
+            variable: Some(Ident::new(variable)),
+            types: vec![],
+            properties: None,
+            direction: RelationshipDirection::None,
+            length: None,
+        }
+    }
+
+    /// An undirected relationship without a variable, such as `-[:KNOWS]-`
+    pub fn anonymous_rel() -> Self {
+        PatternElement::Relationship {
+            variable: None,
+            types: vec![],
+            properties: None,
+            direction: RelationshipDirection::None,
+            length: None,
+        }
+    }
+
+    /// Add a label to a node
+    ///
+    /// On a relationship this adds a type, like [`PatternElement::rtype`].
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        match &mut self {
+            PatternElement::Node { labels: names, .. }
+            | PatternElement::Relationship { types: names, .. } => names.push(Ident::new(label)),
+        }
+        self
+    }
+
+    /// Add an alternative type to a relationship, as in `-[:LIKES|LOVES]-`
+    ///
+    /// On a node this adds a label, like [`PatternElement::label`].
+    pub fn rtype(self, rel_type: impl Into<String>) -> Self {
+        self.label(rel_type)
+    }
+
+    /// Add `key: value` to the property map of the element
+    ///
+    /// A property map that is not a map literal, such as a parameter, is
+    /// replaced.
+    pub fn property(mut self, key: impl Into<String>, value: Expr) -> Self {
+        let field = DictionaryField {
+            key: Ident::new(key),
+            value: Box::new(value),
+        };
+        match &mut self {
+            PatternElement::Node { properties, .. }
+            | PatternElement::Relationship { properties, .. } => match properties {
+                Some(Expr::Dictionary(fields)) => fields.push(field),
+                _ => *properties = Some(Expr::Dictionary(vec![field])),
+            },
+        }
+        self
+    }
+
+    /// Point a relationship from left to right, as in `-[r]->`
+    ///
+    /// Has no effect on nodes, like the other direction methods.
+    pub fn right(self) -> Self {
+        self.with_direction(RelationshipDirection::Right)
+    }
+
+    /// Point a relationship from right to left, as in `<-[r]-`
+    pub fn left(self) -> Self {
+        self.with_direction(RelationshipDirection::Left)
+    }
+
+    /// Point a relationship both ways, as in `<-[r]->`
+    pub fn both(self) -> Self {
+        self.with_direction(RelationshipDirection::Both)
+    }
+
+    /// Make a relationship undirected, as in `-[r]-`
+    pub fn undirected(self) -> Self {
+        self.with_direction(RelationshipDirection::None)
+    }
+
+    fn with_direction(mut self, new_direction: RelationshipDirection) -> Self {
+        if let PatternElement::Relationship { direction, .. } = &mut self {
+            *direction = new_direction;
+        }
+        self
+    }
+
+    /// Set the length of a relationship, as in `-[r*1..3]-`
+    ///
+    /// Has no effect on nodes.
+    pub fn length(mut self, new_length: RelationshipLength) -> Self {
+        if let PatternElement::Relationship { length, .. } = &mut self {
+            *length = Some(new_length);
+        }
+        self
+    }
+
+    /// Whether the element is a node rather than a relationship
+    pub fn is_node(&self) -> bool {
+        matches!(self, PatternElement::Node { .. })
+    }
+
+    /// The variable the element is bound to, if it is named
+    pub fn variable(&self) -> Option<&Ident> {
+        match self {
+            PatternElement::Node { variable, .. }
+            | PatternElement::Relationship { variable, .. } => variable.as_ref(),
+        }
+    }
+
+    /// The labels of a node or the types of a relationship
+    pub fn labels_or_types(&self) -> &[Ident] {
+        match self {
+            PatternElement::Node { labels, .. } => labels,
+            PatternElement::Relationship { types, .. } => types,
+        }
+    }
+
+    /// The property map of the element, if it has one
+    pub fn properties(&self) -> Option<&Expr> {
+        match self {
+            PatternElement::Node { properties, .. }
+            | PatternElement::Relationship { properties, .. } => properties.as_ref(),
+        }
+    }
+
+    /// The direction of a relationship, or `None` for a node
+    pub fn direction(&self) -> Option<&RelationshipDirection> {
+        match self {
+            PatternElement::Node { .. } => None,
+            PatternElement::Relationship { direction, .. } => Some(direction),
+        }
+    }
+}
+
 impl fmt::Display for PatternElement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -170,7 +774,11 @@ impl fmt::Display for PatternElement {
                     write!(f, ":{label}")?;
                 }
                 if let Some(props) = properties {
-                    write!(f, " {props}")?;
+                    // `({k: v})` has nothing before its properties
+                    if variable.is_some() || !labels.is_empty() {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{props}")?;
                 }
                 write!(f, ")")
             }
@@ -182,11 +790,9 @@ impl fmt::Display for PatternElement {
                 length,
             } => {
                 match direction {
-                    RelationshipDirection::Left => write!(f, "<")?,
-                    RelationshipDirection::Both => write!(f, "<")?,
-                    _ => {}
+                    RelationshipDirection::Left | RelationshipDirection::Both => write!(f, "<-[")?,
+                    RelationshipDirection::Right | RelationshipDirection::None => write!(f, "-[")?,
                 }
-                write!(f, "[")?;
                 if let Some(var) = variable {
                     write!(f, "{var}")?;
                 }
@@ -202,15 +808,15 @@ impl fmt::Display for PatternElement {
                     write!(f, "{len}")?;
                 }
                 if let Some(props) = properties {
-                    write!(f, " {props}")?;
+                    if variable.is_some() || !types.is_empty() || length.is_some() {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{props}")?;
                 }
-                write!(f, "]")?;
                 match direction {
-                    RelationshipDirection::Right => write!(f, ">")?,
-                    RelationshipDirection::Both => write!(f, ">")?,
-                    _ => {}
+                    RelationshipDirection::Right | RelationshipDirection::Both => write!(f, "]->"),
+                    RelationshipDirection::Left | RelationshipDirection::None => write!(f, "]-"),
                 }
-                Ok(())
             }
         }
     }
@@ -265,37 +871,240 @@ impl fmt::Display for RelationshipLength {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
 pub struct ReturnClause {
+    #[cfg_attr(feature = "serde", serde(default))]
     pub distinct: bool,
     pub items: Vec<SelectItem>,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub order_by: Vec<OrderByExpr>,
     pub limit: Option<Expr>,
     pub skip: Option<Expr>,
 }
 
-impl fmt::Display for ReturnClause {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "RETURN")?;
+impl ReturnClause {
+    /// A `RETURN` of `items`, without modifiers
+    pub fn items(items: impl IntoIterator<Item = SelectItem>) -> Self {
+        ReturnClause {
+            distinct: false,
+            items: items.into_iter().collect(),
+            order_by: vec![],
+            limit: None,
+            skip: None,
+        }
+    }
+
+    /// Make the clause a `RETURN DISTINCT`
+    pub fn distinct(mut self) -> Self {
+        self.distinct = true;
+        self
+    }
+
+    /// Add a key to the `ORDER BY`, after the existing ones
+    pub fn order_by(mut self, key: impl Into<OrderByExpr>) -> Self {
+        self.order_by.push(key.into());
+        self
+    }
+
+    /// Set the `SKIP` expression
+    pub fn skip(mut self, skip: Expr) -> Self {
+        self.skip = Some(skip);
+        self
+    }
+
+    /// Set the `LIMIT` expression
+    pub fn limit(mut self, limit: Expr) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The expressions of the items and modifiers
+    pub(crate) fn exprs(&self) -> Vec<&Expr> {
+        let mut exprs: Vec<&Expr> = self
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    Some(expr)
+                }
+                _ => None,
+            })
+            .collect();
+        exprs.extend(self.order_by.iter().map(|key| &key.expr));
+        exprs.extend(&self.skip);
+        exprs.extend(&self.limit);
+        exprs
+    }
+
+    /// The expressions of the items and modifiers, mutably
+    pub(crate) fn exprs_mut(&mut self) -> Vec<&mut Expr> {
+        let mut exprs: Vec<&mut Expr> = self
+            .items
+            .iter_mut()
+            .filter_map(|item| match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    Some(expr)
+                }
+                _ => None,
+            })
+            .collect();
+        exprs.extend(self.order_by.iter_mut().map(|key| &mut key.expr));
+        exprs.extend(&mut self.skip);
+        exprs.extend(&mut self.limit);
+        exprs
+    }
+
+    /// Write the clause after its `RETURN` or `WITH` keyword
+    fn fmt_projection(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if self.distinct {
             write!(f, " DISTINCT")?;
         }
         write!(f, " {}", display_comma_separated(&self.items))?;
-        
         if !self.order_by.is_empty() {
             write!(f, " ORDER BY {}", display_comma_separated(&self.order_by))?;
         }
-        
         if let Some(skip) = &self.skip {
             write!(f, " SKIP {skip}")?;
         }
-        
         if let Some(limit) = &self.limit {
             write!(f, " LIMIT {limit}")?;
         }
-        
         Ok(())
     }
 }
 
+impl fmt::Display for ReturnClause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RETURN")?;
+        self.fmt_projection(f)
+    }
+}
+
+/// WITH clause in Cypher queries, which projects the rows matched so far
+/// for the clauses after it
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub struct WithClause {
+    /// The items, with the same modifiers as a `RETURN`
+    pub projection: ReturnClause,
+    /// A condition on the projected rows, which may refer to the aliases
+    /// of the items
+    pub r#where: Option<Expr>,
+}
+
+impl fmt::Display for WithClause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WITH")?;
+        self.projection.fmt_projection(f)?;
+        if let Some(condition) = &self.r#where {
+            write!(f, " WHERE {condition}")?;
+        }
+        Ok(())
+    }
+}
+
+/// When the items of a [`MergeSetAction`] are set
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub enum MergeTrigger {
+    /// `ON CREATE`: when the MERGE creates its pattern
+    OnCreate,
+    /// `ON MATCH`: when the MERGE finds its pattern
+    OnMatch,
+}
+
+impl fmt::Display for MergeTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MergeTrigger::OnCreate => write!(f, "ON CREATE"),
+            MergeTrigger::OnMatch => write!(f, "ON MATCH"),
+        }
+    }
+}
+
+/// What a `SHOW` statement lists
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub enum ShowTarget {
+    Databases,
+    Procedures,
+    Functions,
+    Indexes,
+    Constraints,
+    Transactions,
+}
+
+impl fmt::Display for ShowTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ShowTarget::Databases => "DATABASES",
+            ShowTarget::Procedures => "PROCEDURES",
+            ShowTarget::Functions => "FUNCTIONS",
+            ShowTarget::Indexes => "INDEXES",
+            ShowTarget::Constraints => "CONSTRAINTS",
+            ShowTarget::Transactions => "TRANSACTIONS",
+        })
+    }
+}
+
+/// A directive of a Neo4j Browser or cypher-shell script, written on a line
+/// of its own starting with `:`, e.g. `:param minAge => 21`
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub enum CypherDirective {
+    /// `:param name => value`: set the parameter `$name` for the statements
+    /// after it
+    Param { name: Ident, value: Expr },
+    /// `:use database`: run the statements after it in another database
+    Use(Ident),
+    /// `:begin`: open a transaction
+    Begin,
+    /// `:commit`: commit the open transaction
+    Commit,
+    /// `:rollback`: roll back the open transaction
+    Rollback,
+    /// Any other directive, e.g. `:sysinfo`, with the text after its name
+    Other { name: String, arguments: String },
+}
+
+impl fmt::Display for CypherDirective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CypherDirective::Param { name, value } => write!(f, ":param {name} => {value}"),
+            CypherDirective::Use(database) => write!(f, ":use {database}"),
+            CypherDirective::Begin => write!(f, ":begin"),
+            CypherDirective::Commit => write!(f, ":commit"),
+            CypherDirective::Rollback => write!(f, ":rollback"),
+            CypherDirective::Other { name, arguments } if arguments.is_empty() => {
+                write!(f, ":{name}")
+            }
+            CypherDirective::Other { name, arguments } => write!(f, ":{name} {arguments}"),
+        }
+    }
+}
+
+/// An `ON CREATE SET ...` or `ON MATCH SET ...` action of a MERGE
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub struct MergeSetAction {
+    pub trigger: MergeTrigger,
+    pub items: Vec<SetClause>,
+}
+
+impl fmt::Display for MergeSetAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} SET {}",
+            self.trigger,
+            display_comma_separated(&self.items)
+        )
+    }
+}
+
 /// SET clause for updating properties
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -342,4 +1151,941 @@ impl fmt::Display for SetTarget {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Cypher-specific expressions that have no SQL counterpart
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub enum CypherExpr {
+    /// Label predicate: `n:Person` or `n:Person:Admin`
+    ///
+    /// Evaluates to true when the node has every one of the labels.
+    LabelPredicate { expr: Expr, labels: Vec<Ident> },
+    /// Pattern comprehension: `[(n)-[:KNOWS]->(f) WHERE f.age > 30 | f.name]`
+    ///
+    /// Evaluates to the list of the projection for every match of the
+    /// pattern, which may use the variables bound outside of it.
+    PatternComprehension {
+        pattern: Pattern,
+        r#where: Option<Expr>,
+        projection: Expr,
+    },
+    /// Type predicate: `n.age IS :: INTEGER` or `n.name IS NOT :: STRING`
+    ///
+    /// Evaluates to true when the value has the type, which includes `null`.
+    TypePredicate {
+        expr: Expr,
+        negated: bool,
+        data_type: CypherType,
+    },
+    /// Membership in a list other than a list literal:
+    /// `'Admin' IN labels(n)` or `n.id IN $ids`
+    ///
+    /// A list literal, `n.age IN [1, 2]`, is parsed as an [`Expr::InList`].
+    In { expr: Expr, list: Expr },
+    /// String matching: `n.name STARTS WITH 'A'`, `ENDS WITH` or `CONTAINS`
+    ///
+    /// Evaluates to true when the string has the other one at its start, at
+    /// its end or anywhere in it, comparing case-sensitively.
+    StringPredicate {
+        expr: Expr,
+        operator: StringOperator,
+        pattern: Expr,
+    },
+    /// A pattern as a predicate, the argument of the legacy
+    /// `exists((n)-[:KNOWS]->())`
+    ///
+    /// Evaluates to true when the pattern has a match, which may use the
+    /// variables bound outside of it.
+    PatternPredicate { pattern: Pattern },
+}
+
+impl fmt::Display for CypherExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CypherExpr::LabelPredicate { expr, labels } => {
+                write!(f, "{expr}")?;
+                for label in labels {
+                    write!(f, ":{label}")?;
+                }
+                Ok(())
+            }
+            CypherExpr::PatternComprehension {
+                pattern,
+                r#where,
+                projection,
+            } => {
+                write!(f, "[{pattern}")?;
+                if let Some(condition) = r#where {
+                    write!(f, " WHERE {condition}")?;
+                }
+                write!(f, " | {projection}]")
+            }
+            CypherExpr::TypePredicate {
+                expr,
+                negated,
+                data_type,
+            } => {
+                let not = if *negated { "NOT " } else { "" };
+                write!(f, "{expr} IS {not}:: {data_type}")
+            }
+            CypherExpr::In { expr, list } => write!(f, "{expr} IN {list}"),
+            CypherExpr::StringPredicate {
+                expr,
+                operator,
+                pattern,
+            } => write!(f, "{expr} {operator} {pattern}"),
+            CypherExpr::PatternPredicate { pattern } => write!(f, "{pattern}"),
+        }
+    }
+}
+
+/// The operator of a [`CypherExpr::StringPredicate`]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub enum StringOperator {
+    /// `STARTS WITH`
+    StartsWith,
+    /// `ENDS WITH`
+    EndsWith,
+    /// `CONTAINS`
+    Contains,
+}
+
+impl fmt::Display for StringOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StringOperator::StartsWith => write!(f, "STARTS WITH"),
+            StringOperator::EndsWith => write!(f, "ENDS WITH"),
+            StringOperator::Contains => write!(f, "CONTAINS"),
+        }
+    }
+}
+
+/// A value type named in a [`CypherExpr::TypePredicate`], e.g. `INTEGER`
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub enum CypherType {
+    Any,
+    Boolean,
+    String,
+    Integer,
+    Float,
+    Null,
+    Date,
+    Duration,
+    Point,
+    Node,
+    Relationship,
+    Path,
+    Map,
+    /// `LIST`, or `LIST<INTEGER>` with the type of its elements
+    List(Option<Box<CypherType>>),
+}
+
+impl fmt::Display for CypherType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CypherType::Any => write!(f, "ANY"),
+            CypherType::Boolean => write!(f, "BOOLEAN"),
+            CypherType::String => write!(f, "STRING"),
+            CypherType::Integer => write!(f, "INTEGER"),
+            CypherType::Float => write!(f, "FLOAT"),
+            CypherType::Null => write!(f, "NULL"),
+            CypherType::Date => write!(f, "DATE"),
+            CypherType::Duration => write!(f, "DURATION"),
+            CypherType::Point => write!(f, "POINT"),
+            CypherType::Node => write!(f, "NODE"),
+            CypherType::Relationship => write!(f, "RELATIONSHIP"),
+            CypherType::Path => write!(f, "PATH"),
+            CypherType::Map => write!(f, "MAP"),
+            CypherType::List(None) => write!(f, "LIST"),
+            CypherType::List(Some(element)) => write!(f, "LIST<{element}>"),
+        }
+    }
+}
+
+/// A label, relationship type or property in an [`AccessSummary`]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AccessName {
+    /// Any name: a node without a label, a relationship without a type, or
+    /// every property of an element, e.g. `RETURN n`
+    Any,
+    Named(String),
+}
+
+/// A property of the elements with a label or relationship type
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PropertyAccess {
+    /// The label of the node or the type of the relationship
+    pub owner: AccessName,
+    pub property: AccessName,
+}
+
+/// The labels, relationship types and properties a Cypher statement reads or
+/// writes, as computed by [`CypherStatement::access_summary`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccessSummary {
+    pub labels_read: BTreeSet<AccessName>,
+    pub labels_written: BTreeSet<AccessName>,
+    /// The relationship types that are matched or created
+    pub relationship_types: BTreeSet<AccessName>,
+    pub properties_read: BTreeSet<PropertyAccess>,
+    pub properties_written: BTreeSet<PropertyAccess>,
+}
+
+impl CypherStatement {
+    /// The statement written with the default [`CypherFormatter`] options,
+    /// identical for statements that are equal
+    pub fn to_canonical_string(&self) -> String {
+        CypherFormatter::default().format(self)
+    }
+
+    /// The top-level expressions of the statement and its patterns
+    pub(crate) fn exprs(&self) -> Vec<&Expr> {
+        let mut exprs = vec![];
+        match self {
+            CypherStatement::Match {
+                patterns,
+                r#where,
+                with,
+                r#return,
+                next,
+                ..
+            } => {
+                exprs.extend(patterns.iter().flat_map(Pattern::properties));
+                exprs.extend(r#where);
+                if let Some(with) = with {
+                    exprs.extend(with.projection.exprs());
+                    exprs.extend(&with.r#where);
+                }
+                exprs.extend(next.iter().flat_map(|next| next.exprs()));
+                exprs.extend(r#return.iter().flat_map(ReturnClause::exprs));
+            }
+            CypherStatement::Create {
+                r#match,
+                r#where,
+                patterns,
+                r#return,
+            } => {
+                exprs.extend(r#match.iter().flat_map(Pattern::properties));
+                exprs.extend(r#where);
+                exprs.extend(patterns.iter().flat_map(Pattern::properties));
+                exprs.extend(r#return.iter().flat_map(ReturnClause::exprs));
+            }
+            CypherStatement::Merge { patterns, actions } => {
+                exprs.extend(patterns.iter().flat_map(Pattern::properties));
+                let items = actions.iter().flat_map(|action| &action.items);
+                exprs.extend(items.map(|item| &item.value));
+            }
+            CypherStatement::Delete {
+                patterns,
+                what,
+                r#where,
+                ..
+            } => {
+                exprs.extend(patterns.iter().flat_map(Pattern::properties));
+                exprs.extend(what);
+                exprs.extend(r#where);
+            }
+            CypherStatement::Set {
+                patterns,
+                r#where,
+                items,
+            } => {
+                exprs.extend(patterns.iter().flat_map(Pattern::properties));
+                exprs.extend(r#where);
+                exprs.extend(items.iter().map(|item| &item.value));
+            }
+            CypherStatement::Union { queries, .. } => {
+                exprs.extend(queries.iter().flat_map(CypherStatement::exprs));
+            }
+            CypherStatement::Unwind { list, query, .. } => {
+                exprs.push(list);
+                exprs.extend(query.exprs());
+            }
+            CypherStatement::Recovered { statement } => exprs.extend(statement.exprs()),
+            CypherStatement::Show {
+                yield_items,
+                r#where,
+                ..
+            } => {
+                exprs.extend(yield_items.iter().filter_map(|item| match item {
+                    SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                        Some(expr)
+                    }
+                    _ => None,
+                }));
+                exprs.extend(r#where);
+            }
+        }
+        exprs
+    }
+
+    /// The top-level expressions of the statement and its patterns, mutably
+    pub(crate) fn exprs_mut(&mut self) -> Vec<&mut Expr> {
+        let mut exprs = vec![];
+        match self {
+            CypherStatement::Match {
+                patterns,
+                r#where,
+                with,
+                r#return,
+                next,
+                ..
+            } => {
+                exprs.extend(patterns.iter_mut().flat_map(Pattern::properties_mut));
+                exprs.extend(r#where);
+                if let Some(with) = with {
+                    exprs.extend(with.projection.exprs_mut());
+                    exprs.extend(&mut with.r#where);
+                }
+                exprs.extend(next.iter_mut().flat_map(|next| next.exprs_mut()));
+                exprs.extend(r#return.iter_mut().flat_map(ReturnClause::exprs_mut));
+            }
+            CypherStatement::Create {
+                r#match,
+                r#where,
+                patterns,
+                r#return,
+            } => {
+                exprs.extend(r#match.iter_mut().flat_map(Pattern::properties_mut));
+                exprs.extend(r#where);
+                exprs.extend(patterns.iter_mut().flat_map(Pattern::properties_mut));
+                exprs.extend(r#return.iter_mut().flat_map(ReturnClause::exprs_mut));
+            }
+            CypherStatement::Merge { patterns, actions } => {
+                exprs.extend(patterns.iter_mut().flat_map(Pattern::properties_mut));
+                let items = actions.iter_mut().flat_map(|action| &mut action.items);
+                exprs.extend(items.map(|item| &mut item.value));
+            }
+            CypherStatement::Delete {
+                patterns,
+                what,
+                r#where,
+                ..
+            } => {
+                exprs.extend(patterns.iter_mut().flat_map(Pattern::properties_mut));
+                exprs.extend(what);
+                exprs.extend(r#where);
+            }
+            CypherStatement::Set {
+                patterns,
+                r#where,
+                items,
+            } => {
+                exprs.extend(patterns.iter_mut().flat_map(Pattern::properties_mut));
+                exprs.extend(r#where);
+                exprs.extend(items.iter_mut().map(|item| &mut item.value));
+            }
+            CypherStatement::Union { queries, .. } => {
+                exprs.extend(queries.iter_mut().flat_map(CypherStatement::exprs_mut));
+            }
+            CypherStatement::Unwind { list, query, .. } => {
+                exprs.push(list);
+                exprs.extend(query.exprs_mut());
+            }
+            CypherStatement::Recovered { statement } => exprs.extend(statement.exprs_mut()),
+            CypherStatement::Show {
+                yield_items,
+                r#where,
+                ..
+            } => {
+                exprs.extend(yield_items.iter_mut().filter_map(|item| match item {
+                    SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                        Some(expr)
+                    }
+                    _ => None,
+                }));
+                exprs.extend(r#where);
+            }
+        }
+        exprs
+    }
+
+    /// The labels, relationship types and properties the statement touches
+    ///
+    /// The properties of a variable are attributed to each of its labels or
+    /// types, from its patterns and from label predicates such as `n:Person`,
+    /// or to [`AccessName::Any`] when it has none.
+    pub fn access_summary(&self) -> AccessSummary {
+        let mut summary = AccessSummary::default();
+        self.add_access(&mut summary);
+        summary
+    }
+
+    /// Add the accesses of a MATCH and the query parts after it, with the
+    /// variables of the parts before it bound in `scope`
+    fn add_match_access(&self, mut scope: AccessScope, summary: &mut AccessSummary) {
+        let CypherStatement::Match {
+            patterns,
+            r#where,
+            with,
+            r#return,
+            next,
+            ..
+        } = self
+        else {
+            return;
+        };
+        let with_where = with.iter().flat_map(|with| &with.r#where);
+        scope.bind(patterns, r#where.iter().chain(with_where));
+        scope.add_patterns(patterns, false, summary);
+        if let Some(expr) = r#where {
+            scope.add_reads(expr, summary);
+        }
+        if let Some(with) = with {
+            for expr in with.projection.exprs().into_iter().chain(&with.r#where) {
+                scope.add_reads(expr, summary);
+            }
+        }
+        if let Some(ret) = r#return {
+            for item in &ret.items {
+                match item {
+                    SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                        scope.add_reads(expr, summary)
+                    }
+                    // Every property of every variable
+                    _ => {
+                        for (variable, _) in &scope.variables {
+                            scope.add_properties(variable, &AccessName::Any, false, summary);
+                        }
+                    }
+                }
+            }
+            let modifiers = ret.order_by.iter().map(|key| &key.expr);
+            for expr in modifiers.chain(&ret.skip).chain(&ret.limit) {
+                scope.add_reads(expr, summary);
+            }
+        }
+        if let Some(next) = next {
+            next.add_match_access(scope, summary);
+        }
+    }
+
+    fn add_access(&self, summary: &mut AccessSummary) {
+        match self {
+            CypherStatement::Match { .. } => {
+                self.add_match_access(AccessScope::new(&[], None.iter()), summary)
+            }
+            CypherStatement::Create {
+                r#match,
+                r#where,
+                patterns,
+                ..
+            } => {
+                let mut scope = AccessScope::new(r#match, r#where.iter());
+                scope.add_patterns(r#match, false, summary);
+                if let Some(expr) = r#where {
+                    scope.add_reads(expr, summary);
+                }
+                // The nodes MATCH binds are connected rather than created
+                let bound = matched_variables(r#match);
+                let created: Vec<Pattern> = patterns
+                    .iter()
+                    .map(|pattern| {
+                        Pattern::path(
+                            pattern
+                                .elements
+                                .iter()
+                                .filter(|element| {
+                                    !element.is_node()
+                                        || !element.variable().is_some_and(|v| bound.contains(&v))
+                                })
+                                .cloned(),
+                        )
+                    })
+                    .collect();
+                scope.bind(&created, None.iter());
+                scope.add_patterns(&created, true, summary);
+            }
+            CypherStatement::Merge { patterns, actions } => {
+                // MERGE matches its pattern and creates it when it is missing
+                let scope = AccessScope::new(patterns, None.iter());
+                scope.add_patterns(patterns, false, summary);
+                scope.add_patterns(patterns, true, summary);
+                for clause in actions.iter().flat_map(|action| &action.items) {
+                    scope.add_set(clause, summary);
+                }
+            }
+            CypherStatement::Set {
+                patterns,
+                r#where,
+                items,
+            } => {
+                let scope = AccessScope::new(patterns, r#where.iter());
+                scope.add_patterns(patterns, false, summary);
+                if let Some(expr) = r#where {
+                    scope.add_reads(expr, summary);
+                }
+                for clause in items {
+                    scope.add_set(clause, summary);
+                }
+            }
+            CypherStatement::Delete {
+                patterns,
+                what,
+                r#where,
+                ..
+            } => {
+                let scope = AccessScope::new(patterns, r#where.iter());
+                scope.add_patterns(patterns, false, summary);
+                if let Some(expr) = r#where {
+                    scope.add_reads(expr, summary);
+                }
+                // Deleting a node writes its labels
+                let relationships = patterns
+                    .iter()
+                    .flat_map(|pattern| &pattern.elements)
+                    .filter(|element| !element.is_node());
+                let relationship_variables: Vec<&Ident> =
+                    relationships.filter_map(PatternElement::variable).collect();
+                for target in what {
+                    if let Expr::Identifier(variable) = target {
+                        if relationship_variables.contains(&variable) {
+                            continue;
+                        }
+                        let labels = scope.names_of(&variable.value);
+                        if labels.is_empty() {
+                            summary.labels_written.insert(AccessName::Any);
+                        }
+                        summary.labels_written.extend(labels);
+                    }
+                }
+            }
+            CypherStatement::Union { queries, .. } => {
+                for query in queries {
+                    query.add_access(summary);
+                }
+            }
+            CypherStatement::Unwind { query, .. } => query.add_access(summary),
+            CypherStatement::Recovered { statement } => statement.add_access(summary),
+            // Listings of the database touch no graph data
+            CypherStatement::Show { .. } => {}
+        }
+    }
+}
+
+/// The labels or types of the variables of a statement
+struct AccessScope {
+    variables: Vec<(String, Vec<AccessName>)>,
+}
+
+impl AccessScope {
+    fn new<'a>(patterns: &[Pattern], conditions: impl Iterator<Item = &'a Expr>) -> Self {
+        let mut scope = AccessScope { variables: vec![] };
+        scope.bind(patterns, conditions);
+        scope
+    }
+
+    /// Add the variables of `patterns`, and the labels `conditions` require
+    fn bind<'a>(&mut self, patterns: &[Pattern], conditions: impl Iterator<Item = &'a Expr>) {
+        for element in patterns.iter().flat_map(|pattern| &pattern.elements) {
+            if let Some(variable) = element.variable() {
+                self.add_names(&variable.value, element.labels_or_types());
+            }
+        }
+        for condition in conditions {
+            let mut exprs = vec![condition];
+            while let Some(expr) = exprs.pop() {
+                if let Expr::Cypher(cypher_expr) = expr {
+                    if let CypherExpr::LabelPredicate {
+                        expr: Expr::Identifier(variable),
+                        labels,
+                    } = cypher_expr.as_ref()
+                    {
+                        self.add_names(&variable.value, labels);
+                    }
+                }
+                exprs.extend(subexpressions(expr));
+            }
+        }
+    }
+
+    fn add_names(&mut self, variable: &str, names: &[Ident]) {
+        let names = names
+            .iter()
+            .map(|name| AccessName::Named(name.value.clone()));
+        match self.variables.iter_mut().find(|(v, _)| v == variable) {
+            Some((_, existing)) => {
+                for name in names {
+                    if !existing.contains(&name) {
+                        existing.push(name);
+                    }
+                }
+            }
+            None => self.variables.push((variable.into(), names.collect())),
+        }
+    }
+
+    /// Add the labels, types and property maps of `patterns`
+    fn add_patterns(&self, patterns: &[Pattern], write: bool, summary: &mut AccessSummary) {
+        for element in patterns.iter().flat_map(|pattern| &pattern.elements) {
+            let names = match (element.is_node(), write) {
+                (true, true) => &mut summary.labels_written,
+                (true, false) => &mut summary.labels_read,
+                (false, _) => &mut summary.relationship_types,
+            };
+            let owners = match element.variable() {
+                Some(variable) => self.names_of(&variable.value),
+                None => element
+                    .labels_or_types()
+                    .iter()
+                    .map(|name| AccessName::Named(name.value.clone()))
+                    .collect(),
+            };
+            if owners.is_empty() {
+                names.insert(AccessName::Any);
+            }
+            names.extend(owners.iter().cloned());
+
+            let Some(Expr::Dictionary(fields)) = element.properties() else {
+                continue;
+            };
+            for field in fields {
+                let property = AccessName::Named(field.key.value.clone());
+                self.add_owned_properties(&owners, &property, write, summary);
+                self.add_reads(&field.value, summary);
+            }
+        }
+    }
+
+    /// Add the properties read by `expr`
+    fn add_reads(&self, expr: &Expr, summary: &mut AccessSummary) {
+        let mut exprs = vec![expr];
+        while let Some(expr) = exprs.pop() {
+            if let Some((variable, property)) = properties_field(expr) {
+                let property = AccessName::Named(property.value.clone());
+                self.add_properties(&variable.value, &property, false, summary);
+                continue;
+            }
+            match expr {
+                Expr::CompoundIdentifier(idents) if idents.len() == 2 => {
+                    let property = AccessName::Named(idents[1].value.clone());
+                    self.add_properties(&idents[0].value, &property, false, summary);
+                }
+                // A whole variable reads all of its properties
+                Expr::Identifier(variable) => {
+                    if self.variables.iter().any(|(v, _)| *v == variable.value) {
+                        self.add_properties(&variable.value, &AccessName::Any, false, summary);
+                    }
+                }
+                // A pattern comprehension or predicate matches its pattern
+                // with the variables of the statement in scope
+                Expr::Cypher(cypher_expr) => {
+                    if let CypherExpr::PatternComprehension {
+                        pattern,
+                        r#where,
+                        projection,
+                    } = cypher_expr.as_ref()
+                    {
+                        let pattern = core::slice::from_ref(pattern);
+                        let mut scope = AccessScope {
+                            variables: self.variables.clone(),
+                        };
+                        scope.bind(pattern, r#where.iter());
+                        scope.add_patterns(pattern, false, summary);
+                        for expr in r#where.iter().chain([projection]) {
+                            scope.add_reads(expr, summary);
+                        }
+                    } else if let CypherExpr::PatternPredicate { pattern } = cypher_expr.as_ref() {
+                        let pattern = core::slice::from_ref(pattern);
+                        let mut scope = AccessScope {
+                            variables: self.variables.clone(),
+                        };
+                        scope.bind(pattern, [].into_iter());
+                        scope.add_patterns(pattern, false, summary);
+                    } else {
+                        exprs.extend(subexpressions(expr));
+                    }
+                }
+                _ => exprs.extend(subexpressions(expr)),
+            }
+        }
+    }
+
+    /// Add the properties read by the value of a SET item and written by it
+    fn add_set(&self, clause: &SetClause, summary: &mut AccessSummary) {
+        self.add_reads(&clause.value, summary);
+        match &clause.target {
+            SetTarget::Property { variable, property } => self.add_properties(
+                &variable.value,
+                &AccessName::Named(property.value.clone()),
+                true,
+                summary,
+            ),
+            SetTarget::Variable(variable) => {
+                self.add_properties(&variable.value, &AccessName::Any, true, summary)
+            }
+            SetTarget::Label { label, .. } => {
+                summary
+                    .labels_written
+                    .insert(AccessName::Named(label.value.clone()));
+            }
+        }
+    }
+
+    fn add_properties(
+        &self,
+        variable: &str,
+        property: &AccessName,
+        write: bool,
+        summary: &mut AccessSummary,
+    ) {
+        self.add_owned_properties(&self.names_of(variable), property, write, summary);
+    }
+
+    fn add_owned_properties(
+        &self,
+        owners: &[AccessName],
+        property: &AccessName,
+        write: bool,
+        summary: &mut AccessSummary,
+    ) {
+        let properties = if write {
+            &mut summary.properties_written
+        } else {
+            &mut summary.properties_read
+        };
+        let any = [AccessName::Any];
+        let owners = if owners.is_empty() { &any[..] } else { owners };
+        for owner in owners {
+            properties.insert(PropertyAccess {
+                owner: owner.clone(),
+                property: property.clone(),
+            });
+        }
+    }
+
+    fn names_of(&self, variable: &str) -> Vec<AccessName> {
+        self.variables
+            .iter()
+            .find(|(v, _)| v == variable)
+            .map(|(_, names)| names.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// A string literal written with Cypher's backslash escapes, or `None` when
+/// `expr` is not a string that needs them
+fn cypher_string_literal(expr: &Expr) -> Option<String> {
+    let (value, quote) = match expr {
+        Expr::Value(ValueWithSpan {
+            value: Value::SingleQuotedString(value),
+            ..
+        }) => (value, '\''),
+        Expr::Value(ValueWithSpan {
+            value: Value::DoubleQuotedString(value),
+            ..
+        }) => (value, '"'),
+        _ => return None,
+    };
+    if !value.contains(['\\', quote, '\n', '\r', '\t']) {
+        return None;
+    }
+
+    let mut literal = String::from(quote);
+    for ch in value.chars() {
+        match ch {
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            '\\' => literal.push_str("\\\\"),
+            ch if ch == quote => {
+                literal.push('\\');
+                literal.push(ch);
+            }
+            ch => literal.push(ch),
+        }
+    }
+    literal.push(quote);
+    Some(literal)
+}
+
+/// The direct subexpressions of the expressions a Cypher statement can hold
+pub(crate) fn subexpressions(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::BinaryOp { left, right, .. } => vec![left, right],
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Cast { expr, .. } => vec![expr],
+        Expr::InList { expr, list, .. } => {
+            let mut exprs = vec![expr.as_ref()];
+            exprs.extend(list);
+            exprs
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => vec![expr, low, high],
+        Expr::Like { expr, pattern, .. } => vec![expr, pattern],
+        Expr::Case {
+            operand,
+            conditions,
+            else_result,
+            ..
+        } => {
+            let mut exprs: Vec<&Expr> = operand.iter().map(AsRef::as_ref).collect();
+            for when in conditions {
+                exprs.push(&when.condition);
+                exprs.push(&when.result);
+            }
+            exprs.extend(else_result.iter().map(AsRef::as_ref));
+            exprs
+        }
+        // The fields accessed are names, not expressions
+        Expr::CompoundFieldAccess { root, .. } => vec![root],
+        Expr::Dictionary(fields) => fields.iter().map(|field| field.value.as_ref()).collect(),
+        Expr::Array(array) => array.elem.iter().collect(),
+        Expr::Function(function) => match &function.args {
+            FunctionArguments::List(list) => list
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))
+                    | FunctionArg::Named {
+                        arg: FunctionArgExpr::Expr(expr),
+                        ..
+                    } => Some(expr),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        },
+        Expr::Cypher(cypher_expr) => match cypher_expr.as_ref() {
+            CypherExpr::LabelPredicate { expr, .. } | CypherExpr::TypePredicate { expr, .. } => {
+                vec![expr]
+            }
+            CypherExpr::In { expr, list } => vec![expr, list],
+            CypherExpr::StringPredicate { expr, pattern, .. } => vec![expr, pattern],
+            CypherExpr::PatternPredicate { pattern } => pattern.properties().collect(),
+            CypherExpr::PatternComprehension {
+                pattern,
+                r#where,
+                projection,
+            } => {
+                let mut exprs: Vec<&Expr> = pattern.properties().collect();
+                exprs.extend(r#where);
+                exprs.push(projection);
+                exprs
+            }
+        },
+        _ => vec![],
+    }
+}
+
+/// The variables of the elements of `patterns`
+pub(crate) fn matched_variables(patterns: &[Pattern]) -> Vec<&Ident> {
+    patterns
+        .iter()
+        .flat_map(|pattern| &pattern.elements)
+        .filter_map(PatternElement::variable)
+        .collect()
+}
+
+/// Match a property access on the properties of a variable, e.g.
+/// `properties(n).name`, returning the variable and the property
+pub(crate) fn properties_field(expr: &Expr) -> Option<(&Ident, &Ident)> {
+    let Expr::CompoundFieldAccess { root, access_chain } = expr else {
+        return None;
+    };
+    let (Expr::Function(function), [AccessExpr::Dot(Expr::Identifier(property))]) =
+        (root.as_ref(), access_chain.as_slice())
+    else {
+        return None;
+    };
+    if !function.name.to_string().eq_ignore_ascii_case("properties") {
+        return None;
+    }
+    let FunctionArguments::List(list) = &function.args else {
+        return None;
+    };
+    match list.args.as_slice() {
+        [FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(variable)))] => {
+            Some((variable, property))
+        }
+        _ => None,
+    }
+}
+
+/// The direct subexpressions of `expr`, mutably
+pub(crate) fn subexpressions_mut(expr: &mut Expr) -> Vec<&mut Expr> {
+    match expr {
+        Expr::BinaryOp { left, right, .. } => vec![left, right],
+        Expr::UnaryOp { expr, .. }
+        | Expr::Nested(expr)
+        | Expr::IsNull(expr)
+        | Expr::IsNotNull(expr)
+        | Expr::Cast { expr, .. } => vec![expr],
+        Expr::InList { expr, list, .. } => {
+            let mut exprs = vec![expr.as_mut()];
+            exprs.extend(list);
+            exprs
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => vec![expr, low, high],
+        Expr::Like { expr, pattern, .. } => vec![expr, pattern],
+        Expr::Case {
+            operand,
+            conditions,
+            else_result,
+            ..
+        } => {
+            let mut exprs: Vec<&mut Expr> = operand.iter_mut().map(AsMut::as_mut).collect();
+            for when in conditions {
+                exprs.push(&mut when.condition);
+                exprs.push(&mut when.result);
+            }
+            exprs.extend(else_result.iter_mut().map(AsMut::as_mut));
+            exprs
+        }
+        Expr::CompoundFieldAccess { root, .. } => vec![root],
+        Expr::Dictionary(fields) => fields
+            .iter_mut()
+            .map(|field| field.value.as_mut())
+            .collect(),
+        Expr::Array(array) => array.elem.iter_mut().collect(),
+        Expr::Function(function) => match &mut function.args {
+            FunctionArguments::List(list) => list
+                .args
+                .iter_mut()
+                .filter_map(|arg| match arg {
+                    FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))
+                    | FunctionArg::Named {
+                        arg: FunctionArgExpr::Expr(expr),
+                        ..
+                    } => Some(expr),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        },
+        Expr::Cypher(cypher_expr) => match cypher_expr.as_mut() {
+            CypherExpr::LabelPredicate { expr, .. } | CypherExpr::TypePredicate { expr, .. } => {
+                vec![expr]
+            }
+            CypherExpr::In { expr, list } => vec![expr, list],
+            CypherExpr::StringPredicate { expr, pattern, .. } => vec![expr, pattern],
+            CypherExpr::PatternPredicate { pattern } => pattern.properties_mut().collect(),
+            CypherExpr::PatternComprehension {
+                pattern,
+                r#where,
+                projection,
+            } => {
+                let mut exprs: Vec<&mut Expr> = pattern.properties_mut().collect();
+                exprs.extend(r#where);
+                exprs.push(projection);
+                exprs
+            }
+        },
+        _ => vec![],
+    }
+}
@@ -1437,6 +1437,106 @@ pub enum TableFactor {
         /// The alias for the table
         alias: Option<TableAlias>,
     },
+    /// The `GRAPH_TABLE` of SQL/PGQ (SQL:2023): a row for every match of
+    /// graph patterns in a property graph, of the columns it lists.
+    ///
+    /// ```sql
+    /// SELECT name FROM GRAPH_TABLE (social
+    ///     MATCH (a IS Person)-[e IS KNOWS]->(b IS Person)
+    ///     WHERE a.age > 30
+    ///     COLUMNS (b.name AS name)
+    /// )
+    /// ```
+    GraphTable {
+        /// The property graph
+        graph: ObjectName,
+        /// The path patterns of `MATCH`, each a chain of vertices and edges
+        paths: Vec<Vec<GraphPatternElement>>,
+        /// `WHERE <expr>`
+        selection: Option<Expr>,
+        /// `COLUMNS (<expr> [AS <alias>] [, ... ])`
+        columns: Vec<SelectItem>,
+        alias: Option<TableAlias>,
+    },
+}
+
+/// A vertex or edge pattern in the `MATCH` of a `GRAPH_TABLE`
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub enum GraphPatternElement {
+    /// `(a IS Admin & Person)`, matching a vertex with every one of the
+    /// labels
+    Vertex {
+        variable: Option<Ident>,
+        labels: Vec<Ident>,
+    },
+    /// `-[e IS KNOWS | LIKES]->{1,3}`, matching an edge with any one of the
+    /// labels, or a path of such edges as long as the quantifier allows
+    Edge {
+        variable: Option<Ident>,
+        labels: Vec<Ident>,
+        direction: GraphEdgeDirection,
+        quantifier: Option<RepetitionQuantifier>,
+    },
+}
+
+impl fmt::Display for GraphPatternElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let filler =
+            |f: &mut fmt::Formatter, variable: &Option<Ident>, labels: &[Ident], separator| {
+                if let Some(variable) = variable {
+                    write!(f, "{variable}")?;
+                }
+                if !labels.is_empty() {
+                    if variable.is_some() {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "IS {}", display_separated(labels, separator))?;
+                }
+                Ok(())
+            };
+        match self {
+            GraphPatternElement::Vertex { variable, labels } => {
+                write!(f, "(")?;
+                filler(f, variable, labels, " & ")?;
+                write!(f, ")")
+            }
+            GraphPatternElement::Edge {
+                variable,
+                labels,
+                direction,
+                quantifier,
+            } => {
+                match direction {
+                    GraphEdgeDirection::Left => write!(f, "<-[")?,
+                    GraphEdgeDirection::Right | GraphEdgeDirection::Any => write!(f, "-[")?,
+                }
+                filler(f, variable, labels, " | ")?;
+                match direction {
+                    GraphEdgeDirection::Right => write!(f, "]->")?,
+                    GraphEdgeDirection::Left | GraphEdgeDirection::Any => write!(f, "]-")?,
+                }
+                if let Some(quantifier) = quantifier {
+                    write!(f, "{quantifier}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The direction of an edge pattern in the `MATCH` of a `GRAPH_TABLE`
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "visitor", derive(Visit, VisitMut))]
+pub enum GraphEdgeDirection {
+    /// `<-[e]-`, an edge pointing to the left
+    Left,
+    /// `-[e]->`, an edge pointing to the right
+    Right,
+    /// `-[e]-`, an edge in either direction
+    Any,
 }
 
 /// The table sample modifier options
@@ -2173,6 +2273,29 @@ impl fmt::Display for TableFactor {
 
                 Ok(())
             }
+            TableFactor::GraphTable {
+                graph,
+                paths,
+                selection,
+                columns,
+                alias,
+            } => {
+                write!(f, "GRAPH_TABLE ({graph} MATCH ")?;
+                for (i, path) in paths.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", display_separated(path, ""))?;
+                }
+                if let Some(selection) = selection {
+                    write!(f, " WHERE {selection}")?;
+                }
+                write!(f, " COLUMNS ({}))", display_comma_separated(columns))?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+                Ok(())
+            }
         }
     }
 }
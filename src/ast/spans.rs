@@ -1337,6 +1337,7 @@ impl Spanned for AssignmentTarget {
 /// - [Expr::Dictionary] # DuckDB specific
 /// - [Expr::Map] # DuckDB specific
 /// - [Expr::Lambda]
+/// - [Expr::Cypher]
 impl Spanned for Expr {
     fn span(&self) -> Span {
         match self {
@@ -1539,6 +1540,7 @@ impl Spanned for Expr {
             Expr::Prior(expr) => expr.span(),
             Expr::Lambda(_) => Span::empty(),
             Expr::MemberOf(member_of) => member_of.value.span().union(&member_of.array.span()),
+            Expr::Cypher(_) => Span::empty(),
         }
     }
 }
@@ -1942,6 +1944,21 @@ impl Spanned for TableFactor {
                     .chain(alias.as_ref().map(|a| a.span())),
             ),
             TableFactor::OpenJsonTable { .. } => Span::empty(),
+            TableFactor::GraphTable {
+                graph,
+                paths: _,
+                selection,
+                columns,
+                alias,
+            } => union_spans(
+                graph
+                    .0
+                    .iter()
+                    .map(|i| i.span())
+                    .chain(selection.as_ref().map(|e| e.span()))
+                    .chain(columns.iter().map(|c| c.span()))
+                    .chain(alias.as_ref().map(|a| a.span())),
+            ),
         }
     }
 }
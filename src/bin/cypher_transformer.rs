@@ -16,300 +16,659 @@
 // under the License.
 
 //! Cypher to SQL transformer CLI binary
+//!
+//! Exits with 0 on success, 1 when the query cannot be parsed or
+//! transformed, and 2 on invalid command line arguments.
+//!
+//! With the `json_example` feature, `--batch` transforms a stream of queries,
+//! writing one JSON object per query, `--params` inlines the parameter values
+//! of a JSON object, and `--ast`, `--summary`, `--validate` and
+//! `--capabilities` print the parsed statement, its access summary, its
+//! check and the supported constructs as JSON.
 
+use sqlparser::ast::CypherStatement;
 use sqlparser::dialect::CypherDialect;
-use sqlparser::parser::Parser;
-use sqlparser::tokenizer::Tokenizer;
-// use sqlparser::transformer::CypherToSqlTransformer;  // Uncomment when transformer is enabled
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::{Token, Tokenizer};
+use sqlparser::transformer::{
+    capabilities, check_cypher, parse_cypher_query, CypherToSqlTransformer, ParamStyle, ParamValue,
+    SqlFormat, SqlTarget, TransformConfig, TransformError, TransformOutput,
+};
+use std::collections::BTreeMap;
 use std::env;
+use std::fs;
 use std::io::{self, Read};
 use std::process;
 
+const USAGE: &str = "\
+Usage: cypher_transformer [OPTIONS] [QUERY]
+
+Transforms a Cypher query to SQL. The query is read from stdin when neither
+QUERY nor --file is given.
+
+Options:
+  --file <PATH>           Read the query from a file
+  --ast                   Print the parsed statement instead of SQL, as JSON
+                          with the json_example feature
+  --tokens                Print the tokens of the query with their locations
+  --validate              Only check the query, exiting with 1 if it is
+                          invalid, and print its kind, variables, parameters
+                          and warnings, as JSON with the json_example feature
+  --summary               Print the labels, relationship types and properties
+                          the query reads and writes, as JSON with the
+                          json_example feature
+  --emit-ddl              Print the CREATE TABLE and CREATE INDEX statements
+                          of the tables the SQL reads and writes
+  --capabilities          Print whether each Cypher construct is supported,
+                          partially supported or unsupported with the given
+                          options, without a query, as JSON with the
+                          json_example feature
+  --node-table <NAME>     Table holding the nodes (default: nodes)
+  --rel-table <NAME>      Table holding the relationships (default: relationships)
+  --label-tables          Read labelled nodes from a table per label
+  --target <TARGET>       SQL database to target: sqlite, postgres, mysql,
+                          duckdb or datafusion (default: sqlite)
+  --param-style <STYLE>   How $parameters are written: inline, question or
+                          numbered (default: inline)
+  --pretty                Print each SQL clause on its own line
+  --strict                Fail on Cypher constructs that the SQL would
+                          ignore, rather than warning about them
+  --params <JSON>         Write the values of a JSON object of parameters,
+                          e.g. '{\"name\": \"Ada\"}', into the SQL in place
+                          of $name (requires the json_example feature)
+  --batch                 Read one query per line from stdin and write a
+                          JSON object with its cypher, sql, warnings,
+                          parameters and error per line (requires the
+                          json_example feature)
+  --multiline             With --batch, read queries terminated by `;`, and
+                          apply directives such as `:param name => value`
+                          on lines of their own
+  --stats                 With --batch, add the token, pattern element and
+                          join counts of each query and the time each phase
+                          of its transformation took
+  --config <FILE>         Read options from a file of `key = value` lines,
+                          with keys node_table, rel_table, label_tables,
+                          target, param_style, pretty and strict; command
+                          line options take precedence
+  -h, --help              Print this help
+";
+
+/// An invalid command line, reported with exit code 2
+struct UsageError(String);
+
+/// What the command line asks for
+enum Command {
+    Help,
+    /// Print the support of each Cypher construct under the configuration
+    Capabilities(TransformConfig),
+    Run {
+        config: TransformConfig,
+        mode: Mode,
+        input: Input,
+        /// The values inlined in place of the parameters of the query
+        params: Option<BTreeMap<String, ParamValue>>,
+    },
+    Batch {
+        config: TransformConfig,
+        multiline: bool,
+        stats: bool,
+    },
+}
+
+/// What to do with a single query
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Transform,
+    Ast,
+    Tokens,
+    Validate,
+    Summary,
+    EmitDdl,
+}
+
+/// Where a single query is read from
+enum Input {
+    Argument(String),
+    File(String),
+    Stdin,
+}
+
 fn main() {
-    let result = run();
-    
-    match result {
-        Ok(()) => process::exit(0),
-        Err(err) => {
-            eprintln!("Error: {}", err);
-            process::exit(1);
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (config, mode, input, params) = match parse_args(&args) {
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            process::exit(0);
+        }
+        Ok(Command::Capabilities(config)) => {
+            if let Err(err) = print_structure(&capabilities(&config)) {
+                eprintln!("Error: {err}");
+                process::exit(1);
+            }
+            process::exit(0);
         }
+        Ok(Command::Run {
+            config,
+            mode,
+            input,
+            params,
+        }) => (config, mode, input, params),
+        Ok(Command::Batch {
+            config,
+            multiline,
+            stats,
+        }) => match batch::run_batch(config, multiline, stats) {
+            Ok(true) => process::exit(0),
+            Ok(false) => process::exit(1),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                process::exit(1);
+            }
+        },
+        Err(UsageError(message)) => {
+            eprintln!("Error: {message}");
+            eprintln!("Try 'cypher_transformer --help' for more information.");
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = run(config, mode, input, params) {
+        eprintln!("Error: {err}");
+        process::exit(1);
     }
 }
 
-fn run() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    
-    // Get Cypher query from command line argument or stdin
-    let cypher_query = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        // Read from stdin
-        let mut input = String::new();
-        io::stdin().read_to_string(&mut input)?;
-        input.trim().to_string()
+fn run(
+    config: TransformConfig,
+    mode: Mode,
+    input: Input,
+    params: Option<BTreeMap<String, ParamValue>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cypher_query = match input {
+        Input::Argument(query) => query,
+        Input::File(path) => fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read {path}: {e}"))?
+            .trim()
+            .to_string(),
+        Input::Stdin => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input.trim().to_string()
+        }
     };
 
     if cypher_query.is_empty() {
-        return Err("No Cypher query provided".into());
+        return Err(NO_QUERY.into());
     }
 
-    // Parse the Cypher query
-    let dialect = CypherDialect;
-    let tokens = Tokenizer::new(&dialect, &cypher_query).tokenize()?;
-    let mut parser = Parser::new(&dialect).with_tokens(tokens);
-    
-    // Parse as Cypher statement
-    let cypher_stmt = match parser.parse_cypher_statement() {
-        Ok(stmt) => stmt,
-        Err(e) => {
-            return Err(format!("Failed to parse Cypher query: {}", e).into());
+    match mode {
+        Mode::Transform => {
+            let transformer = CypherToSqlTransformer::with_config(config);
+            let output = match &params {
+                Some(params) => transformer.to_sql_output_with_params(
+                    &parse(&cypher_query, transformer.config.strict)?,
+                    params,
+                )?,
+                None => transform(&transformer, &cypher_query)?,
+            };
+            for warning in &output.warnings {
+                eprintln!("Warning: {warning}");
+            }
+            println!("{}", output.sql);
         }
-    };
+        Mode::Ast => print_structure(&parse(&cypher_query, config.strict)?)?,
+        Mode::Summary => print_structure(&parse(&cypher_query, config.strict)?.access_summary())?,
+        Mode::EmitDdl => {
+            let transformer = CypherToSqlTransformer::with_config(config);
+            let statement = parse(&cypher_query, transformer.config.strict)?;
+            for statement in transformer.required_schema(&statement).ddl {
+                println!("{statement};");
+            }
+        }
+        Mode::Tokens => {
+            let tokens = Tokenizer::new(&CypherDialect, &cypher_query)
+                .tokenize_with_location()
+                .map_err(|e| format!("Failed to tokenize Cypher query: {e}"))?;
+            for token in tokens {
+                if matches!(token.token, Token::Whitespace(_)) {
+                    continue;
+                }
+                let (start, end) = (token.span.start, token.span.end);
+                println!(
+                    "{}:{}-{}:{}\t{:?}",
+                    start.line, start.column, end.line, end.column, token.token
+                );
+            }
+        }
+        Mode::Validate => print_structure(&check_cypher(&cypher_query).map_err(parse_error)?)?,
+    }
+    Ok(())
+}
 
-    // For now, implement basic transformation logic directly here
-    // TODO: Uncomment below when transformer module is enabled
-    /*
-    let transformer = CypherToSqlTransformer::new();
-    let sql_statements = transformer.transform(&cypher_stmt)?;
-    
-    for stmt in sql_statements {
-        println!("{}", stmt);
+/// The statement of `cypher_query`, which under `strict` must be the whole
+/// query rather than stop before a clause the parser does not know
+fn parse(cypher_query: &str, strict: bool) -> Result<CypherStatement, Box<dyn std::error::Error>> {
+    let parser = Parser::new(&CypherDialect)
+        .try_with_sql(cypher_query)
+        .map_err(parse_error)?;
+    match parse_cypher_query(parser, cypher_query, strict) {
+        Ok(statement) => Ok(statement),
+        Err(TransformError::Parse(e)) => Err(parse_error(e).into()),
+        Err(TransformError::EmptyInput) => Err(NO_QUERY.into()),
+        Err(e) => Err(e.into()),
     }
-    */
+}
 
-    // Basic transformation for immediate testing
-    let sql_query = transform_cypher_to_sql_basic(&cypher_stmt)?;
-    println!("{}", sql_query);
+/// The error of an input without a query, or with nothing but comments
+const NO_QUERY: &str = "No Cypher query provided";
 
+fn parse_error(e: ParserError) -> String {
+    match TransformError::from(e) {
+        TransformError::EmptyInput => NO_QUERY.to_string(),
+        TransformError::Parse(ParserError::TokenizerError(e)) => {
+            format!("Failed to tokenize Cypher query: {e}")
+        }
+        e => format!("Failed to parse Cypher query: {e}"),
+    }
+}
+
+fn transform(
+    transformer: &CypherToSqlTransformer,
+    cypher_query: &str,
+) -> Result<TransformOutput, Box<dyn std::error::Error>> {
+    let statement = parse(cypher_query, transformer.config.strict)?;
+    Ok(transformer.to_sql_output(&statement)?)
+}
+
+#[cfg(feature = "json_example")]
+fn print_structure<T: serde::Serialize>(value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
     Ok(())
 }
 
-/// Basic Cypher to SQL transformation for immediate testing
-/// Uses generic schema: nodes(id, label, properties), relationships(from_id, to_id, type, properties)
-fn transform_cypher_to_sql_basic(cypher_stmt: &sqlparser::ast::cypher::CypherStatement) -> Result<String, Box<dyn std::error::Error>> {
-    use sqlparser::ast::cypher::*;
-    
-    match cypher_stmt {
-        CypherStatement::Match { optional: _, patterns, r#where, r#return } => {
-            let mut sql = String::new();
-            let mut node_counter = 0;
-            let mut rel_counter = 0;
-            
-            // Start with SELECT
-            sql.push_str("SELECT ");
-            
-            // Handle return clause
-            if let Some(ret_clause) = r#return {
-                if ret_clause.distinct {
-                    sql.push_str("DISTINCT ");
-                }
-                
-                // Add projection items
-                let items: Vec<String> = ret_clause.items.iter().map(|item| {
-                    match item {
-                        sqlparser::ast::SelectItem::UnnamedExpr(expr) => {
-                            // Transform property access like n.name to json_extract
-                            let expr_str = format!("{}", expr);
-                            if expr_str.contains('.') {
-                                let parts: Vec<&str> = expr_str.split('.').collect();
-                                if parts.len() == 2 {
-                                    let var_name = parts[0];
-                                    let property_name = parts[1];
-                                    return format!("json_extract({}.properties, '$.{}') as {}", var_name, property_name, property_name);
-                                }
-                            }
-                            expr_str
-                        },
-                        sqlparser::ast::SelectItem::ExprWithAlias { expr, alias } => {
-                            let expr_str = format!("{}", expr);
-                            if expr_str.contains('.') {
-                                let parts: Vec<&str> = expr_str.split('.').collect();
-                                if parts.len() == 2 {
-                                    let var_name = parts[0];
-                                    let property_name = parts[1];
-                                    return format!("json_extract({}.properties, '$.{}') AS {}", var_name, property_name, alias);
-                                }
-                            }
-                            format!("{} AS {}", expr, alias)
-                        },
-                        sqlparser::ast::SelectItem::Wildcard(_) => "*".to_string(),
-                        _ => "*".to_string(),
+#[cfg(not(feature = "json_example"))]
+fn print_structure<T: std::fmt::Debug>(value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{value:#?}");
+    Ok(())
+}
+
+/// The values of the JSON object of `--params`, by parameter name
+#[cfg(feature = "json_example")]
+fn parse_params(json: &str) -> Result<BTreeMap<String, ParamValue>, UsageError> {
+    match serde_json::from_str(json) {
+        Ok(serde_json::Value::Object(fields)) => Ok(fields
+            .into_iter()
+            .map(|(name, value)| (name, ParamValue::from(value)))
+            .collect()),
+        Ok(_) => Err(UsageError(
+            "--params expects a JSON object of parameter values".to_string(),
+        )),
+        Err(e) => Err(UsageError(format!("invalid --params JSON: {e}"))),
+    }
+}
+
+#[cfg(not(feature = "json_example"))]
+fn parse_params(_json: &str) -> Result<BTreeMap<String, ParamValue>, UsageError> {
+    Err(UsageError(
+        "--params requires building with the json_example feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "json_example")]
+mod batch {
+    use super::{parse_error, transform};
+    use sqlparser::parser::{CypherScriptItem, CypherStatementIterator};
+    use sqlparser::transformer::{
+        CypherToSqlTransformer, ParamBinding, ScriptTransformer, TransformConfig, TransformError,
+        TransformOutput, TransformStats,
+    };
+    use std::error::Error;
+    use std::io::{self, BufRead, Write};
+
+    /// One line of `--batch` output
+    #[derive(serde::Serialize)]
+    struct BatchRecord<'a> {
+        cypher: &'a str,
+        sql: Option<String>,
+        warnings: Vec<String>,
+        parameters: Vec<ParamBinding>,
+        error: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stats: Option<TransformStats>,
+    }
+
+    /// The output of a query, with its statistics when they were asked for
+    type BatchResult = Result<(TransformOutput, Option<TransformStats>), Box<dyn Error>>;
+
+    /// Transform every query read from stdin, writing one JSON object per query
+    /// as soon as it is read. Returns whether all queries were transformed.
+    pub fn run_batch(config: TransformConfig, multiline: bool, stats: bool) -> io::Result<bool> {
+        let mut script = ScriptTransformer::new(config.clone());
+        let transformer = CypherToSqlTransformer::with_config(config);
+        let mut stdout = io::stdout().lock();
+        let (mut succeeded, mut failed) = (0, 0);
+        let mut write_record = |query: &str, result: BatchResult| {
+            let record = match result {
+                Ok((output, stats)) => {
+                    succeeded += 1;
+                    BatchRecord {
+                        cypher: query,
+                        // A directive may have no SQL
+                        sql: Some(output.sql).filter(|sql| !sql.is_empty()),
+                        warnings: output.warnings.iter().map(ToString::to_string).collect(),
+                        parameters: output.parameters,
+                        error: None,
+                        stats,
                     }
-                }).collect();
-                
-                if items.is_empty() {
-                    sql.push_str("*");
-                } else {
-                    sql.push_str(&items.join(", "));
                 }
-            } else {
-                sql.push_str("*");
-            }
-            
-            // Add FROM clause
-            sql.push_str(" FROM ");
-            
-            // Process patterns to determine tables and joins
-            let mut from_clauses = Vec::new();
-            let mut join_clauses = Vec::new();
-            let mut where_conditions = Vec::new();
-            
-            for pattern in patterns {
-                let mut prev_node_alias: Option<String> = None;
-                
-                for element in &pattern.elements {
-                    match element {
-                        PatternElement::Node { variable, labels, properties: _ } => {
-                            node_counter += 1;
-                            let alias = if let Some(ref var) = variable {
-                                var.value.clone()
-                            } else {
-                                format!("n{}", node_counter)
-                            };
-                            
-                            if from_clauses.is_empty() {
-                                from_clauses.push(format!("nodes {}", alias));
-                            } else {
-                                join_clauses.push(format!("JOIN nodes {} ON TRUE", alias));
-                            }
-                            
-                            // Add label condition
-                            if !labels.is_empty() {
-                                where_conditions.push(format!("{}.label = '{}'", alias, labels[0].value));
-                            }
-                            
-                            prev_node_alias = Some(alias);
-                        }
-                        PatternElement::Relationship { variable, types, properties: _, direction: _, length: _ } => {
-                            rel_counter += 1;
-                            let rel_alias = if let Some(ref var) = variable {
-                                var.value.clone()
-                            } else {
-                                format!("r{}", rel_counter)
-                            };
-                            
-                            // For relationships, we need to join the relationship table
-                            if let Some(ref from_node) = prev_node_alias {
-                                join_clauses.push(format!("JOIN relationships {} ON {}.id = {}.from_id", rel_alias, from_node, rel_alias));
-                                
-                                // Add relationship type condition - types is already a Vec<Ident>
-                                if !types.is_empty() {
-                                    where_conditions.push(format!("{}.type = '{}'", rel_alias, types[0].value));
-                                }
-                            }
-                        }
+                Err(err) => {
+                    failed += 1;
+                    BatchRecord {
+                        cypher: query,
+                        sql: None,
+                        warnings: vec![],
+                        parameters: vec![],
+                        error: Some(err.to_string()),
+                        stats: None,
                     }
                 }
+            };
+            let json = serde_json::to_string(&record).map_err(io::Error::other)?;
+            writeln!(stdout, "{json}")?;
+            stdout.flush()
+        };
+
+        let mut read_error = None;
+        let lines = io::stdin()
+            .lock()
+            .lines()
+            .map_while(|line| line.map_err(|e| read_error = Some(e)).ok());
+        if multiline {
+            // The statements and the directives of the script, whose `:param`
+            // values are inlined into the statements after them
+            let mut statements = CypherStatementIterator::new(lines.map(|line| line + "\n"));
+            while let Some(item) = statements.next_item() {
+                let result = match item {
+                    // The statistics cover the phases from the query text on
+                    Ok(CypherScriptItem::Statement(_))
+                        if stats && script.parameters().is_empty() =>
+                    {
+                        transform_with_stats(&transformer, statements.statement_text())
+                    }
+                    item => item
+                        .map_err(|e| parse_error(e).into())
+                        .and_then(|item| Ok((script.transform(&item)?, None))),
+                };
+                write_record(statements.statement_text(), result)?;
             }
-            
-            // Combine FROM and JOINs
-            sql.push_str(&from_clauses.join(", "));
-            if !join_clauses.is_empty() {
-                sql.push(' ');
-                sql.push_str(&join_clauses.join(" "));
+        } else {
+            for line in lines {
+                let query = line.trim();
+                if query.is_empty() {
+                    continue;
+                }
+                let result = if stats {
+                    transform_with_stats(&transformer, query)
+                } else {
+                    transform(&transformer, query).map(|output| (output, None))
+                };
+                write_record(query, result)?;
             }
-            
-            // Add WHERE clause
-            if let Some(where_expr) = r#where {
-                where_conditions.push(format!("{}", where_expr));
+        }
+        if let Some(e) = read_error {
+            return Err(e);
+        }
+
+        eprintln!(
+            "{} queries: {succeeded} transformed, {failed} failed",
+            succeeded + failed
+        );
+        Ok(failed == 0)
+    }
+
+    fn transform_with_stats(transformer: &CypherToSqlTransformer, query: &str) -> BatchResult {
+        match transformer.to_sql_output_with_stats(query) {
+            Ok((output, stats)) => Ok((output, Some(stats))),
+            Err(TransformError::Parse(e)) => Err(parse_error(e).into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(not(feature = "json_example"))]
+mod batch {
+    use sqlparser::transformer::TransformConfig;
+    use std::io;
+
+    pub fn run_batch(_config: TransformConfig, _multiline: bool, _stats: bool) -> io::Result<bool> {
+        unreachable!("--batch is rejected without the json_example feature")
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Command, UsageError> {
+    // The CLI reads the generic schema unless asked for label tables, and
+    // warns about what the SQL approximates unless asked to be strict
+    let mut config = TransformConfig {
+        use_label_tables: false,
+        strict: false,
+        ..TransformConfig::default()
+    };
+
+    // Options from the config file apply first, whatever their position
+    if let Some(i) = args.iter().position(|arg| arg == "--config") {
+        let path = args
+            .get(i + 1)
+            .ok_or_else(|| UsageError("--config requires a value".to_string()))?;
+        let contents = fs::read_to_string(path)
+            .map_err(|e| UsageError(format!("cannot read config file {path}: {e}")))?;
+        apply_config_file(&mut config, &contents)
+            .map_err(|UsageError(message)| UsageError(format!("{path}: {message}")))?;
+    }
+
+    let mut query = None;
+    let mut file = None;
+    let mut params = None;
+    let mut modes = vec![];
+    let (mut batch, mut multiline, mut stats) = (false, false, false);
+    let mut capabilities = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .ok_or_else(|| UsageError(format!("{flag} requires a value")))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--config" => {
+                value(arg)?;
             }
-            
-            if !where_conditions.is_empty() {
-                sql.push_str(" WHERE ");
-                sql.push_str(&where_conditions.join(" AND "));
+            "--node-table" => config.node_table = value(arg)?.clone(),
+            "--rel-table" => config.relationship_table = value(arg)?.clone(),
+            "--label-tables" => config.use_label_tables = true,
+            "--target" => config.target = parse_target(value(arg)?)?,
+            "--param-style" => config.param_style = parse_param_style(value(arg)?)?,
+            "--pretty" => config.format = SqlFormat::Pretty { indent: 2 },
+            "--strict" => config.strict = true,
+            "--params" => params = Some(parse_params(value(arg)?)?),
+            "--batch" => batch = true,
+            "--multiline" => multiline = true,
+            "--stats" => stats = true,
+            "--capabilities" => capabilities = Some(arg),
+            "--file" => file = Some(value(arg)?.clone()),
+            "--ast" => modes.push((arg, Mode::Ast)),
+            "--tokens" => modes.push((arg, Mode::Tokens)),
+            "--validate" => modes.push((arg, Mode::Validate)),
+            "--summary" => modes.push((arg, Mode::Summary)),
+            "--emit-ddl" => modes.push((arg, Mode::EmitDdl)),
+            flag if flag.starts_with("--") => {
+                return Err(UsageError(format!("unknown option {flag}")))
             }
-            
-            // Add ORDER BY, LIMIT etc. from return clause
-            if let Some(ret_clause) = r#return {
-                if !ret_clause.order_by.is_empty() {
-                    sql.push_str(" ORDER BY ");
-                    let order_items: Vec<String> = ret_clause.order_by.iter().map(|item| {
-                        // Transform property access in ORDER BY as well
-                        let expr_str = format!("{}", item.expr);
-                        if expr_str.contains('.') {
-                            let parts: Vec<&str> = expr_str.split('.').collect();
-                            if parts.len() == 2 {
-                                let var_name = parts[0];
-                                let property_name = parts[1];
-                                return format!("json_extract({}.properties, '$.{}') ASC", var_name, property_name);
-                            }
-                        }
-                        format!("{} ASC", item.expr)
-                    }).collect();
-                    sql.push_str(&order_items.join(", "));
-                }
-                
-                if let Some(limit) = &ret_clause.limit {
-                    sql.push_str(&format!(" LIMIT {}", limit));
-                }
-                
-                if let Some(skip) = &ret_clause.skip {
-                    sql.push_str(&format!(" OFFSET {}", skip));
+            positional => {
+                if query.is_some() {
+                    return Err(UsageError(
+                        "expected a single query; quote the query to pass it as one argument"
+                            .to_string(),
+                    ));
                 }
+                query = Some(positional.to_string());
             }
-            
-            Ok(sql)
         }
-        
-        CypherStatement::Create { patterns } => {
-            let mut sql = String::new();
-            
-            for pattern in patterns {
-                for element in &pattern.elements {
-                    match element {
-                        PatternElement::Node { variable: _, labels, properties } => {
-                            sql.push_str("INSERT INTO nodes (label, properties) VALUES (");
-                            
-                            // Add label
-                            if !labels.is_empty() {
-                                sql.push_str(&format!("'{}'", labels[0].value));
-                            } else {
-                                sql.push_str("NULL");
-                            }
-                            
-                            sql.push_str(", ");
-                            
-                            // Add properties as JSON
-                            if let Some(ref _props) = properties {
-                                sql.push_str("'{}')");  // Simplified - would need proper JSON construction
-                            } else {
-                                sql.push_str("'{}')");
-                            }
-                            
-                            sql.push_str(";\n");
-                        }
-                        PatternElement::Relationship { variable: _, types, properties, direction: _, length: _ } => {
-                            sql.push_str("INSERT INTO relationships (from_id, to_id, type, properties) VALUES (");
-                            sql.push_str("?, ?, ");  // Placeholder for node IDs
-                            
-                            // Add relationship type - types is already a Vec<Ident>
-                            if !types.is_empty() {
-                                sql.push_str(&format!("'{}'", types[0].value));
-                            } else {
-                                sql.push_str("NULL");
-                            }
-                            
-                            sql.push_str(", ");
-                            
-                            // Add properties as JSON
-                            if let Some(ref _props) = properties {
-                                sql.push_str("'{}')");  // Simplified
-                            } else {
-                                sql.push_str("'{}')");
-                            }
-                            
-                            sql.push_str(";\n");
-                        }
-                    }
+    }
+
+    if config.target == SqlTarget::MySql && config.param_style == ParamStyle::Numbered {
+        return Err(UsageError(
+            "numbered parameters are not supported by mysql, use --param-style question"
+                .to_string(),
+        ));
+    }
+
+    if let [(first, _), (second, _), ..] = modes[..] {
+        return Err(UsageError(format!("{first} cannot be used with {second}")));
+    }
+    let mode = modes.first().map_or(Mode::Transform, |(_, mode)| *mode);
+    if params.is_some() {
+        if let Some((flag, _)) = modes.first() {
+            return Err(UsageError(format!("{flag} cannot be used with --params")));
+        }
+        if batch {
+            return Err(UsageError(
+                "--params cannot be used with --batch".to_string(),
+            ));
+        }
+    }
+
+    if let Some(flag) = capabilities {
+        let conflict = match (modes.first(), batch, params.is_some()) {
+            (Some((mode, _)), _, _) => Some(mode.as_str()),
+            (None, true, _) => Some("--batch"),
+            (None, false, true) => Some("--params"),
+            (None, false, false) => None,
+        };
+        if let Some(conflict) = conflict {
+            return Err(UsageError(format!("{flag} cannot be used with {conflict}")));
+        }
+        if query.is_some() || file.is_some() {
+            return Err(UsageError(format!("{flag} takes no query")));
+        }
+        return Ok(Command::Capabilities(config));
+    }
+
+    if multiline && !batch {
+        return Err(UsageError("--multiline requires --batch".to_string()));
+    }
+    if stats && !batch {
+        return Err(UsageError("--stats requires --batch".to_string()));
+    }
+    if batch {
+        if let Some((flag, _)) = modes.first() {
+            return Err(UsageError(format!("{flag} cannot be used with --batch")));
+        }
+        if file.is_some() {
+            return Err(UsageError(
+                "--batch reads queries from stdin and cannot be used with --file".to_string(),
+            ));
+        }
+        if cfg!(not(feature = "json_example")) {
+            return Err(UsageError(
+                "--batch requires building with the json_example feature".to_string(),
+            ));
+        }
+        if query.is_some() {
+            return Err(UsageError(
+                "--batch reads queries from stdin and takes no QUERY argument".to_string(),
+            ));
+        }
+        return Ok(Command::Batch {
+            config,
+            multiline,
+            stats,
+        });
+    }
+
+    let input = match (query, file) {
+        (Some(_), Some(_)) => {
+            return Err(UsageError(
+                "expected either a QUERY argument or --file, not both".to_string(),
+            ))
+        }
+        (Some(query), None) => Input::Argument(query),
+        (None, Some(path)) => Input::File(path),
+        (None, None) => Input::Stdin,
+    };
+
+    Ok(Command::Run {
+        config,
+        mode,
+        input,
+        params,
+    })
+}
+
+/// Apply the `key = value` lines of a config file, skipping blank lines and
+/// `#` comments
+fn apply_config_file(config: &mut TransformConfig, contents: &str) -> Result<(), UsageError> {
+    for (n, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(UsageError(format!(
+                "line {}: expected `key = value`",
+                n + 1
+            )));
+        };
+        let value = value.trim();
+        match key.trim() {
+            "node_table" => config.node_table = value.to_string(),
+            "rel_table" => config.relationship_table = value.to_string(),
+            "label_tables" => config.use_label_tables = parse_bool(value)?,
+            "target" => config.target = parse_target(value)?,
+            "param_style" => config.param_style = parse_param_style(value)?,
+            "pretty" => {
+                config.format = if parse_bool(value)? {
+                    SqlFormat::Pretty { indent: 2 }
+                } else {
+                    SqlFormat::Compact
                 }
             }
-            
-            Ok(sql.trim_end_matches(";\n").to_string())
-        }
-        
-        _ => {
-            Ok("-- Transformation not yet implemented for this Cypher statement type".to_string())
+            "strict" => config.strict = parse_bool(value)?,
+            key => return Err(UsageError(format!("line {}: unknown key {key}", n + 1))),
         }
     }
-}
\ No newline at end of file
+    Ok(())
+}
+
+fn parse_target(value: &str) -> Result<SqlTarget, UsageError> {
+    match value {
+        "sqlite" => Ok(SqlTarget::Sqlite),
+        "postgres" => Ok(SqlTarget::Postgres),
+        "mysql" => Ok(SqlTarget::MySql),
+        "duckdb" => Ok(SqlTarget::DuckDb),
+        "datafusion" => Ok(SqlTarget::DataFusion),
+        _ => Err(UsageError(format!(
+            "invalid target {value}, expected one of sqlite, postgres, mysql, duckdb, datafusion"
+        ))),
+    }
+}
+
+fn parse_param_style(value: &str) -> Result<ParamStyle, UsageError> {
+    match value {
+        "inline" => Ok(ParamStyle::Inline),
+        "question" => Ok(ParamStyle::Question),
+        "numbered" => Ok(ParamStyle::Numbered),
+        _ => Err(UsageError(format!(
+            "invalid parameter style {value}, expected one of inline, question, numbered"
+        ))),
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, UsageError> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(UsageError(format!(
+            "invalid boolean {value}, expected true or false"
+        ))),
+    }
+}
@@ -15,7 +15,14 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::dialect::Dialect;
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+
+use crate::ast::{Expr, UnaryOperator};
+use crate::dialect::{Dialect, Precedence};
+use crate::keywords::{Keyword, CYPHER_RESERVED};
+use crate::parser::{Parser, ParserError};
+use crate::tokenizer::Token;
 
 /// A [`Dialect`] for Cypher query language used in Neo4j
 #[derive(Debug)]
@@ -23,17 +30,33 @@ pub struct CypherDialect;
 
 impl Dialect for CypherDialect {
     fn is_identifier_start(&self, ch: char) -> bool {
-        ch.is_alphabetic() || ch == '_' || ch == '$'
+        ch.is_alphabetic() || ch == '_'
     }
 
     fn is_identifier_part(&self, ch: char) -> bool {
-        ch.is_alphanumeric() || ch == '_' || ch == '$'
+        ch.is_alphanumeric() || ch == '_'
+    }
+
+    /// Cypher parameters, e.g. `$name`
+    fn supports_dollar_placeholder(&self) -> bool {
+        true
     }
 
     fn is_delimited_identifier_start(&self, ch: char) -> bool {
         ch == '`' // Cypher uses backticks for delimited identifiers
     }
 
+    /// Strings in either quote style accept escapes such as `\'` and `\n`
+    fn supports_string_literal_backslash_escape(&self) -> bool {
+        true
+    }
+
+    /// `--` followed by anything but a space is two dashes of a relationship
+    /// without details, e.g. `(a)-->(b)`, rather than a comment
+    fn requires_single_line_comment_whitespace(&self) -> bool {
+        true
+    }
+
     fn supports_filter_during_aggregation(&self) -> bool {
         false
     }
@@ -81,4 +104,130 @@ impl Dialect for CypherDialect {
     fn supports_select_wildcard_except(&self) -> bool {
         false
     }
-}
\ No newline at end of file
+
+    fn is_reserved_for_identifier(&self, kw: Keyword) -> bool {
+        CYPHER_RESERVED.contains(&kw)
+    }
+
+    fn parse_prefix(&self, parser: &mut Parser) -> Option<Result<Expr, ParserError>> {
+        // Keywords Cypher does not reserve are names rather than the start of
+        // SQL syntax, e.g. `skip.limit` or `interval`, unless they call a
+        // function; any word is a property key after a `.`
+        if let Token::Word(word) = &parser.peek_token_ref().token {
+            let property_key = parser.get_current_token().token == Token::Period;
+            if word.keyword != Keyword::NoKeyword
+                && (property_key
+                    || (!self.is_reserved_for_identifier(word.keyword)
+                        && !matches!(
+                            parser.peek_nth_token_ref(1).token,
+                            Token::LParen | Token::LBrace
+                        )))
+            {
+                return Some(parser.parse_identifier().map(Expr::Identifier));
+            }
+        }
+        // `exists(n.email)` is the legacy function rather than an EXISTS
+        // subquery, negated like any other call
+        if parser.peek_keyword(Keyword::EXISTS)
+            && parser.peek_nth_token_ref(1).token == Token::LParen
+        {
+            return Some(parser.parse_cypher_exists());
+        }
+        if parser.peek_keyword(Keyword::NOT)
+            && matches!(
+                &parser.peek_nth_token_ref(1).token,
+                Token::Word(word) if word.keyword == Keyword::EXISTS
+            )
+            && parser.peek_nth_token_ref(2).token == Token::LParen
+        {
+            parser.next_token();
+            return Some(
+                parser
+                    .parse_subexpr(self.prec_value(Precedence::UnaryNot))
+                    .map(|expr| Expr::UnaryOp {
+                        op: UnaryOperator::Not,
+                        expr: Box::new(expr),
+                    }),
+            );
+        }
+        // A bracket followed by a pattern starts a pattern comprehension,
+        // anything else a list
+        if parser.peek_token_ref().token == Token::LBracket
+            && parser.peek_nth_token_ref(1).token == Token::LParen
+        {
+            return parser
+                .maybe_parse(|parser| parser.parse_cypher_pattern_comprehension())
+                .transpose();
+        }
+        None
+    }
+
+    fn get_next_precedence(&self, parser: &Parser) -> Option<Result<u8, ParserError>> {
+        // Label predicates such as `n:Person` bind tighter than comparisons
+        if parser.peek_token_ref().token == Token::Colon {
+            return Some(Ok(self.prec_value(Precedence::DoubleColon)));
+        }
+        // Cypher has no bitwise OR: `|` separates the projection of a
+        // comprehension, so it ends an expression
+        if parser.peek_token_ref().token == Token::Pipe {
+            return Some(Ok(self.prec_unknown()));
+        }
+        // XOR is the boolean operator between OR and AND, rather than the
+        // bitwise one binding tighter than comparisons
+        if parser.peek_keyword(Keyword::XOR) {
+            let (or, and) = (self.prec_value(Precedence::Or), self.prec_value(Precedence::And));
+            return Some(Ok((or + and) / 2));
+        }
+        // String matching binds like LIKE, which it is translated to
+        if starts_string_predicate(parser) {
+            return Some(Ok(self.prec_value(Precedence::Like)));
+        }
+        // A MATCH after the items of a WITH starts the next part of the
+        // query, rather than being SQLite's MATCH operator
+        if parser.peek_keyword(Keyword::MATCH) {
+            return Some(Ok(self.prec_unknown()));
+        }
+        None
+    }
+
+    fn parse_infix(
+        &self,
+        parser: &mut Parser,
+        expr: &Expr,
+        _precedence: u8,
+    ) -> Option<Result<Expr, ParserError>> {
+        if parser.peek_token_ref().token == Token::Colon {
+            return Some(parser.parse_cypher_label_predicate(expr.clone()));
+        }
+        // Type predicates, e.g. `n.age IS :: INTEGER` or `IS NOT :: STRING`
+        if parser.peek_keyword(Keyword::IS) {
+            let negated = matches!(
+                &parser.peek_nth_token_ref(1).token,
+                Token::Word(word) if word.keyword == Keyword::NOT
+            );
+            let type_start = if negated { 2 } else { 1 };
+            if parser.peek_nth_token_ref(type_start).token == Token::DoubleColon {
+                return Some(parser.parse_cypher_type_predicate(expr.clone()));
+            }
+        }
+        if starts_string_predicate(parser) {
+            return Some(parser.parse_cypher_string_predicate(expr.clone()));
+        }
+        // Lists are written in brackets, e.g. `n.age IN [1, 2]`, or are
+        // expressions such as `labels(n)`, but not parenthesized
+        if parser.peek_keyword(Keyword::IN) && parser.peek_nth_token_ref(1).token != Token::LParen {
+            return Some(parser.parse_cypher_in_list(expr.clone()));
+        }
+        None
+    }
+}
+
+/// Whether the next tokens are `STARTS WITH`, `ENDS WITH` or `CONTAINS`
+fn starts_string_predicate(parser: &Parser) -> bool {
+    let is_keyword = |n: usize, keyword: Keyword| {
+        matches!(&parser.peek_nth_token_ref(n).token, Token::Word(word) if word.keyword == keyword)
+    };
+    is_keyword(0, Keyword::CONTAINS)
+        || ((is_keyword(0, Keyword::STARTS) || is_keyword(0, Keyword::ENDS))
+            && is_keyword(1, Keyword::WITH))
+}
@@ -109,6 +109,7 @@ define_keywords!(
     ARRAY_MAX_CARDINALITY,
     AS,
     ASC,
+    ASCENDING,
     ASENSITIVE,
     ASOF,
     ASSERT,
@@ -225,6 +226,7 @@ define_keywords!(
     CONNECTOR,
     CONNECT_BY_ROOT,
     CONSTRAINT,
+    CONSTRAINTS,
     CONTACT,
     CONTAINS,
     CONTINUE,
@@ -299,6 +301,7 @@ define_keywords!(
     DENY,
     DEREF,
     DESC,
+    DESCENDING,
     DESCRIBE,
     DETACH,
     DETAIL,
@@ -337,6 +340,7 @@ define_keywords!(
     END,
     END_EXEC = "END-EXEC",
     ENDPOINT,
+    ENDS,
     END_FRAME,
     END_PARTITION,
     ENFORCED,
@@ -473,6 +477,7 @@ define_keywords!(
     INCREMENT,
     INCREMENTAL,
     INDEX,
+    INDEXES,
     INDICATOR,
     INHERIT,
     INHERITS,
@@ -758,6 +763,7 @@ define_keywords!(
     PRIOR,
     PRIVILEGES,
     PROCEDURE,
+    PROCEDURES,
     PROFILE,
     PROGRAM,
     PROJECTION,
@@ -990,6 +996,7 @@ define_keywords!(
     TRACE,
     TRAILING,
     TRANSACTION,
+    TRANSACTIONS,
     TRANSIENT,
     TRANSLATE,
     TRANSLATE_REGEX,
@@ -1036,6 +1043,7 @@ define_keywords!(
     UNSET,
     UNSIGNED,
     UNTIL,
+    UNWIND,
     UPDATE,
     UPPER,
     URL,
@@ -1094,6 +1102,7 @@ define_keywords!(
     XOR,
     YEAR,
     YEARS,
+    YIELD,
     ZONE,
     ZORDER,
     ZSTD
@@ -1219,3 +1228,29 @@ pub const RESERVED_FOR_IDENTIFIER: &[Keyword] = &[
     Keyword::STRUCT,
     Keyword::TRIM,
 ];
+
+/// Keywords that Cypher reserves in expressions, and so cannot name a
+/// variable unless quoted with backticks. Other keywords, including clause
+/// keywords such as `SKIP` and `LIMIT`, are keywords only in clause position
+/// and may name variables, aliases and properties.
+pub const CYPHER_RESERVED: &[Keyword] = &[
+    Keyword::AND,
+    Keyword::CASE,
+    Keyword::CONTAINS,
+    Keyword::DISTINCT,
+    Keyword::ELSE,
+    Keyword::END,
+    Keyword::ENDS,
+    Keyword::EXISTS,
+    Keyword::FALSE,
+    Keyword::IN,
+    Keyword::IS,
+    Keyword::NOT,
+    Keyword::NULL,
+    Keyword::OR,
+    Keyword::STARTS,
+    Keyword::THEN,
+    Keyword::TRUE,
+    Keyword::WHEN,
+    Keyword::XOR,
+];
@@ -17,15 +17,70 @@
 
 //! Cypher language parser implementation
 
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, format, string::ToString, vec, vec::Vec};
+
 use crate::ast::cypher::*;
 use crate::ast::*;
-use crate::keywords::Keyword;
+use crate::dialect::Precedence;
+use crate::keywords::{Keyword, CYPHER_RESERVED};
 use crate::parser::{Parser, ParserError};
-use crate::tokenizer::Token;
+use crate::tokenizer::{Location, Token};
+
+/// The message of the error parsing a statement that has no tokens but
+/// whitespace, comments and `;` separators
+pub(crate) const EMPTY_CYPHER_STATEMENT: &str = "empty Cypher statement";
 
 impl<'a> Parser<'a> {
     /// Parse a Cypher statement - entry point for Cypher parsing
     pub fn parse_cypher_statement(&mut self) -> Result<CypherStatement, ParserError> {
+        while self.consume_token(&Token::SemiColon) {}
+        if self.peek_token_ref().token == Token::EOF {
+            return Err(ParserError::ParserError(EMPTY_CYPHER_STATEMENT.to_string()));
+        }
+        let statement = self.parse_cypher_single_statement()?;
+        if !self.peek_keyword(Keyword::UNION) {
+            return Ok(statement);
+        }
+
+        let mut queries = vec![statement];
+        let mut all = None;
+        while self.peek_keyword(Keyword::UNION) {
+            let location = self.next_token().span.start;
+            let union_all = self.parse_keyword(Keyword::ALL);
+            if all.is_some_and(|all| all != union_all) {
+                return Err(ParserError::ParserError(format!(
+                    "Cannot mix UNION and UNION ALL in one Cypher query{location}"
+                )));
+            }
+            all = Some(union_all);
+            queries.push(self.parse_cypher_single_statement()?);
+        }
+
+        // Every arm of a UNION must produce rows, from the RETURN after its
+        // last MATCH
+        for query in &queries {
+            let mut last = query;
+            while let CypherStatement::Match { with: None, next: Some(next), .. }
+            | CypherStatement::Unwind { query: next, .. } = last
+            {
+                last = next;
+            }
+            if !matches!(last, CypherStatement::Match { r#return: Some(_), .. }) {
+                return Err(ParserError::ParserError(format!(
+                    "Expected: a MATCH ... RETURN query on each side of UNION, found: {query}"
+                )));
+            }
+        }
+
+        Ok(CypherStatement::Union {
+            all: all.unwrap_or(false),
+            queries,
+        })
+    }
+
+    /// Parse a Cypher statement that is not a UNION
+    fn parse_cypher_single_statement(&mut self) -> Result<CypherStatement, ParserError> {
         match self.peek_token().token {
             Token::Word(ref w) if w.keyword == Keyword::MATCH => {
                 self.parse_cypher_match()
@@ -42,16 +97,40 @@ impl<'a> Parser<'a> {
             Token::Word(ref w) if w.keyword == Keyword::MERGE => {
                 self.parse_cypher_merge()
             }
-            Token::Word(ref w) if w.keyword == Keyword::DELETE => {
-                self.parse_cypher_delete()
+            // DELETE removes what a MATCH binds and filters
+            Token::Word(ref w) if matches!(w.keyword, Keyword::DELETE | Keyword::DETACH) => {
+                Err(ParserError::ParserError(format!(
+                    "DELETE must follow a MATCH that binds its variables, as in MATCH (n) WHERE ... DELETE n{}",
+                    self.peek_token().span.start
+                )))
             }
-            Token::Word(ref w) if w.keyword == Keyword::DETACH => {
-                self.parse_cypher_detach_delete()
+            Token::Word(ref w) if w.keyword == Keyword::SHOW => self.parse_cypher_show(),
+            Token::Word(ref w) if w.keyword == Keyword::UNWIND => self.parse_cypher_unwind(),
+            // A query without a MATCH, e.g. `RETURN 1 + 1 AS two`
+            Token::Word(ref w) if matches!(w.keyword, Keyword::WITH | Keyword::RETURN) => {
+                self.parse_cypher_match_tail(false, vec![], None)
             }
-            _ => self.expected("MATCH, CREATE, MERGE, or DELETE", self.peek_token()),
+            _ => self.expected(
+                "MATCH, CREATE, MERGE, SHOW, UNWIND, WITH, or RETURN",
+                self.peek_token(),
+            ),
         }
     }
 
+    /// Parse an UNWIND of a list, e.g. `UNWIND range(1, 3) AS i RETURN i`,
+    /// and the query after it
+    fn parse_cypher_unwind(&mut self) -> Result<CypherStatement, ParserError> {
+        self.expect_keyword(Keyword::UNWIND)?;
+        let list = self.parse_expr()?;
+        self.expect_keyword(Keyword::AS)?;
+        let variable = self.parse_identifier()?;
+        Ok(CypherStatement::Unwind {
+            list,
+            variable,
+            query: Box::new(self.parse_cypher_single_statement()?),
+        })
+    }
+
     /// Parse a MATCH statement
     fn parse_cypher_match(&mut self) -> Result<CypherStatement, ParserError> {
         self.parse_cypher_match_with_optional(false)
@@ -63,16 +142,69 @@ impl<'a> Parser<'a> {
             self.expect_keyword(Keyword::MATCH)?;
         }
         
-        let patterns = self.parse_cypher_patterns()?;
+        let patterns = self.parse_cypher_patterns(false)?;
         
         let r#where = if self.parse_keyword(Keyword::WHERE) {
             Some(self.parse_expr()?)
         } else {
             None
         };
+
+        if !optional && (self.peek_keyword(Keyword::DELETE) || self.peek_keyword(Keyword::DETACH)) {
+            return self.parse_cypher_delete(patterns, r#where);
+        }
+        if !optional && self.parse_keyword(Keyword::CREATE) {
+            return Ok(CypherStatement::Create {
+                r#match: patterns,
+                r#where,
+                patterns: self.parse_cypher_patterns(true)?,
+                r#return: self.parse_cypher_create_return()?,
+            });
+        }
+        if !optional && self.parse_keyword(Keyword::SET) {
+            return Ok(CypherStatement::Set {
+                patterns,
+                r#where,
+                items: self.parse_cypher_set_clauses()?,
+            });
+        }
+
+        self.parse_cypher_match_tail(optional, patterns, r#where)
+    }
+
+    /// Parse the WITH and RETURN clauses of a query over the rows of the
+    /// MATCH of `patterns`, or of a single empty row without patterns
+    fn parse_cypher_match_tail(
+        &mut self,
+        optional: bool,
+        patterns: Vec<Pattern>,
+        r#where: Option<Expr>,
+    ) -> Result<CypherStatement, ParserError> {
+        let with = if self.parse_keyword(Keyword::WITH) {
+            Some(self.parse_cypher_with_clause()?)
+        } else {
+            None
+        };
         
-        let r#return = if self.parse_keyword(Keyword::RETURN) {
+        // The query may go on with another MATCH over the rows of the WITH,
+        // or of this MATCH without one, which then has the RETURN; a WHERE
+        // belongs to the clause it follows
+        let continues = with.is_some() || !patterns.is_empty();
+        let next = if continues && self.peek_keyword(Keyword::MATCH) {
+            Some(Box::new(self.parse_cypher_match_with_optional(false)?))
+        } else if continues && self.parse_keywords(&[Keyword::OPTIONAL, Keyword::MATCH]) {
+            Some(Box::new(self.parse_cypher_match_with_optional(true)?))
+        } else {
+            None
+        };
+
+        let r#return = if next.is_some() {
+            None
+        } else if self.parse_keyword(Keyword::RETURN) {
             Some(self.parse_cypher_return_clause()?)
+        } else if with.is_some() {
+            // A query cannot conclude with WITH
+            return self.expected("RETURN or MATCH after WITH", self.peek_token());
         } else {
             None
         };
@@ -81,95 +213,228 @@ impl<'a> Parser<'a> {
             optional,
             patterns,
             r#where,
+            with,
             r#return,
+            next,
+        })
+    }
+
+    /// Parse a SHOW statement, e.g.
+    /// `SHOW DATABASES YIELD name, currentStatus AS status WHERE name = 'neo4j'`
+    fn parse_cypher_show(&mut self) -> Result<CypherStatement, ParserError> {
+        self.expect_keyword(Keyword::SHOW)?;
+        let what = match self.parse_one_of_keywords(&[
+            Keyword::DATABASE,
+            Keyword::DATABASES,
+            Keyword::PROCEDURE,
+            Keyword::PROCEDURES,
+            Keyword::FUNCTION,
+            Keyword::FUNCTIONS,
+            Keyword::INDEX,
+            Keyword::INDEXES,
+            Keyword::CONSTRAINT,
+            Keyword::CONSTRAINTS,
+            Keyword::TRANSACTION,
+            Keyword::TRANSACTIONS,
+        ]) {
+            Some(Keyword::DATABASE | Keyword::DATABASES) => ShowTarget::Databases,
+            Some(Keyword::PROCEDURE | Keyword::PROCEDURES) => ShowTarget::Procedures,
+            Some(Keyword::FUNCTION | Keyword::FUNCTIONS) => ShowTarget::Functions,
+            Some(Keyword::INDEX | Keyword::INDEXES) => ShowTarget::Indexes,
+            Some(Keyword::CONSTRAINT | Keyword::CONSTRAINTS) => ShowTarget::Constraints,
+            Some(Keyword::TRANSACTION | Keyword::TRANSACTIONS) => ShowTarget::Transactions,
+            _ => {
+                return self.expected(
+                    "DATABASES, PROCEDURES, FUNCTIONS, INDEXES, CONSTRAINTS, or TRANSACTIONS",
+                    self.peek_token(),
+                )
+            }
+        };
+        let yield_items = if self.parse_keyword(Keyword::YIELD) {
+            self.parse_comma_separated(Parser::parse_cypher_return_item)?
+        } else {
+            vec![]
+        };
+        let r#where = if self.parse_keyword(Keyword::WHERE) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        Ok(CypherStatement::Show {
+            what,
+            yield_items,
+            r#where,
         })
     }
 
     /// Parse a CREATE statement
     fn parse_cypher_create(&mut self) -> Result<CypherStatement, ParserError> {
         self.expect_keyword(Keyword::CREATE)?;
-        let patterns = self.parse_cypher_patterns()?;
+        let patterns = self.parse_cypher_patterns(true)?;
         
-        Ok(CypherStatement::Create { patterns })
+        Ok(CypherStatement::Create {
+            r#match: vec![],
+            r#where: None,
+            patterns,
+            r#return: self.parse_cypher_create_return()?,
+        })
+    }
+
+    /// Parse the optional RETURN after the patterns of a CREATE
+    fn parse_cypher_create_return(&mut self) -> Result<Option<ReturnClause>, ParserError> {
+        if self.parse_keyword(Keyword::RETURN) {
+            Ok(Some(self.parse_cypher_return_clause()?))
+        } else {
+            Ok(None)
+        }
     }
 
     /// Parse a MERGE statement
     fn parse_cypher_merge(&mut self) -> Result<CypherStatement, ParserError> {
         self.expect_keyword(Keyword::MERGE)?;
-        let patterns = self.parse_cypher_patterns()?;
+        let location = self.peek_token().span.start;
+        let patterns = self.parse_cypher_patterns(false)?;
+        Self::validate_cypher_merge_patterns(&patterns, location)?;
         
-        let mut on_create = None;
-        let mut on_match = None;
-        
-        // Parse ON CREATE SET and ON MATCH SET clauses
-        while self.parse_keyword(Keyword::ON) {
-            if self.parse_keyword(Keyword::CREATE) {
-                self.expect_keyword(Keyword::SET)?;
-                on_create = Some(self.parse_cypher_set_clauses()?);
-            } else if self.parse_keyword(Keyword::MATCH) {
-                self.expect_keyword(Keyword::SET)?;
-                on_match = Some(self.parse_cypher_set_clauses()?);
-            } else {
-                return self.expected("CREATE or MATCH after ON", self.peek_token());
+        // ON CREATE SET and ON MATCH SET, in either order but once each
+        let mut actions: Vec<MergeSetAction> = vec![];
+        while self.peek_keyword(Keyword::ON) {
+            let location = self.next_token().span.start;
+            let trigger = match self.parse_one_of_keywords(&[Keyword::CREATE, Keyword::MATCH]) {
+                Some(Keyword::CREATE) => MergeTrigger::OnCreate,
+                Some(_) => MergeTrigger::OnMatch,
+                None => return self.expected("CREATE or MATCH after ON", self.peek_token()),
+            };
+            if actions.iter().any(|action| action.trigger == trigger) {
+                return Err(ParserError::ParserError(format!(
+                    "Duplicate {trigger} action in MERGE{location}"
+                )));
             }
+            self.expect_keyword(Keyword::SET)?;
+            actions.push(MergeSetAction {
+                trigger,
+                items: self.parse_cypher_set_clauses()?,
+            });
         }
         
-        Ok(CypherStatement::Merge {
-            patterns,
-            on_create,
-            on_match,
-        })
+        Ok(CypherStatement::Merge { patterns, actions })
+    }
+
+    /// Check the pattern of a MERGE against the rules of Neo4j: a single
+    /// pattern, whose relationships each have exactly one type and a fixed
+    /// length of one
+    fn validate_cypher_merge_patterns(
+        patterns: &[Pattern],
+        location: Location,
+    ) -> Result<(), ParserError> {
+        if patterns.len() > 1 {
+            return Err(ParserError::ParserError(format!(
+                "MERGE takes a single pattern, found {}{location}",
+                patterns.len()
+            )));
+        }
+        for element in patterns.iter().flat_map(|pattern| &pattern.elements) {
+            let PatternElement::Relationship { types, length, .. } = element else {
+                continue;
+            };
+            if types.len() != 1 {
+                return Err(ParserError::ParserError(format!(
+                    "Exactly one relationship type must be specified for MERGE, found {} in {element}{location}",
+                    types.len()
+                )));
+            }
+            if length.is_some() {
+                return Err(ParserError::ParserError(format!(
+                    "Variable length relationships cannot be used in MERGE: {element}{location}"
+                )));
+            }
+        }
+        Ok(())
     }
 
-    /// Parse a DELETE statement
-    fn parse_cypher_delete(&mut self) -> Result<CypherStatement, ParserError> {
+    /// Parse the [DETACH] DELETE of a MATCH, after its `patterns` and
+    /// condition
+    fn parse_cypher_delete(
+        &mut self,
+        patterns: Vec<Pattern>,
+        r#where: Option<Expr>,
+    ) -> Result<CypherStatement, ParserError> {
+        let detach = self.parse_keyword(Keyword::DETACH);
         self.expect_keyword(Keyword::DELETE)?;
-        let what = self.parse_comma_separated(|parser| parser.parse_expr())?;
-        
-        let r#where = if self.parse_keyword(Keyword::WHERE) {
-            Some(self.parse_expr()?)
-        } else {
-            None
-        };
-        
+        let what = self.parse_cypher_delete_targets(&patterns)?;
         Ok(CypherStatement::Delete {
-            detach: false,
+            patterns,
+            detach,
             what,
             r#where,
         })
     }
 
-    /// Parse a DETACH DELETE statement
-    fn parse_cypher_detach_delete(&mut self) -> Result<CypherStatement, ParserError> {
-        self.expect_keyword(Keyword::DETACH)?;
-        self.expect_keyword(Keyword::DELETE)?;
-        let what = self.parse_comma_separated(|parser| parser.parse_expr())?;
-        
-        let r#where = if self.parse_keyword(Keyword::WHERE) {
-            Some(self.parse_expr()?)
-        } else {
-            None
-        };
-        
-        Ok(CypherStatement::Delete {
-            detach: true,
-            what,
-            r#where,
+    /// Parse the variables a DELETE removes, which must be bound by
+    /// `patterns`
+    fn parse_cypher_delete_targets(
+        &mut self,
+        patterns: &[Pattern],
+    ) -> Result<Vec<Expr>, ParserError> {
+        self.parse_comma_separated(|parser| {
+            let location = parser.peek_token().span.start;
+            let target = parser.parse_expr()?;
+            match &target {
+                Expr::Identifier(variable) => {
+                    let elements = patterns.iter().flat_map(|pattern| &pattern.elements);
+                    let mut variables = elements.filter_map(PatternElement::variable);
+                    if !variables.any(|bound| bound == variable) {
+                        return Err(ParserError::ParserError(format!(
+                            "variable `{variable}` in DELETE is not bound{location}"
+                        )));
+                    }
+                }
+                Expr::CompoundIdentifier(_) => {
+                    return Err(ParserError::ParserError(format!(
+                        "cannot DELETE property expression `{target}`; use REMOVE{location}"
+                    )));
+                }
+                Expr::Cypher(cypher_expr)
+                    if matches!(**cypher_expr, CypherExpr::LabelPredicate { .. }) =>
+                {
+                    return Err(ParserError::ParserError(format!(
+                        "cannot DELETE label expression `{target}`; use REMOVE{location}"
+                    )));
+                }
+                _ => {
+                    return Err(ParserError::ParserError(format!(
+                        "cannot DELETE expression `{target}`; expected a node or relationship variable{location}"
+                    )));
+                }
+            }
+            Ok(target)
         })
     }
 
-    /// Parse comma-separated patterns
-    fn parse_cypher_patterns(&mut self) -> Result<Vec<Pattern>, ParserError> {
-        self.parse_comma_separated(|parser| parser.parse_cypher_pattern())
+    /// Parse comma-separated patterns, of a CREATE when `create` is set
+    fn parse_cypher_patterns(&mut self, create: bool) -> Result<Vec<Pattern>, ParserError> {
+        self.parse_comma_separated(|parser| parser.parse_cypher_pattern(create))
     }
 
-    /// Parse a single pattern (nodes and relationships)
-    fn parse_cypher_pattern(&mut self) -> Result<Pattern, ParserError> {
+    /// Parse a single pattern (nodes and relationships), of a CREATE when
+    /// `create` is set
+    fn parse_cypher_pattern(&mut self, create: bool) -> Result<Pattern, ParserError> {
         let mut elements = vec![];
+
+        // A named path binds the whole pattern: p = (a)-[:KNOWS]->(b)
+        let variable = if matches!(self.peek_token().token, Token::Word(_))
+            && self.peek_nth_token(1).token == Token::Eq
+        {
+            let variable = self.parse_cypher_variable()?;
+            self.expect_token(&Token::Eq)?;
+            Some(variable)
+        } else {
+            None
+        };
         
         // A pattern starts with a node
         if self.consume_token(&Token::LParen) {
-            let node = self.parse_cypher_node()?;
+            let node = self.parse_cypher_node(create)?;
             elements.push(node);
             
             // Parse relationships and connected nodes
@@ -181,7 +446,7 @@ impl<'a> Parser<'a> {
                 
                 // After a relationship, expect another node
                 if self.consume_token(&Token::LParen) {
-                    let node = self.parse_cypher_node()?;
+                    let node = self.parse_cypher_node(create)?;
                     elements.push(node);
                 } else {
                     return self.expected("node after relationship", self.peek_token());
@@ -191,45 +456,43 @@ impl<'a> Parser<'a> {
             return self.expected("pattern starting with '('", self.peek_token());
         }
         
-        Ok(Pattern { elements })
+        Ok(Pattern { variable, elements })
     }
 
-    /// Parse a node pattern: (variable:Label {properties})
-    fn parse_cypher_node(&mut self) -> Result<PatternElement, ParserError> {
-        // Already consumed the opening parenthesis
-    
-        let mut variable = None;
+    /// Parse a node pattern: (variable:Label {properties}), or in a CREATE
+    /// with a parameter as its properties, (variable:Label $properties)
+    fn parse_cypher_node(&mut self, create: bool) -> Result<PatternElement, ParserError> {
+        // Already consumed the opening parenthesis; the variable, labels and
+        // properties follow in this order, each optional
+        let variable = match self.peek_token_ref().token {
+            Token::Word(_) => Some(self.parse_cypher_variable()?),
+            _ => None,
+        };
+
         let mut labels = vec![];
-    
-        // Check what we have: variable, label, or empty
-        if matches!(self.peek_token().token, Token::Word(_)) {
-            if self.peek_nth_token(1).token == Token::Colon {
-                // This is either "variable:Label" or ":Label"
-                variable = Some(self.parse_identifier()?);
-            } else if self.peek_nth_token(1).token == Token::RParen {
-                // This is just "variable" with no labels
-                variable = Some(self.parse_identifier()?);
-            } else {
-                // This might be a variable followed by something else
-                variable = Some(self.parse_identifier()?);
-            }
-        }
-    
-        // Parse labels
         while self.consume_token(&Token::Colon) {
-            labels.push(self.parse_identifier()?);
+            labels.push(self.parse_cypher_label()?);
         }
-        
-        let properties = if self.consume_token(&Token::LBrace) {
-            let props = self.parse_map_literal()?;
-            self.expect_token(&Token::RBrace)?;
-            Some(props)
-        } else {
-            None
+
+        // A map literal, or in a CREATE a parameter holding one
+        let properties = match self.peek_token_ref().token {
+            Token::LBrace => {
+                self.advance_token();
+                Some(self.parse_map_literal()?)
+            }
+            Token::Placeholder(_) if create => Some(Expr::Value(self.parse_value()?)),
+            Token::Placeholder(ref name) => {
+                return Err(ParserError::ParserError(format!(
+                    "A parameter can be the properties of a node in CREATE only, \
+                     use a map of its values instead, e.g. {{name: {name}.name}}{}",
+                    self.peek_token_ref().span.start
+                )))
+            }
+            _ => None,
         };
-        
+
         self.expect_token(&Token::RParen)?;
-        
+
         Ok(PatternElement::Node {
             variable,
             labels,
@@ -249,36 +512,40 @@ impl<'a> Parser<'a> {
         
         // Parse relationship details if present
         if self.consume_token(&Token::LBracket) {
-            // Parse variable name - if we see a word followed by colon, it's "variable:type"
+            // A name before the types, length and properties is the variable
             if matches!(self.peek_token().token, Token::Word(_)) {
-                if matches!(self.peek_nth_token(1).token, Token::Colon) {
-                    // This is "variable:TYPE" pattern
-                    variable = Some(self.parse_identifier()?);
-                } else if matches!(self.peek_nth_token(1).token, Token::RBracket) {
-                    // This is just "[variable]" with no type
-                    variable = Some(self.parse_identifier()?);
-                }
-                // Note: We don't parse the variable if it's followed by something else
+                variable = Some(self.parse_cypher_variable()?);
             }
             
             // Parse relationship types
             while self.consume_token(&Token::Colon) {
-                types.push(self.parse_identifier()?);
+                types.push(self.parse_cypher_relationship_type()?);
                 // Handle multiple types with |
                 while self.consume_token(&Token::Pipe) {
-                    types.push(self.parse_identifier()?);
+                    types.push(self.parse_cypher_relationship_type()?);
                 }
             }
             
-            // Parse length specification
-            if self.consume_token(&Token::Mul) {
-                length = Some(self.parse_cypher_relationship_length()?);
-            }
-            
-            // Parse properties
-            if self.consume_token(&Token::LBrace) {
-                properties = Some(self.parse_map_literal()?);
-                self.expect_token(&Token::RBrace)?;
+            // The length and the properties, in either order but once each
+            loop {
+                let location = self.peek_token().span.start;
+                let (what, specified) = if self.consume_token(&Token::Mul) {
+                    ("length", length.is_some())
+                } else if self.consume_token(&Token::LBrace) {
+                    ("properties", properties.is_some())
+                } else {
+                    break;
+                };
+                if specified {
+                    return Err(ParserError::ParserError(format!(
+                        "Relationship {what} specified twice{location}"
+                    )));
+                }
+                if what == "length" {
+                    length = Some(self.parse_cypher_relationship_length()?);
+                } else {
+                    properties = Some(self.parse_map_literal()?);
+                }
             }
             
             self.expect_token(&Token::RBracket)?;
@@ -310,6 +577,80 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Parse the name of a variable: any word but the [`CYPHER_RESERVED`]
+    /// keywords, which must be quoted with backticks
+    pub fn parse_cypher_variable(&mut self) -> Result<Ident, ParserError> {
+        let next_token = self.next_token();
+        match next_token.token {
+            Token::Word(word) if !CYPHER_RESERVED.contains(&word.keyword) => {
+                Ok(word.into_ident(next_token.span))
+            }
+            _ => self.expected("a variable name", next_token),
+        }
+    }
+
+    /// Parse a relationship type: a bare name such as `IS_A`, or any text
+    /// between backticks such as `` `HAS PART` ``
+    fn parse_cypher_relationship_type(&mut self) -> Result<Ident, ParserError> {
+        self.parse_cypher_name("a relationship type")
+    }
+
+    /// Parse a node label: a bare name such as `Person`, or any text between
+    /// backticks such as `` `E-Commerce` ``
+    fn parse_cypher_label(&mut self) -> Result<Ident, ParserError> {
+        self.parse_cypher_name("a label")
+    }
+
+    /// Parse a label or relationship type, `what` it is
+    ///
+    /// A bare name starting with a digit or holding a dash, e.g.
+    /// `1ST_DEGREE` or `E-Commerce`, is split by the tokenizer, and is
+    /// rejected whole with an error suggesting backticks.
+    fn parse_cypher_name(&mut self, what: &str) -> Result<Ident, ParserError> {
+        let next_token = self.next_token();
+        let ident = match &next_token.token {
+            Token::Word(word) if word.quote_style == Some('`') => {
+                return Ok(word.clone().into_ident(next_token.span));
+            }
+            Token::Word(word) if word.quote_style.is_none() => {
+                Some(word.clone().into_ident(next_token.span))
+            }
+            Token::Number(..) => None,
+            _ => {
+                return self.expected(
+                    &format!("{what}, in backticks unless it is a bare name"),
+                    next_token,
+                )
+            }
+        };
+        // The rest of the name the tokenizer split off, up to a token apart
+        // from it, e.g. `ST_DEGREE` of `1ST_DEGREE`
+        let mut name = next_token.token.to_string();
+        let mut end = next_token.span.end;
+        loop {
+            let token = self.peek_token_ref();
+            let part = match &token.token {
+                Token::Word(word) if word.quote_style.is_none() => true,
+                Token::Number(..) | Token::Minus => true,
+                _ => false,
+            };
+            if !part || token.span.start != end {
+                break;
+            }
+            name.push_str(&token.token.to_string());
+            end = token.span.end;
+            self.advance_token();
+        }
+        match ident {
+            Some(ident) if name == ident.value => Ok(ident),
+            _ => Err(ParserError::ParserError(format!(
+                "Expected: {what}, in backticks as `{name}` since a bare name cannot \
+                 start with a digit or hold a dash, found: {name}{}",
+                next_token.span.start
+            ))),
+        }
+    }
+
     /// Parse relationship length specification: *n or *n..m or *..m or *n.. or *
     fn parse_cypher_relationship_length(&mut self) -> Result<RelationshipLength, ParserError> {
         if let Token::Number(n, _) = &self.peek_token().token {
@@ -352,26 +693,52 @@ impl<'a> Parser<'a> {
 
     /// Parse RETURN clause
     fn parse_cypher_return_clause(&mut self) -> Result<ReturnClause, ParserError> {
-        let distinct = self.parse_keyword(Keyword::DISTINCT);
-        let items = self.parse_projection()?;
-        
-        let order_by = if self.parse_keywords(&[Keyword::ORDER, Keyword::BY]) {
-            vec![self.parse_order_by_expr()?]
-        } else {
-            vec![]
-        };
-        
-        let skip = if self.parse_keyword(Keyword::SKIP) {
+        self.parse_cypher_projection("RETURN")
+    }
+
+    /// Parse WITH clause: the items and modifiers of a RETURN, then an
+    /// optional WHERE
+    fn parse_cypher_with_clause(&mut self) -> Result<WithClause, ParserError> {
+        let projection = self.parse_cypher_projection("WITH")?;
+        let r#where = if self.parse_keyword(Keyword::WHERE) {
             Some(self.parse_expr()?)
         } else {
             None
         };
+        Ok(WithClause {
+            projection,
+            r#where,
+        })
+    }
+
+    /// Parse the items and modifiers of the RETURN or WITH `clause`
+    fn parse_cypher_projection(&mut self, clause: &str) -> Result<ReturnClause, ParserError> {
+        let distinct = self.parse_keyword(Keyword::DISTINCT);
+        let items = self.parse_comma_separated(Parser::parse_cypher_return_item)?;
         
-        let limit = if self.parse_keyword(Keyword::LIMIT) {
-            Some(self.parse_expr()?)
+        let order_by = if self.parse_keywords(&[Keyword::ORDER, Keyword::BY]) {
+            self.parse_comma_separated(Parser::parse_cypher_order_by_expr)?
         } else {
-            None
+            vec![]
         };
+        
+        // SKIP and LIMIT, in either order but once each
+        let mut skip = None;
+        let mut limit = None;
+        loop {
+            let location = self.peek_token().span.start;
+            let (keyword, value) = match self.parse_one_of_keywords(&[Keyword::SKIP, Keyword::LIMIT]) {
+                Some(Keyword::SKIP) => ("SKIP", &mut skip),
+                Some(_) => ("LIMIT", &mut limit),
+                None => break,
+            };
+            if value.is_some() {
+                return Err(ParserError::ParserError(format!(
+                    "Duplicate {keyword} in {clause}{location}"
+                )));
+            }
+            *value = Some(self.parse_expr()?);
+        }
 
         Ok(ReturnClause {
             distinct,
@@ -382,6 +749,257 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Parse an ORDER BY item of a RETURN or WITH: an expression with an
+    /// optional direction, `ASC` or `ASCENDING`, `DESC` or `DESCENDING`
+    pub fn parse_cypher_order_by_expr(&mut self) -> Result<OrderByExpr, ParserError> {
+        let expr = self.parse_expr()?;
+        let asc = match self.parse_one_of_keywords(&[
+            Keyword::ASC,
+            Keyword::ASCENDING,
+            Keyword::DESC,
+            Keyword::DESCENDING,
+        ]) {
+            Some(Keyword::ASC | Keyword::ASCENDING) => Some(true),
+            Some(_) => Some(false),
+            None => None,
+        };
+        Ok(OrderByExpr {
+            expr,
+            options: OrderByOptions {
+                asc,
+                nulls_first: None,
+            },
+            with_fill: None,
+        })
+    }
+
+    /// Parse an item of a RETURN clause: `*`, or an expression with an
+    /// optional `AS` alias, which may be any word since Cypher does not
+    /// reserve keywords as names
+    pub fn parse_cypher_return_item(&mut self) -> Result<SelectItem, ParserError> {
+        if self.peek_token_ref().token == Token::Mul {
+            let wildcard_token = self.next_token();
+            return Ok(SelectItem::Wildcard(
+                self.parse_wildcard_additional_options(wildcard_token)?,
+            ));
+        }
+        let expr = self.parse_expr()?;
+        if self.parse_keyword(Keyword::AS) {
+            let next_token = self.next_token();
+            let Token::Word(word) = next_token.token else {
+                return self.expected("an alias after AS", next_token);
+            };
+            return Ok(SelectItem::ExprWithAlias {
+                expr,
+                alias: word.into_ident(next_token.span),
+            });
+        }
+        // Unlike SQL, Cypher has no implicit aliases
+        if let Token::Word(word) = &self.peek_token_ref().token {
+            if !matches!(
+                word.keyword,
+                Keyword::ORDER
+                    | Keyword::SKIP
+                    | Keyword::LIMIT
+                    | Keyword::UNION
+                    | Keyword::WHERE
+                    | Keyword::RETURN
+                    | Keyword::MATCH
+                    | Keyword::OPTIONAL
+            ) {
+                return Err(ParserError::ParserError(format!(
+                    "Expected: AS before the alias {word} of a RETURN item{}",
+                    self.peek_token_ref().span.start
+                )));
+            }
+        }
+        Ok(SelectItem::UnnamedExpr(expr))
+    }
+
+    /// Parse the labels of a label predicate following `expr`, e.g. `n:Person:Admin`
+    pub fn parse_cypher_label_predicate(&mut self, expr: Expr) -> Result<Expr, ParserError> {
+        let mut labels = vec![];
+        while self.consume_token(&Token::Colon) {
+            labels.push(self.parse_cypher_label()?);
+        }
+        if labels.is_empty() {
+            return self.expected("':' followed by a label", self.peek_token());
+        }
+        Ok(Expr::Cypher(Box::new(CypherExpr::LabelPredicate { expr, labels })))
+    }
+
+    /// Parse a type predicate following `expr`, e.g. `n.age IS :: INTEGER` or
+    /// `n.name IS NOT :: STRING`
+    pub fn parse_cypher_type_predicate(&mut self, expr: Expr) -> Result<Expr, ParserError> {
+        self.expect_keyword_is(Keyword::IS)?;
+        let negated = self.parse_keyword(Keyword::NOT);
+        self.expect_token(&Token::DoubleColon)?;
+        let data_type = self.parse_cypher_type()?;
+        Ok(Expr::Cypher(Box::new(CypherExpr::TypePredicate {
+            expr,
+            negated,
+            data_type,
+        })))
+    }
+
+    /// Parse a Cypher value type, e.g. `INTEGER` or `LIST<STRING>`
+    pub fn parse_cypher_type(&mut self) -> Result<CypherType, ParserError> {
+        let (data_type, closed_enclosing) = self.parse_cypher_type_inner()?;
+        if closed_enclosing {
+            return self.expected("the end of the type", self.get_current_token().clone());
+        }
+        Ok(data_type)
+    }
+
+    /// Parse a Cypher value type, and whether it ended with the `>>` closing
+    /// both its own list type and the enclosing one, e.g. `LIST<LIST<INT>>`
+    fn parse_cypher_type_inner(&mut self) -> Result<(CypherType, bool), ParserError> {
+        let next_token = self.next_token();
+        let Token::Word(word) = &next_token.token else {
+            return self.expected("a type name", next_token);
+        };
+        let data_type = match word.value.to_uppercase().as_str() {
+            "ANY" => CypherType::Any,
+            "BOOL" | "BOOLEAN" => CypherType::Boolean,
+            "STRING" | "VARCHAR" => CypherType::String,
+            "INT" | "INT64" | "INTEGER" => CypherType::Integer,
+            "SIGNED" => {
+                self.expect_keyword_is(Keyword::INTEGER)?;
+                CypherType::Integer
+            }
+            "FLOAT" | "FLOAT64" => CypherType::Float,
+            "NULL" => CypherType::Null,
+            "DATE" => CypherType::Date,
+            "DURATION" => CypherType::Duration,
+            "POINT" => CypherType::Point,
+            "NODE" | "VERTEX" => CypherType::Node,
+            "RELATIONSHIP" | "EDGE" => CypherType::Relationship,
+            "PATH" => CypherType::Path,
+            "MAP" => CypherType::Map,
+            "LIST" | "ARRAY" => {
+                if !self.consume_token(&Token::Lt) {
+                    return Ok((CypherType::List(None), false));
+                }
+                let (element, closed) = self.parse_cypher_type_inner()?;
+                let closed_enclosing = if closed || self.consume_token(&Token::Gt) {
+                    false
+                } else if self.consume_token(&Token::ShiftRight) {
+                    true
+                } else {
+                    let found = self.peek_token();
+                    return self.expected("> after the type of the list elements", found);
+                };
+                return Ok((CypherType::List(Some(Box::new(element))), closed_enclosing));
+            }
+            _ => return self.expected("a type name", next_token),
+        };
+        Ok((data_type, false))
+    }
+
+    /// Parse a pattern comprehension, e.g.
+    /// `[(n)-[:KNOWS]->(f) WHERE f.age > 30 | f.name]`
+    pub fn parse_cypher_pattern_comprehension(&mut self) -> Result<Expr, ParserError> {
+        self.expect_token(&Token::LBracket)?;
+        let pattern = self.parse_cypher_pattern(false)?;
+        if pattern.elements.len() < 2 {
+            return self.expected(
+                "a relationship in the pattern comprehension",
+                self.peek_token(),
+            );
+        }
+        let r#where = if self.parse_keyword(Keyword::WHERE) {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+        self.expect_token(&Token::Pipe)?;
+        let projection = self.parse_expr()?;
+        self.expect_token(&Token::RBracket)?;
+        Ok(Expr::Cypher(Box::new(CypherExpr::PatternComprehension {
+            pattern,
+            r#where,
+            projection,
+        })))
+    }
+
+    /// Parse a call of the legacy `exists()` function, whose argument is a
+    /// property, `exists(n.email)`, or a pattern, `exists((n)-[:KNOWS]->())`
+    ///
+    /// The call is a function call rather than an `EXISTS` subquery, with a
+    /// pattern argument as a [`CypherExpr::PatternPredicate`].
+    pub fn parse_cypher_exists(&mut self) -> Result<Expr, ParserError> {
+        let name = ObjectName::from(vec![self.parse_identifier()?]);
+        // A parenthesized node alone is an expression, e.g. `exists((n.email))`
+        let pattern = self.maybe_parse(|parser| {
+            parser.expect_token(&Token::LParen)?;
+            let pattern = parser.parse_cypher_pattern(false)?;
+            if pattern.elements.len() < 2 {
+                return parser.expected("a relationship in the pattern", parser.peek_token());
+            }
+            parser.expect_token(&Token::RParen)?;
+            Ok(pattern)
+        })?;
+        let Some(pattern) = pattern else {
+            return self.parse_function(name);
+        };
+        let predicate = Expr::Cypher(Box::new(CypherExpr::PatternPredicate { pattern }));
+        Ok(Expr::Function(Function {
+            name,
+            uses_odbc_syntax: false,
+            parameters: FunctionArguments::None,
+            args: FunctionArguments::List(FunctionArgumentList {
+                duplicate_treatment: None,
+                args: vec![FunctionArg::Unnamed(FunctionArgExpr::Expr(predicate))],
+                clauses: vec![],
+            }),
+            filter: None,
+            null_treatment: None,
+            over: None,
+            within_group: vec![],
+        }))
+    }
+
+    /// Parse a list membership test following `expr`, e.g. `n.age IN [1, 2]`
+    /// or `'Admin' IN labels(n)`
+    ///
+    /// A list literal is kept as the items of an [`Expr::InList`], and any
+    /// other list is a [`CypherExpr::In`].
+    pub fn parse_cypher_in_list(&mut self, expr: Expr) -> Result<Expr, ParserError> {
+        self.expect_keyword_is(Keyword::IN)?;
+        if self.peek_token_ref().token != Token::LBracket {
+            let list = self.parse_subexpr(self.dialect.prec_value(Precedence::Between))?;
+            return Ok(Expr::Cypher(Box::new(CypherExpr::In { expr, list })));
+        }
+        self.expect_token(&Token::LBracket)?;
+        let list = self.parse_comma_separated0(Parser::parse_expr, Token::RBracket)?;
+        self.expect_token(&Token::RBracket)?;
+        Ok(Expr::InList {
+            expr: Box::new(expr),
+            list,
+            negated: false,
+        })
+    }
+
+    /// Parse the string matching following `expr`, e.g.
+    /// `n.name STARTS WITH 'A'`, `ENDS WITH 'a'` or `CONTAINS 'd'`
+    pub fn parse_cypher_string_predicate(&mut self, expr: Expr) -> Result<Expr, ParserError> {
+        let operator = if self.parse_keywords(&[Keyword::STARTS, Keyword::WITH]) {
+            StringOperator::StartsWith
+        } else if self.parse_keywords(&[Keyword::ENDS, Keyword::WITH]) {
+            StringOperator::EndsWith
+        } else if self.parse_keyword(Keyword::CONTAINS) {
+            StringOperator::Contains
+        } else {
+            return self.expected("STARTS WITH, ENDS WITH or CONTAINS", self.peek_token());
+        };
+        let pattern = self.parse_subexpr(self.dialect.prec_value(Precedence::Like))?;
+        Ok(Expr::Cypher(Box::new(CypherExpr::StringPredicate {
+            expr,
+            operator,
+            pattern,
+        })))
+    }
+
     /// Parse SET clauses
     fn parse_cypher_set_clauses(&mut self) -> Result<Vec<SetClause>, ParserError> {
         self.parse_comma_separated(|parser| parser.parse_cypher_set_clause())
@@ -398,60 +1016,41 @@ impl<'a> Parser<'a> {
 
     /// Parse SET target (variable.property or variable:Label)
     fn parse_cypher_set_target(&mut self) -> Result<SetTarget, ParserError> {
-        let variable = self.parse_identifier()?;
+        let variable = self.parse_cypher_variable()?;
         
         if self.consume_token(&Token::Period) {
             let property = self.parse_identifier()?;
             Ok(SetTarget::Property { variable, property })
         } else if self.consume_token(&Token::Colon) {
-            let label = self.parse_identifier()?;
+            let label = self.parse_cypher_label()?;
             Ok(SetTarget::Label { variable, label })
         } else {
             Ok(SetTarget::Variable(variable))
         }
     }
 
-    /// Parse a map literal {key: value, ...} - simplified implementation
+    /// Parse the entries of a map literal after its opening brace:
+    /// `key: value, ...}`
     fn parse_map_literal(&mut self) -> Result<Expr, ParserError> {
-        // For now, treat map literals as function calls with named arguments
-        // This is a simplified approach - a full implementation would need
-        // proper AST support for maps
-        let mut args = vec![];
-        
+        let mut fields = vec![];
+
         if !self.consume_token(&Token::RBrace) {
             loop {
                 let key = self.parse_identifier()?;
                 self.expect_token(&Token::Colon)?;
                 let value = self.parse_expr()?;
-                
-                // Convert to a named argument for now
-                args.push(FunctionArg::Named {
-                    name: key,
-                    arg: FunctionArgExpr::Expr(value),
-                    operator: FunctionArgOperator::Colon,
+                fields.push(DictionaryField {
+                    key,
+                    value: Box::new(value),
                 });
-                
+
                 if !self.consume_token(&Token::Comma) {
                     break;
                 }
             }
             self.expect_token(&Token::RBrace)?;
         }
-        
-        // Return as a function call to represent the map
-        Ok(Expr::Function(Function {
-            name: ObjectName(vec![ObjectNamePart::Identifier(Ident::new("MAP"))]),
-            parameters: FunctionArguments::List(FunctionArgumentList {
-                duplicate_treatment: None,
-                args,
-                clauses: vec![],
-            }),
-            filter: None,
-            null_treatment: None,
-            over: None,
-            within_group: vec![],
-            args: FunctionArguments::None, // Add this missing field
-            uses_odbc_syntax: false, // Add this missing field
-        }))
+
+        Ok(Expr::Dictionary(fields))
     }
 }
\ No newline at end of file
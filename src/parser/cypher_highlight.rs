@@ -0,0 +1,321 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Classification of the tokens of Cypher text for syntax highlighting
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dialect::CypherDialect;
+use crate::keywords::{Keyword, CYPHER_RESERVED};
+use crate::tokenizer::{Location, Token, TokenWithSpan, Tokenizer, TokenizerError, Whitespace};
+
+/// What a piece of Cypher text is, for highlighting it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TokenClass {
+    /// A keyword, e.g. `MATCH`, `AS` or `NOT`
+    Keyword,
+    /// A variable or alias, e.g. `n` in `(n:Person)`
+    Variable,
+    /// The name of a called function, e.g. `count` in `count(n)`
+    Function,
+    /// A node label, e.g. `Person` in `(n:Person)` or `WHERE n:Person`
+    Label,
+    /// A relationship type, e.g. `KNOWS` in `-[:KNOWS]->`
+    RelationshipType,
+    /// A property key, e.g. `name` in `n.name` or `{name: 'Ada'}`
+    Property,
+    /// A string literal, with its quotes
+    String,
+    /// A number literal
+    Number,
+    /// A parameter, e.g. `$name`
+    Parameter,
+    /// A `--` or `/* */` comment
+    Comment,
+    /// An operator, e.g. `=`, `+` or the `->` of a relationship
+    Operator,
+    /// Brackets, braces, parentheses and the `,` `.` `:` and `;` separators
+    Punctuation,
+    /// Spaces, tabs, line breaks and the invisible characters the tokenizer
+    /// skips
+    Whitespace,
+}
+
+/// A piece of Cypher text and what it is
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClassifiedToken {
+    pub class: TokenClass,
+    /// The byte offsets of the piece in the text
+    pub span: Range<usize>,
+}
+
+/// The keywords of Cypher clauses, which Cypher does not reserve but which
+/// are highlighted as keywords unless they are plainly used as names
+const CLAUSE_KEYWORDS: &[Keyword] = &[
+    Keyword::ALL,
+    Keyword::AS,
+    Keyword::ASC,
+    Keyword::BY,
+    Keyword::CALL,
+    Keyword::CREATE,
+    Keyword::DELETE,
+    Keyword::DESC,
+    Keyword::DETACH,
+    Keyword::LIMIT,
+    Keyword::MATCH,
+    Keyword::MERGE,
+    Keyword::ON,
+    Keyword::OPTIONAL,
+    Keyword::ORDER,
+    Keyword::REMOVE,
+    Keyword::RETURN,
+    Keyword::SET,
+    Keyword::SHOW,
+    Keyword::SKIP,
+    Keyword::UNION,
+    Keyword::WHERE,
+    Keyword::WITH,
+    Keyword::YIELD,
+];
+
+/// Split Cypher text into classified pieces for syntax highlighting
+///
+/// The pieces follow each other without gaps and cover the whole text, so
+/// the text of each is `&input[token.span.clone()]`. Names are classified
+/// from the tokens around them, e.g. a name after `:` is a label in a node
+/// pattern and a relationship type in a relationship pattern, without
+/// parsing the text, so incomplete queries are classified as well. Only text
+/// the tokenizer rejects, such as an unterminated string, is an error.
+///
+/// ```
+/// # use sqlparser::parser::{classify_cypher_tokens, TokenClass};
+/// let input = "MATCH (n:Person)-[:KNOWS]->(m) RETURN m.name";
+/// let classes: Vec<_> = classify_cypher_tokens(input)?
+///     .into_iter()
+///     .filter(|token| token.class != TokenClass::Whitespace)
+///     .map(|token| (&input[token.span], token.class))
+///     .collect();
+/// assert_eq!(classes[4], ("Person", TokenClass::Label));
+/// assert_eq!(classes[9], ("KNOWS", TokenClass::RelationshipType));
+/// assert_eq!(classes[18], ("name", TokenClass::Property));
+/// # Ok::<(), sqlparser::tokenizer::TokenizerError>(())
+/// ```
+pub fn classify_cypher_tokens(input: &str) -> Result<Vec<ClassifiedToken>, TokenizerError> {
+    let tokens = Tokenizer::new(&CypherDialect, input).tokenize_with_location()?;
+    let mut offsets = ByteOffsets::new(input);
+    let significant: Vec<usize> = (0..tokens.len())
+        .filter(|&i| !matches!(tokens[i].token, Token::Whitespace(_) | Token::EOF))
+        .collect();
+
+    let mut classified: Vec<ClassifiedToken> = vec![];
+    let mut brackets: Vec<Bracket> = vec![];
+    // The class of the previous significant token but `|`, to carry the
+    // class of a label or type over a `|`
+    let mut previous_class = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let TokenWithSpan { token, span } = token;
+        let start = offsets.offset(span.start);
+        let end = offsets.offset(span.end).max(start);
+        // Characters the tokenizer skips, e.g. zero width spaces
+        let gap_start = classified.last().map_or(0, |last| last.span.end);
+        if gap_start < start {
+            classified.push(ClassifiedToken {
+                class: TokenClass::Whitespace,
+                span: gap_start..start,
+            });
+        }
+
+        let position = significant.partition_point(|&j| j < i);
+        let previous = position
+            .checked_sub(1)
+            .map(|j| &tokens[significant[j]].token);
+        let next = significant
+            .get(position + usize::from(significant.get(position) == Some(&i)))
+            .map(|&j| &tokens[j].token);
+        let class = match token {
+            Token::EOF => continue,
+            Token::Whitespace(Whitespace::SingleLineComment { .. })
+            | Token::Whitespace(Whitespace::MultiLineComment(_)) => TokenClass::Comment,
+            Token::Whitespace(_) => TokenClass::Whitespace,
+            Token::Word(word) => {
+                let bracket = brackets.last().copied();
+                match previous {
+                    Some(Token::Period) => TokenClass::Property,
+                    Some(Token::Colon) if bracket == Some(Bracket::Map) => {
+                        name_class(word.keyword, word.quote_style, next)
+                    }
+                    Some(Token::Colon) if bracket == Some(Bracket::Relationship) => {
+                        TokenClass::RelationshipType
+                    }
+                    Some(Token::Colon) => TokenClass::Label,
+                    Some(Token::Pipe)
+                        if matches!(
+                            previous_class,
+                            Some(TokenClass::Label | TokenClass::RelationshipType)
+                        ) =>
+                    {
+                        previous_class.unwrap_or(TokenClass::Label)
+                    }
+                    _ if bracket == Some(Bracket::Map) && next == Some(&Token::Colon) => {
+                        TokenClass::Property
+                    }
+                    _ => name_class(word.keyword, word.quote_style, next),
+                }
+            }
+            Token::Number(..) => TokenClass::Number,
+            Token::Placeholder(_) => TokenClass::Parameter,
+            Token::SingleQuotedString(_)
+            | Token::DoubleQuotedString(_)
+            | Token::NationalStringLiteral(_)
+            | Token::EscapedStringLiteral(_)
+            | Token::UnicodeStringLiteral(_)
+            | Token::HexStringLiteral(_) => TokenClass::String,
+            Token::LParen => {
+                brackets.push(Bracket::Parenthesis);
+                TokenClass::Punctuation
+            }
+            Token::LBracket => {
+                // A bracket after a `-` holds a relationship pattern
+                brackets.push(if previous == Some(&Token::Minus) {
+                    Bracket::Relationship
+                } else {
+                    Bracket::List
+                });
+                TokenClass::Punctuation
+            }
+            Token::LBrace => {
+                brackets.push(Bracket::Map);
+                TokenClass::Punctuation
+            }
+            Token::RParen | Token::RBracket | Token::RBrace => {
+                brackets.pop();
+                TokenClass::Punctuation
+            }
+            Token::Comma | Token::Period | Token::Colon | Token::SemiColon => {
+                TokenClass::Punctuation
+            }
+            _ => TokenClass::Operator,
+        };
+        if !matches!(class, TokenClass::Whitespace | TokenClass::Comment) && *token != Token::Pipe {
+            previous_class = Some(class);
+        }
+        classified.push(ClassifiedToken {
+            class,
+            span: start..end,
+        });
+    }
+
+    let end = classified.last().map_or(0, |last| last.span.end);
+    if end < input.len() {
+        classified.push(ClassifiedToken {
+            class: TokenClass::Whitespace,
+            span: end..input.len(),
+        });
+    }
+    Ok(classified)
+}
+
+/// The class of a name that no `.`, `:` or `|` before it classifies
+fn name_class(keyword: Keyword, quote_style: Option<char>, next: Option<&Token>) -> TokenClass {
+    if quote_style.is_some() {
+        return TokenClass::Variable;
+    }
+    if CYPHER_RESERVED.contains(&keyword) {
+        return TokenClass::Keyword;
+    }
+    // A clause keyword followed by what follows a name is a name, e.g.
+    // `order` in `(order:Order)` or `RETURN n.skip AS limit`
+    let used_as_name = matches!(
+        next,
+        None | Some(
+            Token::Period
+                | Token::Colon
+                | Token::RParen
+                | Token::RBracket
+                | Token::RBrace
+                | Token::Comma
+                | Token::SemiColon
+        )
+    );
+    if CLAUSE_KEYWORDS.contains(&keyword) && !used_as_name {
+        TokenClass::Keyword
+    } else if next == Some(&Token::LParen) {
+        TokenClass::Function
+    } else {
+        TokenClass::Variable
+    }
+}
+
+/// The kind of an open bracket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bracket {
+    /// A node pattern or a parenthesized expression or argument list
+    Parenthesis,
+    /// The brackets of a relationship pattern, e.g. `-[r:KNOWS]->`
+    Relationship,
+    /// A list, comprehension or subscript
+    List,
+    /// A map or the properties of a pattern
+    Map,
+}
+
+/// Converts the line and column locations of the tokenizer, which count
+/// characters, to byte offsets
+struct ByteOffsets<'a> {
+    input: &'a str,
+    /// The location and byte offset last converted, since the locations of
+    /// tokens only grow
+    location: Location,
+    offset: usize,
+}
+
+impl<'a> ByteOffsets<'a> {
+    fn new(input: &'a str) -> Self {
+        ByteOffsets {
+            input,
+            location: Location::new(1, 1),
+            offset: 0,
+        }
+    }
+
+    /// The byte offset of `location`, or of the end of the text past it
+    fn offset(&mut self, location: Location) -> usize {
+        if (location.line, location.column) < (self.location.line, self.location.column) {
+            self.location = Location::new(1, 1);
+            self.offset = 0;
+        }
+        for ch in self.input[self.offset..].chars() {
+            if (self.location.line, self.location.column) >= (location.line, location.column) {
+                break;
+            }
+            if ch == '\n' {
+                self.location = Location::new(self.location.line + 1, 1);
+            } else {
+                self.location.column += 1;
+            }
+            self.offset += ch.len_utf8();
+        }
+        self.offset
+    }
+}
@@ -0,0 +1,255 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Parsing of incomplete or erroneous Cypher, e.g. a query being typed in an
+//! editor, into the statement it most likely is
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+
+use crate::ast::CypherStatement;
+use crate::keywords::Keyword;
+use crate::parser::{Parser, ParserError, EMPTY_CYPHER_STATEMENT};
+use crate::tokenizer::{Span, Token, TokenWithSpan, Tokenizer};
+
+/// The keywords starting a clause, where parsing resumes after an error
+const CLAUSE_KEYWORDS: &[Keyword] = &[
+    Keyword::CREATE,
+    Keyword::DELETE,
+    Keyword::DETACH,
+    Keyword::LIMIT,
+    Keyword::MATCH,
+    Keyword::MERGE,
+    Keyword::OPTIONAL,
+    Keyword::ORDER,
+    Keyword::RETURN,
+    Keyword::SET,
+    Keyword::SKIP,
+    Keyword::UNION,
+    Keyword::UNWIND,
+    Keyword::WHERE,
+    Keyword::WITH,
+];
+
+/// The placeholder standing in for a missing expression, which Cypher,
+/// having `$name` parameters, does not use
+const MISSING_EXPRESSION: &str = "?";
+
+impl<'a> Parser<'a> {
+    /// Tokenize `cypher` for [`Parser::parse_cypher_statement_recovering`],
+    /// keeping the tokens before an error tokenizing it, e.g. an unterminated
+    /// string, which is returned
+    pub fn with_cypher_recovering(self, cypher: &str) -> (Self, Option<ParserError>) {
+        let mut tokens = vec![];
+        let error = Tokenizer::new(self.dialect, cypher)
+            .with_unescape(self.options.unescape)
+            .tokenize_with_location_into_buf(&mut tokens)
+            .err()
+            .map(ParserError::from);
+        (self.with_tokens_with_locations(tokens), error)
+    }
+
+    /// Parse a Cypher statement, recovering from errors in it to parse as
+    /// much of it as possible, e.g. to complete a query being typed
+    ///
+    /// At an error, the element being parsed is closed, with the `?`
+    /// placeholder for a missing expression, and parsing resumes at the next
+    /// clause, e.g. `MATCH (n:Per RETURN` is read as `MATCH (n:Per) RETURN ?`.
+    /// A statement parsed with errors is a [`CypherStatement::Recovered`],
+    /// which the transformer rejects; the statement is `None` when nothing
+    /// of it could be parsed. The statement ends at a `;`, which is left for
+    /// the next statement.
+    ///
+    /// ```
+    /// # use sqlparser::ast::CypherStatement;
+    /// # use sqlparser::dialect::CypherDialect;
+    /// # use sqlparser::parser::Parser;
+    /// let mut parser = Parser::new(&CypherDialect).try_with_sql("MATCH (n:Per")?;
+    /// let (statement, errors) = parser.parse_cypher_statement_recovering();
+    /// let Some(CypherStatement::Recovered { statement }) = statement else {
+    ///     panic!("expected a recovered statement");
+    /// };
+    /// assert_eq!(statement.to_string(), "MATCH (n:Per)");
+    /// assert_eq!(errors.len(), 1);
+    /// # Ok::<(), sqlparser::parser::ParserError>(())
+    /// ```
+    pub fn parse_cypher_statement_recovering(
+        &mut self,
+    ) -> (Option<CypherStatement>, Vec<ParserError>) {
+        while self.consume_token(&Token::SemiColon) {}
+        let mut tokens = vec![];
+        loop {
+            let token = self.peek_token();
+            if matches!(token.token, Token::SemiColon | Token::EOF) {
+                break;
+            }
+            tokens.push(token);
+            self.next_token();
+        }
+        if tokens.is_empty() {
+            let error = ParserError::ParserError(EMPTY_CYPHER_STATEMENT.to_string());
+            return (None, vec![error]);
+        }
+
+        let mut errors: Vec<ParserError> = vec![];
+        // A repair can add tokens as well as skip them, so the repairs are
+        // bounded by the tokens there are to skip
+        for _ in 0..2 * tokens.len() + 2 {
+            let (error, index) = match self.parse_cypher_tokens(&tokens) {
+                Ok(statement) if errors.is_empty() => return (Some(statement), errors),
+                Ok(statement) => {
+                    let statement = Box::new(statement);
+                    return (Some(CypherStatement::Recovered { statement }), errors);
+                }
+                Err(failure) => failure,
+            };
+            if !errors.contains(&error) {
+                errors.push(error);
+            }
+            tokens = match self.repair_cypher_tokens(&tokens, index) {
+                Some(repaired) => repaired,
+                // Without a repair getting further, drop the tokens from the
+                // error on
+                None => tokens[..index.min(tokens.len()).saturating_sub(1)].to_vec(),
+            };
+            if tokens.is_empty() {
+                break;
+            }
+        }
+        (None, errors)
+    }
+
+    /// Parse `tokens` as a whole statement, or the error and the index of
+    /// the token where parsing failed
+    fn parse_cypher_tokens(
+        &self,
+        tokens: &[TokenWithSpan],
+    ) -> Result<CypherStatement, (ParserError, usize)> {
+        let mut parser = Parser::new(self.dialect)
+            .with_options(self.options.clone())
+            .with_tokens_with_locations(tokens.to_vec());
+        let statement = parser
+            .parse_cypher_statement()
+            .map_err(|error| (error, parser.index))?;
+        if parser.peek_token_ref().token != Token::EOF {
+            let error = parser.expected::<()>("end of statement", parser.peek_token());
+            return Err((error.unwrap_err(), parser.index));
+        }
+        Ok(statement)
+    }
+
+    /// The repair of `tokens` for an error at `index` keeping most of them
+    /// that parses, or else parses past the tokens it adds, preferring one
+    /// that parses and then one parsing further
+    ///
+    /// A repair keeps the tokens before the error, or before the token
+    /// before it, which parsing may have taken, closes the brackets they
+    /// open, with or without a placeholder expression before, and resumes
+    /// at a clause keyword at or after the error, or drops the rest.
+    fn repair_cypher_tokens(
+        &self,
+        tokens: &[TokenWithSpan],
+        index: usize,
+    ) -> Option<Vec<TokenWithSpan>> {
+        let index = index.min(tokens.len());
+        let clause_at = |start: usize| {
+            (start..tokens.len())
+                .find(|&i| is_clause_keyword(&tokens[i].token))
+                .unwrap_or(tokens.len())
+        };
+        let mut resumes = vec![clause_at(index), clause_at(index + 1), tokens.len()];
+        resumes.dedup();
+        let mut prefix_ends = vec![index];
+        prefix_ends.extend(index.checked_sub(1));
+
+        // The tokens kept, whether the repair parses and how far past the
+        // tokens it adds
+        let mut best: Option<((usize, bool, usize), Vec<TokenWithSpan>)> = None;
+        for &resume in &resumes {
+            for &prefix_end in &prefix_ends {
+                let prefix = &tokens[..prefix_end];
+                let location = prefix.last().map_or(Span::empty(), |token| {
+                    Span::new(token.span.end, token.span.end)
+                });
+                let closers = closing_brackets(prefix);
+                let placeholder = Token::Placeholder(MISSING_EXPRESSION.to_string());
+                for middle in [closers.clone(), [vec![placeholder], closers].concat()] {
+                    let repaired: Vec<TokenWithSpan> = prefix
+                        .iter()
+                        .cloned()
+                        .chain(
+                            middle
+                                .iter()
+                                .map(|token| TokenWithSpan::new(token.clone(), location)),
+                        )
+                        .chain(tokens[resume..].iter().cloned())
+                        .collect();
+                    if repaired == tokens {
+                        continue;
+                    }
+                    let kept = prefix_end + tokens.len() - resume;
+                    let rest_start = prefix_end + middle.len();
+                    let score = match self.parse_cypher_tokens(&repaired) {
+                        Ok(_) => (kept, true, 0),
+                        Err((_, failed)) if failed > rest_start => {
+                            (kept, false, failed - rest_start)
+                        }
+                        Err(_) => continue,
+                    };
+                    if best.as_ref().is_none_or(|(most, _)| score > *most) {
+                        best = Some((score, repaired));
+                    }
+                }
+            }
+        }
+        best.map(|(_, repaired)| repaired)
+    }
+}
+
+/// Whether `token` is the keyword of a clause
+fn is_clause_keyword(token: &Token) -> bool {
+    matches!(token, Token::Word(word) if CLAUSE_KEYWORDS.contains(&word.keyword))
+}
+
+/// The tokens closing the brackets, braces and parentheses `tokens` leave
+/// open, innermost first, with a node after a relationship pattern, which
+/// it needs to end
+fn closing_brackets(tokens: &[TokenWithSpan]) -> Vec<Token> {
+    let mut open: Vec<Vec<Token>> = vec![];
+    let mut previous = None;
+    for token in tokens {
+        match token.token {
+            Token::LParen => open.push(vec![Token::RParen]),
+            Token::LBracket if previous == Some(&Token::Minus) => open.push(vec![
+                Token::RBracket,
+                Token::Minus,
+                Token::LParen,
+                Token::RParen,
+            ]),
+            Token::LBracket => open.push(vec![Token::RBracket]),
+            Token::LBrace => open.push(vec![Token::RBrace]),
+            Token::RParen | Token::RBracket | Token::RBrace
+                if open.last().map(|closers| &closers[0]) == Some(&token.token) =>
+            {
+                open.pop();
+            }
+            _ => {}
+        }
+        previous = Some(&token.token);
+    }
+    open.into_iter().rev().flatten().collect()
+}
@@ -0,0 +1,387 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Streaming parser of scripts of `;` separated Cypher statements
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::VecDeque,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+use crate::ast::cypher::{CypherDirective, CypherStatement};
+use crate::dialect::CypherDialect;
+use crate::parser::{Parser, ParserError};
+use crate::tokenizer::{Location, Span, Token, TokenWithSpan, Tokenizer};
+
+/// Parses a script of `;` separated Cypher statements one statement at a
+/// time, reading the script in chunks of text such as its lines
+///
+/// Only the text of the statements read but not yet returned is held, and
+/// each statement is tokenized on its own, so the memory used does not grow
+/// with the length of the script. Semicolons in strings, quoted names and
+/// comments do not end a statement. The locations in errors and in the spans
+/// of the statements are those in the whole script.
+///
+/// A line starting with `:` where a statement could start, as in the scripts
+/// of Neo4j Browser and cypher-shell, is a [`CypherDirective`] that ends with
+/// the line, e.g. `:param minAge => 21;`. Iterating skips directives, and
+/// [`CypherStatementIterator::next_item`] returns them along with the
+/// statements.
+///
+/// ```
+/// # use sqlparser::parser::CypherStatementIterator;
+/// let script = "CREATE (:Person {name: 'Ada'});\nMATCH (n) RETURN n;\n";
+/// let mut statements = CypherStatementIterator::new(script.split_inclusive('\n'));
+/// assert!(statements.next().unwrap().is_ok());
+/// assert_eq!(statements.statement_text(), "CREATE (:Person {name: 'Ada'})");
+/// assert_eq!(statements.count(), 1);
+/// ```
+pub struct CypherStatementIterator<I> {
+    chunks: I,
+    splitter: StatementSplitter,
+    /// The statements split from the chunks read so far, with the locations
+    /// they start at and whether they are directives
+    pending: VecDeque<(Location, String, bool)>,
+    /// The text of the statement last returned
+    text: String,
+}
+
+impl<I, S> CypherStatementIterator<I>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    /// An iterator over the statements of the script made of `chunks`, which
+    /// keep their line breaks
+    pub fn new(chunks: I) -> Self {
+        CypherStatementIterator {
+            chunks,
+            splitter: StatementSplitter::default(),
+            pending: VecDeque::new(),
+            text: String::new(),
+        }
+    }
+
+    /// The text of the statement or directive last returned, without its `;`
+    /// and the whitespace around it
+    pub fn statement_text(&self) -> &str {
+        let text = self.text.trim();
+        text.strip_suffix(';').unwrap_or(text).trim_end()
+    }
+
+    /// The next statement or directive of the script
+    ///
+    /// ```
+    /// # use sqlparser::ast::CypherDirective;
+    /// # use sqlparser::parser::{CypherScriptItem, CypherStatementIterator};
+    /// let script = ":param name => 'Ada';\nMATCH (n {name: $name}) RETURN n;\n";
+    /// let mut items = CypherStatementIterator::new(script.split_inclusive('\n'));
+    /// let Some(Ok(CypherScriptItem::Directive(CypherDirective::Param { name, value }))) =
+    ///     items.next_item()
+    /// else {
+    ///     panic!("expected a :param directive");
+    /// };
+    /// assert_eq!((name.value.as_str(), value.to_string().as_str()), ("name", "'Ada'"));
+    /// assert_eq!(items.statement_text(), ":param name => 'Ada'");
+    /// assert!(matches!(items.next_item(), Some(Ok(CypherScriptItem::Statement(_)))));
+    /// assert!(items.next_item().is_none());
+    /// ```
+    pub fn next_item(&mut self) -> Option<Result<CypherScriptItem, ParserError>> {
+        loop {
+            if let Some((start, text, directive)) = self.pending.pop_front() {
+                let item = if directive {
+                    Some(parse_directive(&text, start).map(CypherScriptItem::Directive))
+                } else {
+                    parse_statement(&text, start)
+                        .map(|statement| statement.map(CypherScriptItem::Statement))
+                        .transpose()
+                };
+                self.text = text;
+                // Text holding nothing but comments is not a statement
+                match item {
+                    Some(item) => return Some(item),
+                    None => continue,
+                }
+            }
+            match self.chunks.next() {
+                Some(chunk) => self.splitter.push(chunk.as_ref(), &mut self.pending),
+                None => self.pending.push_back(self.splitter.finish()?),
+            }
+        }
+    }
+}
+
+/// A statement or directive of a script, as returned by
+/// [`CypherStatementIterator::next_item`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CypherScriptItem {
+    Statement(CypherStatement),
+    Directive(CypherDirective),
+}
+
+impl<I, S> Iterator for CypherStatementIterator<I>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    type Item = Result<CypherStatement, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_item()? {
+                Ok(CypherScriptItem::Statement(statement)) => return Some(Ok(statement)),
+                Ok(CypherScriptItem::Directive(_)) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Parse the statement `text` that starts at `start` in its script, or
+/// return `None` when it holds no tokens but whitespace and comments
+fn parse_statement(text: &str, start: Location) -> Result<Option<CypherStatement>, ParserError> {
+    let tokens = tokenize(text, start)?;
+    if tokens
+        .iter()
+        .all(|token| matches!(token.token, Token::Whitespace(_)))
+    {
+        return Ok(None);
+    }
+
+    let mut parser = Parser::new(&CypherDialect).with_tokens_with_locations(tokens);
+    let statement = parser.parse_cypher_statement()?;
+    if parser.peek_token().token != Token::EOF {
+        return parser.expected("end of statement", parser.peek_token());
+    }
+    Ok(Some(statement))
+}
+
+/// Parse the directive `text`, which starts with its `:` at `start` in its
+/// script
+///
+/// The arguments of directives other than `:param` and `:use` are kept as
+/// written, since they need not be made of Cypher tokens, e.g. the address
+/// of `:play`.
+fn parse_directive(text: &str, start: Location) -> Result<CypherDirective, ParserError> {
+    let line = text.trim_end();
+    let line = line.strip_suffix(';').unwrap_or(line);
+    let (name, arguments) = line[1..]
+        .split_once(char::is_whitespace)
+        .unwrap_or((&line[1..], ""));
+    let directive = match name.to_ascii_lowercase().as_str() {
+        "param" | "use" => {
+            let mut parser =
+                Parser::new(&CypherDialect).with_tokens_with_locations(tokenize(line, start)?);
+            parser.expect_token(&Token::Colon)?;
+            parser.parse_identifier()?;
+            let directive = if name.eq_ignore_ascii_case("param") {
+                let name = parser.parse_identifier()?;
+                parser.expect_token(&Token::RArrow)?;
+                CypherDirective::Param {
+                    name,
+                    value: parser.parse_expr()?,
+                }
+            } else {
+                CypherDirective::Use(parser.parse_identifier()?)
+            };
+            if parser.peek_token().token != Token::EOF {
+                return parser.expected("end of directive", parser.peek_token());
+            }
+            directive
+        }
+        "begin" => CypherDirective::Begin,
+        "commit" => CypherDirective::Commit,
+        "rollback" => CypherDirective::Rollback,
+        _ => CypherDirective::Other {
+            name: name.to_string(),
+            arguments: arguments.trim().to_string(),
+        },
+    };
+    Ok(directive)
+}
+
+/// Tokenize `text`, which starts at `start` in its script, with the locations
+/// of the tokens in the script
+fn tokenize(text: &str, start: Location) -> Result<Vec<TokenWithSpan>, ParserError> {
+    let shift = |location: Location| {
+        if location.line == 0 {
+            // An empty span
+            location
+        } else if location.line == 1 {
+            Location::new(start.line, start.column + location.column - 1)
+        } else {
+            Location::new(start.line + location.line - 1, location.column)
+        }
+    };
+    let mut tokens = Tokenizer::new(&CypherDialect, text)
+        .tokenize_with_location()
+        .map_err(|mut error| {
+            error.location = shift(error.location);
+            error
+        })?;
+    for token in &mut tokens {
+        token.span = Span::new(shift(token.span.start), shift(token.span.end));
+    }
+    Ok(tokens)
+}
+
+/// Splits text into statements at the semicolons outside strings, quoted
+/// names and comments, following the tokenizer
+#[derive(Debug)]
+struct StatementSplitter {
+    /// The text of the statement being read
+    current: String,
+    /// The location `current` starts at
+    start: Location,
+    /// The location of the next character
+    end: Location,
+    state: SplitState,
+    /// The previous character, when it may start or end a comment
+    previous: Option<char>,
+    /// Whether `current` holds more than whitespace and comments, after which
+    /// a `:` does not start a directive
+    code: bool,
+}
+
+impl Default for StatementSplitter {
+    fn default() -> Self {
+        StatementSplitter {
+            current: String::new(),
+            start: Location::new(1, 1),
+            end: Location::new(1, 1),
+            state: SplitState::Code,
+            previous: None,
+            code: false,
+        }
+    }
+}
+
+/// What the character a [`StatementSplitter`] reads belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SplitState {
+    Code,
+    /// A string or name in the quote
+    Quoted(char),
+    /// The character after a backslash in a string in the quote
+    Escaped(char),
+    /// Just after `--`, which starts a comment if a space follows, as in
+    /// the tokenizer of the [`CypherDialect`]
+    Dashes,
+    /// A `--` comment
+    LineComment,
+    /// A `/* ... */` comment
+    BlockComment,
+    /// A directive, which ends with its line
+    Directive,
+}
+
+impl StatementSplitter {
+    /// Read `text`, adding the statements it ends to `statements`
+    fn push(&mut self, text: &str, statements: &mut VecDeque<(Location, String, bool)>) {
+        for ch in text.chars() {
+            let mut previous = self.previous.take();
+            if self.state == SplitState::Dashes {
+                if ch == ' ' {
+                    self.state = SplitState::LineComment;
+                } else {
+                    // The second dash may start `--` with the next one
+                    self.state = SplitState::Code;
+                    previous = Some('-');
+                }
+            }
+            // A character that may start a comment is not taken for code
+            let comment = matches!(ch, '-' | '/') || (ch == '*' && previous == Some('/'));
+            let code = self.state == SplitState::Code && !ch.is_whitespace() && !comment;
+            match (self.state, ch) {
+                (SplitState::Code, ';') => {
+                    self.advance(ch);
+                    self.split(false, statements);
+                    continue;
+                }
+                (SplitState::Code, ':') if !self.code => {
+                    // The comments before the directive are a statement of
+                    // their own, which is skipped
+                    if !self.current.is_empty() {
+                        self.split(false, statements);
+                    }
+                    self.state = SplitState::Directive;
+                }
+                (SplitState::Directive, '\n') => {
+                    self.current.push(ch);
+                    self.advance(ch);
+                    self.state = SplitState::Code;
+                    self.split(true, statements);
+                    continue;
+                }
+                (SplitState::Code, '\'' | '"' | '`') => self.state = SplitState::Quoted(ch),
+                (SplitState::Code, '-') if previous == Some('-') => self.state = SplitState::Dashes,
+                (SplitState::Code, '*') if previous == Some('/') => {
+                    self.state = SplitState::BlockComment
+                }
+                (SplitState::Code, '-' | '/') | (SplitState::BlockComment, '*') => {
+                    self.previous = Some(ch)
+                }
+                (SplitState::Quoted(quote), '\\') if quote != '`' => {
+                    self.state = SplitState::Escaped(quote)
+                }
+                (SplitState::Escaped(quote), _) => self.state = SplitState::Quoted(quote),
+                (SplitState::Quoted(quote), _) if ch == quote => self.state = SplitState::Code,
+                (SplitState::LineComment, '\n') => self.state = SplitState::Code,
+                (SplitState::BlockComment, '/') if previous == Some('*') => {
+                    self.state = SplitState::Code
+                }
+                _ => {}
+            }
+            self.code |= code;
+            self.current.push(ch);
+            self.advance(ch);
+        }
+    }
+
+    /// End the statement or directive read so far
+    fn split(&mut self, directive: bool, statements: &mut VecDeque<(Location, String, bool)>) {
+        let statement = core::mem::take(&mut self.current);
+        statements.push_back((self.start, statement, directive));
+        self.start = self.end;
+        self.code = false;
+    }
+
+    /// The statement or directive left at the end of the script without a
+    /// `;` or line break, if any
+    fn finish(&mut self) -> Option<(Location, String, bool)> {
+        let statement = core::mem::take(&mut self.current);
+        let start = self.start;
+        let directive = self.state == SplitState::Directive;
+        self.start = self.end;
+        self.state = SplitState::Code;
+        self.code = false;
+        (!statement.trim().is_empty()).then_some((start, statement, directive))
+    }
+
+    /// Move past `ch`, counting lines and columns as the tokenizer does
+    fn advance(&mut self, ch: char) {
+        if ch == '\n' {
+            self.end = Location::new(self.end.line + 1, 1);
+        } else {
+            self.end.column += 1;
+        }
+    }
+}
@@ -47,6 +47,13 @@ use sqlparser::parser::ParserState::ColumnDefinition;
 
 mod alter;
 mod cypher;
+pub(crate) use cypher::EMPTY_CYPHER_STATEMENT;
+mod cypher_highlight;
+mod cypher_recovery;
+mod cypher_script;
+
+pub use cypher_highlight::{classify_cypher_tokens, ClassifiedToken, TokenClass};
+pub use cypher_script::{CypherScriptItem, CypherStatementIterator};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParserError {
@@ -13791,6 +13798,7 @@ impl<'a> Parser<'a> {
                         | TableFactor::Unpivot { alias, .. }
                         | TableFactor::MatchRecognize { alias, .. }
                         | TableFactor::SemanticView { alias, .. }
+                        | TableFactor::GraphTable { alias, .. }
                         | TableFactor::NestedJoin { alias, .. } => {
                             // but not `FROM (mytable AS alias1) AS alias2`.
                             if let Some(inner_alias) = alias {
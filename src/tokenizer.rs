@@ -42,7 +42,7 @@ use sqlparser_derive::{Visit, VisitMut};
 
 use crate::dialect::Dialect;
 use crate::dialect::{
-    BigQueryDialect, DuckDbDialect, GenericDialect, MySqlDialect, PostgreSqlDialect,
+    BigQueryDialect, CypherDialect, DuckDbDialect, GenericDialect, MySqlDialect, PostgreSqlDialect,
     SnowflakeDialect,
 };
 use crate::keywords::{Keyword, ALL_KEYWORDS, ALL_KEYWORDS_INDEX};
@@ -1207,6 +1207,21 @@ impl<'a> Tokenizer<'a> {
                         );
                     }
 
+                    // The `..` of a Cypher range, e.g. `*1..3` or `*..3`, is two
+                    // periods rather than part of a number
+                    let cypher_range = |chars: &State| {
+                        dialect_of!(self is CypherDialect)
+                            && chars.peekable.clone().nth(1) == Some('.')
+                    };
+                    if ch == '.'
+                        && (cypher_range(chars)
+                            || (dialect_of!(self is CypherDialect)
+                                && prev_token == Some(&Token::Period)))
+                    {
+                        chars.next();
+                        return Ok(Some(Token::Period));
+                    }
+
                     // Some dialects support underscore as number separator
                     // There can only be one at a time and it must be followed by another digit
                     let is_number_separator = |ch: char, next_char: Option<char>| {
@@ -1219,6 +1234,30 @@ impl<'a> Tokenizer<'a> {
                         ch.is_ascii_digit() || is_number_separator(ch, next_ch)
                     });
 
+                    // Cypher hexadecimal (0x) and octal (0o) integers, which
+                    // are normalized to decimal as few SQL targets accept them
+                    if s == "0" && dialect_of!(self is CypherDialect) {
+                        let radix = match chars.peek() {
+                            Some('x') => 16,
+                            Some('o') => 8,
+                            _ => 10,
+                        };
+                        if radix != 10 {
+                            let literal_loc = chars.location();
+                            chars.next();
+                            let digits = peeking_take_while(chars, |ch| ch.is_digit(radix));
+                            return match u64::from_str_radix(&digits, radix) {
+                                Ok(n) => Ok(Some(Token::Number(n.to_string(), false))),
+                                Err(_) if digits.is_empty() => self.tokenizer_error(
+                                    literal_loc,
+                                    "Expected digits after radix prefix",
+                                ),
+                                Err(_) => self
+                                    .tokenizer_error(literal_loc, "Integer literal out of range"),
+                            };
+                        }
+                    }
+
                     // match binary literal that starts with 0x
                     if s == "0" && chars.peek() == Some(&'x') {
                         chars.next();
@@ -1230,6 +1269,9 @@ impl<'a> Tokenizer<'a> {
 
                     // match one period
                     if let Some('.') = chars.peek() {
+                        if cypher_range(chars) {
+                            return Ok(Some(Token::Number(s, false)));
+                        }
                         s.push('.');
                         chars.next();
                     }
@@ -1703,6 +1745,12 @@ impl<'a> Tokenizer<'a> {
                 ch if ch.is_whitespace() => {
                     self.consume_and_return(chars, Token::Whitespace(Whitespace::Space))
                 }
+                // openCypher also counts the information separators, the
+                // Mongolian vowel separator, the zero width space and the byte
+                // order mark as whitespace
+                '\u{1C}'..='\u{1F}' | '\u{180E}' | '\u{200B}' | '\u{FEFF}' if dialect_of!(self is CypherDialect) => {
+                    self.consume_and_return(chars, Token::Whitespace(Whitespace::Space))
+                }
                 other => self.consume_and_return(chars, Token::Char(other)),
             },
             None => Ok(None),
@@ -2068,6 +2116,29 @@ impl<'a> Tokenizer<'a> {
                             s.push(ch);
                             s.push(*next);
                             chars.next(); // consume next
+                        } else if matches!(next, 'u' | 'U') && dialect_of!(self is CypherDialect) {
+                            // Cypher's `\uXXXX` and `\UXXXXXXXX` code point escapes
+                            let len = if *next == 'u' { 4 } else { 8 };
+                            let escape_loc = chars.location();
+                            chars.next(); // consume u
+                            let mut digits = 0;
+                            let hex = peeking_take_while(chars, |ch| {
+                                digits += 1;
+                                digits <= len && ch.is_ascii_hexdigit()
+                            });
+                            let code_point = u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .filter(|_| hex.len() == len)
+                                .and_then(char::from_u32);
+                            match code_point {
+                                Some(code_point) => s.push(code_point),
+                                None => {
+                                    return self.tokenizer_error(
+                                        escape_loc,
+                                        "Invalid Unicode escape sequence",
+                                    )
+                                }
+                            }
                         } else {
                             let n = match next {
                                 '0' => '\0',
@@ -0,0 +1,228 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Which Cypher constructs a configuration of the transformer translates
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dialect::CypherDialect;
+use crate::parser::Parser;
+
+use super::{
+    parse_cypher_query, CypherToSqlTransformer, TransformConfig, TransformError, TransformOutput,
+};
+
+/// The constructs [`capabilities`] reports on, each with the query whose
+/// transformation shows how well it is translated
+pub const CONSTRUCTS: &[(&str, &str)] = &[
+    ("MATCH", "MATCH (n:Person) RETURN n.name"),
+    ("WHERE", "MATCH (n:Person) WHERE n.age > 30 RETURN n.name"),
+    (
+        "STARTS WITH, ENDS WITH and CONTAINS",
+        "MATCH (n:Person) WHERE n.name STARTS WITH 'A' RETURN n.name",
+    ),
+    (
+        "exists()",
+        "MATCH (n:Person) WHERE exists((n)-[:KNOWS]->()) RETURN n.name",
+    ),
+    (
+        "OPTIONAL MATCH",
+        "MATCH (a:Person) OPTIONAL MATCH (a)-[:KNOWS]->(b) RETURN a.name, b.name",
+    ),
+    (
+        "relationship",
+        "MATCH (a:Person)-[:KNOWS]->(b:Person) RETURN a.name, b.name",
+    ),
+    (
+        "undirected relationship",
+        "MATCH (a:Person)-[:KNOWS]-(b:Person) RETURN a.name, b.name",
+    ),
+    (
+        "variable-length relationship",
+        "MATCH (a:Person)-[:KNOWS*1..3]->(b:Person) RETURN b.name",
+    ),
+    (
+        "aggregation",
+        "MATCH (n:Person) RETURN n.city, count(*) AS people",
+    ),
+    (
+        "ORDER BY, SKIP and LIMIT",
+        "MATCH (n:Person) RETURN n.name ORDER BY n.name SKIP 1 LIMIT 10",
+    ),
+    ("RETURN DISTINCT", "MATCH (n:Person) RETURN DISTINCT n.city"),
+    (
+        "WITH",
+        "MATCH (n:Person) WITH n.city AS city, count(*) AS people WHERE people > 1 RETURN city",
+    ),
+    ("UNWIND", "UNWIND [1, 2, 3] AS x RETURN x"),
+    (
+        "UNION",
+        "MATCH (a:Person) RETURN a.name AS name UNION MATCH (c:Company) RETURN c.name AS name",
+    ),
+    (
+        "CASE",
+        "MATCH (n:Person) RETURN CASE WHEN n.age > 30 THEN 'old' ELSE 'young' END",
+    ),
+    (
+        "list comprehension",
+        "MATCH (n:Person) RETURN [x IN n.scores WHERE x > 1 | x * 2]",
+    ),
+    (
+        "pattern comprehension",
+        "MATCH (n:Person) RETURN [(n)-[:KNOWS]->(f) | f.name]",
+    ),
+    ("CREATE node", "CREATE (n:Person {name: 'Ada'})"),
+    (
+        "CREATE relationship",
+        "CREATE (a:Person {name: 'Ada'})-[:KNOWS]->(b:Person {name: 'Bob'})",
+    ),
+    (
+        "MATCH ... CREATE",
+        "MATCH (a:Person), (b:Person) WHERE a.name = 'Ada' AND b.name = 'Bob' \
+         CREATE (a)-[:KNOWS]->(b)",
+    ),
+    (
+        "MATCH ... CREATE of a linked node",
+        "MATCH (a:Person) WHERE a.name = 'Ada' CREATE (a)-[:OWNS]->(c:Car {make: 'VW'})",
+    ),
+    ("MERGE node", "MERGE (n:Person {name: 'Ada'})"),
+    (
+        "MERGE relationship",
+        "MERGE (a:Person {name: 'Ada'})-[:KNOWS]->(b:Person {name: 'Bob'})",
+    ),
+    (
+        "SET",
+        "MATCH (n:Person) WHERE n.name = 'Ada' SET n.age = 36",
+    ),
+    (
+        "REMOVE",
+        "MATCH (n:Person) WHERE n.name = 'Ada' REMOVE n.age",
+    ),
+    ("DELETE", "MATCH (n:Person) WHERE n.name = 'Ada' DELETE n"),
+    (
+        "DETACH DELETE",
+        "MATCH (n:Person) WHERE n.name = 'Ada' DETACH DELETE n",
+    ),
+    ("CALL", "CALL db.labels()"),
+];
+
+/// How well a construct is translated
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Support {
+    /// Translated without warnings
+    Supported,
+    /// Translated with the warnings noted, e.g. of a part that is ignored
+    Partial(String),
+    /// Rejected, for the reason noted
+    Unsupported(String),
+}
+
+/// A construct of [`Capabilities`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Capability {
+    /// The construct, e.g. `OPTIONAL MATCH`
+    pub construct: String,
+    /// The query its support was found with
+    pub example: String,
+    pub support: Support,
+}
+
+/// How well each of the [`CONSTRUCTS`] is translated, as computed by
+/// [`capabilities`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Capabilities {
+    /// The constructs, in the order of [`CONSTRUCTS`]
+    pub constructs: Vec<Capability>,
+}
+
+impl Capabilities {
+    /// The support of `construct`, or `None` when it is not one of the
+    /// [`CONSTRUCTS`]
+    pub fn support(&self, construct: &str) -> Option<&Support> {
+        self.constructs
+            .iter()
+            .find(|capability| capability.construct == construct)
+            .map(|capability| &capability.support)
+    }
+}
+
+/// How well each of the [`CONSTRUCTS`] is translated under `config`, e.g.
+/// for a client to check a feature before sending queries using it
+///
+/// The support of a construct is that of the transformation of its example
+/// query, so the matrix is the transformer's own answer: a construct whose
+/// example is rejected, or is not valid Cypher to the parser, is
+/// [`Support::Unsupported`] with the error, and one translated with warnings
+/// is [`Support::Partial`] with them. Under [`TransformConfig::strict`], which
+/// rejects what would be warned about, no construct is partial.
+///
+/// ```
+/// # use sqlparser::transformer::*;
+/// let capabilities = capabilities(&TransformConfig::default());
+/// assert_eq!(capabilities.support("MATCH"), Some(&Support::Supported));
+/// assert!(matches!(
+///     capabilities.support("MERGE node"),
+///     Some(Support::Unsupported(_))
+/// ));
+/// ```
+pub fn capabilities(config: &TransformConfig) -> Capabilities {
+    let transformer = CypherToSqlTransformer::with_config(config.clone());
+    let constructs = CONSTRUCTS
+        .iter()
+        .map(|(construct, example)| Capability {
+            construct: construct.to_string(),
+            example: example.to_string(),
+            support: support(transform(&transformer, example)),
+        })
+        .collect();
+    Capabilities { constructs }
+}
+
+/// The support shown by the outcome of a transformation
+fn support(result: Result<TransformOutput, TransformError>) -> Support {
+    match result {
+        Ok(output) if output.warnings.is_empty() => Support::Supported,
+        Ok(output) => Support::Partial(
+            output
+                .warnings
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+        Err(e) => Support::Unsupported(e.to_string()),
+    }
+}
+
+fn transform(
+    transformer: &CypherToSqlTransformer,
+    cypher_query: &str,
+) -> Result<TransformOutput, TransformError> {
+    let parser = Parser::new(&CypherDialect).try_with_sql(cypher_query)?;
+    let statement = parse_cypher_query(parser, cypher_query, transformer.config.strict)?;
+    transformer.to_sql_output(&statement)
+}
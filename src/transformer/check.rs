@@ -0,0 +1,337 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Validation of Cypher queries without generating SQL
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::ast::cypher::{matched_variables, subexpressions};
+use crate::ast::{
+    CypherExpr, CypherStatement, Expr, Ident, Pattern, ReturnClause, SelectItem, SetTarget, Value,
+    ValueWithSpan,
+};
+use crate::dialect::CypherDialect;
+use crate::parser::{Parser, ParserError};
+use crate::tokenizer::Token;
+
+use super::{CypherToSqlTransformer, TransformWarning};
+
+/// What [`check_cypher`] found out about a valid query
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CypherCheck {
+    pub kind: StatementKind,
+    /// The variables the patterns bind and the `WITH` items introduce, in
+    /// the order they are bound
+    pub variables: Vec<String>,
+    /// The names of the `$parameters` the query uses, without the `$`, in
+    /// the order they first appear
+    pub parameters: Vec<String>,
+    /// The constructs the SQL of the query would ignore or approximate, and
+    /// its cross joins, as [`CypherToSqlTransformer::to_sql_output`] reports
+    /// them with the default configuration
+    pub warnings: Vec<TransformWarning>,
+}
+
+/// The kind of a Cypher statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StatementKind {
+    /// A reading query: `MATCH`, `WITH` or `RETURN`
+    Match,
+    Create,
+    Merge,
+    Delete,
+    Set,
+    Union,
+    Show,
+}
+
+/// Parse a Cypher query and check it, without generating SQL, e.g. to
+/// reject a malformed query before queueing work for it
+///
+/// The query must be a single statement, optionally followed by `;`. Besides
+/// parse errors, a variable used but not bound by a pattern or a `WITH`, or
+/// a variable deleted but not matched, is an error.
+///
+/// ```
+/// # use sqlparser::transformer::{check_cypher, StatementKind};
+/// let check = check_cypher("MATCH (n:Person) WHERE n.age > $age RETURN n.name")?;
+/// assert_eq!(check.kind, StatementKind::Match);
+/// assert_eq!(check.variables, ["n"]);
+/// assert_eq!(check.parameters, ["age"]);
+///
+/// let error = check_cypher("MATCH (n:Person) RETURN m.name").unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "sql parser error: variable `m` is not bound at Line: 1, Column: 25"
+/// );
+/// # Ok::<(), sqlparser::parser::ParserError>(())
+/// ```
+pub fn check_cypher(query: &str) -> Result<CypherCheck, ParserError> {
+    let statement = parse_single_statement(query)?;
+    let variables = bound_variables(&statement)?;
+    Ok(CypherCheck {
+        kind: statement_kind(&statement),
+        variables: variables.into_iter().map(|ident| ident.value).collect(),
+        parameters: parameters(&statement),
+        warnings: CypherToSqlTransformer::new().statement_warnings(&statement),
+    })
+}
+
+/// Parse `query` as a single statement, optionally followed by `;`
+pub(crate) fn parse_single_statement(query: &str) -> Result<CypherStatement, ParserError> {
+    let mut parser = Parser::new(&CypherDialect).try_with_sql(query)?;
+    let statement = parser.parse_cypher_statement()?;
+    while parser.consume_token(&Token::SemiColon) {}
+    if parser.peek_token_ref().token != Token::EOF {
+        return parser.expected("end of statement", parser.peek_token());
+    }
+    Ok(statement)
+}
+
+/// The variables `statement` binds, in the order they are bound, or an
+/// error at a variable used or deleted without being bound
+pub(crate) fn bound_variables(statement: &CypherStatement) -> Result<Vec<Ident>, ParserError> {
+    let mut variables = vec![];
+    check_scopes(statement, vec![], &mut variables)?;
+    Ok(variables)
+}
+
+fn statement_kind(statement: &CypherStatement) -> StatementKind {
+    match statement {
+        CypherStatement::Match { .. } => StatementKind::Match,
+        CypherStatement::Create { .. } => StatementKind::Create,
+        CypherStatement::Merge { .. } => StatementKind::Merge,
+        CypherStatement::Delete { .. } => StatementKind::Delete,
+        CypherStatement::Set { .. } => StatementKind::Set,
+        CypherStatement::Union { .. } => StatementKind::Union,
+        CypherStatement::Show { .. } => StatementKind::Show,
+        // The kind of the query run for each element
+        CypherStatement::Unwind { query, .. } => statement_kind(query),
+        CypherStatement::Recovered { statement } => statement_kind(statement),
+    }
+}
+
+/// Check that the expressions of `statement` only use variables in scope,
+/// starting with `scope`, adding the variables it binds to `bound`
+fn check_scopes(
+    statement: &CypherStatement,
+    mut scope: Vec<Ident>,
+    bound: &mut Vec<Ident>,
+) -> Result<(), ParserError> {
+    let patterns: Vec<&Pattern> = match statement {
+        CypherStatement::Match { patterns, .. }
+        | CypherStatement::Merge { patterns, .. }
+        | CypherStatement::Delete { patterns, .. }
+        | CypherStatement::Set { patterns, .. } => patterns.iter().collect(),
+        CypherStatement::Create {
+            r#match, patterns, ..
+        } => r#match.iter().chain(patterns).collect(),
+        CypherStatement::Union { queries, .. } => {
+            for query in queries {
+                check_scopes(query, vec![], bound)?;
+            }
+            return Ok(());
+        }
+        // SHOW yields columns rather than variables
+        CypherStatement::Show { .. } => return Ok(()),
+        CypherStatement::Unwind {
+            list,
+            variable,
+            query,
+        } => {
+            check_exprs([list], &scope)?;
+            bind(&mut scope, bound, [variable].into_iter());
+            return check_scopes(query, scope, bound);
+        }
+        CypherStatement::Recovered { statement } => return check_scopes(statement, scope, bound),
+    };
+    for pattern in patterns {
+        let variables = pattern.variable.iter();
+        let elements = matched_variables(core::slice::from_ref(pattern));
+        bind(&mut scope, bound, variables.chain(elements));
+    }
+
+    // The variables SET changes must be bound as well
+    if let CypherStatement::Set { items, .. } = statement {
+        let targets: Vec<Expr> = items
+            .iter()
+            .map(|item| match &item.target {
+                SetTarget::Property { variable, .. }
+                | SetTarget::Variable(variable)
+                | SetTarget::Label { variable, .. } => Expr::Identifier(variable.clone()),
+            })
+            .collect();
+        check_exprs(&targets, &scope)?;
+    }
+    let CypherStatement::Match {
+        patterns,
+        r#where,
+        with,
+        r#return,
+        next,
+        ..
+    } = statement
+    else {
+        return check_exprs(statement.exprs(), &scope);
+    };
+    check_exprs(patterns.iter().flat_map(Pattern::properties), &scope)?;
+    check_exprs(r#where, &scope)?;
+    if let Some(with) = with {
+        check_projection(&with.projection, &scope)?;
+        let items = &with.projection.items;
+        let mut projected = if items
+            .iter()
+            .any(|item| matches!(item, SelectItem::Wildcard(_)))
+        {
+            scope.clone()
+        } else {
+            vec![]
+        };
+        bind(&mut projected, bound, items.iter().filter_map(item_name));
+        check_exprs(&with.r#where, &projected)?;
+        scope = projected;
+    }
+    match (next, r#return) {
+        (Some(next), _) => check_scopes(next, scope, bound),
+        (None, Some(ret)) => check_projection(ret, &scope),
+        (None, None) => Ok(()),
+    }
+}
+
+/// Check the items and modifiers of a `WITH` or `RETURN`, whose ORDER BY
+/// may also sort on the columns of the items
+fn check_projection(projection: &ReturnClause, scope: &[Ident]) -> Result<(), ParserError> {
+    let items = projection.items.iter().filter_map(|item| match item {
+        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => Some(expr),
+        _ => None,
+    });
+    check_exprs(items, scope)?;
+    let mut columns = scope.to_vec();
+    columns.extend(projection.items.iter().filter_map(item_name).cloned());
+    check_exprs(projection.order_by.iter().map(|key| &key.expr), &columns)?;
+    check_exprs(projection.skip.iter().chain(&projection.limit), scope)
+}
+
+/// Add the `variables` missing from `scope` to it and to `bound`
+fn bind<'a>(
+    scope: &mut Vec<Ident>,
+    bound: &mut Vec<Ident>,
+    variables: impl Iterator<Item = &'a Ident>,
+) {
+    for variable in variables {
+        if !scope.iter().any(|ident| ident.value == variable.value) {
+            scope.push(variable.clone());
+        }
+        if !bound.iter().any(|ident| ident.value == variable.value) {
+            bound.push(variable.clone());
+        }
+    }
+}
+
+/// The name of the column of a `WITH` or `RETURN` item: its alias, or the
+/// variable it passes on
+fn item_name(item: &SelectItem) -> Option<&Ident> {
+    match item {
+        SelectItem::ExprWithAlias { alias, .. } => Some(alias),
+        SelectItem::UnnamedExpr(Expr::Identifier(variable)) => Some(variable),
+        _ => None,
+    }
+}
+
+/// Check that `exprs` only use the variables of `scope`
+fn check_exprs<'a>(
+    exprs: impl IntoIterator<Item = &'a Expr>,
+    scope: &[Ident],
+) -> Result<(), ParserError> {
+    // A stack of the expressions left, in reverse, to report the first
+    // unbound variable
+    let mut pending: Vec<&Expr> = exprs.into_iter().collect();
+    pending.reverse();
+    while let Some(expr) = pending.pop() {
+        let variable = match expr {
+            Expr::Identifier(variable) => Some(variable),
+            Expr::CompoundIdentifier(idents) => idents.first(),
+            _ => None,
+        };
+        if let Some(variable) = variable {
+            if !scope.iter().any(|ident| ident.value == variable.value) {
+                return Err(ParserError::ParserError(format!(
+                    "variable `{variable}` is not bound{}",
+                    variable.span.start
+                )));
+            }
+            continue;
+        }
+        // A pattern comprehension or predicate binds the variables of its
+        // pattern
+        if let Expr::Cypher(cypher_expr) = expr {
+            if let CypherExpr::PatternComprehension { pattern, .. }
+            | CypherExpr::PatternPredicate { pattern } = cypher_expr.as_ref()
+            {
+                let mut inner = scope.to_vec();
+                let variables = pattern.variable.iter();
+                bind(
+                    &mut inner,
+                    &mut vec![],
+                    variables.chain(matched_variables(core::slice::from_ref(pattern))),
+                );
+                check_exprs(subexpressions(expr), &inner)?;
+                continue;
+            }
+        }
+        pending.extend(subexpressions(expr).into_iter().rev());
+    }
+    Ok(())
+}
+
+/// The names of the parameters of `statement`, in the order they first
+/// appear in the query
+fn parameters(statement: &CypherStatement) -> Vec<String> {
+    let mut placeholders = vec![];
+    let mut pending = statement.exprs();
+    while let Some(expr) = pending.pop() {
+        if let Expr::Value(ValueWithSpan {
+            value: Value::Placeholder(placeholder),
+            span,
+        }) = expr
+        {
+            if let Some(name) = placeholder.strip_prefix('$') {
+                placeholders.push((span.start, name.to_string()));
+            }
+        }
+        pending.extend(subexpressions(expr));
+    }
+    placeholders.sort_by_key(|(start, _)| (start.line, start.column));
+    let mut names: Vec<String> = vec![];
+    for (_, name) in placeholders {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
@@ -17,15 +17,104 @@
 
 //! Cypher to SQL transformation implementation
 
+#[cfg(all(not(feature = "std"), target_has_atomic = "ptr"))]
+use alloc::sync::Arc;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+// Targets without atomic pointers, e.g. thumbv6m, have no `Arc`
+#[cfg(all(not(feature = "std"), not(target_has_atomic = "ptr")))]
+use alloc::rc::Rc as Arc;
+#[cfg(feature = "std")]
+use std::{collections::BTreeMap, sync::Arc};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::ast::cypher::*;
+use crate::ast::helpers::attached_token::AttachedToken;
 use crate::ast::*;
+use crate::parser::ParserError;
+
+use super::check::bound_variables;
+use super::format::{comment_text, format_statement_with_bindings, SqlFormat};
+use super::params::{bind_parameters, inline_parameters, ParamBinding, ParamStyle, ParamValue};
+use super::plan::{
+    as_label_predicate, split_conjunction, Binding, BindingKind, GraphPlan, PlanPath,
+};
+use super::quoting::{quote_identifiers, table_ident, IdentifierQuoting};
+use super::rewriter::{function_parts, FunctionRewriter, RewriteContext};
+use super::schema::{required_schema, SchemaRequirements};
+use super::{display_variable_groups, TransformError, TransformWarning, WarningKind};
 
 /// Transformer that converts Cypher statements to SQL statements
+///
+/// # Order of conditions
+///
+/// The SQL of a query depends only on its meaning and the order its
+/// variables first appear in, not on how its labels and types are spread
+/// over its patterns and WHERE clause, so that the SQL text can serve as a
+/// cache key. The WHERE clause of a MATCH has, in this order:
+///
+/// 1. for each node and relationship, in the order its variable first
+///    appears in the patterns, its label conditions or its type condition,
+///    with the labels and types in the order of their names; labels a
+///    top-level `WHERE n:Label` adds count as labels of the pattern
+/// 2. the conditions on relationship endpoints that cannot be join
+///    conditions, in the order of the patterns
+/// 3. the conditions that every two relationships differ, see
+///    [`TransformConfig::relationship_uniqueness`], in the order of the first
+///    and then of the second, and then those that the nodes of each pattern
+///    do under [`TransformConfig::distinct_nodes`]
+/// 4. the rest of the query's WHERE clause, as written
+///
+/// MATCH clauses one after the other are a single MATCH of all their
+/// patterns, with their WHERE clauses joined by AND. An OPTIONAL MATCH after
+/// them is a LEFT JOIN of the tables of the variables it adds, whose ON has
+/// its endpoint conditions, then its label and type conditions, then the
+/// conditions that its relationships differ, then its own WHERE clause, so that rows of the MATCH it does not extend are kept with
+/// NULLs rather than filtered out.
+///
+/// ```
+/// # use sqlparser::dialect::CypherDialect;
+/// # use sqlparser::parser::Parser;
+/// # use sqlparser::transformer::*;
+/// // The SQL of a node with several labels never matches a row of the node
+/// // table, which strict rejects
+/// let transformer = CypherToSqlTransformer::with_config(TransformConfig {
+///     use_label_tables: false,
+///     strict: false,
+///     ..Default::default()
+/// });
+/// let to_sql = |cypher: &str| {
+///     let statement = Parser::new(&CypherDialect)
+///         .try_with_sql(cypher)?
+///         .parse_cypher_statement()?;
+///     Ok::<_, Box<dyn std::error::Error>>(transformer.to_sql(&statement)?)
+/// };
+/// assert_eq!(
+///     to_sql("MATCH (a:Admin:Person)-[:LIKES|KNOWS]->(b) RETURN b.name AS name")?,
+///     to_sql("MATCH (a:Person)-[:KNOWS|LIKES]->(b) WHERE a:Admin RETURN b.name AS name")?,
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
 pub struct CypherToSqlTransformer {
     /// Graph-to-relational schema mapping configuration
     pub config: TransformConfig,
 }
 
+/// The default [`TransformConfig::recursion_limit`]
+pub const DEFAULT_RECURSION_LIMIT: usize = 256;
+
+/// The default [`TransformConfig::range_values_limit`]
+pub const DEFAULT_RANGE_VALUES_LIMIT: usize = 100;
+
 /// Configuration for the transformation process
 #[derive(Debug, Clone)]
 pub struct TransformConfig {
@@ -35,6 +124,138 @@ pub struct TransformConfig {
     pub relationship_table: String,
     /// Whether to use node labels as separate tables
     pub use_label_tables: bool,
+    /// The database the generated SQL is meant for
+    pub target: SqlTarget,
+    /// How [`CypherToSqlTransformer::to_sql`] renders the generated SQL
+    pub format: SqlFormat,
+    /// How Cypher parameters are written in the generated SQL
+    pub param_style: ParamStyle,
+    /// Whether `RETURN *` is expanded to every bound variable, projected as
+    /// when returned whole, rather than emitted as a literal `SELECT *`
+    pub expand_return_star: bool,
+    /// The deepest expression nesting that is transformed, e.g. the length of
+    /// a chain of `AND`s; deeper expressions are rejected with
+    /// [`TransformError::RecursionLimitExceeded`] rather than risking a
+    /// stack overflow
+    pub recursion_limit: usize,
+    /// Whether constructs that would produce a [`TransformWarning`] are
+    /// rejected with [`TransformError::Unsupported`] instead, naming the
+    /// construct and its source text, as is the rest of a query that
+    /// follows a clause the parser does not know, e.g. `REMOVE n:Label`
+    ///
+    /// On by default. The functions returning the SQL as a string, e.g.
+    /// [`transform_cypher_to_sql`], are not strict, so that they keep
+    /// translating what they did before.
+    ///
+    /// [`transform_cypher_to_sql`]: super::transform_cypher_to_sql
+    pub strict: bool,
+    /// How relationships without a direction are matched in both orientations
+    pub undirected: UndirectedStrategy,
+    /// What a relationship with both arrows, `(a)<-[r]->(b)`, matches
+    pub both_directions: BothDirections,
+    /// The primary key column of the node tables, which `id(n)` and
+    /// `elementId(n)` are translated to
+    pub id_column: String,
+    /// The type of [`TransformConfig::id_column`]
+    pub id_type: IdType,
+    /// How `RETURN DISTINCT` is translated when its ORDER BY sorts on
+    /// expressions it does not return
+    pub distinct_order_by: DistinctOrderBy,
+    /// Whether an ordered `RETURN DISTINCT` is translated to `SELECT DISTINCT
+    /// ON (...)` of the returned columns where the target supports it, with
+    /// the columns ORDER BY does not sort on appended to it, so that it starts
+    /// with them; only when ORDER BY sorts on returned columns, which keeps
+    /// the rows and their order those of `SELECT DISTINCT`
+    pub distinct_on: bool,
+    /// Whether the generated SQL ends with a `-- aliases:` comment naming the
+    /// table and labels of every alias, and, when pretty printed, each FROM
+    /// and JOIN item is followed by a comment with its pattern element
+    pub annotate_aliases: bool,
+    /// Whether a MATCH of patterns that neither share a variable nor are
+    /// compared for equality in WHERE is rejected with
+    /// [`TransformError::CartesianProduct`] rather than translated to a cross
+    /// join with a [`WarningKind::CartesianProduct`] warning
+    pub strict_cartesian: bool,
+    /// Which table names, aliases and columns of the generated SQL are quoted
+    pub quoting: IdentifierQuoting,
+    /// The rewriters asked, in order, to translate each function call before
+    /// the built-in translations, e.g. of user defined functions
+    pub function_rewriters: Vec<Arc<dyn FunctionRewriter>>,
+    /// Whether a property tested for truth in WHERE, e.g. `n.active` in
+    /// `WHERE NOT n.active`, is read as `coalesce(n.active, false)`, so that
+    /// an element without the property counts as false rather than null and
+    /// `NOT n.active` matches it
+    pub strict_nulls: bool,
+    /// The property keys holding lists, e.g. `tags`, which `+` appends to in
+    /// the value of a SET item, as it does to a list literal, rather than
+    /// adding numbers; a `+` of values of unknown types, neither lists nor
+    /// numbers, is translated as numeric addition with a [`TransformWarning`]
+    pub list_properties: Vec<String>,
+    /// Whether an anonymous node without labels or properties at the end of
+    /// a directed relationship, e.g. `()` in `MATCH (a)-[:KNOWS]->()`, is left
+    /// out of the FROM clause rather than joined, since the endpoint column
+    /// of the relationship holds the id of a node either way; this assumes
+    /// no relationship refers to a node that does not exist
+    pub prune_anonymous_nodes: bool,
+    /// Whether SKIP and LIMIT are translated to the standard `OFFSET n ROWS
+    /// FETCH FIRST m ROWS ONLY` on targets that support it, rather than to
+    /// `LIMIT m OFFSET n`
+    pub fetch_first: bool,
+    /// Whether the relationships of a MATCH are kept apart, as Cypher binds
+    /// a relationship at most once per MATCH, by requiring the identities of
+    /// every two of them that could be the same relationship to differ; on a
+    /// target without row ids and without a
+    /// [`TransformConfig::relationship_id_column`] they are not, with a
+    /// [`TransformWarning`]
+    pub relationship_uniqueness: bool,
+    /// The column identifying a relationship of the relationship table, or
+    /// `None` for the row id of the target, see [`SqlTarget::row_id_column`]
+    pub relationship_id_column: Option<String>,
+    /// Whether the nodes of a pattern are required to be different nodes,
+    /// e.g. `a.id <> b.id` for `(a:Person)-[:KNOWS]->(b:Person)`
+    ///
+    /// Cypher does not require this: unlike its relationships, the nodes of
+    /// a MATCH may be the same node under different variables, so
+    /// `(a)-[:KNOWS]->(b)` matches a person who knows themselves with `a` and
+    /// `b` bound to them both. This is for the queries written assuming the
+    /// variables bind distinct nodes, which then return what was meant; the
+    /// anonymous end nodes [`TransformConfig::prune_anonymous_nodes`] leaves
+    /// out are joined to be compared. Nodes of different label tables are
+    /// different nodes and are not compared.
+    pub distinct_nodes: bool,
+    /// Whether a property compared with a string literal by `=`,
+    /// `STARTS WITH`, `ENDS WITH` or `CONTAINS` ignores case, e.g.
+    /// `n.name ILIKE 'ada%'` on PostgreSQL and
+    /// `lower(n.name) LIKE lower('ada%')` on SQLite for
+    /// `n.name STARTS WITH 'ada'`
+    ///
+    /// Cypher compares strings case-sensitively, and so does the SQL without
+    /// this, whatever the target's collations. This is for queries ported
+    /// from SQL databases that ignore case; each comparison ignoring case is
+    /// a [`WarningKind::CaseInsensitive`] warning. Other comparisons, e.g. of
+    /// two properties or with a parameter, keep to case.
+    pub case_insensitive_text_ops: bool,
+    /// The most elements of a `range()` with literal bounds that UNWIND
+    /// lists in a `VALUES` query; longer ranges, and ranges of other bounds,
+    /// are generated by `generate_series` on PostgreSQL and DuckDB and by a
+    /// recursive CTE elsewhere
+    pub range_values_limit: usize,
+    /// The SQL/PGQ property graph of the node and relationship tables, which
+    /// MATCH ... RETURN queries read through a `GRAPH_TABLE` of, rather than
+    /// by joining the tables, when set
+    ///
+    /// `MATCH (a:Person)-[:KNOWS*1..2]->(b) WHERE a.age > 30 RETURN b.name`
+    /// over a graph `social` is
+    /// `SELECT name FROM GRAPH_TABLE (social MATCH (a IS Person)-[IS KNOWS]->{1,2}(b)
+    /// WHERE a.age > 30 COLUMNS (b.name AS name))`, matching paths of any
+    /// length as the join-based SQL does not. Statements
+    /// updating the graph are translated as they are without it, and reading
+    /// constructs with no counterpart in a graph table, e.g. OPTIONAL MATCH,
+    /// WITH or a variable returned whole, are rejected with
+    /// [`TransformError::Unsupported`]. The
+    /// [`TransformConfig::function_rewriters`] are not asked to translate
+    /// the calls in a graph table.
+    pub property_graph: Option<String>,
 }
 
 impl Default for TransformConfig {
@@ -43,8 +264,523 @@ impl Default for TransformConfig {
             node_table: "nodes".to_string(),
             relationship_table: "relationships".to_string(),
             use_label_tables: true,
+            target: SqlTarget::default(),
+            format: SqlFormat::default(),
+            param_style: ParamStyle::default(),
+            expand_return_star: true,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+            strict: true,
+            undirected: UndirectedStrategy::default(),
+            both_directions: BothDirections::default(),
+            id_column: "id".to_string(),
+            id_type: IdType::default(),
+            distinct_order_by: DistinctOrderBy::default(),
+            distinct_on: false,
+            annotate_aliases: false,
+            strict_cartesian: false,
+            quoting: IdentifierQuoting::default(),
+            function_rewriters: vec![],
+            strict_nulls: false,
+            list_properties: vec![],
+            prune_anonymous_nodes: true,
+            fetch_first: false,
+            relationship_uniqueness: true,
+            relationship_id_column: None,
+            distinct_nodes: false,
+            case_insensitive_text_ops: false,
+            range_values_limit: DEFAULT_RANGE_VALUES_LIMIT,
+            property_graph: None,
+        }
+    }
+}
+
+impl TransformConfig {
+    /// The direction a relationship written with `direction` is matched in,
+    /// where both arrows may mean no direction
+    pub(crate) fn direction(&self, direction: &RelationshipDirection) -> RelationshipDirection {
+        match direction {
+            RelationshipDirection::Both if self.both_directions == BothDirections::Undirected => {
+                RelationshipDirection::None
+            }
+            direction => direction.clone(),
+        }
+    }
+
+    /// The column compared to keep the relationships of a MATCH apart, or
+    /// `None` when there is none to compare
+    pub(crate) fn relationship_id(&self) -> Option<&str> {
+        self.relationship_id_column
+            .as_deref()
+            .or(self.target.row_id_column())
+    }
+}
+
+/// The result of [`CypherToSqlTransformer::to_sql_output`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformOutput {
+    /// The generated SQL, as returned by [`CypherToSqlTransformer::to_sql`]
+    pub sql: String,
+    /// The constructs of the Cypher statement that the SQL does not express
+    pub warnings: Vec<TransformWarning>,
+    /// The SQL parameters of the Cypher parameters, statement by statement in
+    /// the order they appear in the SQL
+    pub parameters: Vec<ParamBinding>,
+    /// The nodes a CREATE inserts, in the order of their statements
+    pub created_nodes: Vec<CreatedNode>,
+}
+
+/// A node inserted by a CREATE
+///
+/// The inserts of the relationships of a created node take its id as a
+/// parameter named after its variable, e.g. `$a`, for the caller to bind to
+/// the id its own insert gave it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreatedNode {
+    /// The node's variable, or the variable generated for an anonymous node,
+    /// e.g. `node_1`
+    pub variable: String,
+    /// Whether `variable` was generated
+    pub synthetic: bool,
+    /// The index of the statement inserting the node
+    pub statement: usize,
+}
+
+/// The SQL statements of a Cypher statement, before they are rendered
+pub(crate) struct Transformed {
+    pub(crate) statements: Vec<Statement>,
+    warnings: Vec<TransformWarning>,
+    parameters: Vec<ParamBinding>,
+    created_nodes: Vec<CreatedNode>,
+    /// The bindings of each SELECT, in the order the SELECTs appear in the
+    /// statements
+    selects: Vec<Vec<Binding>>,
+}
+
+/// A MATCH whose WITH clause has been folded into its condition and RETURN
+struct FoldedWith {
+    r#where: Option<Expr>,
+    r#return: Option<ReturnClause>,
+    /// The conditions on aggregates of the WITH, in Cypher
+    having: Vec<Expr>,
+    /// The items the WITH groups by when it aggregates, in Cypher
+    group_by: Vec<Expr>,
+}
+
+/// An OPTIONAL MATCH clause after a MATCH, whose patterns are left joined
+/// onto the rows of the MATCH
+pub(crate) struct OptionalMatch<'a> {
+    pub(crate) patterns: &'a [Pattern],
+    /// The condition of the clause, which goes to the join condition
+    pub(crate) r#where: &'a Option<Expr>,
+}
+
+/// What a CREATE creates, after a MATCH or on its own
+struct CreatedElements<'a> {
+    /// The created nodes, each with its variable, or a `node_<n>` variable
+    /// generated for it when it has none
+    nodes: Vec<(String, &'a PatternElement)>,
+    /// The created relationships, from and to their endpoints
+    relationships: Vec<(CreatedEndpoint<'a>, CreatedEndpoint<'a>, &'a PatternElement)>,
+}
+
+/// A node a CREATE connects
+#[derive(Clone, Copy)]
+enum CreatedEndpoint<'a> {
+    /// A node the MATCH binds to this variable
+    Matched(&'a Ident),
+    /// The created node at this index
+    Created(usize),
+}
+
+impl<'a> CreatedElements<'a> {
+    /// The property map a created node or relationship has, by variable, or
+    /// `None` when no created element has the variable
+    fn properties(&self, variable: &Ident) -> Option<Option<&'a Expr>> {
+        let nodes = self.nodes.iter().map(|(_, element)| element);
+        let relationships = self.relationships.iter().map(|(_, _, element)| element);
+        nodes
+            .chain(relationships)
+            .find(|element| element.variable() == Some(variable))
+            .map(|element| element.properties())
+    }
+
+    /// Sort the elements of `patterns` into the nodes `match_patterns` bind
+    /// and the elements to create
+    fn new(match_patterns: &'a [Pattern], patterns: &'a [Pattern]) -> Result<Self, TransformError> {
+        let bound = matched_variables(match_patterns);
+        let mut used: Vec<&Ident> = bound.clone();
+        used.extend(matched_variables(patterns));
+        let mut created = CreatedElements {
+            nodes: vec![],
+            relationships: vec![],
+        };
+
+        for pattern in patterns {
+            let mut endpoints = vec![];
+            for element in pattern.elements.iter().filter(|element| element.is_node()) {
+                let variable = element.variable();
+                let bare = element.labels_or_types().is_empty() && element.properties().is_none();
+                let previous = variable.and_then(|variable| {
+                    created
+                        .nodes
+                        .iter()
+                        .position(|(_, node)| node.variable() == Some(variable))
+                });
+                let endpoint = match (variable, previous) {
+                    // The variable of a created relationship
+                    (Some(variable), None) if created.properties(variable).is_some() => {
+                        return Err(TransformError::InvalidQuery(format!(
+                            "Variable `{variable}` is created more than once in CREATE {pattern}"
+                        )))
+                    }
+                    (Some(variable), _) if bound.contains(&variable) => {
+                        if !bare {
+                            return Err(TransformError::InvalidQuery(format!(
+                                "Variable `{variable}` is bound by MATCH, so CREATE {element} \
+                                 cannot give it labels or properties"
+                            )));
+                        }
+                        CreatedEndpoint::Matched(variable)
+                    }
+                    (Some(_), Some(i)) if bare => CreatedEndpoint::Created(i),
+                    (Some(variable), Some(_)) => {
+                        return Err(TransformError::InvalidQuery(format!(
+                            "Variable `{variable}` is created more than once in CREATE {pattern}"
+                        )))
+                    }
+                    // A bare endpoint is meant to be a matched node
+                    (Some(variable), None)
+                        if bare && pattern.elements.len() > 1 && !match_patterns.is_empty() =>
+                    {
+                        return Err(TransformError::InvalidQuery(format!(
+                            "Variable `{variable}` in CREATE {pattern} is not bound by MATCH; \
+                             give it a label or properties to create a new node"
+                        )))
+                    }
+                    (variable, _) => {
+                        let key = match variable {
+                            Some(variable) => variable.value.clone(),
+                            None => (1..)
+                                .map(|n| format!("node_{n}"))
+                                .find(|key| {
+                                    !used.iter().any(|variable| variable.value == *key)
+                                        && !created.nodes.iter().any(|(name, _)| name == key)
+                                })
+                                .expect("an unused name"),
+                        };
+                        created.nodes.push((key, element));
+                        CreatedEndpoint::Created(created.nodes.len() - 1)
+                    }
+                };
+                endpoints.push(endpoint);
+            }
+
+            for (i, element) in pattern.elements.iter().enumerate() {
+                let PatternElement::Relationship {
+                    variable,
+                    types,
+                    direction,
+                    length,
+                    ..
+                } = element
+                else {
+                    continue;
+                };
+                if let Some(variable) = variable.as_ref().filter(|v| bound.contains(v)) {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "Variable `{variable}` is bound by MATCH, so CREATE {pattern} cannot create it"
+                    )));
+                }
+                // A relationship is created once, by the only element with
+                // its variable
+                if let Some(variable) = variable
+                    .as_ref()
+                    .filter(|v| created.properties(v).is_some())
+                {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "Variable `{variable}` is created more than once in CREATE {pattern}"
+                    )));
+                }
+                if types.len() != 1 || length.is_some() {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "A relationship in CREATE must have a single type and length, found {element}"
+                    )));
+                }
+                let (from, to) = (endpoints[i / 2], endpoints[i / 2 + 1]);
+                let (from, to) = match direction {
+                    RelationshipDirection::Right => (from, to),
+                    RelationshipDirection::Left => (to, from),
+                    RelationshipDirection::Both | RelationshipDirection::None => {
+                        return Err(TransformError::InvalidQuery(format!(
+                            "A relationship in CREATE must have a direction, found {element}"
+                        )))
+                    }
+                };
+                created.relationships.push((from, to, element));
+            }
+        }
+        Ok(created)
+    }
+}
+
+/// The SQL database a transformation targets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SqlTarget {
+    #[default]
+    Sqlite,
+    Postgres,
+    MySql,
+    DuckDb,
+    /// Apache DataFusion, whose `properties` columns are maps from property
+    /// keys to values, read with `n.properties['name']`
+    ///
+    /// Only reading statements are translated, as DataFusion tables take no
+    /// updates or deletes and assign no ids; `collect()` is `array_agg()`,
+    /// and a variable returned whole is a struct rather than a JSON object.
+    DataFusion,
+}
+
+/// What a relationship with both arrows, `(a)<-[r:LINKS]->(b)`, matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BothDirections {
+    /// A relationship from `a` to `b` that has a counterpart of the same type
+    /// from `b` to `a`, required by an `EXISTS` on the relationship table;
+    /// `r` is the relationship from `a` to `b`
+    #[default]
+    Mutual,
+    /// A relationship in either direction, as without arrows, with a
+    /// [`TransformWarning`]
+    Undirected,
+}
+
+/// How a relationship without a direction, `(a)-[r]-(b)`, is matched in
+/// either orientation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UndirectedStrategy {
+    /// A single join whose condition accepts both orientations, e.g.
+    /// `(a.id = r.from_id AND b.id = r.to_id) OR (b.id = r.from_id AND a.id = r.to_id)`
+    #[default]
+    OrJoin,
+    /// A `UNION ALL` of one query per orientation, which keeps every join
+    /// condition an equality that can use an index; `RETURN DISTINCT` turns it
+    /// into a `UNION`
+    ///
+    /// Unlike the join condition, the union returns a relationship from a node
+    /// to itself once per orientation, unless the pattern names the same
+    /// variable at both ends. A `MATCH` without `RETURN` keeps the
+    /// [`UndirectedStrategy::OrJoin`] condition, and at most [`MAX_UNDIRECTED_UNION_RELATIONSHIPS`]
+    /// undirected relationships are expanded, as every one of them doubles
+    /// the number of queries.
+    UnionAll,
+}
+
+/// The type of the node id column
+///
+/// Cypher's `id(n)` is an integer and `elementId(n)` a string; comparing
+/// either of them to a literal of the other type converts the literal, so the
+/// comparison stays a primary key lookup, and any other use casts the column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdType {
+    /// Integers the database assigns, e.g. with `AUTOINCREMENT`
+    #[default]
+    Integer,
+    /// Strings the database assigns with a column default
+    Text,
+    /// UUIDs that `CREATE` generates, with `gen_random_uuid()` or MySQL's
+    /// `uuid()`
+    ///
+    /// Both `id(n)` and `elementId(n)` are the UUID as a string, and literals
+    /// compared with them are strings.
+    Uuid,
+}
+
+/// How `RETURN DISTINCT ... ORDER BY` is translated when the ORDER BY sorts
+/// on expressions that are not returned, which `SELECT DISTINCT` does not
+/// allow outside of SQLite
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DistinctOrderBy {
+    /// Group by the returned columns instead of `SELECT DISTINCT`, ordering
+    /// each group by the smallest key of its rows, or the largest for a
+    /// descending sort
+    #[default]
+    GroupBy,
+    /// Reject the query with [`TransformError::InvalidQuery`]
+    Reject,
+}
+
+/// The most undirected relationships a `MATCH` can have under
+/// [`UndirectedStrategy::UnionAll`]
+pub const MAX_UNDIRECTED_UNION_RELATIONSHIPS: usize = 6;
+
+impl SqlTarget {
+    /// Whether output column aliases can be used inside ORDER BY expressions,
+    /// e.g. `ORDER BY lower(name)`, rather than only as a bare sort key
+    pub fn supports_alias_in_order_by_expr(&self) -> bool {
+        !matches!(self, SqlTarget::Postgres | SqlTarget::DataFusion)
+    }
+
+    /// Whether GROUP BY resolves output column aliases, e.g. `GROUP BY name`
+    /// for `... AS name`
+    ///
+    /// Postgres also accepts a bare alias, but takes a column of a table in
+    /// FROM of the same name over it.
+    pub fn supports_alias_in_group_by(&self) -> bool {
+        !matches!(self, SqlTarget::Postgres)
+    }
+
+    /// Whether `SELECT DISTINCT ON (...)` is supported
+    pub fn supports_distinct_on(&self) -> bool {
+        matches!(self, SqlTarget::Postgres)
+    }
+
+    /// Whether `SELECT DISTINCT` can be ordered by expressions it does not
+    /// select
+    pub fn supports_distinct_order_by_unselected(&self) -> bool {
+        matches!(self, SqlTarget::Sqlite)
+    }
+
+    /// The function building a JSON object from alternating keys and values,
+    /// or a struct on DataFusion
+    pub fn json_object_function(&self) -> &'static str {
+        match self {
+            SqlTarget::Postgres => "json_build_object",
+            SqlTarget::Sqlite | SqlTarget::MySql | SqlTarget::DuckDb => "json_object",
+            SqlTarget::DataFusion => "named_struct",
+        }
+    }
+
+    /// The function building a JSON array of its arguments, or a list on
+    /// DataFusion
+    pub fn json_array_function(&self) -> &'static str {
+        match self {
+            SqlTarget::Postgres => "json_build_array",
+            SqlTarget::Sqlite | SqlTarget::MySql | SqlTarget::DuckDb => "json_array",
+            SqlTarget::DataFusion => "make_array",
+        }
+    }
+
+    /// The aggregate function building a JSON array of its argument's values,
+    /// or a list on DataFusion
+    pub fn json_aggregate_function(&self) -> &'static str {
+        match self {
+            SqlTarget::Sqlite | SqlTarget::DuckDb => "json_group_array",
+            SqlTarget::Postgres => "json_agg",
+            SqlTarget::MySql => "json_arrayagg",
+            SqlTarget::DataFusion => "array_agg",
+        }
+    }
+
+    /// The column every table has that identifies its rows, if any: `rowid`
+    /// on SQLite and DuckDB, and `ctid` on Postgres
+    pub fn row_id_column(&self) -> Option<&'static str> {
+        match self {
+            SqlTarget::Sqlite | SqlTarget::DuckDb => Some("rowid"),
+            SqlTarget::Postgres => Some("ctid"),
+            SqlTarget::MySql | SqlTarget::DataFusion => None,
+        }
+    }
+
+    /// Whether an `INSERT` can return the rows it inserted with `RETURNING`,
+    /// as on SQLite 3.35 and later
+    pub fn supports_returning(&self) -> bool {
+        matches!(
+            self,
+            SqlTarget::Sqlite | SqlTarget::Postgres | SqlTarget::DuckDb
+        )
+    }
+
+    /// The function giving the id the last `INSERT` assigned, read after it
+    /// where there is no `RETURNING`: `LAST_INSERT_ID()` on MySQL, and
+    /// `last_insert_rowid()` elsewhere
+    pub fn last_insert_id_function(&self) -> &'static str {
+        match self {
+            SqlTarget::MySql => "LAST_INSERT_ID",
+            _ => "last_insert_rowid",
+        }
+    }
+
+    /// Whether a `WITH` query can hold `INSERT` statements, e.g.
+    /// `WITH created AS (INSERT ...) INSERT ...`
+    pub fn supports_data_modifying_cte(&self) -> bool {
+        matches!(self, SqlTarget::Postgres)
+    }
+
+    /// Whether rows can be paged with `OFFSET n ROWS FETCH FIRST m ROWS
+    /// ONLY`, used under [`TransformConfig::fetch_first`]
+    pub fn supports_fetch_first(&self) -> bool {
+        matches!(self, SqlTarget::Postgres)
+    }
+
+    /// The LIMIT keeping every row, which a SKIP without LIMIT is translated
+    /// with where OFFSET cannot stand alone, or `None` where it can
+    fn unbounded_limit(&self) -> Option<Expr> {
+        match self {
+            SqlTarget::Sqlite => Some(Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr: Box::new(number_literal(1)),
+            }),
+            // The largest row count MySQL takes
+            SqlTarget::MySql => {
+                #[cfg(not(feature = "bigdecimal"))]
+                let value = u64::MAX.to_string();
+                #[cfg(feature = "bigdecimal")]
+                let value = bigdecimal::BigDecimal::from(u64::MAX);
+                Some(Expr::value(Value::Number(value, false)))
+            }
+            SqlTarget::Postgres | SqlTarget::DuckDb | SqlTarget::DataFusion => None,
+        }
+    }
+
+    /// The character quoting identifiers under [`TransformConfig::quoting`]
+    pub fn identifier_quote(&self) -> char {
+        match self {
+            SqlTarget::MySql => '`',
+            SqlTarget::Sqlite | SqlTarget::Postgres | SqlTarget::DuckDb | SqlTarget::DataFusion => {
+                '"'
+            }
         }
     }
+
+    /// Whether a JSON document stored as text must be parsed with `json()`
+    /// to be nested in a JSON object rather than embedded as a string
+    pub fn json_stored_as_text(&self) -> bool {
+        matches!(self, SqlTarget::Sqlite | SqlTarget::DuckDb)
+    }
+
+    /// The names the JSON type function of the database gives the values of
+    /// `data_type`, after the name it gives `null`, or `None` when JSON does
+    /// not hold such values or the database does not store properties as JSON
+    fn json_type_names(&self, data_type: &CypherType) -> Option<Vec<&'static str>> {
+        let null = match self {
+            SqlTarget::Sqlite | SqlTarget::Postgres => "null",
+            SqlTarget::MySql | SqlTarget::DuckDb => "NULL",
+            SqlTarget::DataFusion => return None,
+        };
+        let names: &[&str] = match (self, data_type) {
+            (_, CypherType::Null) => &[],
+            (SqlTarget::Sqlite, CypherType::Boolean) => &["true", "false"],
+            (SqlTarget::Sqlite, CypherType::String) => &["text"],
+            (SqlTarget::Sqlite, CypherType::Integer) => &["integer"],
+            (SqlTarget::Sqlite, CypherType::Float) => &["real"],
+            (SqlTarget::Postgres, CypherType::Boolean) => &["boolean"],
+            (SqlTarget::Postgres, CypherType::String) => &["string"],
+            (SqlTarget::Postgres, CypherType::Integer | CypherType::Float) => &["number"],
+            (SqlTarget::MySql, CypherType::String) => &["STRING"],
+            (SqlTarget::MySql, CypherType::Integer) => &["INTEGER", "UNSIGNED INTEGER"],
+            (SqlTarget::MySql, CypherType::Float) => &["DOUBLE", "DECIMAL"],
+            (SqlTarget::DuckDb, CypherType::String) => &["VARCHAR"],
+            (SqlTarget::DuckDb, CypherType::Integer) => &["BIGINT", "UBIGINT"],
+            (SqlTarget::DuckDb, CypherType::Float) => &["DOUBLE"],
+            (SqlTarget::MySql | SqlTarget::DuckDb, CypherType::Boolean) => &["BOOLEAN"],
+            (SqlTarget::Sqlite | SqlTarget::Postgres, CypherType::Map) => &["object"],
+            (SqlTarget::MySql | SqlTarget::DuckDb, CypherType::Map) => &["OBJECT"],
+            (SqlTarget::Sqlite | SqlTarget::Postgres, CypherType::List(_)) => &["array"],
+            (SqlTarget::MySql | SqlTarget::DuckDb, CypherType::List(_)) => &["ARRAY"],
+            _ => return None,
+        };
+        Some([null].iter().chain(names).copied().collect())
+    }
 }
 
 impl CypherToSqlTransformer {
@@ -60,379 +796,6803 @@ impl CypherToSqlTransformer {
         Self { config }
     }
 
+    /// Transform a Cypher statement to SQL text in the configured format,
+    /// with statements separated by `;` and a newline
+    pub fn to_sql(&self, cypher_stmt: &CypherStatement) -> Result<String, TransformError> {
+        Ok(self.to_sql_output(cypher_stmt)?.sql)
+    }
+
+    /// Transform a Cypher statement to SQL text like [`Self::to_sql`], along
+    /// with warnings for the constructs the SQL does not express
+    pub fn to_sql_output(
+        &self,
+        cypher_stmt: &CypherStatement,
+    ) -> Result<TransformOutput, TransformError> {
+        Ok(self.output(self.transform_with_details(cypher_stmt)?))
+    }
+
+    /// Transform a Cypher statement to SQL text like [`Self::to_sql_output`],
+    /// with its `$name` parameters replaced by the literals of their `params`
+    ///
+    /// A parameter without a value is a [`TransformError::MissingParameters`]
+    /// naming all of them, and a value no parameter uses is reported as a
+    /// [`WarningKind::UnusedParameter`] warning.
+    pub fn to_sql_output_with_params(
+        &self,
+        cypher_stmt: &CypherStatement,
+        params: &BTreeMap<String, ParamValue>,
+    ) -> Result<TransformOutput, TransformError> {
+        let mut inlined = cypher_stmt.clone();
+        let unused = inline_parameters(&mut inlined, params)?;
+        let mut output = self.to_sql_output(&inlined)?;
+        output.warnings.extend(unused);
+        Ok(output)
+    }
+
+    /// Render transformed statements as the SQL text of [`Self::to_sql_output`]
+    pub(crate) fn output(&self, transformed: Transformed) -> TransformOutput {
+        let Transformed {
+            statements,
+            warnings,
+            parameters,
+            selects,
+            created_nodes,
+        } = transformed;
+        // Only annotated SQL shows the bindings
+        let selects = if self.config.annotate_aliases {
+            selects
+        } else {
+            vec![]
+        };
+        let mut sql = statements
+            .iter()
+            .map(|statement| {
+                format_statement_with_bindings(statement, self.config.format, &selects)
+            })
+            .collect::<Vec<_>>()
+            .join(";\n");
+        let mut aliases: Vec<String> = vec![];
+        for binding in selects.iter().flatten() {
+            let alias = format!("{}={}", binding.table_ref(), binding.table_description());
+            if !aliases.contains(&alias) {
+                aliases.push(alias);
+            }
+        }
+        if !aliases.is_empty() {
+            sql.push_str(&format!(
+                "\n-- aliases: {}",
+                comment_text(&aliases.join(", "))
+            ));
+        }
+        TransformOutput {
+            sql,
+            warnings,
+            parameters,
+            created_nodes,
+        }
+    }
+
+    /// The warnings [`Self::to_sql_output`] reports for `cypher_stmt`,
+    /// without transforming it
+    pub(crate) fn statement_warnings(
+        &self,
+        cypher_stmt: &CypherStatement,
+    ) -> Vec<TransformWarning> {
+        let mut warnings = vec![];
+        untranslated_features(cypher_stmt, &self.config, &mut warnings);
+        untranslated_functions(self, cypher_stmt, &mut warnings);
+        untyped_additions(self, cypher_stmt, &mut warnings);
+        for (query, groups) in cartesian_products(cypher_stmt) {
+            warnings.push(cartesian_warning(query, &groups));
+        }
+        case_insensitive_comparisons(cypher_stmt, &self.config, &mut warnings);
+        warnings
+    }
+
     /// Transform a Cypher statement to SQL statements
-    pub fn transform(&self, cypher_stmt: &CypherStatement) -> Result<Vec<Statement>, String> {
-        match cypher_stmt {
+    pub fn transform(
+        &self,
+        cypher_stmt: &CypherStatement,
+    ) -> Result<Vec<Statement>, TransformError> {
+        Ok(self.transform_with_details(cypher_stmt)?.statements)
+    }
+
+    /// The tables, columns and indexes the SQL of a Cypher statement reads
+    /// and writes under the configured schema and target, with the DDL
+    /// creating them, e.g. to prepare an empty database
+    ///
+    /// The statement is not transformed, so the schema of a statement that
+    /// cannot be transformed is still reported.
+    pub fn required_schema(&self, cypher_stmt: &CypherStatement) -> SchemaRequirements {
+        required_schema(cypher_stmt, &self.config)
+    }
+
+    pub(crate) fn transform_with_details(
+        &self,
+        cypher_stmt: &CypherStatement,
+    ) -> Result<Transformed, TransformError> {
+        // Placeholders stand in for what the text lacks, so the statement is
+        // not what it means
+        if let CypherStatement::Recovered { statement } = cypher_stmt {
+            return Err(TransformError::InvalidQuery(format!(
+                "{statement} was recovered from errors parsing it"
+            )));
+        }
+        let mut warnings = vec![];
+        untranslated_features(cypher_stmt, &self.config, &mut warnings);
+        untranslated_functions(self, cypher_stmt, &mut warnings);
+        untyped_additions(self, cypher_stmt, &mut warnings);
+        if self.config.strict {
+            if let Some(warning) = warnings.first() {
+                return Err(TransformError::Unsupported(format!(
+                    "{} in {}",
+                    warning.feature, warning.source_text
+                )));
+            }
+        }
+        for (query, groups) in cartesian_products(cypher_stmt) {
+            if self.config.strict_cartesian {
+                return Err(TransformError::CartesianProduct(groups));
+            }
+            warnings.push(cartesian_warning(query, &groups));
+        }
+        case_insensitive_comparisons(cypher_stmt, &self.config, &mut warnings);
+
+        // DataFusion tables take no updates or deletes and assign no ids
+        let mut updating = cypher_stmt;
+        while let CypherStatement::Unwind { query, .. } = updating {
+            updating = query;
+        }
+        if self.config.target == SqlTarget::DataFusion
+            && matches!(
+                updating,
+                CypherStatement::Create { .. }
+                    | CypherStatement::Merge { .. }
+                    | CypherStatement::Delete { .. }
+                    | CypherStatement::Set { .. }
+            )
+        {
+            return Err(TransformError::Unsupported(format!(
+                "{cypher_stmt} on DataFusion"
+            )));
+        }
+
+        // A variable no pattern binds would be a column of no table. Clauses
+        // that know why it is missing, e.g. a WITH that drops it, say so
+        // instead, so this is only reported for a statement they translate.
+        let unbound = match bound_variables(cypher_stmt) {
+            Err(ParserError::ParserError(message)) => Some(message),
+            _ => None,
+        };
+
+        // MATCH clauses one after the other match their patterns together
+        let merged = merge_reading_clauses(cypher_stmt)?;
+        let (cypher_stmt, optional_matches) = match &merged {
+            Some((statement, optional_matches)) => (statement, &optional_matches[..]),
+            None => (cypher_stmt, &[][..]),
+        };
+
+        let mut selects = vec![];
+        let mut created_nodes = vec![];
+        let mut statements = match cypher_stmt {
+            CypherStatement::Match {
+                patterns,
+                r#where,
+                with: Some(with),
+                next: Some(next),
+                ..
+            } => {
+                let query = self.with_query(patterns, r#where, with, next, &mut selects)?;
+                Ok(vec![Statement::Query(Box::new(query))])
+            }
             CypherStatement::Match {
-                optional,
                 patterns,
                 r#where,
+                with,
+                r#return,
+                ..
+            } => self.transform_match(
+                patterns,
+                optional_matches,
+                r#where,
+                with,
                 r#return,
-            } => self.transform_match(*optional, patterns, r#where, r#return),
-            CypherStatement::Create { patterns } => self.transform_create(patterns),
-            CypherStatement::Merge {
+                &mut selects,
+            ),
+            CypherStatement::Create {
+                r#match,
+                r#where,
                 patterns,
-                on_create,
-                on_match,
-            } => self.transform_merge(patterns, on_create, on_match),
+                r#return,
+            } => {
+                if r#match.is_empty() {
+                    self.transform_create(patterns, r#return.as_ref(), &mut created_nodes)
+                } else if let Some(ret) = r#return {
+                    Err(TransformError::Unsupported(format!(
+                        "{ret} after MATCH ... CREATE"
+                    )))
+                } else {
+                    self.transform_match_create(r#match, r#where, patterns, &mut selects)
+                }
+            }
+            CypherStatement::Merge { patterns, actions } => self.transform_merge(patterns, actions),
             CypherStatement::Delete {
+                patterns,
                 detach,
                 what,
                 r#where,
-            } => self.transform_delete(*detach, what, r#where),
+            } => self.transform_delete(patterns, *detach, what, r#where),
+            CypherStatement::Set {
+                patterns,
+                r#where,
+                items,
+            } => self.transform_set(patterns, r#where, items, &mut selects),
+            CypherStatement::Union { all, queries } => {
+                self.transform_union(*all, queries, &mut selects)
+            }
+            CypherStatement::Show {
+                what,
+                yield_items,
+                r#where,
+            } => self.transform_show(*what, yield_items, r#where),
+            CypherStatement::Unwind {
+                list,
+                variable,
+                query,
+            } => self.transform_unwind(list, variable, query, &mut selects),
+            CypherStatement::Recovered { .. } => unreachable!("recovered statements are rejected"),
+        }?;
+        if let Some(message) = unbound {
+            return Err(TransformError::InvalidQuery(message));
+        }
+
+        let mut parameters = vec![];
+        for statement in &mut statements {
+            parameters.extend(bind_parameters(statement, self.config.param_style));
+            quote_identifiers(statement, self.config.quoting, self.config.target);
         }
+        Ok(Transformed {
+            statements,
+            warnings,
+            parameters,
+            selects,
+            created_nodes,
+        })
     }
 
     /// Transform MATCH statement to SELECT
     fn transform_match(
         &self,
-        optional: bool,
         patterns: &[Pattern],
+        optional_matches: &[OptionalMatch],
         where_clause: &Option<Expr>,
+        with_clause: &Option<WithClause>,
         return_clause: &Option<ReturnClause>,
-    ) -> Result<Vec<Statement>, String> {
-        let mut from_tables = vec![];
-        let mut joins = vec![];
-        let mut where_conditions = vec![];
-
-        // Process patterns to generate table references and joins
-        for pattern in patterns {
-            self.process_pattern_for_match(pattern, &mut from_tables, &mut joins, &mut where_conditions)?;
+        selects: &mut Vec<Vec<Binding>>,
+    ) -> Result<Vec<Statement>, TransformError> {
+        // A graph table matches relationships in either direction itself
+        if let (UndirectedStrategy::UnionAll, Some(ret_clause), None) = (
+            self.config.undirected,
+            return_clause,
+            &self.config.property_graph,
+        ) {
+            let undirected = patterns
+                .iter()
+                .flat_map(|pattern| &pattern.elements)
+                .filter(|element| is_undirected(element, &self.config))
+                .count();
+            if undirected > MAX_UNDIRECTED_UNION_RELATIONSHIPS {
+                return Err(TransformError::Unsupported(format!(
+                    "more than {MAX_UNDIRECTED_UNION_RELATIONSHIPS} undirected relationships \
+                     in a UNION ALL of their orientations"
+                )));
+            }
+            // Each arm would limit the rows of its own orientation
+            if let Some(with) = with_clause
+                .as_ref()
+                .filter(|with| undirected > 0 && has_row_modifiers(with))
+            {
+                return Err(TransformError::Unsupported(format!(
+                    "undirected relationships before {with} in a UNION ALL of their orientations"
+                )));
+            }
+            // Each arm would group, and then page, only the rows of its own
+            // orientation
+            let aggregating = with_clause
+                .iter()
+                .map(|with| &with.projection)
+                .chain([ret_clause])
+                .flat_map(|clause| &clause.items)
+                .any(|item| match item {
+                    SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                        contains_aggregate(expr)
+                    }
+                    _ => false,
+                });
+            if undirected > 0 && aggregating {
+                return Err(TransformError::Unsupported(
+                    "aggregation over undirected relationships in a UNION ALL of their \
+                     orientations"
+                        .to_string(),
+                ));
+            }
+            if undirected > 0 && !optional_matches.is_empty() {
+                return Err(TransformError::Unsupported(
+                    "OPTIONAL MATCH after undirected relationships in a UNION ALL of their \
+                     orientations"
+                        .to_string(),
+                ));
+            }
+            if undirected > 0 {
+                // Like the arms of a Cypher UNION, only the last query carries
+                // the ORDER BY, SKIP and LIMIT that apply to the whole result
+                let arms = oriented_patterns(patterns, &self.config);
+                let last = arms.len() - 1;
+                let queries = arms
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, patterns)| CypherStatement::Match {
+                        optional: false,
+                        patterns,
+                        r#where: where_clause.clone(),
+                        with: with_clause.clone(),
+                        next: None,
+                        r#return: Some(if i == last {
+                            ret_clause.clone()
+                        } else {
+                            ReturnClause {
+                                order_by: vec![],
+                                skip: None,
+                                limit: None,
+                                ..ret_clause.clone()
+                            }
+                        }),
+                    })
+                    .collect::<Vec<_>>();
+                return self.transform_union(!ret_clause.distinct, &queries, selects);
+            }
         }
 
-        // Add WHERE clause conditions
-        if let Some(where_expr) = where_clause {
-            where_conditions.push(where_expr.clone());
-        }
+        let query = self.match_query(
+            patterns,
+            optional_matches,
+            where_clause,
+            with_clause,
+            return_clause,
+            selects,
+        )?;
+        Ok(vec![Statement::Query(Box::new(query))])
+    }
 
-        // Build the final WHERE clause
-        let final_where = if where_conditions.is_empty() {
-            None
-        } else {
-            Some(self.combine_conditions(where_conditions))
-        };
+    /// Transform UNION of MATCH ... RETURN queries to a SQL UNION
+    fn transform_union(
+        &self,
+        all: bool,
+        queries: &[CypherStatement],
+        selects: &mut Vec<Vec<Binding>>,
+    ) -> Result<Vec<Statement>, TransformError> {
+        let mut columns: Option<Vec<String>> = None;
+        let mut body: Option<SetExpr> = None;
+        let mut order_by = None;
+        let mut last_return = None;
 
-        // Build projection
-        let projection = if let Some(ret_clause) = return_clause {
-            ret_clause.items.clone()
-        } else {
-            vec![SelectItem::Wildcard(WildcardAdditionalOptions::default())]
-        };
+        for (i, query) in queries.iter().enumerate() {
+            let merged = merge_reading_clauses(query)?;
+            let (query, optional_matches) = match &merged {
+                Some((statement, optional_matches)) => (statement, &optional_matches[..]),
+                None => (query, &[][..]),
+            };
+            let CypherStatement::Match {
+                optional: _,
+                patterns,
+                r#where,
+                with,
+                r#return: Some(ret_clause),
+                next: None,
+            } = query
+            else {
+                return Err(TransformError::Unsupported(format!(
+                    "UNION arm that is not a MATCH ... RETURN query: {query}"
+                )));
+            };
 
-        // Build ORDER BY
-        let order_by = return_clause
-            .as_ref()
-            .map(|ret| ret.order_by.clone())
-            .unwrap_or_default();
+            // Cypher requires every arm to return the same column names
+            let plan = GraphPlan::build(patterns, r#where, &self.config);
+            let arm_columns = return_column_names(&ret_clause.items, &plan);
+            match &columns {
+                Some(expected) if *expected != arm_columns => {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "All sub queries in a UNION must return the same columns, \
+                         expected ({}) but found ({})",
+                        expected.join(", "),
+                        arm_columns.join(", ")
+                    )));
+                }
+                Some(_) => {}
+                None => columns = Some(arm_columns),
+            }
 
-        // Build LIMIT and OFFSET
-        let (limit, offset) = if let Some(ret_clause) = return_clause {
-            let limit = ret_clause.limit.clone();
-            let offset = ret_clause.skip.clone().map(|skip| Offset {
-                value: skip,
-                rows: OffsetRows::None,
-            });
-            (limit, offset)
-        } else {
-            (None, None)
-        };
+            let is_last = i + 1 == queries.len();
+            let has_modifiers = !ret_clause.order_by.is_empty()
+                || ret_clause.skip.is_some()
+                || ret_clause.limit.is_some();
+            if has_modifiers && !is_last {
+                return Err(TransformError::InvalidQuery(
+                    "ORDER BY, SKIP and LIMIT are only allowed after the last UNION arm"
+                        .to_string(),
+                ));
+            }
 
-        // Create the SELECT statement
-        let mut body = SetExpr::Select(Box::new(Select {
-            distinct: return_clause
-                .as_ref()
-                .map(|ret| if ret.distinct { Some(Distinct::Distinct) } else { None })
-                .flatten(),
-            top: None,
-            projection,
-            into: None,
-            from: from_tables,
-            lateral_views: vec![],
-            prewhere: None,
-            selection: final_where,
-            group_by: GroupByExpr::Expressions(vec![], vec![]),
-            cluster_by: vec![],
-            distribute_by: vec![],
-            sort_by: vec![],
-            having: None,
-            named_window: vec![],
-            qualify: None,
-            window_before_qualify: false,
-            value_table_mode: None,
-            connect_by: None,
-        }));
+            // ORDER BY, SKIP and LIMIT of the last arm apply to the whole union
+            let arm_return = ReturnClause {
+                order_by: vec![],
+                limit: None,
+                skip: None,
+                ..ret_clause.clone()
+            };
+            let arm_return = Some(arm_return);
+            let arm = self.match_query(
+                patterns,
+                optional_matches,
+                r#where,
+                with,
+                &arm_return,
+                selects,
+            )?;
+            body = Some(match body {
+                None => *arm.body,
+                Some(left) => SetExpr::SetOperation {
+                    op: SetOperator::Union,
+                    set_quantifier: if all {
+                        SetQuantifier::All
+                    } else {
+                        SetQuantifier::None
+                    },
+                    left: Box::new(left),
+                    right: arm.body,
+                },
+            });
 
-        // Add joins to the SELECT
-        if !joins.is_empty() {
-            if let SetExpr::Select(ref mut select) = body {
-                for join in joins {
-                    if let Some(last_table) = select.from.last_mut() {
-                        last_table.joins.push(join);
-                    }
-                }
+            if is_last {
+                order_by = self.union_order_by(ret_clause)?;
+                last_return = Some(ret_clause.clone());
             }
         }
 
-        let query = Query {
+        let Some(body) = body else {
+            return Err(TransformError::InvalidQuery(
+                "UNION without any queries".to_string(),
+            ));
+        };
+
+        let mut query = Query {
             with: None,
             body: Box::new(body),
             order_by,
-            limit,
-            limit_by: vec![],
-            offset,
+            limit_clause: None,
             fetch: None,
             locks: vec![],
             for_clause: None,
             settings: None,
             format_clause: None,
+            pipe_operators: vec![],
         };
-
+        if let Some(ret) = &last_return {
+            self.paginate(&mut query, ret)?;
+        }
         Ok(vec![Statement::Query(Box::new(query))])
     }
 
-    /// Process a pattern for MATCH statement
-    fn process_pattern_for_match(
+    /// Resolve the ORDER BY keys of the last UNION arm against its returned
+    /// columns, since a SQL UNION can only be ordered by its output columns
+    fn union_order_by(
         &self,
-        pattern: &Pattern,
-        from_tables: &mut Vec<TableWithJoins>,
-        joins: &mut Vec<Join>,
-        where_conditions: &mut Vec<Expr>,
-    ) -> Result<(), String> {
-        for (i, element) in pattern.elements.iter().enumerate() {
-            match element {
-                PatternElement::Node { variable, labels, properties } => {
-                    self.process_node_for_match(variable, labels, properties, from_tables, where_conditions)?;
+        return_clause: &ReturnClause,
+    ) -> Result<Option<OrderBy>, TransformError> {
+        if return_clause.order_by.is_empty() {
+            return Ok(None);
+        }
+
+        let mut exprs = vec![];
+        for order_by_expr in &return_clause.order_by {
+            let column = return_clause
+                .items
+                .iter()
+                .enumerate()
+                .find(|(_, item)| match item {
+                    SelectItem::ExprWithAlias { expr, alias } => {
+                        *expr == order_by_expr.expr
+                            || matches!(&order_by_expr.expr, Expr::Identifier(ident) if ident.value == alias.value)
+                    }
+                    SelectItem::UnnamedExpr(expr) => *expr == order_by_expr.expr,
+                    _ => false,
+                });
+            let expr = match column {
+                Some((_, SelectItem::ExprWithAlias { alias, .. })) => {
+                    Expr::Identifier(alias.clone())
                 }
-                PatternElement::Relationship { variable, types, properties, direction, length } => {
-                    self.process_relationship_for_match(
-                        variable, types, properties, direction, length,
-                        joins, where_conditions, i
-                    )?;
+                // Unaliased columns are referenced by position
+                Some((i, _)) => number_literal(i + 1),
+                None => {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "ORDER BY after UNION must refer to a returned column, found {}",
+                        order_by_expr.expr
+                    )))
                 }
-            }
+            };
+            exprs.push(OrderByExpr {
+                expr,
+                ..order_by_expr.clone()
+            });
         }
-        Ok(())
+
+        Ok(Some(OrderBy {
+            kind: OrderByKind::Expressions(exprs),
+            interpolate: None,
+        }))
     }
 
-    /// Process a node in MATCH pattern
-    fn process_node_for_match(
+    /// Transform SHOW DATABASES and SHOW INDEXES to queries of the catalog of
+    /// the database, which yield the columns of [`Self::catalog_query`]
+    fn transform_show(
         &self,
-        variable: &Option<Ident>,
-        labels: &[Ident],
-        properties: &Option<Expr>,
-        from_tables: &mut Vec<TableWithJoins>,
-        where_conditions: &mut Vec<Expr>,
-    ) -> Result<(), String> {
-        // Choose table based on configuration and labels
-        let table_name = if self.config.use_label_tables && !labels.is_empty() {
-            // Use the first label as the table name (simplified approach)
-            labels[0].value.clone()
+        what: ShowTarget,
+        yield_items: &[SelectItem],
+        where_clause: &Option<Expr>,
+    ) -> Result<Vec<Statement>, TransformError> {
+        let Some((query, columns)) = self.catalog_query(what) else {
+            return Err(TransformError::Unsupported(format!("SHOW {what}")));
+        };
+        if yield_items.is_empty() && where_clause.is_none() {
+            return Ok(vec![Statement::Query(Box::new(query))]);
+        }
+
+        let unknown_column = |expr: &Expr| {
+            TransformError::Unsupported(format!(
+                "{expr} in SHOW {what}, which yields only {}",
+                columns.join(", ")
+            ))
+        };
+        let is_column = |expr: &Expr| matches!(expr, Expr::Identifier(ident) if columns.contains(&ident.value.as_str()));
+        let mut aliases = vec![];
+        for item in yield_items {
+            match item {
+                SelectItem::Wildcard(_) => {}
+                SelectItem::UnnamedExpr(expr) if is_column(expr) => {}
+                SelectItem::ExprWithAlias { expr, alias } if is_column(expr) => {
+                    aliases.push((alias, expr));
+                }
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    return Err(unknown_column(expr))
+                }
+                _ => {
+                    return Err(unknown_column(&Expr::Identifier(Ident::new(
+                        item.to_string(),
+                    ))))
+                }
+            }
+        }
+        let projection = if yield_items.is_empty() {
+            vec![SelectItem::Wildcard(WildcardAdditionalOptions::default())]
         } else {
-            self.config.node_table.clone()
+            yield_items.to_vec()
         };
 
-        let table_factor = TableFactor::Table {
-            name: ObjectName(vec![Ident::new(table_name)]),
-            alias: variable.as_ref().map(|v| TableAlias {
-                name: v.clone(),
+        // WHERE filters the yielded rows, by column or alias
+        let selection = match where_clause {
+            Some(condition) => {
+                let condition = substitute_aliases(condition, &aliases);
+                let mut pending = vec![&condition];
+                while let Some(expr) = pending.pop() {
+                    match expr {
+                        Expr::Identifier(_) if !is_column(expr) => {
+                            return Err(unknown_column(expr));
+                        }
+                        Expr::CompoundIdentifier(_) => return Err(unknown_column(expr)),
+                        _ => pending.extend(subexpressions(expr)),
+                    }
+                }
+                Some(self.rewrite_expr(&condition, &GraphPlan::default()))
+            }
+            None => None,
+        };
+        let relation = TableFactor::Derived {
+            lateral: false,
+            subquery: Box::new(query),
+            alias: Some(TableAlias {
+                name: Ident::new(what.to_string().to_lowercase()),
                 columns: vec![],
             }),
-            args: None,
-            with_hints: vec![],
+        };
+        Ok(vec![Statement::Query(Box::new(select_from(
+            projection, relation, selection,
+        )))])
+    }
+
+    /// The query of the catalog of the database listing `what`, with the
+    /// columns it yields: the `name` of each database, or the `name` and
+    /// `table_name` of each index; `None` for other listings
+    fn catalog_query(&self, what: ShowTarget) -> Option<(Query, &'static [&'static str])> {
+        let column = |name: &str, alias: Option<&str>| match alias {
+            Some(alias) => SelectItem::ExprWithAlias {
+                expr: Expr::Identifier(Ident::new(name)),
+                alias: Ident::new(alias),
+            },
+            None => SelectItem::UnnamedExpr(Expr::Identifier(Ident::new(name))),
+        };
+        let table = |name: &[&str], function: bool| {
+            let name = ObjectName::from(
+                name.iter()
+                    .map(|part| Ident::new(*part))
+                    .collect::<Vec<_>>(),
+            );
+            named_table(name, function)
+        };
+        let equals = |left: Expr, right: Expr| Expr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::Eq,
+            right: Box::new(right),
+        };
+        let identifier = |name: &str| Expr::Identifier(Ident::new(name));
+        let string = |s: &str| Expr::value(Value::SingleQuotedString(s.to_string()));
+
+        let (projection, relation, selection) = match (what, self.config.target) {
+            (ShowTarget::Databases, SqlTarget::Sqlite) => (
+                vec![column("name", None)],
+                table(&["pragma_database_list"], false),
+                None,
+            ),
+            (ShowTarget::Databases, SqlTarget::Postgres) => (
+                vec![column("datname", Some("name"))],
+                table(&["pg_database"], false),
+                Some(Expr::UnaryOp {
+                    op: UnaryOperator::Not,
+                    expr: Box::new(identifier("datistemplate")),
+                }),
+            ),
+            // DataFusion lists its schemas when its information_schema is
+            // enabled
+            (ShowTarget::Databases, SqlTarget::MySql | SqlTarget::DataFusion) => (
+                vec![column("schema_name", Some("name"))],
+                table(&["information_schema", "schemata"], false),
+                None,
+            ),
+            (ShowTarget::Databases, SqlTarget::DuckDb) => (
+                vec![column("database_name", Some("name"))],
+                table(&["duckdb_databases"], true),
+                None,
+            ),
+            (ShowTarget::Indexes, SqlTarget::Sqlite) => (
+                vec![column("name", None), column("tbl_name", Some("table_name"))],
+                table(&["sqlite_master"], false),
+                Some(equals(identifier("type"), string("index"))),
+            ),
+            (ShowTarget::Indexes, SqlTarget::Postgres) => (
+                vec![
+                    column("indexname", Some("name")),
+                    column("tablename", Some("table_name")),
+                ],
+                table(&["pg_indexes"], false),
+                Some(equals(
+                    identifier("schemaname"),
+                    function_call("current_schema", vec![]),
+                )),
+            ),
+            // A row for each column of each index of every database
+            (ShowTarget::Indexes, SqlTarget::MySql) => (
+                vec![
+                    column("index_name", Some("name")),
+                    column("table_name", None),
+                ],
+                table(&["information_schema", "statistics"], false),
+                Some(Expr::BinaryOp {
+                    left: Box::new(equals(
+                        identifier("table_schema"),
+                        function_call("database", vec![]),
+                    )),
+                    op: BinaryOperator::And,
+                    right: Box::new(equals(identifier("seq_in_index"), number_literal(1))),
+                }),
+            ),
+            (ShowTarget::Indexes, SqlTarget::DuckDb) => (
+                vec![
+                    column("index_name", Some("name")),
+                    column("table_name", None),
+                ],
+                table(&["duckdb_indexes"], true),
+                None,
+            ),
+            _ => return None,
+        };
+        let columns: &[&str] = match what {
+            ShowTarget::Indexes => &["name", "table_name"],
+            _ => &["name"],
+        };
+        Some((select_from(projection, relation, selection), columns))
+    }
+
+    /// Build the SELECT query of a MATCH statement, adding its bindings to
+    /// `selects`
+    fn match_query(
+        &self,
+        patterns: &[Pattern],
+        optional_matches: &[OptionalMatch],
+        where_clause: &Option<Expr>,
+        with_clause: &Option<WithClause>,
+        return_clause: &Option<ReturnClause>,
+        selects: &mut Vec<Vec<Binding>>,
+    ) -> Result<Query, TransformError> {
+        // The rows a write reads are still joined
+        if let (Some(graph), Some(ret)) = (&self.config.property_graph, return_clause) {
+            return self.graph_table_query(
+                graph,
+                patterns,
+                optional_matches,
+                where_clause,
+                with_clause,
+                ret,
+            );
+        }
+        if let Some(with) = with_clause.as_ref().filter(|with| has_row_modifiers(with)) {
+            let mut returned = CypherStatement::match_([]);
+            if let Some(ret) = return_clause {
+                returned = returned.return_(ret.clone());
+            }
+            return self.with_query(patterns, where_clause, with, &returned, selects);
+        }
+        let folded = match with_clause {
+            Some(with) => Some(self.fold_with(patterns, where_clause, with, return_clause)?),
+            None => None,
+        };
+        let (where_clause, return_clause, having) = match &folded {
+            Some(folded) => (&folded.r#where, &folded.r#return, &folded.having[..]),
+            None => (where_clause, return_clause, &[][..]),
+        };
+
+        let return_exprs = return_clause.iter().flat_map(|ret| {
+            ret.items
+                .iter()
+                .filter_map(|item| match item {
+                    SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                        Some(expr)
+                    }
+                    _ => None,
+                })
+                .chain(ret.order_by.iter().map(|key| &key.expr))
+                .chain(&ret.skip)
+                .chain(&ret.limit)
+        });
+        let optional_where = optional_matches.iter().flat_map(|clause| clause.r#where);
+        let optional_patterns = optional_matches.iter().flat_map(|clause| clause.patterns);
+        let exprs = where_clause
+            .iter()
+            .chain(
+                patterns
+                    .iter()
+                    .chain(optional_patterns)
+                    .flat_map(Pattern::properties),
+            )
+            .chain(optional_where)
+            .chain(having)
+            .chain(return_exprs)
+            .collect::<Vec<_>>();
+        for &expr in &exprs {
+            if expression_depth(expr) > self.config.recursion_limit {
+                return Err(TransformError::RecursionLimitExceeded);
+            }
+            if let Some(comprehension) = find_pattern_comprehension(expr) {
+                return Err(TransformError::Unsupported(format!(
+                    "pattern comprehension {comprehension}"
+                )));
+            }
+            if let Some(access) = find_field_access(expr) {
+                return Err(TransformError::Unsupported(format!(
+                    "property access {access}"
+                )));
+            }
+        }
+
+        // The patterns of the OPTIONAL MATCH clauses follow those of the
+        // MATCH, so the bindings each clause adds follow those before it
+        let all_patterns: Vec<Pattern>;
+        let plan_patterns = if optional_matches.is_empty() {
+            patterns
+        } else {
+            all_patterns = patterns
+                .iter()
+                .chain(optional_matches.iter().flat_map(|clause| clause.patterns))
+                .cloned()
+                .collect();
+            &all_patterns
+        };
+        let mut plan = GraphPlan::build(plan_patterns, where_clause, &self.config);
+        for expr in exprs {
+            self.check_predicates(expr, &plan)?;
+        }
+        let mut path_ends = vec![patterns.len()];
+        for clause in optional_matches {
+            path_ends.push(path_ends[path_ends.len() - 1] + clause.patterns.len());
+        }
+        let binding_ends: Vec<usize> = path_ends
+            .iter()
+            .map(|&end| bindings_before(&plan, end))
+            .collect();
+        let clause_of = |binding: usize| binding_ends.iter().rposition(|&end| end <= binding);
+        for (i, binding) in plan.bindings.iter_mut().enumerate() {
+            binding.optional = clause_of(i).is_some();
+        }
+
+        let property_conditions = self.property_map_conditions(plan_patterns, &plan)?;
+        let mut where_conditions = vec![];
+        let mut optional_conditions = vec![vec![]; optional_matches.len()];
+        for (i, binding) in plan.bindings.iter().enumerate() {
+            let mut element_conditions = vec![];
+            match binding.kind {
+                BindingKind::Node => self.process_node_for_match(binding, &mut element_conditions),
+                BindingKind::Relationship => {
+                    self.process_relationship_for_match(binding, &mut element_conditions)
+                }
+            }
+            element_conditions.extend(
+                property_conditions
+                    .iter()
+                    .filter(|(binding, _)| *binding == i)
+                    .map(|(_, condition)| condition.clone()),
+            );
+            match clause_of(i) {
+                Some(clause) => optional_conditions[clause].extend(element_conditions),
+                None => where_conditions.extend(
+                    element_conditions
+                        .into_iter()
+                        .map(|condition| (ConditionOrder::Element(i), condition)),
+                ),
+            }
+        }
+        let mut endpoint_conditions = vec![];
+        let mut from_tables = self.match_from(
+            &plan,
+            &plan.paths[..patterns.len()],
+            &mut endpoint_conditions,
+        );
+        where_conditions.extend(
+            endpoint_conditions
+                .into_iter()
+                .map(|condition| (ConditionOrder::Endpoint, condition)),
+        );
+        // Each clause binds a relationship at most once
+        let relationships = |bindings: core::ops::Range<usize>| {
+            bindings
+                .filter(|&binding| plan.bindings[binding].kind == BindingKind::Relationship)
+                .collect::<Vec<_>>()
+        };
+        where_conditions.extend(
+            self.relationship_uniqueness(&plan, &relationships(0..binding_ends[0]))
+                .into_iter()
+                .map(|condition| (ConditionOrder::Uniqueness, condition)),
+        );
+        where_conditions.extend(
+            self.node_uniqueness(&plan, &plan.paths[..patterns.len()])
+                .into_iter()
+                .map(|condition| (ConditionOrder::Uniqueness, condition)),
+        );
+        for (k, conditions) in optional_conditions.iter_mut().enumerate() {
+            conditions.extend(self.relationship_uniqueness(
+                &plan,
+                &relationships(binding_ends[k]..binding_ends[k + 1]),
+            ));
+            conditions
+                .extend(self.node_uniqueness(&plan, &plan.paths[path_ends[k]..path_ends[k + 1]]));
+        }
+
+        // Each OPTIONAL MATCH is left joined onto the rows of the MATCH, with
+        // its conditions in the join condition
+        if !optional_matches.is_empty() {
+            // The join condition may refer to the nodes of any FROM item
+            let mut items = from_tables.into_iter();
+            let Some(mut from) = items.next() else {
+                return Err(TransformError::Unsupported(
+                    "OPTIONAL MATCH without a MATCH before it".to_string(),
+                ));
+            };
+            for item in items {
+                from.joins.push(Join {
+                    relation: item.relation,
+                    global: false,
+                    join_operator: JoinOperator::CrossJoin(JoinConstraint::None),
+                });
+                from.joins.extend(item.joins);
+            }
+            for (k, clause) in optional_matches.iter().enumerate() {
+                let added: Vec<usize> = (binding_ends[k]..binding_ends[k + 1])
+                    .filter(|&binding| !plan.bindings[binding].pruned)
+                    .collect();
+                if added.is_empty() {
+                    return Err(TransformError::Unsupported(format!(
+                        "OPTIONAL MATCH {} that binds no new node or relationship",
+                        display_comma_separated(clause.patterns)
+                    )));
+                }
+                let mut conditions = vec![];
+                for path in &plan.paths[path_ends[k]..path_ends[k + 1]] {
+                    conditions.extend(self.path_conditions(&plan, path));
+                }
+                conditions.append(&mut optional_conditions[k]);
+                let where_condition = clause.r#where.as_ref().map(|where_expr| {
+                    self.rewrite_expr(&self.null_safe_condition(where_expr, &plan), &plan)
+                });
+                from.joins
+                    .push(self.optional_join(&plan, &added, conditions, where_condition));
+            }
+            from_tables = vec![from];
+        }
+
+        // Add the WHERE conditions that were not folded into the plan
+        if let Some(where_expr) = &plan.residual_where {
+            let condition = self.null_safe_condition(where_expr, &plan);
+            where_conditions.push((ConditionOrder::Where, self.rewrite_expr(&condition, &plan)));
+        }
+
+        // Build the final WHERE clause, in the order of the policy rather
+        // than the order the conditions were generated in
+        where_conditions.sort_by_key(|(order, _)| *order);
+        let final_where = if where_conditions.is_empty() {
+            None
+        } else {
+            let single = where_conditions.len() == 1;
+            let conditions = where_conditions
+                .into_iter()
+                .map(|(_, condition)| {
+                    if single {
+                        condition
+                    } else {
+                        and_operand(condition)
+                    }
+                })
+                .collect();
+            Some(self.combine_conditions(conditions))
+        };
+
+        // Build projection
+        let projection = if let Some(ret_clause) = return_clause {
+            self.projection(&ret_clause.items, &plan)?
+        } else {
+            vec![SelectItem::Wildcard(WildcardAdditionalOptions::default())]
+        };
+
+        // Build ORDER BY
+        let mut order_by = return_clause
+            .as_ref()
+            .filter(|ret| !ret.order_by.is_empty())
+            .map(|ret| OrderBy {
+                kind: OrderByKind::Expressions(
+                    ret.order_by
+                        .iter()
+                        .map(|order_by_expr| OrderByExpr {
+                            expr: self.order_by_key(
+                                &order_by_expr.expr,
+                                &ret.items,
+                                &projection,
+                                &plan,
+                            ),
+                            ..order_by_expr.clone()
+                        })
+                        .collect(),
+                ),
+                interpolate: None,
+            });
+
+        let mut distinct = return_clause
+            .as_ref()
+            .and_then(|ret| ret.distinct.then_some(Distinct::Distinct));
+        let mut group_by = match &folded {
+            Some(folded) => self.group_by_keys(&folded.group_by, &plan, &projection),
+            None => vec![],
+        };
+        // Without an aggregating WITH, the items of a RETURN that aggregates
+        // which do not aggregate are its grouping keys
+        if let Some(ret) = return_clause.as_ref().filter(|_| group_by.is_empty()) {
+            if let Some(keys) = grouping_keys(ret, &plan) {
+                group_by = self.group_by_keys(&keys, &plan, &projection);
+            }
+        }
+        if let (Some(ret), Some(order_by)) = (return_clause, &mut order_by) {
+            if ret.distinct && !self.config.target.supports_distinct_order_by_unselected() {
+                let distinct_group_by = self.distinct_order_by(ret, &projection, order_by)?;
+                if !distinct_group_by.is_empty() {
+                    if !group_by.is_empty() {
+                        return Err(TransformError::Unsupported(format!(
+                            "RETURN DISTINCT ordered by an expression it does not return \
+                             after an aggregating {}",
+                            with_clause
+                                .as_ref()
+                                .map_or(String::new(), ToString::to_string)
+                        )));
+                    }
+                    group_by = distinct_group_by;
+                    distinct = None;
+                }
+            }
+            if distinct.is_some()
+                && self.config.distinct_on
+                && self.config.target.supports_distinct_on()
+            {
+                if let Some(distinct_on) = self.distinct_on(&projection, order_by) {
+                    distinct = Some(distinct_on);
+                }
+            }
+        }
+
+        // PostgreSQL sorts the properties it reads as text as strings, e.g.
+        // `'10'` before `'9'`, but sorts numbers as numbers in jsonb
+        if let (SqlTarget::Postgres, None, true, Some(order_by)) = (
+            self.config.target,
+            &distinct,
+            group_by.is_empty(),
+            &mut order_by,
+        ) {
+            if let OrderByKind::Expressions(keys) = &mut order_by.kind {
+                for key in keys {
+                    let sorted = match &key.expr {
+                        Expr::Identifier(name) => projection.iter().find_map(|item| match item {
+                            SelectItem::ExprWithAlias { expr, alias }
+                                if alias.value == name.value =>
+                            {
+                                Some(expr)
+                            }
+                            _ => None,
+                        }),
+                        expr => Some(expr),
+                    };
+                    if let Some(sort_key) = sorted.and_then(json_sort_key) {
+                        key.expr = sort_key;
+                    }
+                }
+            }
+        }
+
+        let having = if having.is_empty() {
+            None
+        } else {
+            let conditions = having
+                .iter()
+                .map(|condition| {
+                    self.rewrite_expr(&self.null_safe_condition(condition, &plan), &plan)
+                })
+                .collect();
+            Some(self.combine_conditions(conditions))
+        };
+
+        // Create the SELECT statement
+        let select = Select {
+            select_token: AttachedToken::empty(),
+            distinct,
+            top: None,
+            top_before_distinct: false,
+            projection,
+            exclude: None,
+            into: None,
+            from: from_tables,
+            lateral_views: vec![],
+            prewhere: None,
+            selection: final_where,
+            group_by: GroupByExpr::Expressions(group_by, vec![]),
+            cluster_by: vec![],
+            distribute_by: vec![],
+            sort_by: vec![],
+            having,
+            named_window: vec![],
+            qualify: None,
+            window_before_qualify: false,
+            value_table_mode: None,
+            connect_by: None,
+            flavor: SelectFlavor::Standard,
+        };
+
+        selects.push(
+            plan.bindings
+                .into_iter()
+                .filter(|binding| !binding.pruned)
+                .collect(),
+        );
+        let mut query = Query {
+            with: None,
+            body: Box::new(SetExpr::Select(Box::new(select))),
+            order_by,
+            limit_clause: None,
+            fetch: None,
+            locks: vec![],
+            for_clause: None,
+            settings: None,
+            format_clause: None,
+            pipe_operators: vec![],
+        };
+        if let Some(ret) = return_clause {
+            self.paginate(&mut query, ret)?;
+        }
+
+        Ok(query)
+    }
+
+    /// Fold the WITH clause of a MATCH into a single SELECT: the RETURN
+    /// refers to the expressions WITH aliases, and the WITH condition is
+    /// split into the conditions on aggregates, which go to HAVING, and the
+    /// others, which go to WHERE
+    ///
+    /// A condition on the items a WITH groups by can be checked before
+    /// grouping, since it holds for every row of a group or for none.
+    fn fold_with(
+        &self,
+        patterns: &[Pattern],
+        where_clause: &Option<Expr>,
+        with: &WithClause,
+        return_clause: &Option<ReturnClause>,
+    ) -> Result<FoldedWith, TransformError> {
+        let projection = &with.projection;
+        let pattern_variables: Vec<&Ident> = patterns
+            .iter()
+            .flat_map(|pattern| &pattern.elements)
+            .filter_map(PatternElement::variable)
+            .collect();
+        let mut variables: Vec<&Ident> = vec![];
+        let mut aliases: Vec<(&Ident, &Expr)> = vec![];
+        for item in &projection.items {
+            match item {
+                SelectItem::UnnamedExpr(Expr::Identifier(variable)) => variables.push(variable),
+                SelectItem::ExprWithAlias { expr, alias } => aliases.push((alias, expr)),
+                SelectItem::UnnamedExpr(expr) => {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "Expression in WITH must be aliased (use AS): {expr}"
+                    )))
+                }
+                _ => variables.extend(&pattern_variables),
+            }
+        }
+        let aggregating = aliases.iter().any(|(_, expr)| contains_aggregate(expr));
+
+        // Only the variables and aliases of the WITH are visible after it
+        if let Some(ret) = return_clause {
+            for expr in ret.exprs() {
+                for variable in referenced_variables(expr) {
+                    let visible = variables.iter().any(|v| v.value == variable.value)
+                        || aliases
+                            .iter()
+                            .any(|(alias, _)| alias.value == variable.value);
+                    if !visible && pattern_variables.contains(&variable) {
+                        return Err(TransformError::InvalidQuery(format!(
+                            "Variable `{variable}` not defined after {with}"
+                        )));
+                    }
+                }
+            }
+        }
+
+        let mut conditions: Vec<Expr> = where_clause.iter().cloned().map(and_operand).collect();
+        let mut having = vec![];
+        for condition in with.r#where.iter().flat_map(split_conjunction) {
+            let condition = and_operand(substitute_aliases(condition, &aliases));
+            if contains_aggregate(&condition) {
+                having.push(condition);
+            } else {
+                conditions.push(condition);
+            }
+        }
+        let r#where = conditions.into_iter().reduce(|left, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::And,
+            right: Box::new(right),
+        });
+
+        let r#return = match return_clause {
+            Some(ret) => {
+                let items = ret
+                    .items
+                    .iter()
+                    .map(|item| match item {
+                        // An alias returned as is keeps its name
+                        SelectItem::UnnamedExpr(Expr::Identifier(ident))
+                            if aliases.iter().any(|(alias, _)| alias.value == ident.value) =>
+                        {
+                            SelectItem::ExprWithAlias {
+                                expr: substitute_aliases(
+                                    &Expr::Identifier(ident.clone()),
+                                    &aliases,
+                                ),
+                                alias: ident.clone(),
+                            }
+                        }
+                        SelectItem::UnnamedExpr(expr) => {
+                            SelectItem::UnnamedExpr(substitute_aliases(expr, &aliases))
+                        }
+                        SelectItem::ExprWithAlias { expr, alias } => SelectItem::ExprWithAlias {
+                            expr: substitute_aliases(expr, &aliases),
+                            alias: alias.clone(),
+                        },
+                        _ => item.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                if items
+                    .iter()
+                    .any(|item| matches!(item, SelectItem::Wildcard(_)))
+                {
+                    return Err(TransformError::Unsupported(format!(
+                        "RETURN * after {with}"
+                    )));
+                }
+                let order_by = ret
+                    .order_by
+                    .iter()
+                    .map(|key| OrderByExpr {
+                        expr: substitute_aliases(&key.expr, &aliases),
+                        ..key.clone()
+                    })
+                    .collect();
+                Some(ReturnClause {
+                    items,
+                    order_by,
+                    ..ret.clone()
+                })
+            }
+            None => None,
+        };
+
+        let mut group_by = vec![];
+        if aggregating {
+            if return_clause
+                .iter()
+                .flat_map(ReturnClause::exprs)
+                .any(contains_aggregate)
+            {
+                return Err(TransformError::Unsupported(format!(
+                    "aggregation in a RETURN after the aggregating {with}"
+                )));
+            }
+            group_by.extend(
+                variables
+                    .into_iter()
+                    .map(|variable| Expr::Identifier(variable.clone())),
+            );
+            group_by.extend(
+                aliases
+                    .iter()
+                    .filter(|(_, expr)| !contains_aggregate(expr))
+                    .map(|(_, expr)| (*expr).clone()),
+            );
+        }
+
+        Ok(FoldedWith {
+            r#where,
+            r#return,
+            having,
+            group_by,
+        })
+    }
+
+    /// Build the SELECT query of a MATCH whose WITH orders, skips, limits or
+    /// makes distinct the rows it passes on, or passes them on to the MATCH
+    /// of `next`, which has the RETURN
+    ///
+    /// The MATCH up to the WITH becomes a subquery in FROM, which returns the
+    /// id of each node the WITH passes on and the value of each of its other
+    /// aliases. `next` matches those nodes again by id and reads the aliases
+    /// from the subquery, whose rows the condition of the WITH filters.
+    fn with_query(
+        &self,
+        patterns: &[Pattern],
+        where_clause: &Option<Expr>,
+        with: &WithClause,
+        next: &CypherStatement,
+        selects: &mut Vec<Vec<Binding>>,
+    ) -> Result<Query, TransformError> {
+        // The MATCH clauses after the WITH match their patterns together
+        let merged = merge_reading_clauses(next)?;
+        let next = match &merged {
+            Some((_, optional_matches)) if !optional_matches.is_empty() => {
+                return Err(TransformError::Unsupported(format!(
+                    "OPTIONAL MATCH after a MATCH after {with}"
+                )));
+            }
+            Some((statement, _)) => statement,
+            None => next,
+        };
+        let CypherStatement::Match {
+            optional: _,
+            patterns: next_patterns,
+            r#where: next_where,
+            with: next_with,
+            r#return: next_return,
+            next: later,
+        } = next
+        else {
+            return Err(TransformError::Unsupported(format!("{next} after {with}")));
+        };
+        if let Some(next_with) = next_with
+            .as_ref()
+            .filter(|next_with| later.is_some() || has_row_modifiers(next_with))
+        {
+            return Err(TransformError::Unsupported(format!(
+                "{next_with} after {with}"
+            )));
+        }
+
+        let plan = GraphPlan::build(patterns, where_clause, &self.config);
+        let carried = carried_nodes(with, &plan)?;
+        let rows = Ident::new(WITH_ROWS_ALIAS);
+
+        // The subquery returns the id of each node, named after the variable
+        // it is passed on as, and the other aliases
+        let projection = &with.projection;
+        let mut items = vec![];
+        for (variable, _) in &carried {
+            items.push(SelectItem::ExprWithAlias {
+                expr: function_call("id", vec![Expr::Identifier((*variable).clone())]),
+                alias: (*variable).clone(),
+            });
+        }
+        let mut columns = vec![];
+        for item in &projection.items {
+            if let SelectItem::ExprWithAlias { alias, .. } = item {
+                if !carried.iter().any(|(variable, _)| *variable == alias) {
+                    items.push(SelectItem::UnnamedExpr(Expr::Identifier(alias.clone())));
+                    columns.push((
+                        alias,
+                        Expr::CompoundIdentifier(vec![rows.clone(), alias.clone()]),
+                    ));
+                }
+            }
+        }
+        let grouping = WithClause {
+            projection: ReturnClause {
+                distinct: false,
+                order_by: vec![],
+                skip: None,
+                limit: None,
+                ..projection.clone()
+            },
+            r#where: None,
+        };
+        let rows_return = ReturnClause {
+            items,
+            ..projection.clone()
+        };
+        let mut rows_selects = vec![];
+        let rows_query = self.match_query(
+            patterns,
+            &[],
+            where_clause,
+            &Some(grouping),
+            &Some(rows_return),
+            &mut rows_selects,
+        )?;
+
+        // The aliases of the WITH are columns of the subquery after it, and
+        // an alias returned as is keeps its name
+        let aliases: Vec<(&Ident, &Expr)> = columns
+            .iter()
+            .map(|(alias, column)| (*alias, column))
+            .collect();
+        let name_aliases = |clause: &mut ReturnClause| {
+            for item in &mut clause.items {
+                if let SelectItem::UnnamedExpr(Expr::Identifier(ident)) = item {
+                    if aliases.iter().any(|(alias, _)| alias.value == ident.value) {
+                        *item = SelectItem::ExprWithAlias {
+                            expr: Expr::Identifier(ident.clone()),
+                            alias: ident.clone(),
+                        };
+                    }
+                }
+            }
+        };
+        let mut next_with = next_with.clone();
+        let mut next_return = next_return.clone();
+        next_with
+            .iter_mut()
+            .for_each(|with| name_aliases(&mut with.projection));
+        next_return.iter_mut().for_each(name_aliases);
+        let mut next_patterns = continued_patterns(&carried, next_patterns);
+        let mut next_where = with
+            .r#where
+            .iter()
+            .chain(next_where)
+            .cloned()
+            .map(and_operand)
+            .reduce(|left, right| Expr::BinaryOp {
+                left: Box::new(left),
+                op: BinaryOperator::And,
+                right: Box::new(right),
+            });
+
+        let bound: Vec<String> = matched_variables(&next_patterns)
+            .into_iter()
+            .map(|variable| variable.value.clone())
+            .collect();
+        let mut exprs: Vec<&mut Expr> = next_patterns
+            .iter_mut()
+            .flat_map(Pattern::properties_mut)
+            .collect();
+        exprs.extend(&mut next_where);
+        if let Some(next_with) = &mut next_with {
+            exprs.extend(next_with.projection.exprs_mut());
+            exprs.extend(&mut next_with.r#where);
+        }
+        exprs.extend(next_return.iter_mut().flat_map(ReturnClause::exprs_mut));
+        for expr in exprs {
+            *expr = substitute_aliases(expr, &aliases);
+            // Only the variables and aliases of the WITH are visible after it
+            for variable in referenced_variables(expr) {
+                if plan.binding(&variable.value).is_some() && !bound.contains(&variable.value) {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "Variable `{variable}` not defined after {with}"
+                    )));
+                }
+            }
+        }
+
+        let mut query = self.match_query(
+            &next_patterns,
+            &[],
+            &next_where,
+            &next_with,
+            &next_return,
+            selects,
+        )?;
+        selects.extend(rows_selects);
+        if let SetExpr::Select(select) = query.body.as_mut() {
+            let mut conditions: Vec<Expr> = carried
+                .iter()
+                .map(|(variable, _)| Expr::BinaryOp {
+                    left: Box::new(Expr::CompoundIdentifier(vec![
+                        (*variable).clone(),
+                        Ident::new(&self.config.id_column),
+                    ])),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(Expr::CompoundIdentifier(vec![
+                        rows.clone(),
+                        (*variable).clone(),
+                    ])),
+                })
+                .collect();
+            conditions.extend(select.selection.take().map(and_operand));
+            select.selection =
+                (!conditions.is_empty()).then(|| self.combine_conditions(conditions));
+            select.from.insert(
+                0,
+                TableWithJoins {
+                    relation: TableFactor::Derived {
+                        lateral: false,
+                        subquery: Box::new(rows_query),
+                        alias: Some(TableAlias {
+                            name: rows,
+                            columns: vec![],
+                        }),
+                    },
+                    joins: vec![],
+                },
+            );
+        }
+        Ok(query)
+    }
+
+    /// The SQL expressions to group by for the grouping keys of a WITH or
+    /// the items of a RETURN that do not aggregate, where a variable stands
+    /// for the columns it is projected to
+    ///
+    /// A key the `projection` selects is its projected expression, or the
+    /// alias of the column where repeating the expression would bind its
+    /// parameters again, which `?` placeholders cannot share.
+    fn group_by_keys(
+        &self,
+        keys: &[Expr],
+        plan: &GraphPlan,
+        projection: &[SelectItem],
+    ) -> Vec<Expr> {
+        let projected = |key: Expr| {
+            let item = projection.iter().find(|item| match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    *expr == key
+                }
+                _ => false,
+            });
+            match item {
+                Some(item) => self.group_by_column(item),
+                None => key,
+            }
+        };
+        let mut group_by = vec![];
+        for key in keys {
+            let binding = match key {
+                Expr::Identifier(variable) => plan.binding(&variable.value),
+                _ => None,
+            };
+            let Some(binding) = binding else {
+                group_by.push(projected(self.rewrite_expr(key, plan)));
+                continue;
+            };
+            for column in self.variable_columns(binding, &binding.table_ref()) {
+                match column {
+                    SelectItem::ExprWithAlias { expr, .. } => group_by.push(projected(expr)),
+                    // The columns of a label table are functionally dependent
+                    // on its id
+                    _ => group_by.push(Expr::CompoundIdentifier(vec![
+                        binding.table_ref(),
+                        Ident::new(&self.config.id_column),
+                    ])),
+                }
+            }
+        }
+        group_by
+    }
+
+    /// The expression to group by for a projection column: its alias, where
+    /// the target resolves it and the column has parameters, or its
+    /// expression
+    fn group_by_column(&self, item: &SelectItem) -> Expr {
+        match item {
+            SelectItem::ExprWithAlias { expr, alias }
+                if self.config.target.supports_alias_in_group_by() && contains_parameter(expr) =>
+            {
+                Expr::Identifier(alias.clone())
+            }
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => expr.clone(),
+            _ => unreachable!("only expressions are grouped by"),
+        }
+    }
+
+    /// Build the projection of RETURN items: whole variables are projected
+    /// from the columns of their bindings and property accesses are rewritten, with
+    /// a unique alias for each property read from JSON
+    fn projection(
+        &self,
+        items: &[SelectItem],
+        plan: &GraphPlan,
+    ) -> Result<Vec<SelectItem>, TransformError> {
+        let mut projection = vec![];
+        // Properties read from JSON have no column name of their own
+        let mut json_properties = vec![];
+        for item in items {
+            match item {
+                SelectItem::Wildcard(_) if self.config.expand_return_star => {
+                    // Anonymous pattern elements are not part of `*`
+                    for binding in &plan.bindings {
+                        if let Some(variable) = &binding.variable {
+                            projection.extend(self.variable_projection(binding, variable));
+                        }
+                    }
+                }
+                SelectItem::UnnamedExpr(Expr::Identifier(variable)) => {
+                    match plan.binding(&variable.value) {
+                        Some(binding) => {
+                            projection.extend(self.variable_projection(binding, variable))
+                        }
+                        None if plan.path(&variable.value).is_some() => {
+                            projection.push(SelectItem::ExprWithAlias {
+                                expr: self.rewrite_expr(&Expr::Identifier(variable.clone()), plan),
+                                alias: variable.clone(),
+                            })
+                        }
+                        None => projection.push(item.clone()),
+                    }
+                }
+                SelectItem::ExprWithAlias {
+                    expr: Expr::Identifier(variable),
+                    alias,
+                } => match plan.binding(&variable.value) {
+                    Some(binding) => projection.extend(self.variable_projection(binding, alias)),
+                    None if plan.path(&variable.value).is_some() => {
+                        projection.push(SelectItem::ExprWithAlias {
+                            expr: self.rewrite_expr(&Expr::Identifier(variable.clone()), plan),
+                            alias: alias.clone(),
+                        })
+                    }
+                    None => projection.push(item.clone()),
+                },
+                SelectItem::UnnamedExpr(expr) => {
+                    if let Some(property) = json_property(expr, plan) {
+                        json_properties.push((projection.len(), property));
+                    }
+                    projection.push(SelectItem::UnnamedExpr(self.rewrite_expr(expr, plan)));
+                }
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    projection.push(SelectItem::ExprWithAlias {
+                        expr: self.rewrite_expr(expr, plan),
+                        alias: alias.clone(),
+                    });
+                }
+                _ => projection.push(item.clone()),
+            }
+        }
+
+        // The columns of label tables are only known to the database, and
+        // those of two tables, e.g. their ids, would share names
+        let wildcards: Vec<String> = projection
+            .iter()
+            .filter_map(|item| match item {
+                SelectItem::QualifiedWildcard(
+                    SelectItemQualifiedWildcardKind::ObjectName(name),
+                    _,
+                ) => Some(name.to_string()),
+                _ => None,
+            })
+            .collect();
+        if let [first @ .., last] = &wildcards[..] {
+            if !first.is_empty() {
+                return Err(TransformError::Unsupported(format!(
+                    "returning {} and {last} whole from label tables, whose columns may share names",
+                    first.join(", ")
+                )));
+            }
+        }
+
+        let mut names = vec![];
+        for item in &projection {
+            if let SelectItem::ExprWithAlias { alias, .. } = item {
+                if names.contains(&alias.value) {
+                    return Err(duplicate_column(&alias.value));
+                }
+                names.push(alias.value.clone());
+            }
+        }
+        // Columns projected as is are named by the database after the
+        // column, e.g. `name` for a label table's `n.name`
+        for item in &projection {
+            match item {
+                SelectItem::UnnamedExpr(Expr::CompoundIdentifier(idents)) => {
+                    names.extend(idents.last().map(|ident| ident.value.clone()))
+                }
+                SelectItem::UnnamedExpr(Expr::Identifier(ident)) => names.push(ident.value.clone()),
+                _ => {}
+            }
+        }
+
+        // A property is named after itself unless another column uses the
+        // same name, in which case it is qualified by its variable
+        for (i, (variable, property)) in &json_properties {
+            let shared = json_properties
+                .iter()
+                .filter(|(_, (_, other))| other == property)
+                .count()
+                > 1;
+            let alias = if shared || names.contains(property) {
+                format!("{}_{property}", variable.value)
+            } else {
+                property.clone()
+            };
+            if names.contains(&alias) {
+                return Err(duplicate_column(&alias));
+            }
+            names.push(alias.clone());
+
+            let SelectItem::UnnamedExpr(expr) = &projection[*i] else {
+                unreachable!("JSON properties are unnamed projection items");
+            };
+            // A key such as `weird.key` is not a bare SQL name
+            let alias = if is_plain_key(&alias) {
+                Ident::new(alias)
+            } else {
+                Ident::with_quote(self.config.target.identifier_quote(), alias)
+            };
+            projection[*i] = SelectItem::ExprWithAlias {
+                expr: expr.clone(),
+                alias,
+            };
+        }
+
+        Ok(projection)
+    }
+
+    /// Rewrite an ORDER BY key, resolving it against the RETURN `items`
+    ///
+    /// A key naming a returned alias is kept as is, and a key that is
+    /// rewritten to an aliased projection column refers to that alias.
+    /// Aliases used within a larger key are replaced by the expressions they
+    /// name where the target does not resolve them.
+    fn order_by_key(
+        &self,
+        key: &Expr,
+        items: &[SelectItem],
+        projection: &[SelectItem],
+        plan: &GraphPlan,
+    ) -> Expr {
+        let aliases: Vec<(&Ident, &Expr)> = items
+            .iter()
+            .filter_map(|item| match item {
+                SelectItem::ExprWithAlias { expr, alias } => Some((alias, expr)),
+                _ => None,
+            })
+            .collect();
+        if let Expr::Identifier(ident) = key {
+            if aliases.iter().any(|(alias, _)| alias.value == ident.value) {
+                return key.clone();
+            }
+        }
+
+        let key = if self.config.target.supports_alias_in_order_by_expr() {
+            key.clone()
+        } else {
+            substitute_aliases(key, &aliases)
+        };
+        let expr = self.rewrite_expr(&key, plan);
+        for item in projection {
+            if let SelectItem::ExprWithAlias {
+                expr: projected,
+                alias,
+            } = item
+            {
+                if *projected == expr {
+                    return Expr::Identifier(alias.clone());
+                }
+            }
+        }
+        expr
+    }
+
+    /// Make the ORDER BY of a `RETURN DISTINCT` sort on returned columns,
+    /// returning the expressions to group by instead of `SELECT DISTINCT`
+    /// when it sorts on others, or nothing when it does not
+    fn distinct_order_by(
+        &self,
+        return_clause: &ReturnClause,
+        projection: &[SelectItem],
+        order_by: &mut OrderBy,
+    ) -> Result<Vec<Expr>, TransformError> {
+        let OrderByKind::Expressions(keys) = &mut order_by.kind else {
+            return Ok(vec![]);
+        };
+        let unselected: Vec<usize> = (0..keys.len())
+            .filter(|i| !is_selected(&keys[*i].expr, projection))
+            .collect();
+        let Some(first) = unselected.first() else {
+            return Ok(vec![]);
+        };
+        let sort_key = &return_clause.order_by[*first].expr;
+        if self.config.distinct_order_by == DistinctOrderBy::Reject {
+            return Err(TransformError::InvalidQuery(format!(
+                "ORDER BY {sort_key} sorts on an expression that RETURN DISTINCT does not return"
+            )));
+        }
+
+        let mut group_by = vec![];
+        for item in projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. }
+                    if !contains_aggregate(expr) =>
+                {
+                    group_by.push(self.group_by_column(item))
+                }
+                _ => {
+                    return Err(TransformError::Unsupported(format!(
+                    "ORDER BY {sort_key} on an expression that RETURN DISTINCT {} does not return",
+                    display_comma_separated(&return_clause.items)
+                )))
+                }
+            }
+        }
+        for i in unselected {
+            let aggregate = if keys[i].options.asc == Some(false) {
+                "max"
+            } else {
+                "min"
+            };
+            keys[i].expr = function_call(aggregate, vec![keys[i].expr.clone()]);
+        }
+        Ok(group_by)
+    }
+
+    /// `DISTINCT ON` the `projection` columns, those ORDER BY sorts on first,
+    /// with the others appended to ORDER BY so that it starts with them, or
+    /// nothing when it sorts on other expressions or `projection` has a
+    /// wildcard
+    fn distinct_on(&self, projection: &[SelectItem], order_by: &mut OrderBy) -> Option<Distinct> {
+        let OrderByKind::Expressions(keys) = &mut order_by.kind else {
+            return None;
+        };
+        let mut columns = vec![];
+        for key in keys.iter() {
+            let column = selected_column(&key.expr, projection)?;
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        let mut exprs = vec![];
+        for (i, item) in projection.iter().enumerate() {
+            let (SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. }) = item
+            else {
+                return None;
+            };
+            if !columns.contains(&i) {
+                columns.push(i);
+                keys.push(OrderByExpr {
+                    expr: expr.clone(),
+                    options: OrderByOptions::default(),
+                    with_fill: None,
+                });
+            }
+            exprs.push(expr.clone());
+        }
+        Some(Distinct::On(
+            columns.into_iter().map(|i| exprs[i].clone()).collect(),
+        ))
+    }
+
+    /// What a variable returned whole is projected to: with the generic
+    /// schema a JSON object of its columns aliased as `alias`, e.g.
+    /// `json_object('id', a.id, 'label', a.label, ...) AS a`, and with label
+    /// tables its [columns](Self::variable_columns)
+    fn variable_projection(&self, binding: &Binding, alias: &Ident) -> Vec<SelectItem> {
+        if self.config.use_label_tables {
+            return self.variable_columns(binding, alias);
+        }
+        vec![SelectItem::ExprWithAlias {
+            expr: self.element_object(binding),
+            alias: alias.clone(),
+        }]
+    }
+
+    /// The JSON object of the columns of a pattern element stored in the
+    /// generic tables
+    ///
+    /// The object of an element of an OPTIONAL MATCH is null on the rows
+    /// where the clause does not match, e.g. `CASE WHEN c.id IS NULL THEN
+    /// NULL ELSE json_object(...) END`, rather than an object of nulls. Its
+    /// properties need no such guard, as reading a property of a null JSON
+    /// document or map gives null on every target.
+    fn element_object(&self, binding: &Binding) -> Expr {
+        let columns = self.element_columns(binding);
+        let column =
+            |column: &str| Expr::CompoundIdentifier(vec![binding.table_ref(), Ident::new(column)]);
+        let mut args = vec![];
+        for name in &columns {
+            let mut value = column(name);
+            if *name == "properties" && self.config.target.json_stored_as_text() {
+                value = function_call("json", vec![value]);
+            }
+            args.push(Expr::value(Value::SingleQuotedString(name.to_string())));
+            args.push(value);
+        }
+        let object = function_call(self.config.target.json_object_function(), args);
+        self.null_when_unmatched(binding, object)
+    }
+
+    /// `value` of an element of an OPTIONAL MATCH, as null on the rows where
+    /// the clause does not match, e.g. `CASE WHEN c.id IS NULL THEN NULL ELSE
+    /// <value> END`, or `value` itself for an element of a MATCH
+    fn null_when_unmatched(&self, binding: &Binding, value: Expr) -> Expr {
+        if !binding.optional {
+            return value;
+        }
+        let column = Expr::CompoundIdentifier(vec![
+            binding.table_ref(),
+            Ident::new(self.element_columns(binding)[0]),
+        ]);
+        Expr::Case {
+            case_token: AttachedToken::empty(),
+            end_token: AttachedToken::empty(),
+            operand: None,
+            conditions: vec![CaseWhen {
+                condition: Expr::IsNull(Box::new(column)),
+                result: Expr::value(Value::Null),
+            }],
+            else_result: Some(Box::new(value)),
+        }
+    }
+
+    /// The columns a variable is projected to, aliased with `prefix`, e.g.
+    /// `a.id AS a_id, a.label AS a_label, ...`
+    ///
+    /// The columns of a label table are only known to the database, so a node
+    /// bound to one is projected as `a.*`, and at most one such node can be
+    /// returned whole.
+    fn variable_columns(&self, binding: &Binding, prefix: &Ident) -> Vec<SelectItem> {
+        if binding.kind == BindingKind::Node && binding.label_table {
+            return vec![SelectItem::QualifiedWildcard(
+                SelectItemQualifiedWildcardKind::ObjectName(ObjectName::from(vec![
+                    binding.table_ref()
+                ])),
+                WildcardAdditionalOptions::default(),
+            )];
+        }
+        self.element_columns(binding)
+            .into_iter()
+            .map(|column| SelectItem::ExprWithAlias {
+                expr: Expr::CompoundIdentifier(vec![binding.table_ref(), Ident::new(column)]),
+                alias: Ident::new(format!("{}_{column}", prefix.value)),
+            })
+            .collect()
+    }
+
+    /// The columns of a pattern element stored in the generic tables
+    fn element_columns(&self, binding: &Binding) -> Vec<&str> {
+        match binding.kind {
+            BindingKind::Node => vec![&self.config.id_column, "label", "properties"],
+            BindingKind::Relationship => vec!["from_id", "to_id", "type", "properties"],
+        }
+    }
+
+    /// Page the rows of `query` by the SKIP and LIMIT of `return_clause`
+    ///
+    /// Cypher skips and limits the rows a clause passes on after grouping,
+    /// DISTINCT and ORDER BY, and SQL does the same for the LIMIT and OFFSET
+    /// of a query, so they always go to the outermost query the clause
+    /// became: the SELECT with its GROUP BY, or the UNION of the arms whose
+    /// rows it pages, never one of its subqueries.
+    ///
+    /// Engines take a number or a parameter there, so the SKIP and LIMIT
+    /// values are folded to a literal, e.g. `LIMIT 10 - 2` to `LIMIT 8`,
+    /// see [`page_value`].
+    pub(crate) fn paginate(
+        &self,
+        query: &mut Query,
+        return_clause: &ReturnClause,
+    ) -> Result<(), TransformError> {
+        let target = self.config.target;
+        let skip = page_value("SKIP", return_clause.skip.as_ref())?;
+        let limit = page_value("LIMIT", return_clause.limit.as_ref())?;
+        if self.config.fetch_first && target.supports_fetch_first() {
+            query.limit_clause = skip.map(|skip| LimitClause::LimitOffset {
+                limit: None,
+                offset: Some(Offset {
+                    value: skip,
+                    rows: OffsetRows::Rows,
+                }),
+                limit_by: vec![],
+            });
+            query.fetch = limit.map(|limit| Fetch {
+                with_ties: false,
+                percent: false,
+                quantity: Some(limit),
+            });
+            return Ok(());
+        }
+        let limit = match (limit, &skip) {
+            (None, Some(_)) => target.unbounded_limit(),
+            (limit, _) => limit,
+        };
+        if limit.is_none() && skip.is_none() {
+            return Ok(());
+        }
+        query.limit_clause = Some(LimitClause::LimitOffset {
+            limit,
+            offset: skip.map(|skip| Offset {
+                value: skip,
+                rows: OffsetRows::None,
+            }),
+            limit_by: vec![],
+        });
+        Ok(())
+    }
+
+    /// The conditions of the property maps of `patterns` on the bindings of
+    /// `plan` they constrain, by binding, e.g. `(i, json_extract(n.properties,
+    /// '$.name') = 'Alice')` for `(n {name: 'Alice'})`, read as the WHERE
+    /// condition `n.name = 'Alice'` would be
+    ///
+    /// An anonymous element is referred to by its table, as in the rest of
+    /// the SQL, e.g. `relationships.since = 2020` for `-[{since: 2020}]->`.
+    fn property_map_conditions(
+        &self,
+        patterns: &[Pattern],
+        plan: &GraphPlan,
+    ) -> Result<Vec<(usize, Expr)>, TransformError> {
+        let mut named = plan.clone();
+        for binding in &mut named.bindings {
+            if binding.variable.is_none() {
+                binding.variable = Some(binding.table_ref());
+            }
+        }
+        let mut conditions = vec![];
+        for (pattern, path) in patterns.iter().zip(&plan.paths) {
+            let mut nodes = path.nodes.iter();
+            let mut relationships = path.relationships.iter().map(|(binding, _)| binding);
+            for element in &pattern.elements {
+                let binding = if element.is_node() {
+                    nodes.next()
+                } else {
+                    relationships.next()
+                };
+                let (Some(&binding), Some(properties)) = (binding, element.properties()) else {
+                    continue;
+                };
+                let Expr::Dictionary(fields) = properties else {
+                    return Err(TransformError::Unsupported(format!(
+                        "property map {properties} in a MATCH pattern"
+                    )));
+                };
+                let variable = named.bindings[binding].table_ref();
+                for field in fields {
+                    let condition = Expr::BinaryOp {
+                        left: Box::new(Expr::CompoundIdentifier(vec![
+                            variable.clone(),
+                            field.key.clone(),
+                        ])),
+                        op: BinaryOperator::Eq,
+                        right: field.value.clone(),
+                    };
+                    conditions.push((binding, self.rewrite_expr(&condition, &named)));
+                }
+            }
+        }
+        Ok(conditions)
+    }
+
+    /// Process a node binding of a MATCH pattern
+    fn process_node_for_match(&self, binding: &Binding, where_conditions: &mut Vec<Expr>) {
+        // Rows of a label table all carry its label
+        if !binding.label_table {
+            for label in sorted_names(&binding.labels) {
+                where_conditions.push(self.label_condition(binding, label));
+            }
+        }
+    }
+
+    /// Process a relationship binding of a MATCH pattern
+    fn process_relationship_for_match(&self, binding: &Binding, where_conditions: &mut Vec<Expr>) {
+        // Add type conditions
+        let type_conditions: Vec<Expr> = sorted_names(&binding.labels)
+            .into_iter()
+            .map(|rel_type| self.type_condition(binding, rel_type))
+            .collect();
+        if !type_conditions.is_empty() {
+            where_conditions.push(self.combine_conditions_with_or(type_conditions));
+        }
+
+        // TODO: Handle length and properties
+    }
+
+    /// The conditions keeping the relationship `bindings` of a clause apart:
+    /// that the identities of every two of them that could be the same
+    /// relationship differ, see [`TransformConfig::relationship_uniqueness`]
+    fn relationship_uniqueness(&self, plan: &GraphPlan, bindings: &[usize]) -> Vec<Expr> {
+        let Some(id) = self
+            .config
+            .relationship_id()
+            .filter(|_| self.config.relationship_uniqueness)
+        else {
+            return vec![];
+        };
+        let identity = |binding: usize| {
+            Box::new(Expr::CompoundIdentifier(vec![
+                plan.bindings[binding].table_ref(),
+                Ident::new(id),
+            ]))
+        };
+        overlapping_relationships(plan, bindings)
+            .into_iter()
+            .map(|(first, second)| Expr::BinaryOp {
+                left: identity(first),
+                op: BinaryOperator::NotEq,
+                right: identity(second),
+            })
+            .collect()
+    }
+
+    /// The conditions that the nodes of each of `paths` are different nodes,
+    /// under [`TransformConfig::distinct_nodes`]
+    fn node_uniqueness(&self, plan: &GraphPlan, paths: &[PlanPath]) -> Vec<Expr> {
+        if !self.config.distinct_nodes {
+            return vec![];
+        }
+        let mut pairs = vec![];
+        for path in paths {
+            for (i, &first) in path.nodes.iter().enumerate() {
+                for &second in &path.nodes[i + 1..] {
+                    let (a, b) = (&plan.bindings[first], &plan.bindings[second]);
+                    let apart = a.label_table && b.label_table && a.table != b.table;
+                    let pair = (first.min(second), first.max(second));
+                    if first != second && !apart && !pairs.contains(&pair) {
+                        pairs.push(pair);
+                    }
+                }
+            }
+        }
+        let id =
+            |binding: usize| Box::new(self.id_column(&plan.bindings[binding], self.config.id_type));
+        pairs
+            .into_iter()
+            .map(|(first, second)| Expr::BinaryOp {
+                left: id(first),
+                op: BinaryOperator::NotEq,
+                right: id(second),
+            })
+            .collect()
+    }
+
+    /// Build the FROM clause of the `paths` of a MATCH: every path is
+    /// joined, relationship by relationship, onto the FROM item of its first
+    /// node
+    ///
+    /// Endpoint conditions between different FROM items go to the WHERE
+    /// clause, since a join condition can only refer to its own FROM item.
+    fn match_from(
+        &self,
+        plan: &GraphPlan,
+        paths: &[PlanPath],
+        where_conditions: &mut Vec<Expr>,
+    ) -> Vec<TableWithJoins> {
+        let mut from_tables: Vec<TableWithJoins> = vec![];
+        // The FROM item each binding has been joined into
+        let mut item_of: Vec<Option<usize>> = vec![None; plan.bindings.len()];
+
+        for path in paths {
+            let Some(&first) = path.nodes.first() else {
+                continue;
+            };
+            if item_of[first].is_none() && !plan.bindings[first].pruned {
+                from_tables.push(TableWithJoins {
+                    relation: self.table_factor(&plan.bindings[first]),
+                    joins: vec![],
+                });
+                item_of[first] = Some(from_tables.len() - 1);
+            }
+
+            for (i, (rel, direction)) in path.relationships.iter().enumerate() {
+                let (Some(&start), Some(&end)) = (path.nodes.get(i), path.nodes.get(i + 1)) else {
+                    break;
+                };
+                let relationship = &plan.bindings[*rel];
+                let endpoint = |node: usize, column: &str| {
+                    self.endpoint_condition(relationship, column, &plan.bindings[node])
+                };
+                // Both arrows are joined from start to end, with the reverse
+                // relationship required in WHERE
+                let undirected = *direction == RelationshipDirection::None;
+                if *direction == RelationshipDirection::Both {
+                    where_conditions.push(self.reverse_relationship(
+                        relationship,
+                        &plan.bindings[start],
+                        &plan.bindings[end],
+                    ));
+                }
+                // The condition on a single endpoint; an undirected
+                // relationship can hold it in either column
+                let endpoint_of = |node: usize| match direction {
+                    _ if undirected => {
+                        Expr::Nested(Box::new(self.combine_conditions_with_or(vec![
+                            endpoint(node, "from_id"),
+                            endpoint(node, "to_id"),
+                        ])))
+                    }
+                    RelationshipDirection::Left if node == start => endpoint(node, "to_id"),
+                    RelationshipDirection::Left => endpoint(node, "from_id"),
+                    _ if node == start => endpoint(node, "from_id"),
+                    _ => endpoint(node, "to_id"),
+                };
+                // The condition on both endpoints
+                let endpoints = || self.endpoints_condition(plan, *rel, direction, start, end);
+
+                let repeated = item_of[*rel].is_some();
+                let item = match item_of[*rel] {
+                    // A repeated relationship variable is constrained in WHERE
+                    Some(item) => {
+                        if item_of[end].is_some() || undirected {
+                            where_conditions.push(endpoints());
+                        } else {
+                            where_conditions.push(endpoint_of(start));
+                        }
+                        item
+                    }
+                    None => {
+                        let item = item_of[start].unwrap_or(from_tables.len());
+                        let on = match item_of[end] {
+                            Some(end_item) if end_item == item => endpoints(),
+                            Some(_) => {
+                                where_conditions.push(if undirected {
+                                    endpoints()
+                                } else {
+                                    endpoint_of(end)
+                                });
+                                endpoint_of(start)
+                            }
+                            None => endpoint_of(start),
+                        };
+                        match item_of[start] {
+                            Some(_) => from_tables[item]
+                                .joins
+                                .push(self.inner_join(relationship, on)),
+                            // A pruned start node is not joined, so the
+                            // relationship is a FROM item of its own
+                            None => from_tables.push(TableWithJoins {
+                                relation: self.table_factor(relationship),
+                                joins: vec![],
+                            }),
+                        }
+                        item_of[*rel] = Some(item);
+                        item
+                    }
+                };
+
+                if item_of[end].is_none() && !plan.bindings[end].pruned {
+                    // Joining the end node of an undirected relationship
+                    // decides which orientation matched, unless a repeated
+                    // relationship already matched it in WHERE
+                    let condition = if undirected && !repeated {
+                        endpoints()
+                    } else {
+                        endpoint_of(end)
+                    };
+                    from_tables[item]
+                        .joins
+                        .push(self.inner_join(&plan.bindings[end], condition));
+                    item_of[end] = Some(item);
+                }
+            }
+        }
+
+        from_tables
+    }
+
+    /// The condition on both endpoints of the relationship `rel` from
+    /// `start` to `end`, which an undirected relationship holds in either
+    /// orientation
+    fn endpoints_condition(
+        &self,
+        plan: &GraphPlan,
+        rel: usize,
+        direction: &RelationshipDirection,
+        start: usize,
+        end: usize,
+    ) -> Expr {
+        let endpoint = |node: usize, column: &str| {
+            self.endpoint_condition(&plan.bindings[rel], column, &plan.bindings[node])
+        };
+        // A pruned node is not joined, so there is nothing to compare it to
+        let joined = |ends: [(usize, &str); 2]| {
+            self.combine_conditions(
+                ends.into_iter()
+                    .filter(|&(node, _)| !plan.bindings[node].pruned)
+                    .map(|(node, column)| endpoint(node, column))
+                    .collect(),
+            )
+        };
+        match direction {
+            RelationshipDirection::None if start != end => {
+                let orientation = |from: usize, to: usize| {
+                    Expr::Nested(Box::new(self.combine_conditions(vec![
+                        endpoint(from, "from_id"),
+                        endpoint(to, "to_id"),
+                    ])))
+                };
+                Expr::Nested(Box::new(self.combine_conditions_with_or(vec![
+                    orientation(start, end),
+                    orientation(end, start),
+                ])))
+            }
+            RelationshipDirection::Left => joined([(end, "from_id"), (start, "to_id")]),
+            _ => joined([(start, "from_id"), (end, "to_id")]),
+        }
+    }
+
+    /// The conditions on the endpoints of the relationships of a path, with
+    /// the relationships in the other direction both arrows require
+    fn path_conditions(&self, plan: &GraphPlan, path: &PlanPath) -> Vec<Expr> {
+        let mut conditions = vec![];
+        for (i, (rel, direction)) in path.relationships.iter().enumerate() {
+            let (Some(&start), Some(&end)) = (path.nodes.get(i), path.nodes.get(i + 1)) else {
+                break;
+            };
+            if !(plan.bindings[start].pruned && plan.bindings[end].pruned) {
+                conditions.push(self.endpoints_condition(plan, *rel, direction, start, end));
+            }
+            if *direction == RelationshipDirection::Both {
+                conditions.push(self.reverse_relationship(
+                    &plan.bindings[*rel],
+                    &plan.bindings[start],
+                    &plan.bindings[end],
+                ));
+            }
+        }
+        conditions
+    }
+
+    /// `LEFT JOIN` of the bindings an OPTIONAL MATCH adds, on the endpoint,
+    /// label and type `conditions` of its patterns and on the condition of
+    /// its WHERE clause
+    ///
+    /// Several bindings are joined in parentheses, each on the `conditions`
+    /// that refer to it and the bindings before it alone, so that the rows of
+    /// the MATCH are joined with all of them or none.
+    fn optional_join(
+        &self,
+        plan: &GraphPlan,
+        added: &[usize],
+        conditions: Vec<Expr>,
+        where_condition: Option<Expr>,
+    ) -> Join {
+        let tables: Vec<Ident> = added
+            .iter()
+            .map(|&binding| plan.bindings[binding].table_ref())
+            .collect();
+        let mut outer = vec![];
+        let mut inner = vec![vec![]; added.len()];
+        let conjuncts = conditions.iter().flat_map(split_conjunction);
+        for condition in conjuncts.cloned() {
+            let last = referenced_variables(&condition)
+                .into_iter()
+                .map(|table| tables.iter().position(|added| added.value == table.value))
+                .collect::<Option<Vec<_>>>()
+                .and_then(|positions| positions.into_iter().max());
+            match last {
+                Some(i) if i > 0 => inner[i].push(condition),
+                _ => outer.push(condition),
+            }
+        }
+
+        let conjunction = |conditions: Vec<Expr>| match conditions.len() {
+            0 => None,
+            1 => conditions.into_iter().next(),
+            _ => Some(self.combine_conditions(conditions.into_iter().map(and_operand).collect())),
+        };
+        let first = self.table_factor(&plan.bindings[added[0]]);
+        let relation = if added.len() == 1 {
+            first
+        } else {
+            let joins = added[1..]
+                .iter()
+                .zip(inner.into_iter().skip(1))
+                .map(|(&binding, conditions)| match conjunction(conditions) {
+                    Some(condition) => self.inner_join(&plan.bindings[binding], condition),
+                    None => Join {
+                        relation: self.table_factor(&plan.bindings[binding]),
+                        global: false,
+                        join_operator: JoinOperator::CrossJoin(JoinConstraint::None),
+                    },
+                })
+                .collect();
+            TableFactor::NestedJoin {
+                table_with_joins: Box::new(TableWithJoins {
+                    relation: first,
+                    joins,
+                }),
+                alias: None,
+            }
+        };
+        outer.extend(where_condition);
+        let condition = conjunction(outer).unwrap_or_else(|| self.boolean_literal(true));
+        Join {
+            relation,
+            global: false,
+            join_operator: JoinOperator::Left(JoinConstraint::On(condition)),
+        }
+    }
+
+    /// `EXISTS (SELECT 1 FROM relationships AS r_reverse WHERE ...)` of a
+    /// relationship of the type of `relationship` from `end` to `start`
+    fn reverse_relationship(&self, relationship: &Binding, start: &Binding, end: &Binding) -> Expr {
+        let alias = Ident::new(format!("{}_reverse", relationship.table_ref().value));
+        let reverse = Binding {
+            alias: Some(alias.clone()),
+            ..relationship.clone()
+        };
+        let column = |binding: &Binding, column: &str| {
+            Expr::CompoundIdentifier(vec![binding.table_ref(), Ident::new(column)])
+        };
+        let condition = self.combine_conditions(vec![
+            self.endpoint_condition(&reverse, "from_id", end),
+            self.endpoint_condition(&reverse, "to_id", start),
+            Expr::BinaryOp {
+                left: Box::new(column(&reverse, "type")),
+                op: BinaryOperator::Eq,
+                right: Box::new(column(relationship, "type")),
+            },
+        ]);
+        Expr::Exists {
+            subquery: Box::new(select_from(
+                vec![SelectItem::UnnamedExpr(number_literal(1))],
+                self.table_factor(&reverse),
+                Some(condition),
+            )),
+            negated: false,
+        }
+    }
+
+    /// `<node>.id = <relationship>.<column>`
+    fn endpoint_condition(&self, relationship: &Binding, column: &str, node: &Binding) -> Expr {
+        Expr::BinaryOp {
+            left: Box::new(Expr::CompoundIdentifier(vec![
+                node.table_ref(),
+                Ident::new(&self.config.id_column),
+            ])),
+            op: BinaryOperator::Eq,
+            right: Box::new(Expr::CompoundIdentifier(vec![
+                relationship.table_ref(),
+                Ident::new(column),
+            ])),
+        }
+    }
+
+    /// `INNER JOIN <table> ON <condition>` for a binding
+    fn inner_join(&self, binding: &Binding, condition: Expr) -> Join {
+        Join {
+            relation: self.table_factor(binding),
+            global: false,
+            join_operator: JoinOperator::Inner(JoinConstraint::On(condition)),
+        }
+    }
+
+    /// The table reference of a binding, with its alias
+    fn table_factor(&self, binding: &Binding) -> TableFactor {
+        TableFactor::Table {
+            name: ObjectName::from(vec![binding.table_name()]),
+            alias: binding.alias.as_ref().map(|v| TableAlias {
+                name: v.clone(),
+                columns: vec![],
+            }),
+            args: None,
+            with_hints: vec![],
             version: None,
+            with_ordinality: false,
             partitions: vec![],
+            json_path: None,
+            sample: None,
+            index_hints: vec![],
+        }
+    }
+
+    /// `<node>.label = '<label>'`
+    fn label_condition(&self, binding: &Binding, label: &Ident) -> Expr {
+        Expr::BinaryOp {
+            left: Box::new(Expr::CompoundIdentifier(vec![
+                binding.table_ref(),
+                Ident::new("label"),
+            ])),
+            op: BinaryOperator::Eq,
+            right: Box::new(Expr::value(Value::SingleQuotedString(label.value.clone()))),
+        }
+    }
+
+    /// `<relationship>.type = '<type>'`
+    fn type_condition(&self, binding: &Binding, rel_type: &Ident) -> Expr {
+        Expr::BinaryOp {
+            left: Box::new(Expr::CompoundIdentifier(vec![
+                binding.table_ref(),
+                Ident::new("type"),
+            ])),
+            op: BinaryOperator::Eq,
+            right: Box::new(Expr::value(Value::SingleQuotedString(
+                rel_type.value.clone(),
+            ))),
+        }
+    }
+
+    /// `json_extract(<variable>.properties, '$.<property>')`,
+    /// `<variable>.properties ->> '<property>'` on PostgreSQL, which has no
+    /// `json_extract`, or `<variable>.properties['<property>']` on DataFusion
+    ///
+    /// PostgreSQL reads the property as text, which `cast_json_operands`
+    /// casts where an operator takes a number or a boolean.
+    fn json_property_accessor(&self, variable: &Ident, property: &str) -> Expr {
+        let properties = Expr::CompoundIdentifier(vec![variable.clone(), Ident::new("properties")]);
+        if self.config.target == SqlTarget::DataFusion {
+            return Expr::CompoundFieldAccess {
+                root: Box::new(properties),
+                access_chain: vec![AccessExpr::Subscript(Subscript::Index {
+                    index: Expr::value(Value::SingleQuotedString(property.to_string())),
+                })],
+            };
+        }
+        if self.config.target == SqlTarget::Postgres {
+            // A key in a jsonb operator needs no escaping beyond the quotes
+            // of its SQL string
+            return Expr::BinaryOp {
+                left: Box::new(properties),
+                op: BinaryOperator::LongArrow,
+                right: Box::new(Expr::value(Value::SingleQuotedString(property.to_string()))),
+            };
+        }
+        function_call("json_extract", vec![properties, self.json_path(property)])
+    }
+
+    /// The JSON path of the property `key` as a SQL string, e.g. `'$.name'`,
+    /// or `'$."weird.key"'` for a key that is not plain, in which `"` and `\`
+    /// are escaped as in a JSON string
+    ///
+    /// SQLite does not read escapes in a quoted path member, so it finds no
+    /// property whose key holds a `"`.
+    fn json_path(&self, key: &str) -> Expr {
+        let path = if is_plain_key(key) {
+            format!("$.{key}")
+        } else {
+            let escaped = key.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("$.\"{escaped}\"")
+        };
+        // MySQL reads the backslashes of a string as escapes
+        let path = match self.config.target {
+            SqlTarget::MySql => path.replace('\\', "\\\\"),
+            _ => path,
+        };
+        Expr::value(Value::SingleQuotedString(path))
+    }
+
+    /// The SQL of a type predicate on a property of an element of the generic
+    /// tables, which checks the JSON type of the property, e.g.
+    /// `coalesce(json_type(n.properties, '$.age'), 'null') IN ('null', 'integer')`
+    /// for `n.age IS :: INTEGER`, as every type includes `null`; or `None`
+    /// for other expressions
+    fn type_predicate(
+        &self,
+        predicate: &Expr,
+        plan: &GraphPlan,
+    ) -> Option<Result<Expr, TransformError>> {
+        let Expr::Cypher(cypher_expr) = predicate else {
+            return None;
+        };
+        let CypherExpr::TypePredicate {
+            expr,
+            negated,
+            data_type,
+        } = cypher_expr.as_ref()
+        else {
+            return None;
+        };
+        let unsupported = |reason: String| {
+            Some(Err(TransformError::Unsupported(format!(
+                "type predicate {predicate}{reason}"
+            ))))
+        };
+        let Some((variable, property)) = json_property(expr, plan) else {
+            return unsupported(" on a value other than a property stored as JSON".to_string());
+        };
+        match data_type {
+            CypherType::Any => return Some(Ok(self.boolean_literal(!negated))),
+            CypherType::List(Some(element)) if **element != CypherType::Any => {
+                return unsupported(", as the types of list elements are not checked".to_string());
+            }
+            _ => {}
+        }
+        let target = self.config.target;
+        if target == SqlTarget::DataFusion {
+            return unsupported(" on DataFusion, whose properties are not JSON".to_string());
+        }
+        let Some(names) = target.json_type_names(data_type) else {
+            return unsupported(format!(", as JSON does not hold {data_type} values"));
+        };
+
+        let properties =
+            || Expr::CompoundIdentifier(vec![variable.clone(), Ident::new("properties")]);
+        let string = |s: &str| Expr::value(Value::SingleQuotedString(s.to_string()));
+        let member = |op| Expr::BinaryOp {
+            left: Box::new(properties()),
+            op,
+            right: Box::new(string(&property)),
+        };
+        let path = self.json_path(&property);
+        let json_type = match target {
+            SqlTarget::Sqlite | SqlTarget::DuckDb => {
+                function_call("json_type", vec![properties(), path])
+            }
+            SqlTarget::MySql => function_call(
+                "json_type",
+                vec![function_call("json_extract", vec![properties(), path])],
+            ),
+            SqlTarget::Postgres => {
+                function_call("jsonb_typeof", vec![member(BinaryOperator::Arrow)])
+            }
+            SqlTarget::DataFusion => unreachable!("DataFusion has no JSON type names"),
+        };
+        let typed = function_call("coalesce", vec![json_type, string(names[0])]);
+
+        if target == SqlTarget::Postgres
+            && matches!(data_type, CypherType::Integer | CypherType::Float)
+        {
+            // jsonb_typeof does not tell integers from floats, which are
+            // written with a decimal point
+            let equals = |name| Expr::BinaryOp {
+                left: Box::new(typed.clone()),
+                op: BinaryOperator::Eq,
+                right: Box::new(string(name)),
+            };
+            let number = Expr::BinaryOp {
+                left: Box::new(equals("number")),
+                op: BinaryOperator::And,
+                right: Box::new(Expr::Like {
+                    negated: *data_type == CypherType::Integer,
+                    any: false,
+                    expr: Box::new(member(BinaryOperator::LongArrow)),
+                    pattern: Box::new(string("%.%")),
+                    escape_char: None,
+                }),
+            };
+            let condition = Expr::Nested(Box::new(Expr::BinaryOp {
+                left: Box::new(equals(names[0])),
+                op: BinaryOperator::Or,
+                right: Box::new(number),
+            }));
+            return Some(Ok(if *negated {
+                Expr::UnaryOp {
+                    op: UnaryOperator::Not,
+                    expr: Box::new(condition),
+                }
+            } else {
+                condition
+            }));
+        }
+        Some(Ok(Expr::InList {
+            expr: Box::new(typed),
+            list: names.into_iter().map(string).collect(),
+            negated: *negated,
+        }))
+    }
+
+    /// The SQL of a membership test in the labels or property keys of a
+    /// variable, e.g. `'Admin' IN labels(n)` or `'email' IN keys(n)`
+    ///
+    /// The generic node table holds a single label per node, so a node has a
+    /// label when its label column is that label, and has a property key when
+    /// its properties hold a value for the key, since Cypher does not store
+    /// `null` properties.
+    fn list_membership(
+        &self,
+        membership: &Expr,
+        plan: &GraphPlan,
+    ) -> Option<Result<Expr, TransformError>> {
+        let Expr::Cypher(cypher_expr) = membership else {
+            return None;
+        };
+        let CypherExpr::In { expr, list } = cypher_expr.as_ref() else {
+            return None;
+        };
+        let unsupported = |reason: &str| {
+            Some(Err(TransformError::Unsupported(format!(
+                "{membership}{reason}"
+            ))))
+        };
+        let Some((function, binding)) = variable_function(list, plan) else {
+            return unsupported(
+                ", as only list literals and the labels() and keys() of a variable are \
+                 translated",
+            );
+        };
+        let literal = match expr {
+            Expr::Value(ValueWithSpan {
+                value: Value::SingleQuotedString(s) | Value::DoubleQuotedString(s),
+                ..
+            }) => Some(s.as_str()),
+            _ => None,
+        };
+        let item = self.rewrite_expr(expr, plan);
+        let is_not_null = |expr| Some(Ok(Expr::IsNotNull(Box::new(expr))));
+
+        match (function.as_str(), binding.kind) {
+            ("labels", BindingKind::Node) if binding.label_table => match literal {
+                // A label table only holds nodes of its own label
+                Some(label) => Some(Ok(self.boolean_literal(binding.table == label))),
+                None => Some(Ok(Expr::BinaryOp {
+                    left: Box::new(item),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(Expr::value(Value::SingleQuotedString(
+                        binding.table.clone(),
+                    ))),
+                })),
+            },
+            ("labels", BindingKind::Node) => Some(Ok(Expr::BinaryOp {
+                left: Box::new(Expr::CompoundIdentifier(vec![
+                    binding.table_ref(),
+                    Ident::new("label"),
+                ])),
+                op: BinaryOperator::Eq,
+                right: Box::new(item),
+            })),
+            ("keys", BindingKind::Node) if binding.label_table => match literal {
+                Some(key) => is_not_null(Expr::CompoundIdentifier(vec![
+                    binding.table_ref(),
+                    Ident::new(key),
+                ])),
+                None => unsupported(", as the columns of a label table are not known"),
+            },
+            ("keys", _) => {
+                let properties =
+                    Expr::CompoundIdentifier(vec![binding.table_ref(), Ident::new("properties")]);
+                let path = |key: Expr| match self.config.target {
+                    SqlTarget::MySql => function_call(
+                        "concat",
+                        vec![
+                            Expr::value(Value::SingleQuotedString("$.".to_string())),
+                            key,
+                        ],
+                    ),
+                    _ => Expr::BinaryOp {
+                        left: Box::new(Expr::value(Value::SingleQuotedString("$.".to_string()))),
+                        op: BinaryOperator::StringConcat,
+                        right: Box::new(key),
+                    },
+                };
+                match (self.config.target, literal) {
+                    (SqlTarget::Postgres, _) => is_not_null(Expr::BinaryOp {
+                        left: Box::new(properties),
+                        op: BinaryOperator::Arrow,
+                        right: Box::new(item),
+                    }),
+                    (_, Some(key)) => {
+                        is_not_null(self.json_property_accessor(&binding.table_ref(), key))
+                    }
+                    (SqlTarget::DataFusion, None) => Some(Ok(function_call(
+                        "array_has",
+                        vec![function_call("map_keys", vec![properties]), item],
+                    ))),
+                    (_, None) => {
+                        is_not_null(function_call("json_extract", vec![properties, path(item)]))
+                    }
+                }
+            }
+            _ => unsupported(&format!(
+                ", as {function}() does not apply to relationships"
+            )),
+        }
+    }
+
+    /// The SQL of a function of a named path: `length(p)`, the number of its
+    /// relationships, and `nodes(p)` and `relationships(p)`, JSON arrays of
+    /// its elements as they are returned
+    ///
+    /// Variable-length relationships are matched as single hops, so the
+    /// functions of a path holding one are not translated.
+    fn path_function(&self, expr: &Expr, plan: &GraphPlan) -> Option<Result<Expr, TransformError>> {
+        let (function, variable) = function_variable(expr)?;
+        if !matches!(function.as_str(), "length" | "nodes" | "relationships") {
+            return None;
+        }
+        let unsupported =
+            |reason: &str| Some(Err(TransformError::Unsupported(format!("{expr}{reason}"))));
+        let Some(path) = plan.path(&variable.value) else {
+            let kind = match plan.binding(&variable.value)?.kind {
+                BindingKind::Node => "node",
+                BindingKind::Relationship => "relationship",
+            };
+            return Some(Err(TransformError::InvalidQuery(format!(
+                "{expr} expects a path, but {variable} is a {kind}"
+            ))));
+        };
+        if path.variable_length {
+            return unsupported(&format!(
+                ", as the variable-length relationships of {variable} are matched as single hops"
+            ));
+        }
+
+        let elements: Vec<usize> = match function.as_str() {
+            "length" => return Some(Ok(number_literal(path.relationships.len()))),
+            "nodes" => path.nodes.clone(),
+            _ => path.relationships.iter().map(|(i, _)| *i).collect(),
+        };
+        match self.element_array(&elements, plan) {
+            Some(array) => Some(Ok(array)),
+            None => unsupported(", as the columns of a label table are not known"),
+        }
+    }
+
+    /// The SQL of a named path returned whole, e.g. `p` of `p =
+    /// (a)-[r]->(b)`: the array of the objects of its nodes and relationships
+    /// in the order of the pattern, or `None` for other expressions
+    fn path_value(&self, expr: &Expr, plan: &GraphPlan) -> Option<Result<Expr, TransformError>> {
+        let Expr::Identifier(variable) = expr else {
+            return None;
+        };
+        let path = plan.path(&variable.value)?;
+        let unsupported = |reason: &str| {
+            Some(Err(TransformError::Unsupported(format!(
+                "path variable {variable}{reason}"
+            ))))
+        };
+        if path.variable_length {
+            return unsupported(
+                ", as its variable-length relationships are matched as single hops",
+            );
+        }
+        let mut elements = vec![];
+        for (i, &node) in path.nodes.iter().enumerate() {
+            elements.push(node);
+            elements.extend(
+                path.relationships
+                    .get(i)
+                    .map(|(relationship, _)| *relationship),
+            );
+        }
+        let Some(array) = self.element_array(&elements, plan) else {
+            return unsupported(", as the columns of a label table are not known");
+        };
+        // A path an OPTIONAL MATCH leaves unmatched is null as a whole
+        let optional = elements
+            .iter()
+            .map(|&i| &plan.bindings[i])
+            .find(|binding| binding.optional);
+        Some(Ok(match optional {
+            Some(binding) => self.null_when_unmatched(binding, array),
+            None => array,
+        }))
+    }
+
+    /// The array of the objects of the bindings at `elements`, as
+    /// [`Self::element_object`] builds them, or `None` when one is read from
+    /// a label table
+    fn element_array(&self, elements: &[usize], plan: &GraphPlan) -> Option<Expr> {
+        let mut objects = vec![];
+        for binding in elements.iter().map(|&i| &plan.bindings[i]) {
+            if binding.label_table {
+                return None;
+            }
+            objects.push(self.element_object(binding));
+        }
+        Some(function_call(
+            self.config.target.json_array_function(),
+            objects,
+        ))
+    }
+
+    /// The SQL of `labels(n)`, a list of the single label of a node, and of
+    /// `type(r)`, the type column of a relationship
+    ///
+    /// A call on anything but a variable of the kind the function expects is
+    /// an error.
+    fn element_function(
+        &self,
+        expr: &Expr,
+        plan: &GraphPlan,
+    ) -> Option<Result<Expr, TransformError>> {
+        let (name, args) = function_parts(expr)?;
+        let (function, kind) = match name.to_lowercase().as_str() {
+            "labels" => ("labels", BindingKind::Node),
+            "type" => ("type", BindingKind::Relationship),
+            _ => return None,
+        };
+        let kind_name = |kind| match kind {
+            BindingKind::Node => "node",
+            BindingKind::Relationship => "relationship",
+        };
+        let [Expr::Identifier(variable)] = args.as_slice() else {
+            return Some(Err(TransformError::Unsupported(format!(
+                "{expr}, as only {function}() of a {} variable is translated",
+                kind_name(kind)
+            ))));
+        };
+        let Some(binding) = plan.binding(&variable.value) else {
+            return Some(Err(TransformError::Unsupported(format!(
+                "{expr}, as {variable} is not a {} of a pattern",
+                kind_name(kind)
+            ))));
+        };
+        if binding.kind != kind {
+            return Some(Err(TransformError::InvalidQuery(format!(
+                "{expr} expects a {}, but {variable} is a {}",
+                kind_name(kind),
+                kind_name(binding.kind)
+            ))));
+        }
+        let column =
+            |name: &str| Expr::CompoundIdentifier(vec![binding.table_ref(), Ident::new(name)]);
+        Some(Ok(match kind {
+            BindingKind::Relationship => column("type"),
+            BindingKind::Node => {
+                // A label table only holds nodes of its own label
+                let label = if binding.label_table {
+                    Expr::value(Value::SingleQuotedString(binding.table.clone()))
+                } else {
+                    column("label")
+                };
+                // PostgreSQL groups by jsonb, but not by json
+                let function = match self.config.target {
+                    SqlTarget::Postgres => "jsonb_build_array",
+                    target => target.json_array_function(),
+                };
+                let labels = function_call(function, vec![label]);
+                self.null_when_unmatched(binding, labels)
+            }
+        }))
+    }
+
+    /// The SQL of the legacy `exists()` function: of a property,
+    /// `exists(n.email)`, the same as `n.email IS NOT NULL`, and of a
+    /// pattern, `exists((n)-[:KNOWS]->())`, an `EXISTS` subquery of its
+    /// matches
+    ///
+    /// Any other argument, e.g. the variable of `exists(n)`, is an error
+    /// naming it, as it is in Cypher.
+    fn exists_function(
+        &self,
+        expr: &Expr,
+        plan: &GraphPlan,
+    ) -> Option<Result<Expr, TransformError>> {
+        let (name, args) = function_parts(expr)?;
+        if !name.eq_ignore_ascii_case("exists") {
+            return None;
+        }
+        let [arg] = args.as_slice() else {
+            return Some(Err(TransformError::InvalidQuery(format!(
+                "{expr} takes a single argument, a property or a pattern"
+            ))));
+        };
+        let form = match arg {
+            Expr::CompoundIdentifier(parts) if parts.len() == 2 => None,
+            _ if properties_field(arg).is_some() => None,
+            Expr::Cypher(cypher_expr) => match cypher_expr.as_ref() {
+                CypherExpr::PatternPredicate { pattern } => {
+                    return Some(self.pattern_exists(pattern, plan));
+                }
+                _ => Some("an expression"),
+            },
+            Expr::Identifier(_) => Some("a variable"),
+            Expr::Value(ValueWithSpan {
+                value: Value::Placeholder(_),
+                ..
+            }) => Some("a parameter"),
+            Expr::Value(_) => Some("a literal"),
+            Expr::Function(_) => Some("a function call"),
+            _ => Some("an expression"),
+        };
+        Some(match form {
+            Some(form) => Err(TransformError::InvalidQuery(format!(
+                "{expr} takes a property or a pattern, not {form}"
+            ))),
+            None => Ok(self.rewrite_expr(&Expr::IsNotNull(Box::new(arg.clone())), plan)),
+        })
+    }
+
+    /// `EXISTS (SELECT 1 FROM ... WHERE ...)` of the matches of the pattern
+    /// of `exists()`, correlated with the rows of `plan` on the variables it
+    /// binds, which the subquery reads from the outer query's tables
+    fn pattern_exists(&self, pattern: &Pattern, plan: &GraphPlan) -> Result<Expr, TransformError> {
+        let unsupported = |what: String| {
+            Err(TransformError::Unsupported(format!(
+                "{what} in exists({pattern})"
+            )))
+        };
+        if let Some(element) = pattern
+            .elements
+            .iter()
+            .find(|element| element.properties().is_some())
+        {
+            return unsupported(format!("property map {element}"));
+        }
+        let mut sub = GraphPlan::build(core::slice::from_ref(pattern), &None, &self.config);
+        if sub.paths[0].variable_length {
+            return unsupported("variable-length relationship".to_string());
+        }
+
+        let mut conditions = vec![];
+        let mut from = vec![];
+        for binding in &mut sub.bindings {
+            let outer = binding
+                .variable
+                .as_ref()
+                .and_then(|variable| plan.binding(&variable.value));
+            let Some(outer) = outer else {
+                match binding.kind {
+                    BindingKind::Node => self.process_node_for_match(binding, &mut conditions),
+                    BindingKind::Relationship => {
+                        self.process_relationship_for_match(binding, &mut conditions)
+                    }
+                }
+                if !binding.pruned {
+                    from.push(self.table_factor(binding));
+                }
+                continue;
+            };
+            if outer.kind != binding.kind {
+                return Err(TransformError::InvalidQuery(format!(
+                    "{} in exists({pattern}) is bound to a {} outside it",
+                    binding.pattern(),
+                    match outer.kind {
+                        BindingKind::Node => "node",
+                        BindingKind::Relationship => "relationship",
+                    }
+                )));
+            }
+            // The element of the outer row, constrained by the labels or
+            // types the pattern adds
+            let labels = core::mem::take(&mut binding.labels);
+            *binding = outer.clone();
+            match binding.kind {
+                // A label table only holds nodes of its own label
+                BindingKind::Node if binding.label_table => {
+                    if labels.iter().any(|label| label.value != binding.table) {
+                        conditions.push(self.boolean_literal(false));
+                    }
+                }
+                BindingKind::Node => {
+                    for label in sorted_names(&labels) {
+                        if !binding.labels.contains(label) {
+                            conditions.push(self.label_condition(binding, label));
+                        }
+                    }
+                }
+                BindingKind::Relationship => {
+                    let implied = !binding.labels.is_empty()
+                        && binding
+                            .labels
+                            .iter()
+                            .all(|rel_type| labels.contains(rel_type));
+                    if !implied {
+                        let added = Binding {
+                            labels,
+                            ..binding.clone()
+                        };
+                        self.process_relationship_for_match(&added, &mut conditions);
+                    }
+                }
+            }
+        }
+        conditions.extend(self.path_conditions(&sub, &sub.paths[0]));
+        let relationships: Vec<usize> =
+            sub.paths[0].relationships.iter().map(|(i, _)| *i).collect();
+        conditions.extend(self.relationship_uniqueness(&sub, &relationships));
+        conditions.extend(self.node_uniqueness(&sub, &sub.paths));
+
+        let condition = match conditions.len() {
+            0 => None,
+            1 => conditions.pop(),
+            _ => Some(self.combine_conditions(conditions)),
+        };
+        let mut from = from.into_iter();
+        // Of elements the outer rows all bind, the pattern is only a condition
+        let Some(first) = from.next() else {
+            return Ok(condition.unwrap_or_else(|| self.boolean_literal(true)));
+        };
+        let mut subquery = select_from(
+            vec![SelectItem::UnnamedExpr(number_literal(1))],
+            first,
+            condition,
+        );
+        if let SetExpr::Select(select) = subquery.body.as_mut() {
+            select.from.extend(from.map(|relation| TableWithJoins {
+                relation,
+                joins: vec![],
+            }));
+        }
+        Ok(Expr::Exists {
+            subquery: Box::new(subquery),
+            negated: false,
+        })
+    }
+
+    /// The SQL of string matching, e.g. `n.name STARTS WITH 'A'`, which is a
+    /// LIKE of a literal pattern and string functions otherwise, or `None`
+    /// for other expressions
+    ///
+    /// Matching is case-sensitive, as in Cypher: on SQLite, whose LIKE
+    /// ignores the case of ASCII letters, a literal pattern is a GLOB, and on
+    /// MySQL, whose default collations ignore case, the pattern is compared
+    /// under the `utf8mb4_bin` collation. A property matched with a literal
+    /// ignores case under [`TransformConfig::case_insensitive_text_ops`].
+    fn string_predicate(&self, expr: &Expr, plan: &GraphPlan) -> Option<Expr> {
+        let Expr::Cypher(cypher_expr) = expr else {
+            return None;
+        };
+        let CypherExpr::StringPredicate {
+            expr,
+            operator,
+            pattern,
+        } = cypher_expr.as_ref()
+        else {
+            return None;
+        };
+        let target = self.config.target;
+        let string = self.rewrite_text(expr, plan);
+
+        if let Some(literal) = string_value(pattern) {
+            let ignore_case = self.ignores_case(expr, pattern);
+            if target == SqlTarget::Sqlite && !ignore_case {
+                return Some(Expr::BinaryOp {
+                    left: Box::new(string),
+                    op: BinaryOperator::Custom("GLOB".to_string()),
+                    right: Box::new(Expr::value(Value::SingleQuotedString(glob_pattern(
+                        literal, *operator,
+                    )))),
+                });
+            }
+            let (like, escaped) = like_pattern(literal, *operator);
+            let like = Expr::value(Value::SingleQuotedString(like));
+            let mut like = self.string_literal(&like).unwrap_or(like);
+            // MySQL escapes with a backslash without being told
+            let escape_char = (escaped && target != SqlTarget::MySql)
+                .then(|| Value::SingleQuotedString("\\".to_string()));
+            if !ignore_case {
+                if target == SqlTarget::MySql {
+                    like = Expr::Collate {
+                        expr: Box::new(like),
+                        collation: ObjectName::from(vec![Ident::new("utf8mb4_bin")]),
+                    };
+                }
+                return Some(Expr::Like {
+                    negated: false,
+                    any: false,
+                    expr: Box::new(string),
+                    pattern: Box::new(like),
+                    escape_char,
+                });
+            }
+            return Some(match target {
+                SqlTarget::Postgres | SqlTarget::DuckDb | SqlTarget::DataFusion => Expr::ILike {
+                    negated: false,
+                    any: false,
+                    expr: Box::new(string),
+                    pattern: Box::new(like),
+                    escape_char,
+                },
+                SqlTarget::Sqlite | SqlTarget::MySql => Expr::Like {
+                    negated: false,
+                    any: false,
+                    expr: Box::new(function_call("lower", vec![string])),
+                    pattern: Box::new(function_call("lower", vec![like])),
+                    escape_char,
+                },
+            });
+        }
+
+        let mut pattern = self.rewrite_text(pattern, plan);
+        if target == SqlTarget::MySql {
+            pattern = Expr::Collate {
+                expr: Box::new(pattern),
+                collation: ObjectName::from(vec![Ident::new("utf8mb4_bin")]),
+            };
+        }
+        let equals = |left, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::Eq,
+            right: Box::new(right),
+        };
+        let positive = |position| Expr::BinaryOp {
+            left: Box::new(position),
+            op: BinaryOperator::Gt,
+            right: Box::new(number_literal(0)),
+        };
+        let length = |expr: &Expr| function_call("char_length", vec![expr.clone()]);
+        Some(match (target, operator) {
+            (SqlTarget::Sqlite, StringOperator::StartsWith) => equals(
+                function_call(
+                    "substr",
+                    vec![
+                        string,
+                        number_literal(1),
+                        function_call("length", vec![pattern.clone()]),
+                    ],
+                ),
+                pattern,
+            ),
+            // A pattern longer than the string starts before it, where substr
+            // counts from the end, but the part is then shorter than the pattern
+            (SqlTarget::Sqlite, StringOperator::EndsWith) => {
+                let start = Expr::BinaryOp {
+                    left: Box::new(Expr::BinaryOp {
+                        left: Box::new(function_call("length", vec![string.clone()])),
+                        op: BinaryOperator::Minus,
+                        right: Box::new(function_call("length", vec![pattern.clone()])),
+                    }),
+                    op: BinaryOperator::Plus,
+                    right: Box::new(number_literal(1)),
+                };
+                equals(function_call("substr", vec![string, start]), pattern)
+            }
+            (SqlTarget::Sqlite, StringOperator::Contains) => {
+                positive(function_call("instr", vec![string, pattern]))
+            }
+            (SqlTarget::Postgres | SqlTarget::MySql, StringOperator::StartsWith) => equals(
+                function_call("left", vec![string, length(&pattern)]),
+                pattern,
+            ),
+            (SqlTarget::Postgres | SqlTarget::MySql, StringOperator::EndsWith) => equals(
+                function_call("right", vec![string, length(&pattern)]),
+                pattern,
+            ),
+            (SqlTarget::Postgres | SqlTarget::MySql, StringOperator::Contains) => {
+                positive(Expr::Position {
+                    expr: Box::new(pattern),
+                    r#in: Box::new(string),
+                })
+            }
+            (SqlTarget::DuckDb | SqlTarget::DataFusion, StringOperator::StartsWith) => {
+                function_call("starts_with", vec![string, pattern])
+            }
+            (SqlTarget::DuckDb | SqlTarget::DataFusion, StringOperator::EndsWith) => {
+                function_call("ends_with", vec![string, pattern])
+            }
+            (SqlTarget::DuckDb | SqlTarget::DataFusion, StringOperator::Contains) => {
+                positive(function_call("strpos", vec![string, pattern]))
+            }
+        })
+    }
+
+    /// The SQL of `expr` as text, which for a JSON property on MySQL is the
+    /// unquoted string, MySQL's JSON strings being quoted in their text
+    fn rewrite_text(&self, expr: &Expr, plan: &GraphPlan) -> Expr {
+        let text = self.rewrite_expr(expr, plan);
+        if self.config.target == SqlTarget::MySql && json_property(expr, plan).is_some() {
+            return function_call("json_unquote", vec![text]);
+        }
+        text
+    }
+
+    /// Whether the comparison of `left` with `right` ignores case, under
+    /// [`TransformConfig::case_insensitive_text_ops`] for a property and a
+    /// string literal
+    pub(crate) fn ignores_case(&self, left: &Expr, right: &Expr) -> bool {
+        self.config.case_insensitive_text_ops && is_property_with_string(left, right)
+    }
+
+    /// The SQL of `left = right` ignoring case, as the `lower()` of both
+    /// sides, or `None` when the comparison is not one whose case
+    /// [`Self::ignores_case`]
+    fn case_insensitive_equality(&self, expr: &Expr, plan: &GraphPlan) -> Option<Expr> {
+        let Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq,
+            right,
+        } = expr
+        else {
+            return None;
+        };
+        if !self.ignores_case(left, right) && !self.ignores_case(right, left) {
+            return None;
+        }
+        let lower = |side: &Expr| function_call("lower", vec![self.rewrite_text(side, plan)]);
+        Some(Expr::BinaryOp {
+            left: Box::new(lower(left)),
+            op: BinaryOperator::Eq,
+            right: Box::new(lower(right)),
+        })
+    }
+
+    /// Reject the type predicates, list membership tests, path functions
+    /// and `exists()` calls in `expr` that cannot be translated
+    fn check_predicates(&self, expr: &Expr, plan: &GraphPlan) -> Result<(), TransformError> {
+        let mut pending = vec![expr];
+        while let Some(expr) = pending.pop() {
+            // A rewriter takes the call as it is
+            if self.custom_function(expr, plan).is_some() {
+                continue;
+            }
+            // and a path function its path, which is not returned whole
+            if let Some(Ok(_)) = self.path_function(expr, plan) {
+                continue;
+            }
+            let predicate = self
+                .type_predicate(expr, plan)
+                .or_else(|| self.list_membership(expr, plan))
+                .or_else(|| self.path_function(expr, plan))
+                .or_else(|| self.path_value(expr, plan))
+                .or_else(|| self.element_function(expr, plan))
+                .or_else(|| self.exists_function(expr, plan));
+            if let Some(Err(error)) = predicate {
+                return Err(error);
+            }
+            pending.extend(subexpressions(expr));
+        }
+        Ok(())
+    }
+
+    /// Replace the label predicates left in an expression with conditions on
+    /// the label and type columns, and the properties of elements stored in
+    /// the generic tables with JSON accessors
+    pub(crate) fn rewrite_expr(&self, expr: &Expr, plan: &GraphPlan) -> Expr {
+        if let Some(function) = self.custom_function(expr, plan) {
+            return function;
+        }
+        if let Some((variable, property)) = properties_field(expr) {
+            let access = Expr::CompoundIdentifier(vec![variable.clone(), property.clone()]);
+            return self.rewrite_expr(&access, plan);
+        }
+        if let Some((variable, labels)) = as_label_predicate(expr) {
+            if let Some(binding) = plan.binding(&variable.value) {
+                let conditions = labels
+                    .iter()
+                    .map(|label| match binding.kind {
+                        BindingKind::Relationship => self.type_condition(binding, label),
+                        // A label table only holds nodes of its own label
+                        BindingKind::Node if binding.label_table => {
+                            self.boolean_literal(binding.table == label.value)
+                        }
+                        BindingKind::Node => self.label_condition(binding, label),
+                    })
+                    .collect::<Vec<_>>();
+                return if conditions.len() == 1 {
+                    conditions.into_iter().next().unwrap()
+                } else {
+                    Expr::Nested(Box::new(self.combine_conditions(conditions)))
+                };
+            }
+        }
+
+        if let Some(Ok(predicate)) = self.type_predicate(expr, plan) {
+            return predicate;
+        }
+        if let Some(Ok(membership)) = self.list_membership(expr, plan) {
+            return membership;
+        }
+        if let Some(Ok(function)) = self.path_function(expr, plan) {
+            return function;
+        }
+        if let Some(Ok(path)) = self.path_value(expr, plan) {
+            return path;
+        }
+        if let Some(Ok(function)) = self.element_function(expr, plan) {
+            return function;
+        }
+        if let Some(Ok(exists)) = self.exists_function(expr, plan) {
+            return exists;
+        }
+        if let Some(predicate) = self.string_predicate(expr, plan) {
+            return predicate;
+        }
+        if let Some(equality) = self.case_insensitive_equality(expr, plan) {
+            return equality;
+        }
+        if let Some((variable, property)) = json_property(expr, plan) {
+            return self.json_property_accessor(&variable, &property);
+        }
+
+        if let Some(literal) = self.string_literal(expr) {
+            return literal;
+        }
+        if let Expr::Value(ValueWithSpan {
+            value: Value::Boolean(b),
+            ..
+        }) = expr
+        {
+            return self.boolean_literal(*b);
+        }
+        if let Some(lookup) = self.id_lookup(expr, plan) {
+            return lookup;
+        }
+        if let Some((binding, id_type)) = id_function(expr, plan) {
+            return self.id_column(binding, id_type);
+        }
+        if let Some(count) = self.variable_count(expr, plan) {
+            return count;
+        }
+        if let Some(Ok(function)) = self.mapped_function(expr) {
+            return function;
+        }
+        if let Some(aggregate) = self.collect_aggregate(expr, plan) {
+            return aggregate;
+        }
+
+        let expr = map_subexpressions(expr, &mut |sub_expr| self.rewrite_expr(sub_expr, plan));
+        if self.config.target == SqlTarget::Postgres {
+            nest_json_operands(cast_json_operands(expr))
+        } else {
+            nest_json_operands(expr)
+        }
+    }
+
+    /// The SQL of a function call that one of the
+    /// [`TransformConfig::function_rewriters`] takes
+    fn custom_function(&self, expr: &Expr, plan: &GraphPlan) -> Option<Expr> {
+        if self.config.function_rewriters.is_empty() {
+            return None;
+        }
+        let (name, args) = function_parts(expr)?;
+        let ctx = RewriteContext::new(self, plan);
+        self.config
+            .function_rewriters
+            .iter()
+            .find_map(|rewriter| rewriter.rewrite(&name, &args, &ctx))
+    }
+
+    /// A Cypher string as a SQL single quoted string, since double quotes
+    /// delimit identifiers in SQL and MySQL reads backslashes as escapes, or
+    /// `None` when `expr` is not a string that needs rewriting
+    pub(crate) fn string_literal(&self, expr: &Expr) -> Option<Expr> {
+        let Expr::Value(value) = expr else {
+            return None;
+        };
+        let (Value::SingleQuotedString(s) | Value::DoubleQuotedString(s)) = &value.value else {
+            return None;
+        };
+        let escape_backslashes = self.config.target == SqlTarget::MySql && s.contains('\\');
+        if matches!(value.value, Value::SingleQuotedString(_)) && !escape_backslashes {
+            return None;
+        }
+        let s = if escape_backslashes {
+            s.replace('\\', "\\\\")
+        } else {
+            s.clone()
+        };
+        Some(Expr::value(Value::SingleQuotedString(s)))
+    }
+
+    /// A boolean as `1` or `0` on SQLite, which stores booleans, JSON ones
+    /// included, as integers, and as `true` or `false` elsewhere
+    fn boolean_literal(&self, b: bool) -> Expr {
+        match self.config.target {
+            SqlTarget::Sqlite => number_literal(b as usize),
+            _ => Expr::value(Value::Boolean(b)),
+        }
+    }
+
+    /// Translate `=`, `<>` and `IN` comparisons of `id(n)` or `elementId(n)`
+    /// with literals of the other type than the id column, converting the
+    /// literals rather than casting the column
+    fn id_lookup(&self, expr: &Expr, plan: &GraphPlan) -> Option<Expr> {
+        match expr {
+            Expr::BinaryOp {
+                left,
+                op: op @ (BinaryOperator::Eq | BinaryOperator::NotEq),
+                right,
+            } => {
+                let (binding, value) = match (id_function(left, plan), id_function(right, plan)) {
+                    (Some((binding, id_type)), _) => (binding, self.id_literal(right, id_type)?),
+                    (_, Some((binding, id_type))) => (binding, self.id_literal(left, id_type)?),
+                    _ => return None,
+                };
+                let column = Expr::CompoundIdentifier(vec![
+                    binding.table_ref(),
+                    Ident::new(&self.config.id_column),
+                ]);
+                let (left, right) = if id_function(left, plan).is_some() {
+                    (column, value)
+                } else {
+                    (value, column)
+                };
+                Some(Expr::BinaryOp {
+                    left: Box::new(left),
+                    op: op.clone(),
+                    right: Box::new(right),
+                })
+            }
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let (binding, id_type) = id_function(expr, plan)?;
+                Some(Expr::InList {
+                    expr: Box::new(Expr::CompoundIdentifier(vec![
+                        binding.table_ref(),
+                        Ident::new(&self.config.id_column),
+                    ])),
+                    list: list
+                        .iter()
+                        .map(|value| self.id_literal(value, id_type))
+                        .collect::<Option<_>>()?,
+                    negated: *negated,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// A literal compared with an id of `id_type`, as a literal of the id
+    /// column's type, or `None` when the types match or it cannot be converted
+    fn id_literal(&self, expr: &Expr, id_type: IdType) -> Option<Expr> {
+        let Expr::Value(value) = expr else {
+            return None;
+        };
+        match (&value.value, id_type, self.config.id_type) {
+            (Value::Number(n, _), IdType::Integer, IdType::Text)
+            | (Value::Number(n, _), _, IdType::Uuid) => {
+                Some(Expr::value(Value::SingleQuotedString(n.to_string())))
+            }
+            (Value::SingleQuotedString(s) | Value::DoubleQuotedString(s), _, IdType::Uuid) => {
+                Some(Expr::value(Value::SingleQuotedString(s.clone())))
+            }
+            (
+                Value::SingleQuotedString(s) | Value::DoubleQuotedString(s),
+                IdType::Text,
+                IdType::Integer,
+            ) => s.parse().ok().map(number_literal),
+            _ => None,
+        }
+    }
+
+    /// The id column of a node binding, cast to `id_type` if needed
+    fn id_column(&self, binding: &Binding, id_type: IdType) -> Expr {
+        let column = Expr::CompoundIdentifier(vec![
+            binding.table_ref(),
+            Ident::new(&self.config.id_column),
+        ]);
+        self.id_cast(column, id_type)
+    }
+
+    /// The id `column` as the type of `id(n)` or `elementId(n)`, cast when
+    /// it is not the type of the ids
+    fn id_cast(&self, column: Expr, id_type: IdType) -> Expr {
+        if id_type == self.config.id_type {
+            return column;
+        }
+        Expr::Cast {
+            kind: CastKind::Cast,
+            expr: Box::new(column),
+            data_type: if id_type == IdType::Integer && self.config.id_type != IdType::Uuid {
+                DataType::Integer(None)
+            } else {
+                DataType::Text
+            },
+            format: None,
+        }
+    }
+
+    /// The SQL of `count(v)` of a node or relationship variable, counting
+    /// the rows where it is bound by a column that is never null in them:
+    /// the id of a node, or the start of a relationship, which does not
+    /// tell relationships apart for `count(DISTINCT r)`
+    fn variable_count(&self, expr: &Expr, plan: &GraphPlan) -> Option<Expr> {
+        let (name, binding) = variable_function(expr, plan)?;
+        let Expr::Function(function) = expr else {
+            return None;
+        };
+        if name != "count" {
+            return None;
+        }
+        let distinct = matches!(
+            &function.args,
+            FunctionArguments::List(list)
+                if list.duplicate_treatment == Some(DuplicateTreatment::Distinct)
+        );
+        let column = match binding.kind {
+            BindingKind::Node => &self.config.id_column,
+            BindingKind::Relationship if !distinct => "from_id",
+            BindingKind::Relationship => return None,
+        };
+        let mut function = function.clone();
+        if let FunctionArguments::List(list) = &mut function.args {
+            list.args = vec![FunctionArg::Unnamed(FunctionArgExpr::Expr(
+                Expr::CompoundIdentifier(vec![binding.table_ref(), Ident::new(column)]),
+            ))];
+        }
+        Some(Expr::Function(function))
+    }
+
+    /// Transform CREATE statement to INSERT
+    ///
+    /// Every node becomes a row of its label table, with a column per
+    /// property, or, on the generic node table, a row holding its first label
+    /// and its JSON properties.
+    ///
+    /// Every node is inserted before the relationships, which take the ids of
+    /// their endpoints as parameters named after the nodes' variables, with
+    /// variables generated for anonymous nodes.
+    fn transform_create(
+        &self,
+        patterns: &[Pattern],
+        r#return: Option<&ReturnClause>,
+        created_nodes: &mut Vec<CreatedNode>,
+    ) -> Result<Vec<Statement>, TransformError> {
+        let created = CreatedElements::new(&[], patterns)?;
+        let mut statements = vec![];
+
+        for (variable, element) in &created.nodes {
+            let (table_name, mut columns, mut values) = self.node_row(element, &[])?;
+            if let Some(id) = self.generated_id()? {
+                columns.insert(0, Ident::new(&self.config.id_column));
+                values.insert(0, id);
+            }
+            let source = (!values.is_empty()).then(|| values_query(values));
+            created_nodes.push(CreatedNode {
+                variable: variable.clone(),
+                synthetic: element.variable().is_none(),
+                statement: statements.len(),
+            });
+            statements.push(insert_statement(
+                table_ident(&table_name, self.config.target),
+                columns,
+                source,
+            ));
+        }
+        for (from, to, element) in &created.relationships {
+            let [from, to] = [from, to].map(|endpoint| match endpoint {
+                CreatedEndpoint::Created(i) => {
+                    Expr::value(Value::Placeholder(format!("${}", created.nodes[*i].0)))
+                }
+                CreatedEndpoint::Matched(_) => unreachable!("CREATE without MATCH"),
+            });
+            let (columns, values) = self.relationship_row(element, from, to)?;
+            statements.push(insert_statement(
+                table_ident(&self.config.relationship_table, self.config.target),
+                columns,
+                Some(values_query(values)),
+            ));
+        }
+        if let Some(ret) = r#return {
+            self.create_return(&created, ret, &mut statements, created_nodes)?;
+        }
+
+        Ok(statements)
+    }
+
+    /// Return what the RETURN of a CREATE asks for: with `RETURNING` on the
+    /// insert of the node whose id it returns, or of the last node, or else
+    /// with a `SELECT` of the id the database assigned, right after that
+    /// insert
+    ///
+    /// The inserted rows are not read back, so only the id of one created
+    /// node and the literal properties the elements were created with can
+    /// be returned.
+    fn create_return(
+        &self,
+        created: &CreatedElements,
+        ret: &ReturnClause,
+        statements: &mut Vec<Statement>,
+        created_nodes: &mut [CreatedNode],
+    ) -> Result<(), TransformError> {
+        let unsupported = |what: String| {
+            TransformError::Unsupported(format!(
+                "{what} in the RETURN of a CREATE, which returns only id() or elementId() \
+                 of a created node and the properties it was created with"
+            ))
+        };
+        if !ret.order_by.is_empty() || ret.skip.is_some() || ret.limit.is_some() {
+            return Err(unsupported("ORDER BY, SKIP or LIMIT".to_string()));
+        }
+        let returning = self.config.target.supports_returning();
+        let mut node = None;
+        let mut items = vec![];
+        for item in &ret.items {
+            let (expr, alias) = match item {
+                SelectItem::UnnamedExpr(expr) => (expr, None),
+                SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias)),
+                _ => return Err(unsupported(item.to_string())),
+            };
+            if let Some((name, variable)) = function_variable(expr) {
+                let id_type = match name.as_str() {
+                    "id" => IdType::Integer,
+                    "elementid" => IdType::Text,
+                    _ => return Err(unsupported(expr.to_string())),
+                };
+                let index = created
+                    .nodes
+                    .iter()
+                    .position(|(_, element)| element.variable() == Some(variable))
+                    .ok_or_else(|| unsupported(expr.to_string()))?;
+                if node.is_some_and(|node| node != index) {
+                    return Err(unsupported(
+                        "the ids of more than one created node".to_string(),
+                    ));
+                }
+                node = Some(index);
+                let id = if returning {
+                    Expr::Identifier(Ident::new(&self.config.id_column))
+                } else if self.config.id_type == IdType::Integer {
+                    // Only ids the database assigns are read back
+                    function_call(self.config.target.last_insert_id_function(), vec![])
+                } else {
+                    return Err(TransformError::Unsupported(format!(
+                        "{expr} in the RETURN of a CREATE of {:?} ids on {:?}",
+                        self.config.id_type, self.config.target
+                    )));
+                };
+                let id = self.id_cast(id, id_type);
+                items.push(match alias {
+                    Some(alias) => SelectItem::ExprWithAlias {
+                        expr: id,
+                        alias: alias.clone(),
+                    },
+                    None => SelectItem::UnnamedExpr(id),
+                });
+                continue;
+            }
+            let (variable, key) = match expr {
+                Expr::CompoundIdentifier(idents) if idents.len() == 2 => (&idents[0], &idents[1]),
+                _ => return Err(unsupported(expr.to_string())),
+            };
+            let properties = created
+                .properties(variable)
+                .ok_or_else(|| unsupported(expr.to_string()))?;
+            let value = self
+                .created_property(properties, key)
+                .ok_or_else(|| unsupported(expr.to_string()))?;
+            items.push(SelectItem::ExprWithAlias {
+                expr: value,
+                alias: alias.unwrap_or(key).clone(),
+            });
+        }
+
+        let mut names = vec![];
+        for item in &items {
+            if let SelectItem::ExprWithAlias { alias, .. } = item {
+                if names.contains(&&alias.value) {
+                    return Err(duplicate_column(&alias.value));
+                }
+                names.push(&alias.value);
+            }
+        }
+
+        let node = node.unwrap_or(created_nodes.len() - 1);
+        let statement = created_nodes[node].statement;
+        if returning {
+            if let Statement::Insert(insert) = &mut statements[statement] {
+                insert.returning = Some(items);
+            }
+        } else {
+            // The id is the one of the last insert, so the select follows it
+            statements.insert(statement + 1, Statement::Query(Box::new(select_row(items))));
+            for created in &mut created_nodes[node + 1..] {
+                created.statement += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// The SQL literal of the property `key` a node or relationship was
+    /// created with, null when it has none, or `None` when its value is not
+    /// a literal
+    fn created_property(&self, properties: Option<&Expr>, key: &Ident) -> Option<Expr> {
+        let Some(Expr::Dictionary(fields)) = properties else {
+            return properties.is_none().then(|| Expr::value(Value::Null));
+        };
+        let Some(field) = fields.iter().find(|field| field.key.value == key.value) else {
+            return Some(Expr::value(Value::Null));
+        };
+        match field.value.as_ref() {
+            Expr::Value(ValueWithSpan {
+                value: Value::Boolean(b),
+                ..
+            }) => Some(self.boolean_literal(*b)),
+            Expr::Value(ValueWithSpan {
+                value: Value::Placeholder(_),
+                ..
+            }) => None,
+            value @ Expr::Value(_) => Some(value.clone()),
+            value if is_negative_number(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    /// The table, columns and values of the row `CREATE` inserts for a node,
+    /// other than its id
+    fn node_row(
+        &self,
+        element: &PatternElement,
+        row_columns: &[&Ident],
+    ) -> Result<(String, Vec<Ident>, Vec<Expr>), TransformError> {
+        let properties = element.properties();
+        match element.labels_or_types().first() {
+            Some(label) if self.config.use_label_tables => {
+                let (columns, values) = self.property_columns(properties, row_columns)?;
+                Ok((label.value.clone(), columns, values))
+            }
+            label => {
+                let label = match label {
+                    Some(label) => Expr::value(Value::SingleQuotedString(label.value.clone())),
+                    None => Expr::value(Value::Null),
+                };
+                let properties = self.json_properties(properties, row_columns)?;
+                Ok((
+                    self.config.node_table.clone(),
+                    vec![Ident::new("label"), Ident::new("properties")],
+                    vec![label, properties],
+                ))
+            }
+        }
+    }
+
+    /// The columns of a label table a created node's `properties` are stored
+    /// in, one per property key, and their values; null properties are left
+    /// to the column default
+    fn property_columns(
+        &self,
+        properties: Option<&Expr>,
+        row_columns: &[&Ident],
+    ) -> Result<(Vec<Ident>, Vec<Expr>), TransformError> {
+        let fields = match properties {
+            None => &[][..],
+            Some(Expr::Dictionary(fields)) => fields,
+            Some(expr) => {
+                return Err(TransformError::Unsupported(format!(
+                    "property map {expr} in a CREATE pattern"
+                )))
+            }
+        };
+        let mut columns = vec![];
+        let mut values = vec![];
+        for field in fields {
+            let value = field.value.as_ref();
+            let value = match (json_value(value), value) {
+                (
+                    _,
+                    Expr::Value(ValueWithSpan {
+                        value: Value::Null, ..
+                    }),
+                ) => continue,
+                (
+                    Some(_),
+                    Expr::Value(ValueWithSpan {
+                        value: Value::Boolean(b),
+                        ..
+                    }),
+                ) => self.boolean_literal(*b),
+                // Lists and maps are stored as JSON
+                (Some(json), Expr::Array(_) | Expr::Dictionary(_)) => self.json_member(value, json),
+                (Some(_), _) => self.string_literal(value).unwrap_or_else(|| value.clone()),
+                (None, _) => self.property_function(value, row_columns)?,
+            };
+            columns.push(field.key.clone());
+            values.push(value);
+        }
+        Ok((columns, values))
+    }
+
+    /// The columns and values of the row `CREATE` inserts for a
+    /// relationship between the nodes with ids `from` and `to`
+    fn relationship_row(
+        &self,
+        element: &PatternElement,
+        from: Expr,
+        to: Expr,
+    ) -> Result<(Vec<Ident>, Vec<Expr>), TransformError> {
+        let rel_type = &element.labels_or_types()[0];
+        let columns = ["from_id", "to_id", "type", "properties"].map(Ident::new);
+        let values = vec![
+            from,
+            to,
+            Expr::value(Value::SingleQuotedString(rel_type.value.clone())),
+            self.json_properties(element.properties(), &[])?,
+        ];
+        Ok((columns.to_vec(), values))
+    }
+
+    /// Transform a CREATE that follows a MATCH: what it creates is selected
+    /// from the rows of the MATCH, once for each of them, with the ids of the
+    /// matched nodes it connects
+    ///
+    /// A node created along with a relationship to it needs its id before
+    /// either is inserted, so the ids are generated in a CTE of the matched
+    /// rows, `matched`, that every insert of a single statement selects from.
+    fn transform_match_create(
+        &self,
+        match_patterns: &[Pattern],
+        where_clause: &Option<Expr>,
+        patterns: &[Pattern],
+        selects: &mut Vec<Vec<Binding>>,
+    ) -> Result<Vec<Statement>, TransformError> {
+        let created = CreatedElements::new(match_patterns, patterns)?;
+        let id_column = Ident::new(&self.config.id_column);
+
+        let linked_node = created.relationships.iter().find_map(|(from, to, _)| {
+            [from, to].into_iter().find_map(|endpoint| match endpoint {
+                CreatedEndpoint::Created(i) => Some(created.nodes[*i].1),
+                CreatedEndpoint::Matched(_) => None,
+            })
+        });
+        let Some(linked_node) = linked_node else {
+            // Everything is inserted from the matched rows
+            let mut statements = vec![];
+            for (_, element) in &created.nodes {
+                let (table_name, mut columns, mut values) = self.node_row(element, &[])?;
+                if let Some(id) = self.generated_id()? {
+                    columns.insert(0, id_column.clone());
+                    values.insert(0, id);
+                }
+                if values.is_empty() {
+                    return Err(TransformError::Unsupported(format!(
+                        "CREATE {element} for each row of a MATCH with label tables"
+                    )));
+                }
+                let projection = values.into_iter().map(SelectItem::UnnamedExpr).collect();
+                let source =
+                    self.matched_rows(match_patterns, where_clause, projection, selects)?;
+                statements.push(insert_statement(
+                    table_ident(&table_name, self.config.target),
+                    columns,
+                    Some(source),
+                ));
+            }
+            for (from, to, element) in &created.relationships {
+                let [from, to] = [from, to].map(|endpoint| match endpoint {
+                    CreatedEndpoint::Matched(variable) => {
+                        Expr::CompoundIdentifier(vec![(*variable).clone(), id_column.clone()])
+                    }
+                    CreatedEndpoint::Created(_) => unreachable!("no created endpoints"),
+                });
+                let (columns, values) = self.relationship_row(element, from, to)?;
+                let projection = values.into_iter().map(SelectItem::UnnamedExpr).collect();
+                let source =
+                    self.matched_rows(match_patterns, where_clause, projection, selects)?;
+                statements.push(insert_statement(
+                    table_ident(&self.config.relationship_table, self.config.target),
+                    columns,
+                    Some(source),
+                ));
+            }
+            return Ok(statements);
+        };
+
+        if !self.config.target.supports_data_modifying_cte() {
+            return Err(TransformError::Unsupported(format!(
+                "a relationship between a matched node and the created node {linked_node} \
+                 on {:?}, which cannot insert in a WITH query",
+                self.config.target
+            )));
+        }
+        let id_alias = |key: &str| Ident::new(format!("{key}_id"));
+        let mut matched = vec![];
+        for (from, to, _) in &created.relationships {
+            for endpoint in [from, to] {
+                if let CreatedEndpoint::Matched(variable) = endpoint {
+                    let item = SelectItem::ExprWithAlias {
+                        expr: Expr::CompoundIdentifier(vec![
+                            (*variable).clone(),
+                            id_column.clone(),
+                        ]),
+                        alias: id_alias(&variable.value),
+                    };
+                    if !matched.contains(&item) {
+                        matched.push(item);
+                    }
+                }
+            }
+        }
+        for (key, _) in &created.nodes {
+            let Some(id) = self.generated_id()? else {
+                return Err(TransformError::Unsupported(format!(
+                    "a relationship between a matched node and the created node {linked_node} \
+                     without ids generated by the transformer, as with IdType::Uuid"
+                )));
+            };
+            matched.push(SelectItem::ExprWithAlias {
+                expr: id,
+                alias: id_alias(key),
+            });
+        }
+        let matched_query = self.matched_rows(match_patterns, where_clause, matched, selects)?;
+
+        let cte = |name: String, query: Query| Cte {
+            alias: TableAlias {
+                name: Ident::new(name),
+                columns: vec![],
+            },
+            query: Box::new(query),
+            from: None,
+            materialized: None,
+            closing_paren_token: AttachedToken::empty(),
+        };
+        let from_matched = |values: Vec<Expr>| {
+            let projection = values.into_iter().map(SelectItem::UnnamedExpr).collect();
+            select_query(projection, "matched")
+        };
+        let insert_query = |insert: Statement| set_query(SetExpr::Insert(insert));
+
+        let mut ctes = vec![cte("matched".to_string(), matched_query)];
+        for (key, element) in &created.nodes {
+            let (table_name, mut columns, mut values) = self.node_row(element, &[])?;
+            columns.insert(0, id_column.clone());
+            values.insert(0, Expr::Identifier(id_alias(key)));
+            let insert = insert_statement(
+                table_ident(&table_name, self.config.target),
+                columns,
+                Some(from_matched(values)),
+            );
+            ctes.push(cte(format!("created_{key}"), insert_query(insert)));
+        }
+        let mut inserts = vec![];
+        for (from, to, element) in &created.relationships {
+            let [from, to] = [from, to].map(|endpoint| {
+                Expr::Identifier(id_alias(&match endpoint {
+                    CreatedEndpoint::Matched(variable) => variable.value.clone(),
+                    CreatedEndpoint::Created(i) => created.nodes[*i].0.clone(),
+                }))
+            });
+            let (columns, values) = self.relationship_row(element, from, to)?;
+            inserts.push(insert_statement(
+                table_ident(&self.config.relationship_table, self.config.target),
+                columns,
+                Some(from_matched(values)),
+            ));
+        }
+        let last = inserts
+            .pop()
+            .expect("a created node is linked by a relationship");
+        for (i, insert) in inserts.into_iter().enumerate() {
+            ctes.push(cte(
+                format!("created_relationship_{}", i + 1),
+                insert_query(insert),
+            ));
+        }
+
+        Ok(vec![Statement::Query(Box::new(Query {
+            with: Some(With {
+                with_token: AttachedToken::empty(),
+                recursive: false,
+                cte_tables: ctes,
+            }),
+            ..set_query(SetExpr::Insert(last))
+        }))])
+    }
+
+    /// Transform an UNWIND and the query run for each element of its list:
+    /// the rows of the list, one column named after the variable, are joined
+    /// to the FROM clause of the MATCH or RETURN after it, or are the rows a
+    /// CREATE of nodes inserts
+    ///
+    /// A created node is inserted once per element, so the inserts of its
+    /// relationships, which take its id as a parameter, would not know
+    /// which node to connect, and CREATE after UNWIND creates nodes only.
+    fn transform_unwind(
+        &self,
+        list: &Expr,
+        variable: &Ident,
+        query: &CypherStatement,
+        selects: &mut Vec<Vec<Binding>>,
+    ) -> Result<Vec<Statement>, TransformError> {
+        let (with, rows) = self.unwound_rows(list, variable)?;
+        // A WITH passing the variable on to the query after its subquery
+        // names the column
+        let mut query = query.clone();
+        if let CypherStatement::Match {
+            with: Some(with),
+            next,
+            ..
+        } = &mut query
+        {
+            let subquery = next.is_some() || has_row_modifiers(with);
+            for item in &mut with.projection.items {
+                if subquery
+                    && matches!(item, SelectItem::UnnamedExpr(Expr::Identifier(ident)) if ident == variable)
+                {
+                    *item = SelectItem::ExprWithAlias {
+                        expr: Expr::Identifier(variable.clone()),
+                        alias: variable.clone(),
+                    };
+                }
+            }
+        }
+        // The rows of the list cannot be joined into a graph table
+        if let (Some(_), CypherStatement::Match { .. }) = (&self.config.property_graph, &query) {
+            return Err(TransformError::Unsupported(format!(
+                "UNWIND before {query} in GRAPH_TABLE"
+            )));
+        }
+        let query = &query;
+        let merged = merge_reading_clauses(query)?;
+        let (query, optional_matches) = match &merged {
+            Some((statement, optional_matches)) => (statement, &optional_matches[..]),
+            None => (query, &[][..]),
+        };
+        let mut statements = match query {
+            CypherStatement::Match {
+                patterns,
+                r#where,
+                with: Some(with),
+                next: Some(next),
+                ..
+            } => {
+                let query = self.with_query(patterns, r#where, with, next, selects)?;
+                vec![Statement::Query(Box::new(query))]
+            }
+            CypherStatement::Match {
+                patterns,
+                r#where,
+                with,
+                r#return,
+                ..
+            } => {
+                self.transform_match(patterns, optional_matches, r#where, with, r#return, selects)?
+            }
+            CypherStatement::Create {
+                r#match,
+                patterns,
+                r#return: None,
+                ..
+            } if r#match.is_empty() => self.unwound_create(patterns, variable)?,
+            _ => return Err(TransformError::Unsupported(format!("{query} after UNWIND"))),
+        };
+
+        for statement in &mut statements {
+            let query = match statement {
+                Statement::Query(query) => query,
+                Statement::Insert(Insert {
+                    source: Some(source),
+                    ..
+                }) => source,
+                _ => continue,
+            };
+            add_rows(query.body.as_mut(), &rows);
+            if let Some(with) = with.clone() {
+                match &mut query.with {
+                    Some(existing) => {
+                        existing.recursive |= with.recursive;
+                        existing.cte_tables.splice(0..0, with.cte_tables);
+                    }
+                    None => query.with = Some(with),
+                }
+            }
+        }
+        Ok(statements)
+    }
+
+    /// The rows of the elements of the list of an UNWIND, as the table
+    /// [`UNWOUND_ROWS_ALIAS`] whose column is named after `variable`, with
+    /// the CTE defining it, if any
+    ///
+    /// A list literal is a `VALUES` query, as is a `range(start, stop[,
+    /// step])` of integer literals of at most
+    /// [`TransformConfig::range_values_limit`] elements. Other ranges are
+    /// generated, by `generate_series` on PostgreSQL and DuckDB and by a
+    /// recursive CTE elsewhere, which on MySQL stops at its
+    /// `cte_max_recursion_depth` of 1000 rows unless that is raised.
+    fn unwound_rows(
+        &self,
+        list: &Expr,
+        variable: &Ident,
+    ) -> Result<(Option<With>, TableWithJoins), TransformError> {
+        let rows = |relation| TableWithJoins {
+            relation,
+            joins: vec![],
+        };
+        let alias = TableAlias {
+            name: Ident::new(UNWOUND_ROWS_ALIAS),
+            columns: vec![TableAliasColumnDef {
+                name: variable.clone(),
+                data_type: None,
+            }],
+        };
+        let cte_rows = |query: Query, recursive: bool| {
+            let with = With {
+                with_token: AttachedToken::empty(),
+                recursive,
+                cte_tables: vec![Cte {
+                    alias: alias.clone(),
+                    query: Box::new(query),
+                    from: None,
+                    materialized: None,
+                    closing_paren_token: AttachedToken::empty(),
+                }],
+            };
+            let name = ObjectName::from(vec![Ident::new(UNWOUND_ROWS_ALIAS)]);
+            Ok((Some(with), rows(named_table(name, false))))
+        };
+        let plan = GraphPlan::default();
+
+        if let Expr::Array(array) = list {
+            let elements = array
+                .elem
+                .iter()
+                .map(|element| self.rewrite_expr(element, &plan));
+            return cte_rows(self.values_rows(elements.collect()), false);
+        }
+        let (start, stop, step) = match function_parts(list) {
+            Some((name, args)) if name.eq_ignore_ascii_case("range") => match &args[..] {
+                [start, stop] => (start.clone(), stop.clone(), None),
+                [start, stop, step] => (start.clone(), stop.clone(), Some(step.clone())),
+                _ => {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "{list} takes a start, a stop and an optional step"
+                    )))
+                }
+            },
+            _ => {
+                return Err(TransformError::Unsupported(format!(
+                    "UNWIND of {list}, which is neither a list literal nor a range()"
+                )))
+            }
+        };
+        let literal_step = match &step {
+            Some(step) => integer_literal(step),
+            None => Some(1),
+        };
+        if literal_step == Some(0) {
+            return Err(TransformError::InvalidQuery(format!(
+                "{list} has a step of 0"
+            )));
+        }
+
+        // A short range of literals is listed
+        if let (Some(first), Some(last), Some(step)) = (
+            integer_literal(&start),
+            integer_literal(&stop),
+            literal_step,
+        ) {
+            let count = if (step > 0 && first <= last) || (step < 0 && first >= last) {
+                (last.abs_diff(first) / step.unsigned_abs()).saturating_add(1)
+            } else {
+                0
+            };
+            if count <= self.config.range_values_limit as u64 {
+                let elements = (0..count as i64)
+                    .map(|i| first + i * step)
+                    .map(integer_number)
+                    .collect();
+                return cte_rows(self.values_rows(elements), false);
+            }
+        }
+
+        let [start, stop] = [&start, &stop].map(|bound| self.rewrite_expr(bound, &plan));
+        let step = step.map(|step| self.rewrite_expr(&step, &plan));
+        if matches!(self.config.target, SqlTarget::Postgres | SqlTarget::DuckDb) {
+            let args = [Some(start), Some(stop), step]
+                .into_iter()
+                .flatten()
+                .map(|arg| FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)))
+                .collect();
+            let name = ObjectName::from(vec![Ident::new("generate_series")]);
+            let mut series = named_table(name, true);
+            if let TableFactor::Table {
+                alias: series_alias,
+                args: Some(function_args),
+                ..
+            } = &mut series
+            {
+                *series_alias = Some(alias);
+                function_args.args = args;
+            }
+            return Ok((None, rows(series)));
+        }
+
+        // The elements from `start` on while they do not pass `stop`, in the
+        // direction of the step
+        let step_value = step.clone().unwrap_or_else(|| number_literal(1));
+        let in_range = |n: Expr| {
+            let compare = |op, n: Expr| Expr::BinaryOp {
+                left: Box::new(n),
+                op,
+                right: Box::new(stop.clone()),
+            };
+            let and = |left, right| Expr::BinaryOp {
+                left: Box::new(left),
+                op: BinaryOperator::And,
+                right: Box::new(right),
+            };
+            let sign = |op| Expr::BinaryOp {
+                left: Box::new(step_value.clone()),
+                op,
+                right: Box::new(number_literal(0)),
+            };
+            match literal_step {
+                Some(step) if step > 0 => compare(BinaryOperator::LtEq, n),
+                Some(_) => compare(BinaryOperator::GtEq, n),
+                None => Expr::Nested(Box::new(Expr::BinaryOp {
+                    left: Box::new(and(
+                        sign(BinaryOperator::Gt),
+                        compare(BinaryOperator::LtEq, n.clone()),
+                    )),
+                    op: BinaryOperator::Or,
+                    right: Box::new(and(
+                        sign(BinaryOperator::Lt),
+                        compare(BinaryOperator::GtEq, n),
+                    )),
+                })),
+            }
+        };
+        let next = Expr::BinaryOp {
+            left: Box::new(Expr::Identifier(variable.clone())),
+            op: BinaryOperator::Plus,
+            right: Box::new(step_value.clone()),
+        };
+        let mut first = select_row(vec![SelectItem::UnnamedExpr(start.clone())]);
+        if let SetExpr::Select(select) = first.body.as_mut() {
+            select.selection = Some(in_range(start));
+        }
+        let name = ObjectName::from(vec![Ident::new(UNWOUND_ROWS_ALIAS)]);
+        let rest = select_from(
+            vec![SelectItem::UnnamedExpr(next.clone())],
+            named_table(name, false),
+            Some(in_range(next)),
+        );
+        cte_rows(
+            set_query(SetExpr::SetOperation {
+                op: SetOperator::Union,
+                set_quantifier: SetQuantifier::All,
+                left: first.body,
+                right: rest.body,
+            }),
+            true,
+        )
+    }
+
+    /// A `VALUES` query of a row for each of `elements`, or a query of no
+    /// rows without elements, which `VALUES` cannot have
+    fn values_rows(&self, elements: Vec<Expr>) -> Query {
+        if elements.is_empty() {
+            let mut query = select_row(vec![SelectItem::UnnamedExpr(Expr::value(Value::Null))]);
+            if let SetExpr::Select(select) = query.body.as_mut() {
+                select.selection = Some(Expr::BinaryOp {
+                    left: Box::new(number_literal(1)),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(number_literal(0)),
+                });
+            }
+            return query;
+        }
+        set_query(SetExpr::Values(Values {
+            explicit_row: self.config.target == SqlTarget::MySql,
+            rows: elements.into_iter().map(|element| vec![element]).collect(),
+        }))
+    }
+
+    /// The inserts of the nodes a CREATE after an UNWIND creates, one for
+    /// each element of its list, whose property values may use `variable`
+    fn unwound_create(
+        &self,
+        patterns: &[Pattern],
+        variable: &Ident,
+    ) -> Result<Vec<Statement>, TransformError> {
+        let created = CreatedElements::new(&[], patterns)?;
+        if let Some((_, _, element)) = created.relationships.first() {
+            return Err(TransformError::Unsupported(format!(
+                "relationship {element} in a CREATE after UNWIND"
+            )));
+        }
+        let mut statements = vec![];
+        for (_, element) in &created.nodes {
+            let (table_name, mut columns, mut values) = self.node_row(element, &[variable])?;
+            if let Some(id) = self.generated_id()? {
+                columns.insert(0, Ident::new(&self.config.id_column));
+                values.insert(0, id);
+            }
+            // A row of a label table without properties has no columns to
+            // select
+            if columns.is_empty() {
+                return Err(TransformError::Unsupported(format!(
+                    "{element} without properties in a CREATE after UNWIND"
+                )));
+            }
+            let source = select_row(values.into_iter().map(SelectItem::UnnamedExpr).collect());
+            statements.push(insert_statement(
+                table_ident(&table_name, self.config.target),
+                columns,
+                Some(source),
+            ));
+        }
+        Ok(statements)
+    }
+
+    /// The rows of a MATCH, selecting `projection` from each of them
+    fn matched_rows(
+        &self,
+        patterns: &[Pattern],
+        where_clause: &Option<Expr>,
+        projection: Vec<SelectItem>,
+        selects: &mut Vec<Vec<Binding>>,
+    ) -> Result<Query, TransformError> {
+        let mut query = self.match_query(patterns, &[], where_clause, &None, &None, selects)?;
+        if let SetExpr::Select(select) = query.body.as_mut() {
+            select.projection = projection;
+        }
+        Ok(query)
+    }
+
+    /// The id `CREATE` gives a node, or `None` when the database assigns it
+    fn generated_id(&self) -> Result<Option<Expr>, TransformError> {
+        match (self.config.id_type, self.config.target) {
+            (IdType::Integer | IdType::Text, _) => Ok(None),
+            (IdType::Uuid, _) => self.random_uuid("generated UUID ids").map(Some),
+        }
+    }
+
+    /// A random UUID, or an error naming `what` needs one on a database
+    /// without a function for it
+    fn random_uuid(&self, what: &str) -> Result<Expr, TransformError> {
+        match self.config.target {
+            SqlTarget::Sqlite => Err(TransformError::Unsupported(format!("{what} on SQLite"))),
+            SqlTarget::MySql | SqlTarget::DataFusion => Ok(function_call("uuid", vec![])),
+            SqlTarget::Postgres | SqlTarget::DuckDb => Ok(function_call("gen_random_uuid", vec![])),
+        }
+    }
+
+    /// `collect(x)` as the aggregate building a JSON array of `x`, e.g.
+    /// `json_group_array(x)`, or a list on DataFusion, with a variable of the
+    /// generic tables collected as it is returned, or `None` for other
+    /// expressions
+    fn collect_aggregate(&self, expr: &Expr, plan: &GraphPlan) -> Option<Expr> {
+        let Expr::Function(function) = expr else {
+            return None;
+        };
+        if !function.name.to_string().eq_ignore_ascii_case("collect") {
+            return None;
+        }
+        let aggregate = Expr::Function(Function {
+            name: ObjectName::from(vec![Ident::new(
+                self.config.target.json_aggregate_function(),
+            )]),
+            ..function.clone()
+        });
+        Some(map_subexpressions(
+            &aggregate,
+            &mut |sub_expr| match sub_expr {
+                Expr::Identifier(variable) => match plan.binding(&variable.value) {
+                    Some(binding) if !binding.label_table => self.element_object(binding),
+                    _ => sub_expr.clone(),
+                },
+                _ => self.rewrite_expr(sub_expr, plan),
+            },
+        ))
+    }
+
+    /// The SQL of a call of one of the [`MAPPED_FUNCTIONS`], which take no
+    /// arguments, or `None` for other expressions
+    fn mapped_function(&self, expr: &Expr) -> Option<Result<Expr, TransformError>> {
+        let name = zero_arg_function_name(expr)?;
+        let target = self.config.target;
+        let now = || function_call("now", vec![]);
+        let string = |s: &str| Expr::value(Value::SingleQuotedString(s.to_string()));
+        let function = match name.to_lowercase().as_str() {
+            // Milliseconds since the epoch
+            "timestamp" => {
+                let seconds = match target {
+                    SqlTarget::Sqlite => {
+                        function_call("strftime", vec![string("%s"), string("now")])
+                    }
+                    SqlTarget::Postgres | SqlTarget::DataFusion => Expr::Extract {
+                        field: DateTimeField::Epoch,
+                        syntax: ExtractSyntax::From,
+                        expr: Box::new(now()),
+                    },
+                    SqlTarget::MySql => function_call("unix_timestamp", vec![]),
+                    SqlTarget::DuckDb => function_call("epoch", vec![now()]),
+                };
+                Expr::Nested(Box::new(Expr::BinaryOp {
+                    left: Box::new(seconds),
+                    op: BinaryOperator::Multiply,
+                    right: Box::new(number_literal(1000)),
+                }))
+            }
+            "datetime" => match target {
+                SqlTarget::Sqlite => function_call(
+                    "strftime",
+                    vec![string("%Y-%m-%dT%H:%M:%fZ"), string("now")],
+                ),
+                SqlTarget::Postgres
+                | SqlTarget::MySql
+                | SqlTarget::DuckDb
+                | SqlTarget::DataFusion => now(),
+            },
+            "randomuuid" => return Some(self.random_uuid(&expr.to_string())),
+            _ => return None,
+        };
+        Some(Ok(function))
+    }
+
+    /// The JSON text of the property map of a created element, e.g.
+    /// `'{"name":"Ada","active":true}'`, or a JSON object built in SQL when
+    /// the database computes some of its values, e.g.
+    /// `json_object('name', 'Ada', 'id', gen_random_uuid())`
+    ///
+    /// Properties set to `null` are left out, as Cypher does not store them.
+    fn json_properties(
+        &self,
+        properties: Option<&Expr>,
+        row_columns: &[&Ident],
+    ) -> Result<Expr, TransformError> {
+        let fields = match properties {
+            None => &[][..],
+            Some(Expr::Dictionary(fields)) => fields,
+            Some(expr) => {
+                return Err(TransformError::Unsupported(format!(
+                    "property map {expr} in a CREATE pattern"
+                )))
+            }
+        };
+        // The JSON text of literals, and the SQL of other values
+        let mut members = vec![];
+        for field in fields {
+            if matches!(
+                field.value.as_ref(),
+                Expr::Value(ValueWithSpan {
+                    value: Value::Null,
+                    ..
+                })
+            ) {
+                continue;
+            }
+            let value = match json_value(&field.value) {
+                Some(json) => Ok(json),
+                None => Err(self.property_function(&field.value, row_columns)?),
+            };
+            members.push((&field.key.value, field.value.as_ref(), value));
+        }
+
+        if members.iter().all(|(_, _, value)| value.is_ok()) {
+            let members = members
+                .into_iter()
+                .filter_map(|(key, _, json)| Some(format!("{}:{}", json_string(key), json.ok()?)))
+                .collect::<Vec<_>>();
+            let json = Expr::value(Value::SingleQuotedString(format!(
+                "{{{}}}",
+                members.join(",")
+            )));
+            return Ok(self.string_literal(&json).unwrap_or(json));
+        }
+        let mut args = vec![];
+        for (key, expr, value) in members {
+            args.push(Expr::value(Value::SingleQuotedString(key.clone())));
+            args.push(match value {
+                Ok(json) => self.json_member(expr, json),
+                Err(function) => function,
+            });
+        }
+        Ok(function_call(
+            self.config.target.json_object_function(),
+            args,
+        ))
+    }
+
+    /// The SQL of a property value of a created element that is not a
+    /// literal: one of the [`MAPPED_FUNCTIONS`], or another function without
+    /// arguments, which is left as it is, or an expression of the
+    /// `row_columns` of the rows the element is created from
+    fn property_function(
+        &self,
+        expr: &Expr,
+        row_columns: &[&Ident],
+    ) -> Result<Expr, TransformError> {
+        let variables = referenced_variables(expr);
+        match self.mapped_function(expr) {
+            Some(function) => function,
+            None if zero_arg_function_name(expr).is_some() => Ok(expr.clone()),
+            None if !variables.is_empty()
+                && variables
+                    .iter()
+                    .all(|variable| row_columns.contains(variable)) =>
+            {
+                Ok(self.rewrite_expr(expr, &GraphPlan::default()))
+            }
+            None => Err(TransformError::Unsupported(format!(
+                "non-literal property value {expr} in a CREATE pattern"
+            ))),
+        }
+    }
+
+    /// A literal property value, whose JSON text is `json`, as an argument of
+    /// a SQL function building a JSON object
+    fn json_member(&self, expr: &Expr, json: String) -> Expr {
+        match expr {
+            Expr::Array(_) | Expr::Dictionary(_) => {
+                let json = Expr::value(Value::SingleQuotedString(json));
+                if self.config.target.json_stored_as_text() {
+                    function_call("json", vec![json])
+                } else {
+                    Expr::Cast {
+                        kind: CastKind::Cast,
+                        expr: Box::new(json),
+                        data_type: DataType::JSON,
+                        format: None,
+                    }
+                }
+            }
+            Expr::Value(ValueWithSpan {
+                value: Value::Boolean(b),
+                ..
+            }) if self.config.target == SqlTarget::Sqlite => {
+                // SQLite has no boolean type, and would store 1 or 0
+                function_call("json", vec![Expr::value(Value::SingleQuotedString(json))])
+            }
+            Expr::Value(ValueWithSpan {
+                value: Value::Boolean(b),
+                ..
+            }) => self.boolean_literal(*b),
+            _ => self.string_literal(expr).unwrap_or_else(|| expr.clone()),
+        }
+    }
+
+    /// Transform MERGE statement
+    fn transform_merge(
+        &self,
+        patterns: &[Pattern],
+        _actions: &[MergeSetAction],
+    ) -> Result<Vec<Statement>, TransformError> {
+        if let Some(pattern) = patterns.iter().find(|pattern| pattern.elements.len() > 1) {
+            return Err(TransformError::Unsupported(format!(
+                "relationship MERGE {pattern}"
+            )));
+        }
+        // TODO: Implement MERGE transformation (complex - requires UPSERT logic)
+        Err(TransformError::Unsupported("MERGE".to_string()))
+    }
+
+    /// Transform DELETE statement
+    fn transform_delete(
+        &self,
+        _patterns: &[Pattern],
+        _detach: bool,
+        _what: &[Expr],
+        _where_clause: &Option<Expr>,
+    ) -> Result<Vec<Statement>, TransformError> {
+        // TODO: Implement DELETE transformation
+        Err(TransformError::Unsupported("DELETE".to_string()))
+    }
+
+    /// Transform a MATCH ... SET to an UPDATE of the table of each variable
+    /// whose properties it sets, of the rows of the ids the MATCH finds
+    fn transform_set(
+        &self,
+        patterns: &[Pattern],
+        where_clause: &Option<Expr>,
+        items: &[SetClause],
+        selects: &mut Vec<Vec<Binding>>,
+    ) -> Result<Vec<Statement>, TransformError> {
+        let plan = GraphPlan::build(patterns, where_clause, &self.config);
+        let mut updated: Vec<(&Binding, Vec<(&Ident, &Expr)>)> = vec![];
+        for item in items {
+            let SetTarget::Property { variable, property } = &item.target else {
+                return Err(TransformError::Unsupported(format!("SET {item}")));
+            };
+            let binding = match plan.binding(&variable.value) {
+                Some(binding) if binding.kind == BindingKind::Node => binding,
+                // The relationship table has no key to update a row by
+                Some(_) => {
+                    return Err(TransformError::Unsupported(format!(
+                        "SET {item} on a relationship"
+                    )))
+                }
+                None => {
+                    return Err(TransformError::InvalidQuery(format!(
+                        "SET {item} of a variable the MATCH does not bind"
+                    )))
+                }
+            };
+            // Each UPDATE reads the row it updates only
+            let mut pending = vec![&item.value];
+            while let Some(expr) = pending.pop() {
+                let read = match expr {
+                    Expr::Identifier(ident) => Some(ident),
+                    Expr::CompoundIdentifier(idents) => idents.first(),
+                    _ => None,
+                };
+                if read.is_some_and(|read| read.value != variable.value) {
+                    return Err(TransformError::Unsupported(format!(
+                        "SET {item}, whose value reads another variable than {variable}"
+                    )));
+                }
+                pending.extend(subexpressions(expr));
+            }
+            match updated
+                .iter_mut()
+                .find(|(b, _)| b.variable == binding.variable)
+            {
+                Some((_, properties)) => properties.push((property, &item.value)),
+                None => updated.push((binding, vec![(property, &item.value)])),
+            }
+        }
+
+        let id_column = Ident::new(&self.config.id_column);
+        let mut statements = vec![];
+        for (binding, properties) in updated {
+            let alias = binding.table_ref();
+            let assignments = if binding.label_table {
+                properties
+                    .into_iter()
+                    .map(|(property, value)| {
+                        Ok(Assignment {
+                            target: AssignmentTarget::ColumnName(ObjectName::from(vec![
+                                property.clone()
+                            ])),
+                            value: self.set_value(value, false, &plan)?,
+                        })
+                    })
+                    .collect::<Result<_, TransformError>>()?
+            } else {
+                vec![Assignment {
+                    target: AssignmentTarget::ColumnName(ObjectName::from(vec![Ident::new(
+                        "properties",
+                    )])),
+                    value: self.updated_properties(&alias, properties, &plan)?,
+                }]
+            };
+
+            // A MATCH of the single node is the condition of the UPDATE, and
+            // any other a subquery of the ids it finds
+            let id = Expr::CompoundIdentifier(vec![alias.clone(), id_column.clone()]);
+            let rows = self.matched_rows(
+                patterns,
+                where_clause,
+                vec![SelectItem::UnnamedExpr(id.clone())],
+                selects,
+            )?;
+            let selection = match rows.body.as_ref() {
+                SetExpr::Select(select)
+                    if select.from.len() == 1 && select.from[0].joins.is_empty() =>
+                {
+                    select.selection.clone()
+                }
+                _ => {
+                    // MySQL cannot read the updated table in a subquery of
+                    // the UPDATE, but can from a derived table
+                    let rows = if self.config.target == SqlTarget::MySql {
+                        let matched = Ident::new("matched");
+                        select_from(
+                            vec![SelectItem::UnnamedExpr(Expr::CompoundIdentifier(vec![
+                                matched.clone(),
+                                id_column.clone(),
+                            ]))],
+                            TableFactor::Derived {
+                                lateral: false,
+                                subquery: Box::new(rows),
+                                alias: Some(TableAlias {
+                                    name: matched,
+                                    columns: vec![],
+                                }),
+                            },
+                            None,
+                        )
+                    } else {
+                        rows
+                    };
+                    Some(Expr::InSubquery {
+                        expr: Box::new(id),
+                        subquery: Box::new(rows),
+                        negated: false,
+                    })
+                }
+            };
+            statements.push(Statement::Update(Update {
+                table: TableWithJoins {
+                    relation: self.table_factor(binding),
+                    joins: vec![],
+                },
+                assignments,
+                from: None,
+                selection,
+                returning: None,
+                or: None,
+                limit: None,
+            }));
+        }
+        Ok(statements)
+    }
+
+    /// The `properties` of the generic table aliased `alias` with the
+    /// `properties` set to their values, which remove a property when null,
+    /// e.g. `json_patch(n.properties, json_object('age', 37))` on SQLite
+    fn updated_properties(
+        &self,
+        alias: &Ident,
+        properties: Vec<(&Ident, &Expr)>,
+        plan: &GraphPlan,
+    ) -> Result<Expr, TransformError> {
+        let mut members = vec![];
+        for (property, value) in properties {
+            members.push(Expr::value(Value::SingleQuotedString(
+                property.value.clone(),
+            )));
+            members.push(self.set_value(value, true, plan)?);
+        }
+        let current = Expr::CompoundIdentifier(vec![alias.clone(), Ident::new("properties")]);
+        Ok(match self.config.target {
+            SqlTarget::Sqlite => function_call(
+                "json_patch",
+                vec![current, function_call("json_object", members)],
+            ),
+            SqlTarget::MySql | SqlTarget::DuckDb => function_call(
+                "json_merge_patch",
+                vec![current, function_call("json_object", members)],
+            ),
+            // Cypher stores no null properties, so nulls are only those set
+            SqlTarget::Postgres => function_call(
+                "jsonb_strip_nulls",
+                vec![Expr::BinaryOp {
+                    left: Box::new(current),
+                    op: BinaryOperator::StringConcat,
+                    right: Box::new(function_call("jsonb_build_object", members)),
+                }],
+            ),
+            SqlTarget::DataFusion => unreachable!("SET is rejected on DataFusion"),
+        })
+    }
+
+    /// The SQL of the value of a SET item, as a value of a JSON object of
+    /// `json` properties or of a label table column, with `+` appending to
+    /// the lists among its operands
+    fn set_value(
+        &self,
+        value: &Expr,
+        json: bool,
+        plan: &GraphPlan,
+    ) -> Result<Expr, TransformError> {
+        if let Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Plus,
+            right,
+        } = value
+        {
+            if self.is_list(left) || self.is_list(right) {
+                return self.list_append(value, left, right, plan);
+            }
+        }
+        match json_value(value) {
+            Some(text) if json || matches!(value, Expr::Array(_)) => {
+                Ok(self.json_member(value, text))
+            }
+            _ => Ok(self.rewrite_expr(value, plan)),
+        }
+    }
+
+    /// Whether `expr` is a list: a list literal, a property of
+    /// [`TransformConfig::list_properties`], or a `+` of a list
+    fn is_list(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Array(_) => true,
+            Expr::CompoundIdentifier(idents) => matches!(
+                idents.as_slice(),
+                [_, property] if self.config.list_properties.contains(&property.value)
+            ),
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Plus,
+                right,
+            } => self.is_list(left) || self.is_list(right),
+            Expr::Nested(expr) => self.is_list(expr),
+            _ => false,
+        }
+    }
+
+    /// `left + right` of a SET value, one of them a list, as a JSON array:
+    /// `right` appended to `left`, element by element for a list literal,
+    /// e.g. `json_insert(<left>, '$[#]', 'a')` on SQLite and
+    /// `<left> || jsonb_build_array('a')` on PostgreSQL
+    ///
+    /// A left operand that is not a literal is the list appended to, and a
+    /// right one an element unless it is one of the
+    /// [`TransformConfig::list_properties`].
+    fn list_append(
+        &self,
+        value: &Expr,
+        left: &Expr,
+        right: &Expr,
+        plan: &GraphPlan,
+    ) -> Result<Expr, TransformError> {
+        let target = self.config.target;
+        let list = |expr: &Expr| {
+            let list = match expr {
+                Expr::Nested(inner) => self.set_value(inner, false, plan),
+                _ => self.set_value(expr, false, plan),
+            };
+            // A list property is appended to as jsonb rather than as text
+            list.map(|list| match list {
+                Expr::BinaryOp {
+                    left,
+                    op: BinaryOperator::LongArrow,
+                    right,
+                } => Expr::Nested(Box::new(Expr::BinaryOp {
+                    left,
+                    op: BinaryOperator::Arrow,
+                    right,
+                })),
+                list => list,
+            })
+        };
+        let elements = |expr: &Expr| -> Result<Vec<Expr>, TransformError> {
+            let elements = match expr {
+                Expr::Array(array) => array.elem.iter().collect(),
+                _ => vec![expr],
+            };
+            elements
+                .into_iter()
+                .map(|element| self.set_value(element, true, plan))
+                .collect()
+        };
+        // The operands that are lists other than literals
+        let left_list = !matches!(left, Expr::Array(_)) && json_value(left).is_none();
+        let right_list = self.is_list(right) && !matches!(right, Expr::Array(_));
+        match target {
+            SqlTarget::Postgres => {
+                let [left, right] =
+                    [(left, left_list), (right, right_list)].map(|(operand, is_list)| {
+                        if is_list {
+                            list(operand)
+                        } else {
+                            Ok(function_call("jsonb_build_array", elements(operand)?))
+                        }
+                    });
+                Ok(Expr::BinaryOp {
+                    left: Box::new(left?),
+                    op: BinaryOperator::StringConcat,
+                    right: Box::new(right?),
+                })
+            }
+            SqlTarget::Sqlite | SqlTarget::MySql => {
+                // Elements are appended one by one to a list
+                if !(left_list || matches!(left, Expr::Array(_))) || right_list {
+                    return Err(TransformError::Unsupported(format!(
+                        "{value} on {target:?}, which can only append elements to a list"
+                    )));
+                }
+                let (function, path) = match target {
+                    SqlTarget::Sqlite => ("json_insert", "$[#]"),
+                    _ => ("json_array_append", "$"),
+                };
+                let mut args = vec![list(left)?];
+                for element in elements(right)? {
+                    args.push(Expr::value(Value::SingleQuotedString(path.to_string())));
+                    args.push(element);
+                }
+                Ok(function_call(function, args))
+            }
+            SqlTarget::DuckDb | SqlTarget::DataFusion => Err(TransformError::Unsupported(format!(
+                "appending to a list in {value} on {target:?}"
+            ))),
+        }
+    }
+
+    /// `condition` with the properties it tests for truth, directly or under
+    /// `AND`, `OR`, `XOR` and `NOT`, read as `coalesce(<property>, false)`
+    /// under [`TransformConfig::strict_nulls`]
+    fn null_safe_condition(&self, condition: &Expr, plan: &GraphPlan) -> Expr {
+        if !self.config.strict_nulls {
+            return condition.clone();
+        }
+        match condition {
+            Expr::BinaryOp {
+                left,
+                op: op @ (BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Xor),
+                right,
+            } => Expr::BinaryOp {
+                left: Box::new(self.null_safe_condition(left, plan)),
+                op: op.clone(),
+                right: Box::new(self.null_safe_condition(right, plan)),
+            },
+            Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => Expr::UnaryOp {
+                op: UnaryOperator::Not,
+                expr: Box::new(self.null_safe_condition(expr, plan)),
+            },
+            Expr::Nested(expr) => Expr::Nested(Box::new(self.null_safe_condition(expr, plan))),
+            Expr::CompoundIdentifier(idents)
+                if idents.len() == 2 && plan.binding(&idents[0].value).is_some() =>
+            {
+                function_call(
+                    "coalesce",
+                    vec![condition.clone(), Expr::value(Value::Boolean(false))],
+                )
+            }
+            _ => condition.clone(),
+        }
+    }
+
+    /// A condition that reads a PostgreSQL JSON property as text, e.g.
+    /// `WHERE n.active`, cast to a boolean
+    fn truth_value(&self, condition: Expr) -> Expr {
+        if self.config.target == SqlTarget::Postgres && is_json_text(&condition) {
+            json_text_cast(condition, DataType::Boolean)
+        } else {
+            condition
+        }
+    }
+
+    /// Combine multiple conditions with AND
+    pub(crate) fn combine_conditions(&self, conditions: Vec<Expr>) -> Expr {
+        conditions
+            .into_iter()
+            .map(|condition| self.truth_value(condition))
+            .reduce(|acc, expr| Expr::BinaryOp {
+                left: Box::new(acc),
+                op: BinaryOperator::And,
+                right: Box::new(expr),
+            })
+            .unwrap_or_else(|| self.boolean_literal(true))
+    }
+
+    /// Combine multiple conditions with OR
+    fn combine_conditions_with_or(&self, conditions: Vec<Expr>) -> Expr {
+        conditions
+            .into_iter()
+            .map(|condition| self.truth_value(condition))
+            .reduce(|acc, expr| Expr::BinaryOp {
+                left: Box::new(acc),
+                op: BinaryOperator::Or,
+                right: Box::new(expr),
+            })
+            .unwrap_or_else(|| self.boolean_literal(false))
+    }
+}
+
+/// Collect warnings for the constructs of `statement` that the transformer
+/// ignores or approximates
+fn untranslated_features(
+    statement: &CypherStatement,
+    config: &TransformConfig,
+    warnings: &mut Vec<TransformWarning>,
+) {
+    clause_untranslated_features(statement, false, config, warnings)
+}
+
+/// Collect the warnings of [`untranslated_features`] for a clause, which
+/// is `joined` when it follows a MATCH without a WITH between them
+fn clause_untranslated_features(
+    statement: &CypherStatement,
+    joined: bool,
+    config: &TransformConfig,
+    warnings: &mut Vec<TransformWarning>,
+) {
+    let mut warn = |feature: &str, source_text: String| {
+        warnings.push(TransformWarning {
+            feature: feature.to_string(),
+            source_text,
+            kind: WarningKind::Untranslated,
+        })
+    };
+
+    let (patterns, r#where, next) = match statement {
+        CypherStatement::Match {
+            optional,
+            patterns,
+            r#where,
+            with,
+            next,
+            ..
+        } => {
+            // An OPTIONAL MATCH after a MATCH is left joined onto its rows
+            if *optional && !joined {
+                warn("OPTIONAL MATCH", statement.to_string());
+            }
+            (
+                patterns,
+                r#where,
+                next.as_deref().map(|next| (next, with.is_none())),
+            )
+        }
+        CypherStatement::Create {
+            r#match,
+            r#where,
+            patterns: created,
+            ..
+        } => {
+            // A created node is stored with its first label only
+            for element in created.iter().flat_map(|pattern| &pattern.elements) {
+                if element.is_node() && element.labels_or_types().len() > 1 {
+                    warn("extra labels in a CREATE pattern", element.to_string());
+                }
+            }
+            (r#match, r#where, None)
+        }
+        CypherStatement::Union { queries, .. } => {
+            for query in queries {
+                untranslated_features(query, config, warnings);
+            }
+            return;
+        }
+        CypherStatement::Unwind { query, .. } => {
+            return clause_untranslated_features(query, false, config, warnings)
+        }
+        CypherStatement::Recovered { statement } => {
+            return clause_untranslated_features(statement, joined, config, warnings)
+        }
+        _ => return,
+    };
+    // A graph table matches paths of any length
+    let graph_table =
+        config.property_graph.is_some() && matches!(statement, CypherStatement::Match { .. });
+    for element in patterns.iter().flat_map(|pattern| &pattern.elements) {
+        // A row of the node table holds a single label, which cannot equal
+        // all of them
+        if element.is_node() && element.labels_or_types().len() > 1 && !graph_table {
+            warn("multiple labels in a MATCH pattern", element.to_string());
+        }
+        // Matched as a single hop
+        if let PatternElement::Relationship {
+            length: Some(_), ..
+        } = element
+        {
+            if !graph_table {
+                warn("variable-length relationship", element.to_string());
+            }
+        }
+        if element.direction() == Some(&RelationshipDirection::Both)
+            && config.both_directions == BothDirections::Undirected
+        {
+            warn("relationship with both arrows", element.to_string());
+        }
+    }
+    if !graph_table {
+        for source_text in combined_labels(patterns, r#where, config) {
+            warn("multiple labels on a node", source_text);
+        }
+    }
+    if config.relationship_uniqueness && config.relationship_id().is_none() {
+        let plan = GraphPlan::build(patterns, &None, config);
+        let relationships: Vec<usize> = (0..plan.bindings.len())
+            .filter(|&binding| plan.bindings[binding].kind == BindingKind::Relationship)
+            .collect();
+        if !overlapping_relationships(&plan, &relationships).is_empty() {
+            warn(
+                "relationship uniqueness",
+                display_comma_separated(patterns).to_string(),
+            );
+        }
+    }
+    if let Some((next, joined)) = next {
+        clause_untranslated_features(next, joined, config, warnings);
+    }
+}
+
+/// The pattern elements and label predicates of a MATCH that give a node
+/// variable several labels between them, e.g. `(n:Person) WHERE n:Admin`, for
+/// each such variable
+///
+/// The labels of a single element are warned about on their own.
+fn combined_labels(
+    patterns: &[Pattern],
+    r#where: &Option<Expr>,
+    config: &TransformConfig,
+) -> Vec<String> {
+    let plan = GraphPlan::build(patterns, r#where, config);
+    let mut combined = vec![];
+    for binding in &plan.bindings {
+        let Some(variable) = &binding.variable else {
+            continue;
+        };
+        let mut labels: Vec<&str> = binding
+            .labels
+            .iter()
+            .map(|label| label.value.as_str())
+            .collect();
+        labels.sort_unstable();
+        labels.dedup();
+        if binding.kind != BindingKind::Node || labels.len() < 2 {
+            continue;
+        }
+        let elements: Vec<&PatternElement> = patterns
+            .iter()
+            .flat_map(|pattern| &pattern.elements)
+            .filter(|element| {
+                element.is_node()
+                    && element.variable() == Some(variable)
+                    && !element.labels_or_types().is_empty()
+            })
+            .collect();
+        if elements
+            .iter()
+            .any(|element| element.labels_or_types().len() > 1)
+        {
+            continue;
+        }
+        let predicates: Vec<&Expr> = r#where
+            .iter()
+            .flat_map(split_conjunction)
+            .filter(|conjunct| {
+                as_label_predicate(conjunct).is_some_and(|(name, _)| name.value == variable.value)
+            })
+            .collect();
+        let mut source_text = display_comma_separated(&elements).to_string();
+        if !predicates.is_empty() {
+            if !source_text.is_empty() {
+                source_text.push(' ');
+            }
+            source_text.push_str(&format!(
+                "WHERE {}",
+                display_separated(&predicates, " AND ")
+            ));
+        }
+        combined.push(source_text);
+    }
+    combined
+}
+
+/// Warn about the calls of functions without arguments that are neither
+/// [`MAPPED_FUNCTIONS`] nor taken by a [`FunctionRewriter`], which are passed
+/// through to SQL as they are, leaving `exists()` to be rejected, and about
+/// the calls of [`CYPHER_ONLY_FUNCTIONS`] no rewriter takes, but for the
+/// `keys()` of `x IN keys(n)` and those in property accesses
+fn untranslated_functions(
+    transformer: &CypherToSqlTransformer,
+    statement: &CypherStatement,
+    warnings: &mut Vec<TransformWarning>,
+) {
+    let plan = match statement {
+        CypherStatement::Match {
+            patterns, r#where, ..
+        } => GraphPlan::build(patterns, r#where, &transformer.config),
+        _ => GraphPlan::default(),
+    };
+    let mut pending = statement.exprs();
+    pending.reverse();
+    while let Some(expr) = pending.pop() {
+        if let Some(name) = zero_arg_function_name(expr) {
+            if !MAPPED_FUNCTIONS.contains(&name.to_lowercase().as_str())
+                && !name.eq_ignore_ascii_case("exists")
+                && transformer.custom_function(expr, &plan).is_none()
+            {
+                warnings.push(TransformWarning {
+                    feature: "function without arguments".to_string(),
+                    source_text: expr.to_string(),
+                    kind: WarningKind::Untranslated,
+                });
+            }
+        } else if let Some((name, _)) = function_parts(expr) {
+            if CYPHER_ONLY_FUNCTIONS.contains(&name.to_lowercase().as_str())
+                && transformer.custom_function(expr, &plan).is_none()
+            {
+                warnings.push(TransformWarning {
+                    feature: "function without a SQL counterpart".to_string(),
+                    source_text: expr.to_string(),
+                    kind: WarningKind::Untranslated,
+                });
+            }
+        }
+        // Property accesses are either `properties(n).name` or rejected
+        if matches!(expr, Expr::CompoundFieldAccess { .. }) {
+            continue;
+        }
+        if let Expr::Cypher(cypher_expr) = expr {
+            if let CypherExpr::In { expr, list } = cypher_expr.as_ref() {
+                if variable_function(list, &plan).is_some_and(|(name, _)| name == "keys") {
+                    pending.push(expr);
+                    continue;
+                }
+            }
+        }
+        pending.extend(subexpressions(expr).into_iter().rev());
+    }
+}
+
+/// Warn about the `+` of values of unknown types in the values of SET items,
+/// which is translated as numeric addition: of operands that are neither
+/// lists, as [`CypherToSqlTransformer::is_list`] tells them, nor numbers
+fn untyped_additions(
+    transformer: &CypherToSqlTransformer,
+    statement: &CypherStatement,
+    warnings: &mut Vec<TransformWarning>,
+) {
+    let CypherStatement::Set { items, .. } = statement else {
+        return;
+    };
+    for item in items {
+        let mut pending = vec![&item.value];
+        while let Some(expr) = pending.pop() {
+            if let Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Plus,
+                right,
+            } = expr
+            {
+                let typed = |operand: &Expr| {
+                    transformer.is_list(operand)
+                        || matches!(
+                            operand,
+                            Expr::Value(ValueWithSpan {
+                                value: Value::Number(..),
+                                ..
+                            })
+                        )
+                };
+                if !typed(left) && !typed(right) {
+                    warnings.push(TransformWarning {
+                        feature: "`+` of values of unknown types".to_string(),
+                        source_text: item.to_string(),
+                        kind: WarningKind::Untranslated,
+                    });
+                }
+            }
+            pending.extend(subexpressions(expr));
+        }
+    }
+}
+
+/// Warn about the comparisons of `statement` that ignore case under
+/// [`TransformConfig::case_insensitive_text_ops`], in the order they are
+/// written
+fn case_insensitive_comparisons(
+    statement: &CypherStatement,
+    config: &TransformConfig,
+    warnings: &mut Vec<TransformWarning>,
+) {
+    if !config.case_insensitive_text_ops {
+        return;
+    }
+    let mut pending = statement.exprs();
+    pending.reverse();
+    while let Some(expr) = pending.pop() {
+        let operator = match expr {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            } if is_property_with_string(left, right) || is_property_with_string(right, left) => {
+                Some("`=`".to_string())
+            }
+            Expr::Cypher(cypher_expr) => match cypher_expr.as_ref() {
+                CypherExpr::StringPredicate {
+                    expr,
+                    operator,
+                    pattern,
+                } if is_property_with_string(expr, pattern) => Some(format!("`{operator}`")),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(feature) = operator {
+            warnings.push(TransformWarning {
+                feature,
+                source_text: expr.to_string(),
+                kind: WarningKind::CaseInsensitive,
+            });
+        }
+        pending.extend(subexpressions(expr).into_iter().rev());
+    }
+}
+
+/// The warning about the cross join of the `groups` of variables of `query`
+fn cartesian_warning(query: &CypherStatement, groups: &[Vec<String>]) -> TransformWarning {
+    TransformWarning {
+        feature: format!("cartesian product of {}", display_variable_groups(groups)),
+        source_text: query.to_string(),
+        kind: WarningKind::CartesianProduct,
+    }
+}
+
+/// The MATCH queries of `statement` whose patterns fall into more than one
+/// group of connected variables, with those groups
+fn cartesian_products(statement: &CypherStatement) -> Vec<(&CypherStatement, Vec<Vec<String>>)> {
+    match statement {
+        CypherStatement::Match {
+            patterns,
+            r#where,
+            with,
+            next,
+            ..
+        } => {
+            let with_where = with.iter().flat_map(|with| &with.r#where);
+            let conditions: Vec<&Expr> = r#where.iter().chain(with_where).collect();
+            let groups = connected_variable_groups(patterns, &conditions);
+            let mut products = vec![];
+            if groups.len() > 1 {
+                products.push((statement, groups));
+            }
+            products.extend(next.iter().flat_map(|next| cartesian_products(next)));
+            products
+        }
+        CypherStatement::Union { queries, .. } => {
+            queries.iter().flat_map(cartesian_products).collect()
+        }
+        CypherStatement::Unwind { query, .. } => cartesian_products(query),
+        CypherStatement::Recovered { statement } => cartesian_products(statement),
+        _ => vec![],
+    }
+}
+
+/// Group the patterns of a MATCH by the variables that connect them: the
+/// variables of a pattern are connected, and patterns are connected by a
+/// shared variable or by an equality between their variables in one of the
+/// `conditions`
+///
+/// Each group lists its variables, or the text of its patterns when they
+/// have none.
+fn connected_variable_groups(patterns: &[Pattern], conditions: &[&Expr]) -> Vec<Vec<String>> {
+    fn root(parents: &[usize], mut i: usize) -> usize {
+        while parents[i] != i {
+            i = parents[i];
+        }
+        i
+    }
+    fn join(parents: &mut [usize], i: usize, j: usize) {
+        let (i, j) = (root(parents, i), root(parents, j));
+        parents[i.max(j)] = i.min(j);
+    }
+
+    let variables_of = |pattern: &Pattern| -> Vec<Ident> {
+        pattern
+            .elements
+            .iter()
+            .filter_map(PatternElement::variable)
+            .cloned()
+            .collect()
+    };
+    let pattern_of = |variable: &Ident| {
+        patterns
+            .iter()
+            .position(|pattern| variables_of(pattern).contains(variable))
+    };
+
+    let mut parents: Vec<usize> = (0..patterns.len()).collect();
+    for (i, pattern) in patterns.iter().enumerate() {
+        for variable in variables_of(pattern) {
+            if let Some(first) = pattern_of(&variable) {
+                join(&mut parents, first, i);
+            }
+        }
+    }
+    // An equality in a disjunction does not restrict the join
+    for condition in conditions
+        .iter()
+        .flat_map(|condition| split_conjunction(condition))
+    {
+        if let Expr::BinaryOp {
+            op: BinaryOperator::Eq,
+            ..
+        } = condition
+        {
+            let mut compared = referenced_variables(condition)
+                .into_iter()
+                .filter_map(pattern_of);
+            if let Some(first) = compared.next() {
+                for other in compared {
+                    join(&mut parents, first, other);
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<String>)> = vec![];
+    for (i, pattern) in patterns.iter().enumerate() {
+        let group_root = root(&parents, i);
+        let group = match groups.iter().position(|(root, _)| *root == group_root) {
+            Some(group) => group,
+            None => {
+                groups.push((group_root, vec![]));
+                groups.len() - 1
+            }
         };
+        let names = &mut groups[group].1;
+        let variables = variables_of(pattern);
+        if variables.is_empty() {
+            names.push(pattern.to_string());
+        }
+        for variable in variables {
+            if !names.contains(&variable.value) {
+                names.push(variable.value);
+            }
+        }
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Match `id(n)` or `elementId(n)` on a node variable, returning its binding
+/// and the type the function returns
+///
+/// Relationship tables have no id column, so `id(r)` is left as it is.
+fn id_function<'a>(expr: &Expr, plan: &'a GraphPlan) -> Option<(&'a Binding, IdType)> {
+    let (name, binding) = variable_function(expr, plan)?;
+    let id_type = match name.as_str() {
+        "id" => IdType::Integer,
+        "elementid" => IdType::Text,
+        _ => return None,
+    };
+    (binding.kind == BindingKind::Node).then_some((binding, id_type))
+}
+
+/// Match a call of a function on a variable, e.g. `labels(n)`, returning the
+/// function name in lower case and the variable's binding
+fn variable_function<'a>(expr: &Expr, plan: &'a GraphPlan) -> Option<(String, &'a Binding)> {
+    let (name, variable) = function_variable(expr)?;
+    let binding = plan.binding(&variable.value)?;
+    Some((name, binding))
+}
+
+/// Match a call of a function on a single identifier, e.g. `length(p)`,
+/// returning the function name in lower case and the identifier
+fn function_variable(expr: &Expr) -> Option<(String, &Ident)> {
+    let Expr::Function(function) = expr else {
+        return None;
+    };
+    let FunctionArguments::List(list) = &function.args else {
+        return None;
+    };
+    let [FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(variable)))] =
+        list.args.as_slice()
+    else {
+        return None;
+    };
+    Some((function.name.to_string().to_lowercase(), variable))
+}
+
+/// Whether a pattern element is a relationship matched in either orientation
+fn is_undirected(element: &PatternElement, config: &TransformConfig) -> bool {
+    element
+        .direction()
+        .is_some_and(|direction| config.direction(direction) == RelationshipDirection::None)
+}
 
-        from_tables.push(TableWithJoins {
-            relation: table_factor,
+/// Whether the relationship at `index` of `pattern` connects a node variable
+/// to itself, matching the same rows in either orientation
+fn is_self_loop(pattern: &Pattern, index: usize) -> bool {
+    let variable = |i: usize| {
+        let element = pattern
+            .elements
+            .get(i)
+            .filter(|element| element.is_node())?;
+        element.variable().map(|variable| &variable.value)
+    };
+    let start = index.checked_sub(1).and_then(variable);
+    start.is_some() && start == variable(index + 1)
+}
+
+/// Every combination of orientations of the undirected relationships of
+/// `patterns`, each of them made outgoing or incoming
+fn oriented_patterns(patterns: &[Pattern], config: &TransformConfig) -> Vec<Vec<Pattern>> {
+    let mut oriented = vec![patterns.to_vec()];
+    for (i, pattern) in patterns.iter().enumerate() {
+        for (j, element) in pattern.elements.iter().enumerate() {
+            if !is_undirected(element, config) || is_self_loop(pattern, j) {
+                continue;
+            }
+            oriented = oriented
+                .into_iter()
+                .flat_map(|patterns| {
+                    [RelationshipDirection::Right, RelationshipDirection::Left].map(|orientation| {
+                        let mut patterns = patterns.clone();
+                        if let PatternElement::Relationship { direction, .. } =
+                            &mut patterns[i].elements[j]
+                        {
+                            *direction = orientation;
+                        }
+                        patterns
+                    })
+                })
+                .collect();
+        }
+    }
+    oriented
+}
+
+/// Join `rows` to the FROM clause of each SELECT of `body`, or of the
+/// subquery of the rows its WITH passes on, whose FROM clause is that of the
+/// MATCH before the WITH
+fn add_rows(body: &mut SetExpr, rows: &TableWithJoins) {
+    match body {
+        SetExpr::Select(select) => {
+            if let Some(TableWithJoins {
+                relation:
+                    TableFactor::Derived {
+                        subquery,
+                        alias: Some(alias),
+                        ..
+                    },
+                ..
+            }) = select.from.first_mut()
+            {
+                if alias.name.value == WITH_ROWS_ALIAS {
+                    return add_rows(subquery.body.as_mut(), rows);
+                }
+            }
+            select.from.insert(0, rows.clone());
+        }
+        SetExpr::SetOperation { left, right, .. } => {
+            add_rows(left, rows);
+            add_rows(right, rows);
+        }
+        SetExpr::Query(query) => add_rows(query.body.as_mut(), rows),
+        _ => {}
+    }
+}
+
+/// A `VALUES` query of a single row
+fn values_query(row: Vec<Expr>) -> Query {
+    set_query(SetExpr::Values(Values {
+        explicit_row: false,
+        rows: vec![row],
+    }))
+}
+
+/// `SELECT <projection>`, a single row without a table
+fn select_row(projection: Vec<SelectItem>) -> Query {
+    let mut query = select_from(
+        projection,
+        named_table(ObjectName::from(Vec::<Ident>::new()), false),
+        None,
+    );
+    if let SetExpr::Select(select) = query.body.as_mut() {
+        select.from.clear();
+    }
+    query
+}
+
+/// `SELECT <projection> FROM <table>`
+fn select_query(projection: Vec<SelectItem>, table: &str) -> Query {
+    let name = ObjectName::from(vec![Ident::new(table)]);
+    select_from(projection, named_table(name, false), None)
+}
+
+/// The table `name`, or the table function `name()`
+fn named_table(name: ObjectName, function: bool) -> TableFactor {
+    TableFactor::Table {
+        name,
+        alias: None,
+        args: function.then(|| TableFunctionArgs {
+            args: vec![],
+            settings: None,
+        }),
+        with_hints: vec![],
+        version: None,
+        with_ordinality: false,
+        partitions: vec![],
+        json_path: None,
+        sample: None,
+        index_hints: vec![],
+    }
+}
+
+/// `SELECT <projection> FROM <relation> [WHERE <selection>]`
+pub(crate) fn select_from(
+    projection: Vec<SelectItem>,
+    relation: TableFactor,
+    selection: Option<Expr>,
+) -> Query {
+    set_query(SetExpr::Select(Box::new(Select {
+        select_token: AttachedToken::empty(),
+        distinct: None,
+        top: None,
+        top_before_distinct: false,
+        projection,
+        exclude: None,
+        into: None,
+        from: vec![TableWithJoins {
+            relation,
             joins: vec![],
-        });
+        }],
+        lateral_views: vec![],
+        prewhere: None,
+        selection,
+        group_by: GroupByExpr::Expressions(vec![], vec![]),
+        cluster_by: vec![],
+        distribute_by: vec![],
+        sort_by: vec![],
+        having: None,
+        named_window: vec![],
+        qualify: None,
+        window_before_qualify: false,
+        value_table_mode: None,
+        connect_by: None,
+        flavor: SelectFlavor::Standard,
+    })))
+}
 
-        // Add label conditions if using single node table
-        if !self.config.use_label_tables && !labels.is_empty() {
-            let table_ref = variable
-                .as_ref()
-                .map(|v| v.clone())
-                .unwrap_or_else(|| Ident::new(&self.config.node_table));
+/// A query of just `body`
+fn set_query(body: SetExpr) -> Query {
+    Query {
+        with: None,
+        body: Box::new(body),
+        order_by: None,
+        limit_clause: None,
+        fetch: None,
+        locks: vec![],
+        for_clause: None,
+        settings: None,
+        format_clause: None,
+        pipe_operators: vec![],
+    }
+}
 
-            for label in labels {
-                let label_condition = Expr::BinaryOp {
-                    left: Box::new(Expr::CompoundIdentifier(vec![
-                        table_ref.clone(),
-                        Ident::new("label"),
-                    ])),
-                    op: BinaryOperator::Eq,
-                    right: Box::new(Expr::Value(Value::SingleQuotedString(label.value.clone()))),
-                };
-                where_conditions.push(label_condition);
+/// `INSERT INTO <table> (<columns>) <source>`
+fn insert_statement(table: Ident, columns: Vec<Ident>, source: Option<Query>) -> Statement {
+    Statement::Insert(Insert {
+        or: None,
+        ignore: false,
+        into: true,
+        table: TableObject::TableName(ObjectName::from(vec![table])),
+        table_alias: None,
+        columns,
+        overwrite: false,
+        source: source.map(Box::new),
+        assignments: vec![],
+        partitioned: None,
+        after_columns: vec![],
+        has_table_keyword: false,
+        on: None,
+        returning: None,
+        replace_into: false,
+        priority: None,
+        insert_alias: None,
+        settings: None,
+        format_clause: None,
+    })
+}
+
+/// The value of a SKIP or LIMIT: a parameter as it is, or the number an
+/// arithmetic expression of integer literals folds to, e.g. `2 * 3` to `6`
+///
+/// The number of rows must be a non-negative integer, and a value that is
+/// neither a constant nor a parameter, e.g. `n.size`, is an error.
+fn page_value(keyword: &str, value: Option<&Expr>) -> Result<Option<Expr>, TransformError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    if let Expr::Value(ValueWithSpan {
+        value: Value::Placeholder(_),
+        ..
+    }) = value
+    {
+        return Ok(Some(value.clone()));
+    }
+    let invalid = |got: String| {
+        TransformError::InvalidQuery(format!(
+            "{keyword} must be a non-negative integer, got {got}"
+        ))
+    };
+    match fold_integer(value) {
+        Ok(n) => usize::try_from(n)
+            .map(|n| Some(number_literal(n)))
+            .map_err(|_| invalid(n.to_string())),
+        Err(Folding::NotInteger) => Err(invalid(value.to_string())),
+        Err(Folding::NotConstant) => Err(TransformError::InvalidQuery(format!(
+            "{keyword} must be a constant integer or a parameter, got {value}"
+        ))),
+    }
+}
+
+/// Why an expression does not fold to an integer
+enum Folding {
+    /// It is a constant, but not an integer one, e.g. `2.5`, `1 / 0` or a
+    /// result that overflows
+    NotInteger,
+    /// It refers to something other than literals, e.g. a property
+    NotConstant,
+}
+
+/// The integer an expression of integer literals and `+`, `-`, `*`, `/`
+/// and `%` evaluates to, dividing as Cypher does, rounding toward zero
+fn fold_integer(expr: &Expr) -> Result<i64, Folding> {
+    match expr {
+        Expr::Value(ValueWithSpan {
+            value: Value::Number(n, _),
+            ..
+        }) => n.to_string().parse().map_err(|_| Folding::NotInteger),
+        Expr::Nested(expr) => fold_integer(expr),
+        Expr::UnaryOp {
+            op: UnaryOperator::Plus,
+            expr,
+        } => fold_integer(expr),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => fold_integer(expr)?.checked_neg().ok_or(Folding::NotInteger),
+        Expr::BinaryOp { left, op, right } => {
+            let (left, right) = (fold_integer(left), fold_integer(right));
+            // An operand that is not a constant makes the whole expression not
+            // constant, however the other one folds
+            let (left, right) = match (left, right) {
+                (Err(Folding::NotConstant), _) | (_, Err(Folding::NotConstant)) => {
+                    return Err(Folding::NotConstant)
+                }
+                (left, right) => (left?, right?),
+            };
+            match op {
+                BinaryOperator::Plus => left.checked_add(right),
+                BinaryOperator::Minus => left.checked_sub(right),
+                BinaryOperator::Multiply => left.checked_mul(right),
+                BinaryOperator::Divide => left.checked_div(right),
+                BinaryOperator::Modulo => left.checked_rem(right),
+                _ => return Err(Folding::NotConstant),
             }
+            .ok_or(Folding::NotInteger)
         }
+        _ => Err(Folding::NotConstant),
+    }
+}
+
+/// Whether `expr` is a number literal below zero, e.g. `-1`
+fn is_negative_number(expr: &Expr) -> bool {
+    match expr {
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => matches!(
+            expr.as_ref(),
+            Expr::Value(ValueWithSpan {
+                value: Value::Number(n, _),
+                ..
+            }) if n.to_string().chars().any(|c| c.is_ascii_digit() && c != '0')
+        ),
+        _ => false,
+    }
+}
 
-        // Add property conditions
-        if let Some(_props) = properties {
-            // TODO: Implement property matching
-            // This would require parsing the map literal and creating appropriate conditions
+/// The JSON text of a literal property value: a string, number, boolean,
+/// `null` or a list or map of them, or `None` if `expr` is not a literal
+fn json_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Value(value) => match &value.value {
+            Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Some(json_string(s)),
+            // JSON numbers need a digit on both sides of the decimal point
+            Value::Number(n, _) => {
+                let n = n.to_string();
+                let n = match n.strip_prefix('.') {
+                    Some(fraction) => format!("0.{fraction}"),
+                    None => n,
+                };
+                Some(match n.strip_suffix('.') {
+                    Some(integer) => format!("{integer}.0"),
+                    None => n,
+                })
+            }
+            Value::Boolean(b) => Some(b.to_string()),
+            Value::Null => Some("null".to_string()),
+            _ => None,
+        },
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: operand,
+        } => match operand.as_ref() {
+            Expr::Value(ValueWithSpan {
+                value: Value::Number(..),
+                ..
+            }) => json_value(operand).map(|n| format!("-{n}")),
+            _ => None,
+        },
+        Expr::Array(array) => {
+            let elems = array
+                .elem
+                .iter()
+                .map(json_value)
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("[{}]", elems.join(",")))
         }
+        Expr::Dictionary(fields) => {
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    let value = json_value(&field.value)?;
+                    Some(format!("{}:{value}", json_string(&field.key.value)))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("{{{}}}", fields.join(",")))
+        }
+        _ => None,
+    }
+}
 
-        Ok(())
+/// A JSON string literal
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            ch if u32::from(ch) < 0x20 => json.push_str(&format!("\\u{:04x}", u32::from(ch))),
+            ch => json.push(ch),
+        }
     }
+    json.push('"');
+    json
+}
 
-    /// Process a relationship in MATCH pattern
-    fn process_relationship_for_match(
-        &self,
-        variable: &Option<Ident>,
-        types: &[Ident],
-        properties: &Option<Expr>,
-        direction: &RelationshipDirection,
-        length: &Option<RelationshipLength>,
-        joins: &mut Vec<Join>,
-        where_conditions: &mut Vec<Expr>,
-        _element_index: usize,
-    ) -> Result<(), String> {
-        // Create join to relationships table
-        let join = Join {
-            relation: TableFactor::Table {
-                name: ObjectName(vec![Ident::new(self.config.relationship_table.clone())]),
-                alias: variable.as_ref().map(|v| TableAlias {
-                    name: v.clone(),
-                    columns: vec![],
-                }),
-                args: None,
-                with_hints: vec![],
-                version: None,
-                partitions: vec![],
-            },
-            global: false,
-            join_operator: JoinOperator::Inner(JoinConstraint::On(
-                // This is simplified - in practice you'd need to join on node IDs
-                Expr::Value(Value::Boolean(true))
-            )),
-        };
+/// An integer literal expression
+/// Whether the property key `key` is a name that needs no quoting in a JSON
+/// path or as a column alias: letters, digits and underscores, not starting
+/// with a digit
+pub(crate) fn is_plain_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
 
-        joins.push(join);
+/// The value of an integer literal, e.g. `-3`, or `None` for other
+/// expressions
+fn integer_literal(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Value(ValueWithSpan {
+            value: Value::Number(n, _),
+            ..
+        }) => n.to_string().parse().ok(),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr,
+        } => integer_literal(expr)?.checked_neg(),
+        _ => None,
+    }
+}
 
-        // Add type conditions
-        if !types.is_empty() {
-            let table_ref = variable
-                .as_ref()
-                .map(|v| v.clone())
-                .unwrap_or_else(|| Ident::new(&self.config.relationship_table));
+/// The literal of an integer, negative ones included
+fn integer_number(n: i64) -> Expr {
+    let literal = number_literal(n.unsigned_abs() as usize);
+    if n < 0 {
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus,
+            expr: Box::new(literal),
+        }
+    } else {
+        literal
+    }
+}
 
-            let type_conditions: Vec<Expr> = types.iter().map(|rel_type| {
-                Expr::BinaryOp {
-                    left: Box::new(Expr::CompoundIdentifier(vec![
-                        table_ref.clone(),
-                        Ident::new("type"),
-                    ])),
-                    op: BinaryOperator::Eq,
-                    right: Box::new(Expr::Value(Value::SingleQuotedString(rel_type.value.clone()))),
+fn number_literal(n: usize) -> Expr {
+    #[cfg(not(feature = "bigdecimal"))]
+    let value = n.to_string();
+    #[cfg(feature = "bigdecimal")]
+    let value = bigdecimal::BigDecimal::from(n as u64);
+    Expr::value(Value::Number(value, false))
+}
+
+/// Rebuild `expr` with `f` applied to each of its direct sub-expressions
+pub(crate) fn map_subexpressions(expr: &Expr, f: &mut impl FnMut(&Expr) -> Expr) -> Expr {
+    match expr {
+        Expr::BinaryOp { left, op, right } => Expr::BinaryOp {
+            left: Box::new(f(left)),
+            op: op.clone(),
+            right: Box::new(f(right)),
+        },
+        Expr::UnaryOp { op, expr } => Expr::UnaryOp {
+            op: *op,
+            expr: Box::new(f(expr)),
+        },
+        Expr::Nested(inner) => Expr::Nested(Box::new(f(inner))),
+        Expr::Cast {
+            kind,
+            expr,
+            data_type,
+            format,
+        } => Expr::Cast {
+            kind: kind.clone(),
+            expr: Box::new(f(expr)),
+            data_type: data_type.clone(),
+            format: format.clone(),
+        },
+        Expr::IsNull(inner) => Expr::IsNull(Box::new(f(inner))),
+        Expr::IsNotNull(inner) => Expr::IsNotNull(Box::new(f(inner))),
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+        } => Expr::Between {
+            expr: Box::new(f(expr)),
+            negated: *negated,
+            low: Box::new(f(low)),
+            high: Box::new(f(high)),
+        },
+        Expr::Case {
+            case_token,
+            end_token,
+            operand,
+            conditions,
+            else_result,
+        } => Expr::Case {
+            case_token: case_token.clone(),
+            end_token: end_token.clone(),
+            operand: operand.as_ref().map(|operand| Box::new(f(operand))),
+            conditions: conditions
+                .iter()
+                .map(|when| CaseWhen {
+                    condition: f(&when.condition),
+                    result: f(&when.result),
+                })
+                .collect(),
+            else_result: else_result.as_ref().map(|result| Box::new(f(result))),
+        },
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => Expr::InList {
+            expr: Box::new(f(expr)),
+            list: list.iter().map(&mut *f).collect(),
+            negated: *negated,
+        },
+        Expr::Function(function) => {
+            let mut function = function.clone();
+            if let FunctionArguments::List(list) = &mut function.args {
+                for arg in &mut list.args {
+                    if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg_expr))
+                    | FunctionArg::Named {
+                        arg: FunctionArgExpr::Expr(arg_expr),
+                        ..
+                    } = arg
+                    {
+                        *arg_expr = f(arg_expr);
+                    }
                 }
-            }).collect();
+            }
+            Expr::Function(function)
+        }
+        _ => expr.clone(),
+    }
+}
+
+/// A call of the SQL function `name` with `args`
+pub(crate) fn function_call(name: &str, args: Vec<Expr>) -> Expr {
+    Expr::Function(Function {
+        name: ObjectName::from(vec![Ident::new(name)]),
+        uses_odbc_syntax: false,
+        parameters: FunctionArguments::None,
+        args: FunctionArguments::List(FunctionArgumentList {
+            duplicate_treatment: None,
+            args: args
+                .into_iter()
+                .map(|arg| FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)))
+                .collect(),
+            clauses: vec![],
+        }),
+        filter: None,
+        null_treatment: None,
+        over: None,
+        within_group: vec![],
+    })
+}
+
+/// The alias of the subquery of the rows a WITH passes on
+const WITH_ROWS_ALIAS: &str = "with_rows";
+
+/// The name of the rows of the elements of the list of an UNWIND
+const UNWOUND_ROWS_ALIAS: &str = "unwound";
+
+/// The place of a condition in the WHERE clause of a MATCH, whose
+/// conditions are sorted by it as described under [`CypherToSqlTransformer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ConditionOrder {
+    /// The label or type conditions of the binding at the index
+    Element(usize),
+    /// The conditions on the endpoints of relationships that no join holds
+    Endpoint,
+    /// The conditions that the relationships differ
+    Uniqueness,
+    /// The WHERE clause of the query
+    Where,
+}
+
+/// `condition` as an operand of AND: in parentheses if it is a disjunction,
+/// which binds more loosely, e.g. a condition of the query's WHERE clause
+/// `a OR b` or the types of a relationship `[:KNOWS|LIKES]`
+pub(crate) fn and_operand(condition: Expr) -> Expr {
+    match condition {
+        Expr::BinaryOp {
+            op: BinaryOperator::Or | BinaryOperator::Xor,
+            ..
+        } => Expr::Nested(Box::new(condition)),
+        _ => condition,
+    }
+}
+
+/// Labels or relationship types in the order of their names, so their
+/// conditions do not depend on the order they were written in
+fn sorted_names(names: &[Ident]) -> Vec<&Ident> {
+    let mut names: Vec<&Ident> = names.iter().collect();
+    names.sort_by(|a, b| a.value.cmp(&b.value));
+    names
+}
+
+/// The Cypher functions without arguments translated to their SQL
+/// counterparts, in lower case
+const MAPPED_FUNCTIONS: &[&str] = &["datetime", "randomuuid", "timestamp"];
+
+/// The Cypher functions passed through to SQL as they are, which no database
+/// has under their names, in lower case
+const CYPHER_ONLY_FUNCTIONS: &[&str] = &[
+    "endnode",
+    "head",
+    "keys",
+    "last",
+    "properties",
+    "size",
+    "startnode",
+    "tail",
+    "toboolean",
+    "tofloat",
+    "tointeger",
+    "tolower",
+    "tostring",
+    "toupper",
+];
 
-            if type_conditions.len() == 1 {
-                where_conditions.push(type_conditions.into_iter().next().unwrap());
-            } else if type_conditions.len() > 1 {
-                where_conditions.push(self.combine_conditions_with_or(type_conditions));
+/// The name of the function `expr` calls without arguments, e.g. `timestamp`
+fn zero_arg_function_name(expr: &Expr) -> Option<String> {
+    let Expr::Function(function) = expr else {
+        return None;
+    };
+    match &function.args {
+        FunctionArguments::List(list) if list.args.is_empty() => Some(function.name.to_string()),
+        _ => None,
+    }
+}
+
+/// The Cypher aggregating functions, in lower case
+const AGGREGATE_FUNCTIONS: &[&str] = &[
+    "avg",
+    "collect",
+    "count",
+    "max",
+    "min",
+    "percentilecont",
+    "percentiledisc",
+    "stdev",
+    "stdevp",
+    "sum",
+];
+
+/// The grouping keys of a RETURN with aggregating items: the expressions of
+/// the other items, with every variable for `*`, or `None` when no item
+/// aggregates
+fn grouping_keys(ret: &ReturnClause, plan: &GraphPlan) -> Option<Vec<Expr>> {
+    let mut aggregating = false;
+    let mut keys = vec![];
+    for item in &ret.items {
+        match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                if contains_aggregate(expr) {
+                    aggregating = true;
+                } else {
+                    keys.push(expr.clone());
+                }
             }
+            _ => keys.extend(
+                plan.bindings
+                    .iter()
+                    .filter_map(|binding| binding.variable.clone().map(Expr::Identifier)),
+            ),
         }
+    }
+    aggregating.then_some(keys)
+}
 
-        // TODO: Handle direction, length, and properties
+/// Whether `expr` has a Cypher parameter
+fn contains_parameter(expr: &Expr) -> bool {
+    let mut pending = vec![expr];
+    while let Some(expr) = pending.pop() {
+        if let Expr::Value(ValueWithSpan {
+            value: Value::Placeholder(_),
+            ..
+        }) = expr
+        {
+            return true;
+        }
+        pending.extend(subexpressions(expr));
+    }
+    false
+}
 
-        Ok(())
+/// Whether `expr` calls an aggregating function
+pub(crate) fn contains_aggregate(expr: &Expr) -> bool {
+    let mut pending = vec![expr];
+    while let Some(expr) = pending.pop() {
+        if let Expr::Function(function) = expr {
+            if is_aggregate_function(function) {
+                return true;
+            }
+        }
+        pending.extend(subexpressions(expr));
     }
+    false
+}
 
-    /// Transform CREATE statement to INSERT
-    fn transform_create(&self, patterns: &[Pattern]) -> Result<Vec<Statement>, String> {
-        let mut statements = vec![];
+/// Whether `function` is one of the [`AGGREGATE_FUNCTIONS`]
+pub(crate) fn is_aggregate_function(function: &Function) -> bool {
+    let name = function.name.to_string().to_lowercase();
+    AGGREGATE_FUNCTIONS.contains(&name.as_str())
+}
 
-        for pattern in patterns {
-            for element in &pattern.elements {
-                match element {
-                    PatternElement::Node { variable: _, labels, properties } => {
-                        let table_name = if self.config.use_label_tables && !labels.is_empty() {
-                            labels[0].value.clone()
-                        } else {
-                            self.config.node_table.clone()
-                        };
+/// Whether an ORDER BY `key` of the SQL query is one of the `projection`
+/// columns, by expression or by alias
+fn is_selected(key: &Expr, projection: &[SelectItem]) -> bool {
+    selected_column(key, projection).is_some()
+}
+
+/// The position of the `projection` column an ORDER BY `key` of the SQL
+/// query is, by expression or by alias
+fn selected_column(key: &Expr, projection: &[SelectItem]) -> Option<usize> {
+    projection.iter().position(|item| match item {
+        SelectItem::UnnamedExpr(expr) => expr == key,
+        SelectItem::ExprWithAlias { expr, alias } => {
+            expr == key || matches!(key, Expr::Identifier(ident) if ident.value == alias.value)
+        }
+        _ => false,
+    })
+}
 
-                        let insert = Statement::Insert(Insert {
-                            or: None,
-                            into: true,
-                            table_name: ObjectName(vec![Ident::new(table_name)]),
-                            table_alias: None,
-                            columns: vec![], // TODO: Extract from properties
-                            overwrite: false,
-                            source: None, // TODO: Create VALUES from properties
-                            partitioned: None,
-                            after_columns: vec![],
-                            table: false,
-                            on: None,
-                            returning: None,
-                            replace_into: false,
-                            priority: None,
-                            insert_alias: None,
-                            ignore: false,
-                        });
+/// The first pattern comprehension nested in `expr`, which would need a
+/// correlated aggregate to translate
+fn find_pattern_comprehension(expr: &Expr) -> Option<&Expr> {
+    let mut pending = vec![expr];
+    while let Some(expr) = pending.pop() {
+        if let Expr::Cypher(cypher) = expr {
+            if matches!(**cypher, CypherExpr::PatternComprehension { .. }) {
+                return Some(expr);
+            }
+        }
+        pending.extend(subexpressions(expr));
+    }
+    None
+}
 
-                        statements.push(insert);
-                    }
-                    PatternElement::Relationship { .. } => {
-                        // TODO: Implement relationship creation
+/// The first property access on an expression other than `properties(n)`,
+/// such as `head(collect(n)).name`
+fn find_field_access(expr: &Expr) -> Option<&Expr> {
+    let mut pending = vec![expr];
+    while let Some(expr) = pending.pop() {
+        if matches!(expr, Expr::CompoundFieldAccess { .. }) && properties_field(expr).is_none() {
+            return Some(expr);
+        }
+        pending.extend(subexpressions(expr));
+    }
+    None
+}
+
+/// The nesting depth of the subexpressions [`map_subexpressions`] visits,
+/// computed without recursion so that it is safe on any expression
+pub(crate) fn expression_depth(expr: &Expr) -> usize {
+    let mut max_depth = 0;
+    let mut pending = vec![(expr, 1)];
+    while let Some((expr, depth)) = pending.pop() {
+        max_depth = max_depth.max(depth);
+        match expr {
+            Expr::BinaryOp { left, right, .. } => {
+                pending.push((left, depth + 1));
+                pending.push((right, depth + 1));
+            }
+            Expr::UnaryOp { expr, .. } | Expr::Nested(expr) => pending.push((expr, depth + 1)),
+            Expr::Function(function) => {
+                if let FunctionArguments::List(list) = &function.args {
+                    for arg in &list.args {
+                        if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg_expr))
+                        | FunctionArg::Named {
+                            arg: FunctionArgExpr::Expr(arg_expr),
+                            ..
+                        } = arg
+                        {
+                            pending.push((arg_expr, depth + 1));
+                        }
                     }
                 }
             }
+            _ => {}
         }
+    }
+    max_depth
+}
 
-        Ok(statements)
+/// Replace the identifiers naming RETURN aliases with the aliased expressions
+fn substitute_aliases(expr: &Expr, aliases: &[(&Ident, &Expr)]) -> Expr {
+    let aliased = |ident: &Ident| {
+        aliases
+            .iter()
+            .find(|(alias, _)| alias.value == ident.value)
+            .map(|(_, aliased)| *aliased)
+    };
+    match expr {
+        Expr::Identifier(ident) => match aliased(ident) {
+            Some(aliased @ (Expr::BinaryOp { .. } | Expr::UnaryOp { .. })) => {
+                return Expr::Nested(Box::new(aliased.clone()))
+            }
+            Some(aliased) => return aliased.clone(),
+            None => {}
+        },
+        // A property of an alias of a variable is a property of the variable
+        Expr::CompoundIdentifier(idents) => {
+            if let Some(Expr::Identifier(variable)) = idents.first().and_then(aliased) {
+                let mut idents = idents.clone();
+                idents[0] = variable.clone();
+                return Expr::CompoundIdentifier(idents);
+            }
+        }
+        _ => {}
     }
+    map_subexpressions(expr, &mut |sub_expr| substitute_aliases(sub_expr, aliases))
+}
 
-    /// Transform MERGE statement
-    fn transform_merge(
-        &self,
-        _patterns: &[Pattern],
-        _on_create: &Option<Vec<SetClause>>,
-        _on_match: &Option<Vec<SetClause>>,
-    ) -> Result<Vec<Statement>, String> {
-        // TODO: Implement MERGE transformation (complex - requires UPSERT logic)
-        Err("MERGE transformation not yet implemented".to_string())
+/// The number of bindings of the first `paths` paths of `plan`, which bind
+/// them in the order they first appear
+fn bindings_before(plan: &GraphPlan, paths: usize) -> usize {
+    plan.paths[..paths]
+        .iter()
+        .flat_map(|path| {
+            let relationships = path.relationships.iter().map(|(rel, _)| *rel);
+            path.nodes.iter().copied().chain(relationships)
+        })
+        .max()
+        .map_or(0, |last| last + 1)
+}
+
+/// The pairs of the relationship `bindings` that could be the same
+/// relationship: all but those of relationships whose types differ
+fn overlapping_relationships(plan: &GraphPlan, bindings: &[usize]) -> Vec<(usize, usize)> {
+    let mut pairs = vec![];
+    for (i, &first) in bindings.iter().enumerate() {
+        for &second in &bindings[i + 1..] {
+            let types = |binding: usize| &plan.bindings[binding].labels;
+            let disjoint = !types(first).is_empty()
+                && !types(second).is_empty()
+                && !types(first).iter().any(|t| types(second).contains(t));
+            if !disjoint {
+                pairs.push((first, second));
+            }
+        }
     }
+    pairs
+}
 
-    /// Transform DELETE statement
-    fn transform_delete(
-        &self,
-        _detach: bool,
-        _what: &[Expr],
-        _where_clause: &Option<Expr>,
-    ) -> Result<Vec<Statement>, String> {
-        // TODO: Implement DELETE transformation
-        Err("DELETE transformation not yet implemented".to_string())
+/// Whether a WITH orders, skips, limits or makes distinct the rows it passes
+/// on, which a single SELECT with the RETURN cannot do
+fn has_row_modifiers(with: &WithClause) -> bool {
+    let projection = &with.projection;
+    projection.distinct
+        || !projection.order_by.is_empty()
+        || projection.skip.is_some()
+        || projection.limit.is_some()
+}
+
+/// A MATCH followed by MATCH and OPTIONAL MATCH clauses without a WITH
+/// between them, as a single MATCH of the patterns and conditions of its
+/// MATCH clauses and the OPTIONAL MATCH clauses after them, or `None` for
+/// any other statement
+///
+/// The MATCH has the WITH, RETURN and query part after them of the last
+/// clause.
+pub(crate) fn merge_reading_clauses(
+    statement: &CypherStatement,
+) -> Result<Option<(CypherStatement, Vec<OptionalMatch<'_>>)>, TransformError> {
+    let CypherStatement::Match {
+        optional,
+        patterns,
+        r#where,
+        with: None,
+        next: Some(_),
+        ..
+    } = statement
+    else {
+        return Ok(None);
+    };
+    let mut patterns = patterns.clone();
+    let mut conditions: Vec<Expr> = r#where.iter().cloned().collect();
+    let mut optional_matches = vec![];
+    let mut last = statement;
+    while let CypherStatement::Match {
+        with: None,
+        next: Some(next),
+        ..
+    } = last
+    {
+        let CypherStatement::Match {
+            optional: next_optional,
+            patterns: next_patterns,
+            r#where: next_where,
+            ..
+        } = next.as_ref()
+        else {
+            return Err(TransformError::Unsupported(format!("{next} after a MATCH")));
+        };
+        if *next_optional {
+            optional_matches.push(OptionalMatch {
+                patterns: next_patterns,
+                r#where: next_where,
+            });
+        } else if optional_matches.is_empty() {
+            patterns.extend(next_patterns.iter().cloned());
+            conditions.extend(next_where.iter().cloned());
+        } else {
+            return Err(TransformError::Unsupported(format!(
+                "MATCH after OPTIONAL MATCH: {next}"
+            )));
+        }
+        last = next;
+    }
+    let CypherStatement::Match {
+        with,
+        r#return,
+        next,
+        ..
+    } = last
+    else {
+        unreachable!("only a MATCH goes on with another clause");
+    };
+    if let Some(with) = with.as_ref().filter(|_| !optional_matches.is_empty()) {
+        return Err(TransformError::Unsupported(format!(
+            "{with} after OPTIONAL MATCH"
+        )));
     }
 
-    /// Combine multiple conditions with AND
-    fn combine_conditions(&self, conditions: Vec<Expr>) -> Expr {
-        conditions.into_iter().reduce(|acc, expr| {
-            Expr::BinaryOp {
-                left: Box::new(acc),
+    let r#where = if conditions.len() > 1 {
+        conditions
+            .into_iter()
+            .map(and_operand)
+            .reduce(|left, right| Expr::BinaryOp {
+                left: Box::new(left),
                 op: BinaryOperator::And,
-                right: Box::new(expr),
+                right: Box::new(right),
+            })
+    } else {
+        conditions.pop()
+    };
+    let statement = CypherStatement::Match {
+        optional: *optional,
+        patterns,
+        r#where,
+        with: with.clone(),
+        r#return: r#return.clone(),
+        next: next.clone(),
+    };
+    Ok(Some((statement, optional_matches)))
+}
+
+/// The nodes of `plan` that `with` passes on, each with the variable it is
+/// passed on as: the variables projected as they are or under an alias, or
+/// every variable for `WITH *`
+pub(crate) fn carried_nodes<'a>(
+    with: &'a WithClause,
+    plan: &'a GraphPlan,
+) -> Result<Vec<(&'a Ident, &'a Binding)>, TransformError> {
+    let mut carried = vec![];
+    let mut carry = |variable: &'a Ident, binding: &'a Binding| match binding.kind {
+        BindingKind::Node => {
+            carried.push((variable, binding));
+            Ok(())
+        }
+        // Relationship rows have no id to match them again by
+        BindingKind::Relationship => Err(TransformError::Unsupported(format!(
+            "relationship {variable} passed on by {with}"
+        ))),
+    };
+    for item in &with.projection.items {
+        match item {
+            SelectItem::UnnamedExpr(Expr::Identifier(variable)) => {
+                if let Some(binding) = plan.binding(&variable.value) {
+                    carry(variable, binding)?;
+                }
+            }
+            SelectItem::ExprWithAlias {
+                expr: Expr::Identifier(variable),
+                alias,
+            } => {
+                if let Some(binding) = plan.binding(&variable.value) {
+                    carry(alias, binding)?;
+                }
+            }
+            SelectItem::Wildcard(_) => {
+                for binding in &plan.bindings {
+                    if let Some(variable) = &binding.variable {
+                        carry(variable, binding)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(carried)
+}
+
+/// The patterns of the MATCH after a WITH, with the labels of the nodes
+/// the WITH passes on, and a pattern for each of those nodes they leave out
+pub(crate) fn continued_patterns(
+    carried: &[(&Ident, &Binding)],
+    patterns: &[Pattern],
+) -> Vec<Pattern> {
+    let mut patterns = patterns.to_vec();
+    for (variable, binding) in carried {
+        let matched = patterns
+            .iter_mut()
+            .flat_map(|pattern| &mut pattern.elements)
+            .find(|element| element.is_node() && element.variable() == Some(*variable));
+        if let Some(PatternElement::Node { labels, .. }) = matched {
+            for label in &binding.labels {
+                if !labels.contains(label) {
+                    labels.push(label.clone());
+                }
             }
-        }).unwrap_or(Expr::Value(Value::Boolean(true)))
+        } else {
+            patterns.push(Pattern::path([PatternElement::Node {
+                variable: Some((*variable).clone()),
+                labels: binding.labels.clone(),
+                properties: None,
+            }]));
+        }
+    }
+    patterns
+}
+
+/// The variables `expr` refers to, by name or by property access
+fn referenced_variables(expr: &Expr) -> Vec<&Ident> {
+    let mut variables = vec![];
+    let mut pending = vec![expr];
+    while let Some(expr) = pending.pop() {
+        match expr {
+            Expr::Identifier(variable) => variables.push(variable),
+            Expr::CompoundIdentifier(idents) => variables.extend(idents.first()),
+            _ => pending.extend(subexpressions(expr)),
+        }
     }
+    variables
+}
 
-    /// Combine multiple conditions with OR
-    fn combine_conditions_with_or(&self, conditions: Vec<Expr>) -> Expr {
-        conditions.into_iter().reduce(|acc, expr| {
+/// `expr` with its operands that are PostgreSQL JSON operators, e.g.
+/// `n.properties ->> 'age'`, in parentheses when `expr` is an operator that
+/// binds as tightly, e.g. `(n.properties ->> 'age') / 10`, which would
+/// otherwise read as `n.properties ->> ('age' / 10)`
+fn nest_json_operands(expr: Expr) -> Expr {
+    let nest = |operand: Box<Expr>| match *operand {
+        Expr::BinaryOp {
+            op: BinaryOperator::Arrow | BinaryOperator::LongArrow,
+            ..
+        } => Box::new(Expr::Nested(operand)),
+        _ => operand,
+    };
+    match expr {
+        // Comparisons and logical operators bind less tightly
+        Expr::BinaryOp { left, op, right }
+            if !matches!(
+                op,
+                BinaryOperator::Eq
+                    | BinaryOperator::NotEq
+                    | BinaryOperator::Lt
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Gt
+                    | BinaryOperator::GtEq
+                    | BinaryOperator::And
+                    | BinaryOperator::Or
+                    | BinaryOperator::Xor
+            ) =>
+        {
             Expr::BinaryOp {
-                left: Box::new(acc),
-                op: BinaryOperator::Or,
-                right: Box::new(expr),
+                left: nest(left),
+                op,
+                right: nest(right),
+            }
+        }
+        Expr::UnaryOp { op, expr } if op != UnaryOperator::Not => Expr::UnaryOp {
+            op,
+            expr: nest(expr),
+        },
+        expr => expr,
+    }
+}
+
+/// `expr` with its operands that read a PostgreSQL JSON property as text,
+/// e.g. `n.properties ->> 'age'`, cast to the type the operator takes, e.g.
+/// `(n.properties ->> 'age')::NUMERIC > 30`, as PostgreSQL compares and adds
+/// no text with numbers or booleans
+///
+/// The type is known from the operator, or from a literal operand it is
+/// compared with; reads compared with parameters, properties or strings are
+/// left as text.
+fn cast_json_operands(expr: Expr) -> Expr {
+    let cast = |operand: Box<Expr>, data_type: Option<DataType>| match data_type {
+        Some(data_type) if is_json_text(&operand) => Box::new(json_text_cast(*operand, data_type)),
+        _ => operand,
+    };
+    match expr {
+        Expr::BinaryOp { left, op, right } => {
+            let (left_type, right_type) = match op {
+                BinaryOperator::Eq
+                | BinaryOperator::NotEq
+                | BinaryOperator::Lt
+                | BinaryOperator::LtEq
+                | BinaryOperator::Gt
+                | BinaryOperator::GtEq => (sql_type(&right), sql_type(&left)),
+                BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Xor => {
+                    (Some(DataType::Boolean), Some(DataType::Boolean))
+                }
+                // `+` of a string concatenates
+                BinaryOperator::Plus if is_string(&left) || is_string(&right) => (None, None),
+                BinaryOperator::Plus
+                | BinaryOperator::Minus
+                | BinaryOperator::Multiply
+                | BinaryOperator::Divide
+                | BinaryOperator::Modulo => (Some(numeric()), Some(numeric())),
+                _ => (None, None),
+            };
+            Expr::BinaryOp {
+                left: cast(left, left_type),
+                op,
+                right: cast(right, right_type),
+            }
+        }
+        Expr::UnaryOp { op, expr } => {
+            let data_type = match op {
+                UnaryOperator::Not => Some(DataType::Boolean),
+                UnaryOperator::Minus | UnaryOperator::Plus => Some(numeric()),
+                _ => None,
+            };
+            Expr::UnaryOp {
+                op,
+                expr: cast(expr, data_type),
+            }
+        }
+        Expr::InList {
+            expr,
+            list,
+            negated,
+        } => {
+            let data_type = list.first().and_then(sql_type);
+            Expr::InList {
+                expr: cast(expr, data_type),
+                list,
+                negated,
+            }
+        }
+        Expr::Function(mut function) => {
+            let name = function.name.to_string().to_lowercase();
+            if let FunctionArguments::List(list) = &mut function.args {
+                let data_type = match name.as_str() {
+                    "sum" | "avg" | "stdev" | "stddev" | "stddev_samp" | "stddev_pop" => {
+                        Some(numeric())
+                    }
+                    // e.g. `coalesce(n.active, false)` of a strict null
+                    // condition
+                    "coalesce" => list.args.iter().find_map(|arg| match arg {
+                        FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) => sql_type(arg),
+                        _ => None,
+                    }),
+                    _ => None,
+                };
+                for arg in &mut list.args {
+                    if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) = arg {
+                        *arg = *cast(Box::new(arg.clone()), data_type.clone());
+                    }
+                }
+            }
+            Expr::Function(function)
+        }
+        expr => expr,
+    }
+}
+
+/// Whether `expr` reads a PostgreSQL JSON property as text, e.g.
+/// `n.properties ->> 'age'`
+fn is_json_text(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::BinaryOp {
+            op: BinaryOperator::LongArrow,
+            ..
+        }
+    )
+}
+
+/// The read of a PostgreSQL JSON property as text cast to `data_type`,
+/// e.g. `(n.properties ->> 'age')::NUMERIC`
+fn json_text_cast(read: Expr, data_type: DataType) -> Expr {
+    Expr::Cast {
+        kind: CastKind::DoubleColon,
+        expr: Box::new(Expr::Nested(Box::new(read))),
+        data_type,
+        format: None,
+    }
+}
+
+/// The read of a PostgreSQL JSON property as `jsonb`, which sorts numbers
+/// as numbers, e.g. `n.properties -> 'age'` for `n.properties ->> 'age'`,
+/// or `None` for other expressions
+fn json_sort_key(expr: &Expr) -> Option<Expr> {
+    let Expr::BinaryOp {
+        left,
+        op: BinaryOperator::LongArrow,
+        right,
+    } = expr
+    else {
+        return None;
+    };
+    Some(Expr::BinaryOp {
+        left: left.clone(),
+        op: BinaryOperator::Arrow,
+        right: right.clone(),
+    })
+}
+
+fn numeric() -> DataType {
+    DataType::Numeric(ExactNumberInfo::None)
+}
+
+/// The SQL type of a literal, or of an arithmetic expression, or `None`
+/// when it is not known
+fn sql_type(expr: &Expr) -> Option<DataType> {
+    match expr {
+        Expr::Value(value) => match value.value {
+            Value::Number(..) => Some(numeric()),
+            Value::Boolean(_) => Some(DataType::Boolean),
+            _ => None,
+        },
+        Expr::Nested(expr) => sql_type(expr),
+        Expr::UnaryOp {
+            op: UnaryOperator::Minus | UnaryOperator::Plus,
+            expr,
+        } => sql_type(expr),
+        Expr::Cast {
+            data_type: data_type @ (DataType::Numeric(_) | DataType::Boolean),
+            ..
+        } => Some(data_type.clone()),
+        Expr::BinaryOp {
+            left,
+            op:
+                BinaryOperator::Plus
+                | BinaryOperator::Minus
+                | BinaryOperator::Multiply
+                | BinaryOperator::Divide
+                | BinaryOperator::Modulo,
+            right,
+        } => sql_type(left).or_else(|| sql_type(right)),
+        _ => None,
+    }
+}
+
+/// Whether `expr` is a string literal
+fn is_string(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Value(ValueWithSpan {
+            value: Value::SingleQuotedString(_) | Value::DoubleQuotedString(_),
+            ..
+        })
+    )
+}
+
+/// The table reference and property of a property access on an element
+/// whose properties are stored as JSON, e.g. `n.name` on the generic node
+/// table
+///
+/// The reference keeps the quotes of the variable, as the alias of its table
+/// does, e.g. `` `order` `` for `` `order`.total ``.
+fn json_property(expr: &Expr, plan: &GraphPlan) -> Option<(Ident, String)> {
+    let (variable, property) = match expr {
+        Expr::CompoundIdentifier(idents) if idents.len() == 2 => (&idents[0], &idents[1]),
+        _ => properties_field(expr)?,
+    };
+    let binding = plan.binding(&variable.value)?;
+    (!binding.label_table).then(|| (binding.table_ref(), property.value.clone()))
+}
+
+/// Whether `expr` is a property access, e.g. `n.name`, and `other` a string
+/// literal
+fn is_property_with_string(expr: &Expr, other: &Expr) -> bool {
+    let property = match expr {
+        Expr::CompoundIdentifier(idents) => idents.len() == 2,
+        _ => properties_field(expr).is_some(),
+    };
+    property && string_value(other).is_some()
+}
+
+/// The value of a string literal, or `None` for other expressions
+pub(crate) fn string_value(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Value(ValueWithSpan {
+            value: Value::SingleQuotedString(s) | Value::DoubleQuotedString(s),
+            ..
+        }) => Some(s),
+        _ => None,
+    }
+}
+
+/// The LIKE pattern matching what `operator` matches `literal` with, and
+/// whether it escapes `%`, `_` or `\` with a backslash
+pub(crate) fn like_pattern(literal: &str, operator: StringOperator) -> (String, bool) {
+    let escaped = literal.contains(['%', '_', '\\']);
+    let mut pattern = String::new();
+    if operator != StringOperator::StartsWith {
+        pattern.push('%');
+    }
+    for c in literal.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    if operator != StringOperator::EndsWith {
+        pattern.push('%');
+    }
+    (pattern, escaped)
+}
+
+/// The GLOB pattern matching what `operator` matches `literal` with, in
+/// which `*`, `?` and `[` are bracketed to match themselves
+fn glob_pattern(literal: &str, operator: StringOperator) -> String {
+    let mut pattern = String::new();
+    if operator != StringOperator::StartsWith {
+        pattern.push('*');
+    }
+    for c in literal.chars() {
+        match c {
+            '*' | '?' | '[' => {
+                pattern.push('[');
+                pattern.push(c);
+                pattern.push(']');
             }
-        }).unwrap_or(Expr::Value(Value::Boolean(false)))
+            _ => pattern.push(c),
+        }
+    }
+    if operator != StringOperator::EndsWith {
+        pattern.push('*');
+    }
+    pattern
+}
+
+pub(crate) fn duplicate_column(name: &str) -> TransformError {
+    TransformError::InvalidQuery(format!(
+        "Multiple result columns with the same name are not supported: {name}"
+    ))
+}
+
+/// The column name Cypher gives a RETURN item: its alias, or else the text
+/// of its expression
+pub(crate) fn return_column_name(item: &SelectItem) -> String {
+    match item {
+        SelectItem::ExprWithAlias { alias, .. } => alias.value.clone(),
+        _ => item.to_string(),
+    }
+}
+
+/// The Cypher column names of RETURN items, where `*` stands for every named
+/// variable of the pattern
+fn return_column_names(items: &[SelectItem], plan: &GraphPlan) -> Vec<String> {
+    let mut columns = vec![];
+    for item in items {
+        match item {
+            SelectItem::Wildcard(_) => columns.extend(
+                plan.bindings
+                    .iter()
+                    .filter_map(|binding| binding.variable.as_ref())
+                    .map(|variable| variable.value.clone()),
+            ),
+            _ => columns.push(return_column_name(item)),
+        }
     }
+    columns
 }
 
 impl Default for CypherToSqlTransformer {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
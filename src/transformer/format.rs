@@ -0,0 +1,273 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Rendering of generated SQL statements as text
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use core::cell::Cell;
+
+use crate::ast::*;
+
+use super::plan::{split_conjunction, Binding};
+
+/// How generated SQL statements are rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SqlFormat {
+    /// Each statement on a single line
+    #[default]
+    Compact,
+    /// Each clause on its own line, with its contents indented by `indent`
+    /// spaces and join conditions indented one level further
+    Pretty { indent: usize },
+}
+
+/// Render `statement` in the given format
+///
+/// Only queries are laid out clause by clause; other statements are rendered
+/// on a single line in either format.
+pub fn format_statement(statement: &Statement, format: SqlFormat) -> String {
+    format_statement_with_bindings(statement, format, &[])
+}
+
+/// Render `statement` like [`format_statement`], following each FROM and
+/// JOIN item of a pretty printed query with a comment naming the pattern
+/// element it is bound to, from the bindings of each SELECT in `selects`
+pub(crate) fn format_statement_with_bindings(
+    statement: &Statement,
+    format: SqlFormat,
+    selects: &[Vec<Binding>],
+) -> String {
+    match (format, statement) {
+        (SqlFormat::Pretty { indent }, Statement::Query(query)) => {
+            let mut lines = vec![];
+            Pretty::new(indent, selects).query(query, &mut lines);
+            lines.join("\n")
+        }
+        _ => statement.to_string(),
+    }
+}
+
+/// `text` made safe to put in a SQL comment, on a single line and without
+/// the end of a block comment
+pub(crate) fn comment_text(text: &str) -> String {
+    text.replace("*/", "* /").replace(['\n', '\r'], " ")
+}
+
+struct Pretty<'a> {
+    indent: String,
+    /// The bindings of each SELECT, in the order they are printed
+    selects: &'a [Vec<Binding>],
+    /// The index of the next SELECT in `selects`
+    next_select: Cell<usize>,
+}
+
+impl<'a> Pretty<'a> {
+    fn new(indent: usize, selects: &'a [Vec<Binding>]) -> Self {
+        Self {
+            indent: " ".repeat(indent),
+            selects,
+            next_select: Cell::new(0),
+        }
+    }
+
+    fn query(&self, query: &Query, lines: &mut Vec<String>) {
+        if query.with.is_some()
+            || !query.locks.is_empty()
+            || query.for_clause.is_some()
+            || query.settings.is_some()
+            || query.format_clause.is_some()
+            || !query.pipe_operators.is_empty()
+        {
+            lines.push(query.to_string());
+            return;
+        }
+
+        self.set_expr(&query.body, lines);
+        if let Some(order_by) = &query.order_by {
+            match &order_by.kind {
+                OrderByKind::Expressions(exprs) if order_by.interpolate.is_none() => {
+                    lines.push("ORDER BY".to_string());
+                    self.list(exprs, lines);
+                }
+                _ => lines.push(order_by.to_string()),
+            }
+        }
+        if let Some(limit_clause) = &query.limit_clause {
+            lines.push(limit_clause.to_string().trim_start().to_string());
+        }
+        if let Some(fetch) = &query.fetch {
+            lines.push(fetch.to_string());
+        }
+    }
+
+    fn set_expr(&self, body: &SetExpr, lines: &mut Vec<String>) {
+        match body {
+            SetExpr::Select(select) => self.select(select, lines),
+            SetExpr::SetOperation {
+                op,
+                set_quantifier,
+                left,
+                right,
+            } => {
+                self.set_expr(left, lines);
+                match set_quantifier {
+                    SetQuantifier::None => lines.push(op.to_string()),
+                    _ => lines.push(format!("{op} {set_quantifier}")),
+                }
+                self.set_expr(right, lines);
+            }
+            _ => lines.push(body.to_string()),
+        }
+    }
+
+    fn select(&self, select: &Select, lines: &mut Vec<String>) {
+        let bindings = self.selects.get(self.next_select.get());
+        self.next_select.set(self.next_select.get() + 1);
+
+        if select.top.is_some()
+            || select.exclude.is_some()
+            || select.into.is_some()
+            || !select.lateral_views.is_empty()
+            || select.prewhere.is_some()
+            || !select.cluster_by.is_empty()
+            || !select.distribute_by.is_empty()
+            || !select.sort_by.is_empty()
+            || !select.named_window.is_empty()
+            || select.qualify.is_some()
+            || select.value_table_mode.is_some()
+            || select.connect_by.is_some()
+        {
+            lines.push(select.to_string());
+            return;
+        }
+
+        match &select.distinct {
+            Some(distinct) => lines.push(format!("SELECT {distinct}")),
+            None => lines.push("SELECT".to_string()),
+        }
+        self.list(&select.projection, lines);
+
+        if !select.from.is_empty() {
+            lines.push("FROM".to_string());
+            for (i, table) in select.from.iter().enumerate() {
+                lines.push(format!(
+                    "{}{}{}",
+                    self.indent,
+                    table.relation,
+                    annotation(&table.relation, bindings)
+                ));
+                for join in &table.joins {
+                    self.join(join, &annotation(&join.relation, bindings), lines);
+                }
+                if i + 1 < select.from.len() {
+                    if let Some(last) = lines.last_mut() {
+                        last.push(',');
+                    }
+                }
+            }
+        }
+
+        if let Some(selection) = &select.selection {
+            lines.push("WHERE".to_string());
+            self.conjunction(selection, lines);
+        }
+
+        match &select.group_by {
+            GroupByExpr::Expressions(exprs, modifiers) if modifiers.is_empty() => {
+                if !exprs.is_empty() {
+                    lines.push("GROUP BY".to_string());
+                    self.list(exprs, lines);
+                }
+            }
+            group_by => lines.push(group_by.to_string()),
+        }
+
+        if let Some(having) = &select.having {
+            lines.push("HAVING".to_string());
+            self.conjunction(having, lines);
+        }
+    }
+
+    /// A join on its own line, followed by `annotation`, with an `ON`
+    /// condition on the next one
+    fn join(&self, join: &Join, annotation: &str, lines: &mut Vec<String>) {
+        let mut without_condition = join.clone();
+        let condition = match &mut without_condition.join_operator {
+            JoinOperator::Join(constraint)
+            | JoinOperator::Inner(constraint)
+            | JoinOperator::Left(constraint)
+            | JoinOperator::LeftOuter(constraint)
+            | JoinOperator::Right(constraint)
+            | JoinOperator::RightOuter(constraint)
+            | JoinOperator::FullOuter(constraint) => match constraint {
+                JoinConstraint::On(expr) => {
+                    let expr = expr.clone();
+                    *constraint = JoinConstraint::None;
+                    Some(expr)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        lines.push(format!("{}{without_condition}{annotation}", self.indent));
+        if let Some(condition) = condition {
+            lines.push(format!("{0}{0}ON {condition}", self.indent));
+        }
+    }
+
+    /// Comma separated items, one per line
+    fn list<T: core::fmt::Display>(&self, items: &[T], lines: &mut Vec<String>) {
+        for (i, item) in items.iter().enumerate() {
+            let separator = if i + 1 < items.len() { "," } else { "" };
+            lines.push(format!("{}{item}{separator}", self.indent));
+        }
+    }
+
+    /// The top-level `AND` conjuncts of a condition, one per line
+    fn conjunction(&self, condition: &Expr, lines: &mut Vec<String>) {
+        for (i, conjunct) in split_conjunction(condition).into_iter().enumerate() {
+            let and = if i > 0 { "AND " } else { "" };
+            lines.push(format!("{}{and}{conjunct}", self.indent));
+        }
+    }
+}
+
+/// A comment naming the pattern element bound to `relation`, or nothing if
+/// none of `bindings` is
+fn annotation(relation: &TableFactor, bindings: Option<&Vec<Binding>>) -> String {
+    let TableFactor::Table { name, alias, .. } = relation else {
+        return String::new();
+    };
+    let table_ref = match alias {
+        Some(alias) => alias.name.value.clone(),
+        None => name.to_string(),
+    };
+    bindings
+        .into_iter()
+        .flatten()
+        .find(|binding| binding.table_ref().value == table_ref)
+        .map(|binding| format!(" /* {} */", comment_text(&binding.pattern())))
+        .unwrap_or_default()
+}
@@ -0,0 +1,603 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Translation of MATCH ... RETURN queries to the `GRAPH_TABLE` of SQL/PGQ,
+//! under [`TransformConfig::property_graph`]
+//!
+//! The patterns become the `MATCH` of the graph table as they are, labels
+//! as `IS` label expressions and variable-length relationships as
+//! quantified edges, and the WHERE clause its `WHERE`. The expressions the
+//! query returns are the graph table's `COLUMNS`, which a SELECT around it
+//! groups, deduplicates, orders and pages as RETURN asks.
+//!
+//! [`TransformConfig::property_graph`]: super::TransformConfig::property_graph
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::ast::cypher::*;
+use crate::ast::*;
+
+use super::cypher_to_sql::{
+    and_operand, contains_aggregate, duplicate_column, expression_depth, function_call,
+    is_aggregate_function, is_plain_key, like_pattern, map_subexpressions, return_column_name,
+    select_from, string_value, CypherToSqlTransformer, OptionalMatch,
+};
+use super::plan::GraphPlan;
+use super::TransformError;
+
+/// The Cypher functions of graph elements and paths, which have no
+/// counterpart in a graph table, in lower case
+const GRAPH_FUNCTIONS: &[&str] = &[
+    "elementid",
+    "endnode",
+    "exists",
+    "id",
+    "keys",
+    "labels",
+    "nodes",
+    "properties",
+    "relationships",
+    "startnode",
+    "type",
+];
+
+/// The `COLUMNS` of a graph table, each named
+#[derive(Default)]
+struct Columns {
+    items: Vec<(Expr, Ident)>,
+    /// The number of columns named `expr_<n>`
+    unnamed: usize,
+}
+
+impl Columns {
+    /// The name of the column of `expr`, added as `name` unless a column
+    /// already has the expression
+    fn add(&mut self, expr: Expr, name: Ident) -> Ident {
+        if let Some((_, name)) = self.items.iter().find(|(column, _)| *column == expr) {
+            return name.clone();
+        }
+        self.items.push((expr, name.clone()));
+        name
+    }
+
+    /// The name of the column of `expr`, which the query does not return,
+    /// added under a name no other column has: the property of a property
+    /// access, qualified by its variable if need be, or else `expr_<n>`
+    fn add_hidden(&mut self, expr: Expr, returned: &[Ident]) -> Ident {
+        if let Some((_, name)) = self.items.iter().find(|(column, _)| *column == expr) {
+            return name.clone();
+        }
+        let taken = |name: &str| {
+            returned.iter().any(|ident| ident.value == name)
+                || self.items.iter().any(|(_, ident)| ident.value == name)
+        };
+        let candidates = match &expr {
+            Expr::CompoundIdentifier(idents) if idents.len() == 2 => vec![
+                idents[1].value.clone(),
+                format!("{}_{}", idents[0].value, idents[1].value),
+            ],
+            _ => vec![],
+        };
+        let name = match candidates.into_iter().find(|name| !taken(name)) {
+            Some(name) => name,
+            None => loop {
+                self.unnamed += 1;
+                let name = format!("expr_{}", self.unnamed);
+                if !taken(&name) {
+                    break name;
+                }
+            },
+        };
+        self.add(expr, Ident::new(name))
+    }
+}
+
+impl CypherToSqlTransformer {
+    /// Translate a MATCH ... RETURN to a SELECT from a `GRAPH_TABLE` of
+    /// `graph`
+    ///
+    /// An OPTIONAL MATCH or WITH, a named path, and a variable or graph
+    /// function the query uses as a value are rejected with
+    /// [`TransformError::Unsupported`], having no counterpart in the graph
+    /// table.
+    pub(crate) fn graph_table_query(
+        &self,
+        graph: &str,
+        patterns: &[Pattern],
+        optional_matches: &[OptionalMatch],
+        where_clause: &Option<Expr>,
+        with_clause: &Option<WithClause>,
+        return_clause: &ReturnClause,
+    ) -> Result<Query, TransformError> {
+        if let Some(optional) = optional_matches.first() {
+            return Err(TransformError::Unsupported(format!(
+                "OPTIONAL MATCH {} in GRAPH_TABLE",
+                display_comma_separated(optional.patterns)
+            )));
+        }
+        if let Some(with) = with_clause {
+            return Err(TransformError::Unsupported(format!(
+                "{with} in GRAPH_TABLE"
+            )));
+        }
+        let return_exprs = return_clause.items.iter().filter_map(|item| match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => Some(expr),
+            _ => None,
+        });
+        let order_exprs = return_clause.order_by.iter().map(|key| &key.expr);
+        for expr in where_clause.iter().chain(return_exprs).chain(order_exprs) {
+            if expression_depth(expr) > self.config.recursion_limit {
+                return Err(TransformError::RecursionLimitExceeded);
+            }
+        }
+
+        // The labels of a variable, from every pattern it is in and from
+        // WHERE, are written where it first appears
+        let plan = GraphPlan::build(patterns, where_clause, &self.config);
+        let mut used: Vec<String> = matched_variables(patterns)
+            .into_iter()
+            .map(|variable| variable.value.clone())
+            .collect();
+        let mut seen: Vec<&str> = vec![];
+        let mut conditions = vec![];
+        let mut paths = vec![];
+        for pattern in patterns {
+            if pattern.variable.is_some() {
+                return Err(TransformError::Unsupported(format!(
+                    "named path {pattern} in GRAPH_TABLE"
+                )));
+            }
+            let mut path = vec![];
+            for element in &pattern.elements {
+                let mut variable = element.variable().cloned();
+                let mut labels: Vec<Ident> = match &variable {
+                    Some(variable) if seen.contains(&variable.value.as_str()) => vec![],
+                    Some(variable) => plan
+                        .binding(&variable.value)
+                        .map(|binding| binding.labels.clone())
+                        .unwrap_or_default(),
+                    None => element.labels_or_types().to_vec(),
+                };
+                labels.sort_by(|a, b| a.value.cmp(&b.value));
+                labels.dedup_by(|a, b| a.value == b.value);
+                // A name is quoted as SQL needs, not as it was in Cypher
+                let labels = labels
+                    .into_iter()
+                    .map(|label| self.column_name(label.value))
+                    .collect();
+
+                // A property map is a condition on a variable, which an
+                // anonymous element is given
+                if let Some(properties) = element.properties() {
+                    let Expr::Dictionary(fields) = properties else {
+                        return Err(TransformError::Unsupported(format!(
+                            "property map {properties} in GRAPH_TABLE"
+                        )));
+                    };
+                    let variable = variable.get_or_insert_with(|| {
+                        let kind = if element.is_node() {
+                            "node"
+                        } else {
+                            "relationship"
+                        };
+                        let name = (1..)
+                            .map(|n| format!("{kind}_{n}"))
+                            .find(|name| !used.contains(name))
+                            .expect("an unused name");
+                        used.push(name.clone());
+                        Ident::new(name)
+                    });
+                    for field in fields {
+                        conditions.push(Expr::BinaryOp {
+                            left: Box::new(Expr::CompoundIdentifier(vec![
+                                variable.clone(),
+                                field.key.clone(),
+                            ])),
+                            op: BinaryOperator::Eq,
+                            right: field.value.clone(),
+                        });
+                    }
+                }
+                if let Some(variable) = element.variable() {
+                    seen.push(&variable.value);
+                }
+
+                path.push(match element {
+                    PatternElement::Node { .. } => GraphPatternElement::Vertex { variable, labels },
+                    PatternElement::Relationship {
+                        direction, length, ..
+                    } => GraphPatternElement::Edge {
+                        variable,
+                        labels,
+                        direction: match self.config.direction(direction) {
+                            RelationshipDirection::Left => GraphEdgeDirection::Left,
+                            RelationshipDirection::Right => GraphEdgeDirection::Right,
+                            RelationshipDirection::None => GraphEdgeDirection::Any,
+                            RelationshipDirection::Both => {
+                                return Err(TransformError::Unsupported(format!(
+                                    "relationship with both arrows {element} in GRAPH_TABLE"
+                                )))
+                            }
+                        },
+                        quantifier: length
+                            .as_ref()
+                            .map(|length| edge_quantifier(length, element))
+                            .transpose()?,
+                    },
+                });
+            }
+            paths.push(path);
+        }
+        conditions.extend(plan.residual_where.clone());
+        for condition in &conditions {
+            self.check_graph_expr(condition, &plan)?;
+        }
+        let selection = (!conditions.is_empty()).then(|| {
+            let conditions = conditions
+                .iter()
+                .map(|condition| and_operand(self.graph_expr(condition)))
+                .collect();
+            self.combine_conditions(conditions)
+        });
+
+        let (projection, columns, group_by, order_by) =
+            self.graph_table_columns(return_clause, &plan)?;
+        let table = TableFactor::GraphTable {
+            graph: ObjectName::from(vec![Ident::new(graph)]),
+            paths,
+            selection,
+            columns: columns
+                .items
+                .into_iter()
+                .map(|(expr, alias)| SelectItem::ExprWithAlias { expr, alias })
+                .collect(),
+            alias: None,
+        };
+        let mut query = select_from(projection, table, None);
+        if let SetExpr::Select(select) = query.body.as_mut() {
+            if return_clause.distinct {
+                select.distinct = Some(Distinct::Distinct);
+            }
+            select.group_by = GroupByExpr::Expressions(group_by, vec![]);
+        }
+        if !order_by.is_empty() {
+            query.order_by = Some(OrderBy {
+                kind: OrderByKind::Expressions(order_by),
+                interpolate: None,
+            });
+        }
+        self.paginate(&mut query, return_clause)?;
+        Ok(query)
+    }
+
+    /// The projection, graph table columns, GROUP BY and ORDER BY of the
+    /// SELECT of a RETURN
+    ///
+    /// An expression without aggregates is a column, which the SELECT
+    /// returns by name. An expression with them is computed by the SELECT,
+    /// from the columns of the arguments of its aggregates, grouping by
+    /// the other columns.
+    #[allow(clippy::type_complexity)]
+    fn graph_table_columns(
+        &self,
+        return_clause: &ReturnClause,
+        plan: &GraphPlan,
+    ) -> Result<(Vec<SelectItem>, Columns, Vec<Expr>, Vec<OrderByExpr>), TransformError> {
+        let mut items = vec![];
+        for item in &return_clause.items {
+            let (expr, alias) = match item {
+                SelectItem::UnnamedExpr(expr) => (expr, None),
+                SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias)),
+                _ => {
+                    return Err(TransformError::Unsupported(format!(
+                        "RETURN {item} in GRAPH_TABLE"
+                    )))
+                }
+            };
+            let expr = count_rows(expr, plan);
+            self.check_graph_expr(&expr, plan)?;
+            items.push((expr, alias, item));
+        }
+
+        // Returned properties are named after themselves unless another
+        // column is, as the columns of the join-based SQL are
+        let aliases: Vec<&str> = items
+            .iter()
+            .filter_map(|(_, alias, _)| alias.map(|alias| alias.value.as_str()))
+            .collect();
+        let property = |expr: &Expr| match expr {
+            Expr::CompoundIdentifier(idents) if idents.len() == 2 => {
+                Some((idents[0].value.clone(), idents[1].value.clone()))
+            }
+            _ => properties_field(expr)
+                .map(|(variable, property)| (variable.value.clone(), property.value.clone())),
+        };
+        let properties: Vec<String> = items
+            .iter()
+            .filter(|(_, alias, _)| alias.is_none())
+            .filter_map(|(expr, _, _)| property(expr))
+            .map(|(_, property)| property)
+            .collect();
+        let mut names: Vec<Option<Ident>> = vec![];
+        for (expr, alias, item) in &items {
+            let name = match (alias, property(expr)) {
+                _ if contains_aggregate(expr) => alias.cloned(),
+                (Some(alias), _) => Some((*alias).clone()),
+                (None, Some((variable, property))) => {
+                    let shared = properties.iter().filter(|p| **p == property).count() > 1;
+                    Some(if shared || aliases.contains(&property.as_str()) {
+                        self.column_name(format!("{variable}_{property}"))
+                    } else {
+                        self.column_name(property)
+                    })
+                }
+                (None, None) => Some(self.column_name(return_column_name(item))),
+            };
+            if let Some(name) = &name {
+                if names
+                    .iter()
+                    .flatten()
+                    .any(|other| other.value == name.value)
+                {
+                    return Err(duplicate_column(&name.value));
+                }
+            }
+            names.push(name);
+        }
+        let returned: Vec<Ident> = names.iter().flatten().cloned().collect();
+
+        let mut columns = Columns::default();
+        let mut projection = vec![];
+        let mut group_by = vec![];
+        let aggregating = items.iter().any(|(expr, _, _)| contains_aggregate(expr));
+        for ((expr, _, _), name) in items.iter().zip(&names) {
+            if contains_aggregate(expr) {
+                let expr = self.outer_expr(expr, &mut columns, &returned);
+                projection.push(match name {
+                    Some(alias) => SelectItem::ExprWithAlias {
+                        expr,
+                        alias: alias.clone(),
+                    },
+                    None => SelectItem::UnnamedExpr(expr),
+                });
+                continue;
+            }
+            let name = name.clone().expect("columns without aggregates are named");
+            let column = Expr::Identifier(columns.add(self.graph_expr(expr), name));
+            if aggregating {
+                group_by.push(column.clone());
+            }
+            projection.push(SelectItem::UnnamedExpr(column));
+        }
+
+        let mut order_by = vec![];
+        for key in &return_clause.order_by {
+            let returned_name = items
+                .iter()
+                .zip(&names)
+                .find(|((expr, alias, _), _)| {
+                    *expr == key.expr
+                        || matches!(&key.expr, Expr::Identifier(ident)
+                            if alias.is_some_and(|alias| alias.value == ident.value))
+                })
+                .and_then(|(_, name)| name.clone());
+            let expr = match returned_name {
+                Some(name) => Expr::Identifier(name),
+                None => {
+                    let expr = count_rows(&key.expr, plan);
+                    self.check_graph_expr(&expr, plan)?;
+                    let hidden = columns.items.len();
+                    let expr = self.outer_expr(&expr, &mut columns, &returned);
+                    // The rows of RETURN DISTINCT are only those it returns
+                    if return_clause.distinct && columns.items.len() > hidden {
+                        return Err(TransformError::Unsupported(format!(
+                            "ORDER BY {} of RETURN DISTINCT on what it does not return, in \
+                             GRAPH_TABLE",
+                            key.expr
+                        )));
+                    }
+                    expr
+                }
+            };
+            order_by.push(OrderByExpr {
+                expr,
+                options: key.options,
+                with_fill: None,
+            });
+        }
+        Ok((projection, columns, group_by, order_by))
+    }
+
+    /// `name` as the name of a column or label, quoted if it is not a bare
+    /// SQL name, e.g. the text `n.age + 1` of a RETURN item
+    fn column_name(&self, name: String) -> Ident {
+        if is_plain_key(&name) {
+            Ident::new(name)
+        } else {
+            Ident::with_quote(self.config.target.identifier_quote(), name)
+        }
+    }
+
+    /// `expr` as the SELECT around the graph table computes it: the
+    /// arguments of its aggregates, and the properties outside of them, are
+    /// columns of the graph table
+    fn outer_expr(&self, expr: &Expr, columns: &mut Columns, returned: &[Ident]) -> Expr {
+        if let Expr::Function(function) = expr {
+            if is_aggregate_function(function) {
+                let mut function = function.clone();
+                if let FunctionArguments::List(list) = &mut function.args {
+                    for arg in &mut list.args {
+                        if let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) = arg {
+                            let column = columns.add_hidden(self.graph_expr(arg), returned);
+                            *arg = Expr::Identifier(column);
+                        }
+                    }
+                }
+                return Expr::Function(function);
+            }
+        }
+        if let Expr::CompoundIdentifier(_) = expr {
+            return Expr::Identifier(columns.add_hidden(self.graph_expr(expr), returned));
+        }
+        if let Some(literal) = self.string_literal(expr) {
+            return literal;
+        }
+        map_subexpressions(expr, &mut |sub_expr| {
+            self.outer_expr(sub_expr, columns, returned)
+        })
+    }
+
+    /// Reject what `expr` uses that a graph table cannot express: variables
+    /// as values, graph functions, and Cypher predicates other than string
+    /// matching with a literal
+    fn check_graph_expr(&self, expr: &Expr, plan: &GraphPlan) -> Result<(), TransformError> {
+        let mut pending = vec![expr];
+        while let Some(expr) = pending.pop() {
+            if properties_field(expr).is_some() {
+                continue;
+            }
+            let unsupported = match expr {
+                Expr::Identifier(ident) => plan.binding(&ident.value).is_some(),
+                Expr::Function(function) => {
+                    let name = function.name.to_string().to_lowercase();
+                    GRAPH_FUNCTIONS.contains(&name.as_str())
+                }
+                Expr::Cypher(cypher_expr) => !matches!(
+                    cypher_expr.as_ref(),
+                    CypherExpr::StringPredicate { pattern, .. } if string_value(pattern).is_some()
+                ),
+                Expr::CompoundFieldAccess { .. } | Expr::Dictionary(_) => true,
+                _ => false,
+            };
+            if unsupported {
+                return Err(TransformError::Unsupported(format!(
+                    "{expr} in GRAPH_TABLE"
+                )));
+            }
+            pending.extend(subexpressions(expr));
+        }
+        Ok(())
+    }
+
+    /// `expr`, which [`Self::check_graph_expr`] accepts, as an expression of
+    /// the graph table, whose properties are read as `n.name`
+    fn graph_expr(&self, expr: &Expr) -> Expr {
+        if let Some((variable, property)) = properties_field(expr) {
+            return Expr::CompoundIdentifier(vec![variable.clone(), property.clone()]);
+        }
+        if let Expr::Cypher(cypher_expr) = expr {
+            if let CypherExpr::StringPredicate {
+                expr: text,
+                operator,
+                pattern,
+            } = cypher_expr.as_ref()
+            {
+                let literal = string_value(pattern).expect("a checked string pattern");
+                let (like, escaped) = like_pattern(literal, *operator);
+                let like = Expr::value(Value::SingleQuotedString(like));
+                let string = self.graph_expr(text);
+                let (string, like) = if self.ignores_case(text, pattern) {
+                    (
+                        function_call("lower", vec![string]),
+                        function_call("lower", vec![like]),
+                    )
+                } else {
+                    (string, like)
+                };
+                return Expr::Like {
+                    negated: false,
+                    any: false,
+                    expr: Box::new(string),
+                    pattern: Box::new(like),
+                    escape_char: escaped.then(|| Value::SingleQuotedString("\\".to_string())),
+                };
+            }
+        }
+        if let Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq,
+            right,
+        } = expr
+        {
+            if self.ignores_case(left, right) || self.ignores_case(right, left) {
+                let lower = |side: &Expr| function_call("lower", vec![self.graph_expr(side)]);
+                return Expr::BinaryOp {
+                    left: Box::new(lower(left)),
+                    op: BinaryOperator::Eq,
+                    right: Box::new(lower(right)),
+                };
+            }
+        }
+        if let Some(literal) = self.string_literal(expr) {
+            return literal;
+        }
+        map_subexpressions(expr, &mut |sub_expr| self.graph_expr(sub_expr))
+    }
+}
+
+/// `expr` with the counts of variables, e.g. `count(n)`, counting rows
+/// instead, as the variables of a match are never null
+fn count_rows(expr: &Expr, plan: &GraphPlan) -> Expr {
+    if let Expr::Function(function) = expr {
+        if let FunctionArguments::List(list) = &function.args {
+            if let [FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Identifier(variable)))] =
+                list.args.as_slice()
+            {
+                if function.name.to_string().eq_ignore_ascii_case("count")
+                    && list.duplicate_treatment.is_none()
+                    && plan.binding(&variable.value).is_some()
+                {
+                    let mut function = function.clone();
+                    if let FunctionArguments::List(list) = &mut function.args {
+                        list.args = vec![FunctionArg::Unnamed(FunctionArgExpr::Wildcard)];
+                    }
+                    return Expr::Function(function);
+                }
+            }
+        }
+    }
+    map_subexpressions(expr, &mut |sub_expr| count_rows(sub_expr, plan))
+}
+
+/// The quantifier of an edge pattern matching the paths a variable-length
+/// relationship does, e.g. `{1,3}` for `*1..3`
+fn edge_quantifier(
+    length: &RelationshipLength,
+    element: &PatternElement,
+) -> Result<RepetitionQuantifier, TransformError> {
+    let bound = |n: u64| {
+        u32::try_from(n)
+            .map_err(|_| TransformError::Unsupported(format!("length of {element} in GRAPH_TABLE")))
+    };
+    Ok(match length {
+        RelationshipLength::Variable => RepetitionQuantifier::OneOrMore,
+        RelationshipLength::Exact(n) => RepetitionQuantifier::Exactly(bound(*n)?),
+        RelationshipLength::Range(min, None) => {
+            RepetitionQuantifier::AtLeast(bound(min.unwrap_or(1))?)
+        }
+        RelationshipLength::Range(min, Some(max)) => {
+            RepetitionQuantifier::Range(bound(min.unwrap_or(1))?, bound(*max)?)
+        }
+    })
+}
@@ -17,250 +17,326 @@
 
 //! Cypher to SQL transformation module
 
-// pub mod cypher_to_sql;  // Comment this out - it's broken
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
 
-// pub use cypher_to_sql::CypherToSqlTransformer;  // Comment this out too
+pub mod capabilities;
+pub mod check;
+pub mod cypher_to_sql;
+pub mod format;
+mod graph_table;
+pub mod params;
+pub mod plan;
+pub mod quoting;
+pub mod rewriter;
+pub mod schema;
+pub mod script;
+pub mod service;
+pub mod stats;
 
+pub use capabilities::{capabilities, Capabilities, Capability, Support, CONSTRUCTS};
+pub use check::{check_cypher, CypherCheck, StatementKind};
+pub use cypher_to_sql::{
+    BothDirections, CreatedNode, CypherToSqlTransformer, DistinctOrderBy, IdType, SqlTarget,
+    TransformConfig, TransformOutput, UndirectedStrategy,
+};
+pub use format::{format_statement, SqlFormat};
+pub use params::{ParamBinding, ParamStyle, ParamValue};
+pub use plan::{Binding, BindingKind, GraphPlan, PlanPath};
+pub use quoting::IdentifierQuoting;
+pub use rewriter::{FunctionRewriter, RewriteContext};
+pub use schema::{ColumnRequirement, IndexRequirement, SchemaRequirements, TableRequirement};
+pub use script::ScriptTransformer;
+pub use service::{handle_request, ServiceError, ServiceErrorKind, ServiceResponse};
+pub use stats::TransformStats;
+
+use core::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::ast::CypherStatement;
 use crate::dialect::CypherDialect;
-use crate::parser::Parser;
-use crate::tokenizer::Tokenizer;
-use crate::ast::cypher::*;
+use crate::parser::{CypherStatementIterator, Parser, ParserError, EMPTY_CYPHER_STATEMENT};
+use crate::tokenizer::{Token, TokenizerError};
 
-/// Convenience function to transform Cypher query string to SQL
-pub fn transform_cypher_to_sql(cypher_query: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let dialect = CypherDialect;
-    let tokens = Tokenizer::new(&dialect, cypher_query).tokenize()?;
-    let mut parser = Parser::new(&dialect).with_tokens(tokens);
-    
-    let cypher_stmt = parser.parse_cypher_statement()?;
-    
-    // Use the working logic from your binary instead of the broken transformer
-    transform_cypher_to_sql_basic(&cypher_stmt)
+/// Errors produced while translating a Cypher statement to SQL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransformError {
+    /// The statement uses a Cypher construct that cannot be translated yet
+    Unsupported(String),
+    /// The statement is not a valid query, e.g. UNION arms returning
+    /// different columns
+    InvalidQuery(String),
+    /// The query text is not valid Cypher
+    Parse(ParserError),
+    /// The query text has no statement, only whitespace, comments or `;`
+    EmptyInput,
+    /// An expression is nested deeper than
+    /// [`TransformConfig::recursion_limit`]
+    RecursionLimitExceeded,
+    /// The patterns of a MATCH fall into groups of variables that nothing
+    /// connects, whose cross join [`TransformConfig::strict_cartesian`]
+    /// rejects; each group is listed by its variables
+    CartesianProduct(Vec<Vec<String>>),
+    /// Parameters of the statement that
+    /// [`CypherToSqlTransformer::to_sql_output_with_params`] has no value
+    /// for, by name without the `$`
+    MissingParameters(Vec<String>),
 }
 
-/// Basic Cypher to SQL transformation (copied from your working binary)
-/// Uses generic schema: nodes(id, label, properties), relationships(from_id, to_id, type, properties)
-fn transform_cypher_to_sql_basic(cypher_stmt: &CypherStatement) -> Result<String, Box<dyn std::error::Error>> {
-    match cypher_stmt {
-        CypherStatement::Match { optional: _, patterns, r#where, r#return } => {
-            let mut sql = String::new();
-            let mut node_counter = 0;
-            let mut rel_counter = 0;
-            
-            // Start with SELECT
-            sql.push_str("SELECT ");
-            
-            // Handle return clause
-            if let Some(ret_clause) = r#return {
-                if ret_clause.distinct {
-                    sql.push_str("DISTINCT ");
-                }
-                
-                // Add projection items
-                let items: Vec<String> = ret_clause.items.iter().map(|item| {
-                    match item {
-                        crate::ast::SelectItem::UnnamedExpr(expr) => {
-                            // Transform property access like n.name to json_extract
-                            let expr_str = format!("{}", expr);
-                            if expr_str.contains('.') {
-                                let parts: Vec<&str> = expr_str.split('.').collect();
-                                if parts.len() == 2 {
-                                    let var_name = parts[0];
-                                    let property_name = parts[1];
-                                    return format!("json_extract({}.properties, '$.{}') as {}", var_name, property_name, property_name);
-                                }
-                            }
-                            expr_str
-                        },
-                        crate::ast::SelectItem::ExprWithAlias { expr, alias } => {
-                            let expr_str = format!("{}", expr);
-                            if expr_str.contains('.') {
-                                let parts: Vec<&str> = expr_str.split('.').collect();
-                                if parts.len() == 2 {
-                                    let var_name = parts[0];
-                                    let property_name = parts[1];
-                                    return format!("json_extract({}.properties, '$.{}') AS {}", var_name, property_name, alias);
-                                }
-                            }
-                            format!("{} AS {}", expr, alias)
-                        },
-                        crate::ast::SelectItem::Wildcard(_) => "*".to_string(),
-                        _ => "*".to_string(),
-                    }
-                }).collect();
-                
-                if items.is_empty() {
-                    sql.push_str("*");
-                } else {
-                    sql.push_str(&items.join(", "));
-                }
-            } else {
-                sql.push_str("*");
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransformError::Unsupported(s) => write!(f, "unsupported Cypher feature: {s}"),
+            TransformError::InvalidQuery(s) => write!(f, "invalid Cypher query: {s}"),
+            TransformError::Parse(e) => write!(f, "{e}"),
+            TransformError::EmptyInput => write!(f, "{EMPTY_CYPHER_STATEMENT}"),
+            TransformError::RecursionLimitExceeded => write!(f, "recursion limit exceeded"),
+            TransformError::CartesianProduct(groups) => {
+                write!(f, "cartesian product of {}", display_variable_groups(groups))
             }
-            
-            // Add FROM clause
-            sql.push_str(" FROM ");
-            
-            // Process patterns to determine tables and joins
-            let mut from_clauses = Vec::new();
-            let mut join_clauses = Vec::new();
-            let mut where_conditions = Vec::new();
-            
-            for pattern in patterns {
-                let mut prev_node_alias: Option<String> = None;
-                
-                for element in &pattern.elements {
-                    match element {
-                        PatternElement::Node { variable, labels, properties: _ } => {
-                            node_counter += 1;
-                            let alias = if let Some(ref var) = variable {
-                                var.value.clone()
-                            } else {
-                                format!("n{}", node_counter)
-                            };
-                            
-                            if from_clauses.is_empty() {
-                                from_clauses.push(format!("nodes {}", alias));
-                            } else {
-                                join_clauses.push(format!("JOIN nodes {} ON TRUE", alias));
-                            }
-                            
-                            // Add label condition
-                            if !labels.is_empty() {
-                                where_conditions.push(format!("{}.label = '{}'", alias, labels[0].value));
-                            }
-                            
-                            prev_node_alias = Some(alias);
-                        }
-                        PatternElement::Relationship { variable, types, properties: _, direction: _, length: _ } => {
-                            rel_counter += 1;
-                            let rel_alias = if let Some(ref var) = variable {
-                                var.value.clone()
-                            } else {
-                                format!("r{}", rel_counter)
-                            };
-                            
-                            // For relationships, we need to join the relationship table
-                            if let Some(ref from_node) = prev_node_alias {
-                                join_clauses.push(format!("JOIN relationships {} ON {}.id = {}.from_id", rel_alias, from_node, rel_alias));
-                                
-                                // Add relationship type condition
-                                if !types.is_empty() {
-                                    where_conditions.push(format!("{}.type = '{}'", rel_alias, types[0].value));
-                                }
-                            }
-                        }
-                    }
-                }
+            TransformError::MissingParameters(names) => {
+                let names: Vec<String> = names.iter().map(|name| format!("${name}")).collect();
+                let plural = if names.len() == 1 { "" } else { "s" };
+                write!(f, "missing value{plural} for {}", names.join(", "))
             }
-            
-            // Combine FROM and JOINs
-            sql.push_str(&from_clauses.join(", "));
-            if !join_clauses.is_empty() {
-                sql.push(' ');
-                sql.push_str(&join_clauses.join(" "));
+        }
+    }
+}
+
+impl From<ParserError> for TransformError {
+    fn from(e: ParserError) -> Self {
+        match e {
+            ParserError::ParserError(message) if message == EMPTY_CYPHER_STATEMENT => {
+                TransformError::EmptyInput
             }
-            
-            // Add WHERE clause
-            if let Some(where_expr) = r#where {
-                where_conditions.push(format!("{}", where_expr));
+            e => TransformError::Parse(e),
+        }
+    }
+}
+
+impl From<TokenizerError> for TransformError {
+    fn from(e: TokenizerError) -> Self {
+        TransformError::Parse(e.into())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TransformError {}
+
+/// A Cypher construct that the transformer ignores or only approximates, so
+/// the generated SQL may not return what the Cypher query would, or that
+/// the SQL runs at a cost the query does not show
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TransformWarning {
+    /// The construct, e.g. `OPTIONAL MATCH`
+    pub feature: String,
+    /// The Cypher text the construct appears in
+    pub source_text: String,
+    /// What the warning is about, to filter or escalate it
+    pub kind: WarningKind,
+}
+
+/// What a [`TransformWarning`] warns about
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WarningKind {
+    /// The construct is ignored or approximated
+    #[default]
+    Untranslated,
+    /// The construct is a cross join of disconnected patterns, whose size is
+    /// the product of their numbers of matches
+    CartesianProduct,
+    /// A parameter value was given that the statement does not use
+    UnusedParameter,
+    /// A script directive, e.g. `:sysinfo`, that the transformer does not
+    /// know and skips
+    UnknownDirective,
+    /// A string comparison that ignores case, unlike Cypher's, under
+    /// [`TransformConfig::case_insensitive_text_ops`]
+    CaseInsensitive,
+}
+
+impl fmt::Display for TransformWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            WarningKind::Untranslated => {
+                write!(f, "{} is not translated: {}", self.feature, self.source_text)
             }
-            
-            if !where_conditions.is_empty() {
-                sql.push_str(" WHERE ");
-                sql.push_str(&where_conditions.join(" AND "));
+            WarningKind::CartesianProduct => write!(f, "{} in {}", self.feature, self.source_text),
+            WarningKind::UnusedParameter => {
+                write!(f, "{} is not used by {}", self.feature, self.source_text)
             }
-            
-            // Add ORDER BY, LIMIT etc. from return clause
-            if let Some(ret_clause) = r#return {
-                if !ret_clause.order_by.is_empty() {
-                    sql.push_str(" ORDER BY ");
-                    let order_items: Vec<String> = ret_clause.order_by.iter().map(|item| {
-                        // Transform property access in ORDER BY as well
-                        let expr_str = format!("{}", item.expr);
-                        if expr_str.contains('.') {
-                            let parts: Vec<&str> = expr_str.split('.').collect();
-                            if parts.len() == 2 {
-                                let var_name = parts[0];
-                                let property_name = parts[1];
-                                return format!("json_extract({}.properties, '$.{}') ASC", var_name, property_name);
-                            }
-                        }
-                        format!("{} ASC", item.expr)
-                    }).collect();
-                    sql.push_str(&order_items.join(", "));
-                }
-                
-                if let Some(limit) = &ret_clause.limit {
-                    sql.push_str(&format!(" LIMIT {}", limit));
-                }
-                
-                if let Some(skip) = &ret_clause.skip {
-                    sql.push_str(&format!(" OFFSET {}", skip));
-                }
+            WarningKind::UnknownDirective => {
+                write!(
+                    f,
+                    "unknown directive {} is skipped: {}",
+                    self.feature, self.source_text
+                )
             }
-            
-            Ok(sql)
-        }
-        
-        CypherStatement::Create { patterns } => {
-            let mut sql = String::new();
-            
-            for pattern in patterns {
-                for element in &pattern.elements {
-                    match element {
-                        PatternElement::Node { variable: _, labels, properties } => {
-                            sql.push_str("INSERT INTO nodes (label, properties) VALUES (");
-                            
-                            // Add label
-                            if !labels.is_empty() {
-                                sql.push_str(&format!("'{}'", labels[0].value));
-                            } else {
-                                sql.push_str("NULL");
-                            }
-                            
-                            sql.push_str(", ");
-                            
-                            // Add properties as JSON
-                            if let Some(ref _props) = properties {
-                                sql.push_str("'{}')");  // Simplified - would need proper JSON construction
-                            } else {
-                                sql.push_str("'{}')");
-                            }
-                            
-                            sql.push_str(";\n");
-                        }
-                        PatternElement::Relationship { variable: _, types, properties, direction: _, length: _ } => {
-                            sql.push_str("INSERT INTO relationships (from_id, to_id, type, properties) VALUES (");
-                            sql.push_str("?, ?, ");  // Placeholder for node IDs
-                            
-                            // Add relationship type
-                            if !types.is_empty() {
-                                sql.push_str(&format!("'{}'", types[0].value));
-                            } else {
-                                sql.push_str("NULL");
-                            }
-                            
-                            sql.push_str(", ");
-                            
-                            // Add properties as JSON
-                            if let Some(ref _props) = properties {
-                                sql.push_str("'{}')");  // Simplified
-                            } else {
-                                sql.push_str("'{}')");
-                            }
-                            
-                            sql.push_str(";\n");
-                        }
-                    }
-                }
+            WarningKind::CaseInsensitive => {
+                write!(f, "{} ignores case: {}", self.feature, self.source_text)
             }
-            
-            Ok(sql.trim_end_matches(";\n").to_string())
-        }
-        
-        _ => {
-            Ok("-- Transformation not yet implemented for this Cypher statement type".to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+/// Groups of variables written as `{a, r, b}, {c}`
+pub(crate) fn display_variable_groups(groups: &[Vec<String>]) -> String {
+    groups
+        .iter()
+        .map(|group| format!("{{{}}}", group.join(", ")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Transform a Cypher query to SQL over the generic schema:
+/// `nodes(id, label, properties)` and
+/// `relationships(from_id, to_id, type, properties)`, with properties stored
+/// as JSON
+///
+/// Unlike the transformer's default configuration, this is not
+/// [`TransformConfig::strict`]: the constructs the SQL ignores or
+/// approximates are translated as well as they can be, without a warning.
+pub fn transform_cypher_to_sql(cypher_query: &str) -> Result<String, TransformError> {
+    let parser = Parser::new(&CypherDialect).try_with_sql(cypher_query)?;
+    let cypher_stmt = parse_cypher_query(parser, cypher_query, false)?;
+
+    let config = TransformConfig {
+        use_label_tables: false,
+        strict: false,
+        ..TransformConfig::default()
+    };
+    CypherToSqlTransformer::with_config(config).to_sql(&cypher_stmt)
+}
+
+/// Parse the Cypher statement of `cypher_query` with `parser`, which has its
+/// tokens and under `strict` must be the whole query
+///
+/// The parser stops before a clause it does not know, e.g. `REMOVE`, so the
+/// rest of a query would otherwise be dropped without an error.
+///
+/// ```
+/// # use sqlparser::dialect::CypherDialect;
+/// # use sqlparser::parser::Parser;
+/// # use sqlparser::transformer::*;
+/// let query = "MATCH (n) REMOVE n.x RETURN n";
+/// let parser = Parser::new(&CypherDialect).try_with_sql(query)?;
+/// assert_eq!(
+///     parse_cypher_query(parser, query, true),
+///     Err(TransformError::Unsupported(format!("REMOVE in {query}")))
+/// );
+/// # Ok::<(), TransformError>(())
+/// ```
+pub fn parse_cypher_query(
+    mut parser: Parser,
+    cypher_query: &str,
+    strict: bool,
+) -> Result<CypherStatement, TransformError> {
+    let statement = parser.parse_cypher_statement()?;
+    while parser.consume_token(&Token::SemiColon) {}
+    let next = parser.peek_token();
+    if strict && next.token != Token::EOF {
+        return Err(TransformError::Unsupported(format!(
+            "{} in {}",
+            next.token,
+            cypher_query.trim()
+        )));
+    }
+    Ok(statement)
+}
+
+/// Transform a Cypher query to SQL with the given configuration, with its
+/// `$name` parameters replaced by the literals of their `params`, e.g. to run
+/// a query once without binding parameters
+///
+/// A parameter without a value is a [`TransformError::MissingParameters`],
+/// and a value no parameter uses is a [`WarningKind::UnusedParameter`]
+/// warning of the output.
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use sqlparser::transformer::*;
+/// let params = BTreeMap::from([("name".to_string(), ParamValue::String("Ada".to_string()))]);
+/// let config = TransformConfig {
+///     use_label_tables: false,
+///     ..TransformConfig::default()
+/// };
+/// let output = transform_cypher_to_sql_with_params(
+///     "MATCH (n:Person) WHERE n.name = $name RETURN n.age",
+///     &params,
+///     config,
+/// )?;
+/// assert_eq!(
+///     output.sql,
+///     "SELECT json_extract(n.properties, '$.age') AS age FROM nodes AS n \
+///      WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = 'Ada'"
+/// );
+/// # Ok::<(), TransformError>(())
+/// ```
+pub fn transform_cypher_to_sql_with_params(
+    cypher_query: &str,
+    params: &BTreeMap<String, ParamValue>,
+    config: TransformConfig,
+) -> Result<TransformOutput, TransformError> {
+    let parser = Parser::new(&CypherDialect).try_with_sql(cypher_query)?;
+    let cypher_stmt = parse_cypher_query(parser, cypher_query, config.strict)?;
+    CypherToSqlTransformer::with_config(config).to_sql_output_with_params(&cypher_stmt, params)
+}
+
+/// Transform a Cypher query to SQL over the generic schema like
+/// [`transform_cypher_to_sql`], along with the sizes of the query and the
+/// SQL and the time each phase took
+pub fn transform_cypher_to_sql_with_stats(
+    cypher_query: &str,
+) -> Result<(String, TransformStats), TransformError> {
+    let config = TransformConfig {
+        use_label_tables: false,
+        strict: false,
+        ..TransformConfig::default()
+    };
+    let (output, stats) =
+        CypherToSqlTransformer::with_config(config).to_sql_output_with_stats(cypher_query)?;
+    Ok((output.sql, stats))
+}
+
+/// Transform the `;` separated Cypher statements of `script` to SQL over the
+/// generic schema, like [`transform_cypher_to_sql`], parsing each statement
+/// only when the one before it has been transformed
+///
+/// The directives of the script are applied as by [`ScriptTransformer`]:
+/// the values of `:param` are inlined into the statements after it, and
+/// `:begin`, `:commit` and `:rollback` are their SQL statements. The other
+/// directives have no SQL and are skipped.
+///
+/// ```
+/// # use sqlparser::transformer::*;
+/// let script = ":param name => 'Ada';\nMATCH (n) WHERE n.name = $name RETURN n.age;\n";
+/// assert_eq!(
+///     transform_cypher_script(script).collect::<Result<Vec<_>, _>>()?,
+///     ["SELECT json_extract(n.properties, '$.age') AS age FROM nodes AS n \
+///       WHERE json_extract(n.properties, '$.name') = 'Ada'"]
+/// );
+/// # Ok::<(), TransformError>(())
+/// ```
+pub fn transform_cypher_script(
+    script: &str,
+) -> impl Iterator<Item = Result<String, TransformError>> + '_ {
+    let config = TransformConfig {
+        use_label_tables: false,
+        strict: false,
+        ..TransformConfig::default()
+    };
+    let mut transformer = ScriptTransformer::new(config);
+    let mut items = CypherStatementIterator::new(script.split_inclusive('\n'));
+    core::iter::from_fn(move || items.next_item()).filter_map(move |item| {
+        match item.map_err(TransformError::from).and_then(|item| transformer.transform(&item)) {
+            Ok(output) if output.sql.is_empty() => None,
+            output => Some(output.map(|output| output.sql)),
+        }
+    })
+}
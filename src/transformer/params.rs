@@ -0,0 +1,513 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Placeholders for Cypher `$name` parameters in the generated SQL, and
+//! the values of parameters inlined into a Cypher statement
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::ast::cypher::{subexpressions_mut, CypherExpr, CypherStatement};
+use crate::ast::*;
+
+use super::cypher_to_sql::map_subexpressions;
+use super::{TransformError, TransformWarning, WarningKind};
+
+/// How Cypher parameters are written in the generated SQL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParamStyle {
+    /// As written in the Cypher query, e.g. `$name`
+    #[default]
+    Inline,
+    /// A positional `?` for every occurrence of a parameter
+    Question,
+    /// `$1`, `$2`, ... numbered by the first occurrence of each parameter, so
+    /// repeated occurrences share a placeholder
+    Numbered,
+}
+
+/// A SQL parameter that a Cypher parameter became
+///
+/// A Cypher parameter used more than once has a single binding, counting its
+/// occurrences, unless it is written with [`ParamStyle::Question`], where every
+/// occurrence is a positional parameter with a binding of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParamBinding {
+    /// The Cypher parameter name, without the `$`
+    pub name: String,
+    /// The placeholder written in the SQL, e.g. `$name`, `?` or `$2`
+    pub placeholder: String,
+    /// The 1-based position of a positional parameter within its statement,
+    /// or `None` for [`ParamStyle::Inline`] parameters, which are bound by name
+    pub index: Option<usize>,
+    /// How many times the placeholder appears in the SQL
+    pub count: usize,
+}
+
+/// The value of a Cypher parameter, inlined into a statement as a literal by
+/// [`CypherToSqlTransformer::to_sql_output_with_params`]
+///
+/// [`CypherToSqlTransformer::to_sql_output_with_params`]:
+///     super::CypherToSqlTransformer::to_sql_output_with_params
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamValue {
+    Null,
+    Boolean(bool),
+    /// A number written as in JSON, e.g. `42` or `-1.5e3`
+    Number(String),
+    String(String),
+    List(Vec<ParamValue>),
+    /// A map, such as the properties of a created node
+    Map(BTreeMap<String, ParamValue>),
+}
+
+impl ParamValue {
+    /// The value of `expr` when it is made of literals, e.g. the value of a
+    /// `:param` directive, or `None` when it has to be evaluated
+    pub(crate) fn from_literal(expr: &Expr) -> Option<ParamValue> {
+        Some(match expr {
+            Expr::Value(value) => match &value.value {
+                Value::Null => ParamValue::Null,
+                Value::Boolean(b) => ParamValue::Boolean(*b),
+                Value::Number(n, _) => ParamValue::Number(n.to_string()),
+                Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => {
+                    ParamValue::String(s.clone())
+                }
+                _ => return None,
+            },
+            Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            } => match ParamValue::from_literal(expr)? {
+                ParamValue::Number(n) if !n.starts_with('-') => ParamValue::Number(format!("-{n}")),
+                _ => return None,
+            },
+            Expr::Array(array) => ParamValue::List(
+                array
+                    .elem
+                    .iter()
+                    .map(ParamValue::from_literal)
+                    .collect::<Option<_>>()?,
+            ),
+            Expr::Dictionary(fields) => ParamValue::Map(
+                fields
+                    .iter()
+                    .map(|field| {
+                        Some((
+                            field.key.value.clone(),
+                            ParamValue::from_literal(&field.value)?,
+                        ))
+                    })
+                    .collect::<Option<_>>()?,
+            ),
+            Expr::Nested(expr) => ParamValue::from_literal(expr)?,
+            _ => return None,
+        })
+    }
+
+    /// The value of the property at the path of `keys` into nested maps,
+    /// null for a key a map does not have, or `None` when a key is looked up
+    /// in something other than a map
+    fn property(&self, keys: &[String]) -> Option<&ParamValue> {
+        let Some((key, rest)) = keys.split_first() else {
+            return Some(self);
+        };
+        match self {
+            ParamValue::Map(fields) => fields.get(key).unwrap_or(&ParamValue::Null).property(rest),
+            _ => None,
+        }
+    }
+
+    /// The value as a Cypher literal, with a negative number written as the
+    /// negation of its absolute value, as the parser reads it
+    fn to_expr(&self, name: &str) -> Result<Expr, TransformError> {
+        Ok(match self {
+            ParamValue::Null => Expr::value(Value::Null),
+            ParamValue::Boolean(b) => Expr::value(Value::Boolean(*b)),
+            ParamValue::Number(n) => {
+                let (negative, digits) = match n.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, n.as_str()),
+                };
+                let invalid = || {
+                    TransformError::InvalidQuery(format!("parameter ${name} is not a number: {n}"))
+                };
+                if !digits.starts_with(|c: char| c.is_ascii_digit())
+                    || digits.parse::<f64>().is_err()
+                {
+                    return Err(invalid());
+                }
+                let number =
+                    Expr::value(Value::Number(digits.parse().map_err(|_| invalid())?, false));
+                if negative {
+                    Expr::UnaryOp {
+                        op: UnaryOperator::Minus,
+                        expr: Box::new(number),
+                    }
+                } else {
+                    number
+                }
+            }
+            ParamValue::String(s) => Expr::value(Value::SingleQuotedString(s.clone())),
+            ParamValue::List(values) => Expr::Array(Array {
+                elem: values
+                    .iter()
+                    .map(|value| value.to_expr(name))
+                    .collect::<Result<_, _>>()?,
+                named: false,
+            }),
+            ParamValue::Map(fields) => Expr::Dictionary(
+                fields
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok(DictionaryField {
+                            key: Ident::new(key),
+                            value: Box::new(value.to_expr(name)?),
+                        })
+                    })
+                    .collect::<Result<_, TransformError>>()?,
+            ),
+        })
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::Value> for ParamValue {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => ParamValue::Null,
+            serde_json::Value::Bool(b) => ParamValue::Boolean(b),
+            serde_json::Value::Number(n) => ParamValue::Number(n.to_string()),
+            serde_json::Value::String(s) => ParamValue::String(s),
+            serde_json::Value::Array(values) => {
+                ParamValue::List(values.into_iter().map(ParamValue::from).collect())
+            }
+            serde_json::Value::Object(fields) => ParamValue::Map(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key, ParamValue::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Replace the `$name` parameters of `statement` with the literals of their
+/// `values`, returning a warning for each value no parameter uses
+///
+/// A property of a map parameter, e.g. `$person.name`, is replaced with the
+/// value of the property. Parameters without a value are a
+/// [`TransformError::MissingParameters`] naming all of them.
+pub(crate) fn inline_parameters(
+    statement: &mut CypherStatement,
+    values: &BTreeMap<String, ParamValue>,
+) -> Result<Vec<TransformWarning>, TransformError> {
+    let source_text = statement.to_string();
+    let (used, missing) = inline_values(statement, values)?;
+    if !missing.is_empty() {
+        return Err(TransformError::MissingParameters(
+            missing.into_iter().collect(),
+        ));
+    }
+    Ok(values
+        .keys()
+        .filter(|name| !used.contains(*name))
+        .map(|name| TransformWarning {
+            feature: format!("parameter ${name}"),
+            source_text: source_text.clone(),
+            kind: WarningKind::UnusedParameter,
+        })
+        .collect())
+}
+
+/// Replace the `$name` parameters of `statement` that have one of `values`
+/// with its literal, like [`inline_parameters`], leaving the others to be
+/// bound, e.g. with the values a script sets for some of its parameters
+pub(crate) fn inline_known_parameters(
+    statement: &mut CypherStatement,
+    values: &BTreeMap<String, ParamValue>,
+) -> Result<(), TransformError> {
+    inline_values(statement, values).map(|_| ())
+}
+
+/// Inline the parameters of `statement` that have one of `values`, returning
+/// the names of the parameters inlined and of those without a value
+fn inline_values(
+    statement: &mut CypherStatement,
+    values: &BTreeMap<String, ParamValue>,
+) -> Result<(BTreeSet<String>, BTreeSet<String>), TransformError> {
+    let mut used = BTreeSet::new();
+    let mut missing = BTreeSet::new();
+    let mut exprs = statement.exprs_mut();
+    while let Some(expr) = exprs.pop() {
+        let Some((name, keys)) = parameter_access(expr) else {
+            exprs.extend(subexpressions_mut(expr));
+            continue;
+        };
+        let Some(value) = values.get(&name) else {
+            missing.insert(name);
+            continue;
+        };
+        match value.property(&keys) {
+            Some(property) => *expr = property.to_expr(&name)?,
+            // Properties of anything but a map are left to the SQL
+            None => {
+                if let Expr::CompoundFieldAccess { root, .. } = expr {
+                    **root = value.to_expr(&name)?;
+                }
+            }
+        }
+        used.insert(name);
+    }
+    // A list is tested for membership like the list literal of `x IN [...]`
+    let mut exprs = statement.exprs_mut();
+    while let Some(expr) = exprs.pop() {
+        let in_list = match expr {
+            Expr::Cypher(cypher_expr) => match cypher_expr.as_mut() {
+                CypherExpr::In {
+                    expr: item,
+                    list: Expr::Array(array),
+                } => Some((
+                    core::mem::replace(item, Expr::value(Value::Null)),
+                    core::mem::take(&mut array.elem),
+                )),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some((item, list)) = in_list {
+            *expr = Expr::InList {
+                expr: Box::new(item),
+                list,
+                negated: false,
+            };
+        }
+        exprs.extend(subexpressions_mut(expr));
+    }
+    Ok((used, missing))
+}
+
+/// The name of the parameter `expr` is, or whose properties it accesses,
+/// e.g. `$person.address.city`, with the names of those properties
+fn parameter_access(expr: &Expr) -> Option<(String, Vec<String>)> {
+    let (root, access_chain) = match expr {
+        Expr::CompoundFieldAccess { root, access_chain } => (root.as_ref(), &access_chain[..]),
+        expr => (expr, &[][..]),
+    };
+    let Expr::Value(ValueWithSpan {
+        value: Value::Placeholder(placeholder),
+        ..
+    }) = root
+    else {
+        return None;
+    };
+    let keys = access_chain
+        .iter()
+        .map(|access| match access {
+            AccessExpr::Dot(Expr::Identifier(key)) => Some(key.value.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((placeholder.strip_prefix('$')?.to_string(), keys))
+}
+
+/// Rewrite the Cypher parameters of `statement` in the given style, in the
+/// order they appear in the SQL text, returning the SQL parameters they became
+pub(crate) fn bind_parameters(statement: &mut Statement, style: ParamStyle) -> Vec<ParamBinding> {
+    let mut binder = ParamBinder {
+        style,
+        bindings: Vec::new(),
+    };
+    match statement {
+        Statement::Query(query) => binder.query(query),
+        Statement::Insert(insert) => {
+            if let Some(source) = &mut insert.source {
+                binder.query(source);
+            }
+        }
+        _ => {}
+    }
+    binder.bindings
+}
+
+struct ParamBinder {
+    style: ParamStyle,
+    /// The bindings in order of first occurrence
+    bindings: Vec<ParamBinding>,
+}
+
+impl ParamBinder {
+    fn query(&mut self, query: &mut Query) {
+        self.set_expr(&mut query.body);
+        if let Some(OrderBy {
+            kind: OrderByKind::Expressions(exprs),
+            ..
+        }) = &mut query.order_by
+        {
+            for order_by_expr in exprs {
+                self.expr(&mut order_by_expr.expr);
+            }
+        }
+        if let Some(LimitClause::LimitOffset { limit, offset, .. }) = &mut query.limit_clause {
+            if let Some(limit) = limit {
+                self.expr(limit);
+            }
+            if let Some(offset) = offset {
+                self.expr(&mut offset.value);
+            }
+        }
+        if let Some(Fetch {
+            quantity: Some(quantity),
+            ..
+        }) = &mut query.fetch
+        {
+            self.expr(quantity);
+        }
+    }
+
+    fn set_expr(&mut self, body: &mut SetExpr) {
+        match body {
+            SetExpr::Select(select) => self.select(select),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.set_expr(left);
+                self.set_expr(right);
+            }
+            SetExpr::Query(query) => self.query(query),
+            SetExpr::Values(values) => {
+                for row in &mut values.rows {
+                    for expr in row {
+                        self.expr(expr);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The join conditions of a FROM item, and of the joins it nests, and
+    /// the expressions of a graph table
+    fn table_with_joins(&mut self, table: &mut TableWithJoins) {
+        let joins = core::iter::once(&mut table.relation)
+            .chain(table.joins.iter_mut().map(|join| &mut join.relation));
+        for relation in joins {
+            match relation {
+                TableFactor::NestedJoin {
+                    table_with_joins, ..
+                } => self.table_with_joins(table_with_joins),
+                TableFactor::GraphTable {
+                    selection, columns, ..
+                } => {
+                    if let Some(selection) = selection {
+                        self.expr(selection);
+                    }
+                    for column in columns {
+                        if let SelectItem::ExprWithAlias { expr, .. } = column {
+                            self.expr(expr);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for join in &mut table.joins {
+            if let JoinOperator::Join(JoinConstraint::On(expr))
+            | JoinOperator::Inner(JoinConstraint::On(expr))
+            | JoinOperator::Left(JoinConstraint::On(expr))
+            | JoinOperator::LeftOuter(JoinConstraint::On(expr)) = &mut join.join_operator
+            {
+                self.expr(expr);
+            }
+        }
+    }
+
+    fn select(&mut self, select: &mut Select) {
+        for item in &mut select.projection {
+            if let SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } = item {
+                self.expr(expr);
+            }
+        }
+        for table in &mut select.from {
+            self.table_with_joins(table);
+        }
+        if let Some(selection) = &mut select.selection {
+            self.expr(selection);
+        }
+        if let GroupByExpr::Expressions(exprs, _) = &mut select.group_by {
+            for expr in exprs {
+                self.expr(expr);
+            }
+        }
+        if let Some(having) = &mut select.having {
+            self.expr(having);
+        }
+    }
+
+    fn expr(&mut self, expr: &mut Expr) {
+        *expr = self.rewrite(expr);
+    }
+
+    fn rewrite(&mut self, expr: &Expr) -> Expr {
+        if let Expr::Value(ValueWithSpan {
+            value: Value::Placeholder(placeholder),
+            ..
+        }) = expr
+        {
+            if let Some(name) = placeholder.strip_prefix('$') {
+                return Expr::value(Value::Placeholder(self.placeholder(name)));
+            }
+        }
+        map_subexpressions(expr, &mut |sub_expr| self.rewrite(sub_expr))
+    }
+
+    fn placeholder(&mut self, name: &str) -> String {
+        let shared = self.style != ParamStyle::Question;
+        if let Some(binding) = self
+            .bindings
+            .iter_mut()
+            .find(|binding| shared && binding.name == name)
+        {
+            binding.count += 1;
+            return binding.placeholder.clone();
+        }
+
+        let position = self.bindings.len() + 1;
+        let (placeholder, index) = match self.style {
+            ParamStyle::Inline => (format!("${name}"), None),
+            ParamStyle::Question => ("?".to_string(), Some(position)),
+            ParamStyle::Numbered => (format!("${position}"), Some(position)),
+        };
+        self.bindings.push(ParamBinding {
+            name: name.to_string(),
+            placeholder: placeholder.clone(),
+            index,
+            count: 1,
+        });
+        placeholder
+    }
+}
@@ -0,0 +1,423 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Graph plan: the intermediate representation between a parsed Cypher
+//! statement and the generated SQL.
+//!
+//! Building a [`GraphPlan`] resolves every pattern element to a binding,
+//! merging repeated occurrences of the same variable and folding the label
+//! predicates of the `WHERE` clause into the variables they constrain, so the
+//! SQL generator can pick the most specific table for each of them.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::ast::cypher::*;
+use crate::ast::*;
+
+use super::cypher_to_sql::TransformConfig;
+use super::quoting::required_quote;
+
+/// The kind of graph element a binding refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    Node,
+    Relationship,
+}
+
+/// A node or relationship bound by the patterns of a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    /// The Cypher variable, or `None` for an anonymous pattern element
+    pub variable: Option<Ident>,
+    pub kind: BindingKind,
+    /// Node labels or relationship types, from the patterns and, for nodes,
+    /// from top-level `WHERE` label predicates
+    pub labels: Vec<Ident>,
+    /// The table the element is read from
+    pub table: String,
+    /// The quote character `table` is written with when it cannot be written
+    /// bare, e.g. for the label table of `Order`, a reserved word
+    pub table_quote: Option<char>,
+    /// Whether `table` is the table of the binding's single label, making
+    /// label conditions on it redundant
+    pub label_table: bool,
+    /// The alias of the binding's table: its variable, or a generated name
+    /// for an anonymous element whose table is read more than once
+    pub alias: Option<Ident>,
+    /// Whether the binding is an anonymous node without labels or properties
+    /// at the end of a path, whose table is not joined since the endpoint
+    /// column of its relationship already holds a node, see
+    /// [`TransformConfig::prune_anonymous_nodes`]
+    pub pruned: bool,
+    /// Whether the binding is added by an OPTIONAL MATCH, and so is null on
+    /// the rows where the clause does not match
+    pub optional: bool,
+}
+
+impl Binding {
+    /// The name the binding's table is referenced by in the generated SQL
+    pub fn table_ref(&self) -> Ident {
+        self.alias.clone().unwrap_or_else(|| self.table_name())
+    }
+
+    /// The name of the binding's table, quoted as it needs to be
+    pub fn table_name(&self) -> Ident {
+        Ident {
+            quote_style: self.table_quote,
+            ..Ident::new(&self.table)
+        }
+    }
+
+    /// The binding's table followed by its labels or types, e.g.
+    /// `nodes(Person:Admin)` or `relationships(KNOWS|LIKES)`
+    pub fn table_description(&self) -> String {
+        format!("{}({})", self.table, self.label_list())
+    }
+
+    /// The binding written as a Cypher pattern element, e.g. `(a:Person)` or
+    /// `[:KNOWS]`
+    pub fn pattern(&self) -> String {
+        let variable = self
+            .variable
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let labels = if self.labels.is_empty() {
+            String::new()
+        } else {
+            format!(":{}", self.label_list())
+        };
+        match self.kind {
+            BindingKind::Node => format!("({variable}{labels})"),
+            BindingKind::Relationship => format!("[{variable}{labels}]"),
+        }
+    }
+
+    /// The labels of a node, all of which it has, or the types of a
+    /// relationship, any of which it has
+    fn label_list(&self) -> String {
+        let separator = match self.kind {
+            BindingKind::Node => ":",
+            BindingKind::Relationship => "|",
+        };
+        self.labels
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+/// A pattern as a chain of bindings, where `relationships[i]` connects
+/// `nodes[i]` to `nodes[i + 1]`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanPath {
+    /// The variable of a named path, e.g. `p` in `p = (a)-[:KNOWS]->(b)`
+    pub variable: Option<Ident>,
+    /// Indexes of the node bindings, in pattern order
+    pub nodes: Vec<usize>,
+    /// Indexes of the relationship bindings and the direction of each
+    pub relationships: Vec<(usize, RelationshipDirection)>,
+    /// Whether a relationship of the path has a variable length, which is
+    /// matched as a single hop
+    pub variable_length: bool,
+}
+
+/// The resolved variable bindings of a `MATCH`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphPlan {
+    /// Bindings in the order their first occurrence appears in the patterns
+    pub bindings: Vec<Binding>,
+    /// The patterns, one path each
+    pub paths: Vec<PlanPath>,
+    /// The `WHERE` clause without the label predicates folded into bindings
+    pub residual_where: Option<Expr>,
+}
+
+impl GraphPlan {
+    /// Resolve the bindings of `patterns`, constrained by `where_clause`
+    pub fn build(
+        patterns: &[Pattern],
+        where_clause: &Option<Expr>,
+        config: &TransformConfig,
+    ) -> Self {
+        let mut plan = GraphPlan::default();
+        // The bindings of pattern elements with a property map
+        let mut with_properties = vec![];
+
+        for pattern in patterns {
+            let mut path = PlanPath {
+                variable: pattern.variable.clone(),
+                ..PlanPath::default()
+            };
+            for element in &pattern.elements {
+                if let PatternElement::Relationship {
+                    length: Some(_), ..
+                } = element
+                {
+                    path.variable_length = true;
+                }
+                let (variable, names) = (element.variable(), element.labels_or_types());
+                let binding = match element.direction() {
+                    None => {
+                        let binding = plan.bind(variable, BindingKind::Node, names);
+                        path.nodes.push(binding);
+                        binding
+                    }
+                    Some(direction) => {
+                        let binding = plan.bind(variable, BindingKind::Relationship, names);
+                        path.relationships
+                            .push((binding, config.direction(direction)));
+                        binding
+                    }
+                };
+                if element.properties().is_some() {
+                    with_properties.push(binding);
+                }
+            }
+            plan.paths.push(path);
+        }
+
+        // Only label predicates that are top-level conjuncts constrain the
+        // variable for every row; anything under OR or NOT stays in the WHERE
+        let mut residual = vec![];
+        if let Some(expr) = where_clause {
+            for conjunct in split_conjunction(expr) {
+                if let Some((variable, labels)) = as_label_predicate(conjunct) {
+                    if let Some(binding) = plan.node_binding_mut(variable) {
+                        for label in labels {
+                            if !binding.labels.contains(label) {
+                                binding.labels.push(label.clone());
+                            }
+                        }
+                        continue;
+                    }
+                }
+                residual.push(conjunct.clone());
+            }
+        }
+        plan.residual_where = residual.into_iter().reduce(|left, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::And,
+            right: Box::new(right),
+        });
+
+        for binding in &mut plan.bindings {
+            match binding.kind {
+                BindingKind::Node => {
+                    // Ambiguous and multi-label variables keep the generic
+                    // table, in which a multi-label one matches no row and is
+                    // warned about
+                    if config.use_label_tables && binding.labels.len() == 1 {
+                        binding.table = binding.labels[0].value.clone();
+                        binding.label_table = true;
+                    } else {
+                        binding.table = config.node_table.clone();
+                    }
+                }
+                BindingKind::Relationship => {
+                    binding.table = config.relationship_table.clone();
+                }
+            }
+            binding.table_quote = required_quote(&binding.table, config.target);
+        }
+
+        // Nodes compared with the others of their pattern need their rows
+        if config.prune_anonymous_nodes && !config.distinct_nodes {
+            plan.prune_anonymous_nodes(&with_properties);
+        }
+
+        // Anonymous elements sharing a table need aliases to tell them apart
+        let anonymous = |other: &Binding, table: &str| {
+            other.variable.is_none() && !other.pruned && other.table == table
+        };
+        for i in 0..plan.bindings.len() {
+            let binding = &plan.bindings[i];
+            let shared = plan
+                .bindings
+                .iter()
+                .filter(|other| anonymous(other, &binding.table))
+                .count()
+                > 1;
+            if binding.variable.is_none() && !binding.pruned && shared {
+                let n = plan.bindings[..i]
+                    .iter()
+                    .filter(|other| anonymous(other, &binding.table))
+                    .count()
+                    + 1;
+                let alias = format!("{}_{n}", binding.table);
+                let alias = Ident {
+                    quote_style: required_quote(&alias, config.target),
+                    ..Ident::new(alias)
+                };
+                plan.bindings[i].alias = Some(alias);
+            }
+        }
+
+        plan
+    }
+
+    /// Find the binding of a named variable
+    pub fn binding(&self, variable: &str) -> Option<&Binding> {
+        self.bindings.iter().find(|binding| {
+            binding
+                .variable
+                .as_ref()
+                .is_some_and(|var| var.value == variable)
+        })
+    }
+
+    /// Find the path bound to a named path variable
+    pub fn path(&self, variable: &str) -> Option<&PlanPath> {
+        self.paths.iter().find(|path| {
+            path.variable
+                .as_ref()
+                .is_some_and(|var| var.value == variable)
+        })
+    }
+
+    /// Mark the anonymous nodes at the ends of paths that need no join: those
+    /// without labels or properties, of an unnamed path, whose relationship
+    /// has a direction and occurs nowhere else, so that its endpoint column
+    /// is all the query needs of them
+    fn prune_anonymous_nodes(&mut self, with_properties: &[usize]) {
+        let mut pruned = vec![];
+        for path in self.paths.iter().filter(|path| path.variable.is_none()) {
+            let (Some(first), Some(last)) = (path.relationships.first(), path.relationships.last())
+            else {
+                continue;
+            };
+            let ends = [(path.nodes.first(), first), (path.nodes.last(), last)];
+            for (node, (rel, direction)) in ends {
+                let Some(&node) = node else {
+                    continue;
+                };
+                let binding = &self.bindings[node];
+                let occurrences = self
+                    .paths
+                    .iter()
+                    .flat_map(|path| &path.relationships)
+                    .filter(|(other, _)| other == rel)
+                    .count();
+                if binding.variable.is_none()
+                    && binding.labels.is_empty()
+                    && !with_properties.contains(&node)
+                    && matches!(
+                        direction,
+                        RelationshipDirection::Left | RelationshipDirection::Right
+                    )
+                    && occurrences == 1
+                {
+                    pruned.push(node);
+                }
+            }
+        }
+        for node in pruned {
+            self.bindings[node].pruned = true;
+        }
+    }
+
+    fn node_binding_mut(&mut self, variable: &Ident) -> Option<&mut Binding> {
+        self.bindings.iter_mut().find(|binding| {
+            binding.kind == BindingKind::Node
+                && binding
+                    .variable
+                    .as_ref()
+                    .is_some_and(|var| var.value == variable.value)
+        })
+    }
+
+    /// Bind a pattern element, returning the index of its binding
+    fn bind(&mut self, variable: Option<&Ident>, kind: BindingKind, labels: &[Ident]) -> usize {
+        if let Some(var) = variable {
+            if let Some(i) = self.bindings.iter().position(|binding| {
+                binding
+                    .variable
+                    .as_ref()
+                    .is_some_and(|v| v.value == var.value)
+            }) {
+                let existing = &mut self.bindings[i];
+                for label in labels {
+                    if !existing.labels.contains(label) {
+                        existing.labels.push(label.clone());
+                    }
+                }
+                return i;
+            }
+        }
+        self.bindings.push(Binding {
+            variable: variable.cloned(),
+            kind,
+            labels: labels.to_vec(),
+            table: String::new(),
+            table_quote: None,
+            label_table: false,
+            alias: variable.cloned(),
+            pruned: false,
+            optional: false,
+        });
+        self.bindings.len() - 1
+    }
+}
+
+/// Split an expression into its top-level `AND` conjuncts
+pub(crate) fn split_conjunction(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            let mut conjuncts = split_conjunction(left);
+            conjuncts.extend(split_conjunction(right));
+            conjuncts
+        }
+        Expr::Nested(inner)
+            if matches!(
+                inner.as_ref(),
+                Expr::BinaryOp {
+                    op: BinaryOperator::And,
+                    ..
+                }
+            ) =>
+        {
+            split_conjunction(inner)
+        }
+        _ => vec![expr],
+    }
+}
+
+/// Match a label predicate on a plain variable, e.g. `n:Person`
+pub(crate) fn as_label_predicate(expr: &Expr) -> Option<(&Ident, &[Ident])> {
+    match expr {
+        Expr::Cypher(cypher_expr) => match cypher_expr.as_ref() {
+            CypherExpr::LabelPredicate {
+                expr: Expr::Identifier(variable),
+                labels,
+            } => Some((variable, labels)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
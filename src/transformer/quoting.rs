@@ -0,0 +1,486 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Quoting of the table names, aliases and columns in the generated SQL
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
+use crate::ast::*;
+
+use super::cypher_to_sql::{map_subexpressions, SqlTarget};
+
+/// Which identifiers of the generated SQL are quoted, with the quote
+/// character of the target: a backtick for MySQL and a double quote for the
+/// other databases
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdentifierQuoting {
+    /// Identifiers are written bare, or with the backticks of a quoted Cypher
+    /// variable, except for table names that cannot be, e.g. the label table
+    /// `Order`, a reserved word
+    #[default]
+    None,
+    /// Every identifier is quoted
+    Always,
+    /// Identifiers the database would otherwise read differently are quoted:
+    /// reserved words, names with characters other than letters, digits and
+    /// underscores or starting with a digit, and, on PostgreSQL and
+    /// DataFusion, which fold bare identifiers to lower case, names with upper
+    /// case letters
+    WhenNeeded,
+}
+
+/// Words that cannot name a table, alias or column without quotes: those
+/// PostgreSQL reserves, and the common ones MySQL and SQLite add, sorted
+const RESERVED_WORDS: &[&str] = &[
+    "ALL",
+    "ALTER",
+    "ANALYSE",
+    "ANALYZE",
+    "AND",
+    "ANY",
+    "ARRAY",
+    "AS",
+    "ASC",
+    "ASYMMETRIC",
+    "AUTHORIZATION",
+    "BETWEEN",
+    "BINARY",
+    "BOTH",
+    "BY",
+    "CASE",
+    "CAST",
+    "CHECK",
+    "COLLATE",
+    "COLLATION",
+    "COLUMN",
+    "CONCURRENTLY",
+    "CONSTRAINT",
+    "CREATE",
+    "CROSS",
+    "CURRENT_CATALOG",
+    "CURRENT_DATE",
+    "CURRENT_ROLE",
+    "CURRENT_SCHEMA",
+    "CURRENT_TIME",
+    "CURRENT_TIMESTAMP",
+    "CURRENT_USER",
+    "DATABASE",
+    "DEFAULT",
+    "DEFERRABLE",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DO",
+    "DROP",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "EXISTS",
+    "FALSE",
+    "FETCH",
+    "FOR",
+    "FOREIGN",
+    "FREEZE",
+    "FROM",
+    "FULL",
+    "GRANT",
+    "GROUP",
+    "GROUPS",
+    "HAVING",
+    "ILIKE",
+    "IN",
+    "INDEX",
+    "INITIALLY",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTERVAL",
+    "INTO",
+    "IS",
+    "ISNULL",
+    "JOIN",
+    "KEY",
+    "LATERAL",
+    "LEADING",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "LOCALTIME",
+    "LOCALTIMESTAMP",
+    "NATURAL",
+    "NOT",
+    "NOTNULL",
+    "NULL",
+    "OFFSET",
+    "ON",
+    "ONLY",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OVERLAPS",
+    "PLACING",
+    "PRIMARY",
+    "RANGE",
+    "RANK",
+    "REFERENCES",
+    "REPLACE",
+    "RETURNING",
+    "RIGHT",
+    "ROW",
+    "ROWS",
+    "SELECT",
+    "SESSION_USER",
+    "SET",
+    "SIMILAR",
+    "SOME",
+    "SYMMETRIC",
+    "TABLE",
+    "TABLESAMPLE",
+    "THEN",
+    "TO",
+    "TRAILING",
+    "TRUE",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USER",
+    "USING",
+    "VALUES",
+    "VARIADIC",
+    "VERBOSE",
+    "WHEN",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+];
+
+/// Whether `name` is made of letters, digits and underscores, not starting
+/// with a digit, and is not a reserved word, with only ASCII letters unless
+/// `unicode`
+fn is_bare_name(name: &str, unicode: bool) -> bool {
+    let letter = |c: char| c.is_ascii_alphabetic() || (unicode && c.is_alphabetic());
+    let mut chars = name.chars();
+    let plain = chars.next().is_some_and(|c| letter(c) || c == '_')
+        && chars.all(|c| letter(c) || c.is_ascii_digit() || c == '_');
+    plain
+        && RESERVED_WORDS
+            .binary_search(&name.to_uppercase().as_str())
+            .is_err()
+}
+
+/// The quote character of an identifier named `name`, if it is quoted
+pub(crate) fn quote_style(
+    name: &str,
+    quoting: IdentifierQuoting,
+    target: SqlTarget,
+) -> Option<char> {
+    let needed = || {
+        let folded = matches!(target, SqlTarget::Postgres | SqlTarget::DataFusion)
+            && name.chars().any(|c| c.is_ascii_uppercase());
+        !is_bare_name(name, false) || folded
+    };
+    match quoting {
+        IdentifierQuoting::None => None,
+        IdentifierQuoting::Always => Some(target.identifier_quote()),
+        IdentifierQuoting::WhenNeeded => needed().then(|| target.identifier_quote()),
+    }
+}
+
+/// The quote character of a table name that cannot be written bare under
+/// any [`IdentifierQuoting`], e.g. `Order` or `E-Commerce` as the label table
+/// of its label
+///
+/// Every target reads letters beyond ASCII in a bare name, e.g. `Город`.
+pub(crate) fn required_quote(name: &str, target: SqlTarget) -> Option<char> {
+    (!is_bare_name(name, true)).then(|| target.identifier_quote())
+}
+
+/// The identifier of the table `name`, quoted if it [must be](required_quote)
+pub(crate) fn table_ident(name: &str, target: SqlTarget) -> Ident {
+    Ident {
+        quote_style: required_quote(name, target),
+        ..Ident::new(name)
+    }
+}
+
+/// `name` quoted as [`quote_style`] decides, or as it [must be](required_quote),
+/// e.g. for DDL
+pub(crate) fn quote_name(name: &str, quoting: IdentifierQuoting, target: SqlTarget) -> String {
+    match quote_style(name, quoting, target).or_else(|| required_quote(name, target)) {
+        Some(quote) => Ident::with_quote(quote, name).to_string(),
+        None => name.to_string(),
+    }
+}
+
+/// Quote the identifiers of `statement` as `quoting` asks, leaving the names
+/// of functions, including table functions, as they are
+pub(crate) fn quote_identifiers(
+    statement: &mut Statement,
+    quoting: IdentifierQuoting,
+    target: SqlTarget,
+) {
+    if quoting == IdentifierQuoting::None {
+        return;
+    }
+    let quoter = IdentQuoter { quoting, target };
+    quoter.statement(statement);
+}
+
+struct IdentQuoter {
+    quoting: IdentifierQuoting,
+    target: SqlTarget,
+}
+
+impl IdentQuoter {
+    fn statement(&self, statement: &mut Statement) {
+        match statement {
+            Statement::Query(query) => self.query(query),
+            Statement::Insert(insert) => {
+                if let TableObject::TableName(name) = &mut insert.table {
+                    self.object_name(name);
+                }
+                for column in &mut insert.columns {
+                    self.ident(column);
+                }
+                if let Some(source) = &mut insert.source {
+                    self.query(source);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn query(&self, query: &mut Query) {
+        if let Some(with) = &mut query.with {
+            for cte in &mut with.cte_tables {
+                self.ident(&mut cte.alias.name);
+                self.query(&mut cte.query);
+            }
+        }
+        self.set_expr(&mut query.body);
+        if let Some(OrderBy {
+            kind: OrderByKind::Expressions(exprs),
+            ..
+        }) = &mut query.order_by
+        {
+            for order_by_expr in exprs {
+                self.expr(&mut order_by_expr.expr);
+            }
+        }
+        if let Some(LimitClause::LimitOffset { limit, offset, .. }) = &mut query.limit_clause {
+            if let Some(limit) = limit {
+                self.expr(limit);
+            }
+            if let Some(offset) = offset {
+                self.expr(&mut offset.value);
+            }
+        }
+        if let Some(Fetch {
+            quantity: Some(quantity),
+            ..
+        }) = &mut query.fetch
+        {
+            self.expr(quantity);
+        }
+    }
+
+    fn set_expr(&self, body: &mut SetExpr) {
+        match body {
+            SetExpr::Select(select) => self.select(select),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.set_expr(left);
+                self.set_expr(right);
+            }
+            SetExpr::Query(query) => self.query(query),
+            SetExpr::Insert(statement) => self.statement(statement),
+            SetExpr::Values(values) => {
+                for row in &mut values.rows {
+                    for expr in row {
+                        self.expr(expr);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn select(&self, select: &mut Select) {
+        for item in &mut select.projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) => self.expr(expr),
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    self.expr(expr);
+                    self.ident(alias);
+                }
+                SelectItem::QualifiedWildcard(
+                    SelectItemQualifiedWildcardKind::ObjectName(name),
+                    _,
+                ) => self.object_name(name),
+                _ => {}
+            }
+        }
+        for table in &mut select.from {
+            self.table_with_joins(table);
+        }
+        if let Some(selection) = &mut select.selection {
+            self.expr(selection);
+        }
+        if let GroupByExpr::Expressions(exprs, _) = &mut select.group_by {
+            for expr in exprs {
+                self.expr(expr);
+            }
+        }
+        if let Some(having) = &mut select.having {
+            self.expr(having);
+        }
+    }
+
+    fn table_with_joins(&self, table: &mut TableWithJoins) {
+        self.table_factor(&mut table.relation);
+        for join in &mut table.joins {
+            self.table_factor(&mut join.relation);
+            if let JoinOperator::Join(JoinConstraint::On(expr))
+            | JoinOperator::Inner(JoinConstraint::On(expr))
+            | JoinOperator::Left(JoinConstraint::On(expr))
+            | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+            | JoinOperator::CrossJoin(JoinConstraint::On(expr)) = &mut join.join_operator
+            {
+                self.expr(expr);
+            }
+        }
+    }
+
+    fn table_factor(&self, relation: &mut TableFactor) {
+        match relation {
+            TableFactor::Table {
+                name, alias, args, ..
+            } => {
+                if args.is_none() {
+                    self.object_name(name);
+                }
+                if let Some(alias) = alias {
+                    self.ident(&mut alias.name);
+                }
+            }
+            TableFactor::Derived {
+                subquery, alias, ..
+            } => {
+                self.query(subquery);
+                if let Some(alias) = alias {
+                    self.ident(&mut alias.name);
+                }
+            }
+            TableFactor::NestedJoin {
+                table_with_joins, ..
+            } => self.table_with_joins(table_with_joins),
+            TableFactor::GraphTable {
+                graph,
+                paths,
+                selection,
+                columns,
+                alias,
+            } => {
+                self.object_name(graph);
+                for element in paths.iter_mut().flatten() {
+                    let (GraphPatternElement::Vertex { variable, labels }
+                    | GraphPatternElement::Edge {
+                        variable, labels, ..
+                    }) = element;
+                    variable
+                        .iter_mut()
+                        .chain(labels)
+                        .for_each(|ident| self.ident(ident));
+                }
+                if let Some(selection) = selection {
+                    self.expr(selection);
+                }
+                for column in columns {
+                    if let SelectItem::ExprWithAlias { expr, alias } = column {
+                        self.expr(expr);
+                        self.ident(alias);
+                    }
+                }
+                if let Some(alias) = alias {
+                    self.ident(&mut alias.name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn expr(&self, expr: &mut Expr) {
+        *expr = self.rewrite(expr);
+    }
+
+    fn rewrite(&self, expr: &Expr) -> Expr {
+        match expr {
+            Expr::Identifier(ident) => {
+                let mut ident = ident.clone();
+                self.ident(&mut ident);
+                Expr::Identifier(ident)
+            }
+            Expr::CompoundIdentifier(idents) => {
+                let mut idents = idents.clone();
+                idents.iter_mut().for_each(|ident| self.ident(ident));
+                Expr::CompoundIdentifier(idents)
+            }
+            Expr::Exists { subquery, negated } => {
+                let mut subquery = subquery.clone();
+                self.query(&mut subquery);
+                Expr::Exists {
+                    subquery,
+                    negated: *negated,
+                }
+            }
+            Expr::Subquery(subquery) => {
+                let mut subquery = subquery.clone();
+                self.query(&mut subquery);
+                Expr::Subquery(subquery)
+            }
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => {
+                let mut subquery = subquery.clone();
+                self.query(&mut subquery);
+                Expr::InSubquery {
+                    expr: Box::new(self.rewrite(expr)),
+                    subquery,
+                    negated: *negated,
+                }
+            }
+            _ => map_subexpressions(expr, &mut |sub_expr| self.rewrite(sub_expr)),
+        }
+    }
+
+    fn object_name(&self, name: &mut ObjectName) {
+        for part in &mut name.0 {
+            if let ObjectNamePart::Identifier(ident) = part {
+                self.ident(ident);
+            }
+        }
+    }
+
+    fn ident(&self, ident: &mut Ident) {
+        ident.quote_style = quote_style(&ident.value, self.quoting, self.target);
+    }
+}
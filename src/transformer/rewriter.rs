@@ -0,0 +1,160 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Translation of Cypher function calls by user supplied rewriters
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::ast::*;
+
+use super::cypher_to_sql::{function_call, CypherToSqlTransformer, SqlTarget};
+use super::plan::{Binding, GraphPlan};
+
+/// Translates calls of functions the transformer does not know, such as
+/// user defined functions, or translates known ones differently
+///
+/// The rewriters of [`TransformConfig::function_rewriters`] are asked in
+/// order about every function call before the built-in translations, and
+/// the first to return an expression decides the SQL of the call. Calls no
+/// rewriter takes are translated as usual, and passed through to SQL as
+/// they are when the transformer does not know them either.
+///
+/// A rewriter may be asked more than once about the same call, so it should
+/// answer the same each time.
+///
+/// ```
+/// # use sqlparser::ast::Expr;
+/// # use sqlparser::dialect::CypherDialect;
+/// # use sqlparser::parser::Parser;
+/// # use sqlparser::transformer::*;
+/// # use std::sync::Arc;
+/// /// `similarity(a, b)` as the `vec_cosine` function of the database
+/// #[derive(Debug)]
+/// struct Similarity;
+///
+/// impl FunctionRewriter for Similarity {
+///     fn rewrite(&self, name: &str, args: &[Expr], ctx: &RewriteContext) -> Option<Expr> {
+///         if !name.eq_ignore_ascii_case("similarity") {
+///             return None;
+///         }
+///         let args = args.iter().map(|arg| ctx.rewrite(arg)).collect();
+///         Some(ctx.function("vec_cosine", args))
+///     }
+/// }
+///
+/// let config = TransformConfig {
+///     use_label_tables: false,
+///     function_rewriters: vec![Arc::new(Similarity)],
+///     ..Default::default()
+/// };
+/// let cypher = "MATCH (a:Doc), (b:Doc) RETURN similarity(a.embedding, b.embedding) AS s";
+/// let statement = Parser::new(&CypherDialect)
+///     .try_with_sql(cypher)?
+///     .parse_cypher_statement()?;
+/// let sql = CypherToSqlTransformer::with_config(config).to_sql(&statement)?;
+/// assert_eq!(
+///     sql,
+///     "SELECT vec_cosine(json_extract(a.properties, '$.embedding'), \
+///      json_extract(b.properties, '$.embedding')) AS s \
+///      FROM nodes AS a, nodes AS b WHERE a.label = 'Doc' AND b.label = 'Doc'"
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// [`TransformConfig::function_rewriters`]: super::TransformConfig::function_rewriters
+pub trait FunctionRewriter: fmt::Debug + Send + Sync {
+    /// The SQL of a call of the function `name`, as written in the query,
+    /// with the Cypher expressions `args`, or `None` to leave the call to
+    /// the other rewriters and the built-in translations
+    fn rewrite(&self, name: &str, args: &[Expr], ctx: &RewriteContext) -> Option<Expr>;
+}
+
+/// What a [`FunctionRewriter`] knows about the query of the call it
+/// rewrites, and the translations it can reuse
+pub struct RewriteContext<'a> {
+    transformer: &'a CypherToSqlTransformer,
+    plan: &'a GraphPlan,
+}
+
+impl<'a> RewriteContext<'a> {
+    pub(crate) fn new(transformer: &'a CypherToSqlTransformer, plan: &'a GraphPlan) -> Self {
+        RewriteContext { transformer, plan }
+    }
+
+    /// The database the SQL is generated for
+    pub fn target(&self) -> SqlTarget {
+        self.transformer.config.target
+    }
+
+    /// The pattern elements the query binds, which are empty outside of a
+    /// MATCH
+    pub fn plan(&self) -> &GraphPlan {
+        self.plan
+    }
+
+    /// The binding of the Cypher variable `variable`, if the query binds it
+    pub fn binding(&self, variable: &str) -> Option<&Binding> {
+        self.plan.binding(variable)
+    }
+
+    /// The SQL of the Cypher expression `expr`, e.g. of an argument, with its
+    /// property accesses read from their columns or JSON and its function
+    /// calls translated
+    pub fn rewrite(&self, expr: &Expr) -> Expr {
+        self.transformer.rewrite_expr(expr, self.plan)
+    }
+
+    /// The SQL of the property `property` of the element bound to
+    /// `variable`, e.g. `json_extract(n.properties, '$.name')` for `n.name`
+    /// on the generic node table
+    pub fn property(&self, variable: &str, property: &str) -> Expr {
+        self.rewrite(&Expr::CompoundIdentifier(
+            [variable, property].into_iter().map(Ident::new).collect(),
+        ))
+    }
+
+    /// A call of the SQL function `name` with `args`
+    pub fn function(&self, name: &str, args: Vec<Expr>) -> Expr {
+        function_call(name, args)
+    }
+}
+
+/// The name and arguments of a call of a function with a plain list of
+/// arguments, e.g. not `count(*)` or `count(DISTINCT n)`
+pub(crate) fn function_parts(expr: &Expr) -> Option<(String, Vec<Expr>)> {
+    let Expr::Function(function) = expr else {
+        return None;
+    };
+    let args = match &function.args {
+        FunctionArguments::List(list) if list.duplicate_treatment.is_none() => list
+            .args
+            .iter()
+            .map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) => Some(arg.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?,
+        FunctionArguments::None => Vec::new(),
+        _ => return None,
+    };
+    Some((function.name.to_string(), args))
+}
@@ -0,0 +1,565 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The tables, columns and indexes the generated SQL assumes
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::ast::cypher::*;
+use crate::ast::*;
+
+use super::cypher_to_sql::{
+    carried_nodes, continued_patterns, merge_reading_clauses, IdType, SqlTarget, TransformConfig,
+};
+use super::plan::GraphPlan;
+use super::quoting::quote_name;
+
+/// The schema the SQL of a statement reads and writes, as computed by
+/// [`CypherToSqlTransformer::required_schema`]
+///
+/// [`CypherToSqlTransformer::required_schema`]: super::CypherToSqlTransformer::required_schema
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SchemaRequirements {
+    /// The tables, in the order the statement first uses them
+    pub tables: Vec<TableRequirement>,
+    /// The indexes on the columns the SQL filters and joins the generic
+    /// tables on
+    pub indexes: Vec<IndexRequirement>,
+    /// The statements creating the tables and indexes on the configured
+    /// target, without a terminating `;`
+    pub ddl: Vec<String>,
+}
+
+/// A table in [`SchemaRequirements`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TableRequirement {
+    pub name: String,
+    /// Whether the table holds the nodes of a single label, with a column
+    /// per property, rather than being one of the generic tables
+    pub label_table: bool,
+    pub columns: Vec<ColumnRequirement>,
+}
+
+/// A column of a [`TableRequirement`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ColumnRequirement {
+    pub name: String,
+    /// The type of the column on the target, e.g. `JSONB`
+    ///
+    /// The type of a property column is that of the literals the statement
+    /// compares it with or stores in it, or a string type when it has none.
+    pub data_type: String,
+    /// Whether the column is the node id, which the database assigns unless
+    /// the ids are [`IdType::Uuid`]
+    pub primary_key: bool,
+}
+
+/// An index in [`SchemaRequirements`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IndexRequirement {
+    /// The index name, `<table>_<column>`
+    pub name: String,
+    pub table: String,
+    pub column: String,
+}
+
+/// The type a property column is given from the literals it is used with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PropertyType {
+    Integer,
+    Float,
+    Boolean,
+    String,
+}
+
+impl PropertyType {
+    /// The type of a literal value, or `None` for other expressions
+    fn of(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::Value(ValueWithSpan { value, .. }) => match value {
+                Value::Number(number, _) if number.to_string().contains(['.', 'e', 'E']) => {
+                    Some(PropertyType::Float)
+                }
+                Value::Number(..) => Some(PropertyType::Integer),
+                Value::Boolean(_) => Some(PropertyType::Boolean),
+                Value::SingleQuotedString(_) | Value::DoubleQuotedString(_) => {
+                    Some(PropertyType::String)
+                }
+                _ => None,
+            },
+            Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            } => PropertyType::of(expr),
+            _ => None,
+        }
+    }
+
+    /// The type holding both `self` and `other`: a float for numbers, and a
+    /// string for anything else that differs
+    fn union(self, other: Self) -> Self {
+        match (self, other) {
+            _ if self == other => self,
+            (PropertyType::Integer, PropertyType::Float)
+            | (PropertyType::Float, PropertyType::Integer) => PropertyType::Float,
+            _ => PropertyType::String,
+        }
+    }
+
+    fn data_type(self, target: SqlTarget) -> &'static str {
+        match (self, target) {
+            (PropertyType::Integer, SqlTarget::Sqlite) => "INTEGER",
+            (PropertyType::Integer, _) => "BIGINT",
+            (PropertyType::Float, SqlTarget::Sqlite) => "REAL",
+            (PropertyType::Float, SqlTarget::Postgres) => "DOUBLE PRECISION",
+            (PropertyType::Float, SqlTarget::MySql | SqlTarget::DuckDb | SqlTarget::DataFusion) => {
+                "DOUBLE"
+            }
+            (PropertyType::Boolean, _) => "BOOLEAN",
+            (PropertyType::String, SqlTarget::DuckDb | SqlTarget::DataFusion) => "VARCHAR",
+            (PropertyType::String, _) => "TEXT",
+        }
+    }
+}
+
+/// The schema the SQL of `statement` assumes under `config`
+pub(crate) fn required_schema(
+    statement: &CypherStatement,
+    config: &TransformConfig,
+) -> SchemaRequirements {
+    let mut collector = SchemaCollector {
+        config,
+        tables: vec![],
+        property_types: BTreeMap::new(),
+    };
+    collector.statement(statement);
+
+    let summary = statement.access_summary();
+    let properties = summary
+        .properties_read
+        .iter()
+        .chain(&summary.properties_written);
+    let mut requirements = SchemaRequirements::default();
+    for (name, label_table) in &collector.tables {
+        let id = (config.id_column.as_str(), id_data_type(config));
+        let relationships = !label_table && *name == config.relationship_table;
+        let columns = if *label_table {
+            vec![id]
+        } else if relationships {
+            // The relationships compared to keep those of a MATCH apart
+            let rel_id = config
+                .relationship_id_column
+                .as_deref()
+                .map(|column| (column, id_data_type(config)));
+            rel_id
+                .into_iter()
+                .chain([
+                    ("from_id", id_data_type(config)),
+                    ("to_id", id_data_type(config)),
+                    ("type", name_data_type(config.target)),
+                    ("properties", json_data_type(config.target)),
+                ])
+                .collect()
+        } else {
+            vec![
+                id,
+                ("label", name_data_type(config.target)),
+                ("properties", json_data_type(config.target)),
+            ]
+        };
+        let mut table = TableRequirement {
+            name: name.clone(),
+            label_table: *label_table,
+            columns: vec![],
+        };
+        for (column, data_type) in columns {
+            let primary_key = if relationships {
+                config.relationship_id_column.as_deref() == Some(column)
+            } else {
+                column == config.id_column
+            };
+            table.columns.push(ColumnRequirement {
+                name: column.to_string(),
+                data_type: data_type.to_string(),
+                primary_key,
+            });
+            // Nodes are filtered on their label, relationships on their type,
+            // and relationships are joined to their endpoints
+            if !label_table && !primary_key && column != "properties" {
+                requirements.indexes.push(IndexRequirement {
+                    name: format!("{name}_{column}"),
+                    table: name.clone(),
+                    column: column.to_string(),
+                });
+            }
+        }
+        if *label_table {
+            for access in properties.clone() {
+                let (AccessName::Named(owner), AccessName::Named(property)) =
+                    (&access.owner, &access.property)
+                else {
+                    continue;
+                };
+                if owner != name || table.columns.iter().any(|c| c.name == *property) {
+                    continue;
+                }
+                let property_type = collector
+                    .property_types
+                    .get(&(name.clone(), property.clone()))
+                    .copied()
+                    .unwrap_or(PropertyType::String);
+                table.columns.push(ColumnRequirement {
+                    name: property.clone(),
+                    data_type: property_type.data_type(config.target).to_string(),
+                    primary_key: false,
+                });
+            }
+        }
+        requirements.tables.push(table);
+    }
+
+    let quote = |name: &str| quote_name(name, config.quoting, config.target);
+    for table in &requirements.tables {
+        let sequence = id_sequence(config, &table.name);
+        if let Some(sequence) = sequence.filter(|_| table.columns.iter().any(|c| c.primary_key)) {
+            requirements.ddl.push(format!("CREATE SEQUENCE {sequence}"));
+        }
+        let columns = table
+            .columns
+            .iter()
+            .map(|column| column_definition(config, &table.name, column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        requirements
+            .ddl
+            .push(format!("CREATE TABLE {} ({columns})", quote(&table.name)));
+    }
+    for index in &requirements.indexes {
+        requirements.ddl.push(format!(
+            "CREATE INDEX {} ON {} ({})",
+            quote(&index.name),
+            quote(&index.table),
+            quote(&index.column)
+        ));
+    }
+    requirements
+}
+
+/// Gathers the tables the patterns of a statement read and write, and the
+/// types of the literals their label table properties are used with
+struct SchemaCollector<'a> {
+    config: &'a TransformConfig,
+    /// Each table with whether it is a label table, in order of first use
+    tables: Vec<(String, bool)>,
+    /// The type of each property of a label table, by table and property
+    property_types: BTreeMap<(String, String), PropertyType>,
+}
+
+impl SchemaCollector<'_> {
+    fn statement(&mut self, statement: &CypherStatement) {
+        // MATCH clauses one after the other read the tables of all their
+        // patterns, as a single MATCH would
+        if let Ok(Some((mut merged, optional_matches))) = merge_reading_clauses(statement) {
+            if let CypherStatement::Match {
+                patterns, r#where, ..
+            } = &mut merged
+            {
+                for clause in optional_matches {
+                    patterns.extend(clause.patterns.iter().cloned());
+                    *r#where = match (r#where.take(), clause.r#where.clone()) {
+                        (Some(left), Some(right)) => Some(Expr::BinaryOp {
+                            left: Box::new(Expr::Nested(Box::new(left))),
+                            op: BinaryOperator::And,
+                            right: Box::new(Expr::Nested(Box::new(right))),
+                        }),
+                        (left, right) => left.or(right),
+                    };
+                }
+            }
+            return self.statement(&merged);
+        }
+
+        let plans = match statement {
+            CypherStatement::Match {
+                patterns,
+                r#where,
+                with: Some(with),
+                next: Some(next),
+                ..
+            } => {
+                // The MATCH after the WITH reads the nodes it passes on from
+                // the same tables
+                let plan = GraphPlan::build(patterns, r#where, self.config);
+                let next_plan = match next.as_ref() {
+                    CypherStatement::Match {
+                        patterns: next_patterns,
+                        r#where: next_where,
+                        ..
+                    } => {
+                        let carried = carried_nodes(with, &plan).unwrap_or_default();
+                        let next_patterns = continued_patterns(&carried, next_patterns);
+                        Some(GraphPlan::build(&next_patterns, next_where, self.config))
+                    }
+                    _ => None,
+                };
+                let mut plans = vec![plan];
+                plans.extend(next_plan);
+                plans
+            }
+            CypherStatement::Match {
+                patterns, r#where, ..
+            }
+            | CypherStatement::Delete {
+                patterns, r#where, ..
+            }
+            | CypherStatement::Set {
+                patterns, r#where, ..
+            } => vec![GraphPlan::build(patterns, r#where, self.config)],
+            CypherStatement::Create {
+                r#match,
+                r#where,
+                patterns,
+                ..
+            } => vec![
+                GraphPlan::build(r#match, r#where, self.config),
+                GraphPlan::build(patterns, &None, self.config),
+            ],
+            CypherStatement::Merge { patterns, .. } => {
+                vec![GraphPlan::build(patterns, &None, self.config)]
+            }
+            CypherStatement::Union { queries, .. } => {
+                for query in queries {
+                    self.statement(query);
+                }
+                return;
+            }
+            CypherStatement::Unwind { query, .. } => return self.statement(query),
+            CypherStatement::Recovered { statement } => return self.statement(statement),
+            CypherStatement::Show { .. } => return,
+        };
+
+        // The label table of each variable bound to one
+        let mut label_tables = BTreeMap::new();
+        for binding in plans
+            .iter()
+            .flat_map(|plan| &plan.bindings)
+            .filter(|binding| !binding.pruned)
+        {
+            let table = (binding.table.clone(), binding.label_table);
+            if !self.tables.contains(&table) {
+                self.tables.push(table);
+            }
+            if let (Some(variable), true) = (&binding.variable, binding.label_table) {
+                label_tables.insert(variable.value.clone(), binding.table.clone());
+            }
+        }
+
+        for element in statement_patterns(statement)
+            .into_iter()
+            .flat_map(|pattern| &pattern.elements)
+        {
+            let Some(Expr::Dictionary(fields)) = element.properties() else {
+                continue;
+            };
+            let table = match (element.variable(), element.labels_or_types()) {
+                (Some(variable), _) => label_tables.get(&variable.value).cloned(),
+                (None, [label]) if element.is_node() && self.config.use_label_tables => {
+                    Some(label.value.clone())
+                }
+                _ => None,
+            };
+            let Some(table) = table else {
+                continue;
+            };
+            for field in fields {
+                self.add_property_type(&table, &field.key.value, &field.value);
+            }
+        }
+
+        let items: Vec<&SetClause> = match statement {
+            CypherStatement::Merge { actions, .. } => {
+                actions.iter().flat_map(|action| &action.items).collect()
+            }
+            CypherStatement::Set { items, .. } => items.iter().collect(),
+            _ => vec![],
+        };
+        for item in items {
+            if let SetTarget::Property { variable, property } = &item.target {
+                if let Some(table) = label_tables.get(&variable.value) {
+                    self.add_property_type(&table.clone(), &property.value, &item.value);
+                }
+            }
+        }
+
+        // Comparisons of properties with literals
+        let mut pending = statement.exprs();
+        while let Some(expr) = pending.pop() {
+            if let Expr::BinaryOp { left, op, right } = expr {
+                if is_comparison(op) {
+                    for (property, value) in [(left, right), (right, left)] {
+                        let Expr::CompoundIdentifier(idents) = property.as_ref() else {
+                            continue;
+                        };
+                        let [variable, property] = idents.as_slice() else {
+                            continue;
+                        };
+                        if let Some(table) = label_tables.get(&variable.value) {
+                            self.add_property_type(&table.clone(), &property.value, value);
+                        }
+                    }
+                }
+            }
+            pending.extend(subexpressions(expr));
+        }
+    }
+
+    /// Record that `property` of `table` holds or is compared with `value`
+    fn add_property_type(&mut self, table: &str, property: &str, value: &Expr) {
+        let Some(property_type) = PropertyType::of(value) else {
+            return;
+        };
+        self.property_types
+            .entry((table.to_string(), property.to_string()))
+            .and_modify(|existing| *existing = existing.union(property_type))
+            .or_insert(property_type);
+    }
+}
+
+/// The patterns a statement matches, creates or merges, outside of its
+/// expressions
+fn statement_patterns(statement: &CypherStatement) -> Vec<&Pattern> {
+    match statement {
+        CypherStatement::Match { patterns, next, .. } => {
+            let mut all: Vec<&Pattern> = patterns.iter().collect();
+            if let Some(next) = next {
+                all.extend(statement_patterns(next));
+            }
+            all
+        }
+        CypherStatement::Delete { patterns, .. }
+        | CypherStatement::Merge { patterns, .. }
+        | CypherStatement::Set { patterns, .. } => patterns.iter().collect(),
+        CypherStatement::Create {
+            r#match, patterns, ..
+        } => r#match.iter().chain(patterns).collect(),
+        CypherStatement::Unwind { query, .. } => statement_patterns(query),
+        CypherStatement::Recovered { statement } => statement_patterns(statement),
+        CypherStatement::Union { .. } | CypherStatement::Show { .. } => vec![],
+    }
+}
+
+fn is_comparison(op: &BinaryOperator) -> bool {
+    matches!(
+        op,
+        BinaryOperator::Eq
+            | BinaryOperator::NotEq
+            | BinaryOperator::Lt
+            | BinaryOperator::LtEq
+            | BinaryOperator::Gt
+            | BinaryOperator::GtEq
+    )
+}
+
+/// The type of the node id column, and of the relationship columns
+/// referencing it
+fn id_data_type(config: &TransformConfig) -> &'static str {
+    match (config.id_type, config.target) {
+        (IdType::Integer, SqlTarget::Sqlite) => "INTEGER",
+        (IdType::Integer, _) => "BIGINT",
+        (IdType::Text | IdType::Uuid, SqlTarget::Sqlite) => "TEXT",
+        (IdType::Text, SqlTarget::Postgres) => "TEXT",
+        (IdType::Text, SqlTarget::MySql) | (IdType::Uuid, SqlTarget::MySql) => "VARCHAR(36)",
+        (IdType::Text, SqlTarget::DuckDb) => "VARCHAR",
+        (IdType::Uuid, SqlTarget::Postgres | SqlTarget::DuckDb) => "UUID",
+        // DataFusion has no UUID type
+        (IdType::Text | IdType::Uuid, SqlTarget::DataFusion) => "VARCHAR",
+    }
+}
+
+/// The type of labels and relationship types, which MySQL can only index
+/// with a length
+fn name_data_type(target: SqlTarget) -> &'static str {
+    match target {
+        SqlTarget::Sqlite | SqlTarget::Postgres => "TEXT",
+        SqlTarget::MySql => "VARCHAR(255)",
+        SqlTarget::DuckDb | SqlTarget::DataFusion => "VARCHAR",
+    }
+}
+
+/// The type of the `properties` column, which must be `JSONB` for the
+/// `jsonb_typeof` of PostgreSQL type predicates, and is a map of strings on
+/// DataFusion
+fn json_data_type(target: SqlTarget) -> &'static str {
+    match target {
+        SqlTarget::Sqlite => "TEXT",
+        SqlTarget::Postgres => "JSONB",
+        SqlTarget::MySql | SqlTarget::DuckDb => "JSON",
+        SqlTarget::DataFusion => "MAP(VARCHAR, VARCHAR)",
+    }
+}
+
+/// The sequence DuckDB draws the integer ids of `table` from, as it has no
+/// auto-incrementing columns, quoted as configured
+fn id_sequence(config: &TransformConfig, table: &str) -> Option<String> {
+    let name = format!("{table}_{}_seq", config.id_column);
+    (config.target == SqlTarget::DuckDb && config.id_type == IdType::Integer)
+        .then(|| quote_name(&name, config.quoting, config.target))
+}
+
+/// A column of a `CREATE TABLE`, with the default of a node id that the
+/// database assigns
+fn column_definition(config: &TransformConfig, table: &str, column: &ColumnRequirement) -> String {
+    let name = quote_name(&column.name, config.quoting, config.target);
+    let definition = format!("{name} {}", column.data_type);
+    if !column.primary_key {
+        return definition;
+    }
+    let default = match (config.id_type, config.target) {
+        // An INTEGER PRIMARY KEY is the rowid, which SQLite assigns
+        (IdType::Integer, SqlTarget::Sqlite) | (IdType::Uuid, _) => String::new(),
+        (IdType::Integer, SqlTarget::Postgres) => " GENERATED BY DEFAULT AS IDENTITY".to_string(),
+        (IdType::Integer, SqlTarget::MySql) => " AUTO_INCREMENT".to_string(),
+        (IdType::Integer, SqlTarget::DuckDb) => format!(
+            " DEFAULT nextval('{}')",
+            id_sequence(config, table).unwrap_or_default()
+        ),
+        (IdType::Text, SqlTarget::Sqlite) => " DEFAULT (lower(hex(randomblob(16))))".to_string(),
+        (IdType::Text, SqlTarget::Postgres) => " DEFAULT gen_random_uuid()::text".to_string(),
+        (IdType::Text, SqlTarget::MySql) => " DEFAULT (uuid())".to_string(),
+        (IdType::Text, SqlTarget::DuckDb) => " DEFAULT CAST(uuid() AS VARCHAR)".to_string(),
+        // Statements writing to DataFusion are not translated
+        (IdType::Integer | IdType::Text, SqlTarget::DataFusion) => String::new(),
+    };
+    format!("{definition} PRIMARY KEY{default}")
+}
@@ -0,0 +1,156 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Transformation of scripts of Cypher statements and the directives of
+//! Neo4j Browser and cypher-shell
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use crate::ast::CypherDirective;
+use crate::parser::CypherScriptItem;
+
+use super::params::inline_known_parameters;
+use super::{
+    CypherToSqlTransformer, ParamValue, TransformConfig, TransformError, TransformOutput,
+    TransformWarning, WarningKind,
+};
+
+/// Transforms the statements and directives of a script in order, keeping
+/// the parameters its `:param` directives set
+///
+/// The value of a `:param` is inlined into the statements after it, as by
+/// [`CypherToSqlTransformer::to_sql_output_with_params`], while parameters
+/// no directive sets are left to be bound. A directive has no SQL, except
+/// `:begin`, `:commit` and `:rollback`, which are the SQL statements of the
+/// same name. `:use` is not translated and an unknown directive is skipped,
+/// each with a warning naming it, or under [`TransformConfig::strict`] with
+/// a [`TransformError::Unsupported`].
+///
+/// ```
+/// # use sqlparser::parser::CypherStatementIterator;
+/// # use sqlparser::transformer::*;
+/// let config = TransformConfig {
+///     use_label_tables: false,
+///     strict: false,
+///     ..TransformConfig::default()
+/// };
+/// let mut transformer = ScriptTransformer::new(config);
+/// let script = ":param minAge => 21;\n:sysinfo\nMATCH (n) WHERE n.age >= $minAge RETURN n.name;\n";
+/// let mut items = CypherStatementIterator::new(script.split_inclusive('\n'));
+/// let mut outputs = vec![];
+/// while let Some(item) = items.next_item() {
+///     outputs.push(transformer.transform(&item?)?);
+/// }
+/// assert_eq!(outputs[0].sql, "");
+/// assert_eq!(
+///     outputs[1].warnings[0].to_string(),
+///     "unknown directive :sysinfo is skipped: :sysinfo"
+/// );
+/// assert_eq!(
+///     outputs[2].sql,
+///     "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+///      WHERE json_extract(n.properties, '$.age') >= 21"
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct ScriptTransformer {
+    transformer: CypherToSqlTransformer,
+    /// The values of the `:param` directives read so far, by parameter name
+    parameters: BTreeMap<String, ParamValue>,
+}
+
+impl ScriptTransformer {
+    pub fn new(config: TransformConfig) -> Self {
+        ScriptTransformer {
+            transformer: CypherToSqlTransformer::with_config(config),
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    /// The values of the parameters set by the directives transformed so far
+    pub fn parameters(&self) -> &BTreeMap<String, ParamValue> {
+        &self.parameters
+    }
+
+    /// Transform the next statement or directive of the script
+    ///
+    /// The output of a directive without SQL has an empty `sql`. The value
+    /// of a `:param` must be made of literals, since the transformer does
+    /// not evaluate expressions, or it is a [`TransformError::InvalidQuery`].
+    pub fn transform(
+        &mut self,
+        item: &CypherScriptItem,
+    ) -> Result<TransformOutput, TransformError> {
+        let directive = match item {
+            CypherScriptItem::Statement(statement) if self.parameters.is_empty() => {
+                return self.transformer.to_sql_output(statement);
+            }
+            CypherScriptItem::Statement(statement) => {
+                let mut inlined = statement.clone();
+                inline_known_parameters(&mut inlined, &self.parameters)?;
+                return self.transformer.to_sql_output(&inlined);
+            }
+            CypherScriptItem::Directive(directive) => directive,
+        };
+        let (sql, warning) = match directive {
+            CypherDirective::Param { name, value } => {
+                let value = ParamValue::from_literal(value).ok_or_else(|| {
+                    TransformError::InvalidQuery(format!(
+                        "the value of :param {name} is not a literal: {value}"
+                    ))
+                })?;
+                self.parameters.insert(name.value.clone(), value);
+                ("", None)
+            }
+            CypherDirective::Begin => ("BEGIN", None),
+            CypherDirective::Commit => ("COMMIT", None),
+            CypherDirective::Rollback => ("ROLLBACK", None),
+            CypherDirective::Use(_) => ("", Some((":use".to_string(), WarningKind::Untranslated))),
+            CypherDirective::Other { name, .. } => (
+                "",
+                Some((format!(":{name}"), WarningKind::UnknownDirective)),
+            ),
+        };
+        if let Some((feature, kind)) = warning.as_ref().filter(|_| self.transformer.config.strict) {
+            return Err(TransformError::Unsupported(match kind {
+                WarningKind::UnknownDirective => format!("unknown directive {directive}"),
+                _ => format!("{feature} in {directive}"),
+            }));
+        }
+        Ok(TransformOutput {
+            sql: sql.to_string(),
+            warnings: warning
+                .map(|(feature, kind)| TransformWarning {
+                    feature,
+                    source_text: directive.to_string(),
+                    kind,
+                })
+                .into_iter()
+                .collect(),
+            parameters: vec![],
+            created_nodes: vec![],
+        })
+    }
+}
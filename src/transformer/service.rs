@@ -0,0 +1,247 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The handling of a request to transform Cypher to SQL in a web service,
+//! whose response is serialized as is, e.g. by an Axum or actix handler
+//!
+//! See `examples/http_service.rs` for a service built on it.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::parser::ParserError;
+
+use super::check::{bound_variables, parse_single_statement};
+use super::{
+    CypherToSqlTransformer, ParamBinding, TransformConfig, TransformError, TransformStats,
+    TransformWarning,
+};
+
+/// The response to a request to transform a Cypher query, with the SQL or
+/// the error
+///
+/// Serialized, e.g. as JSON, a response has every field, `null` where it
+/// has no value, e.g. for `MATCH (n:Per RETURN n`:
+///
+/// ```json
+/// {
+///   "sql": null,
+///   "warnings": [],
+///   "parameters": [],
+///   "stats": null,
+///   "error": {"kind": "Parse", "message": "Expected: ), found: RETURN", "line": 1, "column": 14}
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServiceResponse {
+    /// The SQL of the query
+    pub sql: Option<String>,
+    /// The constructs the SQL ignores or approximates, and its cross joins
+    pub warnings: Vec<TransformWarning>,
+    /// The SQL parameters of the query's `$parameters`
+    pub parameters: Vec<ParamBinding>,
+    /// The sizes of the query and the SQL and the time each phase took
+    pub stats: Option<TransformStats>,
+    pub error: Option<ServiceError>,
+}
+
+/// Why a query was not transformed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ServiceError {
+    pub kind: ServiceErrorKind,
+    /// The error, without its location
+    pub message: String,
+    /// The 1-based line of the query the error is at, if it has a location
+    pub line: Option<u64>,
+    /// The 1-based column of the query the error is at, if it has a location
+    pub column: Option<u64>,
+}
+
+/// The kind of a [`ServiceError`], which decides the status code of the
+/// response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ServiceErrorKind {
+    /// The request has no query, only whitespace, comments or `;`
+    EmptyInput,
+    /// The query is not valid Cypher, or is more than one statement
+    Parse,
+    /// An expression is nested deeper than the parser or
+    /// [`TransformConfig::recursion_limit`] allow
+    RecursionLimit,
+    /// A variable is used or deleted without being bound
+    Validation,
+    /// The query is not a valid query, e.g. UNION arms returning different
+    /// columns
+    InvalidQuery,
+    /// The query has a cross join that [`TransformConfig::strict_cartesian`]
+    /// rejects
+    CartesianProduct,
+    /// The query uses a construct that cannot be translated, or under
+    /// [`TransformConfig::strict`] one the SQL would ignore or approximate
+    Unsupported,
+}
+
+impl ServiceErrorKind {
+    /// The HTTP status code of a response with an error of this kind: 400
+    /// for a request without a valid query, and 422 for a query that
+    /// cannot be transformed
+    pub fn status_code(self) -> u16 {
+        match self {
+            ServiceErrorKind::EmptyInput
+            | ServiceErrorKind::Parse
+            | ServiceErrorKind::RecursionLimit => 400,
+            ServiceErrorKind::Validation
+            | ServiceErrorKind::InvalidQuery
+            | ServiceErrorKind::CartesianProduct
+            | ServiceErrorKind::Unsupported => 422,
+        }
+    }
+}
+
+impl ServiceResponse {
+    /// The HTTP status code of the response: 200 with the SQL, or that of
+    /// its error's kind
+    pub fn status_code(&self) -> u16 {
+        self.error
+            .as_ref()
+            .map_or(200, |error| error.kind.status_code())
+    }
+
+    fn error(error: ServiceError) -> Self {
+        ServiceResponse {
+            sql: None,
+            warnings: vec![],
+            parameters: vec![],
+            stats: None,
+            error: Some(error),
+        }
+    }
+}
+
+impl ServiceError {
+    /// The error of a query that does not parse, or of `kind` for one that
+    /// parses but does not check, located by the parser's message
+    fn parser(error: ParserError, kind: ServiceErrorKind) -> Self {
+        let message = match error {
+            ParserError::ParserError(message) | ParserError::TokenizerError(message) => message,
+            ParserError::RecursionLimitExceeded => return TransformError::from(error).into(),
+        };
+        let (message, location) = split_location(&message);
+        ServiceError {
+            kind,
+            message: message.to_string(),
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+        }
+    }
+}
+
+impl From<TransformError> for ServiceError {
+    fn from(error: TransformError) -> Self {
+        let kind = match error {
+            TransformError::Parse(ParserError::RecursionLimitExceeded) => {
+                return TransformError::RecursionLimitExceeded.into();
+            }
+            TransformError::Parse(error) => {
+                return ServiceError::parser(error, ServiceErrorKind::Parse);
+            }
+            TransformError::RecursionLimitExceeded => ServiceErrorKind::RecursionLimit,
+            TransformError::EmptyInput => ServiceErrorKind::EmptyInput,
+            TransformError::Unsupported(_) => ServiceErrorKind::Unsupported,
+            TransformError::CartesianProduct(_) => ServiceErrorKind::CartesianProduct,
+            // A request has no parameter values to miss
+            TransformError::InvalidQuery(_) | TransformError::MissingParameters(_) => {
+                ServiceErrorKind::InvalidQuery
+            }
+        };
+        ServiceError {
+            kind,
+            message: error.to_string(),
+            line: None,
+            column: None,
+        }
+    }
+}
+
+/// Transform the Cypher query of a request to SQL, the way a service
+/// should: the query is parsed as a single statement, its variables are
+/// checked to be bound, and it is transformed with `config`, timing each
+/// phase
+///
+/// Every failure is a response with a [`ServiceError`], so the response is
+/// all a handler has to send, with its [`ServiceResponse::status_code`].
+///
+/// ```
+/// # use sqlparser::transformer::TransformConfig;
+/// # use sqlparser::transformer::service::{handle_request, ServiceErrorKind};
+/// let config = TransformConfig::default();
+/// let response = handle_request("MATCH (n:Person) RETURN n.name", &config);
+/// assert_eq!(response.status_code(), 200);
+/// assert_eq!(response.sql.unwrap(), "SELECT n.name FROM Person AS n");
+///
+/// let response = handle_request("MATCH (n:Person) RETURN m.name", &config);
+/// assert_eq!(response.status_code(), 422);
+/// let error = response.error.unwrap();
+/// assert_eq!(error.kind, ServiceErrorKind::Validation);
+/// assert_eq!(error.message, "variable `m` is not bound");
+/// assert_eq!((error.line, error.column), (Some(1), Some(25)));
+/// ```
+pub fn handle_request(input: &str, config: &TransformConfig) -> ServiceResponse {
+    let statement = match parse_single_statement(input) {
+        Ok(statement) => statement,
+        Err(error) => return ServiceResponse::error(TransformError::from(error).into()),
+    };
+    if let Err(error) = bound_variables(&statement) {
+        let error = ServiceError::parser(error, ServiceErrorKind::Validation);
+        return ServiceResponse::error(error);
+    }
+    match CypherToSqlTransformer::with_config(config.clone()).to_sql_output_with_stats(input) {
+        Ok((output, stats)) => ServiceResponse {
+            sql: Some(output.sql),
+            warnings: output.warnings,
+            parameters: output.parameters,
+            stats: Some(stats),
+            error: None,
+        },
+        Err(error) => ServiceResponse::error(error.into()),
+    }
+}
+
+/// `message` without the ` at Line: 1, Column: 5` the parser ends it with,
+/// and the line and column
+fn split_location(message: &str) -> (&str, Option<(u64, u64)>) {
+    let location = message
+        .rsplit_once(" at Line: ")
+        .and_then(|(text, location)| {
+            let (line, column) = location.split_once(", Column: ")?;
+            Some((text, (line.parse().ok()?, column.parse().ok()?)))
+        });
+    match location {
+        Some((text, location)) => (text, Some(location)),
+        None => (message, None),
+    }
+}
@@ -0,0 +1,182 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Sizes and timings of the phases of a transformation
+
+use core::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{
+    CypherStatement, Query, SetExpr, Statement, TableFactor, TableWithJoins, Update,
+    UpdateTableFromKind,
+};
+use crate::dialect::CypherDialect;
+use crate::parser::Parser;
+use crate::tokenizer::{Token, Tokenizer};
+
+use super::{parse_cypher_query, CypherToSqlTransformer, TransformError, TransformOutput};
+
+/// How big a Cypher statement and its SQL are, and how long each phase of
+/// its transformation took
+///
+/// The durations are zero without the `std` feature, which has the clock.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TransformStats {
+    /// The tokens of the query, without whitespace and comments
+    pub token_count: usize,
+    /// The nodes and relationships of the patterns of the statement
+    pub pattern_element_count: usize,
+    /// The joins in the FROM clauses of the generated SQL
+    pub generated_join_count: usize,
+    /// Splitting the query into tokens
+    pub tokenize_time: Duration,
+    /// Parsing the tokens into a Cypher statement
+    pub parse_time: Duration,
+    /// Resolving the patterns of the statement and building the SQL
+    /// statements
+    pub plan_time: Duration,
+    /// Writing the SQL statements as text, with their parameters
+    pub generate_time: Duration,
+}
+
+impl CypherToSqlTransformer {
+    /// Parse and transform a Cypher query like [`Self::to_sql_output`],
+    /// measuring the query and the SQL and timing each phase
+    pub fn to_sql_output_with_stats(
+        &self,
+        cypher_query: &str,
+    ) -> Result<(TransformOutput, TransformStats), TransformError> {
+        let mut stats = TransformStats::default();
+
+        let (tokens, time) =
+            timed(|| Tokenizer::new(&CypherDialect, cypher_query).tokenize_with_location());
+        let tokens = tokens?;
+        stats.tokenize_time = time;
+        stats.token_count = tokens
+            .iter()
+            .filter(|token| !matches!(token.token, Token::Whitespace(_)))
+            .count();
+
+        let (statement, time) = timed(|| {
+            let parser = Parser::new(&CypherDialect).with_tokens_with_locations(tokens);
+            parse_cypher_query(parser, cypher_query, self.config.strict)
+        });
+        let statement = statement?;
+        stats.parse_time = time;
+        stats.pattern_element_count = pattern_element_count(&statement);
+
+        let (transformed, time) = timed(|| self.transform_with_details(&statement));
+        let transformed = transformed?;
+        stats.plan_time = time;
+        stats.generated_join_count = transformed.statements.iter().map(statement_joins).sum();
+
+        let (output, time) = timed(|| self.output(transformed));
+        stats.generate_time = time;
+        Ok((output, stats))
+    }
+}
+
+/// Run `phase`, returning how long it took where there is a clock
+fn timed<T>(phase: impl FnOnce() -> T) -> (T, Duration) {
+    #[cfg(feature = "std")]
+    {
+        let start = std::time::Instant::now();
+        let result = phase();
+        (result, start.elapsed())
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        (phase(), Duration::ZERO)
+    }
+}
+
+/// The elements of the patterns of `statement`, and of the statements it
+/// is made of
+fn pattern_element_count(statement: &CypherStatement) -> usize {
+    let (patterns, next) = match statement {
+        CypherStatement::Match { patterns, next, .. } => (patterns, next.as_deref()),
+        CypherStatement::Create {
+            r#match, patterns, ..
+        } => {
+            let elements = r#match.iter().chain(patterns);
+            return elements.map(|pattern| pattern.elements.len()).sum();
+        }
+        CypherStatement::Merge { patterns, .. }
+        | CypherStatement::Delete { patterns, .. }
+        | CypherStatement::Set { patterns, .. } => (patterns, None),
+        CypherStatement::Union { queries, .. } => {
+            return queries.iter().map(pattern_element_count).sum();
+        }
+        CypherStatement::Unwind { query, .. } => return pattern_element_count(query),
+        CypherStatement::Recovered { statement } => return pattern_element_count(statement),
+        CypherStatement::Show { .. } => return 0,
+    };
+    let elements: usize = patterns.iter().map(|pattern| pattern.elements.len()).sum();
+    elements + next.map_or(0, pattern_element_count)
+}
+
+/// The joins in the FROM clauses of a SQL statement
+fn statement_joins(statement: &Statement) -> usize {
+    match statement {
+        Statement::Query(query) => query_joins(query),
+        Statement::Insert(insert) => insert.source.as_deref().map_or(0, query_joins),
+        Statement::Update(Update { table, from, .. }) => {
+            let from = match from {
+                Some(
+                    UpdateTableFromKind::BeforeSet(from) | UpdateTableFromKind::AfterSet(from),
+                ) => from.iter().map(table_joins).sum(),
+                None => 0,
+            };
+            table_joins(table) + from
+        }
+        _ => 0,
+    }
+}
+
+fn query_joins(query: &Query) -> usize {
+    let ctes: usize = query
+        .with
+        .iter()
+        .flat_map(|with| &with.cte_tables)
+        .map(|cte| query_joins(&cte.query))
+        .sum();
+    ctes + set_expr_joins(&query.body)
+}
+
+fn set_expr_joins(body: &SetExpr) -> usize {
+    match body {
+        SetExpr::Select(select) => select.from.iter().map(table_joins).sum(),
+        SetExpr::Query(query) => query_joins(query),
+        SetExpr::SetOperation { left, right, .. } => set_expr_joins(left) + set_expr_joins(right),
+        _ => 0,
+    }
+}
+
+fn table_joins(table: &TableWithJoins) -> usize {
+    let nested = |relation: &TableFactor| match relation {
+        TableFactor::Derived { subquery, .. } => query_joins(subquery),
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => table_joins(table_with_joins),
+        _ => 0,
+    };
+    let joined: usize = table.joins.iter().map(|join| nested(&join.relation)).sum();
+    table.joins.len() + nested(&table.relation) + joined
+}
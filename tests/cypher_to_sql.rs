@@ -0,0 +1,5523 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![warn(clippy::all)]
+//! Test the translation of Cypher statements to SQL by
+//! [`CypherToSqlTransformer`].
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use sqlparser::ast::Expr;
+use sqlparser::dialect::{
+    CypherDialect, Dialect, GenericDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect,
+};
+use sqlparser::parser::{CypherStatementIterator, Parser, ParserError};
+use sqlparser::transformer::{
+    capabilities, check_cypher, transform_cypher_script, transform_cypher_to_sql,
+    transform_cypher_to_sql_with_params, transform_cypher_to_sql_with_stats, BothDirections,
+    CreatedNode, CypherToSqlTransformer, DistinctOrderBy, FunctionRewriter, IdType,
+    IdentifierQuoting, ParamBinding, ParamStyle, ParamValue, RewriteContext, ScriptTransformer,
+    SqlFormat, SqlTarget, StatementKind, Support, TransformConfig, TransformError,
+    UndirectedStrategy, WarningKind, CONSTRUCTS,
+};
+
+fn try_transform_with(cypher: &str, config: TransformConfig) -> Result<String, TransformError> {
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql(cypher)
+        .unwrap()
+        .parse_cypher_statement()
+        .unwrap();
+    CypherToSqlTransformer::with_config(config).to_sql(&stmt)
+}
+
+fn transform_with(cypher: &str, config: TransformConfig) -> String {
+    try_transform_with(cypher, config).unwrap()
+}
+
+fn label_tables() -> TransformConfig {
+    TransformConfig::default()
+}
+
+fn generic_tables() -> TransformConfig {
+    TransformConfig {
+        use_label_tables: false,
+        ..TransformConfig::default()
+    }
+}
+
+#[test]
+fn where_label_predicate_selects_label_table() {
+    assert_eq!(
+        transform_with("MATCH (n) WHERE n:Person RETURN n.name", label_tables()),
+        "SELECT n.name FROM Person AS n"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n) WHERE n:Person AND n.age > 30 RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n WHERE n.age > 30"
+    );
+}
+
+#[test]
+fn where_label_predicate_on_generic_table() {
+    assert_eq!(
+        transform_with("MATCH (n) WHERE n:Person RETURN n.name", generic_tables()),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE n.label = 'Person'"
+    );
+}
+
+#[test]
+fn pattern_and_where_labels_are_merged() {
+    // Repeating the label is redundant and keeps the label table
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE n:Person RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n"
+    );
+    // A variable shared between patterns is bound once
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person), (n)-[r:KNOWS]->(m) WHERE m:Person RETURN m.name",
+            label_tables()
+        ),
+        "SELECT m.name FROM Person AS n INNER JOIN relationships AS r ON n.id = r.from_id \
+         INNER JOIN Person AS m ON m.id = r.to_id \
+         WHERE r.type = 'KNOWS'"
+    );
+}
+
+#[test]
+fn multi_label_variable_keeps_generic_table() {
+    // whose rows have a single label, so that no row matches
+    let cypher = "MATCH (n:Person) WHERE n:Admin RETURN n.name";
+    assert_eq!(
+        try_transform_with(cypher, label_tables()),
+        Err(TransformError::Unsupported(
+            "multiple labels on a node in (n:Person) WHERE n:Admin".to_string()
+        ))
+    );
+    let lenient = TransformConfig {
+        strict: false,
+        ..label_tables()
+    };
+    let output =
+        transform_cypher_to_sql_with_params(cypher, &BTreeMap::new(), lenient.clone()).unwrap();
+    assert_eq!(
+        output.sql,
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE n.label = 'Admin' AND n.label = 'Person'"
+    );
+    assert_eq!(
+        output.warnings[0].to_string(),
+        "multiple labels on a node is not translated: (n:Person) WHERE n:Admin"
+    );
+
+    // The labels of a variable's pattern elements are combined as well
+    let output = transform_cypher_to_sql_with_params(
+        "MATCH (a:Admin), (a:Person)-[:KNOWS]->(b) WHERE a:Person AND b.age > 30 RETURN b",
+        &BTreeMap::new(),
+        lenient,
+    )
+    .unwrap();
+    assert_eq!(
+        output.warnings[0].to_string(),
+        "multiple labels on a node is not translated: (a:Admin), (a:Person) WHERE a:Person"
+    );
+    // but not a label repeated
+    assert!(try_transform_with(
+        "MATCH (n:Person), (n)-[:KNOWS]->(m) WHERE n:Person RETURN m.name",
+        label_tables()
+    )
+    .is_ok());
+}
+
+#[test]
+fn non_conjunctive_label_predicate_stays_in_where() {
+    assert_eq!(
+        transform_with(
+            "MATCH (n) WHERE n:Admin OR n.age > 30 RETURN n.name",
+            label_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE n.label = 'Admin' OR json_extract(n.properties, '$.age') > 30"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE n:Admin OR n:Person RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n WHERE 0 OR 1"
+    );
+}
+
+#[test]
+fn union_of_matching_arms() {
+    assert_eq!(
+        transform_with(
+            "MATCH (p:Person) RETURN p.name AS name \
+             UNION MATCH (c:Company) RETURN c.title AS name",
+            label_tables()
+        ),
+        "SELECT p.name AS name FROM Person AS p UNION SELECT c.title AS name FROM Company AS c"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN n.name UNION ALL MATCH (n:Company) RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n UNION ALL SELECT n.name FROM Company AS n"
+    );
+}
+
+#[test]
+fn union_with_mismatched_columns() {
+    assert_eq!(
+        try_transform_with(
+            "MATCH (p:Person) RETURN p.name AS name, p.age AS age \
+             UNION MATCH (c:Company) RETURN c.title AS name",
+            label_tables()
+        ),
+        Err(TransformError::InvalidQuery(
+            "All sub queries in a UNION must return the same columns, \
+             expected (name, age) but found (name)"
+                .to_string()
+        ))
+    );
+    assert_eq!(
+        try_transform_with(
+            "MATCH (p:Person) RETURN p.name AS name UNION MATCH (c:Company) RETURN c.title AS title",
+            label_tables()
+        ),
+        Err(TransformError::InvalidQuery(
+            "All sub queries in a UNION must return the same columns, \
+             expected (name) but found (title)"
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn union_order_by_applies_to_combined_result() {
+    assert_eq!(
+        transform_with(
+            "MATCH (p:Person) RETURN p.name AS name \
+             UNION MATCH (c:Company) RETURN c.title AS name ORDER BY name DESC SKIP 1 LIMIT 10",
+            label_tables()
+        ),
+        "SELECT p.name AS name FROM Person AS p \
+         UNION SELECT c.title AS name FROM Company AS c ORDER BY name DESC LIMIT 10 OFFSET 1"
+    );
+    // Unaliased columns are ordered by position
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN n.name UNION MATCH (n:Company) RETURN n.name ORDER BY n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n UNION SELECT n.name FROM Company AS n ORDER BY 1"
+    );
+    assert_eq!(
+        try_transform_with(
+            "MATCH (n:Person) RETURN n.name UNION MATCH (n:Company) RETURN n.name ORDER BY n.age",
+            label_tables()
+        ),
+        Err(TransformError::InvalidQuery(
+            "ORDER BY after UNION must refer to a returned column, found n.age".to_string()
+        ))
+    );
+    assert_eq!(
+        try_transform_with(
+            "MATCH (n:Person) RETURN n.name LIMIT 1 UNION MATCH (n:Company) RETURN n.name",
+            label_tables()
+        ),
+        Err(TransformError::InvalidQuery(
+            "ORDER BY, SKIP and LIMIT are only allowed after the last UNION arm".to_string()
+        ))
+    );
+}
+
+#[test]
+fn return_star_expands_bound_variables() {
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person)-[r:KNOWS]->(b:Person) RETURN *",
+            generic_tables()
+        ),
+        "SELECT json_object('id', a.id, 'label', a.label, 'properties', json(a.properties)) AS a, \
+         json_object('from_id', r.from_id, 'to_id', r.to_id, 'type', r.type, \
+         'properties', json(r.properties)) AS r, \
+         json_object('id', b.id, 'label', b.label, 'properties', json(b.properties)) AS b \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id \
+         WHERE a.label = 'Person' AND r.type = 'KNOWS' AND b.label = 'Person'"
+    );
+    // Anonymous elements are not returned, label tables keep their own columns
+    assert_eq!(
+        transform_with("MATCH (a:Person)-[:KNOWS]->(b) RETURN *", label_tables()),
+        "SELECT a.*, b.id AS b_id, b.label AS b_label, b.properties AS b_properties \
+         FROM Person AS a INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN nodes AS b ON b.id = relationships.to_id \
+         WHERE relationships.type = 'KNOWS'"
+    );
+    // Two label tables, whose columns are unknown, could both have an `id`
+    for cypher in [
+        "MATCH (a:Person)-[r:KNOWS]->(b:Person) RETURN *",
+        "MATCH (a:Person)-[r:KNOWS]->(b:Person) RETURN a, r, b",
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, label_tables()),
+            Err(TransformError::Unsupported(
+                "returning a and b whole from label tables, whose columns may share names"
+                    .to_string()
+            )),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn return_relationship_variable() {
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[r:KNOWS]->(b) RETURN r, a.name",
+            generic_tables()
+        ),
+        "SELECT json_object('from_id', r.from_id, 'to_id', r.to_id, 'type', r.type, \
+         'properties', json(r.properties)) AS r, \
+         json_extract(a.properties, '$.name') AS name \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id \
+         WHERE r.type = 'KNOWS'"
+    );
+    let config = TransformConfig {
+        target: SqlTarget::Postgres,
+        ..generic_tables()
+    };
+    assert_eq!(
+        transform_with("MATCH (a)-[r:KNOWS]->(b) RETURN r AS knows", config),
+        "SELECT json_build_object('from_id', r.from_id, 'to_id', r.to_id, 'type', r.type, \
+         'properties', r.properties) AS knows \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id \
+         WHERE r.type = 'KNOWS'"
+    );
+    // Label tables project the columns of the relationships table
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person)-[r:KNOWS]->(b:Person) RETURN r, a.name",
+            label_tables()
+        ),
+        "SELECT r.from_id AS r_from_id, r.to_id AS r_to_id, r.type AS r_type, \
+         r.properties AS r_properties, a.name \
+         FROM Person AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN Person AS b ON b.id = r.to_id \
+         WHERE r.type = 'KNOWS'"
+    );
+}
+
+#[test]
+fn return_star_kept_literal() {
+    let config = TransformConfig {
+        expand_return_star: false,
+        ..generic_tables()
+    };
+    assert_eq!(
+        transform_with("MATCH (a)-[r]->(b) RETURN *", config),
+        "SELECT * FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id"
+    );
+}
+
+#[test]
+fn return_bare_variable() {
+    assert_eq!(
+        transform_with("MATCH (a)-[r]->(b) RETURN a AS p, b.name", generic_tables()),
+        "SELECT json_object('id', a.id, 'label', a.label, 'properties', json(a.properties)) AS p, \
+         json_extract(b.properties, '$.name') AS name \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id"
+    );
+}
+
+#[test]
+fn return_unbound_variable() {
+    for (cypher, variable, column) in [
+        ("MATCH (n) RETURN m.name", "m", 18),
+        ("MATCH (n) RETURN n.name, m", "m", 26),
+        ("MATCH (n) WHERE n.age > m.age RETURN n", "m", 25),
+    ] {
+        for strict in [false, true] {
+            assert_eq!(
+                try_transform_with(
+                    cypher,
+                    TransformConfig {
+                        strict,
+                        ..generic_tables()
+                    }
+                ),
+                Err(TransformError::InvalidQuery(format!(
+                    "variable `{variable}` is not bound at Line: 1, Column: {column}"
+                ))),
+                "{cypher}"
+            );
+        }
+    }
+}
+
+#[test]
+fn json_property_aliases_are_unique() {
+    assert_eq!(
+        transform_with(
+            "MATCH (n)-[r]->(m) RETURN n.name, m.name, r.since ORDER BY n.name DESC",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS n_name, \
+         json_extract(m.properties, '$.name') AS m_name, \
+         json_extract(r.properties, '$.since') AS since \
+         FROM nodes AS n INNER JOIN relationships AS r ON n.id = r.from_id \
+         INNER JOIN nodes AS m ON m.id = r.to_id \
+         ORDER BY n_name DESC"
+    );
+    // Explicit aliases win over generated ones
+    assert_eq!(
+        transform_with(
+            "MATCH (n), (m) RETURN n.name, m.title AS name",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS n_name, \
+         json_extract(m.properties, '$.title') AS name FROM nodes AS n, nodes AS m"
+    );
+    assert_eq!(
+        try_transform_with(
+            "MATCH (n), (m) RETURN n.name, m.name, m.title AS n_name",
+            generic_tables()
+        ),
+        Err(TransformError::InvalidQuery(
+            "Multiple result columns with the same name are not supported: n_name".to_string()
+        ))
+    );
+    // Label tables keep their property columns
+    assert_eq!(
+        transform_with("MATCH (n:Person) RETURN n.name", label_tables()),
+        "SELECT n.name FROM Person AS n"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person), (m) RETURN n.name, m.name",
+            label_tables()
+        ),
+        "SELECT n.name, json_extract(m.properties, '$.name') AS m_name \
+         FROM Person AS n, nodes AS m"
+    );
+}
+
+#[test]
+fn order_by_return_alias() {
+    assert_eq!(
+        transform_with(
+            "MATCH (n) RETURN n.name AS person ORDER BY person",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS person FROM nodes AS n ORDER BY person"
+    );
+    // Property keys get the rewrite of their projection column
+    assert_eq!(
+        transform_with(
+            "MATCH (n) RETURN n.name AS person, n.age ORDER BY n.name DESC",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS person, \
+         json_extract(n.properties, '$.age') AS age FROM nodes AS n ORDER BY person DESC"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n) RETURN n.name AS person ORDER BY n.age",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS person FROM nodes AS n \
+         ORDER BY json_extract(n.properties, '$.age')"
+    );
+}
+
+#[test]
+fn order_by_mixed_aliases_and_properties() {
+    assert_eq!(
+        transform_with(
+            "MATCH (n)-[:WORKS_AT]->(c) RETURN n.name AS person, c.name AS company \
+             ORDER BY company, n.age DESC, person",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS person, \
+         json_extract(c.properties, '$.name') AS company \
+         FROM nodes AS n INNER JOIN relationships ON n.id = relationships.from_id \
+         INNER JOIN nodes AS c ON c.id = relationships.to_id \
+         WHERE relationships.type = 'WORKS_AT' \
+         ORDER BY company, json_extract(n.properties, '$.age') DESC, person"
+    );
+    // The long direction keywords sort as the short ones
+    assert_eq!(
+        transform_with(
+            "MATCH (n) RETURN n.name AS person ORDER BY n.age DESCENDING, person ASCENDING",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS person FROM nodes AS n \
+         ORDER BY json_extract(n.properties, '$.age') DESC, person ASC"
+    );
+}
+
+#[test]
+fn order_by_alias_within_expression() {
+    let cypher = "MATCH (n) RETURN n.name AS person ORDER BY lower(person)";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "SELECT json_extract(n.properties, '$.name') AS person FROM nodes AS n \
+         ORDER BY lower(person)"
+    );
+    // PostgreSQL only resolves output aliases used as bare sort keys
+    let postgres = TransformConfig {
+        target: SqlTarget::Postgres,
+        ..generic_tables()
+    };
+    assert_eq!(
+        transform_with(cypher, postgres.clone()),
+        "SELECT n.properties ->> 'name' AS person FROM nodes AS n \
+         ORDER BY lower(n.properties ->> 'name')"
+    );
+    // and sorts a property read as text by its jsonb value, which orders
+    // numbers as numbers
+    assert_eq!(
+        transform_with("MATCH (n) RETURN n.age AS age ORDER BY age", postgres),
+        "SELECT n.properties ->> 'age' AS age FROM nodes AS n ORDER BY n.properties -> 'age'"
+    );
+}
+
+#[test]
+fn pretty_two_hop_match() {
+    let config = TransformConfig {
+        format: SqlFormat::Pretty { indent: 2 },
+        ..label_tables()
+    };
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person)-[r1:KNOWS]->(b)-[r2:KNOWS]->(c:Person) \
+             WHERE a.age > 30 AND b.name <> 'Bob' \
+             RETURN DISTINCT a.name, c.name AS friend ORDER BY a.name LIMIT 10",
+            config
+        ),
+        "SELECT DISTINCT
+  a.name,
+  c.name AS friend
+FROM
+  Person AS a
+  INNER JOIN relationships AS r1
+    ON a.id = r1.from_id
+  INNER JOIN nodes AS b
+    ON b.id = r1.to_id
+  INNER JOIN relationships AS r2
+    ON b.id = r2.from_id
+  INNER JOIN Person AS c
+    ON c.id = r2.to_id
+WHERE
+  r1.type = 'KNOWS'
+  AND r2.type = 'KNOWS'
+  AND r1.rowid <> r2.rowid
+  AND a.age > 30
+  AND json_extract(b.properties, '$.name') <> 'Bob'
+ORDER BY
+  a.name
+LIMIT 10"
+    );
+}
+
+#[test]
+fn pretty_union() {
+    let config = TransformConfig {
+        format: SqlFormat::Pretty { indent: 4 },
+        ..label_tables()
+    };
+    assert_eq!(
+        transform_with(
+            "MATCH (p:Person) RETURN p.name AS name UNION ALL MATCH (c:Company) RETURN c.name AS name",
+            config
+        ),
+        "SELECT
+    p.name AS name
+FROM
+    Person AS p
+UNION ALL
+SELECT
+    c.name AS name
+FROM
+    Company AS c"
+    );
+}
+
+#[test]
+fn annotated_aliases() {
+    let cypher = "MATCH (a:Person)-[r:KNOWS]->(b)-[:WORKS_AT]->(c:Company:Startup) RETURN a.name";
+    let lenient = TransformConfig {
+        strict: false,
+        ..generic_tables()
+    };
+    assert!(!transform_with(cypher, lenient.clone()).contains("--"));
+
+    let config = TransformConfig {
+        annotate_aliases: true,
+        ..lenient
+    };
+    assert_eq!(
+        transform_with(cypher, config),
+        "SELECT json_extract(a.properties, '$.name') AS name FROM nodes AS a \
+         INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id \
+         INNER JOIN relationships ON b.id = relationships.from_id \
+         INNER JOIN nodes AS c ON c.id = relationships.to_id \
+         WHERE a.label = 'Person' AND r.type = 'KNOWS' AND relationships.type = 'WORKS_AT' \
+         AND c.label = 'Company' AND c.label = 'Startup'\n\
+         -- aliases: a=nodes(Person), r=relationships(KNOWS), b=nodes(), \
+         relationships=relationships(WORKS_AT), c=nodes(Company:Startup)"
+    );
+
+    let config = TransformConfig {
+        annotate_aliases: true,
+        format: SqlFormat::Pretty { indent: 2 },
+        ..label_tables()
+    };
+    assert_eq!(
+        transform_with(
+            "MATCH (p:Person) RETURN p.name AS name \
+             UNION MATCH (a:Person)-[:KNOWS|LIKES]->(b:Person) RETURN b.name AS name",
+            config
+        ),
+        "SELECT
+  p.name AS name
+FROM
+  Person AS p /* (p:Person) */
+UNION
+SELECT
+  b.name AS name
+FROM
+  Person AS a /* (a:Person) */
+  INNER JOIN relationships /* [:KNOWS|LIKES] */
+    ON a.id = relationships.from_id
+  INNER JOIN Person AS b /* (b:Person) */
+    ON b.id = relationships.to_id
+WHERE
+  relationships.type = 'KNOWS' OR relationships.type = 'LIKES'
+-- aliases: p=Person(Person), a=Person(Person), relationships=relationships(KNOWS|LIKES), b=Person(Person)"
+    );
+}
+
+#[test]
+fn relationships_join_their_endpoints() {
+    assert_eq!(
+        transform_with(
+            "MATCH (a)<-[:MANAGES]-(b)-[:MANAGES]->(c) RETURN c",
+            generic_tables()
+        ),
+        "SELECT json_object('id', c.id, 'label', c.label, 'properties', json(c.properties)) AS c \
+         FROM nodes AS a \
+         INNER JOIN relationships AS relationships_1 ON a.id = relationships_1.to_id \
+         INNER JOIN nodes AS b ON b.id = relationships_1.from_id \
+         INNER JOIN relationships AS relationships_2 ON b.id = relationships_2.from_id \
+         INNER JOIN nodes AS c ON c.id = relationships_2.to_id \
+         WHERE relationships_1.type = 'MANAGES' AND relationships_2.type = 'MANAGES' \
+         AND relationships_1.rowid <> relationships_2.rowid"
+    );
+    // A pattern closing a cycle joins both of its endpoints
+    assert_eq!(
+        transform_with("MATCH (a)-[r]->(a) RETURN r.since", generic_tables()),
+        "SELECT json_extract(r.properties, '$.since') AS since \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id AND a.id = r.to_id"
+    );
+    // Endpoints in another FROM item are matched in WHERE
+    assert_eq!(
+        transform_with(
+            "MATCH (a), (b), (a)-[r]->(b) RETURN r.since",
+            generic_tables()
+        ),
+        "SELECT json_extract(r.properties, '$.since') AS since \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id, nodes AS b \
+         WHERE b.id = r.to_id"
+    );
+}
+
+#[test]
+fn relationships_of_a_match_differ() {
+    let triangle = "MATCH (a)-[r1:KNOWS]->(b)-[r2:KNOWS]->(c)-[r3:KNOWS]->(a) RETURN count(*)";
+    let from = "SELECT count(*) FROM nodes AS a \
+                INNER JOIN relationships AS r1 ON a.id = r1.from_id \
+                INNER JOIN nodes AS b ON b.id = r1.to_id \
+                INNER JOIN relationships AS r2 ON b.id = r2.from_id \
+                INNER JOIN nodes AS c ON c.id = r2.to_id \
+                INNER JOIN relationships AS r3 ON c.id = r3.from_id AND a.id = r3.to_id \
+                WHERE r1.type = 'KNOWS' AND r2.type = 'KNOWS' AND r3.type = 'KNOWS'";
+    // Every pair of relationships, by the row ids of the target
+    for (target, id) in [
+        (SqlTarget::Sqlite, "rowid"),
+        (SqlTarget::DuckDb, "rowid"),
+        (SqlTarget::Postgres, "ctid"),
+    ] {
+        let config = TransformConfig {
+            target,
+            ..generic_tables()
+        };
+        assert_eq!(
+            transform_with(triangle, config),
+            format!("{from} AND r1.{id} <> r2.{id} AND r1.{id} <> r3.{id} AND r2.{id} <> r3.{id}"),
+            "{target:?}"
+        );
+    }
+    // or by a column of the relationship table
+    let mysql = TransformConfig {
+        target: SqlTarget::MySql,
+        relationship_id_column: Some("rel_id".to_string()),
+        ..generic_tables()
+    };
+    assert_eq!(
+        transform_with(triangle, mysql.clone()),
+        format!("{from} AND r1.rel_id <> r2.rel_id AND r1.rel_id <> r3.rel_id AND r2.rel_id <> r3.rel_id")
+    );
+    // Without either they are not compared
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql(triangle)
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let output = CypherToSqlTransformer::with_config(TransformConfig {
+        relationship_id_column: None,
+        strict: false,
+        ..mysql
+    })
+    .to_sql_output(&stmt)
+    .unwrap();
+    assert_eq!(output.sql, from);
+    assert_eq!(
+        output
+            .warnings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["relationship uniqueness is not translated: \
+          (a)-[r1:KNOWS]->(b)-[r2:KNOWS]->(c)-[r3:KNOWS]->(a)"]
+    );
+    let relaxed = TransformConfig {
+        relationship_uniqueness: false,
+        ..generic_tables()
+    };
+    assert_eq!(transform_with(triangle, relaxed), from);
+
+    // Relationships of different types cannot be the same, and those of an
+    // OPTIONAL MATCH differ from each other only
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[r1:KNOWS]->(b)-[r2:LIKES]->(c)-[r3]->(a) RETURN count(*)",
+            generic_tables()
+        ),
+        "SELECT count(*) FROM nodes AS a \
+         INNER JOIN relationships AS r1 ON a.id = r1.from_id \
+         INNER JOIN nodes AS b ON b.id = r1.to_id \
+         INNER JOIN relationships AS r2 ON b.id = r2.from_id \
+         INNER JOIN nodes AS c ON c.id = r2.to_id \
+         INNER JOIN relationships AS r3 ON c.id = r3.from_id AND a.id = r3.to_id \
+         WHERE r1.type = 'KNOWS' AND r2.type = 'LIKES' \
+         AND r1.rowid <> r3.rowid AND r2.rowid <> r3.rowid"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[r1:KNOWS]->(b) OPTIONAL MATCH (b)-[r2:KNOWS]->(c)-[r3:KNOWS]->(a) \
+             RETURN count(*)",
+            generic_tables()
+        ),
+        "SELECT count(*) FROM nodes AS a \
+         INNER JOIN relationships AS r1 ON a.id = r1.from_id \
+         INNER JOIN nodes AS b ON b.id = r1.to_id \
+         LEFT JOIN (relationships AS r2 INNER JOIN nodes AS c ON c.id = r2.to_id \
+         INNER JOIN relationships AS r3 ON c.id = r3.from_id AND r3.type = 'KNOWS' \
+         AND r2.rowid <> r3.rowid) ON b.id = r2.from_id AND a.id = r3.to_id AND r2.type = 'KNOWS' \
+         WHERE r1.type = 'KNOWS'"
+    );
+}
+
+#[test]
+fn distinct_nodes_of_a_pattern() {
+    let distinct = TransformConfig {
+        distinct_nodes: true,
+        ..generic_tables()
+    };
+    let knows = "MATCH (a:Person)-[:KNOWS]->(b:Person) RETURN a.name, b.name";
+    let sql = "SELECT json_extract(a.properties, '$.name') AS a_name, \
+               json_extract(b.properties, '$.name') AS b_name FROM nodes AS a \
+               INNER JOIN relationships ON a.id = relationships.from_id \
+               INNER JOIN nodes AS b ON b.id = relationships.to_id \
+               WHERE a.label = 'Person' AND relationships.type = 'KNOWS' AND b.label = 'Person'";
+    assert_eq!(transform_with(knows, generic_tables()), sql);
+    assert_eq!(
+        transform_with(knows, distinct.clone()),
+        format!("{sql} AND a.id <> b.id")
+    );
+
+    // Every pair of nodes, once, after the relationships
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[r1]->(b)-[r2]->(c)<-[r3]-(a) RETURN c",
+            distinct.clone()
+        ),
+        "SELECT json_object('id', c.id, 'label', c.label, 'properties', json(c.properties)) AS c \
+         FROM nodes AS a INNER JOIN relationships AS r1 ON a.id = r1.from_id \
+         INNER JOIN nodes AS b ON b.id = r1.to_id \
+         INNER JOIN relationships AS r2 ON b.id = r2.from_id \
+         INNER JOIN nodes AS c ON c.id = r2.to_id \
+         INNER JOIN relationships AS r3 ON a.id = r3.from_id AND c.id = r3.to_id \
+         WHERE r1.rowid <> r2.rowid AND r1.rowid <> r3.rowid AND r2.rowid <> r3.rowid \
+         AND a.id <> b.id AND a.id <> c.id AND b.id <> c.id"
+    );
+    // An anonymous end node is joined to be compared
+    assert_eq!(
+        transform_with("MATCH (a)-[:KNOWS]->() RETURN a.name", distinct.clone()),
+        "SELECT json_extract(a.properties, '$.name') AS name FROM nodes AS a \
+         INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN nodes ON nodes.id = relationships.to_id \
+         WHERE relationships.type = 'KNOWS' AND a.id <> nodes.id"
+    );
+    // Nodes of different label tables are different nodes already
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person)-[:LIVES_IN]->(c:City) RETURN c.name",
+            TransformConfig {
+                distinct_nodes: true,
+                ..label_tables()
+            }
+        ),
+        "SELECT c.name FROM Person AS a INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN City AS c ON c.id = relationships.to_id WHERE relationships.type = 'LIVES_IN'"
+    );
+    // A single node, or a node the pattern comes back to, is not compared
+    // with itself
+    assert_eq!(
+        transform_with("MATCH (n:Person) RETURN n.name", distinct.clone()),
+        transform_with("MATCH (n:Person) RETURN n.name", generic_tables())
+    );
+    assert_eq!(
+        transform_with("MATCH (a)-[r]->(a) RETURN r.since", distinct),
+        "SELECT json_extract(r.properties, '$.since') AS since \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id AND a.id = r.to_id"
+    );
+}
+
+#[test]
+fn anonymous_end_nodes_are_not_joined() {
+    // The endpoint columns of a relationship hold the ids of its nodes, so
+    // an anonymous node without labels or properties at the end of a path
+    // needs no join
+    assert_eq!(
+        transform_with("MATCH ()-[r:KNOWS]->() RETURN count(r)", generic_tables()),
+        "SELECT count(r.from_id) FROM relationships AS r WHERE r.type = 'KNOWS'"
+    );
+    assert_eq!(
+        transform_with("MATCH ()<-[:KNOWS]-(b) RETURN b.name", generic_tables()),
+        "SELECT json_extract(b.properties, '$.name') AS name \
+         FROM relationships INNER JOIN nodes AS b ON b.id = relationships.from_id \
+         WHERE relationships.type = 'KNOWS'"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) OPTIONAL MATCH (a)-[r:WORKS_AT]->() RETURN a.name, r.since",
+            label_tables()
+        ),
+        "SELECT a.name, json_extract(r.properties, '$.since') AS since \
+         FROM Person AS a LEFT JOIN relationships AS r ON a.id = r.from_id AND r.type = 'WORKS_AT'"
+    );
+
+    // A node between two relationships joins them, and labels, properties,
+    // both orientations and a named path all need the node's row
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[:KNOWS]->()<-[:KNOWS]-(b) RETURN a.name, b.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(a.properties, '$.name') AS a_name, \
+         json_extract(b.properties, '$.name') AS b_name FROM nodes AS a \
+         INNER JOIN relationships AS relationships_1 ON a.id = relationships_1.from_id \
+         INNER JOIN nodes ON nodes.id = relationships_1.to_id \
+         INNER JOIN relationships AS relationships_2 ON nodes.id = relationships_2.to_id \
+         INNER JOIN nodes AS b ON b.id = relationships_2.from_id \
+         WHERE relationships_1.type = 'KNOWS' AND relationships_2.type = 'KNOWS' \
+         AND relationships_1.rowid <> relationships_2.rowid"
+    );
+    for cypher in [
+        "MATCH (a)-[:KNOWS]->(:Person) RETURN a.name",
+        "MATCH (a)-[:KNOWS]->({name: 'Ada'}) RETURN a.name",
+        "MATCH (a)-[:KNOWS]-() RETURN a.name",
+        "MATCH p = (a)-[:KNOWS]->() RETURN a.name",
+    ] {
+        let config = TransformConfig {
+            strict: false,
+            ..generic_tables()
+        };
+        assert!(
+            transform_with(cypher, config).contains("INNER JOIN nodes ON"),
+            "{cypher}"
+        );
+    }
+
+    let config = TransformConfig {
+        prune_anonymous_nodes: false,
+        ..generic_tables()
+    };
+    assert_eq!(
+        transform_with("MATCH ()-[r:KNOWS]->() RETURN count(r)", config),
+        "SELECT count(r.from_id) FROM nodes AS nodes_1 \
+         INNER JOIN relationships AS r ON nodes_1.id = r.from_id \
+         INNER JOIN nodes AS nodes_2 ON nodes_2.id = r.to_id WHERE r.type = 'KNOWS'"
+    );
+}
+
+#[test]
+fn quoted_relationship_types() {
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[r:`HAS PART`|`it's`]->(b) RETURN b.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(b.properties, '$.name') AS name \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id \
+         WHERE r.type = 'HAS PART' OR r.type = 'it''s'"
+    );
+}
+
+#[test]
+fn quoted_labels() {
+    let cypher = "MATCH (n:`E-Commerce`)-[:`1ST_DEGREE`]->(m) WHERE m:`it's` RETURN n.name";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         INNER JOIN relationships ON n.id = relationships.from_id \
+         INNER JOIN nodes AS m ON m.id = relationships.to_id \
+         WHERE n.label = 'E-Commerce' AND relationships.type = '1ST_DEGREE' \
+         AND m.label = 'it''s'"
+    );
+    // As a table, the label is an identifier to quote
+    assert_eq!(
+        transform_with(
+            cypher,
+            TransformConfig {
+                quoting: IdentifierQuoting::WhenNeeded,
+                ..label_tables()
+            }
+        ),
+        "SELECT n.name FROM \"E-Commerce\" AS n \
+         INNER JOIN relationships ON n.id = relationships.from_id \
+         INNER JOIN \"it's\" AS m ON m.id = relationships.to_id \
+         WHERE relationships.type = '1ST_DEGREE'"
+    );
+}
+
+#[test]
+fn undirected_relationships() {
+    let or_join = generic_tables();
+    let union_all = TransformConfig {
+        undirected: UndirectedStrategy::UnionAll,
+        ..generic_tables()
+    };
+
+    let cypher = "MATCH (a:Person)-[:KNOWS]-(b:Person) RETURN a.name, b.name";
+    assert_eq!(
+        transform_with(cypher, or_join.clone()),
+        "SELECT json_extract(a.properties, '$.name') AS a_name, \
+         json_extract(b.properties, '$.name') AS b_name \
+         FROM nodes AS a \
+         INNER JOIN relationships ON (a.id = relationships.from_id OR a.id = relationships.to_id) \
+         INNER JOIN nodes AS b ON ((a.id = relationships.from_id AND b.id = relationships.to_id) \
+         OR (b.id = relationships.from_id AND a.id = relationships.to_id)) \
+         WHERE a.label = 'Person' AND relationships.type = 'KNOWS' AND b.label = 'Person'"
+    );
+    assert_eq!(
+        transform_with(cypher, union_all.clone()),
+        "SELECT json_extract(a.properties, '$.name') AS a_name, \
+         json_extract(b.properties, '$.name') AS b_name \
+         FROM nodes AS a \
+         INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN nodes AS b ON b.id = relationships.to_id \
+         WHERE a.label = 'Person' AND relationships.type = 'KNOWS' AND b.label = 'Person' \
+         UNION ALL \
+         SELECT json_extract(a.properties, '$.name') AS a_name, \
+         json_extract(b.properties, '$.name') AS b_name \
+         FROM nodes AS a \
+         INNER JOIN relationships ON a.id = relationships.to_id \
+         INNER JOIN nodes AS b ON b.id = relationships.from_id \
+         WHERE a.label = 'Person' AND relationships.type = 'KNOWS' AND b.label = 'Person'"
+    );
+
+    // Both arrows may be matched like no arrow
+    for config in [or_join.clone(), union_all.clone()] {
+        let both = TransformConfig {
+            both_directions: BothDirections::Undirected,
+            strict: false,
+            ..config.clone()
+        };
+        assert_eq!(
+            transform_with(
+                "MATCH (a:Person)<-[:KNOWS]->(b:Person) RETURN a.name, b.name",
+                both
+            ),
+            transform_with(cypher, config)
+        );
+    }
+
+    // Endpoints in another FROM item are matched in WHERE
+    assert_eq!(
+        transform_with(
+            "MATCH (a), (b), (a)-[r]-(b) RETURN r.since",
+            or_join.clone()
+        ),
+        "SELECT json_extract(r.properties, '$.since') AS since \
+         FROM nodes AS a INNER JOIN relationships AS r ON (a.id = r.from_id OR a.id = r.to_id), \
+         nodes AS b \
+         WHERE ((a.id = r.from_id AND b.id = r.to_id) OR (b.id = r.from_id AND a.id = r.to_id))"
+    );
+    // A relationship from a node to itself has a single orientation
+    for config in [or_join, union_all.clone()] {
+        assert_eq!(
+            transform_with("MATCH (a)-[r]-(a) RETURN r.since", config),
+            "SELECT json_extract(r.properties, '$.since') AS since \
+             FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id AND a.id = r.to_id"
+        );
+    }
+
+    // DISTINCT deduplicates the orientations, and the last query of the
+    // union carries its ORDER BY and LIMIT
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[:KNOWS]-(b) RETURN DISTINCT a.name ORDER BY a.name LIMIT 3",
+            union_all.clone()
+        ),
+        "SELECT DISTINCT json_extract(a.properties, '$.name') AS name \
+         FROM nodes AS a \
+         INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN nodes AS b ON b.id = relationships.to_id \
+         WHERE relationships.type = 'KNOWS' \
+         UNION \
+         SELECT DISTINCT json_extract(a.properties, '$.name') AS name \
+         FROM nodes AS a \
+         INNER JOIN relationships ON a.id = relationships.to_id \
+         INNER JOIN nodes AS b ON b.id = relationships.from_id \
+         WHERE relationships.type = 'KNOWS' \
+         ORDER BY 1 LIMIT 3"
+    );
+    let chain = (0..7).fold("MATCH (n0)".to_string(), |chain, n| {
+        format!("{chain}-[:R]-(n{})", n + 1)
+    });
+    assert_eq!(
+        try_transform_with(&format!("{chain} RETURN n0"), union_all.clone()),
+        Err(TransformError::Unsupported(
+            "more than 6 undirected relationships in a UNION ALL of their orientations".to_string()
+        ))
+    );
+    // Each arm would count only the rows of its own orientation
+    assert_eq!(
+        try_transform_with(
+            "MATCH (a)-[:KNOWS]-(b) RETURN a.name, count(b) AS known LIMIT 1",
+            union_all
+        ),
+        Err(TransformError::Unsupported(
+            "aggregation over undirected relationships in a UNION ALL of their orientations"
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn skip_and_limit_page_the_outermost_query() {
+    // The rows are paged after grouping and DISTINCT
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person)-[:KNOWS]->(b) RETURN a.name, count(*) AS known \
+             ORDER BY known DESC SKIP 1 LIMIT 1",
+            label_tables()
+        ),
+        "SELECT a.name, count(*) AS known FROM Person AS a \
+         INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN nodes AS b ON b.id = relationships.to_id \
+         WHERE relationships.type = 'KNOWS' GROUP BY a.name ORDER BY known DESC LIMIT 1 OFFSET 1"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN DISTINCT n.city LIMIT 2",
+            label_tables()
+        ),
+        "SELECT DISTINCT n.city FROM Person AS n LIMIT 2"
+    );
+
+    // The rows a WITH passes on are paged in its subquery, and the RETURN
+    // pages the query over them
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WITH n.city AS city, count(*) AS people \
+             ORDER BY people DESC LIMIT 3 RETURN city SKIP 1 LIMIT 1",
+            label_tables()
+        ),
+        "SELECT with_rows.city AS city FROM (SELECT n.city AS city, count(*) AS people \
+         FROM Person AS n GROUP BY n.city ORDER BY people DESC LIMIT 3) AS with_rows \
+         LIMIT 1 OFFSET 1"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WITH n SKIP 1 MATCH (n)-[:KNOWS]->(m) \
+             RETURN DISTINCT m.name LIMIT 2",
+            label_tables()
+        ),
+        "SELECT DISTINCT json_extract(m.properties, '$.name') AS name \
+         FROM (SELECT n.id AS n FROM Person AS n LIMIT -1 OFFSET 1) AS with_rows, \
+         Person AS n INNER JOIN relationships ON n.id = relationships.from_id \
+         INNER JOIN nodes AS m ON m.id = relationships.to_id \
+         WHERE n.id = with_rows.n AND relationships.type = 'KNOWS' LIMIT 2"
+    );
+
+    // The SKIP and LIMIT of the last arm page the whole UNION
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) RETURN a.name AS name \
+             UNION MATCH (c:Company) RETURN c.name AS name SKIP 1 LIMIT 2",
+            label_tables()
+        ),
+        "SELECT a.name AS name FROM Person AS a \
+         UNION SELECT c.name AS name FROM Company AS c LIMIT 2 OFFSET 1"
+    );
+}
+
+#[test]
+fn skip_and_limit_per_target() {
+    let sql = |paging: &str, target: SqlTarget, fetch_first: bool| {
+        let cypher = format!("MATCH (n:Person) RETURN n.name ORDER BY n.name {paging}");
+        let sql = transform_with(
+            &cypher,
+            TransformConfig {
+                target,
+                fetch_first,
+                ..label_tables()
+            },
+        );
+        sql.strip_prefix("SELECT n.name FROM Person AS n ORDER BY n.name ")
+            .unwrap_or_else(|| panic!("unexpected SQL: {sql}"))
+            .to_string()
+    };
+    // SQLite and MySQL take no OFFSET without a LIMIT, which is then one
+    // that keeps every row
+    let cases = [
+        (
+            SqlTarget::Sqlite,
+            ["LIMIT -1 OFFSET 5", "LIMIT 10", "LIMIT 10 OFFSET 5"],
+        ),
+        (
+            SqlTarget::MySql,
+            [
+                "LIMIT 18446744073709551615 OFFSET 5",
+                "LIMIT 10",
+                "LIMIT 10 OFFSET 5",
+            ],
+        ),
+        (
+            SqlTarget::Postgres,
+            ["OFFSET 5", "LIMIT 10", "LIMIT 10 OFFSET 5"],
+        ),
+        (
+            SqlTarget::DuckDb,
+            ["OFFSET 5", "LIMIT 10", "LIMIT 10 OFFSET 5"],
+        ),
+        (
+            SqlTarget::DataFusion,
+            ["OFFSET 5", "LIMIT 10", "LIMIT 10 OFFSET 5"],
+        ),
+    ];
+    for (target, expected) in cases {
+        for (paging, expected) in ["SKIP 5", "LIMIT 10", "SKIP 5 LIMIT 10"]
+            .into_iter()
+            .zip(expected)
+        {
+            assert_eq!(
+                sql(paging, target, false),
+                expected,
+                "{paging} on {target:?}"
+            );
+            // Only Postgres takes FETCH FIRST
+            if target != SqlTarget::Postgres {
+                assert_eq!(
+                    sql(paging, target, true),
+                    expected,
+                    "{paging} on {target:?}"
+                );
+            }
+        }
+    }
+    assert_eq!(sql("SKIP 5", SqlTarget::Postgres, true), "OFFSET 5 ROWS");
+    assert_eq!(
+        sql("LIMIT 10", SqlTarget::Postgres, true),
+        "FETCH FIRST 10 ROWS ONLY"
+    );
+    assert_eq!(
+        sql("SKIP 5 LIMIT 10", SqlTarget::Postgres, true),
+        "OFFSET 5 ROWS FETCH FIRST 10 ROWS ONLY"
+    );
+
+    // The parameters of FETCH FIRST are bound, and it is pretty printed on a
+    // line of its own
+    let config = TransformConfig {
+        target: SqlTarget::Postgres,
+        fetch_first: true,
+        param_style: ParamStyle::Numbered,
+        format: SqlFormat::Pretty { indent: 2 },
+        ..label_tables()
+    };
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN n.name SKIP $skip LIMIT $limit",
+            config
+        ),
+        "SELECT
+  n.name
+FROM
+  Person AS n
+OFFSET $1 ROWS
+FETCH FIRST $2 ROWS ONLY"
+    );
+}
+
+#[test]
+fn skip_and_limit_folding() {
+    let sql = |paging: &str| {
+        let cypher = format!("MATCH (n:Person) RETURN n.name {paging}");
+        try_transform_with(&cypher, label_tables()).map(|sql| {
+            sql.strip_prefix("SELECT n.name FROM Person AS n ")
+                .unwrap_or_else(|| panic!("unexpected SQL: {sql}"))
+                .to_string()
+        })
+    };
+    // Arithmetic of integers is folded to the number of rows, dividing as
+    // Cypher does
+    assert_eq!(sql("SKIP 2 * 3 LIMIT 10 - 2").unwrap(), "LIMIT 8 OFFSET 6");
+    assert_eq!(
+        sql("SKIP (7 % 4) LIMIT -(1 - 6) / 2").unwrap(),
+        "LIMIT 2 OFFSET 3"
+    );
+    assert_eq!(sql("LIMIT 0x10").unwrap(), "LIMIT 16");
+    // Parameters stay placeholders
+    assert_eq!(
+        sql("SKIP $skip LIMIT $limit").unwrap(),
+        "LIMIT $limit OFFSET $skip"
+    );
+
+    for (paging, error) in [
+        (
+            "LIMIT 10 - 12",
+            "LIMIT must be a non-negative integer, got -2",
+        ),
+        ("SKIP 2.5", "SKIP must be a non-negative integer, got 2.5"),
+        (
+            "LIMIT 2.0 * 3",
+            "LIMIT must be a non-negative integer, got 2.0 * 3",
+        ),
+        (
+            "LIMIT 10 / 0",
+            "LIMIT must be a non-negative integer, got 10 / 0",
+        ),
+        (
+            "LIMIT 9223372036854775807 + 1",
+            "LIMIT must be a non-negative integer, got 9223372036854775807 + 1",
+        ),
+        (
+            "LIMIT n.size",
+            "LIMIT must be a constant integer or a parameter, got n.size",
+        ),
+        (
+            "SKIP $skip + 1",
+            "SKIP must be a constant integer or a parameter, got $skip + 1",
+        ),
+    ] {
+        assert_eq!(
+            sql(paging),
+            Err(TransformError::InvalidQuery(error.to_string())),
+            "{paging}"
+        );
+    }
+    // Strict mode rejects the Cypher function before the LIMIT is read
+    let cypher = "MATCH (n:Person) RETURN n.name LIMIT toInteger('3')";
+    assert_eq!(
+        try_transform_with(cypher, label_tables()),
+        Err(TransformError::Unsupported(
+            "function without a SQL counterpart in toInteger('3')".to_string()
+        ))
+    );
+    let lenient = TransformConfig {
+        strict: false,
+        ..label_tables()
+    };
+    assert_eq!(
+        try_transform_with(cypher, lenient),
+        Err(TransformError::InvalidQuery(
+            "LIMIT must be a constant integer or a parameter, got toInteger('3')".to_string()
+        ))
+    );
+}
+
+#[test]
+fn relationship_directions() {
+    let sql = |left: &str, right: &str, config: TransformConfig| {
+        let cypher = format!("MATCH (a){left}[r:LINKS]{right}(b) RETURN b.name");
+        transform_with(&cypher, config)
+    };
+    assert_eq!(
+        sql("-", "->", generic_tables()),
+        "SELECT json_extract(b.properties, '$.name') AS name \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id WHERE r.type = 'LINKS'"
+    );
+    assert_eq!(
+        sql("<-", "-", generic_tables()),
+        "SELECT json_extract(b.properties, '$.name') AS name \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.to_id \
+         INNER JOIN nodes AS b ON b.id = r.from_id WHERE r.type = 'LINKS'"
+    );
+    assert_eq!(
+        sql("-", "-", generic_tables()),
+        "SELECT json_extract(b.properties, '$.name') AS name \
+         FROM nodes AS a INNER JOIN relationships AS r ON (a.id = r.from_id OR a.id = r.to_id) \
+         INNER JOIN nodes AS b \
+         ON ((a.id = r.from_id AND b.id = r.to_id) OR (b.id = r.from_id AND a.id = r.to_id)) \
+         WHERE r.type = 'LINKS'"
+    );
+    // Both arrows match a relationship from `a` to `b` with one of the same
+    // type back
+    assert_eq!(
+        sql("<-", "->", generic_tables()),
+        "SELECT json_extract(b.properties, '$.name') AS name \
+         FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id WHERE r.type = 'LINKS' \
+         AND EXISTS (SELECT 1 FROM relationships AS r_reverse \
+         WHERE b.id = r_reverse.from_id AND a.id = r_reverse.to_id AND r_reverse.type = r.type)"
+    );
+    let quoted = TransformConfig {
+        quoting: IdentifierQuoting::Always,
+        ..generic_tables()
+    };
+    assert!(sql("<-", "->", quoted).ends_with(
+        "EXISTS (SELECT 1 FROM \"relationships\" AS \"r_reverse\" \
+         WHERE \"b\".\"id\" = \"r_reverse\".\"from_id\" AND \"a\".\"id\" = \"r_reverse\".\"to_id\" \
+         AND \"r_reverse\".\"type\" = \"r\".\"type\")"
+    ));
+
+    // Or a relationship in either direction, with a warning
+    let undirected = TransformConfig {
+        both_directions: BothDirections::Undirected,
+        strict: false,
+        ..generic_tables()
+    };
+    assert_eq!(
+        sql("<-", "->", undirected.clone()),
+        sql("-", "-", generic_tables())
+    );
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql("MATCH (a)<-[r:LINKS]->(b) RETURN b.name")
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let warnings = |config| {
+        CypherToSqlTransformer::with_config(config)
+            .to_sql_output(&stmt)
+            .unwrap()
+            .warnings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        warnings(undirected),
+        ["relationship with both arrows is not translated: <-[r:LINKS]->"]
+    );
+    assert!(warnings(generic_tables()).is_empty());
+}
+
+#[test]
+fn id_lookups() {
+    assert_eq!(
+        transform_with(
+            "MATCH (n) WHERE id(n) = 123 RETURN n.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n WHERE n.id = 123"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE id(n) IN [1, 2, 3] AND 4 <> id(n) RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n WHERE n.id IN (1, 2, 3) AND 4 <> n.id"
+    );
+
+    let text_ids = TransformConfig {
+        id_column: "uid".to_string(),
+        id_type: IdType::Text,
+        ..generic_tables()
+    };
+    assert_eq!(
+        transform_with(
+            "MATCH (n)-[r]->(m) WHERE elementId(n) = 'a1' AND id(m) <> 7 RETURN id(m)",
+            text_ids
+        ),
+        "SELECT CAST(m.uid AS INTEGER) \
+         FROM nodes AS n \
+         INNER JOIN relationships AS r ON n.uid = r.from_id \
+         INNER JOIN nodes AS m ON m.uid = r.to_id \
+         WHERE n.uid = 'a1' AND m.uid <> '7'"
+    );
+
+    // String ids of an integer column are converted when they are numbers
+    assert_eq!(
+        transform_with(
+            "MATCH (n) WHERE elementId(n) IN ['42', '43'] OR elementId(n) = '4:x' RETURN n.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE n.id IN (42, 43) OR CAST(n.id AS TEXT) = '4:x'"
+    );
+}
+
+#[test]
+fn string_literals() {
+    let cypher =
+        r#"MATCH (n:Person) WHERE n.name = "O'Brien" OR n.path = 'C:\\temp' RETURN n.name"#;
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        r"SELECT n.name FROM Person AS n WHERE n.name = 'O''Brien' OR n.path = 'C:\temp'"
+    );
+    // MySQL reads backslashes in strings as escapes
+    let mysql = TransformConfig {
+        target: SqlTarget::MySql,
+        ..label_tables()
+    };
+    assert_eq!(
+        transform_with(cypher, mysql),
+        r"SELECT n.name FROM Person AS n WHERE n.name = 'O''Brien' OR n.path = 'C:\\temp'"
+    );
+}
+
+#[test]
+fn numeric_literals() {
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Flag) WHERE n.bits = 0xFF OR n.mode > -0o755 RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Flag AS n WHERE n.bits = 255 OR n.mode > -493"
+    );
+
+    // A minus before a number or an expression negates it, in a property
+    // map as anywhere else
+    assert_eq!(
+        transform_with(
+            "CREATE (a:Point {x: -3, y: -0.5})-[:NEXT {dx:-1}]->(b:Point {x: -4.25})",
+            generic_tables()
+        ),
+        "INSERT INTO nodes (label, properties) VALUES ('Point', '{\"x\":-3,\"y\":-0.5}');\n\
+         INSERT INTO nodes (label, properties) VALUES ('Point', '{\"x\":-4.25}');\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) \
+         VALUES ($a, $b, 'NEXT', '{\"dx\":-1}')"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Point) WHERE n.x>-3 AND n.y = -0.5 RETURN -(n.x) AS a, -n.y AS b, 1 - -n.x",
+            label_tables()
+        ),
+        "SELECT -(n.x) AS a, -n.y AS b, 1 - -n.x FROM Point AS n WHERE n.x > -3 AND n.y = -0.5"
+    );
+    for (cypher, error) in [
+        (
+            "MATCH (n) RETURN n SKIP -1",
+            "SKIP must be a non-negative integer, got -1",
+        ),
+        (
+            "MATCH (n) WITH n LIMIT -2 RETURN n",
+            "LIMIT must be a non-negative integer, got -2",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, label_tables()),
+            Err(TransformError::InvalidQuery(error.to_string())),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parameter_styles() {
+    let cypher = "MATCH (n) WHERE n.age > $min AND n.score > $min RETURN n.name LIMIT $limit";
+    let with_style = |param_style| TransformConfig {
+        param_style,
+        ..label_tables()
+    };
+    assert_eq!(
+        transform_with(cypher, with_style(ParamStyle::Inline)),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE json_extract(n.properties, '$.age') > $min \
+         AND json_extract(n.properties, '$.score') > $min LIMIT $limit"
+    );
+    assert_eq!(
+        transform_with(cypher, with_style(ParamStyle::Question)),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE json_extract(n.properties, '$.age') > ? \
+         AND json_extract(n.properties, '$.score') > ? LIMIT ?"
+    );
+    assert_eq!(
+        transform_with(cypher, with_style(ParamStyle::Numbered)),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE json_extract(n.properties, '$.age') > $1 \
+         AND json_extract(n.properties, '$.score') > $1 LIMIT $2"
+    );
+}
+
+#[test]
+fn parameter_manifest() {
+    let cypher = "MATCH (n) WHERE n.age > $min AND n.score > $min RETURN n.name LIMIT $limit";
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql(cypher)
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let parameters = |param_style| {
+        let config = TransformConfig {
+            param_style,
+            ..generic_tables()
+        };
+        CypherToSqlTransformer::with_config(config)
+            .to_sql_output(&stmt)
+            .unwrap()
+            .parameters
+    };
+    let binding = |name: &str, placeholder: &str, index, count| ParamBinding {
+        name: name.to_string(),
+        placeholder: placeholder.to_string(),
+        index,
+        count,
+    };
+
+    // Named and numbered parameters are shared by repeated occurrences
+    assert_eq!(
+        parameters(ParamStyle::Inline),
+        vec![
+            binding("min", "$min", None, 2),
+            binding("limit", "$limit", None, 1),
+        ]
+    );
+    assert_eq!(
+        parameters(ParamStyle::Numbered),
+        vec![
+            binding("min", "$1", Some(1), 2),
+            binding("limit", "$2", Some(2), 1),
+        ]
+    );
+    // Every `?` is a parameter of its own
+    assert_eq!(
+        parameters(ParamStyle::Question),
+        vec![
+            binding("min", "?", Some(1), 1),
+            binding("min", "?", Some(2), 1),
+            binding("limit", "?", Some(3), 1),
+        ]
+    );
+}
+
+#[test]
+fn inline_parameters() {
+    let string = |s: &str| ParamValue::String(s.to_string());
+    let number = |n: &str| ParamValue::Number(n.to_string());
+    let params = BTreeMap::from([
+        ("name".to_string(), string("it's \\ Ada")),
+        ("min".to_string(), number("-1.5")),
+        ("limit".to_string(), number("10")),
+        ("active".to_string(), ParamValue::Boolean(true)),
+        ("none".to_string(), ParamValue::Null),
+        (
+            "cities".to_string(),
+            ParamValue::List(vec![string("London"), string("Paris")]),
+        ),
+        (
+            "person".to_string(),
+            ParamValue::Map(BTreeMap::from([
+                ("name".to_string(), string("Bob")),
+                ("age".to_string(), number("25")),
+            ])),
+        ),
+    ]);
+    let inline = |cypher: &str| {
+        transform_cypher_to_sql_with_params(cypher, &params, label_tables()).map(|output| {
+            let unused: Vec<String> = output
+                .warnings
+                .iter()
+                .filter(|warning| warning.kind == WarningKind::UnusedParameter)
+                .map(|warning| warning.feature.clone())
+                .collect();
+            (output.sql, unused.len())
+        })
+    };
+
+    let (sql, unused) = inline(
+        "MATCH (n:Person) WHERE n.name = $name AND n.score > $min AND n.active = $active \
+         AND n.city IN $cities AND n.nickname <> $none RETURN n.age LIMIT $limit",
+    )
+    .unwrap();
+    assert_eq!(
+        sql,
+        "SELECT n.age FROM Person AS n WHERE n.name = 'it''s \\ Ada' AND n.score > -1.5 \
+         AND n.active = 1 AND n.city IN ('London', 'Paris') AND n.nickname <> NULL LIMIT 10"
+    );
+    assert_eq!(unused, 1);
+
+    // Lists and maps are stored as JSON, and the properties of a map are
+    // looked up
+    let sql = transform_cypher_to_sql_with_params(
+        "CREATE (n:Person {name: $name, tags: $cities, friend: $person})",
+        &params,
+        generic_tables(),
+    )
+    .unwrap()
+    .sql;
+    assert_eq!(
+        sql,
+        "INSERT INTO nodes (label, properties) VALUES ('Person', \
+         '{\"name\":\"it''s \\\\ Ada\",\"tags\":[\"London\",\"Paris\"],\
+         \"friend\":{\"age\":25,\"name\":\"Bob\"}}')"
+    );
+    // A map parameter can be the properties of a created node
+    let (sql, _) = inline("CREATE (n:Person $person)").unwrap();
+    assert_eq!(sql, "INSERT INTO Person (age, name) VALUES (25, 'Bob')");
+    let (sql, _) =
+        inline("MATCH (n:Person) WHERE n.name = $person.name AND n.age = $person.height RETURN n")
+            .unwrap();
+    assert!(
+        sql.ends_with("WHERE n.name = 'Bob' AND n.age = NULL"),
+        "{sql}"
+    );
+
+    // Every unused value is a warning
+    let output = transform_cypher_to_sql_with_params(
+        "MATCH (n:Person) RETURN n.name LIMIT $limit",
+        &params,
+        label_tables(),
+    )
+    .unwrap();
+    assert_eq!(output.warnings.len(), params.len() - 1);
+    assert_eq!(
+        output.warnings[0].to_string(),
+        "parameter $active is not used by MATCH (n:Person) RETURN n.name LIMIT $limit"
+    );
+
+    // Parameters without a value are all named
+    let error = inline("MATCH (n) WHERE n.a = $b AND n.c = $a AND n.d = $b RETURN n").unwrap_err();
+    assert_eq!(
+        error,
+        TransformError::MissingParameters(vec!["a".to_string(), "b".to_string()])
+    );
+    assert_eq!(error.to_string(), "missing values for $a, $b");
+    assert_eq!(
+        inline("MATCH (n) RETURN n SKIP $skip")
+            .unwrap_err()
+            .to_string(),
+        "missing value for $skip"
+    );
+    // Inlined values are checked like literals
+    let params = BTreeMap::from([("limit".to_string(), number("-2"))]);
+    assert_eq!(
+        transform_cypher_to_sql_with_params(
+            "MATCH (n) RETURN n LIMIT $limit",
+            &params,
+            label_tables()
+        ),
+        Err(TransformError::InvalidQuery(
+            "LIMIT must be a non-negative integer, got -2".to_string()
+        ))
+    );
+    let params = BTreeMap::from([("limit".to_string(), number("ten"))]);
+    assert_eq!(
+        transform_cypher_to_sql_with_params(
+            "MATCH (n) RETURN n LIMIT $limit",
+            &params,
+            label_tables()
+        ),
+        Err(TransformError::InvalidQuery(
+            "parameter $limit is not a number: ten".to_string()
+        ))
+    );
+}
+
+#[test]
+fn transform_cypher_to_sql_reports_parse_errors() {
+    assert_eq!(
+        transform_cypher_to_sql("MATCH (n RETURN n"),
+        Err(TransformError::Parse(ParserError::ParserError(
+            "Expected: ), found: RETURN at Line: 1, Column: 10".to_string()
+        )))
+    );
+    assert_eq!(
+        transform_cypher_to_sql("MATCH (n) RETURN 'n").map_err(|e| e.to_string()),
+        Err("sql parser error: Unterminated string literal at Line: 1, Column: 18".to_string())
+    );
+}
+
+#[test]
+fn transform_cypher_script_statements() {
+    let script = "MATCH (n:Person) RETURN n.name;\nMATCH (n RETURN n;\nMATCH (m) RETURN m.age\n";
+    assert_eq!(
+        transform_cypher_script(script).collect::<Vec<_>>(),
+        [
+            Ok(
+                "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+                WHERE n.label = 'Person'"
+                    .to_string()
+            ),
+            Err(TransformError::Parse(ParserError::ParserError(
+                "Expected: ), found: RETURN at Line: 2, Column: 10".to_string()
+            ))),
+            Ok("SELECT json_extract(m.properties, '$.age') AS age FROM nodes AS m".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn transform_cypher_script_directives() {
+    let script = ":param person => {name: 'Ada', scores: [1, -2.5]};\n\
+                  :param active => true\n\
+                  :begin\n\
+                  MATCH (n) WHERE n.name = $person.name AND n.active = $active \
+                  AND n.age > $min RETURN n.name;\n\
+                  :commit\n\
+                  :use movies\n\
+                  :param now => datetime()\n\
+                  :sysinfo\n\
+                  MATCH (n) WHERE n.score IN $person.scores RETURN n.name";
+    assert_eq!(
+        transform_cypher_script(script).collect::<Vec<_>>(),
+        [
+            Ok("BEGIN".to_string()),
+            // Parameters no directive sets are left to be bound
+            Ok(
+                "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+                 WHERE json_extract(n.properties, '$.name') = 'Ada' \
+                 AND json_extract(n.properties, '$.active') = 1 \
+                 AND json_extract(n.properties, '$.age') > $min"
+                    .to_string()
+            ),
+            Ok("COMMIT".to_string()),
+            Err(TransformError::InvalidQuery(
+                "the value of :param now is not a literal: datetime()".to_string()
+            )),
+            Ok(
+                "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+                 WHERE json_extract(n.properties, '$.score') IN (1, -2.5)"
+                    .to_string()
+            ),
+        ]
+    );
+
+    // The directives without SQL are reported by the script transformer
+    let mut transformer = ScriptTransformer::new(TransformConfig {
+        strict: false,
+        ..generic_tables()
+    });
+    let mut items = CypherStatementIterator::new(script.split_inclusive('\n'));
+    let mut warnings = vec![];
+    while let Some(item) = items.next_item() {
+        if let Ok(output) = transformer.transform(&item.unwrap()) {
+            warnings.extend(output.warnings.iter().map(ToString::to_string));
+        }
+    }
+    assert_eq!(
+        warnings,
+        [
+            ":use is not translated: :use movies",
+            "unknown directive :sysinfo is skipped: :sysinfo"
+        ]
+    );
+    assert_eq!(
+        transformer.parameters().get("active"),
+        Some(&ParamValue::Boolean(true))
+    );
+}
+
+#[test]
+fn empty_input() {
+    for input in ["", "   \n\t", ";", "-- nothing to see\n/* here */"] {
+        assert_eq!(
+            transform_cypher_to_sql(input),
+            Err(TransformError::EmptyInput),
+            "{input:?}"
+        );
+        assert_eq!(
+            transform_cypher_script(input).collect::<Vec<_>>(),
+            [],
+            "{input:?}"
+        );
+    }
+    assert_eq!(
+        TransformError::EmptyInput.to_string(),
+        "empty Cypher statement"
+    );
+}
+
+#[test]
+fn deeply_nested_expressions_are_rejected() {
+    // Long operator chains parse without recursing, but used to overflow the
+    // stack while being transformed
+    let chain = |terms: usize| {
+        format!(
+            "MATCH (n) WHERE {}n.a = 0 RETURN n.a",
+            "n.a = 1 AND ".repeat(terms - 1)
+        )
+    };
+    assert_eq!(
+        try_transform_with(&chain(2000), generic_tables()),
+        Err(TransformError::RecursionLimitExceeded)
+    );
+    assert_eq!(
+        try_transform_with(
+            &format!("MATCH (n) RETURN {}1", "1 + ".repeat(2000)),
+            generic_tables()
+        ),
+        Err(TransformError::RecursionLimitExceeded)
+    );
+
+    // An AND chain of n conditions nests n + 1 levels deep
+    let limited = |recursion_limit| TransformConfig {
+        recursion_limit,
+        ..generic_tables()
+    };
+    assert!(try_transform_with(&chain(100), limited(101)).is_ok());
+    assert_eq!(
+        try_transform_with(&chain(100), limited(100)),
+        Err(TransformError::RecursionLimitExceeded)
+    );
+}
+
+#[test]
+fn transform_cypher_to_sql_examples() {
+    for (cypher, sql) in [
+        (
+            "MATCH (n:Person) RETURN n.name",
+            "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+             WHERE n.label = 'Person'",
+        ),
+        (
+            "MATCH (n:User) RETURN n.email",
+            "SELECT json_extract(n.properties, '$.email') AS email FROM nodes AS n \
+             WHERE n.label = 'User'",
+        ),
+        (
+            "MATCH (n) RETURN n.id",
+            "SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n",
+        ),
+        (
+            "MATCH (n:Person)-[r:KNOWS]->(m:Person) RETURN n.name, m.name",
+            "SELECT json_extract(n.properties, '$.name') AS n_name, \
+             json_extract(m.properties, '$.name') AS m_name \
+             FROM nodes AS n \
+             INNER JOIN relationships AS r ON n.id = r.from_id \
+             INNER JOIN nodes AS m ON m.id = r.to_id \
+             WHERE n.label = 'Person' AND r.type = 'KNOWS' AND m.label = 'Person'",
+        ),
+        (
+            "CREATE (n:Person)",
+            "INSERT INTO nodes (label, properties) VALUES ('Person', '{}')",
+        ),
+    ] {
+        assert_eq!(
+            transform_cypher_to_sql(cypher).as_deref(),
+            Ok(sql),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn create_nodes() {
+    assert_eq!(
+        transform_with("CREATE (a:Person), (b)", generic_tables()),
+        "INSERT INTO nodes (label, properties) VALUES ('Person', '{}');\n\
+         INSERT INTO nodes (label, properties) VALUES (NULL, '{}')"
+    );
+    assert_eq!(
+        transform_with("CREATE (a:Person), (b)", label_tables()),
+        "INSERT INTO Person DEFAULT VALUES;\n\
+         INSERT INTO nodes (label, properties) VALUES (NULL, '{}')"
+    );
+}
+
+#[test]
+fn create_chains() {
+    // Nodes are inserted first, and relationships take the ids of their
+    // endpoints as parameters named after the nodes
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql("CREATE (:A)-[:R]->(b:B {n: 1})<-[:R2 {w: 2}]-(:C)")
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let config = TransformConfig {
+        param_style: ParamStyle::Question,
+        ..generic_tables()
+    };
+    let output = CypherToSqlTransformer::with_config(config)
+        .to_sql_output(&stmt)
+        .unwrap();
+    assert_eq!(
+        output.sql,
+        "INSERT INTO nodes (label, properties) VALUES ('A', '{}');\n\
+         INSERT INTO nodes (label, properties) VALUES ('B', '{\"n\":1}');\n\
+         INSERT INTO nodes (label, properties) VALUES ('C', '{}');\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) VALUES (?, ?, 'R', '{}');\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) VALUES (?, ?, 'R2', '{\"w\":2}')"
+    );
+    let created = |variable: &str, synthetic, statement| CreatedNode {
+        variable: variable.to_string(),
+        synthetic,
+        statement,
+    };
+    assert_eq!(
+        output.created_nodes,
+        [
+            created("node_1", true, 0),
+            created("b", false, 1),
+            created("node_2", true, 2)
+        ]
+    );
+    let names: Vec<_> = output
+        .parameters
+        .iter()
+        .map(|binding| (binding.name.as_str(), binding.index))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("node_1", Some(1)),
+            ("b", Some(2)),
+            ("node_2", Some(1)),
+            ("b", Some(2))
+        ]
+    );
+
+    // A variable refers to the node it was created as
+    assert_eq!(
+        transform_with(
+            "CREATE (a:A), (node_1:B), (a)-[:R]->(:C)-[:R]->(node_1)",
+            generic_tables()
+        ),
+        "INSERT INTO nodes (label, properties) VALUES ('A', '{}');\n\
+         INSERT INTO nodes (label, properties) VALUES ('B', '{}');\n\
+         INSERT INTO nodes (label, properties) VALUES ('C', '{}');\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) VALUES ($a, $node_2, 'R', '{}');\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) VALUES ($node_2, $node_1, 'R', '{}')"
+    );
+    // A node is created by its first element, and later bare elements with
+    // its variable connect to it, in any of the patterns
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql(
+            "CREATE (a:Person {name:'A'})-[:KNOWS]->(b:Person {name:'B'}), \
+             (a)-[:KNOWS]->(c:Person {name:'C'})",
+        )
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let output = CypherToSqlTransformer::with_config(label_tables())
+        .to_sql_output(&stmt)
+        .unwrap();
+    assert_eq!(
+        output.sql,
+        "INSERT INTO Person (name) VALUES ('A');\n\
+         INSERT INTO Person (name) VALUES ('B');\n\
+         INSERT INTO Person (name) VALUES ('C');\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) VALUES ($a, $b, 'KNOWS', '{}');\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) VALUES ($a, $c, 'KNOWS', '{}')"
+    );
+    assert_eq!(
+        output.created_nodes,
+        [
+            created("a", false, 0),
+            created("b", false, 1),
+            created("c", false, 2)
+        ]
+    );
+    for (cypher, variable) in [
+        ("CREATE (a:A)-[r:R]->(b:B), (a)-[r:R]->(b)", "r"),
+        ("CREATE (a:A)-[r:R]->(b:B), (r:C)", "r"),
+        ("CREATE (a:A)-[a:R]->(b:B)", "a"),
+        ("CREATE (a:A), (a:A)", "a"),
+    ] {
+        let error = try_transform_with(cypher, generic_tables()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("Variable `{variable}` is created more than once")),
+            "{cypher}: {error}"
+        );
+    }
+    assert_eq!(
+        try_transform_with("CREATE (a)-[:KNOWS]-(b)", generic_tables()),
+        Err(TransformError::InvalidQuery(
+            "A relationship in CREATE must have a direction, found -[:KNOWS]-".to_string()
+        ))
+    );
+}
+
+#[test]
+fn zero_argument_functions() {
+    // Passed through functions are warned about rather than rejected
+    let on = |target| TransformConfig {
+        target,
+        strict: false,
+        ..generic_tables()
+    };
+    let create = "CREATE (n:Event {at: timestamp(), tags: ['a'], ok: true})";
+    assert_eq!(
+        transform_with(create, on(SqlTarget::Sqlite)),
+        "INSERT INTO nodes (label, properties) VALUES ('Event', \
+         json_object('at', (strftime('%s', 'now') * 1000), 'tags', json('[\"a\"]'), \
+         'ok', json('true')))"
+    );
+    assert_eq!(
+        transform_with(create, on(SqlTarget::Postgres)),
+        "INSERT INTO nodes (label, properties) VALUES ('Event', \
+         json_build_object('at', (EXTRACT(EPOCH FROM now()) * 1000), \
+         'tags', CAST('[\"a\"]' AS JSON), 'ok', true))"
+    );
+    assert_eq!(
+        transform_with(
+            "CREATE (n:Event {id: randomUUID(), at: datetime(), on: date()})",
+            on(SqlTarget::MySql)
+        ),
+        "INSERT INTO nodes (label, properties) VALUES ('Event', \
+         json_object('id', uuid(), 'at', now(), 'on', date()))"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n) RETURN timestamp(), datetime()",
+            on(SqlTarget::DuckDb)
+        ),
+        "SELECT (epoch(now()) * 1000), now() FROM nodes AS n"
+    );
+    assert_eq!(
+        try_transform_with("CREATE ({id: randomUUID()})", on(SqlTarget::Sqlite)),
+        Err(TransformError::Unsupported(
+            "randomUUID() on SQLite".to_string()
+        ))
+    );
+
+    // Other functions without arguments are passed through with a warning
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql("MATCH (n) RETURN date(), timestamp()")
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let output = CypherToSqlTransformer::with_config(on(SqlTarget::Sqlite))
+        .to_sql_output(&stmt)
+        .unwrap();
+    assert_eq!(
+        output.sql,
+        "SELECT date(), (strftime('%s', 'now') * 1000) FROM nodes AS n"
+    );
+    assert_eq!(
+        output
+            .warnings
+            .iter()
+            .map(|warning| (warning.feature.as_str(), warning.source_text.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("function without arguments", "date()")]
+    );
+}
+
+#[test]
+fn match_create_relationships() {
+    // Matched endpoints are selected from the rows of the MATCH
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person), (b:Person) WHERE a.name = 'A' AND b.name = 'B' \
+             CREATE (a)-[:KNOWS {since: 2024}]->(b), (a)<-[:FOLLOWS]-(b)",
+            generic_tables()
+        ),
+        "INSERT INTO relationships (from_id, to_id, type, properties) \
+         SELECT a.id, b.id, 'KNOWS', '{\"since\":2024}' FROM nodes AS a, nodes AS b \
+         WHERE a.label = 'Person' AND b.label = 'Person' \
+         AND json_extract(a.properties, '$.name') = 'A' \
+         AND json_extract(b.properties, '$.name') = 'B';\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) \
+         SELECT b.id, a.id, 'FOLLOWS', '{}' FROM nodes AS a, nodes AS b \
+         WHERE a.label = 'Person' AND b.label = 'Person' \
+         AND json_extract(a.properties, '$.name') = 'A' \
+         AND json_extract(b.properties, '$.name') = 'B'"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person)-[:KNOWS]->(b:Person) CREATE (b)-[:KNOWS]->(a)",
+            label_tables()
+        ),
+        "INSERT INTO relationships (from_id, to_id, type, properties) \
+         SELECT b.id, a.id, 'KNOWS', '{}' FROM Person AS a \
+         INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN Person AS b ON b.id = relationships.to_id \
+         WHERE relationships.type = 'KNOWS'"
+    );
+    // Property maps select the matched nodes as WHERE would
+    let cypher = "MATCH (a:Person {name:'A'}), (b:Person {name:'B'}) \
+                  CREATE (a)-[:KNOWS {since: 2024}]->(b)";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "INSERT INTO relationships (from_id, to_id, type, properties) \
+         SELECT a.id, b.id, 'KNOWS', '{\"since\":2024}' FROM nodes AS a, nodes AS b \
+         WHERE a.label = 'Person' AND json_extract(a.properties, '$.name') = 'A' \
+         AND b.label = 'Person' AND json_extract(b.properties, '$.name') = 'B'"
+    );
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        "INSERT INTO relationships (from_id, to_id, type, properties) \
+         SELECT a.id, b.id, 'KNOWS', '{\"since\":2024}' FROM Person AS a, Person AS b \
+         WHERE a.name = 'A' AND b.name = 'B'"
+    );
+    // A node on its own is created for each row
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) CREATE (:Audit {note: 'x'})",
+            generic_tables()
+        ),
+        "INSERT INTO nodes (label, properties) \
+         SELECT 'Audit', '{\"note\":\"x\"}' FROM nodes AS a WHERE a.label = 'Person'"
+    );
+
+    // A new endpoint gets its id in a CTE of the matched rows
+    let config = TransformConfig {
+        target: SqlTarget::Postgres,
+        id_type: IdType::Uuid,
+        ..generic_tables()
+    };
+    let cypher = "MATCH (a:Person) WHERE a.name = 'A' CREATE (a)-[:OWNS]->(:Car {make: 'VW'})";
+    assert_eq!(
+        transform_with(cypher, config.clone()),
+        "WITH matched AS (SELECT a.id AS a_id, gen_random_uuid() AS node_1_id FROM nodes AS a \
+         WHERE a.label = 'Person' AND a.properties ->> 'name' = 'A'), \
+         created_node_1 AS (INSERT INTO nodes (id, label, properties) \
+         SELECT node_1_id, 'Car', '{\"make\":\"VW\"}' FROM matched) \
+         INSERT INTO relationships (from_id, to_id, type, properties) \
+         SELECT a_id, node_1_id, 'OWNS', '{}' FROM matched"
+    );
+    assert_eq!(
+        try_transform_with(
+            cypher,
+            TransformConfig {
+                id_type: IdType::Integer,
+                ..config
+            }
+        ),
+        Err(TransformError::Unsupported(
+            "a relationship between a matched node and the created node (:Car {make: 'VW'}) \
+             without ids generated by the transformer, as with IdType::Uuid"
+                .to_string()
+        ))
+    );
+    assert_eq!(
+        try_transform_with(cypher, generic_tables()),
+        Err(TransformError::Unsupported(
+            "a relationship between a matched node and the created node (:Car {make: 'VW'}) \
+             on Sqlite, which cannot insert in a WITH query"
+                .to_string()
+        ))
+    );
+
+    for (cypher, error) in [
+        (
+            "MATCH (a:Person) CREATE (a)-[:KNOWS]->(b)",
+            "Variable `b` in CREATE (a)-[:KNOWS]->(b) is not bound by MATCH; \
+             give it a label or properties to create a new node",
+        ),
+        (
+            "MATCH (a:Person) CREATE (a:Admin)-[:KNOWS]->(b:Person)",
+            "Variable `a` is bound by MATCH, so CREATE (a:Admin) cannot give it labels or properties",
+        ),
+        (
+            "MATCH (a)-[r]->(b) CREATE (a)-[r:KNOWS]->(b)",
+            "Variable `r` is bound by MATCH, so CREATE (a)-[r:KNOWS]->(b) cannot create it",
+        ),
+        (
+            "MATCH (a), (b) CREATE (a)-[:KNOWS]-(b)",
+            "A relationship in CREATE must have a direction, found -[:KNOWS]-",
+        ),
+        (
+            "MATCH (a), (b) CREATE (a)-[:KNOWS|LIKES]->(b)",
+            "A relationship in CREATE must have a single type and length, found -[:KNOWS|LIKES]->",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables()),
+            Err(TransformError::InvalidQuery(error.to_string())),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn match_property_maps() {
+    // Anonymous elements are referred to by their tables
+    assert_eq!(
+        transform_with(
+            "MATCH (:Person {name: 'x'})-[:KNOWS {since: 2020}]->(m) RETURN m.name",
+            label_tables()
+        ),
+        "SELECT json_extract(m.properties, '$.name') AS name FROM Person \
+         INNER JOIN relationships ON Person.id = relationships.from_id \
+         INNER JOIN nodes AS m ON m.id = relationships.to_id \
+         WHERE Person.name = 'x' AND relationships.type = 'KNOWS' \
+         AND json_extract(relationships.properties, '$.since') = 2020"
+    );
+    // The map of an OPTIONAL MATCH constrains its join
+    assert_eq!(
+        transform_with(
+            "MATCH (a) OPTIONAL MATCH (a)-->(b {name: a.name}) RETURN b.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(b.properties, '$.name') AS name FROM nodes AS a \
+         LEFT JOIN (relationships INNER JOIN nodes AS b ON b.id = relationships.to_id) \
+         ON a.id = relationships.from_id \
+         AND json_extract(b.properties, '$.name') = json_extract(a.properties, '$.name')"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person {name: 'x'}) SET n.age = 3",
+            generic_tables()
+        ),
+        "UPDATE nodes AS n SET properties = json_patch(n.properties, json_object('age', 3)) \
+         WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = 'x'"
+    );
+}
+
+#[test]
+fn create_node_properties() {
+    assert_eq!(
+        transform_with(
+            r#"CREATE (a:Person {name: "Ada \"Countess\" King", born: 1815, score: -.5, tags: ['math'], title: null})"#,
+            generic_tables()
+        ),
+        r#"INSERT INTO nodes (label, properties) VALUES ('Person', '{"name":"Ada \"Countess\" King","born":1815,"score":-0.5,"tags":["math"]}')"#
+    );
+    assert_eq!(
+        try_transform_with("CREATE (a:Person {name: $name})", generic_tables()),
+        Err(TransformError::Unsupported(
+            "non-literal property value $name in a CREATE pattern".to_string()
+        ))
+    );
+
+    // A label table has a column per property key
+    assert_eq!(
+        transform_with("CREATE (n:Person {name: 'Ada', age: 36})", label_tables()),
+        "INSERT INTO Person (name, age) VALUES ('Ada', 36)"
+    );
+    assert_eq!(
+        transform_with("CREATE (n:Person)", label_tables()),
+        "INSERT INTO Person DEFAULT VALUES"
+    );
+    assert_eq!(
+        transform_with(
+            "CREATE (n:Person {name: 'Ada', title: null, active: true, tags: ['math'], \
+             address: {city: 'London'}, joined: timestamp()})",
+            label_tables()
+        ),
+        "INSERT INTO Person (name, active, tags, address, joined) \
+         VALUES ('Ada', 1, json('[\"math\"]'), json('{\"city\":\"London\"}'), \
+         (strftime('%s', 'now') * 1000))"
+    );
+    assert_eq!(
+        transform_with(
+            "CREATE (n:Person {name: 'Ada', active: true, tags: ['math']})",
+            TransformConfig {
+                target: SqlTarget::Postgres,
+                ..label_tables()
+            }
+        ),
+        "INSERT INTO Person (name, active, tags) VALUES ('Ada', true, CAST('[\"math\"]' AS JSON))"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) WHERE a.name = 'Ada' CREATE (p:Pet {name: 'Rex'})",
+            label_tables()
+        ),
+        "INSERT INTO Pet (name) SELECT 'Rex' FROM Person AS a WHERE a.name = 'Ada'"
+    );
+    assert_eq!(
+        try_transform_with("CREATE (a:Person {name: $name})", label_tables()),
+        Err(TransformError::Unsupported(
+            "non-literal property value $name in a CREATE pattern".to_string()
+        ))
+    );
+}
+
+#[test]
+fn create_return() {
+    // The insert of the node whose id is returned returns it, with the
+    // properties echoed from the pattern
+    let cypher =
+        "CREATE (a:Person)-[r:KNOWS {since: 2020}]->(b:Person {name: 'Ada', active: true}) \
+                  RETURN id(b), elementId(b) AS key, b.name, b.active AS active, b.age, r.since";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "INSERT INTO nodes (label, properties) VALUES ('Person', '{}');\n\
+         INSERT INTO nodes (label, properties) VALUES ('Person', '{\"name\":\"Ada\",\"active\":true}') \
+         RETURNING id, CAST(id AS TEXT) AS key, 'Ada' AS name, 1 AS active, NULL AS age, 2020 AS since;\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) VALUES ($a, $b, 'KNOWS', '{\"since\":2020}')"
+    );
+    assert_eq!(
+        transform_with(
+            "CREATE (n:Person {name: 'Ada'}) RETURN id(n)",
+            TransformConfig {
+                target: SqlTarget::Postgres,
+                ..label_tables()
+            }
+        ),
+        "INSERT INTO Person (name) VALUES ('Ada') RETURNING id"
+    );
+    // Without an id, the last node insert returns the properties
+    assert_eq!(
+        transform_with("CREATE (a:A {x: 1}), (b:B) RETURN a.x", label_tables()),
+        "INSERT INTO A (x) VALUES (1);\nINSERT INTO B DEFAULT VALUES RETURNING 1 AS x"
+    );
+
+    // MySQL has no RETURNING, so the id is selected right after its insert
+    let mysql = TransformConfig {
+        target: SqlTarget::MySql,
+        ..generic_tables()
+    };
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql("CREATE (a:A)-[:R]->(b:B) RETURN id(a) AS id")
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let output = CypherToSqlTransformer::with_config(mysql.clone())
+        .to_sql_output(&stmt)
+        .unwrap();
+    assert_eq!(
+        output.sql,
+        "INSERT INTO nodes (label, properties) VALUES ('A', '{}');\n\
+         SELECT LAST_INSERT_ID() AS id;\n\
+         INSERT INTO nodes (label, properties) VALUES ('B', '{}');\n\
+         INSERT INTO relationships (from_id, to_id, type, properties) VALUES ($a, $b, 'R', '{}')"
+    );
+    let statements: Vec<_> = output
+        .created_nodes
+        .iter()
+        .map(|node| (node.variable.as_str(), node.statement))
+        .collect();
+    assert_eq!(statements, [("a", 0), ("b", 2)]);
+    assert_eq!(
+        try_transform_with(
+            "CREATE (n:A) RETURN id(n)",
+            TransformConfig {
+                id_type: IdType::Uuid,
+                ..mysql
+            }
+        ),
+        Err(TransformError::Unsupported(
+            "id(n) in the RETURN of a CREATE of Uuid ids on MySql".to_string()
+        ))
+    );
+
+    for (cypher, what) in [
+        ("CREATE (n:A) RETURN n", "n"),
+        ("CREATE (n:A) RETURN count(n)", "count(n)"),
+        ("CREATE (n:A {x: [1]}) RETURN n.x", "n.x"),
+        ("CREATE (n:A) RETURN m.x", "m.x"),
+        ("CREATE ()-[r:R]->() RETURN id(r)", "id(r)"),
+        (
+            "CREATE (n:A), (m:B) RETURN id(n), id(m)",
+            "the ids of more than one created node",
+        ),
+        (
+            "CREATE (n:A) RETURN id(n) LIMIT 1",
+            "ORDER BY, SKIP or LIMIT",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables()),
+            Err(TransformError::Unsupported(format!(
+                "{what} in the RETURN of a CREATE, which returns only id() or elementId() \
+                 of a created node and the properties it was created with"
+            ))),
+            "{cypher}"
+        );
+    }
+    assert_eq!(
+        try_transform_with(
+            "MATCH (a:A) CREATE (a)-[:R]->(b:B) RETURN id(b)",
+            generic_tables()
+        ),
+        Err(TransformError::Unsupported(
+            "RETURN id(b) after MATCH ... CREATE".to_string()
+        ))
+    );
+}
+
+#[test]
+fn booleans() {
+    let cypher = "MATCH (n:Flag) WHERE n.enabled = TRUE AND n.hidden <> false RETURN n.name, true";
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        "SELECT n.name, 1 FROM Flag AS n WHERE n.enabled = 1 AND n.hidden <> 0"
+    );
+    let postgres = TransformConfig {
+        target: SqlTarget::Postgres,
+        ..label_tables()
+    };
+    assert_eq!(
+        transform_with(cypher, postgres),
+        "SELECT n.name, true FROM Flag AS n WHERE n.enabled = true AND n.hidden <> false"
+    );
+    assert_eq!(
+        transform_with("CREATE (n:Flag {enabled: True})", generic_tables()),
+        r#"INSERT INTO nodes (label, properties) VALUES ('Flag', '{"enabled":true}')"#
+    );
+}
+
+#[test]
+fn merge_is_unsupported() {
+    assert_eq!(
+        try_transform_with("MERGE (a:Person) ON CREATE SET a.new = 1", generic_tables()),
+        Err(TransformError::Unsupported("MERGE".to_string()))
+    );
+    assert_eq!(
+        try_transform_with("MERGE (a:Person)-[:KNOWS]->(b:Person)", generic_tables()),
+        Err(TransformError::Unsupported(
+            "relationship MERGE (a:Person)-[:KNOWS]->(b:Person)".to_string()
+        ))
+    );
+}
+
+#[test]
+fn pattern_comprehension_is_unsupported() {
+    assert_eq!(
+        try_transform_with(
+            "MATCH (n:Person) RETURN n.name, [(n)-[:KNOWS]->(f) | f.name] AS friends",
+            generic_tables()
+        ),
+        Err(TransformError::Unsupported(
+            "pattern comprehension [(n)-[:KNOWS]->(f) | f.name]".to_string()
+        ))
+    );
+}
+
+#[test]
+fn untranslated_features_are_reported() {
+    let cypher = "OPTIONAL MATCH (a)-[r:KNOWS*]-(b) RETURN a.name";
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql(cypher)
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+
+    let lenient = TransformConfig {
+        strict: false,
+        ..generic_tables()
+    };
+    let output = CypherToSqlTransformer::with_config(lenient.clone())
+        .to_sql_output(&stmt)
+        .unwrap();
+    assert_eq!(
+        output.sql,
+        transform_with(cypher, lenient),
+        "the SQL does not depend on the API"
+    );
+    assert_eq!(
+        output
+            .warnings
+            .iter()
+            .map(|warning| (warning.feature.as_str(), warning.source_text.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("OPTIONAL MATCH", cypher),
+            ("variable-length relationship", "-[r:KNOWS*]-"),
+        ]
+    );
+
+    // Strict by default, which rejects what would be warned about
+    let strict = generic_tables();
+    assert!(strict.strict);
+    assert_eq!(
+        try_transform_with(cypher, strict.clone()),
+        Err(TransformError::Unsupported(format!(
+            "OPTIONAL MATCH in {cypher}"
+        )))
+    );
+    assert!(try_transform_with("MATCH (a)-[r:KNOWS]->(b) RETURN a.name", strict).is_ok());
+}
+
+#[test]
+fn strict_rejects_dropped_constructs() {
+    let lenient = TransformConfig {
+        strict: false,
+        ..generic_tables()
+    };
+    // A created node is stored with its first label
+    let create = "CREATE (n:Person:Admin {name: 'Ada'})";
+    assert_eq!(
+        try_transform_with(create, generic_tables()),
+        Err(TransformError::Unsupported(
+            "extra labels in a CREATE pattern in (n:Person:Admin {name: 'Ada'})".to_string()
+        ))
+    );
+    let output =
+        transform_cypher_to_sql_with_params(create, &BTreeMap::new(), lenient.clone()).unwrap();
+    assert_eq!(
+        output.sql,
+        "INSERT INTO nodes (label, properties) VALUES ('Person', '{\"name\":\"Ada\"}')"
+    );
+    assert_eq!(
+        output.warnings[0].to_string(),
+        "extra labels in a CREATE pattern is not translated: (n:Person:Admin {name: 'Ada'})"
+    );
+
+    // and a matched one has a single label, which cannot equal each of
+    // several
+    for (cypher, node) in [
+        ("MATCH (n:Person:Admin) RETURN n.name", "(n:Person:Admin)"),
+        (
+            "MATCH (a:Person) OPTIONAL MATCH (a)-[:OWNS]->(c:Car:Electric) RETURN c.model",
+            "(c:Car:Electric)",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, label_tables()),
+            Err(TransformError::Unsupported(format!(
+                "multiple labels in a MATCH pattern in {node}"
+            )))
+        );
+        let output =
+            transform_cypher_to_sql_with_params(cypher, &BTreeMap::new(), lenient.clone()).unwrap();
+        assert_eq!(
+            output.warnings[0].to_string(),
+            format!("multiple labels in a MATCH pattern is not translated: {node}")
+        );
+    }
+
+    // The parser stops before a clause it does not know, whose rest of the
+    // query is rejected rather than dropped
+    let remove = "MATCH (n:Person) REMOVE n:Admin";
+    assert_eq!(
+        transform_cypher_to_sql_with_params(remove, &BTreeMap::new(), generic_tables()),
+        Err(TransformError::Unsupported(format!("REMOVE in {remove}")))
+    );
+    let transformer = CypherToSqlTransformer::with_config(generic_tables());
+    assert_eq!(
+        transformer.to_sql_output_with_stats("MATCH (n) RETURN n.name; MATCH (m) RETURN m"),
+        Err(TransformError::Unsupported(
+            "MATCH in MATCH (n) RETURN n.name; MATCH (m) RETURN m".to_string()
+        ))
+    );
+    assert!(transformer
+        .to_sql_output_with_stats("MATCH (n) RETURN n.name;")
+        .is_ok());
+    assert!(transform_cypher_to_sql_with_params(remove, &BTreeMap::new(), lenient.clone()).is_ok());
+
+    // Directives that are skipped
+    let mut script = ScriptTransformer::new(generic_tables());
+    let mut items = CypherStatementIterator::new([":use movies\n", ":sysinfo\n"].into_iter());
+    for error in [":use in :use movies", "unknown directive :sysinfo"] {
+        let item = items.next_item().unwrap().unwrap();
+        assert_eq!(
+            script.transform(&item),
+            Err(TransformError::Unsupported(error.to_string()))
+        );
+    }
+
+    // The functions returning strings are not strict
+    assert_eq!(
+        transform_cypher_to_sql(create).unwrap(),
+        transform_with(create, lenient.clone())
+    );
+    assert_eq!(
+        transform_cypher_to_sql(remove).unwrap(),
+        transform_with("MATCH (n:Person)", lenient)
+    );
+    assert!(transform_cypher_to_sql_with_stats(create).is_ok());
+    assert_eq!(
+        transform_cypher_script(":sysinfo\nMATCH (n) RETURN n.name;\n")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn optional_variables_returned_whole_are_null_when_unmatched() {
+    let cypher = "MATCH (a:Person) OPTIONAL MATCH (a)-[r:OWNS]->(c:Car) RETURN a, c.model, c, r";
+    let from = "FROM nodes AS a LEFT JOIN (relationships AS r INNER JOIN nodes AS c \
+                ON c.id = r.to_id AND c.label = 'Car') ON a.id = r.from_id AND r.type = 'OWNS' \
+                WHERE a.label = 'Person'";
+    // The properties of an unmatched element read from a null document or
+    // map are null on every target, and need no guard
+    let cases = [
+        (
+            SqlTarget::Sqlite,
+            "json_object('id', a.id, 'label', a.label, 'properties', json(a.properties)) AS a, \
+             json_extract(c.properties, '$.model') AS model, \
+             CASE WHEN c.id IS NULL THEN NULL ELSE \
+             json_object('id', c.id, 'label', c.label, 'properties', json(c.properties)) END AS c, \
+             CASE WHEN r.from_id IS NULL THEN NULL ELSE json_object('from_id', r.from_id, \
+             'to_id', r.to_id, 'type', r.type, 'properties', json(r.properties)) END AS r",
+        ),
+        (
+            SqlTarget::Postgres,
+            "json_build_object('id', a.id, 'label', a.label, 'properties', a.properties) AS a, \
+             c.properties ->> 'model' AS model, \
+             CASE WHEN c.id IS NULL THEN NULL ELSE \
+             json_build_object('id', c.id, 'label', c.label, 'properties', c.properties) END AS c, \
+             CASE WHEN r.from_id IS NULL THEN NULL ELSE json_build_object('from_id', r.from_id, \
+             'to_id', r.to_id, 'type', r.type, 'properties', r.properties) END AS r",
+        ),
+        (
+            SqlTarget::MySql,
+            "json_object('id', a.id, 'label', a.label, 'properties', a.properties) AS a, \
+             json_extract(c.properties, '$.model') AS model, \
+             CASE WHEN c.id IS NULL THEN NULL ELSE \
+             json_object('id', c.id, 'label', c.label, 'properties', c.properties) END AS c, \
+             CASE WHEN r.from_id IS NULL THEN NULL ELSE json_object('from_id', r.from_id, \
+             'to_id', r.to_id, 'type', r.type, 'properties', r.properties) END AS r",
+        ),
+        (
+            SqlTarget::DuckDb,
+            "json_object('id', a.id, 'label', a.label, 'properties', json(a.properties)) AS a, \
+             json_extract(c.properties, '$.model') AS model, \
+             CASE WHEN c.id IS NULL THEN NULL ELSE \
+             json_object('id', c.id, 'label', c.label, 'properties', json(c.properties)) END AS c, \
+             CASE WHEN r.from_id IS NULL THEN NULL ELSE json_object('from_id', r.from_id, \
+             'to_id', r.to_id, 'type', r.type, 'properties', json(r.properties)) END AS r",
+        ),
+        (
+            SqlTarget::DataFusion,
+            "named_struct('id', a.id, 'label', a.label, 'properties', a.properties) AS a, \
+             c.properties['model'] AS model, \
+             CASE WHEN c.id IS NULL THEN NULL ELSE \
+             named_struct('id', c.id, 'label', c.label, 'properties', c.properties) END AS c, \
+             CASE WHEN r.from_id IS NULL THEN NULL ELSE named_struct('from_id', r.from_id, \
+             'to_id', r.to_id, 'type', r.type, 'properties', r.properties) END AS r",
+        ),
+    ];
+    for (target, projection) in cases {
+        let config = TransformConfig {
+            target,
+            ..generic_tables()
+        };
+        assert_eq!(
+            transform_with(cypher, config),
+            format!("SELECT {projection} {from}"),
+            "{target:?}"
+        );
+    }
+
+    // The columns of a label table are null by themselves
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) OPTIONAL MATCH (a)-[:OWNS]->(c:Car) RETURN c",
+            label_tables()
+        ),
+        "SELECT c.* FROM Person AS a LEFT JOIN (relationships INNER JOIN Car AS c \
+         ON c.id = relationships.to_id) ON a.id = relationships.from_id \
+         AND relationships.type = 'OWNS'"
+    );
+
+    // PostgreSQL reads properties as text, cast to numbers for arithmetic,
+    // which keeps them null
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) OPTIONAL MATCH (a)-[:OWNS]->(c:Car) \
+             RETURN c.year + 1 AS next ORDER BY c.year",
+            TransformConfig {
+                target: SqlTarget::Postgres,
+                ..generic_tables()
+            }
+        ),
+        "SELECT (c.properties ->> 'year')::NUMERIC + 1 AS next FROM nodes AS a \
+         LEFT JOIN (relationships INNER JOIN nodes AS c ON c.id = relationships.to_id \
+         AND c.label = 'Car') ON a.id = relationships.from_id AND relationships.type = 'OWNS' \
+         WHERE a.label = 'Person' ORDER BY c.properties -> 'year'"
+    );
+}
+
+#[test]
+fn where_of_optional_match_is_a_left_join_condition() {
+    // The same predicate filters the rows of the MATCH, or only decides
+    // which of them the OPTIONAL MATCH extends
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) WHERE a.age > 30 \
+             OPTIONAL MATCH (a)-[:KNOWS]->(b) RETURN a.name, b.name",
+            label_tables()
+        ),
+        "SELECT a.name, json_extract(b.properties, '$.name') AS b_name FROM Person AS a \
+         LEFT JOIN (relationships INNER JOIN nodes AS b ON b.id = relationships.to_id) \
+         ON a.id = relationships.from_id AND relationships.type = 'KNOWS' WHERE a.age > 30"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) \
+             OPTIONAL MATCH (a)-[:KNOWS]->(b) WHERE a.age > 30 RETURN a.name, b.name",
+            label_tables()
+        ),
+        "SELECT a.name, json_extract(b.properties, '$.name') AS b_name FROM Person AS a \
+         LEFT JOIN (relationships INNER JOIN nodes AS b ON b.id = relationships.to_id) \
+         ON a.id = relationships.from_id AND relationships.type = 'KNOWS' AND a.age > 30"
+    );
+
+    // Labels of the new nodes join them inside the LEFT JOIN, and each
+    // OPTIONAL MATCH is a LEFT JOIN of its own
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) OPTIONAL MATCH (a)-[:KNOWS]->(b:Person) \
+             OPTIONAL MATCH (b)-[:WORKS_AT]->(c) WHERE c.name <> 'Acme' RETURN a.name, c.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(a.properties, '$.name') AS a_name, \
+         json_extract(c.properties, '$.name') AS c_name FROM nodes AS a \
+         LEFT JOIN (relationships AS relationships_1 INNER JOIN nodes AS b \
+         ON b.id = relationships_1.to_id AND b.label = 'Person') \
+         ON a.id = relationships_1.from_id AND relationships_1.type = 'KNOWS' \
+         LEFT JOIN (relationships AS relationships_2 INNER JOIN nodes AS c \
+         ON c.id = relationships_2.to_id) \
+         ON b.id = relationships_2.from_id AND relationships_2.type = 'WORKS_AT' \
+         AND json_extract(c.properties, '$.name') <> 'Acme' WHERE a.label = 'Person'"
+    );
+
+    // MATCH clauses one after the other are a single MATCH
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person) WHERE a.age > 30 MATCH (a)-[:KNOWS]->(b) WHERE b.age < 30 \
+             RETURN b.name",
+            label_tables()
+        ),
+        transform_with(
+            "MATCH (a:Person)-[:KNOWS]->(b) WHERE a.age > 30 AND b.age < 30 RETURN b.name",
+            label_tables()
+        )
+    );
+
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql("MATCH (a) OPTIONAL MATCH (a)-[:KNOWS]->(b) RETURN b.name")
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let output = CypherToSqlTransformer::with_config(generic_tables())
+        .to_sql_output(&stmt)
+        .unwrap();
+    assert_eq!(output.warnings, vec![], "the OPTIONAL MATCH is translated");
+
+    for (cypher, error) in [
+        (
+            "MATCH (a) OPTIONAL MATCH (a)-[:KNOWS]->(b) MATCH (b) RETURN b",
+            "MATCH after OPTIONAL MATCH: MATCH (b) RETURN b",
+        ),
+        (
+            "MATCH (a) OPTIONAL MATCH (a) WHERE a.age > 30 RETURN a",
+            "OPTIONAL MATCH (a) that binds no new node or relationship",
+        ),
+        (
+            "MATCH (a) OPTIONAL MATCH (a)-[:KNOWS]->(b) WITH b RETURN b",
+            "WITH b after OPTIONAL MATCH",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables()),
+            Err(TransformError::Unsupported(error.to_string())),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn cartesian_products_are_reported() {
+    let warnings = |cypher: &str| {
+        let stmt = Parser::new(&CypherDialect)
+            .try_with_sql(cypher)
+            .and_then(|mut parser| parser.parse_cypher_statement())
+            .unwrap();
+        CypherToSqlTransformer::with_config(generic_tables())
+            .to_sql_output(&stmt)
+            .unwrap()
+            .warnings
+    };
+
+    let cypher = "MATCH (a:Person), (b:Person) RETURN a, b";
+    let reported = warnings(cypher);
+    assert_eq!(
+        reported
+            .iter()
+            .map(|warning| (warning.kind, warning.to_string()))
+            .collect::<Vec<_>>(),
+        vec![(
+            WarningKind::CartesianProduct,
+            format!("cartesian product of {{a}}, {{b}} in {cypher}")
+        )]
+    );
+    assert_eq!(
+        warnings("MATCH (a)-[r]->(b), (b)-[]->(c), (d), (:City) RETURN a")[0].feature,
+        "cartesian product of {a, r, b, c}, {d}, {(:City)}"
+    );
+    // An equality in a disjunction does not restrict the join
+    assert_eq!(
+        warnings("MATCH (a), (b) WHERE a.x = b.y OR a.z = 1 RETURN a")[0].feature,
+        "cartesian product of {a}, {b}"
+    );
+
+    // Shared variables and equalities in WHERE connect patterns
+    for cypher in [
+        "MATCH (a)-[r]->(b), (b)-[s]->(c) RETURN a, c",
+        "MATCH (a:Person), (b:Person) WHERE a.city = b.city AND a.age > 30 RETURN a, b",
+        "MATCH (a), (b), (c) WHERE id(a) = id(b) AND b.x = c.x RETURN a",
+        "MATCH (a), (b) WITH a, b WHERE a.name = b.name RETURN a",
+    ] {
+        assert_eq!(warnings(cypher), vec![], "{cypher}");
+    }
+
+    let strict = TransformConfig {
+        strict_cartesian: true,
+        ..generic_tables()
+    };
+    assert_eq!(
+        try_transform_with(
+            "MATCH (a)-[r]->(b), (c) RETURN a UNION MATCH (a), (b) RETURN a",
+            strict.clone()
+        ),
+        Err(TransformError::CartesianProduct(vec![
+            vec!["a".to_string(), "r".to_string(), "b".to_string()],
+            vec!["c".to_string()],
+        ]))
+    );
+    assert_eq!(
+        TransformError::CartesianProduct(vec![vec!["a".to_string()], vec!["b".to_string()]])
+            .to_string(),
+        "cartesian product of {a}, {b}"
+    );
+    assert!(try_transform_with("MATCH (a), (a)-[r]->(b) RETURN a", strict).is_ok());
+}
+
+#[test]
+fn distinct_ordered_by_unreturned_expression() {
+    let cypher = "MATCH (n:Person) RETURN DISTINCT n.city AS city ORDER BY city, n.age DESC";
+    let postgres = TransformConfig {
+        target: SqlTarget::Postgres,
+        ..label_tables()
+    };
+    // Grouped by the returned columns, each sorted by its oldest person
+    assert_eq!(
+        transform_with(cypher, postgres.clone()),
+        "SELECT n.city AS city FROM Person AS n GROUP BY n.city ORDER BY city, max(n.age) DESC"
+    );
+    // SQLite accepts the direct form
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        "SELECT DISTINCT n.city AS city FROM Person AS n ORDER BY city, n.age DESC"
+    );
+    // Sorting on returned columns only keeps SELECT DISTINCT
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN DISTINCT n.city ORDER BY n.city",
+            postgres.clone()
+        ),
+        "SELECT DISTINCT n.city FROM Person AS n ORDER BY n.city"
+    );
+
+    assert_eq!(
+        try_transform_with(
+            cypher,
+            TransformConfig {
+                distinct_order_by: DistinctOrderBy::Reject,
+                ..postgres.clone()
+            }
+        ),
+        Err(TransformError::InvalidQuery(
+            "ORDER BY n.age sorts on an expression that RETURN DISTINCT does not return"
+                .to_string()
+        ))
+    );
+    // Whole label table rows and aggregates cannot be grouped by
+    assert_eq!(
+        try_transform_with(
+            "MATCH (n:Person) RETURN DISTINCT count(*) AS c ORDER BY n.age",
+            postgres
+        ),
+        Err(TransformError::Unsupported(
+            "ORDER BY n.age on an expression that RETURN DISTINCT count(*) AS c does not return"
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn distinct_on_returned_columns() {
+    let postgres = TransformConfig {
+        target: SqlTarget::Postgres,
+        distinct_on: true,
+        ..label_tables()
+    };
+    // The sorted columns lead DISTINCT ON, and the others follow in ORDER BY
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN DISTINCT n.city AS city, n.name ORDER BY n.name DESC",
+            postgres.clone()
+        ),
+        "SELECT DISTINCT ON (n.name, n.city) n.city AS city, n.name FROM Person AS n \
+         ORDER BY n.name DESC, n.city"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN DISTINCT n.city AS c, n.age + 1 AS a ORDER BY a, c, a",
+            postgres.clone()
+        ),
+        "SELECT DISTINCT ON (n.age + 1, n.city) n.city AS c, n.age + 1 AS a FROM Person AS n \
+         ORDER BY a, c, a"
+    );
+
+    // Not when ORDER BY sorts on an expression that is not returned, nor
+    // without ORDER BY
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN DISTINCT n.city AS city ORDER BY city, n.age DESC",
+            postgres.clone()
+        ),
+        "SELECT n.city AS city FROM Person AS n GROUP BY n.city ORDER BY city, max(n.age) DESC"
+    );
+    assert_eq!(
+        transform_with("MATCH (n:Person) RETURN DISTINCT n.city", postgres.clone()),
+        "SELECT DISTINCT n.city FROM Person AS n"
+    );
+    // Nor on other targets, nor unless enabled
+    let cypher = "MATCH (n:Person) RETURN DISTINCT n.city ORDER BY n.city";
+    for config in [
+        TransformConfig {
+            target: SqlTarget::DuckDb,
+            ..postgres.clone()
+        },
+        TransformConfig {
+            distinct_on: false,
+            ..postgres.clone()
+        },
+    ] {
+        assert_eq!(
+            transform_with(cypher, config),
+            "SELECT DISTINCT n.city FROM Person AS n ORDER BY n.city"
+        );
+    }
+    assert_eq!(
+        transform_with(cypher, postgres),
+        "SELECT DISTINCT ON (n.city) n.city FROM Person AS n ORDER BY n.city"
+    );
+}
+
+#[test]
+fn return_aggregates_group_by_projected_expressions() {
+    use sqlparser::ast::{GroupByExpr, SelectItem, SetExpr, Statement};
+
+    let cypher = "MATCH (c:Company)<-[:WORKS_AT]-(p) RETURN c.name, count(p)";
+    for target in [
+        SqlTarget::Sqlite,
+        SqlTarget::Postgres,
+        SqlTarget::MySql,
+        SqlTarget::DuckDb,
+        SqlTarget::DataFusion,
+    ] {
+        let config = TransformConfig {
+            target,
+            ..generic_tables()
+        };
+        let stmt = Parser::new(&CypherDialect)
+            .try_with_sql(cypher)
+            .unwrap()
+            .parse_cypher_statement()
+            .unwrap();
+        let statements = CypherToSqlTransformer::with_config(config)
+            .transform(&stmt)
+            .unwrap();
+        let Statement::Query(query) = &statements[0] else {
+            panic!("expected a query, got {}", statements[0]);
+        };
+        let SetExpr::Select(select) = query.body.as_ref() else {
+            panic!("expected a SELECT, got {query}");
+        };
+        let [SelectItem::ExprWithAlias { expr: name, .. }, SelectItem::UnnamedExpr(count)] =
+            select.projection.as_slice()
+        else {
+            panic!("unexpected projection in {query}");
+        };
+        assert_eq!(
+            select.group_by,
+            GroupByExpr::Expressions(vec![name.clone()], vec![]),
+            "{target:?}: {query}"
+        );
+        assert_eq!(count.to_string(), "count(p.id)", "{target:?}");
+    }
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "SELECT json_extract(c.properties, '$.name') AS name, count(p.id) \
+         FROM nodes AS c INNER JOIN relationships ON c.id = relationships.to_id \
+         INNER JOIN nodes AS p ON p.id = relationships.from_id \
+         WHERE c.label = 'Company' AND relationships.type = 'WORKS_AT' \
+         GROUP BY json_extract(c.properties, '$.name')"
+    );
+
+    // A returned variable is grouped by its columns, or by the id of its
+    // label table
+    assert_eq!(
+        transform_with(
+            "MATCH (c:Company)<-[r:WORKS_AT]-(p) RETURN c, count(r) AS staff",
+            label_tables()
+        ),
+        "SELECT c.*, count(r.from_id) AS staff FROM Company AS c \
+         INNER JOIN relationships AS r ON c.id = r.to_id INNER JOIN nodes AS p ON p.id = r.from_id \
+         WHERE r.type = 'WORKS_AT' GROUP BY c.id"
+    );
+
+    // A key with a parameter is grouped by its alias, so that `?` binds it
+    // once, where the target does not resolve the alias to a column instead
+    let cypher = "MATCH (n:Person) RETURN n.age / $width AS bucket, count(*) AS people";
+    let config = |target| TransformConfig {
+        target,
+        param_style: ParamStyle::Question,
+        ..generic_tables()
+    };
+    assert_eq!(
+        transform_with(cypher, config(SqlTarget::MySql)),
+        "SELECT json_extract(n.properties, '$.age') / ? AS bucket, count(*) AS people \
+         FROM nodes AS n WHERE n.label = 'Person' GROUP BY bucket"
+    );
+    assert_eq!(
+        transform_with(cypher, config(SqlTarget::Postgres)),
+        "SELECT (n.properties ->> 'age')::NUMERIC / ? AS bucket, count(*) AS people \
+         FROM nodes AS n WHERE n.label = 'Person' GROUP BY (n.properties ->> 'age')::NUMERIC / ?"
+    );
+}
+
+#[test]
+fn with_conditions_on_aggregates_go_to_having() {
+    let cypher = "MATCH (p:Person)-[:BOUGHT]->(i) WITH p, count(i) AS n \
+                  WHERE n > 5 AND p.city = 'Oslo' RETURN p.name, n";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "SELECT json_extract(p.properties, '$.name') AS name, count(i.id) AS n \
+         FROM nodes AS p INNER JOIN relationships ON p.id = relationships.from_id \
+         INNER JOIN nodes AS i ON i.id = relationships.to_id \
+         WHERE p.label = 'Person' AND relationships.type = 'BOUGHT' \
+         AND json_extract(p.properties, '$.city') = 'Oslo' \
+         GROUP BY p.id, p.label, p.properties HAVING count(i.id) > 5"
+    );
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        "SELECT p.name, count(i.id) AS n \
+         FROM Person AS p INNER JOIN relationships ON p.id = relationships.from_id \
+         INNER JOIN nodes AS i ON i.id = relationships.to_id \
+         WHERE relationships.type = 'BOUGHT' AND p.city = 'Oslo' \
+         GROUP BY p.id HAVING count(i.id) > 5"
+    );
+
+    // A condition mixing an aggregate with a grouping key is checked on the
+    // groups as a whole
+    assert_eq!(
+        transform_with(
+            "MATCH (p:Person) WITH p.city AS city, count(*) AS n \
+             WHERE (n > 5 OR city = 'Oslo') AND city <> 'Rome' RETURN city, n ORDER BY n DESC",
+            label_tables()
+        ),
+        "SELECT p.city AS city, count(*) AS n FROM Person AS p WHERE p.city <> 'Rome' \
+         GROUP BY p.city HAVING (count(*) > 5 OR p.city = 'Oslo') ORDER BY n DESC"
+    );
+
+    // Without aggregates the whole condition is a WHERE condition
+    assert_eq!(
+        transform_with(
+            "MATCH (p:Person) WHERE p.age > 30 OR p.vip WITH p AS q, p.city AS city \
+             WHERE city = 'Oslo' RETURN q.name, city",
+            label_tables()
+        ),
+        "SELECT p.name, p.city AS city FROM Person AS p \
+         WHERE (p.age > 30 OR p.vip) AND p.city = 'Oslo'"
+    );
+
+    for (cypher, error) in [
+        (
+            "MATCH (p)-[:BOUGHT]->(i) WITH p, count(i) AS n RETURN i.name",
+            "invalid Cypher query: Variable `i` not defined after WITH p, count(i) AS n",
+        ),
+        (
+            "MATCH (p) WITH p.name RETURN p",
+            "invalid Cypher query: Expression in WITH must be aliased (use AS): p.name",
+        ),
+        (
+            "MATCH (p)-[:BOUGHT]->(i) WITH p, count(i) AS n RETURN sum(n)",
+            "unsupported Cypher feature: aggregation in a RETURN after the aggregating \
+             WITH p, count(i) AS n",
+        ),
+        (
+            "MATCH (p)-[r:BOUGHT]->(i) WITH r LIMIT 1 RETURN r",
+            "unsupported Cypher feature: relationship r passed on by WITH r LIMIT 1",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables())
+                .unwrap_err()
+                .to_string(),
+            error,
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn with_order_by_and_limit_go_to_a_subquery() {
+    // The LIMIT applies to the people the WITH passes on, before they are
+    // matched again
+    let cypher = "MATCH (n:Person) WITH n ORDER BY n.age DESC LIMIT 10 \
+                  MATCH (n)-[:KNOWS]->(m) RETURN m.name";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "SELECT json_extract(m.properties, '$.name') AS name \
+         FROM (SELECT n.id AS n FROM nodes AS n WHERE n.label = 'Person' \
+         ORDER BY json_extract(n.properties, '$.age') DESC LIMIT 10) AS with_rows, \
+         nodes AS n INNER JOIN relationships ON n.id = relationships.from_id \
+         INNER JOIN nodes AS m ON m.id = relationships.to_id \
+         WHERE n.id = with_rows.n AND n.label = 'Person' AND relationships.type = 'KNOWS'"
+    );
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        "SELECT json_extract(m.properties, '$.name') AS name \
+         FROM (SELECT n.id AS n FROM Person AS n ORDER BY n.age DESC LIMIT 10) AS with_rows, \
+         Person AS n INNER JOIN relationships ON n.id = relationships.from_id \
+         INNER JOIN nodes AS m ON m.id = relationships.to_id \
+         WHERE n.id = with_rows.n AND relationships.type = 'KNOWS'"
+    );
+
+    // Aliases are read from the subquery, which groups by the nodes it
+    // passes on, and the condition of the WITH applies to its rows
+    assert_eq!(
+        transform_with(
+            "MATCH (p:Person)-[:BOUGHT]->(i) WITH p AS buyer, count(i) AS n \
+             ORDER BY n DESC LIMIT 3 WHERE n > 1 RETURN buyer.name, n",
+            label_tables()
+        ),
+        "SELECT buyer.name, with_rows.n AS n FROM (SELECT p.id AS buyer, count(i.id) AS n \
+         FROM Person AS p INNER JOIN relationships ON p.id = relationships.from_id \
+         INNER JOIN nodes AS i ON i.id = relationships.to_id \
+         WHERE relationships.type = 'BOUGHT' GROUP BY p.id ORDER BY n DESC LIMIT 3) AS with_rows, \
+         Person AS buyer WHERE buyer.id = with_rows.buyer AND with_rows.n > 1"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (p:Person) WITH DISTINCT p.city AS city ORDER BY city SKIP 1 LIMIT 2 \
+             RETURN city",
+            label_tables()
+        ),
+        "SELECT with_rows.city AS city FROM (SELECT DISTINCT p.city AS city \
+         FROM Person AS p ORDER BY city LIMIT 2 OFFSET 1) AS with_rows"
+    );
+
+    for (cypher, error) in [
+        (
+            "MATCH (p:Person)-[:BOUGHT]->(i) WITH p LIMIT 1 MATCH (p)-[:KNOWS]->(q) RETURN i",
+            "invalid Cypher query: Variable `i` not defined after WITH p LIMIT 1",
+        ),
+        (
+            "MATCH (p:Person) WITH p LIMIT 5 MATCH (p)-[:KNOWS]->(q) WITH q LIMIT 1 RETURN q",
+            "unsupported Cypher feature: WITH q LIMIT 1 after WITH p LIMIT 5",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables())
+                .unwrap_err()
+                .to_string(),
+            error,
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn property_access_on_properties() {
+    let cypher = "MATCH (n:Person) WHERE properties(n).name = 'x' \
+                  RETURN properties(n).age, PROPERTIES(n).city AS c";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "SELECT json_extract(n.properties, '$.age') AS age, \
+         json_extract(n.properties, '$.city') AS c FROM nodes AS n \
+         WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = 'x'"
+    );
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        "SELECT n.age, n.city AS c FROM Person AS n WHERE n.name = 'x'"
+    );
+
+    for (cypher, access) in [
+        (
+            "MATCH (n) WHERE head(collect(n)).id = 1 RETURN n",
+            "head(collect(n)).id",
+        ),
+        (
+            "MATCH (n) RETURN properties(n).address.city",
+            "properties(n).address.city",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables()),
+            Err(TransformError::Unsupported(format!(
+                "property access {access}"
+            )))
+        );
+    }
+}
+
+#[test]
+fn ids_per_id_type() {
+    let config = |id_type, target| TransformConfig {
+        id_type,
+        target,
+        ..generic_tables()
+    };
+    let create = "CREATE (n:Person {name: 'Ada'})";
+    let lookup = "MATCH (n) WHERE elementId(n) = 'b5c3' OR id(n) IN [7] RETURN elementId(n), id(n)";
+
+    // The database assigns integer and text ids
+    for id_type in [IdType::Integer, IdType::Text] {
+        assert_eq!(
+            transform_with(create, config(id_type, SqlTarget::Postgres)),
+            "INSERT INTO nodes (label, properties) VALUES ('Person', '{\"name\":\"Ada\"}')"
+        );
+    }
+    assert_eq!(
+        transform_with(lookup, config(IdType::Integer, SqlTarget::Postgres)),
+        "SELECT CAST(n.id AS TEXT), n.id FROM nodes AS n \
+         WHERE CAST(n.id AS TEXT) = 'b5c3' OR n.id IN (7)"
+    );
+    assert_eq!(
+        transform_with(lookup, config(IdType::Text, SqlTarget::Postgres)),
+        "SELECT n.id, CAST(n.id AS INTEGER) FROM nodes AS n \
+         WHERE n.id = 'b5c3' OR n.id IN ('7')"
+    );
+
+    // UUIDs are generated by CREATE and compared as strings
+    assert_eq!(
+        transform_with(create, config(IdType::Uuid, SqlTarget::Postgres)),
+        "INSERT INTO nodes (id, label, properties) \
+         VALUES (gen_random_uuid(), 'Person', '{\"name\":\"Ada\"}')"
+    );
+    assert_eq!(
+        transform_with(create, config(IdType::Uuid, SqlTarget::MySql)),
+        "INSERT INTO nodes (id, label, properties) VALUES (uuid(), 'Person', '{\"name\":\"Ada\"}')"
+    );
+    assert_eq!(
+        transform_with(
+            "CREATE (n:Person)",
+            TransformConfig {
+                id_type: IdType::Uuid,
+                target: SqlTarget::DuckDb,
+                ..label_tables()
+            }
+        ),
+        "INSERT INTO Person (id) VALUES (gen_random_uuid())"
+    );
+    assert_eq!(
+        try_transform_with(create, config(IdType::Uuid, SqlTarget::Sqlite)),
+        Err(TransformError::Unsupported(
+            "generated UUID ids on SQLite".to_string()
+        ))
+    );
+    assert_eq!(
+        transform_with(lookup, config(IdType::Uuid, SqlTarget::Postgres)),
+        "SELECT CAST(n.id AS TEXT), CAST(n.id AS TEXT) FROM nodes AS n \
+         WHERE n.id = 'b5c3' OR n.id IN ('7')"
+    );
+}
+
+/// The SQL of a Cypher query under the configurations the golden tests cover
+struct Golden {
+    cypher: &'static str,
+    /// The default configuration: label tables on SQLite
+    label_tables: &'static str,
+    /// The generic node and relationship tables on SQLite
+    generic_tables: &'static str,
+    /// The generic tables on PostgreSQL
+    postgres: &'static str,
+    /// The generic tables on MySQL
+    mysql: &'static str,
+}
+
+/// Update these when the translation changes on purpose
+const GOLDEN: &[Golden] = &[
+    Golden {
+        cypher: "MATCH (n:Person) RETURN n.name",
+        label_tables: "SELECT n.name FROM Person AS n",
+        generic_tables: "SELECT json_extract(n.properties, '$.name') AS name \
+                         FROM nodes AS n \
+                         WHERE n.label = 'Person'",
+        postgres: "SELECT n.properties ->> 'name' AS name \
+                   FROM nodes AS n \
+                   WHERE n.label = 'Person'",
+        mysql: "SELECT json_extract(n.properties, '$.name') AS name \
+                FROM nodes AS n \
+                WHERE n.label = 'Person'",
+    },
+    Golden {
+        cypher: "MATCH (n:User) RETURN n.email",
+        label_tables: "SELECT n.email FROM \"User\" AS n",
+        generic_tables: "SELECT json_extract(n.properties, '$.email') AS email \
+                         FROM nodes AS n \
+                         WHERE n.label = 'User'",
+        postgres: "SELECT n.properties ->> 'email' AS email \
+                   FROM nodes AS n \
+                   WHERE n.label = 'User'",
+        mysql: "SELECT json_extract(n.properties, '$.email') AS email \
+                FROM nodes AS n \
+                WHERE n.label = 'User'",
+    },
+    Golden {
+        cypher: "MATCH (n) RETURN n.id",
+        label_tables: "SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n",
+        generic_tables: "SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n",
+        postgres: "SELECT n.properties ->> 'id' AS id FROM nodes AS n",
+        mysql: "SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n",
+    },
+    Golden {
+        cypher: "MATCH (n:Person)-[r:KNOWS]->(m:Person) RETURN n.name, m.name",
+        label_tables: "SELECT n.name, m.name \
+                       FROM Person AS n \
+                       INNER JOIN relationships AS r ON n.id = r.from_id \
+                       INNER JOIN Person AS m ON m.id = r.to_id \
+                       WHERE r.type = 'KNOWS'",
+        generic_tables: "SELECT json_extract(n.properties, '$.name') AS n_name, json_extract(m.properties, '$.name') AS m_name \
+                         FROM nodes AS n \
+                         INNER JOIN relationships AS r ON n.id = r.from_id \
+                         INNER JOIN nodes AS m ON m.id = r.to_id \
+                         WHERE n.label = 'Person' AND r.type = 'KNOWS' AND m.label = 'Person'",
+        postgres: "SELECT n.properties ->> 'name' AS n_name, m.properties ->> 'name' AS m_name \
+                   FROM nodes AS n \
+                   INNER JOIN relationships AS r ON n.id = r.from_id \
+                   INNER JOIN nodes AS m ON m.id = r.to_id \
+                   WHERE n.label = 'Person' AND r.type = 'KNOWS' AND m.label = 'Person'",
+        mysql: "SELECT json_extract(n.properties, '$.name') AS n_name, json_extract(m.properties, '$.name') AS m_name \
+                FROM nodes AS n \
+                INNER JOIN relationships AS r ON n.id = r.from_id \
+                INNER JOIN nodes AS m ON m.id = r.to_id \
+                WHERE n.label = 'Person' AND r.type = 'KNOWS' AND m.label = 'Person'",
+    },
+    Golden {
+        cypher: "CREATE (n:Person)",
+        label_tables: "INSERT INTO Person DEFAULT VALUES",
+        generic_tables: "INSERT INTO nodes (label, properties) VALUES ('Person', '{}')",
+        postgres: "INSERT INTO nodes (label, properties) VALUES ('Person', '{}')",
+        mysql: "INSERT INTO nodes (label, properties) VALUES ('Person', '{}')",
+    },
+    Golden {
+        cypher: "MATCH (a:Person)-[:KNOWS]->(b:Person)-[:WORKS_AT]->(c:Company) \
+                 WHERE a.age > 30 RETURN a.name, c.name",
+        label_tables: "SELECT a.name, c.name \
+                       FROM Person AS a \
+                       INNER JOIN relationships AS relationships_1 ON a.id = relationships_1.from_id \
+                       INNER JOIN Person AS b ON b.id = relationships_1.to_id \
+                       INNER JOIN relationships AS relationships_2 ON b.id = relationships_2.from_id \
+                       INNER JOIN Company AS c ON c.id = relationships_2.to_id \
+                       WHERE relationships_1.type = 'KNOWS' AND relationships_2.type = 'WORKS_AT' AND a.age > 30",
+        generic_tables: "SELECT json_extract(a.properties, '$.name') AS a_name, json_extract(c.properties, '$.name') AS c_name \
+                         FROM nodes AS a \
+                         INNER JOIN relationships AS relationships_1 ON a.id = relationships_1.from_id \
+                         INNER JOIN nodes AS b ON b.id = relationships_1.to_id \
+                         INNER JOIN relationships AS relationships_2 ON b.id = relationships_2.from_id \
+                         INNER JOIN nodes AS c ON c.id = relationships_2.to_id \
+                         WHERE a.label = 'Person' AND relationships_1.type = 'KNOWS' AND b.label = 'Person' AND relationships_2.type = 'WORKS_AT' AND c.label = 'Company' AND json_extract(a.properties, '$.age') > 30",
+        postgres: "SELECT a.properties ->> 'name' AS a_name, c.properties ->> 'name' AS c_name \
+                   FROM nodes AS a \
+                   INNER JOIN relationships AS relationships_1 ON a.id = relationships_1.from_id \
+                   INNER JOIN nodes AS b ON b.id = relationships_1.to_id \
+                   INNER JOIN relationships AS relationships_2 ON b.id = relationships_2.from_id \
+                   INNER JOIN nodes AS c ON c.id = relationships_2.to_id \
+                   WHERE a.label = 'Person' AND relationships_1.type = 'KNOWS' AND b.label = 'Person' AND relationships_2.type = 'WORKS_AT' AND c.label = 'Company' AND (a.properties ->> 'age')::NUMERIC > 30",
+        mysql: "SELECT json_extract(a.properties, '$.name') AS a_name, json_extract(c.properties, '$.name') AS c_name \
+                FROM nodes AS a \
+                INNER JOIN relationships AS relationships_1 ON a.id = relationships_1.from_id \
+                INNER JOIN nodes AS b ON b.id = relationships_1.to_id \
+                INNER JOIN relationships AS relationships_2 ON b.id = relationships_2.from_id \
+                INNER JOIN nodes AS c ON c.id = relationships_2.to_id \
+                WHERE a.label = 'Person' AND relationships_1.type = 'KNOWS' AND b.label = 'Person' AND relationships_2.type = 'WORKS_AT' AND c.label = 'Company' AND json_extract(a.properties, '$.age') > 30",
+    },
+    Golden {
+        cypher: "OPTIONAL MATCH (n:Person) RETURN n.name",
+        label_tables: "SELECT n.name FROM Person AS n",
+        generic_tables: "SELECT json_extract(n.properties, '$.name') AS name \
+                         FROM nodes AS n \
+                         WHERE n.label = 'Person'",
+        postgres: "SELECT n.properties ->> 'name' AS name \
+                   FROM nodes AS n \
+                   WHERE n.label = 'Person'",
+        mysql: "SELECT json_extract(n.properties, '$.name') AS name \
+                FROM nodes AS n \
+                WHERE n.label = 'Person'",
+    },
+    Golden {
+        cypher: "MATCH (n:Person {name: 'Ada'}) RETURN n.age",
+        label_tables: "SELECT n.age FROM Person AS n WHERE n.name = 'Ada'",
+        generic_tables: "SELECT json_extract(n.properties, '$.age') AS age \
+                         FROM nodes AS n \
+                         WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = 'Ada'",
+        postgres: "SELECT n.properties ->> 'age' AS age \
+                   FROM nodes AS n \
+                   WHERE n.label = 'Person' AND n.properties ->> 'name' = 'Ada'",
+        mysql: "SELECT json_extract(n.properties, '$.age') AS age \
+                FROM nodes AS n \
+                WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = 'Ada'",
+    },
+    Golden {
+        cypher: "MATCH (n:Person) RETURN n.name ORDER BY n.age DESC LIMIT 10",
+        label_tables: "SELECT n.name FROM Person AS n ORDER BY n.age DESC LIMIT 10",
+        generic_tables: "SELECT json_extract(n.properties, '$.name') AS name \
+                         FROM nodes AS n \
+                         WHERE n.label = 'Person' \
+                         ORDER BY json_extract(n.properties, '$.age') DESC LIMIT 10",
+        postgres: "SELECT n.properties ->> 'name' AS name \
+                   FROM nodes AS n \
+                   WHERE n.label = 'Person' \
+                   ORDER BY n.properties -> 'age' DESC LIMIT 10",
+        mysql: "SELECT json_extract(n.properties, '$.name') AS name \
+                FROM nodes AS n \
+                WHERE n.label = 'Person' \
+                ORDER BY json_extract(n.properties, '$.age') DESC LIMIT 10",
+    },
+    Golden {
+        cypher: "MATCH (n:Person) WHERE n.name = $name AND n.active = true RETURN n",
+        label_tables: "SELECT n.* FROM Person AS n WHERE n.name = $name AND n.active = 1",
+        generic_tables: "SELECT json_object('id', n.id, 'label', n.label, 'properties', json(n.properties)) AS n \
+                         FROM nodes AS n \
+                         WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = $name AND json_extract(n.properties, '$.active') = 1",
+        postgres: "SELECT json_build_object('id', n.id, 'label', n.label, 'properties', n.properties) AS n \
+                   FROM nodes AS n \
+                   WHERE n.label = 'Person' AND n.properties ->> 'name' = $name AND (n.properties ->> 'active')::BOOLEAN = true",
+        mysql: "SELECT json_object('id', n.id, 'label', n.label, 'properties', n.properties) AS n \
+                FROM nodes AS n \
+                WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = $name AND json_extract(n.properties, '$.active') = true",
+    },
+];
+
+#[test]
+fn golden_outputs() {
+    for golden in GOLDEN {
+        let generic_on = |target| TransformConfig {
+            target,
+            ..generic_tables()
+        };
+        let cases: [(&str, TransformConfig, &dyn Dialect); 4] = [
+            (golden.label_tables, label_tables(), &SQLiteDialect {}),
+            (
+                golden.generic_tables,
+                generic_on(SqlTarget::Sqlite),
+                &SQLiteDialect {},
+            ),
+            (
+                golden.postgres,
+                generic_on(SqlTarget::Postgres),
+                &PostgreSqlDialect {},
+            ),
+            (golden.mysql, generic_on(SqlTarget::MySql), &MySqlDialect {}),
+        ];
+        for (expected, config, dialect) in cases {
+            let target = config.target;
+            let stmt = Parser::new(&CypherDialect)
+                .try_with_sql(golden.cypher)
+                .and_then(|mut parser| parser.parse_cypher_statement())
+                .unwrap();
+            let output = CypherToSqlTransformer::with_config(TransformConfig {
+                strict: false,
+                ..config.clone()
+            })
+            .to_sql_output(&stmt)
+            .unwrap();
+            let sql = output.sql;
+            assert_eq!(sql, expected, "{} on {target:?}", golden.cypher);
+            // Strict mode gives the same SQL, or rejects the first construct
+            // the SQL approximates
+            let strict = try_transform_with(golden.cypher, config);
+            match output.warnings.first() {
+                None => assert_eq!(strict.as_ref(), Ok(&sql), "{}", golden.cypher),
+                Some(warning) => assert_eq!(
+                    strict,
+                    Err(TransformError::Unsupported(format!(
+                        "{} in {}",
+                        warning.feature, warning.source_text
+                    ))),
+                    "{}",
+                    golden.cypher
+                ),
+            }
+            // The output is SQL the target's dialect parses back to itself
+            let statements = Parser::parse_sql(dialect, &sql)
+                .unwrap_or_else(|e| panic!("{sql} on {target:?}: {e}"));
+            assert_eq!(statements.len(), 1, "{sql}");
+            assert_eq!(statements[0].to_string(), sql, "{target:?}");
+        }
+    }
+}
+
+#[test]
+fn type_predicates() {
+    let on = |target| TransformConfig {
+        target,
+        ..generic_tables()
+    };
+    // Every type includes null, which is also the type of missing properties
+    let cypher = "MATCH (n) WHERE n.age IS :: INTEGER AND n.name IS NOT :: STRING RETURN n.id";
+    assert_eq!(
+        transform_with(cypher, on(SqlTarget::Sqlite)),
+        "SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n \
+         WHERE coalesce(json_type(n.properties, '$.age'), 'null') IN ('null', 'integer') \
+         AND coalesce(json_type(n.properties, '$.name'), 'null') NOT IN ('null', 'text')"
+    );
+    assert_eq!(
+        transform_with(cypher, on(SqlTarget::Postgres)),
+        "SELECT n.properties ->> 'id' AS id FROM nodes AS n \
+         WHERE (coalesce(jsonb_typeof(n.properties -> 'age'), 'null') = 'null' \
+         OR coalesce(jsonb_typeof(n.properties -> 'age'), 'null') = 'number' \
+         AND n.properties ->> 'age' NOT LIKE '%.%') \
+         AND coalesce(jsonb_typeof(n.properties -> 'name'), 'null') NOT IN ('null', 'string')"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n) RETURN n.ok IS :: BOOLEAN AS ok, n.tags IS :: LIST AS tags, n.x IS :: ANY",
+            on(SqlTarget::MySql)
+        ),
+        "SELECT coalesce(json_type(json_extract(n.properties, '$.ok')), 'NULL') \
+         IN ('NULL', 'BOOLEAN') AS ok, \
+         coalesce(json_type(json_extract(n.properties, '$.tags')), 'NULL') \
+         IN ('NULL', 'ARRAY') AS tags, true FROM nodes AS n"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n) WHERE n.score IS :: FLOAT RETURN n",
+            on(SqlTarget::DuckDb)
+        ),
+        "SELECT json_object('id', n.id, 'label', n.label, 'properties', json(n.properties)) \
+         AS n FROM nodes AS n \
+         WHERE coalesce(json_type(n.properties, '$.score'), 'NULL') IN ('NULL', 'DOUBLE')"
+    );
+
+    for (cypher, config, error) in [
+        (
+            "MATCH (n:Person) WHERE n.age IS :: INTEGER RETURN n",
+            label_tables(),
+            "type predicate n.age IS :: INTEGER on a value other than a property stored as JSON",
+        ),
+        (
+            "MATCH (n) WHERE n.born IS :: DATE RETURN n",
+            generic_tables(),
+            "type predicate n.born IS :: DATE, as JSON does not hold DATE values",
+        ),
+        (
+            "MATCH (n) RETURN n.tags IS :: LIST<STRING>",
+            generic_tables(),
+            "type predicate n.tags IS :: LIST<STRING>, as the types of list elements are not checked",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, config),
+            Err(TransformError::Unsupported(error.to_string())),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn show_catalog() {
+    let on = |target| TransformConfig {
+        target,
+        ..generic_tables()
+    };
+    for (target, databases, indexes) in [
+        (
+            SqlTarget::Sqlite,
+            "SELECT name FROM pragma_database_list",
+            "SELECT name, tbl_name AS table_name FROM sqlite_master WHERE type = 'index'",
+        ),
+        (
+            SqlTarget::Postgres,
+            "SELECT datname AS name FROM pg_database WHERE NOT datistemplate",
+            "SELECT indexname AS name, tablename AS table_name FROM pg_indexes \
+             WHERE schemaname = current_schema()",
+        ),
+        (
+            SqlTarget::MySql,
+            "SELECT schema_name AS name FROM information_schema.schemata",
+            "SELECT index_name AS name, table_name FROM information_schema.statistics \
+             WHERE table_schema = database() AND seq_in_index = 1",
+        ),
+        (
+            SqlTarget::DuckDb,
+            "SELECT database_name AS name FROM duckdb_databases()",
+            "SELECT index_name AS name, table_name FROM duckdb_indexes()",
+        ),
+    ] {
+        assert_eq!(transform_with("SHOW DATABASES", on(target)), databases);
+        assert_eq!(transform_with("SHOW INDEXES", on(target)), indexes);
+    }
+
+    // YIELD and WHERE apply to the rows of the catalog query
+    assert_eq!(
+        transform_with(
+            "SHOW INDEXES YIELD name, table_name AS t WHERE t = \"nodes\"",
+            on(SqlTarget::DuckDb)
+        ),
+        "SELECT name, table_name AS t FROM \
+         (SELECT index_name AS name, table_name FROM duckdb_indexes()) AS indexes \
+         WHERE table_name = 'nodes'"
+    );
+    assert_eq!(
+        transform_with("SHOW DATABASES YIELD *", generic_tables()),
+        "SELECT * FROM (SELECT name FROM pragma_database_list) AS databases"
+    );
+
+    for (cypher, error) in [
+        ("SHOW PROCEDURES", "SHOW PROCEDURES"),
+        ("SHOW CONSTRAINTS YIELD name", "SHOW CONSTRAINTS"),
+        (
+            "SHOW DATABASES WHERE currentStatus = 'online'",
+            "currentStatus in SHOW DATABASES, which yields only name",
+        ),
+        (
+            "SHOW INDEXES YIELD labelsOrTypes",
+            "labelsOrTypes in SHOW INDEXES, which yields only name, table_name",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables()),
+            Err(TransformError::Unsupported(error.to_string())),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn required_schema() {
+    let schema = |cypher: &str, config: TransformConfig| {
+        let stmt = Parser::new(&CypherDialect)
+            .try_with_sql(cypher)
+            .unwrap()
+            .parse_cypher_statement()
+            .unwrap();
+        CypherToSqlTransformer::with_config(config).required_schema(&stmt)
+    };
+    let on = |target, config: TransformConfig| TransformConfig { target, ..config };
+
+    let query = "MATCH (a:Person)-[r:KNOWS]->(b) WHERE a.name = 'Ann' RETURN b, r.since";
+    assert_eq!(
+        schema(query, on(SqlTarget::Sqlite, generic_tables())).ddl,
+        [
+            "CREATE TABLE nodes (id INTEGER PRIMARY KEY, label TEXT, properties TEXT)",
+            "CREATE TABLE relationships (from_id INTEGER, to_id INTEGER, type TEXT, properties TEXT)",
+            "CREATE INDEX nodes_label ON nodes (label)",
+            "CREATE INDEX relationships_from_id ON relationships (from_id)",
+            "CREATE INDEX relationships_to_id ON relationships (to_id)",
+            "CREATE INDEX relationships_type ON relationships (type)",
+        ]
+    );
+    assert_eq!(
+        schema(query, on(SqlTarget::Postgres, generic_tables())).ddl[..2],
+        [
+            "CREATE TABLE nodes (id BIGINT PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY, \
+             label TEXT, properties JSONB)",
+            "CREATE TABLE relationships (from_id BIGINT, to_id BIGINT, type TEXT, properties JSONB)",
+        ]
+    );
+    // A relationship id column is the key of the relationships
+    let rel_ids = TransformConfig {
+        relationship_id_column: Some("rel_id".to_string()),
+        ..on(SqlTarget::MySql, generic_tables())
+    };
+    assert_eq!(
+        schema(query, rel_ids).ddl[1],
+        "CREATE TABLE relationships (rel_id BIGINT PRIMARY KEY AUTO_INCREMENT, \
+         from_id BIGINT, to_id BIGINT, type VARCHAR(255), properties JSON)"
+    );
+
+    // Label tables have a column per property, typed by the literals it is
+    // compared with or stored as
+    let query = "MATCH (a:Person)-[:LIVES_IN]->(c:City) \
+                 WHERE a.age > 30 AND c.area < 10.5 AND a.active = true \
+                 RETURN a.name, c";
+    let requirements = schema(query, on(SqlTarget::Sqlite, label_tables()));
+    let tables: Vec<_> = requirements.tables.iter().map(|t| &t.name).collect();
+    assert_eq!(tables, ["Person", "relationships", "City"]);
+    assert!(requirements.tables[0].label_table);
+    assert_eq!(
+        requirements.ddl[..3],
+        [
+            "CREATE TABLE Person (id INTEGER PRIMARY KEY, active BOOLEAN, age INTEGER, name TEXT)",
+            "CREATE TABLE relationships (from_id INTEGER, to_id INTEGER, type TEXT, properties TEXT)",
+            "CREATE TABLE City (id INTEGER PRIMARY KEY, area REAL)",
+        ]
+    );
+    assert_eq!(
+        schema(query, on(SqlTarget::Postgres, label_tables())).ddl[..3],
+        [
+            "CREATE TABLE Person (id BIGINT PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY, \
+             active BOOLEAN, age BIGINT, name TEXT)",
+            "CREATE TABLE relationships (from_id BIGINT, to_id BIGINT, type TEXT, properties JSONB)",
+            "CREATE TABLE City (id BIGINT PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY, \
+             area DOUBLE PRECISION)",
+        ]
+    );
+
+    // The ids the database assigns are drawn from a sequence on DuckDB
+    assert_eq!(
+        schema(
+            "CREATE (:Person {name: 'Ann'})",
+            on(SqlTarget::DuckDb, generic_tables())
+        )
+        .ddl,
+        [
+            "CREATE SEQUENCE nodes_id_seq",
+            "CREATE TABLE nodes (id BIGINT PRIMARY KEY DEFAULT nextval('nodes_id_seq'), \
+             label VARCHAR, properties JSON)",
+            "CREATE INDEX nodes_label ON nodes (label)",
+        ]
+    );
+    let uuids = TransformConfig {
+        id_type: IdType::Uuid,
+        ..on(SqlTarget::Postgres, label_tables())
+    };
+    assert_eq!(
+        schema("CREATE (:Person)", uuids).ddl,
+        ["CREATE TABLE Person (id UUID PRIMARY KEY)"]
+    );
+
+    // A union needs the tables of both queries, and SHOW none
+    let requirements = schema(
+        "MATCH (a:Person) RETURN a.name UNION MATCH (c:City) RETURN c.name",
+        label_tables(),
+    );
+    let tables: Vec<_> = requirements.tables.iter().map(|t| &t.name).collect();
+    assert_eq!(tables, ["Person", "City"]);
+    assert_eq!(schema("SHOW INDEXES", label_tables()), Default::default());
+
+    // Anonymous end nodes that are not joined need no node table
+    let requirements = schema("MATCH ()-[r:KNOWS]->() RETURN count(r)", label_tables());
+    let tables: Vec<_> = requirements.tables.iter().map(|t| &t.name).collect();
+    assert_eq!(tables, ["relationships"]);
+}
+
+#[test]
+fn property_accesses_rewritten_at_any_depth() {
+    let from = "FROM nodes AS a INNER JOIN relationships ON a.id = relationships.from_id \
+                INNER JOIN nodes AS b ON b.id = relationships.to_id";
+    let age = |v: &str| format!("json_extract({v}.properties, '$.age')");
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person)-[:KNOWS]->(b:Person) WHERE a.age > b.age RETURN a.name",
+            generic_tables()
+        ),
+        format!(
+            "SELECT json_extract(a.properties, '$.name') AS name {from} \
+             WHERE a.label = 'Person' AND relationships.type = 'KNOWS' \
+             AND b.label = 'Person' AND {} > {}",
+            age("a"),
+            age("b")
+        )
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[:KNOWS]->(b) \
+             WHERE abs(a.age - 30) < 5 AND b.age BETWEEN 18 AND a.age AND a.email IS NULL \
+             RETURN CASE WHEN a.age > b.age THEN 'older' ELSE b.name END AS relative",
+            generic_tables()
+        ),
+        format!(
+            "SELECT CASE WHEN {} > {} THEN 'older' \
+             ELSE json_extract(b.properties, '$.name') END AS relative {from} \
+             WHERE relationships.type = 'KNOWS' AND abs({} - 30) < 5 \
+             AND {} BETWEEN 18 AND {} AND json_extract(a.properties, '$.email') IS NULL",
+            age("a"),
+            age("b"),
+            age("a"),
+            age("b"),
+            age("a")
+        )
+    );
+}
+
+#[test]
+fn identifier_quoting() {
+    let query = "MATCH (order:Order)-[:CONTAINS]->(item) WHERE order.total > 10 \
+                 RETURN order.`Total Price` AS price, item.sku";
+    let quoted = |target, quoting| {
+        transform_with(
+            query,
+            TransformConfig {
+                target,
+                quoting,
+                ..label_tables()
+            },
+        )
+    };
+
+    assert_eq!(
+        quoted(SqlTarget::Postgres, IdentifierQuoting::Always),
+        "SELECT \"order\".\"Total Price\" AS \"price\", \
+         \"item\".\"properties\" ->> 'sku' AS \"sku\" \
+         FROM \"Order\" AS \"order\" \
+         INNER JOIN \"relationships\" ON \"order\".\"id\" = \"relationships\".\"from_id\" \
+         INNER JOIN \"nodes\" AS \"item\" ON \"item\".\"id\" = \"relationships\".\"to_id\" \
+         WHERE \"relationships\".\"type\" = 'CONTAINS' AND \"order\".\"total\" > 10"
+    );
+    assert_eq!(
+        quoted(SqlTarget::MySql, IdentifierQuoting::Always),
+        "SELECT `order`.`Total Price` AS `price`, \
+         json_extract(`item`.`properties`, '$.sku') AS `sku` \
+         FROM `Order` AS `order` \
+         INNER JOIN `relationships` ON `order`.`id` = `relationships`.`from_id` \
+         INNER JOIN `nodes` AS `item` ON `item`.`id` = `relationships`.`to_id` \
+         WHERE `relationships`.`type` = 'CONTAINS' AND `order`.`total` > 10"
+    );
+
+    // Only reserved words and names that are not plain identifiers are
+    // quoted, along with upper case names that PostgreSQL would fold
+    let from = |quote: &str, table: &str| {
+        format!(
+            "FROM {table} AS {quote}order{quote} \
+             INNER JOIN relationships ON {quote}order{quote}.id = relationships.from_id \
+             INNER JOIN nodes AS item ON item.id = relationships.to_id \
+             WHERE relationships.type = 'CONTAINS' AND {quote}order{quote}.total > 10"
+        )
+    };
+    assert_eq!(
+        quoted(SqlTarget::Postgres, IdentifierQuoting::WhenNeeded),
+        format!(
+            "SELECT \"order\".\"Total Price\" AS price, \
+             item.properties ->> 'sku' AS sku {}",
+            from("\"", "\"Order\"")
+        )
+    );
+    assert_eq!(
+        quoted(SqlTarget::Sqlite, IdentifierQuoting::WhenNeeded),
+        format!(
+            "SELECT \"order\".\"Total Price\" AS price, \
+             json_extract(item.properties, '$.sku') AS sku {}",
+            from("\"", "\"Order\"")
+        )
+    );
+    assert_eq!(
+        quoted(SqlTarget::MySql, IdentifierQuoting::WhenNeeded),
+        format!(
+            "SELECT `order`.`Total Price` AS price, \
+             json_extract(item.properties, '$.sku') AS sku {}",
+            from("`", "`Order`")
+        )
+    );
+
+    // Without quoting, a quoted Cypher variable keeps its backticks, and a
+    // table named after a reserved word is still quoted
+    assert_eq!(
+        quoted(SqlTarget::Sqlite, IdentifierQuoting::None),
+        format!(
+            "SELECT order.`Total Price` AS price, \
+             json_extract(item.properties, '$.sku') AS sku {}",
+            from("", "\"Order\"")
+        )
+    );
+    let query = "MATCH (`order`:Order)-[:CONTAINS]->(`match`) WHERE `order`.total > 10 \
+                 RETURN `order`.total, `match`.sku ORDER BY `order`.total";
+    assert_eq!(
+        transform_with(query, generic_tables()),
+        "SELECT json_extract(`order`.properties, '$.total') AS total, \
+         json_extract(`match`.properties, '$.sku') AS sku \
+         FROM nodes AS `order` \
+         INNER JOIN relationships ON `order`.id = relationships.from_id \
+         INNER JOIN nodes AS `match` ON `match`.id = relationships.to_id \
+         WHERE `order`.label = 'Order' AND relationships.type = 'CONTAINS' \
+         AND json_extract(`order`.properties, '$.total') > 10 ORDER BY total"
+    );
+    assert_eq!(
+        transform_with(query, label_tables()),
+        "SELECT `order`.total, json_extract(`match`.properties, '$.sku') AS sku \
+         FROM \"Order\" AS `order` \
+         INNER JOIN relationships ON `order`.id = relationships.from_id \
+         INNER JOIN nodes AS `match` ON `match`.id = relationships.to_id \
+         WHERE relationships.type = 'CONTAINS' AND `order`.total > 10 \
+         ORDER BY `order`.total"
+    );
+    // Anonymous elements are referred to by their quoted tables
+    assert_eq!(
+        transform_with(
+            "MATCH (:Group)<-[:IN]-(:User {name: 'x'}) CREATE (:Order {total: 1})",
+            label_tables()
+        ),
+        "INSERT INTO \"Order\" (total) SELECT 1 FROM \"Group\" \
+         INNER JOIN relationships ON \"Group\".id = relationships.to_id \
+         INNER JOIN \"User\" ON \"User\".id = relationships.from_id \
+         WHERE relationships.type = 'IN' AND \"User\".name = 'x'"
+    );
+
+    // The DDL of the required schema is quoted alike
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql("MATCH (n:Person) RETURN n.name")
+        .unwrap()
+        .parse_cypher_statement()
+        .unwrap();
+    let config = TransformConfig {
+        target: SqlTarget::Postgres,
+        quoting: IdentifierQuoting::WhenNeeded,
+        ..label_tables()
+    };
+    assert_eq!(
+        CypherToSqlTransformer::with_config(config)
+            .required_schema(&stmt)
+            .ddl,
+        ["CREATE TABLE \"Person\" (id BIGINT PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY, name TEXT)"]
+    );
+}
+
+#[test]
+fn membership_in_labels_and_keys() {
+    let on = |target| TransformConfig {
+        target,
+        ..generic_tables()
+    };
+    let query = "MATCH (n) WHERE 'Admin' IN labels(n) AND NOT 'email' IN keys(n) RETURN n.name";
+    let from = "FROM nodes AS n WHERE n.label = 'Admin' AND NOT";
+    assert_eq!(
+        transform_with(query, on(SqlTarget::Sqlite)),
+        format!(
+            "SELECT json_extract(n.properties, '$.name') AS name {from} \
+             json_extract(n.properties, '$.email') IS NOT NULL"
+        )
+    );
+    assert_eq!(
+        transform_with(query, on(SqlTarget::Postgres)),
+        format!(
+            "SELECT n.properties ->> 'name' AS name {from} \
+             n.properties -> 'email' IS NOT NULL"
+        )
+    );
+
+    // A key that is not a literal is appended to the JSON path
+    assert_eq!(
+        transform_with(
+            "MATCH (a)-[r:KNOWS]->(b) WHERE $key IN keys(r) RETURN b.name",
+            on(SqlTarget::MySql)
+        ),
+        "SELECT json_extract(b.properties, '$.name') AS name FROM nodes AS a \
+         INNER JOIN relationships AS r ON a.id = r.from_id \
+         INNER JOIN nodes AS b ON b.id = r.to_id \
+         WHERE r.type = 'KNOWS' AND json_extract(r.properties, concat('$.', $key)) IS NOT NULL"
+    );
+
+    // Label tables hold the nodes of their label, with a column per key
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE 'Admin' IN labels(n) OR 'email' IN keys(n) RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n WHERE 0 OR n.email IS NOT NULL"
+    );
+
+    for (query, error) in [
+        (
+            "MATCH (n) WHERE n.id IN $ids RETURN n",
+            "n.id IN $ids, as only list literals and the labels() and keys() of a variable \
+             are translated",
+        ),
+        (
+            "MATCH ()-[r]->() WHERE 'KNOWS' IN labels(r) RETURN r",
+            "'KNOWS' IN labels(r), as labels() does not apply to relationships",
+        ),
+        (
+            "MATCH (n:Person) WHERE $key IN keys(n) RETURN n",
+            "$key IN keys(n), as the columns of a label table are not known",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(query, label_tables()),
+            Err(TransformError::Unsupported(error.to_string())),
+            "{query}"
+        );
+    }
+}
+
+#[test]
+fn named_path_functions() {
+    let from = "FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+                INNER JOIN nodes AS b ON b.id = r.to_id WHERE r.type = 'KNOWS'";
+    assert_eq!(
+        transform_with(
+            "MATCH p = (a)-[r:KNOWS]->(b) WHERE length(p) < 3 RETURN length(p) AS hops",
+            generic_tables()
+        ),
+        format!("SELECT 1 AS hops {from} AND 1 < 3")
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH p = (a)-[r:KNOWS]->(b) RETURN nodes(p) AS n, relationships(p) AS rels",
+            TransformConfig {
+                target: SqlTarget::Postgres,
+                ..generic_tables()
+            }
+        ),
+        format!(
+            "SELECT json_build_array(\
+             json_build_object('id', a.id, 'label', a.label, 'properties', a.properties), \
+             json_build_object('id', b.id, 'label', b.label, 'properties', b.properties)) AS n, \
+             json_build_array(json_build_object('from_id', r.from_id, 'to_id', r.to_id, \
+             'type', r.type, 'properties', r.properties)) AS rels {from}"
+        )
+    );
+
+    // A path returned whole is the array of its nodes and relationships, or
+    // null when an OPTIONAL MATCH leaves it unmatched
+    let node = |v: &str| {
+        format!("json_object('id', {v}.id, 'label', {v}.label, 'properties', json({v}.properties))")
+    };
+    let relationship = "json_object('from_id', r.from_id, 'to_id', r.to_id, 'type', r.type, \
+                        'properties', json(r.properties))";
+    assert_eq!(
+        transform_with(
+            "MATCH p = (a)-[r:KNOWS]->(b) RETURN p, p AS path",
+            generic_tables()
+        ),
+        format!(
+            "SELECT json_array({a}, {relationship}, {b}) AS p, \
+             json_array({a}, {relationship}, {b}) AS path {from}",
+            a = node("a"),
+            b = node("b"),
+        )
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a) OPTIONAL MATCH p = (a)-[r:KNOWS]->(b) RETURN p",
+            generic_tables()
+        ),
+        format!(
+            "SELECT CASE WHEN r.from_id IS NULL THEN NULL ELSE json_array({a}, \
+             CASE WHEN r.from_id IS NULL THEN NULL ELSE {relationship} END, \
+             CASE WHEN b.id IS NULL THEN NULL ELSE {b} END) END AS p \
+             FROM nodes AS a LEFT JOIN (relationships AS r \
+             INNER JOIN nodes AS b ON b.id = r.to_id) ON a.id = r.from_id AND r.type = 'KNOWS'",
+            a = node("a"),
+            b = node("b"),
+        )
+    );
+
+    // length() needs none of the columns of a path of label tables
+    assert_eq!(
+        transform_with(
+            "MATCH p = (a:Person)-[:KNOWS]->(b) RETURN length(p)",
+            label_tables()
+        ),
+        "SELECT 1 FROM Person AS a INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN nodes AS b ON b.id = relationships.to_id WHERE relationships.type = 'KNOWS'"
+    );
+
+    for (query, error) in [
+        (
+            "MATCH p = (a)-[:KNOWS*2]->(b) RETURN p",
+            TransformError::Unsupported(
+                "path variable p, as its variable-length relationships are matched as single hops"
+                    .to_string(),
+            ),
+        ),
+        (
+            "MATCH p = (a:Person)-[:KNOWS]->(b) RETURN p",
+            TransformError::Unsupported(
+                "path variable p, as the columns of a label table are not known".to_string(),
+            ),
+        ),
+        (
+            "MATCH p = (a)-[:KNOWS*2]->(b) RETURN length(p)",
+            TransformError::Unsupported(
+                "length(p), as the variable-length relationships of p are matched as single hops"
+                    .to_string(),
+            ),
+        ),
+        (
+            "MATCH p = (a:Person)-[:KNOWS]->(b) RETURN nodes(p)",
+            TransformError::Unsupported(
+                "nodes(p), as the columns of a label table are not known".to_string(),
+            ),
+        ),
+        (
+            "MATCH p = (a)-[r:KNOWS]->(b) WHERE length(r) > 1 RETURN b",
+            TransformError::InvalidQuery(
+                "length(r) expects a path, but r is a relationship".to_string(),
+            ),
+        ),
+    ] {
+        // Checked without strict, which would reject variable-length
+        // relationships before the functions on their paths
+        let config = TransformConfig {
+            strict: false,
+            ..label_tables()
+        };
+        assert_eq!(try_transform_with(query, config), Err(error), "{query}");
+    }
+}
+
+#[test]
+fn unicode_names() {
+    let query = "MATCH\u{A0}(n:Город)\u{A0}WHERE n.größe > 1 RETURN n.größe";
+    assert_eq!(
+        transform_with(query, generic_tables()),
+        "SELECT json_extract(n.properties, '$.größe') AS größe FROM nodes AS n \
+         WHERE n.label = 'Город' AND json_extract(n.properties, '$.größe') > 1"
+    );
+    assert_eq!(
+        transform_with(query, label_tables()),
+        "SELECT n.größe FROM Город AS n WHERE n.größe > 1"
+    );
+    assert_eq!(
+        transform_with(
+            query,
+            TransformConfig {
+                target: SqlTarget::Postgres,
+                quoting: IdentifierQuoting::WhenNeeded,
+                ..label_tables()
+            }
+        ),
+        r#"SELECT n."größe" FROM "Город" AS n WHERE n."größe" > 1"#
+    );
+}
+
+#[test]
+fn postgres_properties_cast_to_their_operators_types() {
+    let postgres = TransformConfig {
+        target: SqlTarget::Postgres,
+        ..generic_tables()
+    };
+    let age = "(n.properties ->> 'age')::NUMERIC";
+    let active = "(n.properties ->> 'active')::BOOLEAN";
+    let cases = [
+        (
+            "MATCH (n) WHERE n.age > 30 AND n.active = true RETURN n.age + 1",
+            format!("SELECT {age} + 1 FROM nodes AS n WHERE {age} > 30 AND {active} = true"),
+        ),
+        (
+            "MATCH (n) WHERE n.active RETURN sum(n.age), avg(n.age)",
+            format!("SELECT sum({age}), avg({age}) FROM nodes AS n WHERE {active}"),
+        ),
+        (
+            "MATCH (n) WHERE NOT n.active OR n.age IN [1, 2] RETURN -n.age",
+            format!("SELECT -{age} FROM nodes AS n WHERE NOT {active} OR {age} IN (1, 2)"),
+        ),
+        // Strings, parameters and other properties are compared as text
+        (
+            "MATCH (n) WHERE n.name = 'Ada' AND n.age = $age AND n.age > n.min RETURN n.name",
+            "SELECT n.properties ->> 'name' AS name FROM nodes AS n \
+             WHERE n.properties ->> 'name' = 'Ada' AND n.properties ->> 'age' = $age \
+             AND n.properties ->> 'age' > n.properties ->> 'min'"
+                .to_string(),
+        ),
+    ];
+    for (cypher, expected) in cases {
+        let sql = transform_with(cypher, postgres.clone());
+        assert_eq!(sql, expected);
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, &sql).unwrap();
+        assert_eq!(statements[0].to_string(), sql);
+    }
+}
+
+#[test]
+fn json_path_escaping() {
+    let query = "MATCH (n) WHERE n.`$x` = 1 \
+                 RETURN n.`weird.key`, n.`a\"b`, n.`it's`, n.`größe` ORDER BY n.`weird.key`";
+    assert_eq!(
+        transform_with(query, generic_tables()),
+        r#"SELECT json_extract(n.properties, '$."weird.key"') AS "weird.key", json_extract(n.properties, '$."a\"b"') AS "a""b", json_extract(n.properties, '$."it''s"') AS "it's", json_extract(n.properties, '$.größe') AS größe FROM nodes AS n WHERE json_extract(n.properties, '$."$x"') = 1 ORDER BY "weird.key""#
+    );
+    assert_eq!(
+        transform_with(
+            query,
+            TransformConfig {
+                target: SqlTarget::Postgres,
+                ..generic_tables()
+            }
+        ),
+        r#"SELECT n.properties ->> 'weird.key' AS "weird.key", n.properties ->> 'a"b' AS "a""b", n.properties ->> 'it''s' AS "it's", n.properties ->> 'größe' AS größe FROM nodes AS n WHERE (n.properties ->> '$x')::NUMERIC = 1 ORDER BY n.properties -> 'weird.key'"#
+    );
+    assert_eq!(
+        transform_with(
+            query,
+            TransformConfig {
+                target: SqlTarget::MySql,
+                ..generic_tables()
+            }
+        ),
+        r#"SELECT json_extract(n.properties, '$."weird.key"') AS `weird.key`, json_extract(n.properties, '$."a\\"b"') AS `a"b`, json_extract(n.properties, '$."it''s"') AS `it's`, json_extract(n.properties, '$.größe') AS größe FROM nodes AS n WHERE json_extract(n.properties, '$."$x"') = 1 ORDER BY `weird.key`"#
+    );
+
+    // A backslash is escaped in the path, and again in a MySQL string
+    let query = r"MATCH (n) WHERE n.`a\b` IS :: INTEGER RETURN n.id";
+    assert_eq!(
+        transform_with(
+            query,
+            TransformConfig {
+                target: SqlTarget::DuckDb,
+                ..generic_tables()
+            }
+        ),
+        r#"SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n WHERE coalesce(json_type(n.properties, '$."a\\b"'), 'NULL') IN ('NULL', 'BIGINT', 'UBIGINT')"#
+    );
+    assert_eq!(
+        transform_with(
+            query,
+            TransformConfig {
+                target: SqlTarget::MySql,
+                ..generic_tables()
+            }
+        ),
+        r#"SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n WHERE coalesce(json_type(json_extract(n.properties, '$."a\\\\b"')), 'NULL') IN ('NULL', 'INTEGER', 'UNSIGNED INTEGER')"#
+    );
+
+    // The keys of created properties are JSON strings
+    assert_eq!(
+        transform_with(
+            r#"CREATE (n:Thing {`weird.key`: 1, `a"b`: 2, `it's`: 3, `$x`: 4, `größe`: 5})"#,
+            generic_tables()
+        ),
+        r#"INSERT INTO nodes (label, properties) VALUES ('Thing', '{"weird.key":1,"a\"b":2,"it''s":3,"$x":4,"größe":5}')"#
+    );
+}
+
+#[test]
+fn unwind_range() {
+    let on = |target| TransformConfig {
+        target,
+        ..generic_tables()
+    };
+
+    // A short range of literals is listed on every target
+    let query = "UNWIND range(10, 1, -3) AS x RETURN x";
+    assert_eq!(
+        transform_with(query, generic_tables()),
+        "WITH unwound (x) AS (VALUES (10), (7), (4), (1)) SELECT x FROM unwound"
+    );
+    assert_eq!(
+        transform_with(query, on(SqlTarget::MySql)),
+        "WITH unwound (x) AS (VALUES ROW(10), ROW(7), ROW(4), ROW(1)) SELECT x FROM unwound"
+    );
+    assert_eq!(
+        transform_with("UNWIND range(5, 1) AS x RETURN x", generic_tables()),
+        "WITH unwound (x) AS (SELECT NULL WHERE 1 = 0) SELECT x FROM unwound"
+    );
+
+    // A longer one is generated
+    let query = "UNWIND range(1, 1000) AS i CREATE (:Item {n: i})";
+    assert_eq!(
+        transform_with(query, on(SqlTarget::Postgres)),
+        "INSERT INTO nodes (label, properties) SELECT 'Item', json_build_object('n', i) \
+         FROM generate_series(1, 1000) AS unwound (i)"
+    );
+    assert_eq!(
+        transform_with(query, generic_tables()),
+        "INSERT INTO nodes (label, properties) WITH RECURSIVE unwound (i) AS \
+         (SELECT 1 WHERE 1 <= 1000 UNION ALL SELECT i + 1 FROM unwound WHERE i + 1 <= 1000) \
+         SELECT 'Item', json_object('n', i) FROM unwound"
+    );
+    assert_eq!(
+        transform_with(
+            "UNWIND range(1, 3) AS i RETURN i",
+            TransformConfig {
+                range_values_limit: 2,
+                ..on(SqlTarget::DuckDb)
+            }
+        ),
+        "SELECT i FROM generate_series(1, 3) AS unwound (i)"
+    );
+    assert_eq!(
+        transform_with(
+            "UNWIND range(0, -10, -5) AS i RETURN i",
+            TransformConfig {
+                range_values_limit: 0,
+                ..on(SqlTarget::MySql)
+            }
+        ),
+        "WITH RECURSIVE unwound (i) AS (SELECT 0 WHERE 0 >= -10 \
+         UNION ALL SELECT i + -5 FROM unwound WHERE i + -5 >= -10) SELECT i FROM unwound"
+    );
+
+    // The direction of a step that is not a literal is only known to the
+    // database
+    let query =
+        "UNWIND range($first, $last, $step) AS i MATCH (n:Person) WHERE n.age = i RETURN n.name";
+    assert_eq!(
+        transform_with(query, generic_tables()),
+        "WITH RECURSIVE unwound (i) AS (SELECT $first WHERE ($step > 0 AND $first <= $last \
+         OR $step < 0 AND $first >= $last) UNION ALL SELECT i + $step FROM unwound \
+         WHERE ($step > 0 AND i + $step <= $last OR $step < 0 AND i + $step >= $last)) \
+         SELECT json_extract(n.properties, '$.name') AS name FROM unwound, nodes AS n \
+         WHERE n.label = 'Person' AND json_extract(n.properties, '$.age') = i"
+    );
+    assert_eq!(
+        transform_with(query, on(SqlTarget::Postgres)),
+        "SELECT n.properties ->> 'name' AS name \
+         FROM generate_series($first, $last, $step) AS unwound (i), nodes AS n \
+         WHERE n.label = 'Person' AND n.properties ->> 'age' = i"
+    );
+
+    for (query, error) in [
+        (
+            "UNWIND range(1, 5, 0) AS i RETURN i",
+            "invalid Cypher query: range(1, 5, 0) has a step of 0",
+        ),
+        (
+            "UNWIND range(1) AS i RETURN i",
+            "invalid Cypher query: range(1) takes a start, a stop and an optional step",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(query, generic_tables())
+                .unwrap_err()
+                .to_string(),
+            error
+        );
+    }
+}
+
+#[test]
+fn unwind_list() {
+    assert_eq!(
+        transform_with(
+            "UNWIND ['a', 'b'] AS name RETURN name, count(*) AS n",
+            generic_tables()
+        ),
+        "WITH unwound (name) AS (VALUES ('a'), ('b')) \
+         SELECT name, count(*) AS n FROM unwound GROUP BY name"
+    );
+    assert_eq!(
+        transform_with("UNWIND [] AS x RETURN x", generic_tables()),
+        "WITH unwound (x) AS (SELECT NULL WHERE 1 = 0) SELECT x FROM unwound"
+    );
+    // The rows a WITH passes on are those of its subquery
+    assert_eq!(
+        transform_with(
+            "UNWIND [3, 1, 2] AS x WITH x ORDER BY x LIMIT 2 RETURN x",
+            generic_tables()
+        ),
+        "WITH unwound (x) AS (VALUES (3), (1), (2)) SELECT with_rows.x AS x \
+         FROM (SELECT x AS x FROM unwound ORDER BY x LIMIT 2) AS with_rows"
+    );
+    assert_eq!(
+        transform_with(
+            "UNWIND [1, 2] AS i CREATE (:Item {n: i, double: i * 2, kind: 'x'})",
+            label_tables()
+        ),
+        "INSERT INTO Item (n, double, kind) WITH unwound (i) AS (VALUES (1), (2)) \
+         SELECT i, i * 2, 'x' FROM unwound"
+    );
+
+    for (query, error) in [
+        (
+            "UNWIND [1, 2] AS i CREATE (a:A {n: i})-[:R]->(b:B)",
+            "relationship -[:R]-> in a CREATE after UNWIND",
+        ),
+        (
+            "UNWIND [1, 2] AS i MERGE (a:A {n: i})",
+            "MERGE (a:A {n: i}) after UNWIND",
+        ),
+        (
+            "UNWIND [1] AS a UNWIND [2] AS b RETURN a, b",
+            "UNWIND [2] AS b RETURN a, b after UNWIND",
+        ),
+        (
+            "UNWIND $ids AS id RETURN id",
+            "UNWIND of $ids, which is neither a list literal nor a range()",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(query, generic_tables())
+                .unwrap_err()
+                .to_string(),
+            format!("unsupported Cypher feature: {error}")
+        );
+    }
+}
+
+/// Translates the functions of a deployment: `similarity(a, b)` to the
+/// database's vector similarity, `tenant()` to a property of the first
+/// node, and `timestamp()` to a function of the database
+#[derive(Debug)]
+struct DeploymentFunctions;
+
+impl FunctionRewriter for DeploymentFunctions {
+    fn rewrite(&self, name: &str, args: &[Expr], ctx: &RewriteContext) -> Option<Expr> {
+        match (name.to_lowercase().as_str(), args) {
+            ("similarity", [left, right]) => {
+                let function = match ctx.target() {
+                    SqlTarget::Postgres => "cosine_similarity",
+                    _ => "vec_cosine",
+                };
+                Some(ctx.function(function, vec![ctx.rewrite(left), ctx.rewrite(right)]))
+            }
+            // The tenant of the first node of the query
+            ("tenant", []) => {
+                let binding = ctx.plan().bindings.first()?;
+                Some(ctx.property(&binding.variable.as_ref()?.value, "tenant"))
+            }
+            ("timestamp", []) => Some(ctx.function("clock_ms", vec![])),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn function_rewriters() {
+    let with_rewriter = |config: TransformConfig| TransformConfig {
+        function_rewriters: vec![Arc::new(DeploymentFunctions)],
+        ..config
+    };
+    let cypher = "MATCH (a:Doc), (b:Doc) WHERE similarity(a.embedding, b.embedding) > 0.9 \
+                  RETURN a.title, SIMILARITY(a.embedding, $query) AS s";
+    assert_eq!(
+        transform_with(cypher, with_rewriter(generic_tables())),
+        "SELECT json_extract(a.properties, '$.title') AS title, \
+         vec_cosine(json_extract(a.properties, '$.embedding'), $query) AS s \
+         FROM nodes AS a, nodes AS b WHERE a.label = 'Doc' AND b.label = 'Doc' \
+         AND vec_cosine(json_extract(a.properties, '$.embedding'), \
+         json_extract(b.properties, '$.embedding')) > 0.9"
+    );
+    assert_eq!(
+        transform_with(
+            cypher,
+            with_rewriter(TransformConfig {
+                target: SqlTarget::Postgres,
+                ..label_tables()
+            })
+        ),
+        "SELECT a.title, cosine_similarity(a.embedding, $query) AS s FROM Doc AS a, Doc AS b \
+         WHERE cosine_similarity(a.embedding, b.embedding) > 0.9"
+    );
+
+    // Rewriters come before the built-in translations, and the functions
+    // without arguments they take are not warned about
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql("MATCH (n:Person) RETURN tenant() AS t, timestamp() AS ts, pi() AS p")
+        .unwrap()
+        .parse_cypher_statement()
+        .unwrap();
+    let output = CypherToSqlTransformer::with_config(with_rewriter(TransformConfig {
+        strict: false,
+        ..label_tables()
+    }))
+    .to_sql_output(&stmt)
+    .unwrap();
+    assert_eq!(
+        output.sql,
+        "SELECT n.tenant AS t, clock_ms() AS ts, pi() AS p FROM Person AS n"
+    );
+    let warned: Vec<_> = output.warnings.iter().map(|w| &w.source_text).collect();
+    assert_eq!(warned, ["pi()"]);
+
+    // Calls no rewriter takes are translated as before
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) RETURN similarity(n.a) AS s",
+            with_rewriter(label_tables())
+        ),
+        "SELECT similarity(n.a) AS s FROM Person AS n"
+    );
+}
+
+#[test]
+fn queries_without_match() {
+    for (cypher, sql) in [
+        ("RETURN 1 + 1 AS two", "SELECT 1 + 1 AS two"),
+        ("RETURN $x AS echoed", "SELECT $x AS echoed"),
+        (
+            "WITH 2 AS a WHERE a > 1 RETURN a ORDER BY a LIMIT 1",
+            "SELECT 2 AS a WHERE 2 > 1 ORDER BY a LIMIT 1",
+        ),
+        (
+            "RETURN 1 AS x UNION RETURN 2 AS x",
+            "SELECT 1 AS x UNION SELECT 2 AS x",
+        ),
+        (
+            "WITH 'Ada' AS name MATCH (n:Person) WHERE n.name = name RETURN n.age",
+            "SELECT json_extract(n.properties, '$.age') AS age FROM (SELECT 'Ada' AS name) AS with_rows, \
+             nodes AS n WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = with_rows.name",
+        ),
+    ] {
+        assert_eq!(transform_with(cypher, generic_tables()), sql, "{cypher}");
+    }
+}
+
+#[test]
+fn condition_order_does_not_depend_on_spelling() {
+    let sql = "SELECT json_extract(b.properties, '$.name') AS name FROM nodes AS a \
+               INNER JOIN relationships AS r ON a.id = r.from_id \
+               INNER JOIN nodes AS b ON b.id = r.to_id \
+               WHERE a.label = 'Admin' AND a.label = 'Person' \
+               AND (r.type = 'KNOWS' OR r.type = 'LIKES') AND b.label = 'City' \
+               AND json_extract(b.properties, '$.size') > 3";
+    for cypher in [
+        "MATCH (a:Person:Admin)-[r:KNOWS|LIKES]->(b:City) WHERE b.size > 3 RETURN b.name",
+        "MATCH (a:Admin:Person)-[r:LIKES|KNOWS]->(b:City) WHERE b.size > 3 RETURN b.name",
+        "MATCH (a:Person)-[r:KNOWS|LIKES]->(b) WHERE b:City AND a:Admin AND b.size > 3 \
+         RETURN b.name",
+        "MATCH (a:Admin), (a:Person)-[r:KNOWS|LIKES]->(b:City) WHERE b.size > 3 RETURN b.name",
+    ] {
+        let lenient = TransformConfig {
+            strict: false,
+            ..generic_tables()
+        };
+        assert_eq!(transform_with(cypher, lenient), sql, "{cypher}");
+    }
+}
+
+#[test]
+fn where_disjunction_keeps_its_grouping() {
+    // Without the parentheses the label condition would only apply to the
+    // first branch, returning VIP nodes of any label
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE NOT (n.age > 30 AND n.city = 'Oslo') OR n.vip = true \
+             RETURN n.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE n.label = 'Person' AND (NOT (json_extract(n.properties, '$.age') > 30 \
+         AND json_extract(n.properties, '$.city') = 'Oslo') \
+         OR json_extract(n.properties, '$.vip') = 1)"
+    );
+    // A label predicate under XOR stays in the WHERE clause
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE n:Admin XOR n.age > 30 RETURN n.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE n.label = 'Person' AND (n.label = 'Admin' XOR json_extract(n.properties, '$.age') > 30)"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person), (b:Person) WHERE a.id = 1 OR b.id = 2 CREATE (a)-[:KNOWS]->(b)",
+            generic_tables()
+        ),
+        "INSERT INTO relationships (from_id, to_id, type, properties) \
+         SELECT a.id, b.id, 'KNOWS', '{}' FROM nodes AS a, nodes AS b \
+         WHERE a.label = 'Person' AND b.label = 'Person' \
+         AND (json_extract(a.properties, '$.id') = 1 OR json_extract(b.properties, '$.id') = 2)"
+    );
+    // A single condition needs no parentheses
+    assert_eq!(
+        transform_with(
+            "MATCH (n) WHERE n.a = 1 OR n.b = 2 RETURN n.a",
+            generic_tables()
+        ),
+        "SELECT json_extract(n.properties, '$.a') AS a FROM nodes AS n \
+         WHERE json_extract(n.properties, '$.a') = 1 OR json_extract(n.properties, '$.b') = 2"
+    );
+}
+
+#[test]
+fn datafusion_target() {
+    let config = || TransformConfig {
+        target: SqlTarget::DataFusion,
+        ..generic_tables()
+    };
+    let cases = [
+        (
+            "MATCH (a:Person)-[:KNOWS]->(b:Person) WHERE a.age > 30 \
+             RETURN a.name AS name, collect(b.name) AS friends ORDER BY name SKIP 5 LIMIT 10",
+            "SELECT a.properties['name'] AS name, array_agg(b.properties['name']) AS friends \
+             FROM nodes AS a INNER JOIN relationships ON a.id = relationships.from_id \
+             INNER JOIN nodes AS b ON b.id = relationships.to_id \
+             WHERE a.label = 'Person' AND relationships.type = 'KNOWS' AND b.label = 'Person' \
+             AND a.properties['age'] > 30 GROUP BY a.properties['name'] \
+             ORDER BY name LIMIT 10 OFFSET 5",
+        ),
+        (
+            "MATCH (n:Person) RETURN n SKIP 2",
+            "SELECT named_struct('id', n.id, 'label', n.label, 'properties', n.properties) AS n \
+             FROM nodes AS n WHERE n.label = 'Person' OFFSET 2",
+        ),
+        (
+            "MATCH (n) WHERE 'age' IN keys(n) AND $key IN keys(n) RETURN n.name",
+            "SELECT n.properties['name'] AS name FROM nodes AS n \
+             WHERE n.properties['age'] IS NOT NULL \
+             AND array_has(map_keys(n.properties), $key)",
+        ),
+    ];
+    for (cypher, expected) in cases {
+        let sql = transform_with(cypher, config());
+        assert_eq!(sql, expected);
+        // DataFusion parses SQL with the generic dialect
+        let statements =
+            Parser::parse_sql(&GenericDialect {}, &sql).unwrap_or_else(|e| panic!("{sql}: {e}"));
+        assert_eq!(statements.len(), 1, "{sql}");
+        assert_eq!(statements[0].to_string(), sql);
+    }
+
+    assert_eq!(
+        try_transform_with("CREATE (n:Person {name: 'Ada'})", config()),
+        Err(TransformError::Unsupported(
+            "CREATE (n:Person {name: 'Ada'}) on DataFusion".to_string()
+        ))
+    );
+    assert_eq!(
+        try_transform_with("MATCH (n) WHERE n.age IS :: INTEGER RETURN n", config()),
+        Err(TransformError::Unsupported(
+            "type predicate n.age IS :: INTEGER on DataFusion, whose properties are not JSON"
+                .to_string()
+        ))
+    );
+}
+
+#[test]
+fn coalesce_and_strict_nulls() {
+    let cypher = "MATCH (n:Person) RETURN coalesce(n.nickname, n.name) AS display";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "SELECT coalesce(json_extract(n.properties, '$.nickname'), \
+         json_extract(n.properties, '$.name')) AS display \
+         FROM nodes AS n WHERE n.label = 'Person'"
+    );
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        "SELECT coalesce(n.nickname, n.name) AS display FROM Person AS n"
+    );
+
+    // A missing property is null, which NOT keeps null unless strict_nulls
+    // reads it as false
+    let cypher = "MATCH (n) WHERE NOT n.active OR (n.vip AND n.age > 30) RETURN n.id";
+    assert_eq!(
+        transform_with(cypher, generic_tables()),
+        "SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n \
+         WHERE NOT json_extract(n.properties, '$.active') \
+         OR (json_extract(n.properties, '$.vip') AND json_extract(n.properties, '$.age') > 30)"
+    );
+    let strict_nulls = |target| TransformConfig {
+        target,
+        strict_nulls: true,
+        ..generic_tables()
+    };
+    assert_eq!(
+        transform_with(cypher, strict_nulls(SqlTarget::Sqlite)),
+        "SELECT json_extract(n.properties, '$.id') AS id FROM nodes AS n \
+         WHERE NOT coalesce(json_extract(n.properties, '$.active'), 0) \
+         OR (coalesce(json_extract(n.properties, '$.vip'), 0) \
+         AND json_extract(n.properties, '$.age') > 30)"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE n.active RETURN n.name",
+            TransformConfig {
+                strict_nulls: true,
+                ..label_tables()
+            }
+        ),
+        "SELECT n.name FROM Person AS n WHERE coalesce(n.active, 0)"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (n) WHERE n.active RETURN n.name",
+            strict_nulls(SqlTarget::DataFusion)
+        ),
+        "SELECT n.properties['name'] AS name FROM nodes AS n \
+         WHERE coalesce(n.properties['active'], false)"
+    );
+}
+
+#[test]
+fn set_properties() {
+    let sqlite = generic_tables();
+    let postgres = TransformConfig {
+        target: SqlTarget::Postgres,
+        ..generic_tables()
+    };
+    let cypher = "MATCH (n:Person) WHERE n.name = 'Ada' SET n.count = n.count + 1, n.city = null";
+    assert_eq!(
+        transform_with(cypher, sqlite.clone()),
+        "UPDATE nodes AS n SET properties = json_patch(n.properties, json_object(\
+         'count', json_extract(n.properties, '$.count') + 1, 'city', NULL)) \
+         WHERE n.label = 'Person' AND json_extract(n.properties, '$.name') = 'Ada'"
+    );
+    assert_eq!(
+        transform_with(cypher, postgres.clone()),
+        "UPDATE nodes AS n SET properties = jsonb_strip_nulls(n.properties || jsonb_build_object(\
+         'count', (n.properties ->> 'count')::NUMERIC + 1, 'city', NULL)) \
+         WHERE n.label = 'Person' AND n.properties ->> 'name' = 'Ada'"
+    );
+    assert_eq!(
+        transform_with(cypher, label_tables()),
+        "UPDATE Person AS n SET count = n.count + 1, city = NULL WHERE n.name = 'Ada'"
+    );
+    // Other patterns find the ids of the updated rows
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person)-[:KNOWS]->(b) WHERE a.name = 'Ada' SET b.seen = true",
+            label_tables()
+        ),
+        "UPDATE nodes AS b SET properties = json_patch(b.properties, json_object('seen', json('true'))) \
+         WHERE b.id IN (SELECT b.id FROM Person AS a \
+         INNER JOIN relationships ON a.id = relationships.from_id \
+         INNER JOIN nodes AS b ON b.id = relationships.to_id \
+         WHERE relationships.type = 'KNOWS' AND a.name = 'Ada')"
+    );
+
+    for (cypher, error) in [
+        (
+            "MATCH (a)-[r:KNOWS]->(b) SET r.since = 2020",
+            "SET r.since = 2020 on a relationship",
+        ),
+        (
+            "MATCH (a)-[:KNOWS]->(b) SET a.friend = b.name",
+            "SET a.friend = b.name, whose value reads another variable than a",
+        ),
+        ("MATCH (n) SET n = {name: 'Ada'}", "SET n = {name: 'Ada'}"),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables()),
+            Err(TransformError::Unsupported(error.to_string())),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn set_list_append() {
+    let list_properties = |target| TransformConfig {
+        target,
+        list_properties: vec!["tags".to_string()],
+        ..generic_tables()
+    };
+    let cypher = "MATCH (n:Person) SET n.tags = n.tags + 'new'";
+    assert_eq!(
+        transform_with(cypher, list_properties(SqlTarget::Sqlite)),
+        "UPDATE nodes AS n SET properties = json_patch(n.properties, json_object(\
+         'tags', json_insert(json_extract(n.properties, '$.tags'), '$[#]', 'new'))) \
+         WHERE n.label = 'Person'"
+    );
+    assert_eq!(
+        transform_with(cypher, list_properties(SqlTarget::Postgres)),
+        "UPDATE nodes AS n SET properties = jsonb_strip_nulls(n.properties || jsonb_build_object(\
+         'tags', (n.properties -> 'tags') || jsonb_build_array('new'))) \
+         WHERE n.label = 'Person'"
+    );
+    assert_eq!(
+        transform_with(cypher, list_properties(SqlTarget::MySql)),
+        "UPDATE nodes AS n SET properties = json_merge_patch(n.properties, json_object(\
+         'tags', json_array_append(json_extract(n.properties, '$.tags'), '$', 'new'))) \
+         WHERE n.label = 'Person'"
+    );
+    // A list literal is appended element by element, and makes the other
+    // operand a list without a hint
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) SET n.langs = n.langs + ['Rust', 'SQL']",
+            label_tables()
+        ),
+        "UPDATE Person AS n SET langs = json_insert(n.langs, '$[#]', 'Rust', '$[#]', 'SQL')"
+    );
+    // Lists that are not literals are concatenated on PostgreSQL only
+    let prepend = "MATCH (n:Person) SET n.tags = ['Cypher'] + n.tags";
+    assert_eq!(
+        transform_with(prepend, list_properties(SqlTarget::Postgres)),
+        "UPDATE nodes AS n SET properties = jsonb_strip_nulls(n.properties || jsonb_build_object(\
+         'tags', jsonb_build_array('Cypher') || (n.properties -> 'tags'))) \
+         WHERE n.label = 'Person'"
+    );
+    assert_eq!(
+        try_transform_with(prepend, list_properties(SqlTarget::Sqlite)),
+        Err(TransformError::Unsupported(
+            "['Cypher'] + n.tags on Sqlite, which can only append elements to a list".to_string()
+        ))
+    );
+
+    // Without a hint, `+` adds numbers and is reported, or rejected when
+    // strict
+    assert_eq!(
+        try_transform_with(cypher, generic_tables()),
+        Err(TransformError::Unsupported(
+            "`+` of values of unknown types in n.tags = n.tags + 'new'".to_string()
+        ))
+    );
+    let stmt = Parser::new(&CypherDialect)
+        .try_with_sql(cypher)
+        .and_then(|mut parser| parser.parse_cypher_statement())
+        .unwrap();
+    let output = CypherToSqlTransformer::with_config(TransformConfig {
+        strict: false,
+        ..generic_tables()
+    })
+    .to_sql_output(&stmt)
+    .unwrap();
+    assert_eq!(
+        output.sql,
+        "UPDATE nodes AS n SET properties = json_patch(n.properties, json_object(\
+         'tags', json_extract(n.properties, '$.tags') + 'new')) WHERE n.label = 'Person'"
+    );
+    assert_eq!(
+        output
+            .warnings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["`+` of values of unknown types is not translated: n.tags = n.tags + 'new'"]
+    );
+    let numeric = check_cypher("MATCH (n:Person) SET n.count = n.count + 1").unwrap();
+    assert_eq!(numeric.kind, StatementKind::Set);
+    assert!(numeric.warnings.is_empty());
+}
+
+#[test]
+fn check_without_transforming() {
+    let check = check_cypher(
+        "MATCH (a)-[r:KNOWS]->(b) WHERE a.age > $age WITH a, count(b) AS known \
+         MATCH p = (a)-[:LIVES_IN]->(c) RETURN c.name AS city, known \
+         ORDER BY city LIMIT $limit;",
+    )
+    .unwrap();
+    assert_eq!(check.kind, StatementKind::Match);
+    assert_eq!(check.variables, ["a", "r", "b", "known", "p", "c"]);
+    assert_eq!(check.parameters, ["age", "limit"]);
+    assert!(check.warnings.is_empty());
+
+    let check = check_cypher("MATCH (a), (b) WHERE a.id = $id OR b.id = $id RETURN a").unwrap();
+    assert_eq!(check.parameters, ["id"]);
+    assert_eq!(check.warnings.len(), 1);
+    assert_eq!(check.warnings[0].kind, WarningKind::CartesianProduct);
+
+    let check = check_cypher("MATCH (a) WHERE a.id = $id DETACH DELETE a").unwrap();
+    assert_eq!(check.kind, StatementKind::Delete);
+    assert_eq!(check.variables, ["a"]);
+
+    let check = check_cypher("CREATE (a:Person {name: $name})-[:KNOWS]->(b)").unwrap();
+    assert_eq!(check.kind, StatementKind::Create);
+    assert_eq!(check.variables, ["a", "b"]);
+
+    for (query, error) in [
+        (
+            "MATCH (a) WITH a.name AS name RETURN a",
+            "variable `a` is not bound at Line: 1, Column: 38",
+        ),
+        (
+            "MATCH (a) RETURN [(a)-[:KNOWS]->(b) | b.name] AS names, b",
+            "variable `b` is not bound at Line: 1, Column: 57",
+        ),
+        (
+            "MATCH (a) DELETE b",
+            "variable `b` in DELETE is not bound at Line: 1, Column: 18",
+        ),
+        (
+            "MATCH (a) RETURN a MATCH (b) RETURN b",
+            "Expected: end of statement, found: MATCH at Line: 1, Column: 20",
+        ),
+    ] {
+        assert_eq!(
+            check_cypher(query),
+            Err(ParserError::ParserError(error.to_string())),
+            "{query}"
+        );
+    }
+    // RETURN aliases are in scope in ORDER BY
+    assert!(check_cypher("MATCH (a) RETURN a.name AS name ORDER BY name").is_ok());
+}
+
+#[test]
+fn transform_stats() {
+    for (query, tokens, elements, joins) in [
+        ("MATCH (n) RETURN n.name", 8, 1, 0),
+        ("MATCH (a)-[:KNOWS]->(b) -- friends\nRETURN b", 15, 3, 2),
+        (
+            "MATCH (a)-[:KNOWS]->(b) WITH b MATCH (b)-[:LIKES]->(c) RETURN c",
+            30,
+            6,
+            4,
+        ),
+        (
+            "MATCH (a:Person) RETURN a.name AS name UNION MATCH (b)<--(c) RETURN b.name AS name",
+            29,
+            4,
+            2,
+        ),
+        ("CREATE (a:Person)-[:KNOWS]->(b:Person)", 17, 3, 0),
+    ] {
+        let (sql, stats) = transform_cypher_to_sql_with_stats(query).unwrap();
+        assert_eq!(sql, transform_cypher_to_sql(query).unwrap(), "{query}");
+        assert_eq!(
+            (
+                stats.token_count,
+                stats.pattern_element_count,
+                stats.generated_join_count
+            ),
+            (tokens, elements, joins),
+            "{query}: {sql}"
+        );
+    }
+
+    let transformer = CypherToSqlTransformer::with_config(generic_tables());
+    let (output, _) = transformer
+        .to_sql_output_with_stats("MATCH (a), (b) RETURN a, b")
+        .unwrap();
+    assert_eq!(output.warnings.len(), 1);
+    assert!(matches!(
+        transformer.to_sql_output_with_stats("MATCH (a RETURN a"),
+        Err(TransformError::Parse(_))
+    ));
+}
+
+#[test]
+fn capabilities_match_transformation() {
+    // Another query of each construct is translated as well as its example
+    let queries = [
+        ("MATCH", "MATCH (c:Company) RETURN c"),
+        ("WHERE", "MATCH (c:Company) WHERE c.size >= 10 RETURN c.name"),
+        (
+            "STARTS WITH, ENDS WITH and CONTAINS",
+            "MATCH (c:Company) WHERE c.name CONTAINS $part RETURN c.name",
+        ),
+        (
+            "exists()",
+            "MATCH (c:Company) WHERE exists(c.size) RETURN c.name",
+        ),
+        (
+            "OPTIONAL MATCH",
+            "MATCH (c:Company) OPTIONAL MATCH (p)-[:WORKS_AT]->(c) RETURN c.name, p.name",
+        ),
+        ("relationship", "MATCH (p)-[w:WORKS_AT]->(c:Company) RETURN w.since"),
+        ("undirected relationship", "MATCH (p)-[:WORKS_AT]-(c) RETURN c.name"),
+        (
+            "variable-length relationship",
+            "MATCH (a)-[:KNOWS*]->(b) RETURN a.name",
+        ),
+        ("aggregation", "MATCH (c:Company) RETURN max(c.founded)"),
+        ("ORDER BY, SKIP and LIMIT", "MATCH (c:Company) RETURN c.name LIMIT 3"),
+        ("RETURN DISTINCT", "MATCH (c:Company) RETURN DISTINCT c.city AS city"),
+        ("WITH", "MATCH (c:Company) WITH c ORDER BY c.name LIMIT 2 RETURN c.name"),
+        ("UNWIND", "UNWIND ['a'] AS name RETURN name"),
+        (
+            "UNION",
+            "MATCH (c:Company) RETURN c.name AS name UNION ALL MATCH (p:Person) RETURN p.name AS name",
+        ),
+        ("CASE", "MATCH (c:Company) RETURN CASE c.size WHEN 1 THEN 'small' END"),
+        ("list comprehension", "RETURN [x IN [1, 2] WHERE x > 1 | x]"),
+        (
+            "pattern comprehension",
+            "MATCH (c:Company) RETURN [(p)-[:WORKS_AT]->(c) | p.name]",
+        ),
+        ("CREATE node", "CREATE (c:Company {name: 'Acme', founded: 1999})"),
+        ("CREATE relationship", "CREATE (p:Person)-[:WORKS_AT]->(c:Company)"),
+        (
+            "MATCH ... CREATE",
+            "MATCH (p:Person), (c:Company) CREATE (p)-[:WORKS_AT]->(c)",
+        ),
+        (
+            "MATCH ... CREATE of a linked node",
+            "MATCH (c:Company) CREATE (p:Person {name: 'Bob'})-[:WORKS_AT]->(c)",
+        ),
+        ("MERGE node", "MERGE (c:Company {name: 'Acme'})"),
+        (
+            "MERGE relationship",
+            "MERGE (p:Person)-[:WORKS_AT]->(c:Company)",
+        ),
+        ("SET", "MATCH (c:Company) SET c.name = 'Acme', c.size = 2"),
+        ("REMOVE", "MATCH (c:Company) REMOVE c.size"),
+        ("DELETE", "MATCH (c:Company) DELETE c"),
+        ("DETACH DELETE", "MATCH (c:Company) DETACH DELETE c"),
+        ("CALL", "CALL db.relationshipTypes()"),
+    ];
+    assert_eq!(
+        queries.map(|(construct, _)| construct).to_vec(),
+        CONSTRUCTS
+            .iter()
+            .map(|(construct, _)| *construct)
+            .collect::<Vec<_>>()
+    );
+    let kind = |support: &Support| core::mem::discriminant(support);
+    let configs = [
+        generic_tables(),
+        label_tables(),
+        TransformConfig {
+            target: SqlTarget::Postgres,
+            id_type: IdType::Uuid,
+            ..label_tables()
+        },
+        TransformConfig {
+            strict: true,
+            ..generic_tables()
+        },
+    ];
+    for config in configs {
+        let matrix = capabilities(&config);
+        let transformer = CypherToSqlTransformer::with_config(config.clone());
+        for (construct, query) in queries {
+            let expected = match transformer.to_sql_output_with_stats(query) {
+                Ok((output, _)) if output.warnings.is_empty() => Support::Supported,
+                Ok((output, _)) => Support::Partial(format!("{:?}", output.warnings)),
+                Err(e) => Support::Unsupported(e.to_string()),
+            };
+            assert_eq!(
+                kind(matrix.support(construct).unwrap()),
+                kind(&expected),
+                "{construct} on {:?}: {expected:?}",
+                config.target
+            );
+        }
+    }
+
+    let matrix = capabilities(&TransformConfig {
+        strict: false,
+        ..generic_tables()
+    });
+    assert_eq!(matrix.support("MATCH"), Some(&Support::Supported));
+    assert_eq!(
+        matrix.support("variable-length relationship"),
+        Some(&Support::Partial(
+            "variable-length relationship is not translated: -[:KNOWS*1..3]->".to_string()
+        ))
+    );
+    assert_eq!(
+        matrix.support("MERGE node"),
+        Some(&Support::Unsupported(
+            "unsupported Cypher feature: MERGE".to_string()
+        ))
+    );
+    assert_eq!(matrix.support("SHOW"), None);
+    // What a construct needs of the target is reported for the target
+    assert!(matches!(
+        matrix.support("MATCH ... CREATE of a linked node"),
+        Some(Support::Unsupported(reason)) if reason.contains("on Sqlite")
+    ));
+    let postgres = TransformConfig {
+        target: SqlTarget::Postgres,
+        id_type: IdType::Uuid,
+        ..generic_tables()
+    };
+    assert_eq!(
+        capabilities(&postgres).support("MATCH ... CREATE of a linked node"),
+        Some(&Support::Supported)
+    );
+    // Strict mode rejects what it would warn about
+    let strict = TransformConfig {
+        strict: true,
+        ..generic_tables()
+    };
+    assert!(matches!(
+        capabilities(&strict).support("variable-length relationship"),
+        Some(Support::Unsupported(_))
+    ));
+}
+
+#[test]
+fn string_predicates() {
+    let on = |target| TransformConfig {
+        target,
+        ..label_tables()
+    };
+    let query = "MATCH (n:Person) WHERE n.name STARTS WITH 'A_' AND n.name ENDS WITH 'a' \
+                 AND n.name CONTAINS 'd*' RETURN n.name";
+    // Matching is case-sensitive on every target: a LIKE where it is, a GLOB
+    // on SQLite and a binary collation on MySQL
+    assert_eq!(
+        transform_with(query, on(SqlTarget::Sqlite)),
+        "SELECT n.name FROM Person AS n WHERE n.name GLOB 'A_*' AND n.name GLOB '*a' \
+         AND n.name GLOB '*d[*]*'"
+    );
+    assert_eq!(
+        transform_with(query, on(SqlTarget::Postgres)),
+        r"SELECT n.name FROM Person AS n WHERE n.name LIKE 'A\_%' ESCAPE '\' AND n.name LIKE '%a' AND n.name LIKE '%d*%'"
+    );
+    assert_eq!(
+        transform_with(query, on(SqlTarget::MySql)),
+        r"SELECT n.name FROM Person AS n WHERE n.name LIKE 'A\\_%' COLLATE utf8mb4_bin AND n.name LIKE '%a' COLLATE utf8mb4_bin AND n.name LIKE '%d*%' COLLATE utf8mb4_bin"
+    );
+    // MySQL compares the string of a JSON property, without its quotes
+    assert_eq!(
+        transform_with(
+            "MATCH (n) WHERE n.name STARTS WITH 'A' RETURN n.name",
+            TransformConfig {
+                target: SqlTarget::MySql,
+                ..generic_tables()
+            }
+        ),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE json_unquote(json_extract(n.properties, '$.name')) LIKE 'A%' COLLATE utf8mb4_bin"
+    );
+
+    // Other patterns are matched with string functions
+    let query = "MATCH (n:Person) WHERE n.name STARTS WITH $prefix AND n.name ENDS WITH n.suffix \
+                 AND n.name CONTAINS $part RETURN n.name";
+    assert_eq!(
+        transform_with(query, on(SqlTarget::Sqlite)),
+        "SELECT n.name FROM Person AS n WHERE substr(n.name, 1, length($prefix)) = $prefix \
+         AND substr(n.name, length(n.name) - length(n.suffix) + 1) = n.suffix \
+         AND instr(n.name, $part) > 0"
+    );
+    assert_eq!(
+        transform_with(query, on(SqlTarget::Postgres)),
+        "SELECT n.name FROM Person AS n WHERE left(n.name, char_length($prefix)) = $prefix \
+         AND right(n.name, char_length(n.suffix)) = n.suffix AND POSITION($part IN n.name) > 0"
+    );
+    assert_eq!(
+        transform_with(query, on(SqlTarget::DuckDb)),
+        "SELECT n.name FROM Person AS n WHERE starts_with(n.name, $prefix) \
+         AND ends_with(n.name, n.suffix) AND strpos(n.name, $part) > 0"
+    );
+}
+
+#[test]
+fn case_insensitive_text_ops() {
+    let query = "MATCH (n:Person) WHERE n.name STARTS WITH 'ad' AND 'Ada' = n.name \
+                 AND n.name CONTAINS $part AND n.name = n.nickname RETURN n.name";
+    let output = |target| {
+        let config = TransformConfig {
+            target,
+            case_insensitive_text_ops: true,
+            ..label_tables()
+        };
+        let stmt = Parser::new(&CypherDialect)
+            .try_with_sql(query)
+            .and_then(|mut parser| parser.parse_cypher_statement())
+            .unwrap();
+        CypherToSqlTransformer::with_config(config)
+            .to_sql_output(&stmt)
+            .unwrap()
+    };
+
+    // A property compared with a string literal ignores case, with ILIKE
+    // where there is one; a parameter or another property keeps to case
+    let postgres = output(SqlTarget::Postgres);
+    assert_eq!(
+        postgres.sql,
+        "SELECT n.name FROM Person AS n WHERE n.name ILIKE 'ad%' AND lower('Ada') = lower(n.name) \
+         AND POSITION($part IN n.name) > 0 AND n.name = n.nickname"
+    );
+    assert_eq!(
+        output(SqlTarget::Sqlite).sql,
+        "SELECT n.name FROM Person AS n WHERE lower(n.name) LIKE lower('ad%') \
+         AND lower('Ada') = lower(n.name) AND instr(n.name, $part) > 0 AND n.name = n.nickname"
+    );
+    assert_eq!(
+        output(SqlTarget::MySql).sql,
+        "SELECT n.name FROM Person AS n WHERE lower(n.name) LIKE lower('ad%') \
+         AND lower('Ada') = lower(n.name) AND POSITION($part COLLATE utf8mb4_bin IN n.name) > 0 \
+         AND n.name = n.nickname"
+    );
+
+    // Each comparison that ignores case is warned about
+    let warnings: Vec<String> = postgres
+        .warnings
+        .iter()
+        .filter(|warning| warning.kind == WarningKind::CaseInsensitive)
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            "`STARTS WITH` ignores case: n.name STARTS WITH 'ad'",
+            "`=` ignores case: 'Ada' = n.name"
+        ]
+    );
+    assert_eq!(postgres.warnings.len(), 2);
+}
+
+#[test]
+fn exists_function() {
+    // Of a property, exists() is the same as IS NOT NULL
+    for config in [label_tables(), generic_tables()] {
+        assert_eq!(
+            transform_with(
+                "MATCH (n:Person) WHERE exists(n.email) RETURN n.name",
+                config.clone()
+            ),
+            transform_with(
+                "MATCH (n:Person) WHERE n.email IS NOT NULL RETURN n.name",
+                config
+            )
+        );
+    }
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE exists(n.email) RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n WHERE n.email IS NOT NULL"
+    );
+
+    // Of a pattern, it is an EXISTS subquery reading the variables bound
+    // outside it from the outer row
+    assert_eq!(
+        transform_with(
+            "MATCH (n:Person) WHERE NOT exists((n)-[:KNOWS]->(:Person)) RETURN n.name",
+            label_tables()
+        ),
+        "SELECT n.name FROM Person AS n WHERE NOT EXISTS (SELECT 1 FROM relationships, Person \
+         WHERE relationships.type = 'KNOWS' AND n.id = relationships.from_id \
+         AND Person.id = relationships.to_id)"
+    );
+    assert_eq!(
+        transform_with(
+            "MATCH (a:Person), (b:Person) WHERE exists((a)-[:KNOWS]-(b)) RETURN a.name, b.name",
+            generic_tables()
+        ),
+        "SELECT json_extract(a.properties, '$.name') AS a_name, \
+         json_extract(b.properties, '$.name') AS b_name FROM nodes AS a, nodes AS b \
+         WHERE a.label = 'Person' AND b.label = 'Person' AND EXISTS (SELECT 1 FROM relationships \
+         WHERE relationships.type = 'KNOWS' AND ((a.id = relationships.from_id \
+         AND b.id = relationships.to_id) OR (b.id = relationships.from_id \
+         AND a.id = relationships.to_id)))"
+    );
+    // A label the pattern adds to an outer node is a condition on it, which
+    // a label table of another label never meets
+    let query = "MATCH (n:Person) WHERE exists((n:Admin)-[:MANAGES]->()) RETURN n.name";
+    assert_eq!(
+        transform_with(query, generic_tables()),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE n.label = 'Person' AND EXISTS (SELECT 1 FROM relationships \
+         WHERE n.label = 'Admin' AND relationships.type = 'MANAGES' \
+         AND n.id = relationships.from_id)"
+    );
+    assert_eq!(
+        transform_with(query, label_tables()),
+        "SELECT n.name FROM Person AS n WHERE EXISTS (SELECT 1 FROM relationships \
+         WHERE 0 AND relationships.type = 'MANAGES' AND n.id = relationships.from_id)"
+    );
+
+    for (cypher, error) in [
+        (
+            "MATCH (n) WHERE exists(n) RETURN n",
+            TransformError::InvalidQuery(
+                "exists(n) takes a property or a pattern, not a variable".to_string(),
+            ),
+        ),
+        (
+            "MATCH (n) WHERE exists($email) RETURN n",
+            TransformError::InvalidQuery(
+                "exists($email) takes a property or a pattern, not a parameter".to_string(),
+            ),
+        ),
+        (
+            "MATCH (n) WHERE exists('email') RETURN n",
+            TransformError::InvalidQuery(
+                "exists('email') takes a property or a pattern, not a literal".to_string(),
+            ),
+        ),
+        (
+            "MATCH (n) WHERE exists(coalesce(n.email, n.phone)) RETURN n",
+            TransformError::InvalidQuery(
+                "exists(coalesce(n.email, n.phone)) takes a property or a pattern, \
+                 not a function call"
+                    .to_string(),
+            ),
+        ),
+        (
+            "MATCH (n) WHERE exists(n.email + n.phone) RETURN n",
+            TransformError::InvalidQuery(
+                "exists(n.email + n.phone) takes a property or a pattern, not an expression"
+                    .to_string(),
+            ),
+        ),
+        (
+            "MATCH (n) WHERE exists() RETURN n",
+            TransformError::InvalidQuery(
+                "exists() takes a single argument, a property or a pattern".to_string(),
+            ),
+        ),
+        (
+            "MATCH (n)-[r]->(m) WHERE exists((r)-[:KNOWS]->(m)) RETURN n",
+            TransformError::InvalidQuery(
+                "(r) in exists((r)-[:KNOWS]->(m)) is bound to a relationship outside it"
+                    .to_string(),
+            ),
+        ),
+        (
+            "MATCH (n) WHERE exists((n)-[:KNOWS*]->()) RETURN n",
+            TransformError::Unsupported(
+                "variable-length relationship in exists((n)-[:KNOWS*]->())".to_string(),
+            ),
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables()),
+            Err(error),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn cypher_functions() {
+    let config = |target| TransformConfig {
+        target,
+        ..generic_tables()
+    };
+    // labels() is the list of the node's label and type() the relationship's
+    // type, null for an element an OPTIONAL MATCH leaves unmatched
+    let cypher = "MATCH (a:Person)-[r]->(b) OPTIONAL MATCH (b)-[:OWNS]->(c) \
+                  RETURN labels(b), type(r), labels(c)";
+    let from = "FROM nodes AS a INNER JOIN relationships AS r ON a.id = r.from_id \
+                INNER JOIN nodes AS b ON b.id = r.to_id LEFT JOIN (relationships \
+                INNER JOIN nodes AS c ON c.id = relationships.to_id) \
+                ON b.id = relationships.from_id AND relationships.type = 'OWNS' \
+                WHERE a.label = 'Person'";
+    for (target, labels) in [
+        (SqlTarget::Sqlite, "json_array"),
+        (SqlTarget::Postgres, "jsonb_build_array"),
+        (SqlTarget::DataFusion, "make_array"),
+    ] {
+        assert_eq!(
+            transform_with(cypher, config(target)),
+            format!(
+                "SELECT {labels}(b.label), r.type, CASE WHEN c.id IS NULL THEN NULL \
+                 ELSE {labels}(c.label) END {from}"
+            )
+        );
+    }
+    assert_eq!(
+        transform_with("MATCH (n:Person) RETURN labels(n)", label_tables()),
+        "SELECT json_array('Person') FROM Person AS n"
+    );
+    assert_eq!(
+        try_transform_with("MATCH (a)-[r]->(b) RETURN labels(r)", generic_tables()),
+        Err(TransformError::InvalidQuery(
+            "labels(r) expects a node, but r is a relationship".to_string()
+        ))
+    );
+    assert_eq!(
+        try_transform_with("MATCH (n) RETURN type(n.kind)", generic_tables()),
+        Err(TransformError::Unsupported(
+            "type(n.kind), as only type() of a relationship variable is translated".to_string()
+        ))
+    );
+
+    // collect() is the aggregate building a JSON array, of the objects
+    // returned for whole variables
+    let cypher = "MATCH (a:Person)-[:KNOWS]->(b) RETURN a.name, collect(b.name), collect(b)";
+    for (target, select) in [
+        (
+            SqlTarget::Sqlite,
+            "SELECT json_extract(a.properties, '$.name') AS name, \
+             json_group_array(json_extract(b.properties, '$.name')), \
+             json_group_array(json_object('id', b.id, 'label', b.label, \
+             'properties', json(b.properties)))",
+        ),
+        (
+            SqlTarget::Postgres,
+            "SELECT a.properties ->> 'name' AS name, json_agg(b.properties ->> 'name'), \
+             json_agg(json_build_object('id', b.id, 'label', b.label, \
+             'properties', b.properties))",
+        ),
+        (
+            SqlTarget::MySql,
+            "SELECT json_extract(a.properties, '$.name') AS name, \
+             json_arrayagg(json_extract(b.properties, '$.name')), \
+             json_arrayagg(json_object('id', b.id, 'label', b.label, \
+             'properties', b.properties))",
+        ),
+    ] {
+        let sql = transform_with(cypher, config(target));
+        assert!(sql.starts_with(&format!("{select} FROM ")), "{sql}");
+    }
+
+    // Functions no database has are rejected, or passed through with a
+    // warning
+    let lenient = TransformConfig {
+        strict: false,
+        ..generic_tables()
+    };
+    for (cypher, call) in [
+        ("MATCH (n) RETURN size(n.tags)", "size(n.tags)"),
+        ("MATCH (n) RETURN keys(n)", "keys(n)"),
+        (
+            "MATCH (n) WHERE toLower(n.name) = 'ada' RETURN n",
+            "toLower(n.name)",
+        ),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, generic_tables()),
+            Err(TransformError::Unsupported(format!(
+                "function without a SQL counterpart in {call}"
+            )))
+        );
+        let output =
+            transform_cypher_to_sql_with_params(cypher, &BTreeMap::new(), lenient.clone()).unwrap();
+        assert_eq!(
+            output.warnings[0].to_string(),
+            format!("function without a SQL counterpart is not translated: {call}")
+        );
+    }
+    // but for a membership of keys(), which is translated
+    assert!(transform_cypher_to_sql_with_params(
+        "MATCH (n) WHERE 'age' IN keys(n) RETURN n.name",
+        &BTreeMap::new(),
+        lenient
+    )
+    .unwrap()
+    .warnings
+    .is_empty());
+}
+
+fn property_graph() -> TransformConfig {
+    TransformConfig {
+        property_graph: Some("social".to_string()),
+        ..generic_tables()
+    }
+}
+
+#[test]
+fn graph_table_examples() {
+    for (cypher, sql) in [
+        (
+            "MATCH (n:Person) RETURN n.name",
+            "SELECT name FROM GRAPH_TABLE (social MATCH (n IS Person) \
+             COLUMNS (n.name AS name))",
+        ),
+        (
+            "MATCH (n:User) RETURN n.email",
+            "SELECT email FROM GRAPH_TABLE (social MATCH (n IS User) \
+             COLUMNS (n.email AS email))",
+        ),
+        (
+            "MATCH (n) RETURN n.id",
+            "SELECT id FROM GRAPH_TABLE (social MATCH (n) COLUMNS (n.id AS id))",
+        ),
+        (
+            "MATCH (n:Person)-[r:KNOWS]->(m:Person) RETURN n.name, m.name",
+            "SELECT n_name, m_name FROM GRAPH_TABLE (social \
+             MATCH (n IS Person)-[r IS KNOWS]->(m IS Person) \
+             COLUMNS (n.name AS n_name, m.name AS m_name))",
+        ),
+        // Writes are translated as without a property graph
+        (
+            "CREATE (n:Person)",
+            "INSERT INTO nodes (label, properties) VALUES ('Person', '{}')",
+        ),
+        (
+            "MATCH (n:Person) SET n.age = 3",
+            "UPDATE nodes AS n SET properties = json_patch(n.properties, json_object('age', 3)) \
+             WHERE n.label = 'Person'",
+        ),
+    ] {
+        assert_eq!(transform_with(cypher, property_graph()), sql, "{cypher}");
+    }
+}
+
+#[test]
+fn graph_table_patterns() {
+    for (cypher, sql) in [
+        (
+            "MATCH (a:Person)-[:KNOWS*1..3]->(b) RETURN b.name",
+            "SELECT name FROM GRAPH_TABLE (social \
+             MATCH (a IS Person)-[IS KNOWS]->{1,3}(b) COLUMNS (b.name AS name))",
+        ),
+        (
+            "MATCH (a)<-[:KNOWS*]-(b) RETURN b.name",
+            "SELECT name FROM GRAPH_TABLE (social \
+             MATCH (a)<-[IS KNOWS]-+(b) COLUMNS (b.name AS name))",
+        ),
+        (
+            "MATCH (a:Person)-[:KNOWS|FOLLOWS]-(b) WHERE a:Admin RETURN b.name",
+            "SELECT name FROM GRAPH_TABLE (social \
+             MATCH (a IS Admin & Person)-[IS FOLLOWS | KNOWS]-(b) COLUMNS (b.name AS name))",
+        ),
+        (
+            "MATCH (n:Person {name: 'Alice'})-[:KNOWS {since: 2020}]->(m) \
+             WHERE m.age > 30 RETURN m.name AS friend",
+            "SELECT friend FROM GRAPH_TABLE (social \
+             MATCH (n IS Person)-[relationship_1 IS KNOWS]->(m) \
+             WHERE n.name = 'Alice' AND relationship_1.since = 2020 AND m.age > 30 \
+             COLUMNS (m.name AS friend))",
+        ),
+        (
+            "MATCH (a:Person), (b:Company) RETURN a.name, b.name",
+            "SELECT a_name, b_name FROM GRAPH_TABLE (social \
+             MATCH (a IS Person), (b IS Company) COLUMNS (a.name AS a_name, b.name AS b_name))",
+        ),
+        (
+            "MATCH (n:Person) WHERE n.name STARTS WITH 'A' RETURN n.name",
+            "SELECT name FROM GRAPH_TABLE (social \
+             MATCH (n IS Person) WHERE n.name LIKE 'A%' COLUMNS (n.name AS name))",
+        ),
+    ] {
+        assert_eq!(transform_with(cypher, property_graph()), sql, "{cypher}");
+    }
+
+    let config = TransformConfig {
+        quoting: IdentifierQuoting::WhenNeeded,
+        param_style: ParamStyle::Numbered,
+        target: SqlTarget::Postgres,
+        ..property_graph()
+    };
+    assert_eq!(
+        transform_with(
+            "MATCH (n:`E-Commerce`)<-[:`1ST`]-(m) WHERE m.`from` = $min RETURN n.name",
+            config
+        ),
+        "SELECT name FROM GRAPH_TABLE (social \
+         MATCH (n IS \"E-Commerce\")<-[IS \"1ST\"]-(m) WHERE m.\"from\" = $1 \
+         COLUMNS (n.name AS name))"
+    );
+}
+
+#[test]
+fn graph_table_return() {
+    for (cypher, sql) in [
+        (
+            "MATCH (n:Person) RETURN n.city, count(n) AS people, avg(n.age)",
+            "SELECT city, count(*) AS people, avg(age) FROM GRAPH_TABLE (social \
+             MATCH (n IS Person) COLUMNS (n.city AS city, n.age AS age)) GROUP BY city",
+        ),
+        (
+            "MATCH (n:Person) RETURN DISTINCT n.name ORDER BY n.name SKIP 5 LIMIT 10",
+            "SELECT DISTINCT name FROM GRAPH_TABLE (social \
+             MATCH (n IS Person) COLUMNS (n.name AS name)) ORDER BY name LIMIT 10 OFFSET 5",
+        ),
+        (
+            "MATCH (n:Person) RETURN n.age + 1, n.name ORDER BY n.age",
+            "SELECT \"n.age + 1\", name FROM GRAPH_TABLE (social \
+             MATCH (n IS Person) \
+             COLUMNS (n.age + 1 AS \"n.age + 1\", n.name AS name, n.age AS age)) ORDER BY age",
+        ),
+        (
+            "MATCH (a:Person) RETURN a.name AS name \
+             UNION MATCH (b:Company) RETURN b.name AS name",
+            "SELECT name FROM GRAPH_TABLE (social MATCH (a IS Person) COLUMNS (a.name AS name)) \
+             UNION \
+             SELECT name FROM GRAPH_TABLE (social MATCH (b IS Company) COLUMNS (b.name AS name))",
+        ),
+    ] {
+        assert_eq!(transform_with(cypher, property_graph()), sql, "{cypher}");
+    }
+}
+
+#[test]
+fn graph_table_unsupported() {
+    for (cypher, feature) in [
+        ("MATCH (n) WITH n RETURN n.name", "WITH n in GRAPH_TABLE"),
+        (
+            "MATCH p = (n)-->(m) RETURN m.name",
+            "named path p = (n)-[]->(m) in GRAPH_TABLE",
+        ),
+        ("MATCH (n) RETURN n", "n in GRAPH_TABLE"),
+        ("MATCH (n) RETURN id(n)", "id(n) in GRAPH_TABLE"),
+    ] {
+        assert_eq!(
+            try_transform_with(cypher, property_graph()),
+            Err(TransformError::Unsupported(feature.to_string())),
+            "{cypher}"
+        );
+    }
+    assert!(matches!(
+        try_transform_with(
+            "MATCH (n) OPTIONAL MATCH (n)-->(m) RETURN m.name",
+            property_graph()
+        ),
+        Err(TransformError::Unsupported(_))
+    ));
+}
@@ -0,0 +1,577 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![warn(clippy::all)]
+//! Tests driving the `cypher_transformer` binary
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn cypher_transformer(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cypher_transformer"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Usage errors exit before reading stdin, closing the pipe early
+    let _ = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn transforms_query_argument() {
+    let output = cypher_transformer(&["MATCH (n:Person) RETURN n.name"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n \
+         WHERE n.label = 'Person'\n"
+    );
+}
+
+#[test]
+fn transforms_stdin() {
+    let output = cypher_transformer(&["--label-tables"], "MATCH (n:Person) RETURN n.name\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "SELECT n.name FROM Person AS n\n");
+}
+
+#[test]
+fn table_and_style_flags() {
+    let output = cypher_transformer(
+        &[
+            "--node-table",
+            "vertices",
+            "--rel-table",
+            "edges",
+            "--param-style",
+            "numbered",
+            "--target",
+            "postgres",
+            "--pretty",
+            "MATCH (a)-[r:KNOWS]->(b) WHERE a.age > $age RETURN b.name",
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "SELECT
+  b.properties ->> 'name' AS name
+FROM
+  vertices AS a
+  INNER JOIN edges AS r
+    ON a.id = r.from_id
+  INNER JOIN vertices AS b
+    ON b.id = r.to_id
+WHERE
+  r.type = 'KNOWS'
+  AND a.properties ->> 'age' > $1
+"
+    );
+}
+
+#[test]
+fn config_file() {
+    let path = std::env::temp_dir().join(format!(
+        "cypher_transformer_cli_{}.conf",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        "# label tables, question mark parameters\n\
+         label_tables = true\n\
+         param_style = question\n\
+         node_table = vertices\n",
+    )
+    .unwrap();
+    let config = path.to_str().unwrap();
+
+    let output = cypher_transformer(
+        &[
+            "--config",
+            config,
+            "MATCH (n:Person) WHERE n.age > $age RETURN n.name",
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "SELECT n.name FROM Person AS n WHERE n.age > ?\n"
+    );
+
+    // Command line options take precedence over the file
+    let output = cypher_transformer(
+        &[
+            "--param-style",
+            "inline",
+            "--config",
+            config,
+            "MATCH (n) RETURN n.name",
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM vertices AS n\n"
+    );
+
+    std::fs::write(&path, "colour = blue\n").unwrap();
+    let output = cypher_transformer(&["--config", config, "MATCH (n) RETURN n"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("line 1: unknown key colour"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn help() {
+    let output = cypher_transformer(&["--help"], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).starts_with("Usage: cypher_transformer [OPTIONS] [QUERY]"));
+}
+
+#[test]
+fn invalid_arguments_exit_with_2() {
+    for args in [
+        &["--bogus"][..],
+        &["--target"][..],
+        &["--target", "oracle", "MATCH (n) RETURN n"][..],
+        &["--param-style", "named", "MATCH (n) RETURN n"][..],
+        &[
+            "--target",
+            "mysql",
+            "--param-style",
+            "numbered",
+            "MATCH (n) RETURN n",
+        ][..],
+        &["MATCH (n) RETURN n", "MATCH (m) RETURN m"][..],
+        &["--multiline"][..],
+        &["--stats", "MATCH (n) RETURN n"][..],
+        &["--batch", "MATCH (n) RETURN n"][..],
+        &["--ast", "--validate", "MATCH (n) RETURN n"][..],
+        &["--file", "query.cypher", "MATCH (n) RETURN n"][..],
+    ] {
+        let output = cypher_transformer(args, "");
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(stderr(&output).starts_with("Error: "), "{args:?}");
+        assert!(stdout(&output).is_empty(), "{args:?}");
+    }
+}
+
+#[test]
+fn parse_errors_exit_with_1() {
+    let output = cypher_transformer(&["MATCH (n RETURN n"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: Failed to parse Cypher query"));
+
+    // Tokenizer errors fail every mode, with the location of the error
+    for args in [&["--tokens"][..], &["--ast"], &[]] {
+        let output = cypher_transformer(args, "MATCH (n) WHERE n.name = 'Ada RETURN n");
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+        assert_eq!(
+            stderr(&output),
+            "Error: Failed to tokenize Cypher query: \
+             Unterminated string literal at Line: 1, Column: 26\n",
+            "{args:?}"
+        );
+    }
+
+    for input in ["", "   \n\t", ";", "-- nothing to see\n/* here */"] {
+        let output = cypher_transformer(&[], input);
+        assert_eq!(output.status.code(), Some(1), "{input:?}");
+        assert_eq!(
+            stderr(&output),
+            "Error: No Cypher query provided\n",
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn warnings_and_strict() {
+    let query = "OPTIONAL MATCH (a) RETURN a.name";
+    let output = cypher_transformer(&[query], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stderr(&output),
+        "Warning: OPTIONAL MATCH is not translated: OPTIONAL MATCH (a) RETURN a.name\n"
+    );
+    assert!(stdout(&output).starts_with("SELECT "));
+
+    let output = cypher_transformer(&["--strict", query], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "Error: unsupported Cypher feature: OPTIONAL MATCH in OPTIONAL MATCH (a) RETURN a.name\n"
+    );
+
+    // The parser stops before what it does not know, which strict rejects
+    for (query, rest) in [
+        ("MATCH (n) REMOVE n.x RETURN n", "REMOVE"),
+        ("MATCH (n) foo bar RETURN n.name", "foo"),
+    ] {
+        let output = cypher_transformer(&[query], "");
+        assert_eq!(output.status.code(), Some(0), "{query}");
+        let output = cypher_transformer(&["--strict", query], "");
+        assert_eq!(output.status.code(), Some(1), "{query}");
+        assert_eq!(stdout(&output), "");
+        assert_eq!(
+            stderr(&output),
+            format!("Error: unsupported Cypher feature: {rest} in {query}\n")
+        );
+    }
+}
+
+#[test]
+fn query_file() {
+    let path = std::env::temp_dir().join(format!(
+        "cypher_transformer_cli_{}.cypher",
+        std::process::id()
+    ));
+    std::fs::write(&path, "MATCH (n:Person)\nRETURN n.name\n").unwrap();
+
+    let output = cypher_transformer(&["--file", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n WHERE n.label = 'Person'\n"
+    );
+
+    let output = cypher_transformer(&["--file", path.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Error: cannot read "));
+}
+
+#[test]
+fn tokens() {
+    let output = cypher_transformer(&["--tokens"], "MATCH (n)\nRETURN n.name");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        r#"1:1-1:6	Word(Word { value: "MATCH", quote_style: None, keyword: MATCH })
+1:7-1:8	LParen
+1:8-1:9	Word(Word { value: "n", quote_style: None, keyword: NoKeyword })
+1:9-1:10	RParen
+2:1-2:7	Word(Word { value: "RETURN", quote_style: None, keyword: RETURN })
+2:8-2:9	Word(Word { value: "n", quote_style: None, keyword: NoKeyword })
+2:9-2:10	Period
+2:10-2:14	Word(Word { value: "name", quote_style: None, keyword: NAME })
+"#
+    );
+}
+
+#[test]
+fn validate() {
+    let output = cypher_transformer(&["--validate", "MATCH (n) WHERE n.age > $age RETURN n"], "");
+    assert_eq!(output.status.code(), Some(0));
+    let check = stdout(&output);
+    if cfg!(feature = "json_example") {
+        assert!(check.contains("\"kind\": \"Match\""), "{check}");
+        assert!(
+            check.contains("\"parameters\": [\n    \"age\"\n  ]"),
+            "{check}"
+        );
+    } else {
+        assert!(check.starts_with("CypherCheck {"), "{check}");
+    }
+
+    let output = cypher_transformer(&["--validate", "MATCH (n) RETURN m"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).contains("variable `m` is not bound"));
+
+    let output = cypher_transformer(&["--validate", "MATCH (n RETURN n"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).starts_with("Error: Failed to parse Cypher query"));
+}
+
+#[test]
+fn ast() {
+    let output = cypher_transformer(&["--ast"], "MATCH (n) RETURN n");
+    assert_eq!(output.status.code(), Some(0));
+    let ast = stdout(&output);
+    if cfg!(feature = "json_example") {
+        assert!(ast.starts_with("{\n  \"Match\": {"), "{ast}");
+    } else {
+        assert!(ast.starts_with("Match {\n"), "{ast}");
+    }
+
+    let output = cypher_transformer(&["--ast"], "MATCH (n RETURN n");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn summary() {
+    let output = cypher_transformer(&["--summary", "MATCH (n:Person) RETURN n.name"], "");
+    assert_eq!(output.status.code(), Some(0));
+    let summary = stdout(&output);
+    if cfg!(feature = "json_example") {
+        assert!(
+            summary.starts_with("{\n  \"labels_read\": [\n    {\n      \"Named\": \"Person\""),
+            "{summary}"
+        );
+    } else {
+        assert!(summary.starts_with("AccessSummary {\n"), "{summary}");
+    }
+
+    let output = cypher_transformer(&["--summary", "--ast", "MATCH (n) RETURN n"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn emit_ddl() {
+    let query = "MATCH (a:Person)-[:KNOWS]->(b:Person) WHERE a.age > 30 RETURN b.name";
+    let output = cypher_transformer(&["--emit-ddl", "--label-tables", query], "");
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "CREATE TABLE Person (id INTEGER PRIMARY KEY, age INTEGER, name TEXT);\n\
+         CREATE TABLE relationships (from_id INTEGER, to_id INTEGER, type TEXT, properties TEXT);\n\
+         CREATE INDEX relationships_from_id ON relationships (from_id);\n\
+         CREATE INDEX relationships_to_id ON relationships (to_id);\n\
+         CREATE INDEX relationships_type ON relationships (type);\n"
+    );
+
+    let output = cypher_transformer(&["--emit-ddl", "--target", "postgres", query], "");
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        "CREATE TABLE nodes (id BIGINT PRIMARY KEY GENERATED BY DEFAULT AS IDENTITY, \
+         label TEXT, properties JSONB);\n\
+         CREATE TABLE relationships (from_id BIGINT, to_id BIGINT, type TEXT, properties JSONB);\n\
+         CREATE INDEX nodes_label ON nodes (label);\n\
+         CREATE INDEX relationships_from_id ON relationships (from_id);\n\
+         CREATE INDEX relationships_to_id ON relationships (to_id);\n\
+         CREATE INDEX relationships_type ON relationships (type);\n"
+    );
+
+    let output = cypher_transformer(&["--emit-ddl", "--ast", query], "");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn capabilities() {
+    let output = cypher_transformer(&["--capabilities", "--target", "postgres"], "");
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let capabilities = stdout(&output);
+    if cfg!(feature = "json_example") {
+        assert!(
+            capabilities.starts_with(
+                "{\n  \"constructs\": [\n    {\n      \"construct\": \"MATCH\",\n      \
+                 \"example\": \"MATCH (n:Person) RETURN n.name\",\n      \
+                 \"support\": \"Supported\"\n    },"
+            ),
+            "{capabilities}"
+        );
+        assert!(
+            capabilities.contains(
+                "\"support\": {\n        \"Unsupported\": \"unsupported Cypher feature: MERGE\""
+            ),
+            "{capabilities}"
+        );
+    } else {
+        assert!(
+            capabilities.starts_with("Capabilities {\n"),
+            "{capabilities}"
+        );
+    }
+
+    for args in [
+        &["--capabilities", "MATCH (n) RETURN n"][..],
+        &["--capabilities", "--ast"][..],
+        &["--capabilities", "--batch"][..],
+    ] {
+        let output = cypher_transformer(args, "");
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+    }
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn batch() {
+    let output = cypher_transformer(
+        &["--batch"],
+        "MATCH (n) RETURN n.name\nMATCH (n RETURN n\n\nMATCH (n:Person) RETURN n.age\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        r#"{"cypher":"MATCH (n) RETURN n.name","sql":"SELECT json_extract(n.properties, '$.name') AS name FROM nodes AS n","warnings":[],"parameters":[],"error":null}
+{"cypher":"MATCH (n RETURN n","sql":null,"warnings":[],"parameters":[],"error":"Failed to parse Cypher query: sql parser error: Expected: ), found: RETURN at Line: 1, Column: 10"}
+{"cypher":"MATCH (n:Person) RETURN n.age","sql":"SELECT json_extract(n.properties, '$.age') AS age FROM nodes AS n WHERE n.label = 'Person'","warnings":[],"parameters":[],"error":null}
+"#
+    );
+    assert_eq!(stderr(&output), "3 queries: 2 transformed, 1 failed\n");
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn batch_multiline() {
+    let output = cypher_transformer(
+        &["--batch", "--multiline", "--label-tables"],
+        "MATCH (n:Person)\nWHERE n.name = 'a;b'\nRETURN n.age;\nMATCH (c:City) RETURN c.name; MATCH (x:City) RETURN x.name",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        r#"{"cypher":"MATCH (n:Person)\nWHERE n.name = 'a;b'\nRETURN n.age","sql":"SELECT n.age FROM Person AS n WHERE n.name = 'a;b'","warnings":[],"parameters":[],"error":null}
+{"cypher":"MATCH (c:City) RETURN c.name","sql":"SELECT c.name FROM City AS c","warnings":[],"parameters":[],"error":null}
+{"cypher":"MATCH (x:City) RETURN x.name","sql":"SELECT x.name FROM City AS x","warnings":[],"parameters":[],"error":null}
+"#
+    );
+    assert_eq!(stderr(&output), "3 queries: 3 transformed, 0 failed\n");
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn batch_multiline_directives() {
+    let output = cypher_transformer(
+        &["--batch", "--multiline", "--label-tables"],
+        ":param minAge => 21;\n:use movies\n:begin\n-- Adults\nMATCH (n:Person) WHERE n.age >= $minAge AND n.name = $name RETURN n.name;\n:commit\n:sysinfo\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        r#"{"cypher":":param minAge => 21","sql":null,"warnings":[],"parameters":[],"error":null}
+{"cypher":":use movies","sql":null,"warnings":[":use is not translated: :use movies"],"parameters":[],"error":null}
+{"cypher":":begin","sql":"BEGIN","warnings":[],"parameters":[],"error":null}
+{"cypher":"-- Adults\nMATCH (n:Person) WHERE n.age >= $minAge AND n.name = $name RETURN n.name","sql":"SELECT n.name FROM Person AS n WHERE n.age >= 21 AND n.name = $name","warnings":[],"parameters":[{"name":"name","placeholder":"$name","index":null,"count":1}],"error":null}
+{"cypher":":commit","sql":"COMMIT","warnings":[],"parameters":[],"error":null}
+{"cypher":":sysinfo","sql":null,"warnings":["unknown directive :sysinfo is skipped: :sysinfo"],"parameters":[],"error":null}
+"#
+    );
+    assert_eq!(stderr(&output), "6 queries: 6 transformed, 0 failed\n");
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn batch_parameters() {
+    let output = cypher_transformer(
+        &["--batch", "--param-style", "numbered"],
+        "MATCH (n) WHERE n.age > $min AND n.score > $min RETURN n.name LIMIT $limit\n",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert!(
+        stdout(&output).ends_with(
+            r#""parameters":[{"name":"min","placeholder":"$1","index":1,"count":2},{"name":"limit","placeholder":"$2","index":2,"count":1}],"error":null}
+"#
+        ),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn batch_stats() {
+    let output = cypher_transformer(
+        &["--batch", "--stats"],
+        "MATCH (a)-[:KNOWS]->(b) RETURN b.name\nMATCH (n RETURN n\n",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let records: Vec<serde_json::Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let stats = &records[0]["stats"];
+    assert_eq!(stats["token_count"], 17);
+    assert_eq!(stats["pattern_element_count"], 3);
+    assert_eq!(stats["generated_join_count"], 2);
+    for phase in ["tokenize_time", "parse_time", "plan_time", "generate_time"] {
+        assert!(stats[phase]["nanos"].is_u64(), "{phase}: {stats}");
+    }
+    assert!(records[1].get("stats").is_none(), "{}", records[1]);
+    assert_eq!(
+        records[1]["error"],
+        "Failed to parse Cypher query: sql parser error: Expected: ), found: RETURN at Line: 1, Column: 10"
+    );
+
+    // Statistics are only written when asked for
+    let output = cypher_transformer(&["--batch"], "MATCH (n) RETURN n\n");
+    assert!(!stdout(&output).contains("stats"), "{}", stdout(&output));
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn inline_params() {
+    let query = "MATCH (n:Person) WHERE n.name = $name AND n.age > $age.min RETURN n.city";
+    let output = cypher_transformer(
+        &[
+            "--label-tables",
+            "--params",
+            r#"{"name": "O'Hara", "age": {"min": 30}, "city": "Paris"}"#,
+            query,
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "SELECT n.city FROM Person AS n WHERE n.name = 'O''Hara' AND n.age > 30\n"
+    );
+    assert_eq!(
+        stderr(&output),
+        format!("Warning: parameter $city is not used by {query}\n")
+    );
+
+    let output = cypher_transformer(&["--params", "{}", query], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "Error: missing values for $age, $name\n");
+
+    for args in [
+        &["--params", "[1]", query][..],
+        &["--params", "{", query][..],
+        &["--params", "{}", "--ast", query][..],
+        &["--params", "{}", "--batch"][..],
+    ] {
+        let output = cypher_transformer(args, "");
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(stderr(&output).starts_with("Error: "), "{args:?}");
+    }
+}
+
+#[cfg(not(feature = "json_example"))]
+#[test]
+fn batch_requires_json_feature() {
+    let output = cypher_transformer(&["--batch"], "MATCH (n) RETURN n\n");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--batch requires building with the json_example feature"));
+}
+
+#[cfg(not(feature = "json_example"))]
+#[test]
+fn params_require_json_feature() {
+    let output = cypher_transformer(&["--params", "{}", "MATCH (n) RETURN n"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("--params requires building with the json_example feature"));
+}
@@ -0,0 +1,465 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![cfg(feature = "sqlite-tests")]
+#![warn(clippy::all)]
+//! Test that the SQL [`transform_cypher_to_sql`] generates runs on SQLite
+//! and returns the rows the Cypher query would, over a small graph stored in
+//! the generic tables.
+//!
+//! Run with `cargo test --features sqlite-tests --test execute_sqlite`.
+
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use sqlparser::transformer::transform_cypher_to_sql;
+
+/// The generic tables, with people who know each other and work at a
+/// company:
+///
+/// ```text
+/// Ada (36, London) -KNOWS-> Bob (25, Paris) -KNOWS-> Cy (41, London)
+/// Ada -KNOWS-> Cy
+/// Ada, Cy -WORKS_AT-> Acme
+/// ```
+const FIXTURE: &str = "
+    CREATE TABLE nodes (id INTEGER PRIMARY KEY, label TEXT, properties TEXT);
+    CREATE TABLE relationships (from_id INTEGER, to_id INTEGER, type TEXT, properties TEXT);
+    INSERT INTO nodes VALUES
+        (1, 'Person', '{\"name\": \"Ada\", \"age\": 36, \"city\": \"London\", \"langs\": [\"SQL\"]}'),
+        (2, 'Person', '{\"name\": \"Bob\", \"age\": 25, \"city\": \"Paris\"}'),
+        (3, 'Person', '{\"name\": \"Cy\", \"age\": 41, \"city\": \"London\"}'),
+        (4, 'Company', '{\"name\": \"Acme\"}');
+    INSERT INTO relationships VALUES
+        (1, 2, 'KNOWS', '{\"since\": 2010}'),
+        (1, 3, 'KNOWS', '{\"since\": 2015}'),
+        (2, 3, 'KNOWS', '{}'),
+        (1, 4, 'WORKS_AT', '{}'),
+        (3, 4, 'WORKS_AT', '{}');
+";
+
+fn database() -> Connection {
+    let connection = Connection::open_in_memory().unwrap();
+    connection.execute_batch(FIXTURE).unwrap();
+    connection
+}
+
+/// The rows the SQL of `cypher` returns, with every value as text
+fn query(connection: &Connection, cypher: &str) -> Vec<Vec<String>> {
+    let sql = transform_cypher_to_sql(cypher).unwrap_or_else(|e| panic!("{cypher}: {e}"));
+    let mut statement = connection
+        .prepare(&sql)
+        .unwrap_or_else(|e| panic!("{sql}: {e}"));
+    let columns = statement.column_count();
+    let rows = statement
+        .query_map([], |row| {
+            (0..columns)
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Null => "null".to_string(),
+                        ValueRef::Integer(i) => i.to_string(),
+                        ValueRef::Real(f) => f.to_string(),
+                        ValueRef::Text(text) | ValueRef::Blob(text) => {
+                            String::from_utf8_lossy(text).into_owned()
+                        }
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_else(|e| panic!("{sql}: {e}"));
+    rows.collect::<Result<_, _>>()
+        .unwrap_or_else(|e| panic!("{sql}: {e}"))
+}
+
+/// Run the SQL of a Cypher statement that returns no rows
+fn execute(connection: &Connection, cypher: &str) {
+    let sql = transform_cypher_to_sql(cypher).unwrap_or_else(|e| panic!("{cypher}: {e}"));
+    connection
+        .execute_batch(&sql)
+        .unwrap_or_else(|e| panic!("{sql}: {e}"));
+}
+
+fn assert_rows(connection: &Connection, cypher: &str, expected: &[&[&str]]) {
+    let expected: Vec<Vec<String>> = expected
+        .iter()
+        .map(|row| row.iter().map(|value| value.to_string()).collect())
+        .collect();
+    assert_eq!(query(connection, cypher), expected, "{cypher}");
+}
+
+#[test]
+fn match_and_where() {
+    let db = database();
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN n.name ORDER BY n.name",
+        &[&["Ada"], &["Bob"], &["Cy"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) WHERE n.age > 30 AND n.city = 'London' RETURN n.name ORDER BY n.name",
+        &[&["Ada"], &["Cy"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n) WHERE n:Person AND (n.city = 'Paris' OR n.age > 40) \
+         RETURN n.name ORDER BY n.name",
+        &[&["Bob"], &["Cy"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n) WHERE n.name IN ['Ada', 'Acme'] RETURN n.name ORDER BY n.name",
+        &[&["Acme"], &["Ada"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n) WHERE NOT 'age' IN keys(n) RETURN n.name",
+        &[&["Acme"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n) RETURN coalesce(n.city, n.name) AS place ORDER BY place",
+        &[&["Acme"], &["London"], &["London"], &["Paris"]],
+    );
+}
+
+#[test]
+fn relationships() {
+    let db = database();
+    assert_rows(
+        &db,
+        "MATCH (a:Person)-[:KNOWS]->(b:Person) RETURN a.name, b.name ORDER BY a.name, b.name",
+        &[&["Ada", "Bob"], &["Ada", "Cy"], &["Bob", "Cy"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (c:Company)<-[:WORKS_AT]-(p) RETURN p.name ORDER BY p.name DESC",
+        &[&["Cy"], &["Ada"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a)-[r:KNOWS]->(b) WHERE r.since >= 2015 RETURN a.name, b.name",
+        &[&["Ada", "Cy"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a)-[:KNOWS]->(b)-[:KNOWS]->(c) WHERE a.name = 'Ada' RETURN c.name",
+        &[&["Cy"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a:Person)-[:KNOWS]-(b) WHERE a.name = 'Cy' RETURN b.name ORDER BY b.name",
+        &[&["Ada"], &["Bob"]],
+    );
+    // A path does not go back along the relationship it came by
+    assert_rows(
+        &db,
+        "MATCH (a)-[:KNOWS]-(b)-[:KNOWS]-(c) WHERE a.name = 'Cy' RETURN c.name ORDER BY c.name",
+        &[&["Ada"], &["Bob"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a)-[:KNOWS|WORKS_AT]->(b) WHERE a.name = 'Cy' RETURN b.name",
+        &[&["Acme"]],
+    );
+    assert_rows(&db, "MATCH ()-[r:KNOWS]->() RETURN count(r)", &[&["3"]]);
+    assert_rows(
+        &db,
+        "MATCH p = (a)-[:KNOWS]->(b) WHERE a.name = 'Bob' RETURN p",
+        &[&["[{\"id\":2,\"label\":\"Person\",\"properties\":{\"name\":\"Bob\",\"age\":25,\"city\":\"Paris\"}},\
+             {\"from_id\":2,\"to_id\":3,\"type\":\"KNOWS\",\"properties\":{}},\
+             {\"id\":3,\"label\":\"Person\",\"properties\":{\"name\":\"Cy\",\"age\":41,\"city\":\"London\"}}]"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a)-[:KNOWS]->()-[:KNOWS]->() RETURN a.name",
+        &[&["Ada"]],
+    );
+
+    // Both arrows need a relationship each way
+    let mutual = "MATCH (a)<-[:KNOWS]->(b) RETURN a.name, b.name ORDER BY a.name";
+    assert_rows(&db, mutual, &[]);
+    db.execute_batch("INSERT INTO relationships VALUES (2, 1, 'KNOWS', '{}')")
+        .unwrap();
+    assert_rows(&db, mutual, &[&["Ada", "Bob"], &["Bob", "Ada"]]);
+}
+
+#[test]
+fn optional_match() {
+    let db = database();
+    assert_rows(
+        &db,
+        "MATCH (a:Person) OPTIONAL MATCH (a)-[:WORKS_AT]->(c) RETURN a.name, c.name ORDER BY a.name",
+        &[&["Ada", "Acme"], &["Bob", "null"], &["Cy", "Acme"]],
+    );
+    // An unmatched element returned whole is null, not an object of nulls
+    assert_rows(
+        &db,
+        "MATCH (a:Person) OPTIONAL MATCH (a)-[:WORKS_AT]->(c) WHERE a.name = 'Ada' \
+         RETURN a.name, c ORDER BY a.name",
+        &[
+            &[
+                "Ada",
+                r#"{"id":4,"label":"Company","properties":{"name":"Acme"}}"#,
+            ],
+            &["Bob", "null"],
+            &["Cy", "null"],
+        ],
+    );
+    // The WHERE of the OPTIONAL MATCH keeps the people it does not extend
+    assert_rows(
+        &db,
+        "MATCH (a:Person) OPTIONAL MATCH (a)-[:KNOWS]->(b) WHERE b.city = 'Paris' \
+         RETURN a.name, b.name ORDER BY a.name",
+        &[&["Ada", "Bob"], &["Bob", "null"], &["Cy", "null"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a:Person)-[:KNOWS]->(b) WHERE b.city = 'Paris' RETURN a.name, b.name",
+        &[&["Ada", "Bob"]],
+    );
+}
+
+#[test]
+fn order_and_paging() {
+    let db = database();
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN n.name ORDER BY n.age DESC SKIP 1 LIMIT 1",
+        &[&["Ada"]],
+    );
+    // SQLite takes no OFFSET without a LIMIT
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN n.name ORDER BY n.age DESC SKIP 1",
+        &[&["Ada"], &["Bob"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN DISTINCT n.city AS city ORDER BY city",
+        &[&["London"], &["Paris"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN n.name ORDER BY n.city, n.age DESC",
+        &[&["Cy"], &["Ada"], &["Bob"]],
+    );
+    // Rows are paged after DISTINCT and grouping, not before
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN DISTINCT n.city AS city ORDER BY city LIMIT 2",
+        &[&["London"], &["Paris"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a:Person)-[:KNOWS]->(b) RETURN a.name, count(*) AS known \
+         ORDER BY known DESC LIMIT 1",
+        &[&["Ada", "2"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) WITH n ORDER BY n.age LIMIT 2 \
+         MATCH (n)-[:KNOWS]->(m) RETURN DISTINCT m.name ORDER BY m.name SKIP 1 LIMIT 2",
+        &[&["Cy"]],
+    );
+}
+
+#[test]
+fn aggregates() {
+    let db = database();
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN count(*) AS people, sum(n.age) AS total, min(n.age) AS youngest",
+        &[&["3", "102", "25"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a:Person)-[:KNOWS]->(b) WITH a.name AS name, count(*) AS known \
+         RETURN name, known ORDER BY known DESC, name",
+        &[&["Ada", "2"], &["Bob", "1"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) WITH n.city AS city, count(*) AS people, max(n.age) AS oldest \
+         RETURN city, people, oldest ORDER BY city",
+        &[&["London", "2", "41"], &["Paris", "1", "25"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a)-[:KNOWS]->(b) WITH a, count(*) AS known WHERE known > 1 RETURN a.name",
+        &[&["Ada"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (c:Company)<-[:WORKS_AT]-(p) RETURN c.name, count(p)",
+        &[&["Acme", "2"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN n.city AS city, count(*) AS people ORDER BY city",
+        &[&["London", "2"], &["Paris", "1"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a)-[r:KNOWS]->(b) WHERE a.name = 'Ada' \
+         RETURN labels(a), type(r), collect(b.name)",
+        &[&["[\"Person\"]", "KNOWS", "[\"Bob\",\"Cy\"]"]],
+    );
+}
+
+#[test]
+fn set_properties() {
+    let db = database();
+    execute(
+        &db,
+        "MATCH (n:Person) WHERE n.name = 'Bob' SET n.age = n.age + 1, n.city = null",
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) WHERE n.name = 'Bob' RETURN n.age, n.city",
+        &[&["26", "null"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n) WHERE NOT 'city' IN keys(n) RETURN n.name ORDER BY n.name",
+        &[&["Acme"], &["Bob"]],
+    );
+    execute(
+        &db,
+        "MATCH (a:Person)-[:WORKS_AT]->(c) SET a.langs = a.langs + ['Cypher', 'Rust']",
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) RETURN n.name, n.langs ORDER BY n.name",
+        &[
+            &["Ada", "[\"SQL\",\"Cypher\",\"Rust\"]"],
+            &["Bob", "null"],
+            &["Cy", "null"],
+        ],
+    );
+}
+
+#[test]
+fn create_returning() {
+    let db = database();
+    assert_rows(
+        &db,
+        "CREATE (n:Person {name: 'Dee', age: 30}) RETURN id(n), n.name",
+        &[&["5", "Dee"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) WHERE id(n) = 5 RETURN n.name, n.age",
+        &[&["Dee", "30"]],
+    );
+}
+
+#[test]
+fn property_keys_needing_quotes() {
+    let db = database();
+    execute(
+        &db,
+        r"CREATE (n:Thing {`weird.key`: 1, `it's`: 2, `$x`: 3, `a\b`: 4, `größe`: 5})",
+    );
+    assert_rows(
+        &db,
+        r"MATCH (n:Thing) WHERE n.`$x` = 3 RETURN n.`weird.key`, n.`it's`, n.`a\b`, n.`größe`",
+        &[&["1", "2", "4", "5"]],
+    );
+}
+
+#[test]
+fn variables_needing_quotes() {
+    let db = database();
+    assert_rows(
+        &db,
+        "MATCH (`order`:Person)-[:KNOWS]->(`match`) WHERE `order`.age > 30 \
+         RETURN `order`.name, `match`.name ORDER BY `match`.name",
+        &[&["Ada", "Bob"], &["Ada", "Cy"]],
+    );
+}
+
+#[test]
+fn unwind_range() {
+    let db = database();
+    execute(&db, "UNWIND range(1, 250) AS i CREATE (:Item {n: i})");
+    assert_rows(
+        &db,
+        "MATCH (i:Item) RETURN count(*) AS items, min(i.n) AS first, max(i.n) AS last",
+        &[&["250", "1", "250"]],
+    );
+    assert_rows(
+        &db,
+        "UNWIND range(10, 1, -4) AS x RETURN x",
+        &[&["10"], &["6"], &["2"]],
+    );
+    assert_rows(
+        &db,
+        "UNWIND range(3, 240, 100) AS n MATCH (i:Item) WHERE i.n = n RETURN n",
+        &[&["3"], &["103"], &["203"]],
+    );
+}
+
+#[test]
+fn string_predicates() {
+    let db = database();
+    assert_rows(
+        &db,
+        "MATCH (n:Person) WHERE n.name STARTS WITH 'A' OR n.city ENDS WITH 'is' \
+         RETURN n.name ORDER BY n.name",
+        &[&["Ada"], &["Bob"]],
+    );
+    // Matching keeps to case, as in Cypher
+    assert_rows(
+        &db,
+        "MATCH (n) WHERE n.name STARTS WITH 'a' OR n.name CONTAINS 'C' RETURN n.name",
+        &[&["Cy"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a:Person), (b:Person) WHERE a.city CONTAINS b.name + 'd' \
+         RETURN a.name, b.name",
+        &[],
+    );
+    assert_rows(
+        &db,
+        "MATCH (a:Person), (b:Person) WHERE a.name < b.name AND a.city ENDS WITH b.city \
+         RETURN a.name, b.name",
+        &[&["Ada", "Cy"]],
+    );
+}
+
+#[test]
+fn exists_function() {
+    let db = database();
+    assert_rows(
+        &db,
+        "MATCH (n) WHERE exists(n.city) AND NOT exists(n.langs) RETURN n.name ORDER BY n.name",
+        &[&["Bob"], &["Cy"]],
+    );
+    assert_rows(
+        &db,
+        "MATCH (n:Person) WHERE exists((n)-[:WORKS_AT]->(:Company)) RETURN n.name ORDER BY n.name",
+        &[&["Ada"], &["Cy"]],
+    );
+    // Of the people known, only Bob neither knows back nor works anywhere
+    assert_rows(
+        &db,
+        "MATCH (a)-[:KNOWS]->(b) WHERE NOT exists((b)-[:KNOWS]->(a)) AND NOT exists((b)-->(:Company)) \
+         RETURN a.name, b.name",
+        &[&["Ada", "Bob"]],
+    );
+}
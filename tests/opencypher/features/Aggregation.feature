@@ -0,0 +1,65 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Aggregation - Group and aggregate rows
+
+  Scenario: Count all rows
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN count(*)
+      """
+    Then the result should be, in any order:
+      | count(*) |
+      | 0        |
+    And no side effects
+
+  Scenario: Group by a returned key
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n:Person)
+      RETURN n.city AS city, count(n) AS people, avg(n.age) AS age
+      """
+    Then the result should be, in any order:
+      | city | people | age |
+    And no side effects
+
+  Scenario: Count distinct values
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN count(DISTINCT n.city) AS cities
+      """
+    Then the result should be, in any order:
+      | cities |
+      | 0      |
+    And no side effects
+
+  Scenario: Collect values into a list
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN collect(n.name) AS names
+      """
+    Then the result should be, in any order:
+      | names |
+      | []    |
+    And no side effects
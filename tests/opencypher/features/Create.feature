@@ -0,0 +1,86 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Create - Create nodes and relationships
+
+  Scenario: Create a single node
+    Given any graph
+    When executing query:
+      """
+      CREATE ()
+      """
+    Then the result should be empty
+    And the side effects should be:
+      | +nodes | 1 |
+
+  Scenario: Create a node with labels and properties
+    Given any graph
+    When executing query:
+      """
+      CREATE (:A:B {name: 'Ada', age: -36, score: 0.5, tags: ['x', 'y']})
+      """
+    Then the result should be empty
+    And the side effects should be:
+      | +nodes      | 1 |
+      | +labels     | 2 |
+      | +properties | 4 |
+
+  Scenario: Create a relationship between two new nodes
+    Given any graph
+    When executing query:
+      """
+      CREATE (:A)-[:R {num: 42}]->(:B)
+      """
+    Then the result should be empty
+    And the side effects should be:
+      | +nodes         | 2 |
+      | +relationships | 1 |
+      | +labels        | 2 |
+      | +properties    | 1 |
+
+  Scenario: Create a relationship between matched nodes
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a:A), (b:B)
+      CREATE (a)-[:LINK]->(b)
+      """
+    Then the result should be empty
+    And no side effects
+
+  Scenario: Create and return a node
+    Given any graph
+    When executing query:
+      """
+      CREATE (n:A {name: 'Ada'})
+      RETURN n.name
+      """
+    Then the result should be, in any order:
+      | n.name |
+      | 'Ada'  |
+    And the side effects should be:
+      | +nodes      | 1 |
+      | +labels     | 1 |
+      | +properties | 1 |
+
+  Scenario: Fail when creating an undirected relationship
+    Given any graph
+    When executing query:
+      """
+      CREATE (a)-[:FOO]-(b)
+      """
+    Then a SyntaxError should be raised at compile time: RequiresDirectedRelationship
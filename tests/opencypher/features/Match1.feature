@@ -0,0 +1,123 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Match1 - Match nodes
+
+  Scenario: Match non-existent nodes returns empty
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n |
+    And no side effects
+
+  Scenario: Matching all nodes
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (:A), (:B {name: 'b'}), ({name: 'c'})
+      """
+    When executing query:
+      """
+      MATCH (n)
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n                 |
+      | (:A)              |
+      | (:B {name: 'b'})  |
+      | ({name: 'c'})     |
+    And no side effects
+
+  Scenario: Matching nodes using multiple labels
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (:A:B:C), (:A:B), (:A:C), (:B:C)
+      """
+    When executing query:
+      """
+      MATCH (a:A:B)
+      RETURN a
+      """
+    Then the result should be, in any order:
+      | a        |
+      | (:A:B:C) |
+      | (:A:B)   |
+    And no side effects
+
+  Scenario: Simple node inline property predicate
+    Given an empty graph
+    And having executed:
+      """
+      CREATE ({name: 'bar'}), ({name: 'monkey'}), ({firstname: 'bar'})
+      """
+    When executing query:
+      """
+      MATCH (n {name: 'bar'})
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n               |
+      | ({name: 'bar'}) |
+    And no side effects
+
+  Scenario: Use multiple MATCH clauses to do a Cartesian product
+    Given an empty graph
+    And having executed:
+      """
+      CREATE ({num: 1}), ({num: 2}), ({num: 3})
+      """
+    When executing query:
+      """
+      MATCH (n), (m)
+      RETURN n.num AS n, m.num AS m
+      """
+    Then the result should be, in any order:
+      | n | m |
+      | 1 | 1 |
+      | 1 | 2 |
+      | 1 | 3 |
+      | 2 | 1 |
+      | 2 | 2 |
+      | 2 | 3 |
+      | 3 | 1 |
+      | 3 | 2 |
+      | 3 | 3 |
+    And no side effects
+
+  Scenario: Fail when using parameter as node predicate in MATCH
+    Given any graph
+    When executing query:
+      """
+      MATCH (n $param)
+      RETURN n
+      """
+    Then a SyntaxError should be raised at compile time: InvalidParameterUse
+
+  Scenario: Fail when a node has the same variable in a preceding MATCH
+    Given any graph
+    When executing query:
+      """
+      MATCH (a)-[r]->()
+      MATCH (r)
+      RETURN r
+      """
+    Then a SyntaxError should be raised at compile time: VariableTypeConflict
@@ -0,0 +1,125 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Match2 - Match relationships
+
+  Scenario: Match non-existent relationships returns empty
+    Given an empty graph
+    When executing query:
+      """
+      MATCH ()-[r]->()
+      RETURN r
+      """
+    Then the result should be, in any order:
+      | r |
+    And no side effects
+
+  Scenario: Matching a relationship pattern using a label predicate on both sides
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (:A)-[:T1]->(:B),
+             (:B)-[:T2]->(:A),
+             (:B)-[:T3]->(:B),
+             (:A)-[:T4]->(:A)
+      """
+    When executing query:
+      """
+      MATCH (:A)-[r]->(:B)
+      RETURN r
+      """
+    Then the result should be, in any order:
+      | r     |
+      | [:T1] |
+    And no side effects
+
+  Scenario: Matching a self-loop with an undirected relationship pattern
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (a)
+      CREATE (a)-[:T]->(a)
+      """
+    When executing query:
+      """
+      MATCH ()-[r]-()
+      RETURN type(r) AS r
+      """
+    Then the result should be, in any order:
+      | r   |
+      | 'T' |
+    And no side effects
+
+  Scenario: Match relationship with inline property value
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (:A)<-[:KNOWS {name: 'monkey'}]-()-[:KNOWS {name: 'woot'}]->(:B)
+      """
+    When executing query:
+      """
+      MATCH (node)-[r:KNOWS {name: 'monkey'}]->(a)
+      RETURN a
+      """
+    Then the result should be, in any order:
+      | a    |
+      | (:A) |
+    And no side effects
+
+  Scenario: Match relationships with a type alternative
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (a {name: 'A'}), (b {name: 'B'}), (c {name: 'C'})
+      CREATE (a)-[:KNOWS]->(b), (a)-[:HATES]->(c), (a)-[:WONDERS]->(c)
+      """
+    When executing query:
+      """
+      MATCH (n)-[r:KNOWS|HATES]->(x)
+      RETURN r
+      """
+    Then the result should be, in any order:
+      | r        |
+      | [:KNOWS] |
+      | [:HATES] |
+    And no side effects
+
+  Scenario: Matching twice with conflicting relationship types on same relationship
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (a)-[:T]->(b)
+      """
+    When executing query:
+      """
+      MATCH (a1)-[r:T]->()
+      WITH r, a1
+      MATCH (a1)-[r:Y]->(b2)
+      RETURN a1, r, b2
+      """
+    Then the result should be, in any order:
+      | a1 | r | b2 |
+    And no side effects
+
+  Scenario: Fail when a relationship pattern has no closing node
+    Given any graph
+    When executing query:
+      """
+      MATCH (a)-[r]->
+      RETURN r
+      """
+    Then a SyntaxError should be raised at compile time: UnexpectedSyntax
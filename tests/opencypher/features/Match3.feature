@@ -0,0 +1,110 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Match3 - Match fixed length patterns
+
+  Scenario: Get neighbours
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (a:A {num: 1})-[:KNOWS]->(b:B {num: 2})
+      """
+    When executing query:
+      """
+      MATCH (n1)-[rel:KNOWS]->(n2)
+      RETURN n1, n2
+      """
+    Then the result should be, in any order:
+      | n1            | n2            |
+      | (:A {num: 1}) | (:B {num: 2}) |
+    And no side effects
+
+  Scenario: Directed match of a simple relationship, incoming
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (:A)-[:LOOP]->(:B)
+      """
+    When executing query:
+      """
+      MATCH (a)<-[r]-(b)
+      RETURN a, r, b
+      """
+    Then the result should be, in any order:
+      | a    | r       | b    |
+      | (:B) | [:LOOP] | (:A) |
+    And no side effects
+
+  Scenario: Return two subgraphs with bound undirected relationship
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (:A {num: 1})-[:REL {name: 'r'}]->(:B {num: 2})
+      """
+    When executing query:
+      """
+      MATCH (a)-[r {name: 'r'}]-(b)
+      RETURN a, b
+      """
+    Then the result should be, in any order:
+      | a             | b             |
+      | (:B {num: 2}) | (:A {num: 1}) |
+      | (:A {num: 1}) | (:B {num: 2}) |
+    And no side effects
+
+  Scenario: Matching a path of two hops
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (a:A)-[:KNOWS]->(b:B)-[:FRIEND]->(c:C)
+      """
+    When executing query:
+      """
+      MATCH (a)-[:KNOWS]->(b)-[:FRIEND]->(c)
+      RETURN c
+      """
+    Then the result should be, in any order:
+      | c    |
+      | (:C) |
+    And no side effects
+
+  Scenario: Matching a named path of one hop
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (:A)-[:T]->(:B)
+      """
+    When executing query:
+      """
+      MATCH p = (a)-[:T]->(b)
+      RETURN length(p) AS len
+      """
+    Then the result should be, in any order:
+      | len |
+      | 1   |
+    And no side effects
+
+  Scenario: Matching nodes with many labels and relationships between them
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a:A:B:C)-[:T1|T2]->(b:D)<-[:T3]-(c)
+      RETURN a, b, c
+      """
+    Then the result should be, in any order:
+      | a | b | c |
+    And no side effects
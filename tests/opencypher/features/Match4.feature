@@ -0,0 +1,68 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Match4 - Match variable length patterns
+
+  Scenario: Handling fixed-length variable length pattern
+    Given an empty graph
+    And having executed:
+      """
+      CREATE ()-[:T]->()
+      """
+    When executing query:
+      """
+      MATCH (a)-[r*1..1]->(b)
+      RETURN r
+      """
+    Then the result should be, in any order:
+      | r      |
+      | [[:T]] |
+    And no side effects
+
+  Scenario: Matching variable length patterns from a bound node
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a:A)
+      MATCH (a)-[r*2]->()
+      RETURN r
+      """
+    Then the result should be, in any order:
+      | r |
+    And no side effects
+
+  Scenario: Matching variable length patterns without bounds
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a)-[:KNOWS*]->(b)
+      RETURN b
+      """
+    Then the result should be, in any order:
+      | b |
+    And no side effects
+
+  Scenario: Matching variable length patterns with a property predicate
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a:Artist)-[:WORKED_WITH* {year: 1988}]->(b:Artist)
+      RETURN *
+      """
+    Then the result should be, in any order:
+      | a | b |
+    And no side effects
@@ -0,0 +1,121 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: MatchWhere - Filter the rows of a MATCH
+
+  Scenario: Filter node with property predicate on a single variable
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (:A {name: 'Andres'}), (:B {name: 'Someone'})
+      """
+    When executing query:
+      """
+      MATCH (n)
+      WHERE n.name = 'Andres'
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n                     |
+      | (:A {name: 'Andres'}) |
+    And no side effects
+
+  Scenario: Filter node with a label predicate
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a)
+      WHERE a:Person
+      RETURN a
+      """
+    Then the result should be, in any order:
+      | a |
+    And no side effects
+
+  Scenario: Filter on a disjunction and negation
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      WHERE NOT (n.age > 30 OR n.name STARTS WITH 'A') AND n.city IS NOT NULL
+      RETURN n.name
+      """
+    Then the result should be, in any order:
+      | n.name |
+    And no side effects
+
+  Scenario: Filter on membership in a list literal
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      WHERE n.name IN ['Ada', 'Bob'] AND n.age <> -1
+      RETURN n.name
+      """
+    Then the result should be, in any order:
+      | n.name |
+    And no side effects
+
+  Scenario: Filter on a parameter
+    Given an empty graph
+    And parameters are:
+      | name | 'Ada' |
+    When executing query:
+      """
+      MATCH (n:Person)
+      WHERE n.name = $name
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n |
+    And no side effects
+
+  Scenario: Filter on an existential subquery
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      WHERE EXISTS { (n)-[:KNOWS]->() }
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n |
+    And no side effects
+
+  Scenario: Filter on a pattern predicate
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a), (b)
+      WHERE (a)-[:KNOWS]->(b)
+      RETURN a, b
+      """
+    Then the result should be, in any order:
+      | a | b |
+    And no side effects
+
+  Scenario: Filter on a regular expression
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      WHERE n.name =~ 'A.*'
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n |
+    And no side effects
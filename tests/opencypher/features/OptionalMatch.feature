@@ -0,0 +1,73 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: OptionalMatch - Optionally match patterns
+
+  Scenario: Return null when no matches due to inline label predicate
+    Given an empty graph
+    And having executed:
+      """
+      CREATE (s:Single), (s)-[:REL]->(:A)
+      """
+    When executing query:
+      """
+      MATCH (n:Single)
+      OPTIONAL MATCH (n)-[r]-(m:NonExistent)
+      RETURN r
+      """
+    Then the result should be, in any order:
+      | r    |
+      | null |
+    And no side effects
+
+  Scenario: Optional match with a WHERE of its own
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a:A)
+      OPTIONAL MATCH (a)-->(b)
+      WHERE b.num > 1
+      RETURN a, b
+      """
+    Then the result should be, in any order:
+      | a | b |
+    And no side effects
+
+  Scenario: Optional match on its own
+    Given an empty graph
+    When executing query:
+      """
+      OPTIONAL MATCH (n)
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n    |
+      | null |
+    And no side effects
+
+  Scenario: Two optional matches after a match
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a:A)
+      OPTIONAL MATCH (a)-[:X]->(b)
+      OPTIONAL MATCH (b)-[:Y]->(c)
+      RETURN a, b, c
+      """
+    Then the result should be, in any order:
+      | a | b | c |
+    And no side effects
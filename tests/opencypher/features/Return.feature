@@ -0,0 +1,143 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Return - Project the rows of a query
+
+  Scenario: Allow addition
+    Given an empty graph
+    And having executed:
+      """
+      CREATE ({id: 1337, version: 99})
+      """
+    When executing query:
+      """
+      MATCH (a)
+      WHERE a.id = 1337
+      RETURN a.version + 5
+      """
+    Then the result should be, in any order:
+      | a.version + 5 |
+      | 104           |
+    And no side effects
+
+  Scenario: Return a literal without a MATCH
+    Given any graph
+    When executing query:
+      """
+      RETURN 1 + 1 AS two, 'a' AS text, null AS nothing
+      """
+    Then the result should be, in any order:
+      | two | text | nothing |
+      | 2   | 'a'  | null    |
+    And no side effects
+
+  Scenario: Return distinct values
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN DISTINCT n.city AS city
+      """
+    Then the result should be, in any order:
+      | city |
+    And no side effects
+
+  Scenario: Return star
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a)-[r]->(b)
+      RETURN *
+      """
+    Then the result should be, in any order:
+      | a | b | r |
+    And no side effects
+
+  Scenario: Return a list literal
+    Given any graph
+    When executing query:
+      """
+      RETURN [1, 2, 3] AS list
+      """
+    Then the result should be, in any order:
+      | list      |
+      | [1, 2, 3] |
+    And no side effects
+
+  Scenario: Return a map literal
+    Given any graph
+    When executing query:
+      """
+      RETURN {name: 'Ada', age: 36} AS map
+      """
+    Then the result should be, in any order:
+      | map                     |
+      | {name: 'Ada', age: 36}  |
+    And no side effects
+
+  Scenario: Return a CASE expression
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN CASE WHEN n.age > 30 THEN 'old' ELSE 'young' END AS age
+      """
+    Then the result should be, in any order:
+      | age |
+    And no side effects
+
+  Scenario: Return a list comprehension
+    Given any graph
+    When executing query:
+      """
+      RETURN [x IN [1, 2, 3] WHERE x > 1 | x * 10] AS list
+      """
+    Then the result should be, in any order:
+      | list     |
+      | [20, 30] |
+    And no side effects
+
+  Scenario: Return a pattern comprehension
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN [(n)-[:KNOWS]->(m) | m.name] AS names
+      """
+    Then the result should be, in any order:
+      | names |
+    And no side effects
+
+  Scenario: Return a list slice
+    Given any graph
+    When executing query:
+      """
+      RETURN [1, 2, 3, 4][1..3] AS slice
+      """
+    Then the result should be, in any order:
+      | slice  |
+      | [2, 3] |
+    And no side effects
+
+  Scenario: Fail when returning nothing
+    Given any graph
+    When executing query:
+      """
+      MATCH (a)
+      RETURN
+      """
+    Then a SyntaxError should be raised at compile time: UnexpectedSyntax
@@ -0,0 +1,82 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: ReturnOrderBy - Order, skip and limit the returned rows
+
+  Scenario: Order by a returned property, descending
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN n.name AS name
+      ORDER BY name DESC
+      """
+    Then the result should be, in order:
+      | name |
+    And no side effects
+
+  Scenario: Order by an expression that is not returned
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN n.name
+      ORDER BY n.age ASC, n.name
+      """
+    Then the result should be, in order:
+      | n.name |
+    And no side effects
+
+  Scenario: Order by with the long direction keywords
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN n.name
+      ORDER BY n.age DESCENDING, n.name ASCENDING
+      """
+    Then the result should be, in order:
+      | n.name |
+    And no side effects
+
+  Scenario: Skip and limit the returned rows
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      RETURN n
+      ORDER BY n.name
+      SKIP 2
+      LIMIT 3
+      """
+    Then the result should be, in order:
+      | n |
+    And no side effects
+
+  Scenario: Limit by a parameter
+    Given an empty graph
+    And parameters are:
+      | limit | 2 |
+    When executing query:
+      """
+      MATCH (n)
+      RETURN n
+      LIMIT $limit
+      """
+    Then the result should be, in any order:
+      | n |
+    And no side effects
@@ -0,0 +1,58 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Union - Combine the rows of queries
+
+  Scenario: Union of two queries removes duplicates
+    Given any graph
+    When executing query:
+      """
+      RETURN 1 AS x
+      UNION
+      RETURN 2 AS x
+      """
+    Then the result should be, in any order:
+      | x |
+      | 1 |
+      | 2 |
+    And no side effects
+
+  Scenario: Union all of two matches
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a:A)
+      RETURN a.name AS name
+      UNION ALL
+      MATCH (b:B)
+      RETURN b.name AS name
+      """
+    Then the result should be, in any order:
+      | name |
+    And no side effects
+
+  Scenario: Fail when mixing UNION and UNION ALL
+    Given any graph
+    When executing query:
+      """
+      RETURN 1 AS a
+      UNION
+      RETURN 2 AS a
+      UNION ALL
+      RETURN 3 AS a
+      """
+    Then a SyntaxError should be raised at compile time: InvalidClauseComposition
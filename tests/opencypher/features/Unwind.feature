@@ -0,0 +1,58 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Unwind - Turn a list into rows
+
+  Scenario: Unwind a list literal
+    Given any graph
+    When executing query:
+      """
+      UNWIND [1, 2, 3] AS x
+      RETURN x
+      """
+    Then the result should be, in any order:
+      | x |
+      | 1 |
+      | 2 |
+      | 3 |
+    And no side effects
+
+  Scenario: Unwind a range
+    Given any graph
+    When executing query:
+      """
+      UNWIND range(1, 3) AS x
+      RETURN x
+      """
+    Then the result should be, in any order:
+      | x |
+      | 1 |
+      | 2 |
+      | 3 |
+    And no side effects
+
+  Scenario: Unwind after a match
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      UNWIND n.tags AS tag
+      RETURN tag
+      """
+    Then the result should be, in any order:
+      | tag |
+    And no side effects
@@ -0,0 +1,80 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: Update - Set properties and delete graph elements
+
+  Scenario: Set a property
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n:A)
+      SET n.name = 'Ada'
+      """
+    Then the result should be empty
+    And no side effects
+
+  Scenario: Set a label
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n:A)
+      SET n:B
+      """
+    Then the result should be empty
+    And no side effects
+
+  Scenario: Delete a node
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n:A)
+      DELETE n
+      """
+    Then the result should be empty
+    And no side effects
+
+  Scenario: Detach delete a node
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n:A)
+      DETACH DELETE n
+      """
+    Then the result should be empty
+    And no side effects
+
+  Scenario: Merge a node
+    Given an empty graph
+    When executing query:
+      """
+      MERGE (a:A {name: 'Ada'})
+      """
+    Then the result should be empty
+    And the side effects should be:
+      | +nodes      | 1 |
+      | +labels     | 1 |
+      | +properties | 1 |
+
+  Scenario: Remove a property
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n:A)
+      REMOVE n.name
+      """
+    Then the result should be empty
+    And no side effects
@@ -0,0 +1,80 @@
+# Licensed to the Apache Software Foundation (ASF) under one
+# or more contributor license agreements.  See the NOTICE file
+# distributed with this work for additional information
+# regarding copyright ownership.  The ASF licenses this file
+# to you under the Apache License, Version 2.0 (the
+# "License"); you may not use this file except in compliance
+# with the License.  You may obtain a copy of the License at
+#
+#   http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing,
+# software distributed under the License is distributed on an
+# "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+# KIND, either express or implied.  See the License for the
+# specific language governing permissions and limitations
+# under the License.
+
+Feature: With - Pass rows on to the next part of a query
+
+  Scenario: Pass a variable on
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a:A)
+      WITH a
+      MATCH (a)-->(b)
+      RETURN b
+      """
+    Then the result should be, in any order:
+      | b |
+    And no side effects
+
+  Scenario: Filter the rows of a WITH
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (a)-->(b)
+      WITH a, count(b) AS degree
+      WHERE degree > 2
+      RETURN a, degree
+      """
+    Then the result should be, in any order:
+      | a | degree |
+    And no side effects
+
+  Scenario: Order and limit the rows of a WITH
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      WITH n
+      ORDER BY n.name
+      LIMIT 1
+      RETURN n
+      """
+    Then the result should be, in any order:
+      | n |
+    And no side effects
+
+  Scenario: Chain two WITH clauses
+    Given an empty graph
+    When executing query:
+      """
+      MATCH (n)
+      WITH n.city AS city
+      WITH city, count(*) AS people
+      RETURN city, people
+      """
+    Then the result should be, in any order:
+      | city | people |
+    And no side effects
+
+  Scenario: Fail when a query ends with WITH
+    Given any graph
+    When executing query:
+      """
+      MATCH (n)
+      WITH n
+      """
+    Then a SyntaxError should be raised at compile time: InvalidClauseComposition
@@ -0,0 +1,214 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![warn(clippy::all)]
+//! Parse the queries of a curated subset of the openCypher TCK scenarios in
+//! `features/`, and compare which scenarios pass with `baseline.txt`.
+//!
+//! A scenario passes when every query it executes parses, or, for a
+//! scenario that expects a `SyntaxError`, when the query it executes does
+//! not. The baseline records each scenario as `pass`, or as `skip` with the
+//! construct it needs that the parser does not support yet:
+//!
+//! ```text
+//! pass | Match1 | Matching all nodes
+//! skip | Unwind | Unwind after a match | UNWIND after MATCH
+//! ```
+//!
+//! A scenario that stops passing fails the test, and so does a skipped one
+//! that starts passing, whose line is then flipped to `pass`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use sqlparser::dialect::CypherDialect;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::Token;
+
+/// A scenario of a feature file
+struct Scenario {
+    feature: String,
+    name: String,
+    /// The queries of the `having executed` steps, which set up the graph
+    setup: Vec<String>,
+    /// The query of the `executing query` step
+    query: String,
+    /// Whether the query must be rejected with a `SyntaxError`
+    syntax_error: bool,
+}
+
+/// The expected outcome of a scenario
+#[derive(Debug, PartialEq, Eq)]
+enum Expected {
+    Pass,
+    /// The scenario needs the named construct
+    Skip(String),
+}
+
+/// The scenarios of a feature file, in Gherkin: a `Feature:` line, then
+/// `Scenario:` lines each followed by steps, whose queries are doc strings
+/// between `"""` lines
+fn parse_feature(text: &str) -> Vec<Scenario> {
+    let mut feature = String::new();
+    let mut scenarios: Vec<Scenario> = vec![];
+    let mut lines = text.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        if let Some(name) = line.strip_prefix("Feature:") {
+            let name = name.trim();
+            // `Match1 - Match nodes` is the feature `Match1`
+            feature = name.split(" - ").next().unwrap_or(name).to_string();
+        } else if let Some(name) = line.strip_prefix("Scenario:") {
+            scenarios.push(Scenario {
+                feature: feature.clone(),
+                name: name.trim().to_string(),
+                setup: vec![],
+                query: String::new(),
+                syntax_error: false,
+            });
+        } else if let Some(scenario) = scenarios.last_mut() {
+            let step = line.split_once(' ').map_or("", |(_, step)| step);
+            if step.starts_with("a SyntaxError should be raised") {
+                scenario.syntax_error = true;
+            } else if step == "having executed:" || step == "executing query:" {
+                assert_eq!(lines.next(), Some("\"\"\""), "{}: {step}", scenario.name);
+                let doc_string: Vec<&str> = lines.by_ref().take_while(|&l| l != "\"\"\"").collect();
+                let query = doc_string.join("\n");
+                if step == "having executed:" {
+                    scenario.setup.push(query);
+                } else {
+                    scenario.query = query;
+                }
+            }
+        }
+    }
+    scenarios
+}
+
+/// Parse a whole query as a single Cypher statement, with an optional `;`
+fn parse_query(query: &str) -> Result<(), ParserError> {
+    let mut parser = Parser::new(&CypherDialect).try_with_sql(query)?;
+    parser.parse_cypher_statement()?;
+    let _ = parser.consume_token(&Token::SemiColon);
+    match parser.peek_token().token {
+        Token::EOF => Ok(()),
+        token => Err(ParserError::ParserError(format!(
+            "Expected: end of statement, found: {token}"
+        ))),
+    }
+}
+
+/// Whether `scenario` passes, or why not
+fn run(scenario: &Scenario) -> Result<(), String> {
+    for query in &scenario.setup {
+        parse_query(query).map_err(|e| format!("{e} in setup query {query:?}"))?;
+    }
+    match (parse_query(&scenario.query), scenario.syntax_error) {
+        (Ok(()), false) | (Err(_), true) => Ok(()),
+        (Ok(()), true) => Err("no SyntaxError".to_string()),
+        (Err(e), false) => Err(e.to_string()),
+    }
+}
+
+/// The expected outcome of each scenario, by feature and scenario name
+fn parse_baseline(text: &str) -> BTreeMap<(String, String), Expected> {
+    let mut baseline = BTreeMap::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+        let expected = match fields[..] {
+            ["pass", _, _] => Expected::Pass,
+            ["skip", _, _, construct] => Expected::Skip(construct.to_string()),
+            _ => panic!("invalid baseline line: {line}"),
+        };
+        let key = (fields[1].to_string(), fields[2].to_string());
+        assert!(
+            baseline.insert(key, expected).is_none(),
+            "duplicate baseline line: {line}"
+        );
+    }
+    baseline
+}
+
+#[test]
+fn opencypher_tck_subset() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/opencypher");
+    let mut paths: Vec<_> = fs::read_dir(dir.join("features"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "feature")
+        })
+        .collect();
+    paths.sort();
+    let baseline_path = dir.join("baseline.txt");
+    let mut baseline = parse_baseline(&fs::read_to_string(&baseline_path).unwrap());
+
+    let mut passed = 0;
+    let mut skipped: BTreeMap<String, usize> = BTreeMap::new();
+    let mut mismatches = String::new();
+    for path in paths {
+        let text = fs::read_to_string(&path).unwrap();
+        for scenario in parse_feature(&text) {
+            let key = (scenario.feature.clone(), scenario.name.clone());
+            let result = run(&scenario);
+            let line = format!("{} | {}", scenario.feature, scenario.name);
+            match (baseline.remove(&key), result) {
+                (Some(Expected::Pass), Ok(())) => passed += 1,
+                (Some(Expected::Skip(construct)), Err(_)) => {
+                    *skipped.entry(construct).or_default() += 1;
+                }
+                (Some(Expected::Pass), Err(e)) => {
+                    writeln!(mismatches, "no longer passes: {line}: {e}").unwrap();
+                }
+                (Some(Expected::Skip(_)), Ok(())) => {
+                    writeln!(mismatches, "passes now, flip to `pass | {line}`").unwrap();
+                }
+                (None, Ok(())) => {
+                    writeln!(mismatches, "not in the baseline: pass | {line}").unwrap();
+                }
+                (None, Err(e)) => writeln!(
+                    mismatches,
+                    "not in the baseline: skip | {line} | <construct> ({e})"
+                )
+                .unwrap(),
+            }
+        }
+    }
+    for (feature, name) in baseline.keys() {
+        writeln!(mismatches, "no such scenario: {feature} | {name}").unwrap();
+    }
+
+    let skips: Vec<String> = skipped
+        .iter()
+        .map(|(construct, count)| format!("{construct}: {count}"))
+        .collect();
+    println!(
+        "openCypher TCK subset: {passed} passed, {} skipped ({})",
+        skipped.values().sum::<usize>(),
+        skips.join(", ")
+    );
+    assert!(
+        mismatches.is_empty(),
+        "scenarios differ from {}:\n{mismatches}",
+        baseline_path.display()
+    );
+}
@@ -0,0 +1,2429 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![warn(clippy::all)]
+//! Test Cypher syntax parsed by [`Parser::parse_cypher_statement`] under
+//! the [`CypherDialect`].
+
+use sqlparser::ast::cypher::*;
+use sqlparser::ast::*;
+use sqlparser::dialect::CypherDialect;
+use sqlparser::parser::{CypherStatementIterator, Parser, ParserError};
+use sqlparser::transformer::CypherToSqlTransformer;
+
+fn try_parse_cypher(cypher: &str) -> Result<CypherStatement, ParserError> {
+    Parser::new(&CypherDialect)
+        .try_with_sql(cypher)?
+        .parse_cypher_statement()
+}
+
+fn parse_cypher(cypher: &str) -> CypherStatement {
+    try_parse_cypher(cypher).unwrap()
+}
+
+#[test]
+fn parse_where_label_predicate() {
+    let stmt = parse_cypher("MATCH (n) WHERE n:Person AND n.age > 30 RETURN n.name");
+    let CypherStatement::Match {
+        r#where: Some(Expr::BinaryOp { left, op, .. }),
+        ..
+    } = &stmt
+    else {
+        panic!("unexpected statement: {stmt:?}");
+    };
+    assert_eq!(*op, BinaryOperator::And);
+    assert_eq!(
+        **left,
+        Expr::Cypher(Box::new(CypherExpr::LabelPredicate {
+            expr: Expr::Identifier(Ident::new("n")),
+            labels: vec![Ident::new("Person")],
+        }))
+    );
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) WHERE n:Person AND n.age > 30 RETURN n.name"
+    );
+}
+
+#[test]
+fn parse_multi_label_predicate() {
+    let stmt = parse_cypher("MATCH (n) WHERE NOT n:Person:Admin RETURN n");
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) WHERE NOT n:Person:Admin RETURN n"
+    );
+}
+
+#[test]
+fn parse_union() {
+    let sql =
+        "MATCH (a:Person) RETURN a.name AS name UNION ALL MATCH (b:Company) RETURN b.name AS name";
+    let stmt = parse_cypher(sql);
+    match &stmt {
+        CypherStatement::Union { all, queries } => {
+            assert!(*all);
+            assert_eq!(queries.len(), 2);
+        }
+        _ => panic!("unexpected statement: {stmt:?}"),
+    }
+    assert_eq!(stmt.to_string(), sql);
+
+    let sql = "MATCH (a) RETURN a UNION MATCH (b) RETURN b UNION MATCH (c) RETURN c";
+    assert_eq!(parse_cypher(sql).to_string(), sql);
+}
+
+#[test]
+fn parse_union_errors() {
+    assert_eq!(
+        try_parse_cypher("MATCH (a) RETURN a UNION MATCH (b) RETURN b UNION ALL MATCH (c) RETURN c")
+            .unwrap_err()
+            .to_string(),
+        "sql parser error: Cannot mix UNION and UNION ALL in one Cypher query at Line: 1, Column: 45"
+    );
+    assert_eq!(
+        try_parse_cypher("MATCH (a) RETURN a UNION CREATE (b)")
+            .unwrap_err()
+            .to_string(),
+        "sql parser error: Expected: a MATCH ... RETURN query on each side of UNION, found: CREATE (b)"
+    );
+}
+
+#[test]
+fn parse_order_by_list() {
+    let stmt = parse_cypher("MATCH (n) RETURN n.name AS name ORDER BY name, n.age DESC");
+    let CypherStatement::Match {
+        r#return: Some(ret),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... RETURN, got {stmt:?}");
+    };
+    assert_eq!(ret.order_by.len(), 2);
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) RETURN n.name AS name ORDER BY name, n.age DESC"
+    );
+
+    // The long direction keywords are displayed as the short ones
+    let stmt = parse_cypher(
+        "MATCH (n) WITH n ORDER BY n.age DESCENDING, n.name ascending \
+         RETURN n.name AS name ORDER BY name Descending, n.age ASC",
+    );
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) WITH n ORDER BY n.age DESC, n.name ASC \
+         RETURN n.name AS name ORDER BY name DESC, n.age ASC"
+    );
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+    // They are not reserved
+    assert_eq!(
+        parse_cypher(
+            "MATCH (descending) RETURN descending.ascending AS ascending ORDER BY ascending"
+        )
+        .to_string(),
+        "MATCH (descending) RETURN descending.ascending AS ascending ORDER BY ascending"
+    );
+}
+
+#[test]
+fn parse_parameters() {
+    let stmt = parse_cypher("MATCH (n) WHERE n.age > $minAge RETURN n LIMIT $limit");
+    let CypherStatement::Match {
+        r#where: Some(Expr::BinaryOp { right, .. }),
+        r#return: Some(ret),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE ... RETURN, got {stmt:?}");
+    };
+    assert_eq!(
+        **right,
+        Expr::value(Value::Placeholder("$minAge".to_string()))
+    );
+    assert_eq!(
+        ret.limit,
+        Some(Expr::value(Value::Placeholder("$limit".to_string())))
+    );
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) WHERE n.age > $minAge RETURN n LIMIT $limit"
+    );
+}
+
+#[test]
+fn parse_in_list() {
+    let stmt = parse_cypher("MATCH (n) WHERE id(n) IN [1, 2, 3] RETURN n");
+    let CypherStatement::Match {
+        r#where: Some(Expr::InList { list, negated, .. }),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE ... IN, got {stmt:?}");
+    };
+    assert_eq!(list.len(), 3);
+    assert!(!negated);
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) WHERE id(n) IN (1, 2, 3) RETURN n"
+    );
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    assert_eq!(
+        parse_cypher("MATCH (n) WHERE NOT n.name IN [] RETURN n").to_string(),
+        "MATCH (n) WHERE NOT n.name IN () RETURN n"
+    );
+}
+
+#[test]
+fn access_summary() {
+    fn named(name: &str) -> AccessName {
+        AccessName::Named(name.to_string())
+    }
+    fn property(owner: AccessName, property: AccessName) -> PropertyAccess {
+        PropertyAccess { owner, property }
+    }
+
+    let summary = parse_cypher(
+        "MATCH (a:Person)-[r:KNOWS]->(b) WHERE b:Admin AND a.age > 30 \
+         RETURN a.name, b ORDER BY r.since",
+    )
+    .access_summary();
+    assert_eq!(
+        summary,
+        AccessSummary {
+            labels_read: [named("Admin"), named("Person")].into(),
+            labels_written: [].into(),
+            relationship_types: [named("KNOWS")].into(),
+            properties_read: [
+                property(named("Person"), named("age")),
+                property(named("Person"), named("name")),
+                property(named("Admin"), AccessName::Any),
+                property(named("KNOWS"), named("since")),
+            ]
+            .into(),
+            properties_written: [].into(),
+        }
+    );
+
+    // Elements without a label or type and RETURN * are wildcards
+    let summary = parse_cypher("MATCH (n)-[]->(m:City) RETURN *").access_summary();
+    assert_eq!(summary.labels_read, [AccessName::Any, named("City")].into());
+    assert_eq!(summary.relationship_types, [AccessName::Any].into());
+    assert_eq!(
+        summary.properties_read,
+        [
+            property(AccessName::Any, AccessName::Any),
+            property(named("City"), AccessName::Any),
+        ]
+        .into()
+    );
+
+    let summary =
+        parse_cypher("MERGE (c:City) ON CREATE SET c.name = c.code ON MATCH SET c = $props")
+            .access_summary();
+    assert_eq!(summary.labels_read, [named("City")].into());
+    assert_eq!(summary.labels_written, [named("City")].into());
+    assert_eq!(
+        summary.properties_read,
+        [property(named("City"), named("code"))].into()
+    );
+    assert_eq!(
+        summary.properties_written,
+        [
+            property(named("City"), AccessName::Any),
+            property(named("City"), named("name")),
+        ]
+        .into()
+    );
+
+    let summary = parse_cypher("CREATE (n), (m:Person {name: 'Ada'})").access_summary();
+    assert_eq!(
+        summary.labels_written,
+        [AccessName::Any, named("Person")].into()
+    );
+    assert!(summary.labels_read.is_empty());
+    assert_eq!(
+        summary.properties_written,
+        [property(named("Person"), named("name"))].into()
+    );
+
+    // UNION arms are summarized together
+    let summary =
+        parse_cypher("MATCH (a:A) RETURN a.x UNION MATCH (a:B) RETURN a.x").access_summary();
+    assert_eq!(
+        summary.properties_read,
+        [
+            property(named("A"), named("x")),
+            property(named("B"), named("x"))
+        ]
+        .into()
+    );
+}
+
+#[test]
+fn parse_string_literals() {
+    let stmt = parse_cypher(
+        r#"MATCH (n) WHERE n.name = "O'Brien" OR n.name = 'It\'s' OR n.bio = 'a\\b\n\u00e9\U0001F600' RETURN n"#,
+    );
+    let CypherStatement::Match {
+        r#where: Some(condition),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE, got {stmt:?}");
+    };
+    let mut values = vec![];
+    let mut exprs = vec![condition];
+    while let Some(expr) = exprs.pop() {
+        match expr {
+            Expr::BinaryOp { left, right, .. } => exprs.extend([left.as_ref(), right.as_ref()]),
+            Expr::Value(value) => values.push(value.value.clone()),
+            _ => {}
+        }
+    }
+    values.reverse();
+    assert_eq!(
+        values,
+        vec![
+            Value::DoubleQuotedString("O'Brien".to_string()),
+            Value::SingleQuotedString("It's".to_string()),
+            Value::SingleQuotedString("a\\b\né😀".to_string()),
+        ]
+    );
+
+    // Display escapes the strings again
+    assert_eq!(
+        stmt.to_string(),
+        r#"MATCH (n) WHERE n.name = "O'Brien" OR n.name = 'It\'s' OR n.bio = 'a\\b\né😀' RETURN n"#
+    );
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+    assert_eq!(
+        parse_cypher(r#"MATCH (n) RETURN "say \"hi\"""#).to_string(),
+        r#"MATCH (n) RETURN "say \"hi\"""#
+    );
+
+    assert_eq!(
+        try_parse_cypher(r"MATCH (n) WHERE n.name = 'a\u00' RETURN n")
+            .unwrap_err()
+            .to_string(),
+        "sql parser error: Invalid Unicode escape sequence at Line: 1, Column: 29"
+    );
+}
+
+#[test]
+fn parse_numeric_literals() {
+    // Hexadecimal and octal integers are normalized to decimal
+    let stmt = parse_cypher("MATCH (n) WHERE n.flags = 0x1F AND n.mode <> -0o17 RETURN n");
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) WHERE n.flags = 31 AND n.mode <> -15 RETURN n"
+    );
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    // Exponents and leading-dot fractions are numbers, negated by unary minus
+    let stmt = parse_cypher("MATCH (n) RETURN 1e6, -.5, 2.5E-3");
+    let CypherStatement::Match {
+        r#return: Some(ret),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... RETURN, got {stmt:?}");
+    };
+    for item in &ret.items {
+        let expr = match item {
+            SelectItem::UnnamedExpr(Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            }) => expr.as_ref(),
+            SelectItem::UnnamedExpr(expr) => expr,
+            _ => panic!("expected an expression, got {item:?}"),
+        };
+        let Expr::Value(value) = expr else {
+            panic!("expected a number, got {expr:?}");
+        };
+        assert!(matches!(value.value, Value::Number(..)), "{value:?}");
+    }
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    // Negative numbers in property maps
+    let stmt = parse_cypher("CREATE (n:Point {x: -1, y: -0x10, z: -2.5})");
+    assert_eq!(
+        stmt.to_string(),
+        "CREATE (n:Point {x: -1, y: -16, z: -2.5})"
+    );
+
+    assert_eq!(
+        try_parse_cypher("MATCH (n) WHERE n.flags = 0x RETURN n")
+            .unwrap_err()
+            .to_string(),
+        "sql parser error: Expected digits after radix prefix at Line: 1, Column: 28"
+    );
+    assert_eq!(
+        try_parse_cypher("MATCH (n) WHERE n.flags = 0x1FFFFFFFFFFFFFFFF RETURN n")
+            .unwrap_err()
+            .to_string(),
+        "sql parser error: Integer literal out of range at Line: 1, Column: 28"
+    );
+}
+
+#[test]
+fn parse_booleans_and_null() {
+    let stmt = parse_cypher(
+        "MATCH (n) WHERE n.active = TRUE AND n.deleted = False AND n.owner <> null RETURN true",
+    );
+    let CypherStatement::Match {
+        r#where: Some(condition),
+        r#return: Some(ret),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE ... RETURN, got {stmt:?}");
+    };
+    let mut values = vec![];
+    let mut exprs = vec![condition];
+    exprs.extend(ret.items.iter().filter_map(|item| match item {
+        SelectItem::UnnamedExpr(expr) => Some(expr),
+        _ => None,
+    }));
+    while let Some(expr) = exprs.pop() {
+        match expr {
+            Expr::BinaryOp { left, right, .. } => exprs.extend([right.as_ref(), left.as_ref()]),
+            Expr::Value(value) => values.push(value.value.clone()),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        values,
+        vec![
+            Value::Boolean(true),
+            Value::Boolean(true),
+            Value::Boolean(false),
+            Value::Null,
+        ]
+    );
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) WHERE n.active = true AND n.deleted = false AND n.owner <> NULL RETURN true"
+    );
+
+    let stmt = parse_cypher("CREATE (n:Flag {enabled: TRUE, hidden: false, note: NULL})");
+    let CypherStatement::Create { patterns, .. } = &stmt else {
+        panic!("expected CREATE, got {stmt:?}");
+    };
+    let PatternElement::Node {
+        properties: Some(Expr::Dictionary(fields)),
+        ..
+    } = &patterns[0].elements[0]
+    else {
+        panic!("expected a node with a property map, got {:?}", patterns[0]);
+    };
+    let values: Vec<_> = fields
+        .iter()
+        .map(|field| match field.value.as_ref() {
+            Expr::Value(value) => value.value.clone(),
+            expr => panic!("expected a literal, got {expr:?}"),
+        })
+        .collect();
+    assert_eq!(
+        values,
+        [Value::Boolean(true), Value::Boolean(false), Value::Null]
+    );
+}
+
+#[test]
+fn parse_negative_numbers() {
+    // A minus in an expression is a unary minus, and in a pattern a dash
+    let cypher = "CREATE (a:Point {x: -3, y: -0.5})-[:NEXT {dx: -1}]->(b {x: -4.25})";
+    let stmt = parse_cypher(cypher);
+    let CypherStatement::Create { patterns, .. } = &stmt else {
+        panic!("expected CREATE, got {stmt:?}");
+    };
+    let values: Vec<_> = patterns[0]
+        .elements
+        .iter()
+        .flat_map(|element| match element.properties() {
+            Some(Expr::Dictionary(fields)) => fields.iter(),
+            properties => panic!("expected a property map, got {properties:?}"),
+        })
+        .map(|field| match field.value.as_ref() {
+            Expr::UnaryOp {
+                op: UnaryOperator::Minus,
+                expr,
+            } => expr.to_string(),
+            expr => panic!("expected a negative number, got {expr:?}"),
+        })
+        .collect();
+    assert_eq!(values, ["3", "0.5", "1", "4.25"]);
+    assert_eq!(stmt.to_string(), cypher);
+
+    for (cypher, display) in [
+        (
+            "CREATE (n:Point {x:-3, y:- 0.5})",
+            "CREATE (n:Point {x: -3, y: -0.5})",
+        ),
+        (
+            "MATCH (a {x: -1})-->(b) WHERE b.x>-3 AND b.y<-1 RETURN -(b.a), -b.x, 1 - -2",
+            "MATCH (a {x: -1})-[]->(b) WHERE b.x > -3 AND b.y < -1 RETURN -(b.a), -b.x, 1 - -2",
+        ),
+        (
+            "MATCH (n) RETURN n SKIP -1 LIMIT -2",
+            "MATCH (n) RETURN n SKIP -1 LIMIT -2",
+        ),
+    ] {
+        assert_eq!(parse_cypher(cypher).to_string(), display, "{cypher}");
+    }
+}
+
+#[test]
+fn parse_merge_patterns() {
+    let stmt = parse_cypher("MERGE (a:Person)-[r:KNOWS]->(b:Person)");
+    let CypherStatement::Merge { patterns, .. } = &stmt else {
+        panic!("expected MERGE, got {stmt:?}");
+    };
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].elements.len(), 3);
+
+    let error = |cypher| try_parse_cypher(cypher).unwrap_err().to_string();
+    assert_eq!(
+        error("MERGE (a), (b)"),
+        "sql parser error: MERGE takes a single pattern, found 2 at Line: 1, Column: 7"
+    );
+    assert_eq!(
+        error("MERGE (a)-[r]->(b)"),
+        "sql parser error: Exactly one relationship type must be specified for MERGE, \
+         found 0 in -[r]-> at Line: 1, Column: 7"
+    );
+    assert_eq!(
+        error("MERGE (a)<-[r:KNOWS|LIKES]-(b)"),
+        "sql parser error: Exactly one relationship type must be specified for MERGE, \
+         found 2 in <-[r:KNOWS|LIKES]- at Line: 1, Column: 7"
+    );
+    assert_eq!(
+        error("MERGE (a)-[r:KNOWS*2]-(b)"),
+        "sql parser error: Variable length relationships cannot be used in MERGE: \
+         -[r:KNOWS*2]- at Line: 1, Column: 7"
+    );
+}
+
+#[test]
+fn parse_merge_actions() {
+    let triggers = |cypher| match parse_cypher(cypher) {
+        CypherStatement::Merge { actions, .. } => actions
+            .iter()
+            .map(|action| (action.trigger, action.items.len()))
+            .collect::<Vec<_>>(),
+        stmt => panic!("expected MERGE, got {stmt:?}"),
+    };
+    assert_eq!(triggers("MERGE (a:Person)"), []);
+    assert_eq!(
+        triggers("MERGE (a:Person) ON CREATE SET a.created = 1 ON MATCH SET a.seen = 1, a.n = 2"),
+        [(MergeTrigger::OnCreate, 1), (MergeTrigger::OnMatch, 2)]
+    );
+    assert_eq!(
+        triggers("MERGE (a:Person) ON MATCH SET a.seen = 1 ON CREATE SET a.created = 1"),
+        [(MergeTrigger::OnMatch, 1), (MergeTrigger::OnCreate, 1)]
+    );
+
+    // Display keeps the order of the actions
+    let cypher = "MERGE (a:Person) ON MATCH SET a.seen = 1 ON CREATE SET a.created = 1";
+    assert_eq!(parse_cypher(cypher).to_string(), cypher);
+
+    for (cypher, error) in [
+        (
+            "MERGE (a) ON CREATE SET a.x = 1 ON CREATE SET a.y = 2",
+            "Duplicate ON CREATE action in MERGE at Line: 1, Column: 33",
+        ),
+        (
+            "MERGE (a) ON MATCH SET a.x = 1 ON CREATE SET a.y = 2 ON MATCH SET a.z = 3",
+            "Duplicate ON MATCH action in MERGE at Line: 1, Column: 54",
+        ),
+        (
+            "MERGE (a) ON ON CREATE SET a.x = 1",
+            "Expected: CREATE or MATCH after ON, found: ON at Line: 1, Column: 14",
+        ),
+        (
+            "MERGE (a) ON DELETE SET a.x = 1",
+            "Expected: CREATE or MATCH after ON, found: DELETE at Line: 1, Column: 14",
+        ),
+        (
+            "MERGE (a) ON MATCH a.x = 1",
+            "Expected: SET, found: a at Line: 1, Column: 20",
+        ),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!("sql parser error: {error}"),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn pattern_element_accessors() {
+    let cypher =
+        "MATCH (a:Person)<-[r:KNOWS]-(b)-[:LIKES|LOVES]->(c)-[s]-(d)<-[t:R*2]->(e) RETURN a";
+    let stmt = parse_cypher(cypher);
+    // Every direction displays with its own arrows and round trips
+    assert_eq!(stmt.to_string(), cypher);
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    let CypherStatement::Match { patterns, .. } = &stmt else {
+        panic!("expected MATCH, got {stmt:?}");
+    };
+    let elements = &patterns[0].elements;
+    assert_eq!(
+        elements
+            .iter()
+            .map(|element| element.direction().cloned())
+            .collect::<Vec<_>>(),
+        [
+            None,
+            Some(RelationshipDirection::Left),
+            None,
+            Some(RelationshipDirection::Right),
+            None,
+            Some(RelationshipDirection::None),
+            None,
+            Some(RelationshipDirection::Both),
+            None,
+        ]
+    );
+    assert!(elements[0].is_node());
+    assert!(!elements[1].is_node());
+    assert_eq!(elements[0].variable(), Some(&Ident::new("a")));
+    assert_eq!(elements[3].variable(), None);
+    assert_eq!(elements[0].labels_or_types(), [Ident::new("Person")]);
+    assert_eq!(
+        elements[3].labels_or_types(),
+        [Ident::new("LIKES"), Ident::new("LOVES")]
+    );
+    assert!(elements[2].labels_or_types().is_empty());
+    assert_eq!(elements[0].properties(), None);
+
+    assert_eq!(
+        parse_cypher("MATCH (a)-[]->(b) RETURN a").to_string(),
+        "MATCH (a)-[]->(b) RETURN a"
+    );
+}
+
+#[test]
+fn parse_pattern_comprehension() {
+    let cypher = "MATCH (n:Person) RETURN [(n)-[:KNOWS]->(f) WHERE f.age > 30 | f.name] AS friends";
+    let stmt = parse_cypher(cypher);
+    assert_eq!(stmt.to_string(), cypher);
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    let CypherStatement::Match {
+        r#return: Some(return_clause),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... RETURN, got {stmt:?}");
+    };
+    let SelectItem::ExprWithAlias {
+        expr: Expr::Cypher(comprehension),
+        ..
+    } = &return_clause.items[0]
+    else {
+        panic!("expected a pattern comprehension, got {return_clause:?}");
+    };
+    let CypherExpr::PatternComprehension {
+        pattern, r#where, ..
+    } = comprehension.as_ref()
+    else {
+        panic!("expected a pattern comprehension, got {comprehension:?}");
+    };
+    assert_eq!(pattern.elements.len(), 3);
+    assert!(r#where.is_some());
+
+    // Reads through the comprehension are part of the access summary
+    let summary = stmt.access_summary();
+    assert_eq!(
+        summary.relationship_types,
+        [AccessName::Named("KNOWS".to_string())].into()
+    );
+    assert!(summary.properties_read.contains(&PropertyAccess {
+        owner: AccessName::Any,
+        property: AccessName::Named("age".to_string()),
+    }));
+
+    // Brackets around anything but a relationship pattern are still lists
+    assert_eq!(
+        parse_cypher("MATCH (n) RETURN [(1), 2] AS l").to_string(),
+        "MATCH (n) RETURN [(1), 2] AS l"
+    );
+    assert!(try_parse_cypher("MATCH (n) RETURN [(n) | n.name]").is_err());
+}
+
+#[test]
+fn parse_match_set() {
+    for cypher in [
+        "MATCH (n:Person) WHERE n.name = 'Ada' SET n.age = n.age + 1",
+        "MATCH (a)-[:KNOWS]->(b) SET b.tags = b.tags + ['friend'], b.seen = true",
+    ] {
+        let stmt = parse_cypher(cypher);
+        assert_eq!(stmt.to_string(), cypher);
+        assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+    }
+
+    let stmt = parse_cypher("MATCH (n:Person) SET n.age = n.age + 1");
+    let CypherStatement::Set { items, .. } = &stmt else {
+        panic!("expected SET, got {stmt:?}");
+    };
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].value.to_string(), "n.age + 1");
+    let person_age = PropertyAccess {
+        owner: AccessName::Named("Person".to_string()),
+        property: AccessName::Named("age".to_string()),
+    };
+    let summary = stmt.access_summary();
+    assert_eq!(summary.properties_read, [person_age.clone()].into());
+    assert_eq!(summary.properties_written, [person_age].into());
+}
+
+#[test]
+fn parse_delete_targets() {
+    // Nodes and relationships bound by the MATCH
+    for cypher in [
+        "MATCH (n:Person) WHERE n.age > 100 DELETE n",
+        "MATCH (a:Person)-[r:KNOWS]->(b) DETACH DELETE r, b",
+    ] {
+        let stmt = parse_cypher(cypher);
+        assert_eq!(stmt.to_string(), cypher);
+        assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+    }
+
+    let stmt = parse_cypher("MATCH (a:Person)-[r:KNOWS]->(b:City) DELETE r, a");
+    let CypherStatement::Delete { patterns, what, .. } = &stmt else {
+        panic!("expected DELETE, got {stmt:?}");
+    };
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(
+        what,
+        &[
+            Expr::Identifier(Ident::new("r")),
+            Expr::Identifier(Ident::new("a"))
+        ]
+    );
+    // Only the labels of the deleted node are written
+    let summary = stmt.access_summary();
+    assert_eq!(
+        summary.labels_written,
+        [AccessName::Named("Person".to_string())].into()
+    );
+
+    for (cypher, error) in [
+        (
+            "MATCH (n) DELETE n.name",
+            "cannot DELETE property expression `n.name`; use REMOVE at Line: 1, Column: 18",
+        ),
+        (
+            "MATCH (n) DELETE n:Person",
+            "cannot DELETE label expression `n:Person`; use REMOVE at Line: 1, Column: 18",
+        ),
+        (
+            "MATCH (n)-[r]->(m) DELETE r, x",
+            "variable `x` in DELETE is not bound at Line: 1, Column: 30",
+        ),
+        (
+            "MATCH (n) DELETE n + 1",
+            "cannot DELETE expression `n + 1`; expected a node or relationship variable \
+             at Line: 1, Column: 18",
+        ),
+        // Conditions belong to the MATCH, so there is no standalone DELETE
+        (
+            "DELETE n WHERE n.age > 100",
+            "DELETE must follow a MATCH that binds its variables, \
+             as in MATCH (n) WHERE ... DELETE n at Line: 1, Column: 1",
+        ),
+        (
+            "DETACH DELETE n",
+            "DELETE must follow a MATCH that binds its variables, \
+             as in MATCH (n) WHERE ... DELETE n at Line: 1, Column: 1",
+        ),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!("sql parser error: {error}"),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parse_match_create() {
+    let cypher = "MATCH (a:Person), (b:Person) WHERE a.name = 'A' AND b.name = 'B' \
+                  CREATE (a)-[:KNOWS {since: 2024}]->(b), (a)-[:OWNS]->(c:Car)";
+    let stmt = parse_cypher(cypher);
+    assert_eq!(stmt.to_string(), cypher);
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+    let CypherStatement::Create {
+        r#match,
+        r#where,
+        patterns,
+        r#return: None,
+    } = &stmt
+    else {
+        panic!("expected CREATE, got {stmt:?}");
+    };
+    assert_eq!(r#match.len(), 2);
+    assert!(r#where.is_some());
+    assert_eq!(patterns.len(), 2);
+
+    // The matched nodes are read, only the new node is written
+    let summary = stmt.access_summary();
+    assert_eq!(
+        summary.labels_read,
+        [AccessName::Named("Person".to_string())].into()
+    );
+    assert_eq!(
+        summary.labels_written,
+        [AccessName::Named("Car".to_string())].into()
+    );
+    assert_eq!(
+        summary.relationship_types,
+        [
+            AccessName::Named("KNOWS".to_string()),
+            AccessName::Named("OWNS".to_string())
+        ]
+        .into()
+    );
+}
+
+#[test]
+fn parse_create_return() {
+    for cypher in [
+        "CREATE (n:Person {name: 'Ada'}) RETURN id(n)",
+        "CREATE (a)-[:KNOWS]->(b) RETURN id(a) AS a, b.name",
+        "MATCH (a:Person) CREATE (a)-[:OWNS]->(c:Car) RETURN elementId(c)",
+    ] {
+        let stmt = parse_cypher(cypher);
+        assert_eq!(stmt.to_string(), cypher);
+        assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+        let CypherStatement::Create {
+            r#return: Some(ret),
+            ..
+        } = &stmt
+        else {
+            panic!("expected CREATE ... RETURN, got {stmt:?}");
+        };
+        assert!(!ret.items.is_empty());
+    }
+}
+
+#[test]
+fn parse_unwind() {
+    for cypher in [
+        "UNWIND [1, 2, 3] AS x RETURN x",
+        "UNWIND range(1, 100) AS i CREATE (:Item {n: i})",
+        "UNWIND $ids AS id MATCH (n) WHERE n.id = id RETURN n",
+        "UNWIND [1] AS x RETURN x UNION UNWIND [2] AS x RETURN x",
+    ] {
+        let stmt = parse_cypher(cypher);
+        assert_eq!(stmt.to_string(), cypher);
+        assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+    }
+
+    let CypherStatement::Unwind {
+        list,
+        variable,
+        query,
+    } = parse_cypher("UNWIND range(1, 3) AS i RETURN i * 2 AS doubled")
+    else {
+        panic!("expected UNWIND");
+    };
+    assert_eq!(list.to_string(), "range(1, 3)");
+    assert_eq!(variable, Ident::new("i"));
+    assert!(matches!(*query, CypherStatement::Match { r#return: Some(_), .. }));
+
+    for (cypher, error) in [
+        ("UNWIND [1, 2] RETURN 1", "Expected: AS, found: RETURN"),
+        (
+            "UNWIND [1] AS x",
+            "Expected: MATCH, CREATE, MERGE, SHOW, UNWIND, WITH, or RETURN, found: EOF",
+        ),
+    ] {
+        let message = try_parse_cypher(cypher).unwrap_err().to_string();
+        assert!(message.contains(error), "{cypher}: {message}");
+    }
+}
+
+#[test]
+fn build_statements() {
+    let property = |variable: &str, property: &str| {
+        Expr::CompoundIdentifier(vec![Ident::new(variable), Ident::new(property)])
+    };
+    let stmt = CypherStatement::match_([Pattern::path([
+        PatternElement::node("n").label("Person"),
+        PatternElement::anonymous_rel().rtype("KNOWS").right(),
+        PatternElement::node("m"),
+    ])])
+    .where_(Expr::BinaryOp {
+        left: Box::new(property("n", "age")),
+        op: BinaryOperator::Gt,
+        right: Box::new(Expr::value(Value::Number("30".parse().unwrap(), false))),
+    })
+    .return_(
+        ReturnClause::items([SelectItem::UnnamedExpr(property("m", "name"))])
+            .limit(Expr::value(Value::Number("5".parse().unwrap(), false))),
+    );
+    let cypher = "MATCH (n:Person)-[:KNOWS]->(m) WHERE n.age > 30 RETURN m.name LIMIT 5";
+    assert_eq!(stmt.to_string(), cypher);
+    assert_eq!(parse_cypher(cypher), stmt);
+
+    let stmt = CypherStatement::match_([Pattern::path([
+        PatternElement::anonymous_node()
+            .label("Person")
+            .property("name", Expr::value(Value::SingleQuotedString("Ada".into())))
+            .property("born", Expr::value(Value::Placeholder("$born".into()))),
+        PatternElement::rel("r")
+            .rtype("LIKES")
+            .rtype("LOVES")
+            .length(RelationshipLength::Variable)
+            .left(),
+        PatternElement::node("b"),
+    ])])
+    .optional()
+    .return_(
+        ReturnClause::items([SelectItem::UnnamedExpr(Expr::Identifier(Ident::new("b")))])
+            .distinct()
+            .order_by(Ident::new("b"))
+            .skip(Expr::value(Value::Placeholder("$skip".into()))),
+    );
+    let cypher = "OPTIONAL MATCH (:Person {name: 'Ada', born: $born})<-[r:LIKES|LOVES*]-(b) \
+                  RETURN DISTINCT b ORDER BY b SKIP $skip";
+    assert_eq!(stmt.to_string(), cypher);
+    assert_eq!(parse_cypher(cypher), stmt);
+}
+
+#[test]
+fn canonical_text() {
+    for cypher in [
+        "match (n:Person {name: 'Ada', age: 36})-[r:KNOWS]->(m)   where n.age > 30 and not m.name starts with 'A' \
+         return distinct m.name as name order by name desc skip 1 limit 5",
+        "MATCH (n) WHERE n.name = 'it''s  spaced' OR n.limit IS NULL RETURN n.order, n.as AS `match`",
+        "merge (c:City {name: $name}) on create set c.created = true",
+        "MATCH (a)-[r]->(b) WHERE a:Admin DETACH DELETE r, b",
+        "MATCH (n) RETURN n UNION ALL MATCH (m) RETURN m",
+    ] {
+        let stmt = parse_cypher(cypher);
+        let canonical = stmt.to_canonical_string();
+        assert_eq!(parse_cypher(&canonical), stmt, "{canonical}");
+        assert_eq!(parse_cypher(&canonical).to_canonical_string(), canonical);
+    }
+
+    assert_eq!(
+        parse_cypher("match (n {b: 1,   a: 'x  y'}) where n.limit <> 2 return n.on as limit")
+            .to_canonical_string(),
+        "MATCH (n {b: 1, a: 'x  y'}) WHERE n.limit <> 2 RETURN n.on AS limit"
+    );
+
+    let formatter = CypherFormatter {
+        uppercase_keywords: false,
+        sort_property_keys: true,
+        ..Default::default()
+    };
+    let stmt = parse_cypher(
+        "MATCH (n:Person {name: 'Ada', age: 36})-[:KNOWS {since: 1, via: 'work'}]->(m) \
+         WHERE n.born IS NOT NULL RETURN m.name ORDER BY m.name DESC",
+    );
+    let canonical = formatter.format(&stmt);
+    assert_eq!(
+        canonical,
+        "match (n:Person {age: 36, name: 'Ada'})-[:KNOWS {since: 1, via: 'work'}]->(m) \
+         where n.born is not null return m.name order by m.name desc"
+    );
+    assert_eq!(formatter.format(&parse_cypher(&canonical)), canonical);
+}
+
+#[test]
+fn parse_skip_and_limit_in_either_order() {
+    let canonical = parse_cypher("MATCH (n) RETURN n ORDER BY n.name SKIP 5 LIMIT 10");
+    let stmt = parse_cypher("MATCH (n) RETURN n ORDER BY n.name LIMIT 10 SKIP 5");
+    assert_eq!(stmt, canonical);
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (n) RETURN n ORDER BY n.name SKIP 5 LIMIT 10"
+    );
+
+    for (cypher, error) in [
+        (
+            "MATCH (n) RETURN n LIMIT 5 LIMIT 10",
+            "Duplicate LIMIT in RETURN at Line: 1, Column: 28",
+        ),
+        (
+            "MATCH (n) RETURN n ORDER BY n.name SKIP 1 LIMIT 5 SKIP 2",
+            "Duplicate SKIP in RETURN at Line: 1, Column: 51",
+        ),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!("sql parser error: {error}"),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parse_return_aliases() {
+    let stmt = parse_cypher(
+        "MATCH (n:Person) RETURN n.name AS fullName, count(*) AS c, n.age AS end, \
+         n.city AS `home town`, n SKIP 1",
+    );
+    let CypherStatement::Match {
+        r#return: Some(return_clause),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... RETURN, got {stmt:?}");
+    };
+    let aliases: Vec<_> = return_clause
+        .items
+        .iter()
+        .map(|item| match item {
+            SelectItem::ExprWithAlias { alias, .. } => Some(alias.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        aliases,
+        [
+            Some("fullName".to_string()),
+            Some("c".to_string()),
+            Some("end".to_string()),
+            Some("`home town`".to_string()),
+            None,
+        ]
+    );
+    assert!(return_clause.skip.is_some());
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    for (cypher, error) in [
+        (
+            "MATCH (n) RETURN n.name fullName",
+            "Expected: AS before the alias fullName of a RETURN item at Line: 1, Column: 25",
+        ),
+        (
+            "MATCH (n) RETURN n.name AS 'fullName'",
+            "Expected: an alias after AS, found: 'fullName' at Line: 1, Column: 28",
+        ),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!("sql parser error: {error}"),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parse_relationship_types() {
+    let stmt = parse_cypher("MATCH (a)-[r:`HAS PART`|IS_A|`it's`]->(b) RETURN b");
+    let CypherStatement::Match { patterns, .. } = &stmt else {
+        panic!("expected MATCH, got {stmt:?}");
+    };
+    let types: Vec<_> = patterns[0].elements[1]
+        .labels_or_types()
+        .iter()
+        .map(|ident| (ident.value.as_str(), ident.quote_style))
+        .collect();
+    assert_eq!(
+        types,
+        [("HAS PART", Some('`')), ("IS_A", None), ("it's", Some('`'))]
+    );
+    assert_eq!(
+        stmt.to_string(),
+        "MATCH (a)-[r:`HAS PART`|IS_A|`it's`]->(b) RETURN b"
+    );
+
+    for (cypher, found, column) in [
+        ("MATCH (a)-[r:'KNOWS']->(b) RETURN a", "'KNOWS'", 14),
+        ("MATCH (a)-[r:KNOWS|>]->(b) RETURN a", ">", 20),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!(
+                "sql parser error: Expected: a relationship type, in backticks unless it is \
+                 a bare name, found: {found} at Line: 1, Column: {column}"
+            ),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parse_names_needing_backticks() {
+    // Names the tokenizer would split parse in backticks, and are displayed
+    // in them
+    let cypher = "MATCH (n:`E-Commerce`:`2nd`)-[r:`1ST_DEGREE`|KNOWS]->(m) \
+                  WHERE m:`B-2` RETURN n";
+    let stmt = parse_cypher(cypher);
+    let CypherStatement::Match { patterns, .. } = &stmt else {
+        panic!("expected MATCH, got {stmt:?}");
+    };
+    let names: Vec<_> = patterns[0].elements[..2]
+        .iter()
+        .flat_map(|element| element.labels_or_types())
+        .map(|ident| (ident.value.as_str(), ident.quote_style))
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("E-Commerce", Some('`')),
+            ("2nd", Some('`')),
+            ("1ST_DEGREE", Some('`')),
+            ("KNOWS", None)
+        ]
+    );
+    assert_eq!(stmt.to_string(), cypher);
+
+    // Bare, they are rejected whole, with the backticks they need
+    for (cypher, what, name, column) in [
+        ("MATCH (n:E-Commerce) RETURN n", "a label", "E-Commerce", 10),
+        ("MATCH (n:Shop:2nd) RETURN n", "a label", "2nd", 15),
+        ("MATCH (n) WHERE n:Tech-2-Go RETURN n", "a label", "Tech-2-Go", 19),
+        ("MATCH ()-[r:1ST_DEGREE]->() RETURN r", "a relationship type", "1ST_DEGREE", 13),
+        ("MATCH ()-[r:KNOWS|CO-AUTHOR]->() RETURN r", "a relationship type", "CO-AUTHOR", 19),
+        ("MATCH (a)-[r:123]->(b) RETURN a", "a relationship type", "123", 14),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!(
+                "sql parser error: Expected: {what}, in backticks as `{name}` since a bare \
+                 name cannot start with a digit or hold a dash, found: {name} \
+                 at Line: 1, Column: {column}"
+            ),
+            "{cypher}"
+        );
+    }
+    // A dash apart from the name is still an operator
+    let stmt = parse_cypher("MATCH (n) RETURN n:Person - 1");
+    assert_eq!(stmt.to_string(), "MATCH (n) RETURN n:Person - 1");
+}
+
+#[test]
+fn parse_relationship_spacing() {
+    // Every direction, with and without details, with or without a space in
+    // each gap between `(a)`, `<`, `-`, the details, `-`, `>` and `(b)`
+    for (left, right, canonical) in [
+        (false, true, "-[{}]->"),
+        (true, false, "<-[{}]-"),
+        (false, false, "-[{}]-"),
+        (true, true, "<-[{}]->"),
+    ] {
+        for details in ["r:KNOWS", ""] {
+            let expected = format!("MATCH (a){}(b) RETURN a", canonical.replace("{}", details));
+            for spaces in 0..1 << 6 {
+                let gap = |i: u32| if spaces & 1 << i == 0 { "" } else { " " };
+                let mut cypher = format!("MATCH (a){}", gap(0));
+                if left {
+                    cypher += &format!("<{}", gap(1));
+                }
+                cypher += &format!("-{}", gap(2));
+                if !details.is_empty() {
+                    cypher += &format!("[{details}]{}", gap(3));
+                }
+                cypher += "-";
+                if right {
+                    cypher += &format!("{}>", gap(4));
+                }
+                cypher += &format!("{}(b) RETURN a", gap(5));
+                // `-- ` starts a comment
+                if cypher.contains("-- ") {
+                    continue;
+                }
+                let stmt = try_parse_cypher(&cypher).unwrap_or_else(|e| panic!("{cypher}: {e}"));
+                assert_eq!(stmt.to_string(), expected, "{cypher}");
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_anonymous_nodes() {
+    let nodes = [
+        ("()", PatternElement::anonymous_node()),
+        (
+            "(:Person)",
+            PatternElement::anonymous_node().label("Person"),
+        ),
+        (
+            "(:A:B)",
+            PatternElement::anonymous_node().label("A").label("B"),
+        ),
+        (
+            "({k: 1})",
+            PatternElement::anonymous_node()
+                .property("k", Expr::value(Value::Number("1".parse().unwrap(), false))),
+        ),
+    ];
+    for (node, element) in &nodes {
+        // Alone, at either end of a relationship, and between two
+        for (cypher, elements) in [
+            (format!("MATCH {node} RETURN 1"), vec![element.clone()]),
+            (
+                format!("MATCH {node}-[:R]->(b) RETURN b"),
+                vec![
+                    element.clone(),
+                    PatternElement::anonymous_rel().rtype("R").right(),
+                    PatternElement::node("b"),
+                ],
+            ),
+            (
+                format!("MATCH (a)<-[:R]-{node} RETURN a"),
+                vec![
+                    PatternElement::node("a"),
+                    PatternElement::anonymous_rel().rtype("R").left(),
+                    element.clone(),
+                ],
+            ),
+            (
+                format!("MATCH (a)-[:R]-{node}-[:R]-(b) RETURN a"),
+                vec![
+                    PatternElement::node("a"),
+                    PatternElement::anonymous_rel().rtype("R"),
+                    element.clone(),
+                    PatternElement::anonymous_rel().rtype("R"),
+                    PatternElement::node("b"),
+                ],
+            ),
+        ] {
+            let stmt = parse_cypher(&cypher);
+            assert_eq!(stmt.to_string(), cypher);
+            let CypherStatement::Match { patterns, .. } = stmt else {
+                panic!("{cypher}: not a MATCH");
+            };
+            assert_eq!(patterns, [Pattern::path(elements)], "{cypher}");
+        }
+        for cypher in [
+            format!("OPTIONAL MATCH {node}-[:R]->() RETURN 1"),
+            format!("CREATE {node}"),
+            format!("MERGE {node}"),
+        ] {
+            assert_eq!(parse_cypher(&cypher).to_string(), cypher);
+        }
+    }
+}
+
+#[test]
+fn parse_node_contents() {
+    // Every combination of a variable, labels and properties, written
+    // without spaces, with them, and as displayed
+    let variables = [
+        ("", "", None),
+        ("n", "n", Some(Ident::new("n"))),
+        (
+            "`my node`",
+            "`my node`",
+            Some(Ident::with_quote('`', "my node")),
+        ),
+        ("`match`", "`match`", Some(Ident::with_quote('`', "match"))),
+        ("match", "match", Some(Ident::new("match"))),
+    ];
+    let labels: [(&[&str], &[&str]); 3] = [
+        (&[], &[]),
+        (&[":Person"], &["Person"]),
+        (&[":Person", ":Admin"], &["Person", "Admin"]),
+    ];
+    let properties = [
+        ("", "", None),
+        (
+            "{name:'x'}",
+            "{name: 'x'}",
+            Some(Expr::Dictionary(vec![DictionaryField {
+                key: Ident::new("name"),
+                value: Box::new(Expr::value(Value::SingleQuotedString("x".to_string()))),
+            }])),
+        ),
+        (
+            "$props",
+            "$props",
+            Some(Expr::value(Value::Placeholder("$props".to_string()))),
+        ),
+    ];
+    for (variable, displayed_variable, ident) in &variables {
+        for (label_texts, names) in &labels {
+            for (map, displayed_map, map_expr) in &properties {
+                let element = PatternElement::Node {
+                    variable: ident.clone(),
+                    labels: names.iter().map(|name| Ident::new(*name)).collect(),
+                    properties: map_expr.clone(),
+                };
+                let head = format!("{displayed_variable}{}", label_texts.concat());
+                let displayed = match (head.is_empty(), displayed_map.is_empty()) {
+                    (false, false) => format!("({head} {displayed_map})"),
+                    _ => format!("({head}{displayed_map})"),
+                };
+                let compact = format!("({variable}{}{map})", label_texts.concat());
+                let spaced = format!("( {variable} {} {map} )", label_texts.join(" "));
+                for node in [&compact, &spaced, &displayed] {
+                    let cypher = format!("CREATE {node}");
+                    let stmt = parse_cypher(&cypher);
+                    let CypherStatement::Create { patterns, .. } = &stmt else {
+                        panic!("{cypher}: not a CREATE");
+                    };
+                    assert_eq!(
+                        patterns,
+                        &[Pattern::path(vec![element.clone()])],
+                        "{cypher}"
+                    );
+                    assert_eq!(stmt.to_string(), format!("CREATE {displayed}"), "{cypher}");
+                }
+            }
+        }
+    }
+
+    for cypher in [
+        "MATCH (n {a: 1} {b: 2}) RETURN n",
+        "MATCH (n $a $b) RETURN n",
+        "MATCH ({a: 1}:Person) RETURN 1",
+        "MATCH (:Person n) RETURN n",
+        "MATCH (n:) RETURN n",
+    ] {
+        assert!(try_parse_cypher(cypher).is_err(), "{cypher}");
+    }
+
+    // Only the nodes CREATE creates take a parameter as their properties
+    let cypher = "MATCH (a:Person) CREATE (a)-[:OWNS]->(c:Car $car)";
+    assert_eq!(parse_cypher(cypher).to_string(), cypher);
+    for cypher in [
+        "MATCH (n $props) RETURN n",
+        "MATCH (a) OPTIONAL MATCH (a)-->(b $props) RETURN b",
+        "MERGE (n:Person $props)",
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher)
+                .unwrap_err()
+                .to_string()
+                .split(" at Line")
+                .next(),
+            Some(
+                "sql parser error: A parameter can be the properties of a node in CREATE only, \
+                 use a map of its values instead, e.g. {name: $props.name}"
+            ),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parse_with_clause() {
+    let cypher =
+        "MATCH (p:Person)-[:BOUGHT]->(i) WITH p, count(i) AS n WHERE n > 5 RETURN p.name, n";
+    let stmt = parse_cypher(cypher);
+    let CypherStatement::Match {
+        with: Some(with),
+        r#return: Some(_),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WITH ... RETURN, got {stmt:?}");
+    };
+    assert_eq!(with.projection.items.len(), 2);
+    assert_eq!(with.r#where.as_ref().unwrap().to_string(), "n > 5");
+    assert_eq!(stmt.to_string(), cypher);
+
+    let cypher = "MATCH (n) WITH DISTINCT n.city AS city ORDER BY city SKIP 1 LIMIT 2 RETURN city";
+    assert_eq!(parse_cypher(cypher).to_string(), cypher);
+
+    // The query goes on with a MATCH over the rows the WITH passes on
+    let cypher = "MATCH (n:Person) WITH n ORDER BY n.age DESC LIMIT 10 \
+                  OPTIONAL MATCH (n)-[:KNOWS]->(m) RETURN m.name";
+    let stmt = parse_cypher(cypher);
+    let CypherStatement::Match {
+        r#return: None,
+        next: Some(next),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WITH ... MATCH, got {stmt:?}");
+    };
+    assert_eq!(
+        next.to_string(),
+        "OPTIONAL MATCH (n)-[:KNOWS]->(m) RETURN m.name"
+    );
+    assert_eq!(stmt.to_string(), cypher);
+
+    for (cypher, error) in [
+        (
+            "MATCH (n) WITH n",
+            "Expected: RETURN or MATCH after WITH, found: EOF",
+        ),
+        (
+            "MATCH (n) WITH n LIMIT 1 LIMIT 2 RETURN n",
+            "Duplicate LIMIT in WITH at Line: 1, Column: 26",
+        ),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!("sql parser error: {error}"),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parse_where_of_each_reading_clause() {
+    // Each WHERE belongs to the MATCH or OPTIONAL MATCH right before it
+    let cypher = "MATCH (a:Person) WHERE a.age > 30 \
+                  OPTIONAL MATCH (a)-[:KNOWS]->(b) WHERE b.city = 'Paris' \
+                  MATCH (a)-[:WORKS_AT]->(c) RETURN a.name, b.name";
+    let stmt = parse_cypher(cypher);
+    let CypherStatement::Match {
+        optional: false,
+        r#where: Some(first),
+        r#return: None,
+        next: Some(second),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... OPTIONAL MATCH, got {stmt:?}");
+    };
+    assert_eq!(first.to_string(), "a.age > 30");
+    let CypherStatement::Match {
+        optional: true,
+        r#where: Some(second_where),
+        next: Some(third),
+        ..
+    } = second.as_ref()
+    else {
+        panic!("expected OPTIONAL MATCH ... MATCH, got {second:?}");
+    };
+    assert_eq!(second_where.to_string(), "b.city = 'Paris'");
+    let CypherStatement::Match {
+        optional: false,
+        r#where: None,
+        r#return: Some(_),
+        next: None,
+        ..
+    } = third.as_ref()
+    else {
+        panic!("expected MATCH ... RETURN, got {third:?}");
+    };
+    assert_eq!(stmt.to_string(), cypher);
+
+    // Each arm of a UNION returns after its last clause
+    let cypher = "MATCH (a) RETURN a.name AS name \
+                  UNION MATCH (a) OPTIONAL MATCH (a)-[:KNOWS]->(b) RETURN b.name AS name";
+    assert_eq!(parse_cypher(cypher).to_string(), cypher);
+    assert_eq!(
+        try_parse_cypher("MATCH (a) RETURN a UNION MATCH (a) OPTIONAL MATCH (a)-->(b)")
+            .unwrap_err()
+            .to_string(),
+        "sql parser error: Expected: a MATCH ... RETURN query on each side of UNION, \
+         found: MATCH (a) OPTIONAL MATCH (a)-[]->(b)"
+    );
+}
+
+#[test]
+fn parse_xor_between_or_and_and() {
+    let stmt = parse_cypher("MATCH (n) WHERE n.a = 1 OR n.b = 2 XOR n.c = 3 AND n.d = 4 RETURN n");
+    let CypherStatement::Match {
+        r#where: Some(Expr::BinaryOp { op, right, .. }),
+        ..
+    } = &stmt
+    else {
+        panic!("expected a WHERE clause, got {stmt:?}");
+    };
+    assert_eq!(*op, BinaryOperator::Or);
+    assert_eq!(right.to_string(), "n.b = 2 XOR n.c = 3 AND n.d = 4");
+    let Expr::BinaryOp { op, right, .. } = right.as_ref() else {
+        panic!("expected XOR, got {right:?}");
+    };
+    assert_eq!(*op, BinaryOperator::Xor);
+    assert_eq!(right.to_string(), "n.c = 3 AND n.d = 4");
+}
+
+#[test]
+fn parse_query_without_match() {
+    let stmt = parse_cypher("RETURN 1 + 1 AS two");
+    let CypherStatement::Match {
+        patterns,
+        with: None,
+        r#return: Some(ret),
+        ..
+    } = &stmt
+    else {
+        panic!("expected a RETURN without MATCH, got {stmt:?}");
+    };
+    assert!(patterns.is_empty());
+    assert_eq!(ret.items[0].to_string(), "1 + 1 AS two");
+
+    for cypher in [
+        "RETURN $x AS echoed",
+        "RETURN DISTINCT 'a' AS a ORDER BY a LIMIT 1",
+        "WITH 2 AS a WHERE a > 1 RETURN a",
+        "WITH 2 AS a MATCH (n {x: a}) RETURN n",
+        "RETURN 1 AS x UNION ALL RETURN 2 AS x",
+    ] {
+        assert_eq!(parse_cypher(cypher).to_string(), cypher);
+    }
+
+    for (cypher, error) in [
+        ("RETURN", "Expected: an expression, found: EOF"),
+        ("WITH 1 AS a", "Expected: RETURN or MATCH after WITH, found: EOF"),
+        (
+            "REMOVE n.x",
+            "Expected: MATCH, CREATE, MERGE, SHOW, UNWIND, WITH, or RETURN, found: REMOVE at Line: 1, Column: 1",
+        ),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!("sql parser error: {error}"),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parse_property_access_on_expressions() {
+    let stmt = parse_cypher(
+        "MATCH (n:Person) WHERE properties(n).name = 'x' AND head(collect(n)).id = 1 \
+         AND (n).age > 3 AND {a: 1}.a = 1 RETURN properties(n).address.city",
+    );
+    let CypherStatement::Match {
+        r#where: Some(condition),
+        r#return: Some(return_clause),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE ... RETURN, got {stmt:?}");
+    };
+    let mut conjuncts = vec![];
+    let mut pending = vec![condition];
+    while let Some(expr) = pending.pop() {
+        match expr {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And,
+                right,
+            } => pending.extend([left.as_ref(), right.as_ref()]),
+            Expr::BinaryOp { left, .. } => conjuncts.push(left.as_ref()),
+            _ => panic!("expected a comparison, got {expr:?}"),
+        }
+    }
+    assert_eq!(conjuncts.len(), 4);
+    for access in conjuncts {
+        let Expr::CompoundFieldAccess { access_chain, .. } = access else {
+            panic!("expected a field access, got {access:?}");
+        };
+        assert_eq!(access_chain.len(), 1, "{access}");
+    }
+    let [SelectItem::UnnamedExpr(Expr::CompoundFieldAccess { access_chain, .. })] =
+        return_clause.items.as_slice()
+    else {
+        panic!("expected a field access, got {return_clause}");
+    };
+    assert_eq!(access_chain.len(), 2);
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    // A property of the properties of a variable is a property of the variable
+    let summary =
+        parse_cypher("MATCH (n:Person) WHERE properties(n).age > 3 RETURN n.name").access_summary();
+    assert_eq!(
+        summary.properties_read,
+        ["age", "name"]
+            .map(|name| PropertyAccess {
+                owner: AccessName::Named("Person".to_string()),
+                property: AccessName::Named(name.to_string()),
+            })
+            .into()
+    );
+}
+
+#[test]
+fn parse_keywords_as_names() {
+    // Clause keywords are keywords only in clause position
+    let stmt = parse_cypher("MATCH (skip:Skip) RETURN skip.limit");
+    let CypherStatement::Match {
+        patterns,
+        r#return: Some(return_clause),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... RETURN, got {stmt:?}");
+    };
+    assert_eq!(
+        patterns[0].elements[0]
+            .variable()
+            .map(|ident| ident.value.as_str()),
+        Some("skip")
+    );
+    assert_eq!(
+        return_clause.items,
+        [SelectItem::UnnamedExpr(Expr::CompoundIdentifier(vec![
+            Ident::new("skip"),
+            Ident::new("limit"),
+        ]))]
+    );
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    for cypher in [
+        "MATCH (detach)-[optional:REL]->(merge) DETACH DELETE detach",
+        "MATCH (return) RETURN return SKIP 1",
+        "MATCH (node:Node) WHERE node.properties = 1 RETURN node.match",
+        "MATCH (interval) WHERE interval.trim > 1 RETURN interval ORDER BY interval.order",
+        "MATCH (n) RETURN n.exists, n.case, n.null",
+        "MERGE (limit:Limit) ON CREATE SET limit.skip = 1",
+    ] {
+        let stmt = parse_cypher(cypher);
+        assert_eq!(stmt.to_string(), cypher);
+    }
+
+    // Words that start Cypher expressions need backticks to name a variable
+    assert_eq!(
+        try_parse_cypher("MATCH (case) RETURN 1")
+            .unwrap_err()
+            .to_string(),
+        "sql parser error: Expected: a variable name, found: case at Line: 1, Column: 8"
+    );
+    assert!(try_parse_cypher("MATCH (`case`) RETURN `case`").is_ok());
+}
+
+#[test]
+fn parse_type_predicates() {
+    let stmt = parse_cypher(
+        "MATCH (n) WHERE n.age IS :: INTEGER AND n.name IS NOT :: STRING \
+         RETURN n.tags IS :: LIST<LIST<STRING>> AS nested",
+    );
+    let CypherStatement::Match {
+        r#where: Some(Expr::BinaryOp { left, right, .. }),
+        r#return: Some(return_clause),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE ... RETURN, got {stmt:?}");
+    };
+    assert_eq!(
+        **left,
+        Expr::Cypher(Box::new(CypherExpr::TypePredicate {
+            expr: Expr::CompoundIdentifier(vec![Ident::new("n"), Ident::new("age")]),
+            negated: false,
+            data_type: CypherType::Integer,
+        }))
+    );
+    assert!(matches!(
+        right.as_ref(),
+        Expr::Cypher(predicate) if matches!(
+            predicate.as_ref(),
+            CypherExpr::TypePredicate { negated: true, data_type: CypherType::String, .. }
+        )
+    ));
+    assert_eq!(
+        return_clause.to_string(),
+        "RETURN n.tags IS :: LIST<LIST<STRING>> AS nested"
+    );
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    // Synonyms are written with the canonical type names
+    assert_eq!(
+        parse_cypher("MATCH (n) WHERE n.a IS :: bool OR n.b IS :: SIGNED INTEGER RETURN n")
+            .to_string(),
+        "MATCH (n) WHERE n.a IS :: BOOLEAN OR n.b IS :: INTEGER RETURN n"
+    );
+
+    for (cypher, error) in [
+        (
+            "MATCH (n) WHERE n.a IS :: NUMBER RETURN n",
+            "Expected: a type name, found: NUMBER at Line: 1, Column: 27",
+        ),
+        (
+            "MATCH (n) WHERE n.a IS :: LIST<INTEGER RETURN n",
+            "Expected: > after the type of the list elements, found: RETURN at Line: 1, Column: 40",
+        ),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!("sql parser error: {error}"),
+            "{cypher}"
+        );
+    }
+}
+
+#[test]
+fn parse_show() {
+    let stmt =
+        parse_cypher("SHOW DATABASES YIELD name, currentStatus AS status WHERE name = 'neo4j'");
+    let CypherStatement::Show {
+        what,
+        yield_items,
+        r#where,
+    } = &stmt
+    else {
+        panic!("expected SHOW, got {stmt:?}");
+    };
+    assert_eq!(*what, ShowTarget::Databases);
+    assert_eq!(yield_items.len(), 2);
+    assert!(r#where.is_some());
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    for (cypher, canonical) in [
+        ("SHOW PROCEDURES", "SHOW PROCEDURES"),
+        ("show function", "SHOW FUNCTIONS"),
+        ("SHOW INDEX YIELD *", "SHOW INDEXES YIELD *"),
+        (
+            "SHOW CONSTRAINTS WHERE type = 'UNIQUENESS'",
+            "SHOW CONSTRAINTS WHERE type = 'UNIQUENESS'",
+        ),
+        ("SHOW TRANSACTIONS", "SHOW TRANSACTIONS"),
+    ] {
+        assert_eq!(parse_cypher(cypher).to_string(), canonical);
+    }
+    assert_eq!(
+        parse_cypher("SHOW DATABASES").access_summary(),
+        AccessSummary::default()
+    );
+
+    assert_eq!(
+        try_parse_cypher("SHOW USERS").unwrap_err().to_string(),
+        "sql parser error: Expected: DATABASES, PROCEDURES, FUNCTIONS, INDEXES, CONSTRAINTS, \
+         or TRANSACTIONS, found: USERS at Line: 1, Column: 6"
+    );
+}
+
+#[test]
+fn parse_in_list_expression() {
+    let stmt = parse_cypher(
+        "MATCH (n) WHERE 'Admin' IN labels(n) AND NOT 'email' IN keys(n) RETURN n.id IN $ids",
+    );
+    let CypherStatement::Match {
+        r#where: Some(Expr::BinaryOp { left, right, .. }),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE ... RETURN, got {stmt:?}");
+    };
+    let Expr::Cypher(membership) = left.as_ref() else {
+        panic!("expected a list membership test, got {left:?}");
+    };
+    let CypherExpr::In { expr, list } = membership.as_ref() else {
+        panic!("expected a list membership test, got {membership:?}");
+    };
+    assert_eq!(
+        *expr,
+        Expr::value(Value::SingleQuotedString("Admin".to_string()))
+    );
+    assert!(matches!(list, Expr::Function(function) if function.name.to_string() == "labels"));
+    assert!(matches!(
+        right.as_ref(),
+        Expr::UnaryOp { op: UnaryOperator::Not, expr }
+            if matches!(expr.as_ref(), Expr::Cypher(membership)
+                if matches!(membership.as_ref(), CypherExpr::In { .. }))
+    ));
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    // List literals and parenthesized lists keep their own expressions
+    assert!(matches!(
+        parse_cypher("MATCH (n) WHERE n.age IN [1, 2] RETURN n"),
+        CypherStatement::Match {
+            r#where: Some(Expr::InList { .. }),
+            ..
+        }
+    ));
+    assert!(matches!(
+        parse_cypher("MATCH (n) WHERE n.age IN (1, 2) RETURN n"),
+        CypherStatement::Match {
+            r#where: Some(Expr::InList { .. }),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn parse_named_paths() {
+    let stmt = parse_cypher("MATCH p = (a)-[:KNOWS]->(b), (c) RETURN length(p)");
+    let CypherStatement::Match { patterns, .. } = &stmt else {
+        panic!("expected MATCH, got {stmt:?}");
+    };
+    assert_eq!(
+        patterns[0],
+        Pattern::named_path(
+            "p",
+            [
+                PatternElement::node("a"),
+                PatternElement::anonymous_rel().rtype("KNOWS").right(),
+                PatternElement::node("b"),
+            ]
+        )
+    );
+    assert_eq!(patterns[1].variable, None);
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+}
+
+#[test]
+fn parse_relationship_length_and_properties_in_either_order() {
+    let properties = "{strength: 5, since: 2020}";
+    for length in ["*", "*2", "*1..3", "*..3", "*2..", "*1 .. 3"] {
+        let canonical = format!("MATCH (a)-[r:KNOWS{length} {properties}]->(b) RETURN b");
+        let expected = parse_cypher(&canonical);
+        for brackets in [
+            format!("[r:KNOWS{length} {properties}]"),
+            format!("[r:KNOWS {properties} {length}]"),
+            format!("[r:KNOWS {properties}{length}]"),
+        ] {
+            let cypher = format!("MATCH (a)-{brackets}->(b) RETURN b");
+            let stmt = parse_cypher(&cypher);
+            assert_eq!(stmt, expected, "{cypher}");
+            assert_eq!(
+                stmt.to_string(),
+                canonical.replace("1 .. 3", "1..3"),
+                "{cypher}"
+            );
+        }
+    }
+
+    // A variable may be followed directly by a length or properties
+    for cypher in [
+        "MATCH (a)-[r*2]->(b) RETURN r",
+        "MATCH (a)-[r {since: 2020}]->(b) RETURN r",
+        "MATCH (a)-[r*..2 {since: 2020}]->(b) RETURN r",
+    ] {
+        assert_eq!(parse_cypher(cypher).to_string(), cypher);
+    }
+
+    for (cypher, error) in [
+        (
+            "MATCH (a)-[r*1*2]->(b) RETURN b",
+            "Relationship length specified twice at Line: 1, Column: 15",
+        ),
+        (
+            "MATCH (a)-[r {a: 1} *2 {b: 2}]->(b) RETURN b",
+            "Relationship properties specified twice at Line: 1, Column: 24",
+        ),
+        (
+            "MATCH (a)-[r*2:KNOWS]->(b) RETURN b",
+            "Expected: ], found: : at Line: 1, Column: 15",
+        ),
+    ] {
+        assert_eq!(
+            try_parse_cypher(cypher).unwrap_err().to_string(),
+            format!("sql parser error: {error}"),
+            "{cypher}"
+        );
+    }
+}
+
+/// Statements covering every [`CypherStatement`] variant and every form of
+/// [`PatternElement`], [`RelationshipLength`] and [`SetTarget`], each with
+/// the name of its JSON fixture
+fn representative_statements() -> Vec<(&'static str, CypherStatement)> {
+    let parsed = [
+        (
+            "match",
+            "OPTIONAL MATCH p = (a:Person:Admin {name: 'Ada'})-[r:KNOWS|LIKES*2]->(b)\
+             <-[:FOLLOWS {since: 2020}]-(:Bot)<-[*]->()-[:NEAR]-(c) \
+             WHERE a.age > 30 AND b:Admin AND 'x' IN labels(c) \
+             WITH DISTINCT a, b ORDER BY a.name SKIP 1 LIMIT 10 WHERE b.age IS NOT NULL \
+             RETURN a.name AS name, count(*) ORDER BY name DESC LIMIT $limit",
+        ),
+        (
+            "match_expressions",
+            "MATCH (n) WHERE n.age IS NOT :: INTEGER \
+             RETURN [(n)-[:KNOWS]->(f) WHERE f.age > 30 | f.name] AS friends",
+        ),
+        (
+            "create",
+            "CREATE (a:Person {name: 'Ada', tags: ['x'], score: 1.5}), (a)-[:KNOWS]->(b:Person)",
+        ),
+        (
+            "match_create",
+            "MATCH (a:Person), (b:Person) WHERE a.id = 1 AND b.id = 2 \
+             CREATE (a)-[:KNOWS {since: $since}]->(b)",
+        ),
+        (
+            "merge",
+            "MERGE (n:Person {id: 1}) ON CREATE SET n.created = true, n = {name: 'Ada'} \
+             ON MATCH SET n:Seen = true",
+        ),
+        (
+            "delete",
+            "MATCH (n:Person)-[r]-() WHERE n.name = 'Ada' DETACH DELETE n, r",
+        ),
+        (
+            "union",
+            "MATCH (n:A) RETURN n.name AS name UNION ALL MATCH (n:B) RETURN n.name AS name \
+             ORDER BY name",
+        ),
+        (
+            "show",
+            "SHOW INDEXES YIELD name, type AS kind WHERE kind = 'RANGE'",
+        ),
+        (
+            "relationship_lengths",
+            "MATCH (a)-[:KNOWS*1..3]->(b)-[:KNOWS*..2]->(c)-[:KNOWS*2..]->(d)",
+        ),
+        (
+            "unwind",
+            "UNWIND range(1, 10, 2) AS i CREATE (:Item {n: i})",
+        ),
+    ];
+    parsed
+        .into_iter()
+        .map(|(name, cypher)| (name, parse_cypher(cypher)))
+        .collect()
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn serde_snapshots() {
+    // Regenerate with UPDATE_CYPHER_SNAPSHOTS=1 after a deliberate change to
+    // the shape of the AST, and review the changes to the fixtures
+    let fixtures =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cypher_ast");
+    let update = std::env::var_os("UPDATE_CYPHER_SNAPSHOTS").is_some();
+    for (name, stmt) in representative_statements() {
+        let path = fixtures.join(format!("{name}.json"));
+        let json = serde_json::to_string_pretty(&stmt).unwrap() + "\n";
+        if update {
+            std::fs::write(&path, &json).unwrap();
+            continue;
+        }
+        let fixture =
+            std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        pretty_assertions::assert_eq!(json, fixture, "serialization of {name} changed");
+        let restored: CypherStatement = serde_json::from_str(&fixture).unwrap();
+        assert_eq!(restored, stmt, "{name}");
+    }
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn serde_missing_fields_default() {
+    for (json, expected) in [
+        (
+            r#"{"Match": {"patterns": [{"elements": [{"Node": {"variable": null}}]}]}}"#,
+            "MATCH ()",
+        ),
+        (
+            r#"{"Merge": {"patterns": [{"elements": [{"Node": {"variable": null}}]}]}}"#,
+            "MERGE ()",
+        ),
+        (
+            r#"{"Create": {"patterns": [{"elements": [{"Node": {"variable": null}}]}]}}"#,
+            "CREATE ()",
+        ),
+        (r#"{"Show": {"what": "Indexes"}}"#, "SHOW INDEXES"),
+    ] {
+        let stmt: CypherStatement = serde_json::from_str(json).unwrap();
+        assert_eq!(stmt.to_string(), expected);
+    }
+}
+
+#[test]
+fn ord_and_hash_consistent_with_eq() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::{BTreeSet, HashSet};
+    use std::hash::{Hash, Hasher};
+
+    let hash = |stmt: &CypherStatement| {
+        let mut hasher = DefaultHasher::new();
+        stmt.hash(&mut hasher);
+        hasher.finish()
+    };
+    let statements: Vec<_> = representative_statements()
+        .into_iter()
+        .map(|(_, stmt)| stmt)
+        .collect();
+    // Parsed again after a line break, the statements differ in spans only
+    let reparsed: Vec<_> = statements
+        .iter()
+        .filter_map(|stmt| try_parse_cypher(&format!("\n  {stmt}")).ok())
+        .collect();
+    assert!(reparsed.len() >= statements.len() - 1);
+
+    for a in statements.iter().chain(&reparsed) {
+        for b in statements.iter().chain(&reparsed) {
+            assert_eq!(a == b, a.cmp(b).is_eq(), "{a} and {b}");
+            assert_eq!(a.partial_cmp(b), Some(a.cmp(b)), "{a} and {b}");
+            assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{a} and {b}");
+            if a == b {
+                assert_eq!(hash(a), hash(b), "{a}");
+            }
+        }
+    }
+    let all = || statements.iter().chain(&reparsed).cloned();
+    assert_eq!(all().collect::<BTreeSet<_>>().len(), statements.len());
+    assert_eq!(all().collect::<HashSet<_>>().len(), statements.len());
+}
+
+#[test]
+fn parse_unicode_whitespace_and_names() {
+    // Non-breaking, ideographic and zero width spaces, a byte order mark and
+    // Windows line breaks separate tokens
+    let stmt = parse_cypher(
+        "\u{FEFF}MATCH\u{A0}(n:Город\u{A0}{größe:\u{3000}1})\r\n\
+         WHERE n.größe\u{200B}> 0\r\nRETURN\u{A0}n.größe AS straße",
+    );
+    let cypher = "MATCH (n:Город {größe: 1}) WHERE n.größe > 0 RETURN n.größe AS straße";
+    assert_eq!(stmt.to_string(), cypher);
+    assert_eq!(stmt, parse_cypher(cypher));
+    assert_eq!(stmt.to_canonical_string(), cypher);
+
+    let CypherStatement::Match { patterns, .. } = &stmt else {
+        panic!("expected MATCH, got {stmt:?}");
+    };
+    assert_eq!(
+        patterns[0].elements[0].labels_or_types(),
+        [Ident::new("Город")]
+    );
+}
+
+#[test]
+fn parse_empty_input() {
+    for input in ["", "   \n\t", ";", "-- nothing to see\n/* here */"] {
+        assert_eq!(
+            try_parse_cypher(input),
+            Err(ParserError::ParserError(
+                "empty Cypher statement".to_string()
+            )),
+            "{input:?}"
+        );
+        let statements = CypherStatementIterator::new(input.split_inclusive('\n'));
+        assert_eq!(statements.count(), 0, "{input:?}");
+    }
+    // Leading separators are skipped
+    assert_eq!(
+        parse_cypher(";; MATCH (n) RETURN n").to_string(),
+        "MATCH (n) RETURN n"
+    );
+}
+
+#[test]
+fn parse_cypher_script() {
+    let script = "MATCH (n) WHERE n.name = 'a;b' RETURN n.`x;y`;\n\
+                  -- a comment; not a statement\n\
+                  /* ; */ CREATE (:Person {name: \"it\\\";s\"});;\n\
+                  MATCH (a)-->(b)<--(c) RETURN c;\n\
+                  MATCH (n RETURN n;\n\
+                  MATCH (n) RETURN n) RETURN m;\n\
+                  MATCH (n) RETURN n";
+    let mut statements = CypherStatementIterator::new(script.split_inclusive('\n'));
+    let mut next = || {
+        let statement = statements
+            .next()
+            .map(|statement| statement.map(|s| s.to_string()));
+        (statement, statements.statement_text().to_string())
+    };
+    assert_eq!(
+        next(),
+        (
+            Some(Ok(
+                "MATCH (n) WHERE n.name = 'a;b' RETURN n.`x;y`".to_string()
+            )),
+            "MATCH (n) WHERE n.name = 'a;b' RETURN n.`x;y`".to_string()
+        )
+    );
+    assert_eq!(
+        next().0,
+        Some(Ok(r#"CREATE (:Person {name: "it\";s"})"#.to_string()))
+    );
+    // `--` starts a comment only before a space
+    assert_eq!(
+        next().0,
+        Some(Ok("MATCH (a)-[]->(b)<-[]-(c) RETURN c".to_string()))
+    );
+    // Locations are those in the script
+    assert_eq!(
+        next(),
+        (
+            Some(Err(ParserError::ParserError(
+                "Expected: ), found: RETURN at Line: 5, Column: 10".to_string()
+            ))),
+            "MATCH (n RETURN n".to_string()
+        )
+    );
+    assert_eq!(
+        next().0,
+        Some(Err(ParserError::ParserError(
+            "Expected: end of statement, found: ) at Line: 6, Column: 19".to_string()
+        )))
+    );
+    assert_eq!(next().0, Some(Ok("MATCH (n) RETURN n".to_string())));
+    assert_eq!(next().0, None);
+}
+
+#[test]
+fn parse_cypher_script_directives() {
+    use sqlparser::parser::CypherScriptItem;
+
+    let script = ":param minAge => 21;\n\
+                  :PARAM person => {name: 'Ada', tags: ['a;b']}\n\
+                  -- before a directive\n\
+                  :use movies\n\
+                  :begin\n\
+                  MATCH (n) WHERE n.age >= $minAge\n\
+                  RETURN n;\n\
+                  :commit\n\
+                  :play https://guides.neo4j.com/intro;\n\
+                  :param 1 => 2\n\
+                  :param limit 10\n\
+                  :rollback";
+    let mut items = CypherStatementIterator::new(script.split_inclusive('\n'));
+    let mut next = || {
+        let item = items.next_item().map(|item| {
+            item.map(|item| match item {
+                CypherScriptItem::Statement(statement) => statement.to_string(),
+                CypherScriptItem::Directive(directive) => format!("directive {directive}"),
+            })
+        });
+        (item, items.statement_text().to_string())
+    };
+    assert_eq!(
+        next(),
+        (
+            Some(Ok("directive :param minAge => 21".to_string())),
+            ":param minAge => 21".to_string()
+        )
+    );
+    // Semicolons in the line of a directive do not end it
+    assert_eq!(
+        next(),
+        (
+            Some(Ok(
+                "directive :param person => {name: 'Ada', tags: ['a;b']}".to_string()
+            )),
+            ":PARAM person => {name: 'Ada', tags: ['a;b']}".to_string()
+        )
+    );
+    // Comments before a directive are skipped
+    assert_eq!(
+        next(),
+        (
+            Some(Ok("directive :use movies".to_string())),
+            ":use movies".to_string()
+        )
+    );
+    assert_eq!(next().0, Some(Ok("directive :begin".to_string())));
+    assert_eq!(
+        next().0,
+        Some(Ok("MATCH (n) WHERE n.age >= $minAge RETURN n".to_string()))
+    );
+    assert_eq!(next().0, Some(Ok("directive :commit".to_string())));
+    assert_eq!(
+        next().0,
+        Some(Ok(
+            "directive :play https://guides.neo4j.com/intro".to_string()
+        ))
+    );
+    // Locations are those in the script
+    assert_eq!(
+        next().0,
+        Some(Err(ParserError::ParserError(
+            "Expected: identifier, found: 1 at Line: 10, Column: 8".to_string()
+        )))
+    );
+    assert_eq!(
+        next().0,
+        Some(Err(ParserError::ParserError(
+            "Expected: =>, found: 10 at Line: 11, Column: 14".to_string()
+        )))
+    );
+    // The last line need not end
+    assert_eq!(next().0, Some(Ok("directive :rollback".to_string())));
+    assert_eq!(next().0, None);
+
+    // Iterating skips the directives but not their errors, and a `:` after
+    // the start of a statement is not one
+    let statements = CypherStatementIterator::new(script.split_inclusive('\n'))
+        .map(|statement| statement.map(|s| s.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(statements.len(), 3);
+    assert_eq!(
+        statements[0],
+        Ok("MATCH (n) WHERE n.age >= $minAge RETURN n".to_string())
+    );
+    assert_eq!(
+        CypherStatementIterator::new(["MATCH (n\n", ":Person) RETURN n"].into_iter())
+            .map(|statement| statement.map(|s| s.to_string()))
+            .collect::<Vec<_>>(),
+        [Ok("MATCH (n:Person) RETURN n".to_string())]
+    );
+}
+
+#[test]
+fn parse_cypher_script_streams_statements() {
+    // The chunks read are counted to show that statements are parsed as
+    // soon as they are read, without reading the rest of the script
+    let read = std::cell::Cell::new(0);
+    let lines = (0..100_000).map(|i| {
+        read.set(read.get() + 1);
+        format!("CREATE (:Item {{id: {i}}});\n")
+    });
+    let mut statements = CypherStatementIterator::new(lines);
+    for i in 0..100_000 {
+        let statement = statements.next().unwrap().unwrap();
+        if i % 10_000 == 0 {
+            assert_eq!(read.get(), i + 1);
+            assert_eq!(statement.to_string(), format!("CREATE (:Item {{id: {i}}})"));
+        }
+    }
+    assert!(statements.next().is_none());
+}
+
+#[test]
+fn classify_tokens_for_highlighting() {
+    use sqlparser::parser::{classify_cypher_tokens, TokenClass::*};
+
+    let input = "MATCH (n:Person {name: $name})-[r:KNOWS|LIKES*1..2]->(order) \
+                 WHERE n:Admin|Owner AND n.age >= 21 -- adults\n\
+                 RETURN count(r) AS limit, [x IN labels(n) | x] AS `all`, 'Ada' + \"!\";";
+    let tokens = classify_cypher_tokens(input).unwrap();
+    let mut end = 0;
+    for token in &tokens {
+        assert_eq!(token.span.start, end, "{tokens:?}");
+        end = token.span.end;
+    }
+    assert_eq!(end, input.len());
+
+    let classes: Vec<_> = tokens
+        .iter()
+        .filter(|token| token.class != Whitespace)
+        .map(|token| (&input[token.span.clone()], token.class))
+        .collect();
+    assert_eq!(
+        classes,
+        [
+            ("MATCH", Keyword),
+            ("(", Punctuation),
+            ("n", Variable),
+            (":", Punctuation),
+            ("Person", Label),
+            ("{", Punctuation),
+            ("name", Property),
+            (":", Punctuation),
+            ("$name", Parameter),
+            ("}", Punctuation),
+            (")", Punctuation),
+            ("-", Operator),
+            ("[", Punctuation),
+            ("r", Variable),
+            (":", Punctuation),
+            ("KNOWS", RelationshipType),
+            ("|", Operator),
+            ("LIKES", RelationshipType),
+            ("*", Operator),
+            ("1", Number),
+            (".", Punctuation),
+            (".", Punctuation),
+            ("2", Number),
+            ("]", Punctuation),
+            ("->", Operator),
+            ("(", Punctuation),
+            ("order", Variable),
+            (")", Punctuation),
+            ("WHERE", Keyword),
+            ("n", Variable),
+            (":", Punctuation),
+            ("Admin", Label),
+            ("|", Operator),
+            ("Owner", Label),
+            ("AND", Keyword),
+            ("n", Variable),
+            (".", Punctuation),
+            ("age", Property),
+            (">=", Operator),
+            ("21", Number),
+            ("-- adults\n", Comment),
+            ("RETURN", Keyword),
+            ("count", Function),
+            ("(", Punctuation),
+            ("r", Variable),
+            (")", Punctuation),
+            ("AS", Keyword),
+            ("limit", Variable),
+            (",", Punctuation),
+            ("[", Punctuation),
+            ("x", Variable),
+            ("IN", Keyword),
+            ("labels", Function),
+            ("(", Punctuation),
+            ("n", Variable),
+            (")", Punctuation),
+            ("|", Operator),
+            ("x", Variable),
+            ("]", Punctuation),
+            ("AS", Keyword),
+            ("`all`", Variable),
+            (",", Punctuation),
+            ("'Ada'", String),
+            ("+", Operator),
+            ("\"!\"", String),
+            (";", Punctuation),
+        ]
+    );
+
+    // Characters the tokenizer skips are whitespace, and byte offsets count
+    // the bytes of characters outside ASCII
+    let input = "\u{FEFF}MATCH (é:Café) RETURN é\u{200B}";
+    let tokens = classify_cypher_tokens(input).unwrap();
+    assert_eq!(tokens[0].class, Whitespace);
+    assert_eq!(&input[tokens[0].span.clone()], "\u{FEFF}");
+    let label = tokens.iter().find(|token| token.class == Label).unwrap();
+    assert_eq!(&input[label.span.clone()], "Café");
+    assert_eq!(tokens.last().unwrap().span.end, input.len());
+
+    // Incomplete queries are classified, only tokenizer errors fail
+    assert!(classify_cypher_tokens("MATCH (n:").is_ok());
+    assert_eq!(classify_cypher_tokens("").unwrap(), []);
+    assert!(classify_cypher_tokens("RETURN 'open").is_err());
+}
+
+#[test]
+fn parse_recovering_from_errors() {
+    let recover = |cypher: &str| {
+        let (mut parser, error) = Parser::new(&CypherDialect).with_cypher_recovering(cypher);
+        let (statement, errors) = parser.parse_cypher_statement_recovering();
+        let errors: Vec<String> = error.into_iter().chain(errors).map(|e| e.to_string()).collect();
+        (statement, errors)
+    };
+
+    for (cypher, recovered, error) in [
+        ("MATCH (n:Per", "MATCH (n:Per)", "Expected: ), found: EOF"),
+        ("MATCH (n:Per RETURN n", "MATCH (n:Per) RETURN n", "Expected: ), found: RETURN"),
+        ("MATCH (n:Per RETURN", "MATCH (n:Per) RETURN ?", "Expected: ), found: RETURN"),
+        ("MATCH (n)-[:KNOWS", "MATCH (n)-[:KNOWS]-()", "Expected: ], found: EOF"),
+        ("MATCH (n {name: ", "MATCH (n {name: ?})", "Expected: an expression"),
+        ("MATCH (n) RETURN", "MATCH (n) RETURN ?", "Expected: an expression, found: EOF"),
+        ("MATCH (n) RETURN n.", "MATCH (n) RETURN n.?", "Expected: an expression"),
+        ("MATCH (n) REMOVE n.x RETURN n", "MATCH (n) RETURN n", "found: REMOVE"),
+        (
+            "MATCH (n) WHERE n.name = 'Ad",
+            "MATCH (n) WHERE n.name = ?",
+            "Unterminated string literal at Line: 1, Column: 26",
+        ),
+    ] {
+        let (statement, errors) = recover(cypher);
+        let Some(CypherStatement::Recovered { statement }) = statement else {
+            panic!("{cypher}: expected a recovered statement, found {statement:?}");
+        };
+        assert_eq!(statement.to_string(), recovered, "{cypher}");
+        assert!(errors[0].contains(error), "{cypher}: {errors:?}");
+    }
+
+    // A statement without errors is parsed as it is, up to its `;`
+    let mut parser = Parser::new(&CypherDialect)
+        .try_with_sql("MATCH (n) RETURN n; MATCH (m")
+        .unwrap();
+    let (statement, errors) = parser.parse_cypher_statement_recovering();
+    assert_eq!(statement, Some(parse_cypher("MATCH (n) RETURN n")));
+    assert!(errors.is_empty());
+    let (statement, errors) = parser.parse_cypher_statement_recovering();
+    assert!(matches!(statement, Some(CypherStatement::Recovered { .. })));
+    assert_eq!(errors.len(), 1);
+
+    let (statement, errors) = recover("foo bar");
+    assert_eq!(statement, None);
+    assert!(errors[0].contains("found: foo"), "{errors:?}");
+
+    // The placeholders are no query to run
+    let (Some(statement), _) = recover("MATCH (n) WHERE n.name = 'Ad") else {
+        panic!("expected a recovered statement");
+    };
+    let error = CypherToSqlTransformer::new().transform(&statement).unwrap_err();
+    assert!(error.to_string().contains("was recovered from errors parsing it"), "{error}");
+}
+
+#[test]
+fn parse_string_predicates() {
+    let stmt = parse_cypher(
+        "MATCH (n) WHERE n.name STARTS WITH 'A' AND NOT n.name ENDS WITH $suffix \
+         RETURN n.name CONTAINS 'd' + 'a' AS has",
+    );
+    let CypherStatement::Match {
+        r#where: Some(Expr::BinaryOp { left, op: BinaryOperator::And, right }),
+        r#return: Some(return_clause),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE ... AND ... RETURN, got {stmt:?}");
+    };
+    assert_eq!(
+        **left,
+        Expr::Cypher(Box::new(CypherExpr::StringPredicate {
+            expr: Expr::CompoundIdentifier(vec![Ident::new("n"), Ident::new("name")]),
+            operator: StringOperator::StartsWith,
+            pattern: Expr::value(Value::SingleQuotedString("A".to_string())),
+        }))
+    );
+    // NOT applies to the whole predicate, and `+` to its pattern
+    assert!(matches!(
+        right.as_ref(),
+        Expr::UnaryOp { op: UnaryOperator::Not, expr }
+            if matches!(expr.as_ref(), Expr::Cypher(predicate)
+                if matches!(predicate.as_ref(),
+                    CypherExpr::StringPredicate { operator: StringOperator::EndsWith, .. }))
+    ));
+    assert_eq!(return_clause.to_string(), "RETURN n.name CONTAINS 'd' + 'a' AS has");
+    let SelectItem::ExprWithAlias { expr: Expr::Cypher(predicate), .. } = &return_clause.items[0]
+    else {
+        panic!("expected a string predicate, got {return_clause}");
+    };
+    assert!(matches!(
+        predicate.as_ref(),
+        CypherExpr::StringPredicate { operator: StringOperator::Contains, pattern, .. }
+            if matches!(pattern, Expr::BinaryOp { op: BinaryOperator::Plus, .. })
+    ));
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    // The operator words are reserved, but are still property keys
+    assert!(try_parse_cypher("MATCH (ends) RETURN ends").is_err());
+    let keys = "MATCH (n) RETURN n.starts, n.ends, n.contains";
+    assert_eq!(parse_cypher(keys).to_string(), keys);
+}
+
+#[test]
+fn parse_exists_function() {
+    let stmt = parse_cypher(
+        "MATCH (n) WHERE exists(n.email) AND NOT EXISTS((n)-[:KNOWS]->(:Person)) RETURN n",
+    );
+    let CypherStatement::Match {
+        r#where: Some(Expr::BinaryOp { left, op: BinaryOperator::And, right }),
+        ..
+    } = &stmt
+    else {
+        panic!("expected MATCH ... WHERE ... AND ..., got {stmt:?}");
+    };
+    // A property argument is a plain function call
+    let Expr::Function(function) = left.as_ref() else {
+        panic!("expected a function call, got {left:?}");
+    };
+    assert_eq!(function.to_string(), "exists(n.email)");
+    // A pattern argument is a pattern predicate, under the NOT
+    let Expr::UnaryOp { op: UnaryOperator::Not, expr } = right.as_ref() else {
+        panic!("expected NOT, got {right:?}");
+    };
+    let Expr::Function(function) = expr.as_ref() else {
+        panic!("expected a function call, got {expr:?}");
+    };
+    let FunctionArguments::List(list) = &function.args else {
+        panic!("expected an argument list, got {function}");
+    };
+    let [FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Cypher(arg)))] = list.args.as_slice()
+    else {
+        panic!("expected a single Cypher argument, got {function}");
+    };
+    let CypherExpr::PatternPredicate { pattern } = arg.as_ref() else {
+        panic!("expected a pattern predicate, got {arg:?}");
+    };
+    assert_eq!(pattern.to_string(), "(n)-[:KNOWS]->(:Person)");
+    assert_eq!(parse_cypher(&stmt.to_string()), stmt);
+
+    // A parenthesized expression is not a pattern
+    let stmt = parse_cypher("MATCH (n) RETURN exists((n.email))");
+    assert_eq!(stmt.to_string(), "MATCH (n) RETURN exists((n.email))");
+    assert!(try_parse_cypher("MATCH (n) RETURN exists((n)-[:KNOWS]->()").is_err());
+}
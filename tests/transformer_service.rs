@@ -0,0 +1,281 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+#![warn(clippy::all)]
+//! Tests of the responses of [`handle_request`], whose serialization is the
+//! contract of the services built on it
+
+use sqlparser::transformer::service::{
+    handle_request, ServiceError, ServiceErrorKind, ServiceResponse,
+};
+use sqlparser::transformer::{ParamBinding, TransformConfig, WarningKind};
+
+/// A query of each kind of error, with the configuration it fails under and
+/// its error
+fn errors() -> Vec<(String, TransformConfig, ServiceError)> {
+    let error = |kind, message: &str, location: Option<(u64, u64)>| ServiceError {
+        kind,
+        message: message.to_string(),
+        line: location.map(|(line, _)| line),
+        column: location.map(|(_, column)| column),
+    };
+    let default = TransformConfig::default();
+    vec![
+        (
+            " ; /* nothing */\n".to_string(),
+            default.clone(),
+            error(ServiceErrorKind::EmptyInput, "empty Cypher statement", None),
+        ),
+        (
+            "MATCH (n:Person\nRETURN n".to_string(),
+            default.clone(),
+            error(
+                ServiceErrorKind::Parse,
+                "Expected: ), found: RETURN",
+                Some((2, 1)),
+            ),
+        ),
+        (
+            "MATCH (n) WHERE n.name = 'Ad".to_string(),
+            default.clone(),
+            error(
+                ServiceErrorKind::Parse,
+                "Unterminated string literal",
+                Some((1, 26)),
+            ),
+        ),
+        // A request is a single statement
+        (
+            "MATCH (n) RETURN n; MATCH (m) RETURN m".to_string(),
+            default.clone(),
+            error(
+                ServiceErrorKind::Parse,
+                "Expected: end of statement, found: MATCH",
+                Some((1, 21)),
+            ),
+        ),
+        (
+            "MATCH (n) RETURN".to_string(),
+            default.clone(),
+            error(
+                ServiceErrorKind::Parse,
+                "Expected: an expression, found: EOF",
+                None,
+            ),
+        ),
+        (
+            format!("RETURN {}1{}", "(".repeat(100), ")".repeat(100)),
+            default.clone(),
+            error(
+                ServiceErrorKind::RecursionLimit,
+                "recursion limit exceeded",
+                None,
+            ),
+        ),
+        (
+            "MATCH (n:Person)\nRETURN m.name".to_string(),
+            default.clone(),
+            error(
+                ServiceErrorKind::Validation,
+                "variable `m` is not bound",
+                Some((2, 8)),
+            ),
+        ),
+        (
+            "MATCH (n:Person) RETURN n.name AS name UNION MATCH (c:City) RETURN c.name AS city"
+                .to_string(),
+            default.clone(),
+            error(
+                ServiceErrorKind::InvalidQuery,
+                "invalid Cypher query: All sub queries in a UNION must return the same columns, \
+                 expected (name) but found (city)",
+                None,
+            ),
+        ),
+        (
+            "MATCH (a:Person), (b:City) RETURN a.name, b.name".to_string(),
+            TransformConfig {
+                strict_cartesian: true,
+                ..default.clone()
+            },
+            error(
+                ServiceErrorKind::CartesianProduct,
+                "cartesian product of {a}, {b}",
+                None,
+            ),
+        ),
+        (
+            "MERGE (a)-[:KNOWS]->(b)".to_string(),
+            default,
+            error(
+                ServiceErrorKind::Unsupported,
+                "unsupported Cypher feature: relationship MERGE (a)-[:KNOWS]->(b)",
+                None,
+            ),
+        ),
+    ]
+}
+
+#[test]
+fn transformed_request() {
+    let response = handle_request(
+        "MATCH (a:Person), (b:City) WHERE a.name = $name RETURN a.name, b.name",
+        &TransformConfig::default(),
+    );
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.error, None);
+    assert_eq!(
+        response.sql.as_deref(),
+        Some(
+            "SELECT a.name, b.name FROM Person AS a, City AS b \
+             WHERE a.name = $name"
+        )
+    );
+    let warnings: Vec<WarningKind> = response
+        .warnings
+        .iter()
+        .map(|warning| warning.kind)
+        .collect();
+    assert_eq!(warnings, [WarningKind::CartesianProduct]);
+    assert_eq!(
+        response.parameters,
+        [ParamBinding {
+            name: "name".to_string(),
+            placeholder: "$name".to_string(),
+            index: None,
+            count: 1,
+        }]
+    );
+    let stats = response.stats.unwrap();
+    assert_eq!(stats.token_count, 26);
+    assert_eq!(stats.pattern_element_count, 2);
+}
+
+#[test]
+fn error_responses() {
+    for (query, config, error) in errors() {
+        let response = handle_request(&query, &config);
+        assert_eq!(
+            response,
+            ServiceResponse {
+                sql: None,
+                warnings: vec![],
+                parameters: vec![],
+                stats: None,
+                error: Some(error.clone()),
+            },
+            "{query}"
+        );
+        assert_eq!(response.status_code(), error.kind.status_code(), "{query}");
+    }
+
+    let status = |kind: ServiceErrorKind| kind.status_code();
+    assert_eq!(status(ServiceErrorKind::EmptyInput), 400);
+    assert_eq!(status(ServiceErrorKind::Parse), 400);
+    assert_eq!(status(ServiceErrorKind::RecursionLimit), 400);
+    assert_eq!(status(ServiceErrorKind::Validation), 422);
+    assert_eq!(status(ServiceErrorKind::InvalidQuery), 422);
+    assert_eq!(status(ServiceErrorKind::CartesianProduct), 422);
+    assert_eq!(status(ServiceErrorKind::Unsupported), 422);
+}
+
+#[cfg(feature = "json_example")]
+#[test]
+fn json_contract() {
+    use serde_json::json;
+
+    let response = handle_request(
+        "MATCH (n:Person) WHERE n.age > $age RETURN n.name",
+        &TransformConfig::default(),
+    );
+    let mut json = serde_json::to_value(&response).unwrap();
+    let stats = json.as_object_mut().unwrap().remove("stats").unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "sql": "SELECT n.name FROM Person AS n WHERE n.age > $age",
+            "warnings": [],
+            "parameters": [{"name": "age", "placeholder": "$age", "index": null, "count": 1}],
+            "error": null,
+        })
+    );
+    let mut keys: Vec<&str> = stats
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    assert_eq!(
+        keys,
+        [
+            "generate_time",
+            "generated_join_count",
+            "parse_time",
+            "pattern_element_count",
+            "plan_time",
+            "token_count",
+            "tokenize_time"
+        ]
+    );
+
+    let warning = handle_request(
+        "MATCH (a:Person), (b:City) RETURN a.name, b.name",
+        &TransformConfig::default(),
+    );
+    assert_eq!(
+        serde_json::to_value(&warning.warnings).unwrap(),
+        json!([{
+            "feature": "cartesian product of {a}, {b}",
+            "source_text": "MATCH (a:Person), (b:City) RETURN a.name, b.name",
+            "kind": "CartesianProduct",
+        }])
+    );
+
+    let kinds = [
+        "EmptyInput",
+        "Parse",
+        "Parse",
+        "Parse",
+        "Parse",
+        "RecursionLimit",
+        "Validation",
+        "InvalidQuery",
+        "CartesianProduct",
+        "Unsupported",
+    ];
+    let errors = errors();
+    assert_eq!(errors.len(), kinds.len());
+    for ((query, config, error), kind) in errors.into_iter().zip(kinds) {
+        assert_eq!(
+            serde_json::to_value(handle_request(&query, &config)).unwrap(),
+            json!({
+                "sql": null,
+                "warnings": [],
+                "parameters": [],
+                "stats": null,
+                "error": {
+                    "kind": kind,
+                    "message": error.message,
+                    "line": error.line,
+                    "column": error.column,
+                },
+            }),
+            "{query}"
+        );
+    }
+}